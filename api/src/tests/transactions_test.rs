@@ -7,7 +7,7 @@ use crate::tests::new_test_context_with_config;
 use aptos_api_test_context::{assert_json, current_function_name, pretty, TestContext};
 use aptos_config::config::{GasEstimationStaticOverride, NodeConfig};
 use aptos_crypto::{
-    ed25519::Ed25519PrivateKey,
+    ed25519::{Ed25519PrivateKey, Ed25519Signature},
     multi_ed25519::{MultiEd25519PrivateKey, MultiEd25519PublicKey},
     PrivateKey, SigningKey, Uniform,
 };
@@ -15,7 +15,7 @@ use aptos_sdk::types::LocalAccount;
 use aptos_types::{
     account_address::AccountAddress,
     transaction::{
-        authenticator::{AuthenticationKey, TransactionAuthenticator},
+        authenticator::{AccountAuthenticator, AuthenticationKey, TransactionAuthenticator},
         EntryFunction, Script, SignedTransaction,
     },
     utility_coin::APTOS_COIN_TYPE,
@@ -229,7 +229,7 @@ async fn test_multi_agent_signed_transaction() {
             "sender": {
                 "type": "ed25519_signature",
                 "public_key": format!("0x{}", hex::encode(sender.public_key_bytes())),
-                "signature": format!("0x{}", hex::encode(sender.signature_bytes())),
+                "signature": format!("0x{}", hex::encode(sender.signature_bytes().unwrap())),
             },
             "secondary_signer_addresses": [
                 secondary.address().to_hex_literal(),
@@ -238,7 +238,7 @@ async fn test_multi_agent_signed_transaction() {
                 {
                     "type": "ed25519_signature",
                     "public_key": format!("0x{}",hex::encode(secondary_signers[0].public_key_bytes())),
-                    "signature": format!("0x{}", hex::encode(secondary_signers[0].signature_bytes())),
+                    "signature": format!("0x{}", hex::encode(secondary_signers[0].signature_bytes().unwrap())),
                 }
             ]
         }),
@@ -297,7 +297,7 @@ async fn test_fee_payer_signed_transaction() {
             "sender": {
                 "type": "ed25519_signature",
                 "public_key": format!("0x{}", hex::encode(sender.public_key_bytes())),
-                "signature": format!("0x{}", hex::encode(sender.signature_bytes())),
+                "signature": format!("0x{}", hex::encode(sender.signature_bytes().unwrap())),
             },
             "secondary_signer_addresses": [
             ],
@@ -307,7 +307,7 @@ async fn test_fee_payer_signed_transaction() {
             "fee_payer_signer": {
                 "type": "ed25519_signature",
                 "public_key": format!("0x{}",hex::encode(fee_payer_signer.public_key_bytes())),
-                "signature": format!("0x{}", hex::encode(fee_payer_signer.signature_bytes())),
+                "signature": format!("0x{}", hex::encode(fee_payer_signer.signature_bytes().unwrap())),
             },
         }),
     );
@@ -1293,6 +1293,241 @@ async fn test_gas_estimation_static_override() {
     context.check_golden_output(resp);
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_simulate_transaction_detailed() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.create_account().await;
+    let receiver = context.gen_account();
+
+    // The balance override should be visible to the simulation and reported as the value before
+    // the transaction.
+    let resp = context
+        .simulate_transaction_detailed(
+            &account,
+            json!({
+                "type": "entry_function_payload",
+                "function": "0x1::aptos_account::transfer",
+                "type_arguments": [],
+                "arguments": [receiver.address().to_hex_literal(), "10"],
+            }),
+            json!([{
+                "type": "balance_override",
+                "address": account.address().to_hex_literal(),
+                "amount": "123456789",
+            }]),
+            200,
+        )
+        .await;
+    assert!(resp["transaction"]["success"].as_bool().unwrap());
+
+    let call_graph = &resp["trace"]["call_graph"];
+    assert_eq!(
+        call_graph["function"].as_str().unwrap(),
+        "0x1::aptos_account::transfer"
+    );
+    assert!(!call_graph["calls"].as_array().unwrap().is_empty());
+
+    let coin_store = "0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>";
+    let coin_value = |changes: &serde_json::Value| {
+        changes
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|change| change["data"]["type"].as_str() == Some(coin_store))
+            .map(|change| {
+                change["data"]["data"]["coin"]["value"]
+                    .as_str()
+                    .unwrap()
+                    .to_owned()
+            })
+    };
+    let sender_diff = resp["state_diff"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|diff| {
+            diff["after"][0]["address"].as_str() == Some(&account.address().to_hex_literal())
+                && coin_value(&diff["after"]).is_some()
+        })
+        .unwrap();
+    // Gas is free in simulations with a gas unit price of 0.
+    assert_eq!(coin_value(&sender_diff["before"]).unwrap(), "123456789");
+    assert_eq!(coin_value(&sender_diff["after"]).unwrap(), "123456779");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_simulate_transaction_with_resource_group_override() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.create_account().await;
+    let receiver = context.gen_account();
+    let object = AccountAddress::random().to_hex_literal();
+
+    // The object core is a member of the object group, so overriding it must create the group
+    // for the transfer to find the object.
+    let resp = context
+        .simulate_transaction_detailed(
+            &account,
+            json!({
+                "type": "entry_function_payload",
+                "function": "0x1::object::transfer_call",
+                "type_arguments": [],
+                "arguments": [object, receiver.address().to_hex_literal()],
+            }),
+            json!([{
+                "type": "resource_override",
+                "address": object,
+                "resource_type": "0x1::object::ObjectCore",
+                "data": {
+                    "guid_creation_num": "1125899906842625",
+                    "owner": account.address().to_hex_literal(),
+                    "allow_ungated_transfer": true,
+                    "transfer_events": {
+                        "counter": "0",
+                        "guid": {
+                            "id": {
+                                "creation_num": "1125899906842624",
+                                "addr": object,
+                            },
+                        },
+                    },
+                },
+            }]),
+            200,
+        )
+        .await;
+    assert!(
+        resp["transaction"]["success"].as_bool().unwrap(),
+        "{}",
+        resp["transaction"]["vm_status"]
+    );
+
+    let object_core = resp["state_diff"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .flat_map(|diff| diff["after"].as_array().unwrap())
+        .find(|change| change["data"]["type"].as_str() == Some("0x1::object::ObjectCore"))
+        .unwrap();
+    assert_eq!(
+        object_core["data"]["data"]["owner"].as_str().unwrap(),
+        receiver.address().to_hex_literal()
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_simulate_transaction_bundle() {
+    let mut context = new_test_context(current_function_name!());
+    let mut account = context.create_account().await;
+    let receiver = context.gen_account();
+    let receiver_address = receiver.address().to_hex_literal();
+
+    // `coin::transfer` requires the receiver to have a coin store, which only exists after the
+    // first transaction of the bundle created the receiver account.
+    let coin_transfer = json!({
+        "type": "entry_function_payload",
+        "function": "0x1::coin::transfer",
+        "type_arguments": ["0x1::aptos_coin::AptosCoin"],
+        "arguments": [receiver_address, "10"],
+    });
+    let resp = context
+        .simulate_transaction(&account, coin_transfer.clone(), 200)
+        .await;
+    assert!(!resp[0]["success"].as_bool().unwrap());
+
+    let resp = context
+        .simulate_transaction_bundle(
+            &mut account,
+            vec![
+                json!({
+                    "type": "entry_function_payload",
+                    "function": "0x1::aptos_account::transfer",
+                    "type_arguments": [],
+                    "arguments": [receiver_address, "10"],
+                }),
+                coin_transfer,
+            ],
+            200,
+        )
+        .await;
+    let txns = resp.as_array().unwrap();
+    assert_eq!(txns.len(), 2);
+    for (index, txn) in txns.iter().enumerate() {
+        assert!(txn["success"].as_bool().unwrap(), "{}", pretty(txn));
+        assert_eq!(
+            txn["sequence_number"].as_str().unwrap(),
+            (account.sequence_number() + index as u64).to_string()
+        );
+    }
+
+    // Nothing was committed
+    context
+        .expect_status_code(404)
+        .get(&format!("/accounts/{}", receiver_address))
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_simulate_fee_payer_transaction_estimates_max_gas_from_fee_payer() {
+    let mut context = new_test_context(current_function_name!());
+    let fee_payer = context.create_account().await;
+    // The sender has no coins to pay for gas.
+    let sender = context.gen_account();
+    let txn = context.create_user_account(&sender).await;
+    context.commit_block(&[txn]).await;
+    assert_eq!(context.get_apt_balance(sender.address()).await, 0);
+
+    let receiver = context.gen_account();
+    let raw_txn = context
+        .transaction_factory()
+        .create_user_account(receiver.public_key())
+        .sender(sender.address())
+        .sequence_number(0)
+        .gas_unit_price(100)
+        .max_gas_amount(1)
+        .expiration_timestamp_secs(u64::MAX)
+        .build();
+    // The simulation API rejects valid signatures.
+    let invalid_authenticator = |account: &LocalAccount| {
+        AccountAuthenticator::ed25519(
+            account.public_key().clone(),
+            Ed25519Signature::try_from([0u8; 64].as_ref()).unwrap(),
+        )
+    };
+    let txn = SignedTransaction::new_fee_payer(
+        raw_txn,
+        invalid_authenticator(&sender),
+        vec![],
+        vec![],
+        fee_payer.address(),
+        invalid_authenticator(&fee_payer),
+    );
+
+    let resp = context
+        .expect_status_code(200)
+        .post_bcs_txn(
+            "/transactions/simulate?estimate_max_gas_amount=true",
+            bcs::to_bytes(&txn).unwrap(),
+        )
+        .await;
+    let simulated = &resp[0];
+    assert!(simulated["success"].as_bool().unwrap(), "{}", pretty(&resp));
+    assert_eq!(
+        simulated["signature"]["fee_payer_address"]
+            .as_str()
+            .unwrap(),
+        fee_payer.address().to_hex_literal()
+    );
+    // The max gas is estimated from the balance of the fee payer, not the one of the sender.
+    let max_gas_amount: u64 = simulated["max_gas_amount"]
+        .as_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    let gas_used: u64 = simulated["gas_used"].as_str().unwrap().parse().unwrap();
+    assert!(max_gas_amount > gas_used);
+    assert!(max_gas_amount <= context.get_apt_balance(fee_payer.address()).await / 100);
+}
+
 fn gen_string(len: u64) -> String {
     let mut rng = thread_rng();
     std::iter::repeat(())
@@ -18,27 +18,48 @@ use crate::{
     },
     ApiTags,
 };
-use anyhow::{anyhow, Context as AnyhowContext};
+use anyhow::{anyhow, bail, Context as AnyhowContext};
 use aptos_api_types::{
     verify_function_identifier, verify_module_identifier, Address, AptosError, AptosErrorCode,
-    AsConverter, EncodeSubmissionRequest, GasEstimation, GasEstimationBcs, HashValue,
-    HexEncodedBytes, LedgerInfo, MoveType, PendingTransaction, SubmitTransactionRequest,
+    AsConverter, BalanceOverride, CallTraceFrame, EncodeSubmissionRequest, GasEstimation,
+    GasEstimationBcs, HashValue, HexEncodedBytes, LedgerInfo, MoveConverter, MoveType,
+    PendingTransaction, ResourceGroup, ResourceOverride, SimulateTransactionRequest,
+    SimulationResult, SimulationTrace, StateDiff, StateOverride, SubmitTransactionRequest,
     Transaction, TransactionData, TransactionOnChainData, TransactionsBatchSingleSubmissionFailure,
     TransactionsBatchSubmissionResult, UserTransaction, VerifyInput, VerifyInputWithRecursion,
     MAX_RECURSIVE_TYPES_ALLOWED, U64,
 };
 use aptos_crypto::{hash::CryptoHash, signing_message};
+use aptos_gas_meter::{StandardGasAlgebra, StandardGasMeter};
+use aptos_gas_profiling::{
+    CallFrame, ExecutionGasEvent, FrameName, GasProfiler, TransactionGasLog,
+};
+use aptos_memory_usage_tracker::MemoryTrackedGasMeter;
+use aptos_state_view::{overlay_state_view::OverlayStateView, StateView, TStateView};
 use aptos_types::{
+    access_path::AccessPath,
     account_config::CoinStoreResource,
     account_view::AccountView,
     mempool_status::MempoolStatusCode,
+    state_store::{state_key::StateKey, state_value::StateValue},
     transaction::{
         EntryFunction, ExecutionStatus, MultisigTransactionPayload, RawTransaction,
-        RawTransactionWithData, SignedTransaction, TransactionPayload, TransactionStatus,
+        RawTransactionWithData, SignedTransaction, TransactionOutput, TransactionPayload,
+        TransactionStatus,
     },
     vm_status::StatusCode,
+    write_set::WriteOp,
+};
+use aptos_vm::{
+    data_cache::{get_resource_group_from_metadata, AsMoveResolver},
+    move_vm_ext::MoveResolverExt,
+    AptosVM,
+};
+use move_core_types::{
+    language_storage::{StructTag, TypeTag},
+    move_resource::MoveStructType,
+    resolver::ModuleResolver,
 };
-use aptos_vm::{data_cache::AsMoveResolver, AptosVM};
 use poem_openapi::{
     param::{Path, Query},
     payload::Json,
@@ -456,14 +477,14 @@ impl TransactionsApi {
                 / u64::from(gas_params.vm.txn.gas_unit_scaling_factor);
             let max_number_of_gas_units = u64::from(gas_params.vm.txn.maximum_number_of_gas_units);
 
-            // Retrieve account balance to determine max gas available
+            // Retrieve the balance of the account paying for gas to determine max gas available
+            let gas_payer = signed_transaction
+                .authenticator_ref()
+                .fee_payer_address()
+                .unwrap_or_else(|| signed_transaction.sender());
             let account_state = self
                 .context
-                .get_account_state(
-                    signed_transaction.sender(),
-                    ledger_info.version(),
-                    &ledger_info,
-                )?
+                .get_account_state(gas_payer, ledger_info.version(), &ledger_info)?
                 .ok_or_else(|| {
                     SubmitTransactionError::bad_request_with_code(
                         "Account not found",
@@ -474,12 +495,7 @@ impl TransactionsApi {
             let coin_store: CoinStoreResource = account_state
                 .get_coin_store_resource()
                 .and_then(|inner| {
-                    inner.ok_or_else(|| {
-                        anyhow!(
-                            "No coin store found for account {}",
-                            signed_transaction.sender()
-                        )
-                    })
+                    inner.ok_or_else(|| anyhow!("No coin store found for account {}", gas_payer))
                 })
                 .map_err(|err| {
                     SubmitTransactionError::internal_with_code(
@@ -525,6 +541,125 @@ impl TransactionsApi {
             .await
     }
 
+    /// Simulate transaction in detail
+    ///
+    /// Simulates a transaction like `/transactions/simulate`, with the following additions:
+    /// - State overrides can be applied on top of the latest state before the simulation, e.g. to
+    ///   set the APT balance of an account or one of its resources.
+    /// - The execution trace of the transaction can be returned. It is a call graph with the gas
+    ///   used by every call.
+    /// - The values before and after every write of the transaction can be returned.
+    ///
+    /// As for `/transactions/simulate`, the transaction must have a non-valid signature.
+    ///
+    /// This endpoint only supports JSON.
+    #[oai(
+        path = "/transactions/simulate_detailed",
+        method = "post",
+        operation_id = "simulate_transaction_detailed",
+        tag = "ApiTags::Transactions"
+    )]
+    async fn simulate_transaction_detailed(
+        &self,
+        accept_type: AcceptType,
+        data: Json<SimulateTransactionRequest>,
+    ) -> SimulateTransactionResult<SimulationResult> {
+        data.0
+            .verify()
+            .context("Simulated transaction invalid")
+            .map_err(|err| {
+                SubmitTransactionError::bad_request_with_code_no_info(
+                    err,
+                    AptosErrorCode::InvalidInput,
+                )
+            })?;
+        fail_point_poem("endpoint_simulate_transaction_detailed")?;
+        if !self.context.node_config.api.transaction_simulation_enabled {
+            return Err(api_disabled("Simulate transaction"));
+        }
+        self.context
+            .check_api_output_enabled("Simulate transaction", &accept_type)?;
+        if accept_type == AcceptType::Bcs {
+            return Err(SubmitTransactionError::bad_request_with_code_no_info(
+                "BCS is not supported for detailed simulation",
+                AptosErrorCode::BcsNotSupported,
+            ));
+        }
+
+        let SimulateTransactionRequest {
+            transaction,
+            state_overrides,
+            include_trace,
+            include_state_diff,
+        } = data.0;
+        let ledger_info = self.context.get_latest_ledger_info()?;
+        let signed_transaction = self
+            .get_signed_transaction(&ledger_info, SubmitTransactionPost::Json(Json(transaction)))?;
+
+        self.simulate_detailed(
+            ledger_info,
+            signed_transaction,
+            state_overrides.unwrap_or_default(),
+            include_trace.unwrap_or_default(),
+            include_state_diff.unwrap_or_default(),
+        )
+        .await
+    }
+
+    /// Simulate a bundle of transactions
+    ///
+    /// Simulates the transactions in order against the latest state, each of them observing the
+    /// writes of the previous ones, e.g. to simulate an approval followed by a swap. Nothing is
+    /// committed, and the outputs are returned in the same order as the transactions.
+    ///
+    /// As for `/transactions/simulate`, all transactions must have a non-valid signature. The
+    /// bundle can contain at most as many transactions as a batch submission.
+    ///
+    /// To use this endpoint with BCS, you must submit a vector of SignedTransactions
+    /// encoded as BCS. See SignedTransaction in types/src/transaction/mod.rs.
+    #[oai(
+        path = "/transactions/simulate_bundle",
+        method = "post",
+        operation_id = "simulate_transaction_bundle",
+        tag = "ApiTags::Transactions"
+    )]
+    async fn simulate_transaction_bundle(
+        &self,
+        accept_type: AcceptType,
+        data: SubmitTransactionsBatchPost,
+    ) -> SimulateTransactionResult<Vec<UserTransaction>> {
+        data.verify()
+            .context("Simulated transactions invalid")
+            .map_err(|err| {
+                SubmitTransactionError::bad_request_with_code_no_info(
+                    err,
+                    AptosErrorCode::InvalidInput,
+                )
+            })?;
+        fail_point_poem("endpoint_simulate_transaction_bundle")?;
+        if !self.context.node_config.api.transaction_simulation_enabled {
+            return Err(api_disabled("Simulate transaction bundle"));
+        }
+        self.context
+            .check_api_output_enabled("Simulate transaction bundle", &accept_type)?;
+        let ledger_info = self.context.get_latest_ledger_info()?;
+        let signed_transactions = self.get_signed_transactions_batch(&ledger_info, data)?;
+        if self.context.max_submit_transaction_batch_size() < signed_transactions.len() {
+            return Err(SubmitTransactionError::bad_request_with_code(
+                format!(
+                    "Simulated too many transactions: {}, while limit is {}",
+                    signed_transactions.len(),
+                    self.context.max_submit_transaction_batch_size(),
+                ),
+                AptosErrorCode::InvalidInput,
+                &ledger_info,
+            ));
+        }
+
+        self.simulate_bundle(&accept_type, ledger_info, signed_transactions)
+            .await
+    }
+
     /// Encode submission
     ///
     /// This endpoint accepts an EncodeSubmissionRequest, which internally is a
@@ -1189,57 +1324,64 @@ impl TransactionsApi {
         let state_view = self.context.latest_state_view_poem(&ledger_info)?;
         let move_resolver = state_view.as_move_resolver();
         let (_, output) = AptosVM::simulate_signed_transaction(&txn, &move_resolver);
-        let version = ledger_info.version();
+        let simulated_txn = simulated_transaction_data(ledger_info.version(), txn, &output);
 
-        // Ensure that all known statuses return their values in the output (even if they aren't supposed to)
-        let exe_status = match output.status().clone() {
-            TransactionStatus::Keep(exec_status) => exec_status,
-            TransactionStatus::Discard(status) => ExecutionStatus::MiscellaneousError(Some(status)),
-            _ => ExecutionStatus::MiscellaneousError(None),
-        };
+        match accept_type {
+            AcceptType::Json => {
+                let user_transactions =
+                    self.render_simulated_transactions(&ledger_info, vec![simulated_txn])?;
+                BasicResponse::try_from_json((
+                    user_transactions,
+                    &ledger_info,
+                    BasicResponseStatus::Ok,
+                ))
+            },
+            AcceptType::Bcs => {
+                BasicResponse::try_from_bcs((simulated_txn, &ledger_info, BasicResponseStatus::Ok))
+            },
+        }
+    }
 
-        // Build up a transaction from the outputs
-        // All state hashes are invalid, and will be filled with 0s
-        let txn = aptos_types::transaction::Transaction::UserTransaction(txn);
-        let zero_hash = aptos_crypto::HashValue::zero();
-        let info = aptos_types::transaction::TransactionInfo::new(
-            txn.hash(),
-            zero_hash,
-            zero_hash,
-            None,
-            output.gas_used(),
-            exe_status,
-        );
-        let simulated_txn = TransactionOnChainData {
-            version,
-            transaction: txn,
-            info,
-            events: output.events().to_vec(),
-            accumulator_root_hash: zero_hash,
-            changes: output.write_set().clone(),
-        };
+    /// Simulate transactions in order in the VM, each of them against the latest state with the
+    /// writes of the previous ones applied
+    pub async fn simulate_bundle(
+        &self,
+        accept_type: &AcceptType,
+        ledger_info: LedgerInfo,
+        txns: Vec<SignedTransaction>,
+    ) -> SimulateTransactionResult<Vec<UserTransaction>> {
+        // Transactions shouldn't have a valid signature or this could be used to attack
+        if let Some(index) = txns.iter().position(|txn| txn.signature_is_valid()) {
+            return Err(SubmitTransactionError::bad_request_with_code(
+                format!(
+                    "Simulated transactions must have a non-valid signature, found a valid one at position {}",
+                    index
+                ),
+                AptosErrorCode::InvalidInput,
+                &ledger_info,
+            ));
+        }
+
+        let state_view = self.context.latest_state_view_poem(&ledger_info)?;
+        let mut overlay = OverlayStateView::new(&state_view);
+        let mut simulated_txns = Vec::with_capacity(txns.len());
+        for txn in txns {
+            let (_, output) = AptosVM::simulate_signed_transaction(&txn, &overlay);
+            // Discarded transactions have no writes, so there is nothing to apply for them
+            if let TransactionStatus::Keep(_) = output.status() {
+                overlay.apply_write_set(output.write_set());
+            }
+            simulated_txns.push(simulated_transaction_data(
+                ledger_info.version(),
+                txn,
+                &output,
+            ));
+        }
 
         match accept_type {
             AcceptType::Json => {
-                let transactions = self
-                    .context
-                    .render_transactions_non_sequential(&ledger_info, vec![simulated_txn])?;
-
-                // Users can only make requests to simulate UserTransactions, so unpack
-                // the Vec<Transaction> into Vec<UserTransaction>.
-                let mut user_transactions = Vec::new();
-                for transaction in transactions.into_iter() {
-                    match transaction {
-                        Transaction::UserTransaction(user_txn) => user_transactions.push(*user_txn),
-                        _ => {
-                            return Err(SubmitTransactionError::internal_with_code(
-                                "Simulation transaction resulted in a non-UserTransaction",
-                                AptosErrorCode::InternalError,
-                                &ledger_info,
-                            ))
-                        },
-                    }
-                }
+                let user_transactions =
+                    self.render_simulated_transactions(&ledger_info, simulated_txns)?;
                 BasicResponse::try_from_json((
                     user_transactions,
                     &ledger_info,
@@ -1247,11 +1389,157 @@ impl TransactionsApi {
                 ))
             },
             AcceptType::Bcs => {
-                BasicResponse::try_from_bcs((simulated_txn, &ledger_info, BasicResponseStatus::Ok))
+                BasicResponse::try_from_bcs((simulated_txns, &ledger_info, BasicResponseStatus::Ok))
             },
         }
     }
 
+    fn render_simulated_transactions(
+        &self,
+        ledger_info: &LedgerInfo,
+        simulated_txns: Vec<TransactionOnChainData>,
+    ) -> Result<Vec<UserTransaction>, SubmitTransactionError> {
+        let transactions = self
+            .context
+            .render_transactions_non_sequential(ledger_info, simulated_txns)?;
+
+        // Users can only make requests to simulate UserTransactions, so unpack
+        // the Vec<Transaction> into Vec<UserTransaction>.
+        let mut user_transactions = Vec::new();
+        for transaction in transactions.into_iter() {
+            match transaction {
+                Transaction::UserTransaction(user_txn) => user_transactions.push(*user_txn),
+                _ => {
+                    return Err(SubmitTransactionError::internal_with_code(
+                        "Simulation transaction resulted in a non-UserTransaction",
+                        AptosErrorCode::InternalError,
+                        ledger_info,
+                    ))
+                },
+            }
+        }
+        Ok(user_transactions)
+    }
+
+    /// Simulate a transaction in the VM against the latest state with the given overrides applied
+    pub async fn simulate_detailed(
+        &self,
+        ledger_info: LedgerInfo,
+        txn: SignedTransaction,
+        state_overrides: Vec<StateOverride>,
+        include_trace: bool,
+        include_state_diff: bool,
+    ) -> SimulateTransactionResult<SimulationResult> {
+        // Transactions shouldn't have a valid signature or this could be used to attack
+        if txn.signature_is_valid() {
+            return Err(SubmitTransactionError::bad_request_with_code(
+                "Simulated transactions must have a non-valid signature",
+                AptosErrorCode::InvalidInput,
+                &ledger_info,
+            ));
+        }
+
+        let state_view = self.context.latest_state_view_poem(&ledger_info)?;
+        let mut overlay = OverlayStateView::new(&state_view);
+        {
+            let resolver = state_view.as_move_resolver();
+            let converter = resolver.as_converter(self.context.db.clone());
+            for state_override in state_overrides {
+                apply_state_override(&resolver, &converter, &mut overlay, state_override)
+                    .context("Failed to apply state override")
+                    .map_err(|err| {
+                        SubmitTransactionError::bad_request_with_code(
+                            err,
+                            AptosErrorCode::InvalidInput,
+                            &ledger_info,
+                        )
+                    })?;
+            }
+        }
+
+        // Simulate transaction, profiling its gas usage if a trace is requested
+        let (output, trace) = if include_trace {
+            let (_, output, gas_profiler) =
+                AptosVM::simulate_signed_transaction_with_custom_gas_meter(
+                    &txn,
+                    &overlay,
+                    |gas_feature_version, gas_params, storage_gas_params, balance| {
+                        let gas_meter = MemoryTrackedGasMeter::new(StandardGasMeter::new(
+                            StandardGasAlgebra::new(
+                                gas_feature_version,
+                                gas_params,
+                                storage_gas_params,
+                                balance,
+                            ),
+                        ));
+                        Ok(new_gas_profiler(gas_meter, txn.payload()))
+                    },
+                );
+            let trace = gas_profiler.map(|gas_profiler| simulation_trace(gas_profiler.finish()));
+            (output, trace)
+        } else {
+            let (_, output) = AptosVM::simulate_signed_transaction(&txn, &overlay);
+            (output, None)
+        };
+
+        let state_diff = if include_state_diff {
+            let resolver = overlay.as_move_resolver();
+            let converter = resolver.as_converter(self.context.db.clone());
+            let state_diff = output
+                .write_set()
+                .iter()
+                .map(|(state_key, op)| {
+                    let before = match overlay.get_state_value_bytes(state_key)? {
+                        Some(bytes) => converter.try_into_write_set_changes(
+                            state_key.clone(),
+                            WriteOp::Modification(bytes),
+                        )?,
+                        None => vec![],
+                    };
+                    Ok(StateDiff {
+                        state_key_hash: state_key.hash().to_hex_literal(),
+                        before,
+                        after: converter
+                            .try_into_write_set_changes(state_key.clone(), op.clone())?,
+                    })
+                })
+                .collect::<anyhow::Result<Vec<_>>>()
+                .context("Failed to compute the state diff of the simulated transaction")
+                .map_err(|err| {
+                    SubmitTransactionError::internal_with_code(
+                        err,
+                        AptosErrorCode::InternalError,
+                        &ledger_info,
+                    )
+                })?;
+            Some(state_diff)
+        } else {
+            None
+        };
+
+        let simulated_txn = simulated_transaction_data(ledger_info.version(), txn, &output);
+        let transaction = self
+            .render_simulated_transactions(&ledger_info, vec![simulated_txn])?
+            .pop()
+            .ok_or_else(|| {
+                SubmitTransactionError::internal_with_code(
+                    "Simulation transaction wasn't rendered",
+                    AptosErrorCode::InternalError,
+                    &ledger_info,
+                )
+            })?;
+
+        BasicResponse::try_from_json((
+            SimulationResult {
+                transaction,
+                trace,
+                state_diff,
+            },
+            &ledger_info,
+            BasicResponseStatus::Ok,
+        ))
+    }
+
     /// Encode message as BCS
     pub fn get_signing_message(
         &self,
@@ -1337,3 +1625,200 @@ enum GetByVersionResponse {
     VersionTooOld,
     Found(TransactionData),
 }
+
+/// Builds up a transaction from the output of a simulation
+///
+/// All state hashes are invalid, and will be filled with 0s
+fn simulated_transaction_data(
+    version: u64,
+    txn: SignedTransaction,
+    output: &TransactionOutput,
+) -> TransactionOnChainData {
+    // Ensure that all known statuses return their values in the output (even if they aren't supposed to)
+    let exe_status = match output.status().clone() {
+        TransactionStatus::Keep(exec_status) => exec_status,
+        TransactionStatus::Discard(status) => ExecutionStatus::MiscellaneousError(Some(status)),
+        _ => ExecutionStatus::MiscellaneousError(None),
+    };
+
+    let txn = aptos_types::transaction::Transaction::UserTransaction(txn);
+    let zero_hash = aptos_crypto::HashValue::zero();
+    let info = aptos_types::transaction::TransactionInfo::new(
+        txn.hash(),
+        zero_hash,
+        zero_hash,
+        None,
+        output.gas_used(),
+        exe_status,
+    );
+    TransactionOnChainData {
+        version,
+        transaction: txn,
+        info,
+        events: output.events().to_vec(),
+        accumulator_root_hash: zero_hash,
+        changes: output.write_set().clone(),
+    }
+}
+
+/// Applies a state override on top of the given state view. Overrides of resource group
+/// members are merged into the group they belong to.
+fn apply_state_override<R: MoveResolverExt, S: StateView>(
+    resolver: &R,
+    converter: &MoveConverter<'_, R>,
+    overlay: &mut OverlayStateView<'_, S>,
+    state_override: StateOverride,
+) -> anyhow::Result<()> {
+    match state_override {
+        StateOverride::ResourceOverride(ResourceOverride {
+            address,
+            resource_type,
+            data,
+        }) => {
+            let address = address.into();
+            let struct_tag: StructTag = resource_type.try_into()?;
+            let bytes = match data {
+                Some(data) => {
+                    let value = converter
+                        .try_into_vm_value(&TypeTag::Struct(Box::new(struct_tag.clone())), data)?;
+                    Some(
+                        value.simple_serialize().ok_or_else(|| {
+                            anyhow!("Failed to serialize resource {}", struct_tag)
+                        })?,
+                    )
+                },
+                None => None,
+            };
+
+            let resource_group = get_resource_group_from_metadata(
+                &struct_tag,
+                &resolver.get_module_metadata(&struct_tag.module_id()),
+            );
+            match resource_group {
+                Some(resource_group) => {
+                    let state_key = StateKey::access_path(AccessPath::resource_group_access_path(
+                        address,
+                        resource_group,
+                    ));
+                    let mut group: ResourceGroup =
+                        match overlay.get_state_value_bytes(&state_key)? {
+                            Some(group_bytes) => bcs::from_bytes(&group_bytes)?,
+                            None => ResourceGroup::new(),
+                        };
+                    match bytes {
+                        Some(bytes) => group.insert(struct_tag, bytes),
+                        None => group.remove(&struct_tag),
+                    };
+                    let value = if group.is_empty() {
+                        None
+                    } else {
+                        Some(StateValue::new_legacy(bcs::to_bytes(&group)?))
+                    };
+                    overlay.set(state_key, value);
+                },
+                None => {
+                    let state_key = StateKey::access_path(AccessPath::resource_access_path(
+                        address, struct_tag,
+                    )?);
+                    overlay.set(state_key, bytes.map(StateValue::new_legacy));
+                },
+            }
+        },
+        StateOverride::BalanceOverride(BalanceOverride { address, amount }) => {
+            let address = address.into();
+            let state_key = StateKey::access_path(AccessPath::resource_access_path(
+                address,
+                CoinStoreResource::struct_tag(),
+            )?);
+            let coin_store: CoinStoreResource = match overlay.get_state_value_bytes(&state_key)? {
+                Some(bytes) => bcs::from_bytes(&bytes)?,
+                None => bail!("No coin store found for account {}", address),
+            };
+            let coin_store = CoinStoreResource::new(
+                amount.0,
+                coin_store.frozen(),
+                coin_store.deposit_events().clone(),
+                coin_store.withdraw_events().clone(),
+            );
+            overlay.set(
+                state_key,
+                Some(StateValue::new_legacy(bcs::to_bytes(&coin_store)?)),
+            );
+        },
+    }
+    Ok(())
+}
+
+/// Creates a gas profiler whose root frame is the entry point of the payload
+fn new_gas_profiler<G>(gas_meter: G, payload: &TransactionPayload) -> GasProfiler<G> {
+    let entry_function = match payload {
+        TransactionPayload::EntryFunction(entry_function) => Some(entry_function),
+        TransactionPayload::Multisig(multisig) => match &multisig.transaction_payload {
+            Some(MultisigTransactionPayload::EntryFunction(entry_function)) => Some(entry_function),
+            None => None,
+        },
+        TransactionPayload::Script(_) | TransactionPayload::ModuleBundle(_) => None,
+    };
+    match entry_function {
+        Some(entry_function) => GasProfiler::new_function(
+            gas_meter,
+            entry_function.module().clone(),
+            entry_function.function().to_owned(),
+            entry_function.ty_args().to_vec(),
+        ),
+        None => GasProfiler::new_script(gas_meter),
+    }
+}
+
+fn simulation_trace(gas_log: TransactionGasLog) -> SimulationTrace {
+    let exec_io = gas_log.exec_io;
+    SimulationTrace {
+        gas_scaling_factor: u64::from(exec_io.gas_scaling_factor).into(),
+        intrinsic_internal_gas: u64::from(exec_io.intrinsic_cost).into(),
+        execution_internal_gas: u64::from(exec_io.total).into(),
+        call_graph: call_trace_frame(&exec_io.call_graph),
+    }
+}
+
+fn call_trace_frame(frame: &CallFrame) -> CallTraceFrame {
+    let (function, type_arguments) = match &frame.name {
+        FrameName::Script => ("script".to_string(), vec![]),
+        FrameName::Function {
+            module_id,
+            name,
+            ty_args,
+        } => (
+            format!("{}::{}", module_id.short_str_lossless(), name),
+            ty_args.iter().map(MoveType::from).collect(),
+        ),
+    };
+    let calls = frame
+        .events
+        .iter()
+        .filter_map(|event| match event {
+            ExecutionGasEvent::Call(frame) => Some(call_trace_frame(frame)),
+            ExecutionGasEvent::CallNative {
+                module_id,
+                fn_name,
+                ty_args,
+                cost,
+            } => Some(CallTraceFrame {
+                function: format!("{}::{}", module_id.short_str_lossless(), fn_name),
+                type_arguments: ty_args.iter().map(MoveType::from).collect(),
+                is_native: true,
+                internal_gas_used: u64::from(*cost).into(),
+                calls: vec![],
+            }),
+            ExecutionGasEvent::Loc(_)
+            | ExecutionGasEvent::Bytecode { .. }
+            | ExecutionGasEvent::LoadResource { .. } => None,
+        })
+        .collect();
+    CallTraceFrame {
+        function,
+        type_arguments,
+        is_native: false,
+        internal_gas_used: u64::from(frame.total_cost()).into(),
+        calls,
+    }
+}
@@ -833,6 +833,53 @@ impl TestContext {
         payload: Value,
         status_code: u16,
     ) -> Value {
+        let request = self.simulation_request(sender, payload).await;
+        self.expect_status_code(status_code)
+            .post("/transactions/simulate", request)
+            .await
+    }
+
+    pub async fn simulate_transaction_detailed(
+        &mut self,
+        sender: &LocalAccount,
+        payload: Value,
+        state_overrides: Value,
+        status_code: u16,
+    ) -> Value {
+        let transaction = self.simulation_request(sender, payload).await;
+        let request = json!({
+            "transaction": transaction,
+            "state_overrides": state_overrides,
+            "include_trace": true,
+            "include_state_diff": true,
+        });
+        self.expect_status_code(status_code)
+            .post("/transactions/simulate_detailed", request)
+            .await
+    }
+
+    /// Simulates the payloads as consecutive transactions of the sender, without changing its
+    /// sequence number.
+    pub async fn simulate_transaction_bundle(
+        &mut self,
+        sender: &mut LocalAccount,
+        payloads: Vec<Value>,
+        status_code: u16,
+    ) -> Value {
+        let sequence_number = sender.sequence_number();
+        let mut requests = vec![];
+        for payload in payloads {
+            requests.push(self.simulation_request(sender, payload).await);
+            *sender.sequence_number_mut() += 1;
+        }
+        *sender.sequence_number_mut() = sequence_number;
+
+        self.expect_status_code(status_code)
+            .post("/transactions/simulate_bundle", json!(requests))
+            .await
+    }
+
+    async fn simulation_request(&mut self, sender: &LocalAccount, payload: Value) -> Value {
         let mut request = json!({
             "sender": sender.address(),
             "sequence_number": sender.sequence_number().to_string(),
@@ -863,10 +910,7 @@ impl TestContext {
             "public_key": HexEncodedBytes::from(sender.public_key().to_bytes().to_vec()),
             "signature": HexEncodedBytes::from(sig.to_bytes().to_vec()),
         });
-
-        self.expect_status_code(status_code)
-            .post("/transactions/simulate", request)
-            .await
+        request
     }
 
     pub fn prepend_path(&self, path: &str) -> String {
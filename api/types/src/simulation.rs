@@ -0,0 +1,111 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    Address, MoveStructTag, MoveType, SubmitTransactionRequest, UserTransaction, VerifyInput,
+    WriteSetChange, U64,
+};
+use poem_openapi::{Object, Union};
+use serde::{Deserialize, Serialize};
+
+/// Request to simulate a transaction in detail
+///
+/// Besides the transaction itself, this allows overriding state before the simulation, and asking
+/// for the execution trace and the state diff of the transaction.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct SimulateTransactionRequest {
+    pub transaction: SubmitTransactionRequest,
+    /// State overrides, applied in order on top of the latest state before simulating
+    pub state_overrides: Option<Vec<StateOverride>>,
+    /// If set to true, the execution trace of the transaction will be returned
+    pub include_trace: Option<bool>,
+    /// If set to true, the values before and after every write of the transaction will be
+    /// returned
+    pub include_state_diff: Option<bool>,
+}
+
+impl VerifyInput for SimulateTransactionRequest {
+    fn verify(&self) -> anyhow::Result<()> {
+        self.transaction.verify()
+    }
+}
+
+/// An override of the state a transaction is simulated against
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Union)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[oai(one_of, discriminator_name = "type", rename_all = "snake_case")]
+pub enum StateOverride {
+    ResourceOverride(ResourceOverride),
+    BalanceOverride(BalanceOverride),
+}
+
+/// Overrides a resource stored under an account
+///
+/// Overrides of resource group members (e.g., `0x1::object::ObjectCore`) are merged into their group.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct ResourceOverride {
+    pub address: Address,
+    pub resource_type: MoveStructTag,
+    /// The JSON representation of the new resource, if not set the resource will be deleted
+    pub data: Option<serde_json::Value>,
+}
+
+/// Overrides the APT balance of an account
+///
+/// The account must already have an APT coin store.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct BalanceOverride {
+    pub address: Address,
+    pub amount: U64,
+}
+
+/// The result of a detailed transaction simulation
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct SimulationResult {
+    pub transaction: UserTransaction,
+    /// The execution trace, if requested and the transaction was executed
+    pub trace: Option<SimulationTrace>,
+    /// The values before and after every write of the transaction, if requested
+    pub state_diff: Option<Vec<StateDiff>>,
+}
+
+/// The execution trace of a simulated transaction
+///
+/// Gas is reported in internal gas units, which are converted to gas units by dividing them by the
+/// gas scaling factor.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct SimulationTrace {
+    pub gas_scaling_factor: U64,
+    /// Internal gas charged for the transaction itself, before execution
+    pub intrinsic_internal_gas: U64,
+    /// Internal gas charged for execution and IO, including the intrinsic gas
+    pub execution_internal_gas: U64,
+    pub call_graph: CallTraceFrame,
+}
+
+/// A call in the execution trace of a transaction
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct CallTraceFrame {
+    /// The called function, e.g. `0x1::coin::transfer`, or `script`
+    pub function: String,
+    pub type_arguments: Vec<MoveType>,
+    /// Whether the function is a native function
+    pub is_native: bool,
+    /// Internal gas charged by the call, including the calls it made
+    pub internal_gas_used: U64,
+    /// Calls made by the function, in order
+    pub calls: Vec<CallTraceFrame>,
+}
+
+/// The values of a state item before and after a simulated transaction
+///
+/// Both values are represented as write set changes. A resource group may expand into several
+/// changes.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct StateDiff {
+    pub state_key_hash: String,
+    /// The state item before the transaction, empty if it didn't exist
+    pub before: Vec<WriteSetChange>,
+    /// The write performed by the transaction
+    pub after: Vec<WriteSetChange>,
+}
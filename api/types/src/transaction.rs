@@ -11,6 +11,14 @@ use anyhow::{bail, Context as AnyhowContext};
 use aptos_crypto::{
     ed25519::{self, Ed25519PublicKey, ED25519_PUBLIC_KEY_LENGTH, ED25519_SIGNATURE_LENGTH},
     multi_ed25519::{self, MultiEd25519PublicKey, BITMAP_NUM_OF_BYTES, MAX_NUM_OF_KEYS},
+    secp256k1_ecdsa::{
+        self, Secp256k1EcdsaPublicKey, SECP256K1_ECDSA_PUBLIC_KEY_LENGTH,
+        SECP256K1_ECDSA_SIGNATURE_LENGTH,
+    },
+    secp256r1_ecdsa::{
+        Secp256r1EcdsaPublicKey, SECP256R1_ECDSA_PUBLIC_KEY_LENGTH,
+        SECP256R1_ECDSA_SIGNATURE_LENGTH,
+    },
 };
 use aptos_types::{
     account_address::AccountAddress,
@@ -18,6 +26,7 @@ use aptos_types::{
     contract_event::{ContractEvent, EventWithVersion},
     transaction::{
         authenticator::{AccountAuthenticator, TransactionAuthenticator, MAX_NUM_OF_SIGS},
+        webauthn::{PartialAuthenticatorAssertionResponse, MIN_AUTHENTICATOR_DATA_LENGTH},
         Script, SignedTransaction, TransactionOutput, TransactionWithProof,
     },
 };
@@ -869,6 +878,9 @@ pub enum TransactionSignature {
     MultiEd25519Signature(MultiEd25519Signature),
     MultiAgentSignature(MultiAgentSignature),
     FeePayerSignature(FeePayerSignature),
+    Secp256k1EcdsaSignature(Secp256k1EcdsaSignature),
+    WebAuthnSignature(WebAuthnSignature),
+    AbstractionSignature(AbstractionSignature),
 }
 
 impl VerifyInput for TransactionSignature {
@@ -878,6 +890,9 @@ impl VerifyInput for TransactionSignature {
             TransactionSignature::MultiEd25519Signature(inner) => inner.verify(),
             TransactionSignature::MultiAgentSignature(inner) => inner.verify(),
             TransactionSignature::FeePayerSignature(inner) => inner.verify(),
+            TransactionSignature::Secp256k1EcdsaSignature(inner) => inner.verify(),
+            TransactionSignature::WebAuthnSignature(inner) => inner.verify(),
+            TransactionSignature::AbstractionSignature(inner) => inner.verify(),
         }
     }
 }
@@ -891,6 +906,9 @@ impl TryFrom<TransactionSignature> for TransactionAuthenticator {
             TransactionSignature::MultiEd25519Signature(sig) => sig.try_into()?,
             TransactionSignature::MultiAgentSignature(sig) => sig.try_into()?,
             TransactionSignature::FeePayerSignature(sig) => sig.try_into()?,
+            TransactionSignature::Secp256k1EcdsaSignature(sig) => sig.try_into()?,
+            TransactionSignature::WebAuthnSignature(sig) => sig.try_into()?,
+            TransactionSignature::AbstractionSignature(sig) => sig.try_into()?,
         })
     }
 }
@@ -965,6 +983,202 @@ impl TryFrom<Ed25519Signature> for AccountAuthenticator {
     }
 }
 
+/// A single Secp256k1 ECDSA signature
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct Secp256k1EcdsaSignature {
+    pub public_key: HexEncodedBytes,
+    pub signature: HexEncodedBytes,
+}
+
+impl VerifyInput for Secp256k1EcdsaSignature {
+    fn verify(&self) -> anyhow::Result<()> {
+        let public_key_len = self.public_key.inner().len();
+        let signature_len = self.signature.inner().len();
+        if public_key_len != SECP256K1_ECDSA_PUBLIC_KEY_LENGTH {
+            bail!(
+                "Secp256k1 ECDSA signature's public key is an invalid number of bytes, should be {} bytes but found {}",
+                SECP256K1_ECDSA_PUBLIC_KEY_LENGTH, public_key_len
+            )
+        } else if signature_len != SECP256K1_ECDSA_SIGNATURE_LENGTH {
+            bail!(
+                "Secp256k1 ECDSA signature length is an invalid number of bytes, should be {} bytes but found {}",
+                SECP256K1_ECDSA_SIGNATURE_LENGTH, signature_len
+            )
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl TryFrom<Secp256k1EcdsaSignature> for TransactionAuthenticator {
+    type Error = anyhow::Error;
+
+    fn try_from(value: Secp256k1EcdsaSignature) -> Result<Self, Self::Error> {
+        let Secp256k1EcdsaSignature {
+            public_key,
+            signature,
+        } = value;
+        Ok(TransactionAuthenticator::secp256k1_ecdsa(
+            public_key
+                .inner()
+                .try_into()
+                .context("Failed to parse given public_key bytes as a Secp256k1EcdsaPublicKey")?,
+            signature
+                .inner()
+                .try_into()
+                .context("Failed to parse given signature as a Secp256k1EcdsaSignature")?,
+        ))
+    }
+}
+
+impl TryFrom<Secp256k1EcdsaSignature> for AccountAuthenticator {
+    type Error = anyhow::Error;
+
+    fn try_from(value: Secp256k1EcdsaSignature) -> Result<Self, Self::Error> {
+        let Secp256k1EcdsaSignature {
+            public_key,
+            signature,
+        } = value;
+        Ok(AccountAuthenticator::secp256k1_ecdsa(
+            public_key
+                .inner()
+                .try_into()
+                .context("Failed to parse given public_key bytes as a Secp256k1EcdsaPublicKey")?,
+            signature
+                .inner()
+                .try_into()
+                .context("Failed to parse given signature as a Secp256k1EcdsaSignature")?,
+        ))
+    }
+}
+
+/// A single WebAuthn (passkey) assertion
+///
+/// The signature is a Secp256r1 ECDSA signature, in its low-S form, over the authenticator data
+/// concatenated with the SHA2-256 hash of the client data JSON. The challenge in the client data
+/// must be the base64url encoded SHA3-256 hash of the transaction's signing message.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct WebAuthnSignature {
+    pub public_key: HexEncodedBytes,
+    pub signature: HexEncodedBytes,
+    pub authenticator_data: HexEncodedBytes,
+    pub client_data_json: HexEncodedBytes,
+}
+
+impl WebAuthnSignature {
+    fn parse(
+        self,
+    ) -> anyhow::Result<(
+        Secp256r1EcdsaPublicKey,
+        PartialAuthenticatorAssertionResponse,
+    )> {
+        let WebAuthnSignature {
+            public_key,
+            signature,
+            authenticator_data,
+            client_data_json,
+        } = self;
+        Ok((
+            public_key
+                .inner()
+                .try_into()
+                .context("Failed to parse given public_key bytes as a Secp256r1EcdsaPublicKey")?,
+            PartialAuthenticatorAssertionResponse::new(
+                signature
+                    .inner()
+                    .try_into()
+                    .context("Failed to parse given signature as a Secp256r1EcdsaSignature")?,
+                authenticator_data.into(),
+                client_data_json.into(),
+            ),
+        ))
+    }
+}
+
+impl VerifyInput for WebAuthnSignature {
+    fn verify(&self) -> anyhow::Result<()> {
+        let public_key_len = self.public_key.inner().len();
+        let signature_len = self.signature.inner().len();
+        let authenticator_data_len = self.authenticator_data.inner().len();
+        if public_key_len != SECP256R1_ECDSA_PUBLIC_KEY_LENGTH {
+            bail!(
+                "WebAuthn signature's public key is an invalid number of bytes, should be {} bytes but found {}",
+                SECP256R1_ECDSA_PUBLIC_KEY_LENGTH, public_key_len
+            )
+        } else if signature_len != SECP256R1_ECDSA_SIGNATURE_LENGTH {
+            bail!(
+                "WebAuthn signature length is an invalid number of bytes, should be {} bytes but found {}",
+                SECP256R1_ECDSA_SIGNATURE_LENGTH, signature_len
+            )
+        } else if authenticator_data_len < MIN_AUTHENTICATOR_DATA_LENGTH {
+            bail!(
+                "WebAuthn authenticator data is too short, should be at least {} bytes but found {}",
+                MIN_AUTHENTICATOR_DATA_LENGTH, authenticator_data_len
+            )
+        } else if std::str::from_utf8(self.client_data_json.inner()).is_err() {
+            bail!("WebAuthn client data JSON is not valid UTF-8")
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl TryFrom<WebAuthnSignature> for TransactionAuthenticator {
+    type Error = anyhow::Error;
+
+    fn try_from(value: WebAuthnSignature) -> Result<Self, Self::Error> {
+        let (public_key, assertion) = value.parse()?;
+        Ok(TransactionAuthenticator::webauthn(public_key, assertion))
+    }
+}
+
+impl TryFrom<WebAuthnSignature> for AccountAuthenticator {
+    type Error = anyhow::Error;
+
+    fn try_from(value: WebAuthnSignature) -> Result<Self, Self::Error> {
+        let (public_key, assertion) = value.parse()?;
+        Ok(AccountAuthenticator::webauthn(public_key, assertion))
+    }
+}
+
+/// An opaque authenticator for an account using account abstraction
+///
+/// The authenticator is passed, along with the SHA3-256 digest of the transaction's signing
+/// message, to the Move function registered by the account, which decides whether it is valid.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct AbstractionSignature {
+    pub authenticator: HexEncodedBytes,
+}
+
+impl VerifyInput for AbstractionSignature {
+    fn verify(&self) -> anyhow::Result<()> {
+        if self.authenticator.inner().is_empty() {
+            bail!("Abstraction signature's authenticator must not be empty")
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<AbstractionSignature> for TransactionAuthenticator {
+    type Error = anyhow::Error;
+
+    fn try_from(value: AbstractionSignature) -> Result<Self, Self::Error> {
+        Ok(TransactionAuthenticator::abstraction(
+            value.authenticator.into(),
+        ))
+    }
+}
+
+impl TryFrom<AbstractionSignature> for AccountAuthenticator {
+    type Error = anyhow::Error;
+
+    fn try_from(value: AbstractionSignature) -> Result<Self, Self::Error> {
+        Ok(AccountAuthenticator::abstraction(
+            value.authenticator.into(),
+        ))
+    }
+}
+
 /// A Ed25519 multi-sig signature
 ///
 /// This allows k-of-n signing for a transaction
@@ -1089,16 +1303,22 @@ impl TryFrom<MultiEd25519Signature> for AccountAuthenticator {
 
 /// Account signature scheme
 ///
-/// The account signature scheme allows you to have two types of accounts:
+/// The account signature scheme allows you to have five types of accounts:
 ///
 ///   1. A single Ed25519 key account, one private key
 ///   2. A k-of-n multi-Ed25519 key account, multiple private keys, such that k-of-n must sign a transaction.
+///   3. A single Secp256k1 ECDSA key account, one private key
+///   4. A single WebAuthn (passkey) account, one Secp256r1 ECDSA credential
+///   5. An account abstraction account, authenticated by a Move function registered by the account
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Union)]
 #[serde(tag = "type", rename_all = "snake_case")]
 #[oai(one_of, discriminator_name = "type", rename_all = "snake_case")]
 pub enum AccountSignature {
     Ed25519Signature(Ed25519Signature),
     MultiEd25519Signature(MultiEd25519Signature),
+    Secp256k1EcdsaSignature(Secp256k1EcdsaSignature),
+    WebAuthnSignature(WebAuthnSignature),
+    AbstractionSignature(AbstractionSignature),
 }
 
 impl VerifyInput for AccountSignature {
@@ -1106,6 +1326,9 @@ impl VerifyInput for AccountSignature {
         match self {
             AccountSignature::Ed25519Signature(inner) => inner.verify(),
             AccountSignature::MultiEd25519Signature(inner) => inner.verify(),
+            AccountSignature::Secp256k1EcdsaSignature(inner) => inner.verify(),
+            AccountSignature::WebAuthnSignature(inner) => inner.verify(),
+            AccountSignature::AbstractionSignature(inner) => inner.verify(),
         }
     }
 }
@@ -1117,6 +1340,9 @@ impl TryFrom<AccountSignature> for AccountAuthenticator {
         Ok(match sig {
             AccountSignature::Ed25519Signature(s) => s.try_into()?,
             AccountSignature::MultiEd25519Signature(s) => s.try_into()?,
+            AccountSignature::Secp256k1EcdsaSignature(s) => s.try_into()?,
+            AccountSignature::WebAuthnSignature(s) => s.try_into()?,
+            AccountSignature::AbstractionSignature(s) => s.try_into()?,
         })
     }
 }
@@ -1184,6 +1410,46 @@ impl From<(&Ed25519PublicKey, &ed25519::Ed25519Signature)> for Ed25519Signature
     }
 }
 
+impl
+    From<(
+        &Secp256k1EcdsaPublicKey,
+        &secp256k1_ecdsa::Secp256k1EcdsaSignature,
+    )> for Secp256k1EcdsaSignature
+{
+    fn from(
+        (pk, sig): (
+            &Secp256k1EcdsaPublicKey,
+            &secp256k1_ecdsa::Secp256k1EcdsaSignature,
+        ),
+    ) -> Self {
+        Self {
+            public_key: pk.to_bytes().to_vec().into(),
+            signature: sig.to_bytes().to_vec().into(),
+        }
+    }
+}
+
+impl
+    From<(
+        &Secp256r1EcdsaPublicKey,
+        &PartialAuthenticatorAssertionResponse,
+    )> for WebAuthnSignature
+{
+    fn from(
+        (pk, assertion): (
+            &Secp256r1EcdsaPublicKey,
+            &PartialAuthenticatorAssertionResponse,
+        ),
+    ) -> Self {
+        Self {
+            public_key: pk.to_bytes().to_vec().into(),
+            signature: assertion.signature().to_bytes().to_vec().into(),
+            authenticator_data: assertion.authenticator_data().to_vec().into(),
+            client_data_json: assertion.client_data_json().to_vec().into(),
+        }
+    }
+}
+
 impl
     From<(
         &MultiEd25519PublicKey,
@@ -1225,6 +1491,17 @@ impl From<&AccountAuthenticator> for AccountSignature {
                 public_key,
                 signature,
             } => Self::MultiEd25519Signature((public_key, signature).into()),
+            Secp256k1Ecdsa {
+                public_key,
+                signature,
+            } => Self::Secp256k1EcdsaSignature((public_key, signature).into()),
+            WebAuthn {
+                public_key,
+                signature,
+            } => Self::WebAuthnSignature((public_key, signature).into()),
+            Abstraction { authenticator } => Self::AbstractionSignature(AbstractionSignature {
+                authenticator: authenticator.clone().into(),
+            }),
         }
     }
 }
@@ -1369,6 +1646,17 @@ impl From<TransactionAuthenticator> for TransactionSignature {
                 )
                     .into(),
             ),
+            Secp256k1Ecdsa {
+                public_key,
+                signature,
+            } => Self::Secp256k1EcdsaSignature((public_key, signature).into()),
+            WebAuthn {
+                public_key,
+                signature,
+            } => Self::WebAuthnSignature((public_key, signature).into()),
+            Abstraction { authenticator } => Self::AbstractionSignature(AbstractionSignature {
+                authenticator: authenticator.clone().into(),
+            }),
         }
     }
 }
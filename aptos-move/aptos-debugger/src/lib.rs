@@ -22,6 +22,10 @@ use aptos_types::{
 use aptos_validator_interface::{
     AptosValidatorInterface, DBDebuggerInterface, DebuggerStateView, RestDebuggerInterface,
 };
+#[cfg(feature = "instruction-tracing")]
+use aptos_vm::move_vm_ext::trace::execute_block_with_trace;
+#[cfg(feature = "instruction-tracing")]
+pub use aptos_vm::move_vm_ext::trace::{FrameValues, TraceDetail, TraceEvent, TransactionTrace};
 use aptos_vm::{
     data_cache::StorageAdapter,
     move_vm_ext::{MoveVmExt, SessionExt, SessionId},
@@ -30,15 +34,43 @@ use aptos_vm::{
 use aptos_vm_logging::log_schema::AdapterLogSchema;
 use aptos_vm_types::{change_set::VMChangeSet, output::VMOutput, storage::ChangeSetConfigs};
 use move_binary_format::errors::VMResult;
+#[cfg(feature = "instruction-tracing")]
+use std::{fs, path::PathBuf};
 use std::{path::Path, sync::Arc};
 
 pub struct AptosDebugger {
     debugger: Arc<dyn AptosValidatorInterface + Send>,
+    #[cfg(feature = "instruction-tracing")]
+    trace_dir: Option<PathBuf>,
+    #[cfg(feature = "instruction-tracing")]
+    trace_detail: TraceDetail,
 }
 
 impl AptosDebugger {
     pub fn new(debugger: Arc<dyn AptosValidatorInterface + Send>) -> Self {
-        Self { debugger }
+        Self {
+            debugger,
+            #[cfg(feature = "instruction-tracing")]
+            trace_dir: None,
+            #[cfg(feature = "instruction-tracing")]
+            trace_detail: TraceDetail::default(),
+        }
+    }
+
+    /// Traces every executed transaction at the bytecode level, into a BCS encoded
+    /// [`TransactionTrace`] per transaction in `trace_dir`, see [`Self::trace_file`].
+    /// Transactions are then executed sequentially.
+    #[cfg(feature = "instruction-tracing")]
+    pub fn with_trace_dir(mut self, trace_dir: PathBuf, detail: TraceDetail) -> Self {
+        self.trace_dir = Some(trace_dir);
+        self.trace_detail = detail;
+        self
+    }
+
+    /// Where the trace of the transaction at `version` is written to.
+    #[cfg(feature = "instruction-tracing")]
+    pub fn trace_file(trace_dir: &Path, version: Version) -> PathBuf {
+        trace_dir.join(format!("{}.trace", version))
     }
 
     pub fn rest_client(rest_client: Client) -> Result<Self> {
@@ -57,10 +89,37 @@ impl AptosDebugger {
         txns: Vec<Transaction>,
     ) -> Result<Vec<TransactionOutput>> {
         let state_view = DebuggerStateView::new(self.debugger.clone(), version);
+        #[cfg(feature = "instruction-tracing")]
+        if let Some(trace_dir) = &self.trace_dir {
+            return self.execute_transactions_with_trace(&state_view, version, txns, trace_dir);
+        }
+
         AptosVM::execute_block(txns, &state_view, None)
             .map_err(|err| format_err!("Unexpected VM Error: {:?}", err))
     }
 
+    #[cfg(feature = "instruction-tracing")]
+    fn execute_transactions_with_trace(
+        &self,
+        state_view: &DebuggerStateView,
+        version: Version,
+        txns: Vec<Transaction>,
+        trace_dir: &Path,
+    ) -> Result<Vec<TransactionOutput>> {
+        let results = execute_block_with_trace(txns, state_view, None, self.trace_detail)
+            .map_err(|err| format_err!("Unexpected VM Error: {:?}", err))?;
+        fs::create_dir_all(trace_dir)?;
+        let mut outputs = vec![];
+        for (idx, (output, trace)) in results.into_iter().enumerate() {
+            fs::write(
+                Self::trace_file(trace_dir, version + idx as Version),
+                bcs::to_bytes(&trace)?,
+            )?;
+            outputs.push(output);
+        }
+        Ok(outputs)
+    }
+
     pub fn execute_transaction_at_version_with_gas_profiler(
         &self,
         version: Version,
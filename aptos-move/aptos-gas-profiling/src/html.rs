@@ -0,0 +1,564 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::report::{GasItem, GasProfileSummary};
+use anyhow::bail;
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Write},
+};
+
+const STYLE: &str = r#"
+body { font-family: sans-serif; margin: 2em; color: #222; }
+h1 { font-size: 1.4em; }
+h2 { font-size: 1.1em; margin-top: 2em; }
+table { border-collapse: collapse; margin-top: 0.5em; }
+th, td { border: 1px solid #ccc; padding: 0.25em 0.75em; text-align: right; }
+th { background: #f0f0f0; cursor: pointer; user-select: none; }
+td:first-child, th:first-child { text-align: left; font-family: monospace; }
+td.increase { color: #b00020; }
+td.decrease { color: #007a33; }
+"#;
+
+/// Sorts a table by the clicked column, using the `data-value` of the cells when present.
+const SCRIPT: &str = r#"
+document.querySelectorAll("table.sortable th").forEach(function (th, col) {
+  th.addEventListener("click", function () {
+    var table = th.closest("table");
+    var rows = Array.from(table.querySelectorAll("tbody tr"));
+    var asc = th.dataset.order !== "asc";
+    th.dataset.order = asc ? "asc" : "desc";
+    var key = function (row) {
+      var cell = row.children[col];
+      return cell.dataset.value !== undefined ? parseFloat(cell.dataset.value) : cell.textContent;
+    };
+    rows.sort(function (a, b) {
+      var x = key(a), y = key(b);
+      return (x < y ? -1 : x > y ? 1 : 0) * (asc ? 1 : -1);
+    });
+    rows.forEach(function (row) { table.querySelector("tbody").appendChild(row); });
+  });
+});
+"#;
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '&' => escaped.push_str("&amp;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn fmt_scaled(amount: i128, scale: u64) -> String {
+    let scaled = format!("{:.8}", amount as f64 / scale as f64);
+    crate::misc::strip_trailing_zeros_and_decimal_point(&scaled).to_string()
+}
+
+fn fmt_percentage(amount: u64, total: u64) -> String {
+    if total == 0 {
+        return "".to_string();
+    }
+    format!("{:.2}%", amount as f64 / total as f64 * 100.0)
+}
+
+/// A table cell, rendered with its raw value so that the table can be sorted numerically.
+enum Cell {
+    Text(String),
+    Amount { value: i128, scale: u64 },
+    Count(usize),
+    Percentage { amount: u64, total: u64 },
+    Delta { value: i128, scale: u64 },
+}
+
+impl Cell {
+    fn render(&self, output: &mut impl Write) -> fmt::Result {
+        match self {
+            Cell::Text(text) => write!(output, "<td>{}</td>", escape(text)),
+            Cell::Amount { value, scale } => write!(
+                output,
+                "<td data-value=\"{}\">{}</td>",
+                value,
+                fmt_scaled(*value, *scale)
+            ),
+            Cell::Count(count) => write!(output, "<td data-value=\"{}\">{}</td>", count, count),
+            Cell::Percentage { amount, total } => write!(
+                output,
+                "<td data-value=\"{}\">{}</td>",
+                amount,
+                fmt_percentage(*amount, *total)
+            ),
+            Cell::Delta { value, scale } => {
+                let (class, sign) = match value.signum() {
+                    1 => ("increase", "+"),
+                    -1 => ("decrease", ""),
+                    _ => ("", ""),
+                };
+                write!(
+                    output,
+                    "<td class=\"{}\" data-value=\"{}\">{}{}</td>",
+                    class,
+                    value,
+                    sign,
+                    fmt_scaled(*value, *scale)
+                )
+            },
+        }
+    }
+}
+
+fn render_table(
+    output: &mut impl Write,
+    title: &str,
+    header: &[&str],
+    rows: Vec<Vec<Cell>>,
+) -> fmt::Result {
+    writeln!(output, "<h2>{}</h2>", escape(title))?;
+    if rows.is_empty() {
+        return writeln!(output, "<p>None</p>");
+    }
+    write!(output, "<table class=\"sortable\"><thead><tr>")?;
+    for column in header {
+        write!(output, "<th>{}</th>", escape(column))?;
+    }
+    writeln!(output, "</tr></thead><tbody>")?;
+    for row in rows {
+        write!(output, "<tr>")?;
+        for cell in row {
+            cell.render(output)?;
+        }
+        writeln!(output, "</tr>")?;
+    }
+    writeln!(output, "</tbody></table>")
+}
+
+fn render_page(
+    title: &str,
+    body: impl FnOnce(&mut String) -> fmt::Result,
+) -> anyhow::Result<String> {
+    let mut output = String::new();
+    writeln!(output, "<!DOCTYPE html>")?;
+    writeln!(output, "<html><head><meta charset=\"utf-8\">")?;
+    writeln!(output, "<title>{}</title>", escape(title))?;
+    writeln!(output, "<style>{}</style>", STYLE)?;
+    writeln!(output, "</head><body>")?;
+    writeln!(output, "<h1>{}</h1>", escape(title))?;
+    body(&mut output)?;
+    writeln!(output, "<script>{}</script>", SCRIPT)?;
+    writeln!(output, "</body></html>")?;
+    Ok(output)
+}
+
+/// Octas per APT, the unit storage fees are displayed in.
+const OCTAS_PER_APT: u64 = 1_0000_0000;
+
+impl GasProfileSummary {
+    fn gas_item_rows(&self, items: &[GasItem]) -> Vec<Vec<Cell>> {
+        items
+            .iter()
+            .map(|item| {
+                vec![
+                    Cell::Text(item.name.clone()),
+                    Cell::Count(item.count),
+                    Cell::Amount {
+                        value: item.cost as i128,
+                        scale: self.gas_scaling_factor,
+                    },
+                    Cell::Percentage {
+                        amount: item.cost,
+                        total: self.execution_and_io,
+                    },
+                ]
+            })
+            .collect()
+    }
+
+    /// Renders the summary as a self-contained HTML page with sortable tables.
+    pub fn to_html_report(&self, title: &str) -> anyhow::Result<String> {
+        render_page(title, |output| {
+            let gas = |value: u64| Cell::Amount {
+                value: value as i128,
+                scale: self.gas_scaling_factor,
+            };
+            let apt = |value: u64| Cell::Amount {
+                value: value as i128,
+                scale: OCTAS_PER_APT,
+            };
+
+            render_table(output, "Summary", &["", "Amount"], vec![
+                vec![
+                    Cell::Text("entry point".to_string()),
+                    Cell::Text(self.entry_point.clone()),
+                ],
+                vec![
+                    Cell::Text("execution & IO (gas units)".to_string()),
+                    gas(self.execution_and_io),
+                ],
+                vec![
+                    Cell::Text("intrinsic (gas units)".to_string()),
+                    gas(self.intrinsic),
+                ],
+                vec![
+                    Cell::Text("storage fee (APT)".to_string()),
+                    apt(self.storage_fee),
+                ],
+                vec![
+                    Cell::Text("transaction storage (APT)".to_string()),
+                    apt(self.txn_storage),
+                ],
+                vec![
+                    Cell::Text("event discount (APT)".to_string()),
+                    apt(self.event_discount),
+                ],
+                vec![
+                    Cell::Text("refundable deposits (APT)".to_string()),
+                    apt(self.total_refundable()),
+                ],
+            ])?;
+
+            let header = ["Name", "Count", "Gas units", "Share"];
+            render_table(
+                output,
+                "Instructions & native calls",
+                &header,
+                self.gas_item_rows(&self.ops),
+            )?;
+            render_table(
+                output,
+                "Storage reads",
+                &header,
+                self.gas_item_rows(&self.storage_reads),
+            )?;
+            render_table(
+                output,
+                "Storage writes",
+                &header,
+                self.gas_item_rows(&self.storage_writes),
+            )?;
+
+            let slot_rows = self
+                .storage_slots
+                .iter()
+                .map(|slot| {
+                    vec![
+                        Cell::Text(slot.key.clone()),
+                        Cell::Text(slot.op_type.clone()),
+                        apt(slot.fee),
+                        apt(slot.refundable),
+                        gas(slot.io_cost),
+                        Cell::Percentage {
+                            amount: slot.fee,
+                            total: self.storage_fee,
+                        },
+                    ]
+                })
+                .collect();
+            render_table(
+                output,
+                "Storage fee per state slot",
+                &[
+                    "State key",
+                    "Operation",
+                    "Fee (APT)",
+                    "Refundable (APT)",
+                    "IO gas units",
+                    "Share",
+                ],
+                slot_rows,
+            )?;
+
+            let event_rows = self
+                .events
+                .iter()
+                .map(|event| {
+                    vec![
+                        Cell::Text(event.name.clone()),
+                        Cell::Count(event.count),
+                        apt(event.cost),
+                        Cell::Percentage {
+                            amount: event.cost,
+                            total: self.storage_fee,
+                        },
+                    ]
+                })
+                .collect();
+            render_table(
+                output,
+                "Storage fee per event type",
+                &["Event type", "Count", "Fee (APT)", "Share"],
+                event_rows,
+            )
+        })
+    }
+
+    /// Renders the difference between this profile and a baseline profile of the same entry
+    /// function as a self-contained HTML page. Items are sorted by how much their cost changed.
+    pub fn to_html_diff_report(
+        &self,
+        baseline: &GasProfileSummary,
+        title: &str,
+    ) -> anyhow::Result<String> {
+        if self.entry_point != baseline.entry_point {
+            bail!(
+                "cannot compare gas profiles of different entry points: {} and {}",
+                baseline.entry_point,
+                self.entry_point
+            );
+        }
+        if self.gas_scaling_factor != baseline.gas_scaling_factor {
+            bail!(
+                "cannot compare gas profiles with different gas scaling factors: {} and {}",
+                baseline.gas_scaling_factor,
+                self.gas_scaling_factor
+            );
+        }
+
+        render_page(title, |output| {
+            let scale = self.gas_scaling_factor;
+            let totals = [
+                (
+                    "execution & IO (gas units)",
+                    baseline.execution_and_io,
+                    self.execution_and_io,
+                    scale,
+                ),
+                (
+                    "intrinsic (gas units)",
+                    baseline.intrinsic,
+                    self.intrinsic,
+                    scale,
+                ),
+                (
+                    "storage fee (APT)",
+                    baseline.storage_fee,
+                    self.storage_fee,
+                    OCTAS_PER_APT,
+                ),
+                (
+                    "transaction storage (APT)",
+                    baseline.txn_storage,
+                    self.txn_storage,
+                    OCTAS_PER_APT,
+                ),
+                (
+                    "event discount (APT)",
+                    baseline.event_discount,
+                    self.event_discount,
+                    OCTAS_PER_APT,
+                ),
+                (
+                    "refundable deposits (APT)",
+                    baseline.total_refundable(),
+                    self.total_refundable(),
+                    OCTAS_PER_APT,
+                ),
+            ];
+            let header = ["", "Baseline", "Current", "Delta"];
+            render_table(
+                output,
+                &format!("Summary of {}", self.entry_point),
+                &header,
+                totals
+                    .iter()
+                    .map(|(name, before, after, scale)| {
+                        diff_row(name.to_string(), *before, *after, *scale)
+                    })
+                    .collect(),
+            )?;
+
+            let header = ["Name", "Baseline", "Current", "Delta"];
+            let items = |items: &[GasItem]| {
+                items
+                    .iter()
+                    .map(|item| (item.name.clone(), item.cost))
+                    .collect::<Vec<_>>()
+            };
+            render_table(
+                output,
+                "Instructions & native calls (gas units)",
+                &header,
+                diff_rows(items(&baseline.ops), items(&self.ops), scale),
+            )?;
+            render_table(
+                output,
+                "Storage reads (gas units)",
+                &header,
+                diff_rows(
+                    items(&baseline.storage_reads),
+                    items(&self.storage_reads),
+                    scale,
+                ),
+            )?;
+            render_table(
+                output,
+                "Storage writes (gas units)",
+                &header,
+                diff_rows(
+                    items(&baseline.storage_writes),
+                    items(&self.storage_writes),
+                    scale,
+                ),
+            )?;
+
+            let slots = |summary: &GasProfileSummary| {
+                summary
+                    .storage_slots
+                    .iter()
+                    .map(|slot| (format!("{} ({})", slot.key, slot.op_type), slot.fee))
+                    .collect::<Vec<_>>()
+            };
+            render_table(
+                output,
+                "Storage fee per state slot (APT)",
+                &["State key", "Baseline", "Current", "Delta"],
+                diff_rows(slots(baseline), slots(self), OCTAS_PER_APT),
+            )?;
+            render_table(
+                output,
+                "Storage fee per event type (APT)",
+                &["Event type", "Baseline", "Current", "Delta"],
+                diff_rows(items(&baseline.events), items(&self.events), OCTAS_PER_APT),
+            )
+        })
+    }
+}
+
+fn diff_row(name: String, before: u64, after: u64, scale: u64) -> Vec<Cell> {
+    vec![
+        Cell::Text(name),
+        Cell::Amount {
+            value: before as i128,
+            scale,
+        },
+        Cell::Amount {
+            value: after as i128,
+            scale,
+        },
+        Cell::Delta {
+            value: after as i128 - before as i128,
+            scale,
+        },
+    ]
+}
+
+/// Matches the items of two profiles by name, treating missing items as free, and sorts them by
+/// the absolute change of their cost.
+fn diff_rows(before: Vec<(String, u64)>, after: Vec<(String, u64)>, scale: u64) -> Vec<Vec<Cell>> {
+    let mut costs: BTreeMap<String, (u64, u64)> = BTreeMap::new();
+    for (name, cost) in before {
+        costs.entry(name).or_default().0 += cost;
+    }
+    for (name, cost) in after {
+        costs.entry(name).or_default().1 += cost;
+    }
+    let mut costs = costs.into_iter().collect::<Vec<_>>();
+    costs.sort_by_key(|(_name, (before, after))| std::cmp::Reverse(before.abs_diff(*after)));
+    costs
+        .into_iter()
+        .map(|(name, (before, after))| diff_row(name, before, after, scale))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::tests::{gas_item, summary};
+
+    /// The name, baseline, current and delta of every row.
+    fn diff_values(rows: Vec<Vec<Cell>>) -> Vec<(String, i128, i128, i128)> {
+        rows.into_iter()
+            .map(|row| match row.as_slice() {
+                [Cell::Text(name), Cell::Amount { value: before, .. }, Cell::Amount { value: after, .. }, Cell::Delta { value: delta, .. }] => {
+                    (name.clone(), *before, *after, *delta)
+                },
+                _ => panic!("unexpected diff row"),
+            })
+            .collect()
+    }
+
+    fn items(items: &[(&str, u64)]) -> Vec<(String, u64)> {
+        items
+            .iter()
+            .map(|(name, cost)| (name.to_string(), *cost))
+            .collect()
+    }
+
+    #[test]
+    fn test_diff_rows_matches_items_by_name() {
+        let rows = diff_rows(
+            items(&[("call", 100), ("ld_u64", 10), ("call", 50)]),
+            items(&[("ld_u64", 10), ("call", 120)]),
+            1,
+        );
+        assert_eq!(diff_values(rows), vec![
+            ("call".to_string(), 150, 120, -30),
+            ("ld_u64".to_string(), 10, 10, 0),
+        ]);
+    }
+
+    #[test]
+    fn test_diff_rows_treats_missing_items_as_free() {
+        let rows = diff_rows(
+            items(&[("removed", 40), ("kept", 5)]),
+            items(&[("kept", 5), ("added", 70)]),
+            1,
+        );
+        assert_eq!(diff_values(rows), vec![
+            ("added".to_string(), 0, 70, 70),
+            ("removed".to_string(), 40, 0, -40),
+            ("kept".to_string(), 5, 5, 0),
+        ]);
+    }
+
+    #[test]
+    fn test_diff_rows_sorted_by_absolute_change() {
+        let rows = diff_rows(
+            items(&[("a", 100), ("b", 100), ("c", 100), ("d", 100)]),
+            items(&[("a", 101), ("b", 10), ("c", 150), ("d", 100)]),
+            1,
+        );
+        let names = diff_values(rows)
+            .into_iter()
+            .map(|(name, ..)| name)
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["b", "c", "a", "d"]);
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(
+            escape("vector<0x1::string::String> & \"quoted\""),
+            "vector&lt;0x1::string::String&gt; &amp; &quot;quoted&quot;"
+        );
+        assert_eq!(escape("plain"), "plain");
+    }
+
+    #[test]
+    fn test_reports_escape_names() {
+        let mut current = summary("0xcafe::m::f");
+        current.ops.push(gas_item("<script>alert(1)</script>", 1));
+
+        let report = current.to_html_report("Gas report for <f>").unwrap();
+        assert!(!report.contains("<script>alert(1)</script>"));
+        assert!(report.contains("&lt;script&gt;alert(1)&lt;/script&gt;"));
+        assert!(report.contains("<title>Gas report for &lt;f&gt;</title>"));
+
+        let diff = current
+            .to_html_diff_report(&summary("0xcafe::m::f"), "Gas diff")
+            .unwrap();
+        assert!(!diff.contains("<script>alert(1)</script>"));
+        assert!(diff.contains("&lt;script&gt;alert(1)&lt;/script&gt;"));
+    }
+
+    #[test]
+    fn test_diff_report_rejects_different_entry_points() {
+        assert!(summary("0xcafe::m::f")
+            .to_html_diff_report(&summary("0xcafe::m::g"), "Gas diff")
+            .is_err());
+    }
+}
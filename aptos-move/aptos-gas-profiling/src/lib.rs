@@ -4,11 +4,14 @@
 mod aggregate;
 mod erased;
 mod flamegraph;
+mod html;
 mod log;
 mod misc;
 mod profiler;
 mod render;
+mod report;
 mod textualize;
 
-pub use log::{FrameName, TransactionGasLog};
+pub use log::{CallFrame, ExecutionGasEvent, FrameName, TransactionGasLog};
 pub use profiler::GasProfiler;
+pub use report::{GasItem, GasProfileSummary, StorageSlotFee};
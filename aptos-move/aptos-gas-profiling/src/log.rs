@@ -81,6 +81,8 @@ pub struct WriteStorage {
     pub key: StateKey,
     pub op_type: WriteOpType,
     pub cost: Fee,
+    /// The part of the cost recorded as a deposit on the slot, refunded when it is deleted.
+    pub refundable: Fee,
 }
 
 #[derive(Debug)]
@@ -166,6 +168,26 @@ impl CallFrame {
             events: vec![],
         }
     }
+
+    /// Returns the cost of the frame, including the costs of all nested calls.
+    pub fn total_cost(&self) -> InternalGas {
+        self.events
+            .iter()
+            .fold(0.into(), |total, event| total + event.total_cost())
+    }
+}
+
+impl ExecutionGasEvent {
+    /// Returns the cost of the event, including the costs of all nested calls.
+    pub fn total_cost(&self) -> InternalGas {
+        use ExecutionGasEvent::*;
+
+        match self {
+            Loc(_) => 0.into(),
+            Bytecode { cost, .. } | CallNative { cost, .. } | LoadResource { cost, .. } => *cost,
+            Call(frame) => frame.total_cost(),
+        }
+    }
 }
 
 impl ExecutionAndIOCosts {
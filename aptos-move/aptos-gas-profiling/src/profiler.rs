@@ -541,10 +541,15 @@ where
             Self::maybe_record_storage_deposit(op, slot_fee);
 
             let fee = slot_fee + bytes_fee;
+            let refundable = match op {
+                WriteOp::CreationWithMetadata { .. } => slot_fee,
+                _ => 0.into(),
+            };
             write_set_storage.push(WriteStorage {
                 key: key.clone(),
                 op_type: write_op_type(op),
                 cost: fee,
+                refundable,
             });
             write_fee += fee;
         }
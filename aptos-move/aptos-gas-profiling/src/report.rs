@@ -0,0 +1,208 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    log::{FrameName, TransactionGasLog},
+    render::Render,
+};
+use aptos_types::state_store::state_key::StateKeyInner;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops::Deref,
+};
+
+/// An item of an aggregated gas log, e.g. an instruction or a resource type, along with the
+/// number of times it was charged for and its total cost.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct GasItem {
+    pub name: String,
+    pub count: usize,
+    pub cost: u64,
+}
+
+/// The storage fee charged for a single state slot written by the transaction.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct StorageSlotFee {
+    pub key: String,
+    pub op_type: String,
+    /// Storage fee, in octas.
+    pub fee: u64,
+    /// The part of the fee that is refunded when the slot is deleted, in octas.
+    pub refundable: u64,
+    /// IO gas charged for writing the slot, in internal gas units.
+    pub io_cost: u64,
+}
+
+/// A self-contained summary of a transaction gas log, with every item rendered to a name.
+///
+/// Unlike the gas log itself, the summary can be saved and later compared against the profile
+/// of another run of the same entry function, e.g. before and after a code change or a gas
+/// schedule update.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct GasProfileSummary {
+    /// The entry function, without its type arguments, or `<script>`.
+    pub entry_point: String,
+    pub gas_scaling_factor: u64,
+
+    /// Execution & IO costs, in internal gas units.
+    pub execution_and_io: u64,
+    pub intrinsic: u64,
+    pub ops: Vec<GasItem>,
+    pub storage_reads: Vec<GasItem>,
+    pub storage_writes: Vec<GasItem>,
+
+    /// Storage fees, in octas.
+    pub storage_fee: u64,
+    pub storage_slots: Vec<StorageSlotFee>,
+    pub events: Vec<GasItem>,
+    pub event_discount: u64,
+    pub txn_storage: u64,
+}
+
+fn gas_items<N: Into<u64>>(items: Vec<(String, usize, N)>) -> Vec<GasItem> {
+    items
+        .into_iter()
+        .map(|(name, count, cost)| GasItem {
+            name,
+            count,
+            cost: cost.into(),
+        })
+        .collect()
+}
+
+impl TransactionGasLog {
+    /// Flattens the log into a summary, which can be rendered as an HTML report.
+    pub fn summarize(&self) -> GasProfileSummary {
+        let aggregated = self.exec_io.aggregate_gas_events();
+
+        let entry_point = match self.entry_point() {
+            FrameName::Script => "<script>".to_string(),
+            FrameName::Function {
+                module_id, name, ..
+            } => format!("{}::{}", module_id.short_str_lossless(), name),
+        };
+
+        let mut io_costs = HashMap::new();
+        for write in &self.exec_io.write_set_transient {
+            *io_costs.entry(&write.key).or_insert(0) += u64::from(write.cost);
+        }
+
+        let mut storage_slots = self
+            .storage
+            .write_set_storage
+            .iter()
+            .map(|write| {
+                use StateKeyInner::*;
+
+                let key = match write.key.deref() {
+                    AccessPath(ap) => {
+                        format!(
+                            "{}::{}",
+                            ap.address.to_hex_literal(),
+                            Render(&ap.get_path())
+                        )
+                    },
+                    TableItem { handle, key } => format!(
+                        "table_item<{},0x{}>",
+                        handle.0.to_hex_literal(),
+                        key.iter()
+                            .map(|byte| format!("{:02x}", byte))
+                            .collect::<String>()
+                    ),
+                    Raw(..) => panic!("not supported"),
+                };
+                StorageSlotFee {
+                    key,
+                    op_type: format!("{}", Render(&write.op_type)),
+                    fee: write.cost.into(),
+                    refundable: write.refundable.into(),
+                    io_cost: io_costs.get(&write.key).copied().unwrap_or(0),
+                }
+            })
+            .collect::<Vec<_>>();
+        storage_slots.sort_by(|slot1, slot2| slot2.fee.cmp(&slot1.fee));
+
+        let mut events: BTreeMap<String, (usize, u64)> = BTreeMap::new();
+        for event in &self.storage.events {
+            let entry = events.entry(format!("{}", event.ty)).or_default();
+            entry.0 += 1;
+            entry.1 += u64::from(event.cost);
+        }
+        let mut events = events
+            .into_iter()
+            .map(|(name, (count, cost))| GasItem { name, count, cost })
+            .collect::<Vec<_>>();
+        events.sort_by(|event1, event2| event2.cost.cmp(&event1.cost));
+
+        GasProfileSummary {
+            entry_point,
+            gas_scaling_factor: aggregated.gas_scaling_factor.into(),
+            execution_and_io: aggregated.total.into(),
+            intrinsic: self.exec_io.intrinsic_cost.into(),
+            ops: gas_items(aggregated.ops),
+            storage_reads: gas_items(aggregated.storage_reads),
+            storage_writes: gas_items(aggregated.storage_writes),
+            storage_fee: self.storage.total.into(),
+            storage_slots,
+            events,
+            event_discount: self.storage.event_discount.into(),
+            txn_storage: self.storage.txn_storage.into(),
+        }
+    }
+}
+
+impl GasProfileSummary {
+    /// Total refundable deposits of the slots created by the transaction, in octas.
+    pub fn total_refundable(&self) -> u64 {
+        self.storage_slots.iter().map(|slot| slot.refundable).sum()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub(crate) fn gas_item(name: &str, cost: u64) -> GasItem {
+        GasItem {
+            name: name.to_string(),
+            count: 1,
+            cost,
+        }
+    }
+
+    pub(crate) fn summary(entry_point: &str) -> GasProfileSummary {
+        GasProfileSummary {
+            entry_point: entry_point.to_string(),
+            gas_scaling_factor: 100,
+            execution_and_io: 1_500,
+            intrinsic: 300,
+            ops: vec![gas_item("ld_u64", 200), gas_item("call", 1_000)],
+            storage_reads: vec![gas_item(
+                "0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>",
+                300,
+            )],
+            storage_writes: vec![],
+            storage_fee: 50_000,
+            storage_slots: vec![StorageSlotFee {
+                key: "0xcafe::counter::Counter".to_string(),
+                op_type: "create".to_string(),
+                fee: 40_000,
+                refundable: 40_000,
+                io_cost: 1_000,
+            }],
+            events: vec![gas_item("0x1::coin::DepositEvent", 10_000)],
+            event_discount: 0,
+            txn_storage: 0,
+        }
+    }
+
+    #[test]
+    fn test_summary_json_round_trip() {
+        let summary = summary("0xcafe::counter::increment");
+        let json = serde_json::to_string_pretty(&summary).unwrap();
+        let parsed: GasProfileSummary = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, summary);
+        assert_eq!(parsed.total_refundable(), 40_000);
+    }
+}
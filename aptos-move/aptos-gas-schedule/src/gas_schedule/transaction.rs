@@ -173,6 +173,13 @@ crate::gas_schedule::macros::define_gas_parameters!(
             max_storage_fee: Fee,
             { 7.. => "max_storage_fee" },
             2_0000_0000, // 2 APT
+        ],
+        // The maximum amount of gas the authentication function of an account using account
+        // abstraction may consume, per signer.
+        [
+            max_aa_gas: Gas,
+            { 12.. => "max_aa_gas" },
+            60,
         ]
     ]
 );
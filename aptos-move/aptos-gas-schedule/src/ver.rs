@@ -8,6 +8,8 @@
 ///   - Changing how gas is calculated in any way
 ///
 /// Change log:
+/// - V12
+///   - Added max_aa_gas, the gas limit for account abstraction authentication functions
 /// - V11
 //    - Ristretto255 natives (point cloning & double-scalar multiplication) and Bulletproofs natives
 /// - V10
@@ -41,4 +43,4 @@
 ///       global operations.
 /// - V1
 ///   - TBA
-pub const LATEST_GAS_FEATURE_VERSION: u64 = 11;
+pub const LATEST_GAS_FEATURE_VERSION: u64 = 12;
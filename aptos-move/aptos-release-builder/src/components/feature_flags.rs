@@ -47,6 +47,10 @@ pub enum FeatureFlag {
     BulletproofsNatives,
     SignerNativeFormatFix,
     ModuleEvent,
+    Secp256k1EcdsaAuthenticator,
+    WebAuthnAuthenticator,
+    AccountAbstraction,
+    AccessSummaryMetadata,
 }
 
 fn generate_features_blob(writer: &CodeWriter, data: &[u64]) {
@@ -172,6 +176,12 @@ impl From<FeatureFlag> for AptosFeatureFlag {
             FeatureFlag::BulletproofsNatives => AptosFeatureFlag::BULLETPROOFS_NATIVES,
             FeatureFlag::SignerNativeFormatFix => AptosFeatureFlag::SIGNER_NATIVE_FORMAT_FIX,
             FeatureFlag::ModuleEvent => AptosFeatureFlag::MODULE_EVENT,
+            FeatureFlag::Secp256k1EcdsaAuthenticator => {
+                AptosFeatureFlag::SECP256K1_ECDSA_AUTHENTICATOR
+            },
+            FeatureFlag::WebAuthnAuthenticator => AptosFeatureFlag::WEBAUTHN_AUTHENTICATOR,
+            FeatureFlag::AccountAbstraction => AptosFeatureFlag::ACCOUNT_ABSTRACTION,
+            FeatureFlag::AccessSummaryMetadata => AptosFeatureFlag::ACCESS_SUMMARY_METADATA,
         }
     }
 }
@@ -220,6 +230,12 @@ impl From<AptosFeatureFlag> for FeatureFlag {
             AptosFeatureFlag::BULLETPROOFS_NATIVES => FeatureFlag::BulletproofsNatives,
             AptosFeatureFlag::SIGNER_NATIVE_FORMAT_FIX => FeatureFlag::SignerNativeFormatFix,
             AptosFeatureFlag::MODULE_EVENT => FeatureFlag::ModuleEvent,
+            AptosFeatureFlag::SECP256K1_ECDSA_AUTHENTICATOR => {
+                FeatureFlag::Secp256k1EcdsaAuthenticator
+            },
+            AptosFeatureFlag::WEBAUTHN_AUTHENTICATOR => FeatureFlag::WebAuthnAuthenticator,
+            AptosFeatureFlag::ACCOUNT_ABSTRACTION => FeatureFlag::AccountAbstraction,
+            AptosFeatureFlag::ACCESS_SUMMARY_METADATA => FeatureFlag::AccessSummaryMetadata,
         }
     }
 }
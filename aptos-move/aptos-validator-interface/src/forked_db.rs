@@ -0,0 +1,988 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::AptosValidatorInterface;
+use anyhow::Result;
+use aptos_crypto::HashValue;
+use aptos_storage_interface::{
+    cached_state_view::ShardedStateCache, state_delta::StateDelta, DbReader, DbReaderWriter,
+    DbWriter, ExecutedTrees, Order, StateSnapshotReceiver, MAX_REQUEST_LIMIT,
+};
+use aptos_types::{
+    account_address::AccountAddress,
+    account_config::NewBlockEvent,
+    contract_event::{ContractEvent, EventWithVersion},
+    epoch_change::EpochChangeProof,
+    epoch_state::EpochState,
+    event::EventKey,
+    ledger_info::LedgerInfoWithSignatures,
+    proof::{
+        AccumulatorConsistencyProof, SparseMerkleProofExt, TransactionAccumulatorRangeProof,
+        TransactionAccumulatorSummary,
+    },
+    state_proof::StateProof,
+    state_store::{
+        state_key::StateKey,
+        state_key_prefix::StateKeyPrefix,
+        state_storage_usage::StateStorageUsage,
+        state_value::{StateValue, StateValueChunkWithProof},
+        table::{TableHandle, TableInfo},
+        ShardedStateUpdates,
+    },
+    transaction::{
+        AccountTransactionsWithProof, Transaction, TransactionInfo, TransactionListWithProof,
+        TransactionOutputListWithProof, TransactionToCommit, TransactionWithProof, Version,
+    },
+    write_set::WriteSet,
+};
+use lru::LruCache;
+use std::{
+    collections::HashSet,
+    fs::{File, OpenOptions},
+    io::{Read, Write},
+    path::Path,
+    sync::{mpsc, Arc, Mutex, RwLock},
+};
+use tokio::runtime::Runtime;
+
+/// Version reported for state values read from the forked network. They predate everything
+/// committed locally.
+const FORKED_STATE_VALUE_VERSION: Version = 0;
+
+/// Name of the file, in the directory of the local DB, logging the keys deleted locally.
+const DELETIONS_LOG_FILE_NAME: &str = "forked_db_deletions.log";
+
+/// A DB running a local chain on top of the state of another network.
+///
+/// Everything is read from and written to the `local` DB, except for the state values of keys
+/// that are absent locally and were never deleted locally: those are lazily read from the
+/// `remote` network as of `fork_version`, and cached in memory.
+///
+/// Known limitations:
+///   * Iterating state values by key prefix (e.g. listing the resources of an account) only sees
+///     the local state.
+///   * Once a key is deleted locally, its forked value is no longer visible, even when reading
+///     at a local version before that deletion.
+///   * State storage usage only accounts for the local state, plus or minus the forked values
+///     that got overwritten or deleted.
+///   * Nothing served from the remote network can be proven against the local state tree.
+pub struct ForkedDb {
+    local: DbReaderWriter,
+    remote: Arc<dyn AptosValidatorInterface + Send>,
+    fork_version: Version,
+    // `None` only while being dropped.
+    runtime: Option<Runtime>,
+    cache: Mutex<LruCache<StateKey, Option<StateValue>>>,
+    local_deletions: RwLock<HashSet<StateKey>>,
+    // Each record is the length-prefixed BCS of the number of transactions committed locally,
+    // along with the keys they deleted since the previous record.
+    deletions_log: Mutex<File>,
+}
+
+impl ForkedDb {
+    /// `db_dir` is the directory of the `local` DB, where the keys deleted locally are logged.
+    /// At most `cache_size` forked state values are cached in memory.
+    pub fn new(
+        local: DbReaderWriter,
+        remote: Arc<dyn AptosValidatorInterface + Send>,
+        fork_version: Version,
+        cache_size: usize,
+        db_dir: &Path,
+    ) -> Result<Self> {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .thread_name("forked-db")
+            .enable_all()
+            .build()?;
+        let (deletions_log, local_deletions) =
+            Self::open_deletions_log(&db_dir.join(DELETIONS_LOG_FILE_NAME), local.reader.as_ref())?;
+
+        Ok(Self {
+            local,
+            remote,
+            fork_version,
+            runtime: Some(runtime),
+            cache: Mutex::new(LruCache::new(cache_size)),
+            local_deletions: RwLock::new(local_deletions),
+            deletions_log: Mutex::new(deletions_log),
+        })
+    }
+
+    pub fn fork_version(&self) -> Version {
+        self.fork_version
+    }
+
+    /// Reads the keys deleted locally from the log, and catches up with the transactions
+    /// committed locally after its last record (e.g. if the node stopped in between).
+    fn open_deletions_log(path: &Path, reader: &dyn DbReader) -> Result<(File, HashSet<StateKey>)> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(path)?;
+        let mut bytes = vec![];
+        file.read_to_end(&mut bytes)?;
+
+        let mut deletions = HashSet::new();
+        let mut next_version = 0;
+        let mut offset = 0;
+        while let Some(len_bytes) = bytes.get(offset..offset + 4) {
+            let len = u32::from_le_bytes(len_bytes.try_into().expect("Length checked above."));
+            let record_bytes = match bytes.get(offset + 4..offset + 4 + len as usize) {
+                Some(record_bytes) => record_bytes,
+                None => break,
+            };
+            let (num_versions, keys): (Version, Vec<StateKey>) = bcs::from_bytes(record_bytes)?;
+            deletions.extend(keys);
+            next_version = num_versions;
+            offset += 4 + len as usize;
+        }
+        // Drops a record that was only partially written.
+        if offset < bytes.len() {
+            file.set_len(offset as u64)?;
+        }
+
+        let num_versions = match reader.get_latest_ledger_info_option()? {
+            Some(ledger_info) => ledger_info.ledger_info().version() + 1,
+            None => return Ok((file, deletions)),
+        };
+        let mut keys = vec![];
+        let mut version = next_version;
+        while version < num_versions {
+            let limit = std::cmp::min(MAX_REQUEST_LIMIT, num_versions - version);
+            for write_set in reader.get_write_set_iterator(version, limit)? {
+                keys.extend(Self::deleted_keys(&write_set?));
+            }
+            version += limit;
+        }
+        if next_version < num_versions {
+            Self::append_deletions(&mut file, num_versions, &keys)?;
+        }
+        deletions.extend(keys);
+        Ok((file, deletions))
+    }
+
+    fn deleted_keys(write_set: &WriteSet) -> impl Iterator<Item = StateKey> + '_ {
+        write_set
+            .iter()
+            .filter(|(_, write_op)| write_op.is_deletion())
+            .map(|(key, _)| key.clone())
+    }
+
+    fn append_deletions(file: &mut File, num_versions: Version, keys: &[StateKey]) -> Result<()> {
+        let record_bytes = bcs::to_bytes(&(num_versions, keys))?;
+        let mut bytes = (record_bytes.len() as u32).to_le_bytes().to_vec();
+        bytes.extend(record_bytes);
+        // A single write, so that a record is never interleaved with another one.
+        file.write_all(&bytes)?;
+        Ok(())
+    }
+
+    /// Logs the keys deleted by the transactions just committed locally, the last of which is
+    /// at version `num_versions - 1`.
+    fn record_local_deletions<'a>(
+        &self,
+        num_versions: Version,
+        write_sets: impl Iterator<Item = &'a WriteSet>,
+    ) -> Result<()> {
+        let keys: Vec<_> = write_sets.flat_map(Self::deleted_keys).collect();
+        if keys.is_empty() {
+            return Ok(());
+        }
+        // Transactions committed after the last record are replayed when opening the log, so a
+        // failure here doesn't lose anything once the node restarts.
+        Self::append_deletions(&mut self.deletions_log.lock().unwrap(), num_versions, &keys)?;
+        self.local_deletions.write().unwrap().extend(keys);
+        Ok(())
+    }
+
+    fn is_local_deletion(&self, state_key: &StateKey) -> bool {
+        self.local_deletions.read().unwrap().contains(state_key)
+    }
+
+    fn get_forked_state_value(&self, state_key: &StateKey) -> Result<Option<StateValue>> {
+        if let Some(value_opt) = self.cache.lock().unwrap().get(state_key) {
+            return Ok(value_opt.clone());
+        }
+
+        // The remote interface is async, while DB reads are blocking and might come from within
+        // another runtime. So the request is run on our own runtime and waited for here.
+        let (sender, receiver) = mpsc::channel();
+        let remote = self.remote.clone();
+        let key = state_key.clone();
+        let fork_version = self.fork_version;
+        self.runtime
+            .as_ref()
+            .expect("Runtime is only taken on drop.")
+            .spawn(async move {
+                // The receiver is only gone if the reading thread panicked.
+                let _ = sender.send(remote.get_state_value_by_version(&key, fork_version).await);
+            });
+        let value_opt = receiver.recv()??;
+
+        self.cache
+            .lock()
+            .unwrap()
+            .put(state_key.clone(), value_opt.clone());
+        Ok(value_opt)
+    }
+}
+
+impl Drop for ForkedDb {
+    fn drop(&mut self) {
+        // Dropping a runtime blocks, which panics if the DB is dropped within an async context.
+        if let Some(runtime) = self.runtime.take() {
+            runtime.shutdown_background();
+        }
+    }
+}
+
+impl DbReader for ForkedDb {
+    fn get_epoch_ending_ledger_infos(
+        &self,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> Result<EpochChangeProof> {
+        self.local
+            .reader
+            .get_epoch_ending_ledger_infos(start_epoch, end_epoch)
+    }
+
+    fn get_transactions(
+        &self,
+        start_version: Version,
+        batch_size: u64,
+        ledger_version: Version,
+        fetch_events: bool,
+    ) -> Result<TransactionListWithProof> {
+        self.local
+            .reader
+            .get_transactions(start_version, batch_size, ledger_version, fetch_events)
+    }
+
+    fn get_transaction_by_hash(
+        &self,
+        hash: HashValue,
+        ledger_version: Version,
+        fetch_events: bool,
+    ) -> Result<Option<TransactionWithProof>> {
+        self.local
+            .reader
+            .get_transaction_by_hash(hash, ledger_version, fetch_events)
+    }
+
+    fn get_transaction_by_version(
+        &self,
+        version: Version,
+        ledger_version: Version,
+        fetch_events: bool,
+    ) -> Result<TransactionWithProof> {
+        self.local
+            .reader
+            .get_transaction_by_version(version, ledger_version, fetch_events)
+    }
+
+    fn get_first_txn_version(&self) -> Result<Option<Version>> {
+        self.local.reader.get_first_txn_version()
+    }
+
+    fn get_first_viable_txn_version(&self) -> Result<Version> {
+        self.local.reader.get_first_viable_txn_version()
+    }
+
+    fn get_first_write_set_version(&self) -> Result<Option<Version>> {
+        self.local.reader.get_first_write_set_version()
+    }
+
+    fn get_transaction_outputs(
+        &self,
+        start_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<TransactionOutputListWithProof> {
+        self.local
+            .reader
+            .get_transaction_outputs(start_version, limit, ledger_version)
+    }
+
+    fn get_events(
+        &self,
+        event_key: &EventKey,
+        start: u64,
+        order: Order,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<EventWithVersion>> {
+        self.local
+            .reader
+            .get_events(event_key, start, order, limit, ledger_version)
+    }
+
+    fn get_transaction_iterator(
+        &self,
+        start_version: Version,
+        limit: u64,
+    ) -> Result<Box<dyn Iterator<Item = Result<Transaction>> + '_>> {
+        self.local
+            .reader
+            .get_transaction_iterator(start_version, limit)
+    }
+
+    fn get_transaction_info_iterator(
+        &self,
+        start_version: Version,
+        limit: u64,
+    ) -> Result<Box<dyn Iterator<Item = Result<TransactionInfo>> + '_>> {
+        self.local
+            .reader
+            .get_transaction_info_iterator(start_version, limit)
+    }
+
+    fn get_events_iterator(
+        &self,
+        start_version: Version,
+        limit: u64,
+    ) -> Result<Box<dyn Iterator<Item = Result<Vec<ContractEvent>>> + '_>> {
+        self.local.reader.get_events_iterator(start_version, limit)
+    }
+
+    fn get_write_set_iterator(
+        &self,
+        start_version: Version,
+        limit: u64,
+    ) -> Result<Box<dyn Iterator<Item = Result<WriteSet>> + '_>> {
+        self.local
+            .reader
+            .get_write_set_iterator(start_version, limit)
+    }
+
+    fn get_transaction_accumulator_range_proof(
+        &self,
+        start_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<TransactionAccumulatorRangeProof> {
+        self.local.reader.get_transaction_accumulator_range_proof(
+            start_version,
+            limit,
+            ledger_version,
+        )
+    }
+
+    fn get_block_timestamp(&self, version: Version) -> Result<u64> {
+        self.local.reader.get_block_timestamp(version)
+    }
+
+    fn get_next_block_event(&self, version: Version) -> Result<(Version, NewBlockEvent)> {
+        self.local.reader.get_next_block_event(version)
+    }
+
+    fn get_block_info_by_version(
+        &self,
+        version: Version,
+    ) -> Result<(Version, Version, NewBlockEvent)> {
+        self.local.reader.get_block_info_by_version(version)
+    }
+
+    fn get_block_info_by_height(&self, height: u64) -> Result<(Version, Version, NewBlockEvent)> {
+        self.local.reader.get_block_info_by_height(height)
+    }
+
+    fn get_last_version_before_timestamp(
+        &self,
+        timestamp: u64,
+        ledger_version: Version,
+    ) -> Result<Version> {
+        self.local
+            .reader
+            .get_last_version_before_timestamp(timestamp, ledger_version)
+    }
+
+    fn get_latest_epoch_state(&self) -> Result<EpochState> {
+        self.local.reader.get_latest_epoch_state()
+    }
+
+    fn get_prefixed_state_value_iterator(
+        &self,
+        key_prefix: &StateKeyPrefix,
+        cursor: Option<&StateKey>,
+        version: Version,
+    ) -> Result<Box<dyn Iterator<Item = Result<(StateKey, StateValue)>> + '_>> {
+        self.local
+            .reader
+            .get_prefixed_state_value_iterator(key_prefix, cursor, version)
+    }
+
+    fn get_latest_ledger_info_option(&self) -> Result<Option<LedgerInfoWithSignatures>> {
+        self.local.reader.get_latest_ledger_info_option()
+    }
+
+    fn get_latest_version(&self) -> Result<Version> {
+        self.local.reader.get_latest_version()
+    }
+
+    fn get_latest_state_checkpoint_version(&self) -> Result<Option<Version>> {
+        self.local.reader.get_latest_state_checkpoint_version()
+    }
+
+    fn get_state_snapshot_before(
+        &self,
+        next_version: Version,
+    ) -> Result<Option<(Version, HashValue)>> {
+        self.local.reader.get_state_snapshot_before(next_version)
+    }
+
+    fn get_account_transaction(
+        &self,
+        address: AccountAddress,
+        seq_num: u64,
+        include_events: bool,
+        ledger_version: Version,
+    ) -> Result<Option<TransactionWithProof>> {
+        self.local
+            .reader
+            .get_account_transaction(address, seq_num, include_events, ledger_version)
+    }
+
+    fn get_account_transactions(
+        &self,
+        address: AccountAddress,
+        seq_num: u64,
+        limit: u64,
+        include_events: bool,
+        ledger_version: Version,
+    ) -> Result<AccountTransactionsWithProof> {
+        self.local.reader.get_account_transactions(
+            address,
+            seq_num,
+            limit,
+            include_events,
+            ledger_version,
+        )
+    }
+
+    fn get_state_proof_with_ledger_info(
+        &self,
+        known_version: u64,
+        ledger_info: LedgerInfoWithSignatures,
+    ) -> Result<StateProof> {
+        self.local
+            .reader
+            .get_state_proof_with_ledger_info(known_version, ledger_info)
+    }
+
+    fn get_state_proof(&self, known_version: u64) -> Result<StateProof> {
+        self.local.reader.get_state_proof(known_version)
+    }
+
+    fn get_state_proof_by_version_ext(
+        &self,
+        state_key: &StateKey,
+        version: Version,
+    ) -> Result<SparseMerkleProofExt> {
+        self.local
+            .reader
+            .get_state_proof_by_version_ext(state_key, version)
+    }
+
+    fn get_state_value_with_proof_by_version_ext(
+        &self,
+        state_key: &StateKey,
+        version: Version,
+    ) -> Result<(Option<StateValue>, SparseMerkleProofExt)> {
+        self.local
+            .reader
+            .get_state_value_with_proof_by_version_ext(state_key, version)
+    }
+
+    fn get_latest_executed_trees(&self) -> Result<ExecutedTrees> {
+        self.local.reader.get_latest_executed_trees()
+    }
+
+    fn get_epoch_ending_ledger_info(&self, known_version: u64) -> Result<LedgerInfoWithSignatures> {
+        self.local
+            .reader
+            .get_epoch_ending_ledger_info(known_version)
+    }
+
+    fn get_accumulator_root_hash(&self, version: Version) -> Result<HashValue> {
+        self.local.reader.get_accumulator_root_hash(version)
+    }
+
+    fn get_accumulator_consistency_proof(
+        &self,
+        client_known_version: Option<Version>,
+        ledger_version: Version,
+    ) -> Result<AccumulatorConsistencyProof> {
+        self.local
+            .reader
+            .get_accumulator_consistency_proof(client_known_version, ledger_version)
+    }
+
+    fn get_accumulator_summary(
+        &self,
+        ledger_version: Version,
+    ) -> Result<TransactionAccumulatorSummary> {
+        self.local.reader.get_accumulator_summary(ledger_version)
+    }
+
+    fn get_state_leaf_count(&self, version: Version) -> Result<usize> {
+        self.local.reader.get_state_leaf_count(version)
+    }
+
+    fn get_state_value_chunk_with_proof(
+        &self,
+        version: Version,
+        start_idx: usize,
+        chunk_size: usize,
+    ) -> Result<StateValueChunkWithProof> {
+        self.local
+            .reader
+            .get_state_value_chunk_with_proof(version, start_idx, chunk_size)
+    }
+
+    fn is_state_merkle_pruner_enabled(&self) -> Result<bool> {
+        self.local.reader.is_state_merkle_pruner_enabled()
+    }
+
+    fn get_epoch_snapshot_prune_window(&self) -> Result<usize> {
+        self.local.reader.get_epoch_snapshot_prune_window()
+    }
+
+    fn is_ledger_pruner_enabled(&self) -> Result<bool> {
+        self.local.reader.is_ledger_pruner_enabled()
+    }
+
+    fn get_ledger_prune_window(&self) -> Result<usize> {
+        self.local.reader.get_ledger_prune_window()
+    }
+
+    fn get_table_info(&self, handle: TableHandle) -> Result<TableInfo> {
+        self.local.reader.get_table_info(handle)
+    }
+
+    fn indexer_enabled(&self) -> bool {
+        self.local.reader.indexer_enabled()
+    }
+
+    fn get_state_storage_usage(&self, version: Option<Version>) -> Result<StateStorageUsage> {
+        self.local.reader.get_state_storage_usage(version)
+    }
+
+    fn get_state_value_by_version(
+        &self,
+        state_key: &StateKey,
+        version: Version,
+    ) -> Result<Option<StateValue>> {
+        match self
+            .local
+            .reader
+            .get_state_value_by_version(state_key, version)?
+        {
+            None if !self.is_local_deletion(state_key) => self.get_forked_state_value(state_key),
+            value_opt => Ok(value_opt),
+        }
+    }
+
+    fn get_state_value_with_version_by_version(
+        &self,
+        state_key: &StateKey,
+        version: Version,
+    ) -> Result<Option<(Version, StateValue)>> {
+        match self
+            .local
+            .reader
+            .get_state_value_with_version_by_version(state_key, version)?
+        {
+            None if !self.is_local_deletion(state_key) => Ok(self
+                .get_forked_state_value(state_key)?
+                .map(|value| (FORKED_STATE_VALUE_VERSION, value))),
+            version_and_value_opt => Ok(version_and_value_opt),
+        }
+    }
+
+    fn is_state_forked(&self) -> bool {
+        true
+    }
+}
+
+impl DbWriter for ForkedDb {
+    fn get_state_snapshot_receiver(
+        &self,
+        version: Version,
+        expected_root_hash: HashValue,
+    ) -> Result<Box<dyn StateSnapshotReceiver<StateKey, StateValue>>> {
+        self.local
+            .writer
+            .get_state_snapshot_receiver(version, expected_root_hash)
+    }
+
+    fn finalize_state_snapshot(
+        &self,
+        version: Version,
+        output_with_proof: TransactionOutputListWithProof,
+        ledger_infos: &[LedgerInfoWithSignatures],
+    ) -> Result<()> {
+        self.local
+            .writer
+            .finalize_state_snapshot(version, output_with_proof, ledger_infos)
+    }
+
+    fn save_transactions(
+        &self,
+        txns_to_commit: &[TransactionToCommit],
+        first_version: Version,
+        base_state_version: Option<Version>,
+        ledger_info_with_sigs: Option<&LedgerInfoWithSignatures>,
+        sync_commit: bool,
+        latest_in_memory_state: StateDelta,
+    ) -> Result<()> {
+        self.local.writer.save_transactions(
+            txns_to_commit,
+            first_version,
+            base_state_version,
+            ledger_info_with_sigs,
+            sync_commit,
+            latest_in_memory_state,
+        )?;
+        self.record_local_deletions(
+            first_version + txns_to_commit.len() as u64,
+            txns_to_commit.iter().map(|txn| txn.write_set()),
+        )
+    }
+
+    fn save_transaction_block(
+        &self,
+        txns_to_commit: &[Arc<TransactionToCommit>],
+        first_version: Version,
+        base_state_version: Option<Version>,
+        ledger_info_with_sigs: Option<&LedgerInfoWithSignatures>,
+        sync_commit: bool,
+        latest_in_memory_state: StateDelta,
+        block_state_updates: ShardedStateUpdates,
+        sharded_state_cache: &ShardedStateCache,
+    ) -> Result<()> {
+        self.local.writer.save_transaction_block(
+            txns_to_commit,
+            first_version,
+            base_state_version,
+            ledger_info_with_sigs,
+            sync_commit,
+            latest_in_memory_state,
+            block_state_updates,
+            sharded_state_cache,
+        )?;
+        self.record_local_deletions(
+            first_version + txns_to_commit.len() as u64,
+            txns_to_commit.iter().map(|txn| txn.write_set()),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_temppath::TempPath;
+    use aptos_types::{
+        account_state::AccountState,
+        state_store::create_empty_sharded_state_updates,
+        transaction::ExecutionStatus,
+        write_set::{TransactionWrite, WriteOp, WriteSetMut},
+    };
+    use std::{
+        collections::HashMap,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    const FORK_VERSION: Version = 100;
+
+    /// A local DB that only keeps the latest value written (or deleted) for each key.
+    #[derive(Default)]
+    struct FakeLocalDb {
+        values: RwLock<HashMap<StateKey, Option<StateValue>>>,
+    }
+
+    impl DbReader for FakeLocalDb {
+        fn get_latest_ledger_info_option(&self) -> Result<Option<LedgerInfoWithSignatures>> {
+            Ok(None)
+        }
+
+        fn get_state_value_by_version(
+            &self,
+            state_key: &StateKey,
+            _version: Version,
+        ) -> Result<Option<StateValue>> {
+            Ok(self
+                .values
+                .read()
+                .unwrap()
+                .get(state_key)
+                .cloned()
+                .flatten())
+        }
+
+        fn get_state_value_with_version_by_version(
+            &self,
+            state_key: &StateKey,
+            version: Version,
+        ) -> Result<Option<(Version, StateValue)>> {
+            Ok(self
+                .get_state_value_by_version(state_key, version)?
+                .map(|value| (version, value)))
+        }
+
+        fn get_state_storage_usage(&self, _version: Option<Version>) -> Result<StateStorageUsage> {
+            let mut usage = StateStorageUsage::zero();
+            for (key, value) in self.values.read().unwrap().iter() {
+                if let Some(value) = value {
+                    usage.add_item(key.size() + value.size());
+                }
+            }
+            Ok(usage)
+        }
+    }
+
+    impl DbWriter for FakeLocalDb {
+        fn save_transactions(
+            &self,
+            txns_to_commit: &[TransactionToCommit],
+            _first_version: Version,
+            _base_state_version: Option<Version>,
+            _ledger_info_with_sigs: Option<&LedgerInfoWithSignatures>,
+            _sync_commit: bool,
+            _latest_in_memory_state: StateDelta,
+        ) -> Result<()> {
+            let mut values = self.values.write().unwrap();
+            for txn in txns_to_commit {
+                for (key, write_op) in txn.write_set().iter() {
+                    values.insert(key.clone(), write_op.as_state_value());
+                }
+            }
+            Ok(())
+        }
+    }
+
+    /// The state of the forked network, which counts the values read from it.
+    struct FakeRemote {
+        values: HashMap<StateKey, StateValue>,
+        num_reads: AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl AptosValidatorInterface for FakeRemote {
+        async fn get_account_state_by_version(
+            &self,
+            _account: AccountAddress,
+            _version: Version,
+        ) -> Result<Option<AccountState>> {
+            unimplemented!()
+        }
+
+        async fn get_state_value_by_version(
+            &self,
+            state_key: &StateKey,
+            version: Version,
+        ) -> Result<Option<StateValue>> {
+            assert_eq!(version, FORK_VERSION);
+            self.num_reads.fetch_add(1, Ordering::SeqCst);
+            Ok(self.values.get(state_key).cloned())
+        }
+
+        async fn get_committed_transactions(
+            &self,
+            _start: Version,
+            _limit: u64,
+        ) -> Result<(Vec<Transaction>, Vec<TransactionInfo>)> {
+            unimplemented!()
+        }
+
+        async fn get_latest_version(&self) -> Result<Version> {
+            Ok(FORK_VERSION)
+        }
+
+        async fn get_version_by_account_sequence(
+            &self,
+            _account: AccountAddress,
+            _seq: u64,
+        ) -> Result<Option<Version>> {
+            unimplemented!()
+        }
+    }
+
+    fn state_key(name: &str) -> StateKey {
+        StateKey::raw(name.as_bytes().to_vec())
+    }
+
+    fn state_value(value: &str) -> StateValue {
+        StateValue::new_legacy(value.as_bytes().to_vec())
+    }
+
+    fn create_db_dir() -> TempPath {
+        let db_dir = TempPath::new();
+        db_dir.create_as_dir().unwrap();
+        db_dir
+    }
+
+    fn create_forked_db(
+        db_dir: &TempPath,
+        remote_values: &[(&str, &str)],
+    ) -> (ForkedDb, Arc<FakeRemote>) {
+        let remote = Arc::new(FakeRemote {
+            values: remote_values
+                .iter()
+                .map(|(key, value)| (state_key(key), state_value(value)))
+                .collect(),
+            num_reads: AtomicUsize::new(0),
+        });
+        let forked_db = ForkedDb::new(
+            DbReaderWriter::new(FakeLocalDb::default()),
+            remote.clone(),
+            FORK_VERSION,
+            1024,
+            db_dir.path(),
+        )
+        .unwrap();
+        (forked_db, remote)
+    }
+
+    fn commit_write_ops(forked_db: &ForkedDb, write_ops: Vec<(StateKey, WriteOp)>) {
+        let txn_to_commit = TransactionToCommit::new(
+            Transaction::StateCheckpoint(HashValue::zero()),
+            TransactionInfo::new_placeholder(0, None, ExecutionStatus::Success),
+            create_empty_sharded_state_updates(),
+            WriteSetMut::new(write_ops).freeze().unwrap(),
+            vec![],
+            false,
+        );
+        forked_db
+            .save_transactions(
+                &[txn_to_commit],
+                0,
+                None,
+                None,
+                true,
+                StateDelta::new_empty(),
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn test_read_forked_state_values() {
+        let db_dir = create_db_dir();
+        let (forked_db, remote) = create_forked_db(&db_dir, &[("a", "remote_a")]);
+
+        // Keys never written locally are read from the forked state, at the fork version
+        assert_eq!(
+            forked_db
+                .get_state_value_by_version(&state_key("a"), 5)
+                .unwrap(),
+            Some(state_value("remote_a"))
+        );
+        assert_eq!(
+            forked_db
+                .get_state_value_with_version_by_version(&state_key("a"), 5)
+                .unwrap(),
+            Some((FORKED_STATE_VALUE_VERSION, state_value("remote_a")))
+        );
+        assert_eq!(
+            forked_db
+                .get_state_value_by_version(&state_key("b"), 5)
+                .unwrap(),
+            None
+        );
+
+        // Forked values (including missing ones) are cached
+        assert_eq!(remote.num_reads.load(Ordering::SeqCst), 2);
+        forked_db
+            .get_state_value_by_version(&state_key("b"), 5)
+            .unwrap();
+        assert_eq!(remote.num_reads.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_local_writes_shadow_forked_state_values() {
+        let db_dir = create_db_dir();
+        let remote_values = [("modified", "remote"), ("deleted", "remote")];
+        let (forked_db, remote) = create_forked_db(&db_dir, &remote_values);
+        commit_write_ops(&forked_db, vec![
+            (
+                state_key("modified"),
+                WriteOp::Modification(b"local".to_vec()),
+            ),
+            (state_key("deleted"), WriteOp::Deletion),
+        ]);
+
+        assert_eq!(
+            forked_db
+                .get_state_value_by_version(&state_key("modified"), 1)
+                .unwrap(),
+            Some(state_value("local"))
+        );
+        // Deleting a forked value locally hides it, rather than falling back to the forked state
+        assert_eq!(
+            forked_db
+                .get_state_value_by_version(&state_key("deleted"), 1)
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            forked_db
+                .get_state_value_with_version_by_version(&state_key("deleted"), 1)
+                .unwrap(),
+            None
+        );
+        assert_eq!(remote.num_reads.load(Ordering::SeqCst), 0);
+
+        // The local deletions are logged, so they still hide the forked values once reopened,
+        // even if a partially written record follows
+        drop(forked_db);
+        let log_path = db_dir.path().join(DELETIONS_LOG_FILE_NAME);
+        let log_len = std::fs::metadata(&log_path).unwrap().len();
+        OpenOptions::new()
+            .append(true)
+            .open(&log_path)
+            .unwrap()
+            .write_all(&[42, 0])
+            .unwrap();
+        let (forked_db, remote) = create_forked_db(&db_dir, &remote_values);
+        assert_eq!(
+            forked_db
+                .get_state_value_by_version(&state_key("deleted"), 1)
+                .unwrap(),
+            None
+        );
+        assert_eq!(remote.num_reads.load(Ordering::SeqCst), 0);
+        assert_eq!(std::fs::metadata(&log_path).unwrap().len(), log_len);
+    }
+
+    #[test]
+    fn test_storage_usage() {
+        let db_dir = create_db_dir();
+        let (forked_db, _remote) = create_forked_db(&db_dir, &[("forked", "remote")]);
+        let local_key = state_key("local");
+        let local_value = state_value("local");
+        commit_write_ops(&forked_db, vec![(
+            local_key.clone(),
+            WriteOp::Creation(b"local".to_vec()),
+        )]);
+
+        // Only the local state is accounted for
+        let usage = forked_db.get_state_storage_usage(None).unwrap();
+        assert_eq!(
+            usage,
+            StateStorageUsage::new(1, local_key.size() + local_value.size())
+        );
+
+        // Deleting forked values removes items that were never accounted for, which saturates
+        // rather than underflowing
+        let forked_key = state_key("forked");
+        let forked_value = forked_db
+            .get_state_value_by_version(&forked_key, 1)
+            .unwrap()
+            .unwrap();
+        let bytes_delta = (forked_key.size() + forked_value.size()) as i64;
+        assert_eq!(
+            usage.apply_delta_saturating(-2, -2 * bytes_delta),
+            StateStorageUsage::zero()
+        );
+    }
+}
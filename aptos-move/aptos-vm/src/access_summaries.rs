@@ -0,0 +1,233 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Looks up the access summaries published in the metadata of modules, to derive the read and
+//! write hints of the transactions calling their entry functions.
+//!
+//! Summaries are not checked against the code when published, so the hints may be wrong. They
+//! must only be used where a wrong hint costs performance but never changes the outputs, e.g. to
+//! schedule parallel execution, which validates every read. In particular, they must not be used
+//! to partition blocks.
+
+use crate::adapter_common::PreprocessedTransaction;
+use aptos_block_executor::task::Accesses;
+use aptos_framework::get_access_summaries;
+use aptos_infallible::Mutex;
+use aptos_state_view::StateView;
+use aptos_types::{
+    access_path::AccessPath,
+    state_store::state_key::StateKey,
+    transaction::{
+        access_summary::AccessSummary,
+        analyzed_transaction::{AnalyzedTransaction, StorageLocation},
+        SignedTransaction, TransactionPayload,
+    },
+};
+use move_binary_format::CompiledModule;
+use move_core_types::language_storage::ModuleId;
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
+type ModuleAccessSummaries = Arc<BTreeMap<String, AccessSummary>>;
+
+pub struct AccessSummaryResolver<'a, S> {
+    state_view: &'a S,
+    summaries: Mutex<HashMap<ModuleId, Option<ModuleAccessSummaries>>>,
+}
+
+impl<'a, S: StateView> AccessSummaryResolver<'a, S> {
+    pub fn new(state_view: &'a S) -> Self {
+        Self {
+            state_view,
+            summaries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn module_summaries(&self, module_id: &ModuleId) -> Option<ModuleAccessSummaries> {
+        self.summaries
+            .lock()
+            .entry(module_id.clone())
+            .or_insert_with(|| {
+                let bytes = self
+                    .state_view
+                    .get_state_value_bytes(&StateKey::access_path(AccessPath::code_access_path(
+                        module_id.clone(),
+                    )))
+                    .ok()??;
+                let module = CompiledModule::deserialize(&bytes).ok()?;
+                get_access_summaries(&module.metadata).map(Arc::new)
+            })
+            .clone()
+    }
+
+    /// Returns the read and write hints of an entry function call, if the function has an access
+    /// summary.
+    pub fn access_hints(
+        &self,
+        signed_txn: &SignedTransaction,
+    ) -> Option<(Vec<StorageLocation>, Vec<StorageLocation>)> {
+        let func = match signed_txn.payload() {
+            TransactionPayload::EntryFunction(func) => func,
+            _ => return None,
+        };
+        let summaries = self.module_summaries(func.module())?;
+        let summary = summaries.get(func.function().as_str())?;
+        AnalyzedTransaction::access_summary_hints(signed_txn, summary)
+    }
+
+    /// Returns the keys each transaction of a block is estimated to read and write, for those
+    /// with an access summary. Wildcards, which don't denote keys, are left out. Only the
+    /// transactions whose signature was verified are looked up, so that modules aren't read on
+    /// behalf of invalid transactions.
+    pub(crate) fn estimate_accesses(
+        &self,
+        txns: &[PreprocessedTransaction],
+    ) -> Vec<Option<Accesses<StateKey>>> {
+        let keys = |locations: Vec<StorageLocation>| {
+            locations
+                .into_iter()
+                .filter_map(|location| match location {
+                    StorageLocation::Specific(state_key) => Some(state_key),
+                    _ => None,
+                })
+                .collect()
+        };
+        txns.iter()
+            .map(|txn| {
+                let signed_txn = match txn {
+                    PreprocessedTransaction::UserTransaction(signed_txn) => signed_txn,
+                    _ => return None,
+                };
+                let (read_hints, write_hints) = self.access_hints(signed_txn)?;
+                Some(Accesses {
+                    keys_read: keys(read_hints),
+                    keys_written: keys(write_hints),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_framework::APTOS_ACCESS_SUMMARY_KEY;
+    use aptos_language_e2e_tests::{account::Account, data_store::FakeDataStore};
+    use aptos_types::transaction::{
+        access_summary::{AccessAddress, AccessStructTag, ResourceAccess},
+        EntryFunction,
+    };
+    use move_binary_format::file_format::empty_module;
+    use move_core_types::{
+        account_address::AccountAddress, ident_str, identifier::Identifier,
+        language_storage::StructTag, metadata::Metadata,
+    };
+    use std::collections::BTreeSet;
+
+    /// Publishes an empty module at 0xcafe, with the given access summaries in its metadata.
+    fn publish_module(
+        data_store: &mut FakeDataStore,
+        name: &str,
+        summaries: Option<BTreeMap<String, AccessSummary>>,
+    ) -> ModuleId {
+        let mut module = empty_module();
+        module.identifiers[0] = Identifier::new(name).unwrap();
+        module.address_identifiers[0] = AccountAddress::from_hex_literal("0xcafe").unwrap();
+        if let Some(summaries) = summaries {
+            module.metadata.push(Metadata {
+                key: APTOS_ACCESS_SUMMARY_KEY.to_vec(),
+                value: bcs::to_bytes(&summaries).unwrap(),
+            });
+        }
+        let mut bytes = vec![];
+        module.serialize(&mut bytes).unwrap();
+        data_store.add_module(&module.self_id(), bytes);
+        module.self_id()
+    }
+
+    fn call(sender: &Account, module_id: &ModuleId, function: &str) -> PreprocessedTransaction {
+        let txn = sender
+            .transaction()
+            .sequence_number(0)
+            .entry_function(EntryFunction::new(
+                module_id.clone(),
+                Identifier::new(function).unwrap(),
+                vec![],
+                vec![],
+            ))
+            .sign();
+        PreprocessedTransaction::UserTransaction(Box::new(txn.check_signature().unwrap()))
+    }
+
+    fn resource_key(address: AccountAddress, struct_tag: StructTag) -> StateKey {
+        StateKey::access_path(AccessPath::resource_access_path(address, struct_tag).unwrap())
+    }
+
+    #[test]
+    fn test_estimate_accesses() {
+        let resource = AccessStructTag {
+            address: AccountAddress::from_hex_literal("0xcafe").unwrap(),
+            module: ident_str!("m").to_owned(),
+            name: ident_str!("R").to_owned(),
+            type_args: vec![],
+        };
+        // `f` writes `R` at the sender and reads it at any address.
+        let summary = AccessSummary {
+            reads: BTreeSet::from([ResourceAccess {
+                resource: resource.clone(),
+                resource_group: false,
+                address: AccessAddress::Any,
+            }]),
+            writes: BTreeSet::from([ResourceAccess {
+                resource: resource.clone(),
+                resource_group: false,
+                address: AccessAddress::Signer(0),
+            }]),
+        };
+        let mut data_store = FakeDataStore::default();
+        let summarized = publish_module(
+            &mut data_store,
+            "m",
+            Some(BTreeMap::from([("f".to_string(), summary)])),
+        );
+        let unsummarized = publish_module(&mut data_store, "n", None);
+        let unpublished = ModuleId::new(
+            AccountAddress::from_hex_literal("0xcafe").unwrap(),
+            ident_str!("o").to_owned(),
+        );
+
+        let sender = Account::new();
+        let txns = vec![
+            call(&sender, &summarized, "f"),
+            call(&sender, &summarized, "g"),
+            call(&sender, &unsummarized, "f"),
+            call(&sender, &unpublished, "f"),
+            PreprocessedTransaction::InvalidSignature,
+            PreprocessedTransaction::StateCheckpoint,
+        ];
+        let accesses = AccessSummaryResolver::new(&data_store).estimate_accesses(&txns);
+        assert_eq!(accesses.len(), txns.len());
+
+        // The wildcard read is left out, and the prologue and epilogue write the sender's account
+        // and coin store.
+        let Accesses {
+            keys_read,
+            keys_written,
+        } = accesses[0].as_ref().unwrap();
+        assert!(keys_read.is_empty());
+        let key = |location| match location {
+            StorageLocation::Specific(state_key) => state_key,
+            _ => unreachable!(),
+        };
+        assert_eq!(keys_written, &vec![
+            resource_key(*sender.address(), resource.instantiate(&[]).unwrap()),
+            key(AnalyzedTransaction::account_resource_location(
+                *sender.address()
+            )),
+            key(AnalyzedTransaction::coin_store_location(*sender.address())),
+        ]);
+        assert!(accesses[1..].iter().all(Option::is_none));
+    }
+}
@@ -18,27 +18,30 @@ use crate::{
     verifier, VMExecutor, VMValidator,
 };
 use anyhow::{anyhow, Result};
-use aptos_block_executor::txn_commit_hook::NoOpTransactionCommitHook;
+use aptos_block_executor::{
+    execution_mode::{BlockExecutionMode, ExecutionModeSelector, ExecutionModeSelectorConfig},
+    txn_commit_hook::NoOpTransactionCommitHook,
+};
 use aptos_crypto::HashValue;
 use aptos_framework::natives::code::PublishRequest;
-use aptos_gas_algebra::Gas;
-use aptos_gas_meter::{AptosGasMeter, StandardGasAlgebra, StandardGasMeter};
+use aptos_gas_algebra::{Gas, InternalGas};
+use aptos_gas_meter::{AptosGasMeter, GasAlgebra, StandardGasAlgebra, StandardGasMeter};
 use aptos_gas_schedule::VMGasParameters;
 use aptos_logger::{enabled, prelude::*, Level};
 use aptos_memory_usage_tracker::MemoryTrackedGasMeter;
 use aptos_state_view::StateView;
 use aptos_types::{
     account_config,
-    account_config::new_block_event_key,
+    account_config::{new_block_event_key, DispatchableAuthenticatorResource},
     block_executor::partitioner::PartitionedTransactions,
     block_metadata::BlockMetadata,
     fee_statement::FeeStatement,
     on_chain_config::{new_epoch_event_key, ConfigStorage, FeatureFlag, TimedFeatureOverride},
     transaction::{
-        EntryFunction, ExecutionError, ExecutionStatus, ModuleBundle, Multisig,
-        MultisigTransactionPayload, SignatureCheckedTransaction, SignedTransaction, Transaction,
-        TransactionOutput, TransactionPayload, TransactionStatus, VMValidatorResult,
-        WriteSetPayload,
+        authenticator::AccountAuthenticator, EntryFunction, ExecutionError, ExecutionStatus,
+        ModuleBundle, Multisig, MultisigTransactionPayload, SignatureCheckedTransaction,
+        SignedTransaction, Transaction, TransactionOutput, TransactionPayload, TransactionStatus,
+        VMValidatorResult, WriteSetPayload,
     },
     vm_status::{AbortLocation, StatusCode, VMStatus},
 };
@@ -61,12 +64,13 @@ use move_core_types::{
     ident_str,
     identifier::Identifier,
     language_storage::{ModuleId, TypeTag},
+    move_resource::MoveStructType,
     transaction_argument::convert_txn_args,
     value::{serialize_values, MoveValue},
     vm_status::StatusType,
 };
-use move_vm_runtime::session::SerializedReturnValues;
-use move_vm_types::gas::UnmeteredGasMeter;
+use move_vm_runtime::session::{LoadedFunctionInstantiation, SerializedReturnValues};
+use move_vm_types::{gas::UnmeteredGasMeter, loaded_data::runtime_types::Type};
 use num_cpus;
 use once_cell::sync::{Lazy, OnceCell};
 use std::{
@@ -81,9 +85,11 @@ use std::{
 
 static EXECUTION_CONCURRENCY_LEVEL: OnceCell<usize> = OnceCell::new();
 static NUM_EXECUTION_SHARD: OnceCell<usize> = OnceCell::new();
+static EXECUTION_MODE_SELECTOR: OnceCell<Arc<ExecutionModeSelector>> = OnceCell::new();
 static NUM_PROOF_READING_THREADS: OnceCell<usize> = OnceCell::new();
 static PARANOID_TYPE_CHECKS: OnceCell<bool> = OnceCell::new();
 static PROCESSED_TRANSACTIONS_DETAILED_COUNTERS: OnceCell<bool> = OnceCell::new();
+static ACCESS_SUMMARY_HINTS: OnceCell<bool> = OnceCell::new();
 static TIMED_FEATURE_OVERRIDE: OnceCell<TimedFeatureOverride> = OnceCell::new();
 
 pub static RAYON_EXEC_POOL: Lazy<Arc<rayon::ThreadPool>> = Lazy::new(|| {
@@ -164,6 +170,31 @@ impl AptosVM {
         }
     }
 
+    /// Sets how the execution mode of blocks is picked when invoked the first time.
+    pub fn set_execution_mode_selector_config_once(config: ExecutionModeSelectorConfig) {
+        // Only the first call succeeds, due to OnceCell semantics.
+        EXECUTION_MODE_SELECTOR
+            .set(Arc::new(ExecutionModeSelector::new(config)))
+            .ok();
+    }
+
+    /// Get the execution mode selector, executing every block in parallel if not set.
+    pub fn get_execution_mode_selector() -> Arc<ExecutionModeSelector> {
+        EXECUTION_MODE_SELECTOR
+            .get_or_init(|| {
+                Arc::new(ExecutionModeSelector::new(ExecutionModeSelectorConfig {
+                    mode_override: Some(BlockExecutionMode::Parallel),
+                    ..Default::default()
+                }))
+            })
+            .clone()
+    }
+
+    /// Picks the mode to execute the next block in.
+    pub fn select_block_execution_mode() -> BlockExecutionMode {
+        Self::get_execution_mode_selector().select()
+    }
+
     /// Sets runtime config when invoked the first time.
     pub fn set_paranoid_type_checks(enable: bool) {
         // Only the first call succeeds, due to OnceCell semantics.
@@ -218,6 +249,21 @@ impl AptosVM {
         }
     }
 
+    /// Sets whether parallel execution is scheduled using access summaries when invoked the
+    /// first time.
+    pub fn set_access_summary_hints_once(enable: bool) {
+        // Only the first call succeeds, due to OnceCell semantics.
+        ACCESS_SUMMARY_HINTS.set(enable).ok();
+    }
+
+    /// Get whether parallel execution is scheduled using access summaries, default false
+    pub fn get_access_summary_hints() -> bool {
+        match ACCESS_SUMMARY_HINTS.get() {
+            Some(value) => *value,
+            None => false,
+        }
+    }
+
     pub fn internals(&self) -> AptosVMInternals {
         AptosVMInternals::new(&self.0)
     }
@@ -1052,6 +1098,18 @@ impl AptosVM {
             return discard_error_vm_status(err);
         };
 
+        // Charge the gas used by the account abstraction authentication functions (if any),
+        // whose writes are discarded.
+        let aa_gas_used = unwrap_or_discard!(self.run_account_abstraction_authentication(
+            resolver,
+            txn,
+            log_context
+        ));
+        unwrap_or_discard!(gas_meter
+            .algebra_mut()
+            .charge_execution(aa_gas_used)
+            .map_err(|err| err.finish(Location::Undefined).into_vm_status()));
+
         if self.0.get_gas_feature_version() >= 1 {
             // Create a new session so that the data cache is flushed.
             // This is to ensure we correctly charge for loading certain resources, even if they
@@ -1363,20 +1421,52 @@ impl AptosVM {
         txn: &SignedTransaction,
         state_view: &impl StateView,
     ) -> (VMStatus, TransactionOutput) {
+        let (vm_status, output, _) = Self::simulate_signed_transaction_with_custom_gas_meter(
+            txn,
+            state_view,
+            |gas_feature_version, gas_params, storage_gas_params, balance| {
+                Ok(MemoryTrackedGasMeter::new(StandardGasMeter::new(
+                    StandardGasAlgebra::new(
+                        gas_feature_version,
+                        gas_params,
+                        storage_gas_params,
+                        balance,
+                    ),
+                )))
+            },
+        );
+        (vm_status, output)
+    }
+
+    /// Simulates a transaction like `simulate_signed_transaction`, metering it with the gas meter
+    /// returned by `make_gas_meter` (e.g., a gas profiler).
+    ///
+    /// The gas meter is returned as well, unless the transaction was discarded before execution.
+    pub fn simulate_signed_transaction_with_custom_gas_meter<G, F>(
+        txn: &SignedTransaction,
+        state_view: &impl StateView,
+        make_gas_meter: F,
+    ) -> (VMStatus, TransactionOutput, Option<G>)
+    where
+        G: AptosGasMeter,
+        F: FnOnce(u64, VMGasParameters, StorageGasParameters, Gas) -> Result<G, VMStatus>,
+    {
         let vm = AptosVM::new_from_state_view(state_view);
         let simulation_vm = AptosSimulationVM(vm);
         let log_context = AdapterLogSchema::new(state_view.id(), 0);
 
-        let (vm_status, vm_output) = simulation_vm.simulate_signed_transaction(
+        let (vm_status, vm_output, gas_meter) = simulation_vm.simulate_signed_transaction(
             &simulation_vm.0.as_move_resolver(state_view),
             txn,
             &log_context,
+            make_gas_meter,
         );
         (
             vm_status,
             vm_output
                 .try_into_transaction_output(state_view)
                 .expect("Simulation cannot fail"),
+            gas_meter,
         )
     }
 
@@ -1427,6 +1517,119 @@ impl AptosVM {
             .collect::<Vec<_>>())
     }
 
+    /// Runs the authentication functions registered by the signers of `txn` that use account
+    /// abstraction (see `account_abstraction.move`). Each function is called with the signer, the
+    /// SHA3-256 digest of the transaction's signing message and the signer's authenticator, and
+    /// must abort to reject the transaction.
+    ///
+    /// The functions run in a separate session whose writes are discarded, and their combined
+    /// execution is capped at `max_aa_gas`. Returns the gas used, which is charged to the
+    /// transaction.
+    fn run_account_abstraction_authentication(
+        &self,
+        resolver: &impl AptosMoveResolver,
+        txn: &SignedTransaction,
+        log_context: &AdapterLogSchema,
+    ) -> Result<InternalGas, VMStatus> {
+        let authenticator = txn.authenticator_ref();
+        let mut signers = vec![(txn.sender(), authenticator.sender())];
+        signers.extend(
+            authenticator
+                .secondary_signer_addreses()
+                .into_iter()
+                .zip(authenticator.secondary_signers()),
+        );
+        signers.extend(
+            authenticator
+                .fee_payer_address()
+                .zip(authenticator.fee_payer_signer()),
+        );
+        let abstracted_signers: Vec<_> = signers
+            .into_iter()
+            .filter_map(|(address, signer)| match signer {
+                AccountAuthenticator::Abstraction { authenticator } => {
+                    Some((address, authenticator))
+                },
+                _ => None,
+            })
+            .collect();
+        if abstracted_signers.is_empty() {
+            return Ok(InternalGas::zero());
+        }
+
+        let invalid_signature = || VMStatus::error(StatusCode::INVALID_SIGNATURE, None);
+        let signing_message = authenticator
+            .signing_message(txn.raw_transaction_ref())
+            .map_err(|_| invalid_signature())?;
+        let digest = HashValue::sha3_256_of(&signing_message);
+        let gas_params = self.0.get_gas_parameters(log_context)?;
+        let storage_gas_params = self.0.get_storage_gas_parameters(log_context)?;
+        let max_gas = min(gas_params.vm.txn.max_aa_gas, txn.max_gas_amount().into());
+        let mut gas_meter =
+            MemoryTrackedGasMeter::new(StandardGasMeter::new(StandardGasAlgebra::new(
+                self.0.get_gas_feature_version(),
+                gas_params.vm.clone(),
+                storage_gas_params.clone(),
+                max_gas,
+            )));
+        let mut session = self.0.new_session(
+            resolver,
+            SessionId::txn_meta(&TransactionMetadata::new(txn)),
+        );
+
+        for (address, authenticator) in abstracted_signers {
+            let (module_id, function_name) = resolver
+                .get_resource(&address, &DispatchableAuthenticatorResource::struct_tag())
+                .map_err(|err| VMStatus::error(StatusCode::STORAGE_ERROR, Some(err.to_string())))?
+                .and_then(|bytes| bcs::from_bytes::<DispatchableAuthenticatorResource>(&bytes).ok())
+                .and_then(|resource| resource.function())
+                .ok_or_else(invalid_signature)?;
+
+            // The authentication function must be `fun(signer, vector<u8>, vector<u8>)`, so
+            // that it can only reject (by aborting) and not be passed anything unexpected.
+            let function = session
+                .load_function(&module_id, &function_name, &[])
+                .map_err(|_| invalid_signature())?;
+            if !is_valid_account_abstraction_function(&function) {
+                speculative_log(
+                    Level::Debug,
+                    log_context,
+                    format!(
+                        "[aptos_vm] Invalid account abstraction function {}::{} for {}",
+                        module_id, function_name, address
+                    ),
+                );
+                return Err(invalid_signature());
+            }
+
+            session
+                .execute_function_bypass_visibility(
+                    &module_id,
+                    &function_name,
+                    vec![],
+                    serialize_values(&vec![
+                        MoveValue::Signer(address),
+                        MoveValue::vector_u8(digest.to_vec()),
+                        MoveValue::vector_u8(authenticator),
+                    ]),
+                    &mut gas_meter,
+                )
+                .map_err(|err| {
+                    speculative_log(
+                        Level::Debug,
+                        log_context,
+                        format!(
+                            "[aptos_vm] Account abstraction authentication failed for {}: {:?}",
+                            address, err
+                        ),
+                    );
+                    invalid_signature()
+                })?;
+        }
+        let algebra = gas_meter.algebra();
+        Ok(algebra.execution_gas_used() + algebra.io_gas_used())
+    }
+
     fn run_prologue_with_payload(
         &self,
         session: &mut SessionExt,
@@ -1476,6 +1679,18 @@ impl AptosVM {
     }
 }
 
+/// Whether `function` can authenticate transactions with account abstraction, i.e., it is
+/// `fun(signer, vector<u8>, vector<u8>)` without type parameters or return values.
+fn is_valid_account_abstraction_function(function: &LoadedFunctionInstantiation) -> bool {
+    let is_bytes = |ty: &Type| matches!(ty, Type::Vector(inner) if matches!(**inner, Type::U8));
+    function.type_arguments.is_empty()
+        && function.return_.is_empty()
+        && matches!(
+            function.parameters.as_slice(),
+            [Type::Signer, digest, authenticator] if is_bytes(digest) && is_bytes(authenticator)
+        )
+}
+
 // Executor external API
 impl VMExecutor for AptosVM {
     /// Execute a block of `transactions`. The output vector will have the exact same length as the
@@ -1502,16 +1717,26 @@ impl VMExecutor for AptosVM {
         );
 
         let count = transactions.len();
-        let ret = BlockAptosVM::execute_block::<
+        let concurrency_level = match Self::select_block_execution_mode() {
+            BlockExecutionMode::Sequential => 1,
+            BlockExecutionMode::Parallel => Self::get_concurrency_level(),
+        };
+        if concurrency_level > 1 {
+            BlockExecutionMode::Parallel.observe();
+        } else {
+            BlockExecutionMode::Sequential.observe();
+        }
+        let ret = BlockAptosVM::execute_block_with_mode_selector::<
             _,
             NoOpTransactionCommitHook<AptosTransactionOutput, VMStatus>,
         >(
             Arc::clone(&RAYON_EXEC_POOL),
             transactions,
             state_view,
-            Self::get_concurrency_level(),
+            concurrency_level,
             maybe_block_gas_limit,
             None,
+            Some(Self::get_execution_mode_selector()),
         );
         if ret.is_ok() {
             // Record the histogram count for transactions per block.
@@ -1534,6 +1759,8 @@ impl VMExecutor for AptosVM {
         );
 
         let count = transactions.num_txns();
+        // Conflicts are not sampled across shards.
+        Self::get_execution_mode_selector().record_unsampled();
         let ret = sharded_block_executor.execute_block(
             state_view,
             transactions,
@@ -1577,13 +1804,18 @@ impl VMValidator for AptosVM {
 
         let resolver = self.as_move_resolver(state_view);
         let mut session = self.0.new_session(&resolver, SessionId::prologue(&txn));
-        let validation_result = self.validate_signature_checked_transaction(
-            &mut session,
-            &resolver,
-            &txn,
-            true,
-            &log_context,
-        );
+        let validation_result = self
+            .validate_signature_checked_transaction(
+                &mut session,
+                &resolver,
+                &txn,
+                true,
+                &log_context,
+            )
+            .and_then(|_| {
+                self.run_account_abstraction_authentication(&resolver, &txn, &log_context)
+                    .map(|_| ())
+            });
 
         // Increment the counter for transactions verified.
         let (counter_label, result) = match validation_result {
@@ -1626,6 +1858,26 @@ impl VMAdapter for AptosVM {
             ));
         }
 
+        let features = self.0.get_features();
+        for signer in txn.authenticator_ref().all_signers() {
+            let enabled = match signer {
+                AccountAuthenticator::Secp256k1Ecdsa { .. } => {
+                    features.is_secp256k1_ecdsa_authenticator_enabled()
+                },
+                AccountAuthenticator::WebAuthn { .. } => {
+                    features.is_webauthn_authenticator_enabled()
+                },
+                AccountAuthenticator::Abstraction { .. } => {
+                    features.is_account_abstraction_enabled()
+                },
+                AccountAuthenticator::Ed25519 { .. }
+                | AccountAuthenticator::MultiEd25519 { .. } => true,
+            };
+            if !enabled {
+                return Err(VMStatus::error(StatusCode::FEATURE_UNDER_GATING, None));
+            }
+        }
+
         Ok(())
     }
 
@@ -1786,43 +2038,62 @@ impl AptosSimulationVM {
         )
     }
 
-    fn simulate_signed_transaction(
+    fn simulate_signed_transaction<G, F>(
         &self,
         resolver: &impl MoveResolverExt,
         txn: &SignedTransaction,
         log_context: &AdapterLogSchema,
-    ) -> (VMStatus, VMOutput) {
+        make_gas_meter: F,
+    ) -> (VMStatus, VMOutput, Option<G>)
+    where
+        G: AptosGasMeter,
+        F: FnOnce(u64, VMGasParameters, StorageGasParameters, Gas) -> Result<G, VMStatus>,
+    {
+        let discard = |err| {
+            let (vm_status, output) = discard_error_vm_status(err);
+            (vm_status, output, None)
+        };
+
         // simulation transactions should not carry valid signatures, otherwise malicious fullnodes
-        // may execute them without user's explicit permission.
+        // may execute them without user's explicit permission. The same holds for account
+        // abstraction authenticators.
+        let txn_data = TransactionMetadata::new(txn);
         if txn.signature_is_valid() {
-            return discard_error_vm_status(VMStatus::error(StatusCode::INVALID_SIGNATURE, None));
+            if self
+                .0
+                .run_account_abstraction_authentication(resolver, txn, log_context)
+                .is_ok()
+            {
+                return discard(VMStatus::error(StatusCode::INVALID_SIGNATURE, None));
+            }
         }
 
         // Revalidate the transaction.
-        let txn_data = TransactionMetadata::new(txn);
         let mut session = self.0.new_session(resolver, SessionId::txn_meta(&txn_data));
         if let Err(err) =
             self.validate_simulated_transaction(&mut session, resolver, txn, &txn_data, log_context)
         {
-            return discard_error_vm_status(err);
+            return discard(err);
         };
 
         let gas_params = match self.0 .0.get_gas_parameters(log_context) {
-            Err(err) => return discard_error_vm_status(err),
+            Err(err) => return discard(err),
             Ok(s) => s,
         };
         let storage_gas_params = match self.0 .0.get_storage_gas_parameters(log_context) {
-            Err(err) => return discard_error_vm_status(err),
+            Err(err) => return discard(err),
             Ok(s) => s,
         };
 
-        let mut gas_meter =
-            MemoryTrackedGasMeter::new(StandardGasMeter::new(StandardGasAlgebra::new(
-                self.0 .0.get_gas_feature_version(),
-                gas_params.vm.clone(),
-                storage_gas_params.clone(),
-                txn_data.max_gas_amount(),
-            )));
+        let mut gas_meter = match make_gas_meter(
+            self.0 .0.get_gas_feature_version(),
+            gas_params.vm.clone(),
+            storage_gas_params.clone(),
+            txn_data.max_gas_amount(),
+        ) {
+            Err(err) => return discard(err),
+            Ok(gas_meter) => gas_meter,
+        };
 
         let mut new_published_modules_loaded = false;
         let result = match txn.payload() {
@@ -1892,7 +2163,7 @@ impl AptosSimulationVM {
             ),
         };
 
-        match result {
+        let (vm_status, output) = match result {
             Ok(output) => output,
             Err(err) => {
                 // Invalidate the loader cache in case there was a new module loaded from a module
@@ -1924,6 +2195,7 @@ impl AptosSimulationVM {
                     (vm_status, output)
                 }
             },
-        }
+        };
+        (vm_status, output, Some(gas_meter))
     }
 }
@@ -5,6 +5,7 @@
 pub(crate) mod vm_wrapper;
 
 use crate::{
+    access_summaries::AccessSummaryResolver,
     adapter_common::{preprocess_transaction, PreprocessedTransaction},
     block_executor::vm_wrapper::AptosExecutorTask,
     counters::{
@@ -16,6 +17,7 @@ use crate::{
 use aptos_aggregator::delta_change_set::DeltaOp;
 use aptos_block_executor::{
     errors::Error,
+    execution_mode::ExecutionModeSelector,
     executor::BlockExecutor,
     task::{
         Transaction as BlockExecutorTransaction,
@@ -198,6 +200,31 @@ impl BlockAptosVM {
         concurrency_level: usize,
         maybe_block_gas_limit: Option<u64>,
         transaction_commit_listener: Option<L>,
+    ) -> Result<Vec<TransactionOutput>, VMStatus> {
+        Self::execute_block_with_mode_selector(
+            executor_thread_pool,
+            transactions,
+            state_view,
+            concurrency_level,
+            maybe_block_gas_limit,
+            transaction_commit_listener,
+            None,
+        )
+    }
+
+    /// Same as `execute_block`, sampling the conflicts of the block into the execution mode
+    /// selector if provided.
+    pub(crate) fn execute_block_with_mode_selector<
+        S: StateView + Sync,
+        L: TransactionCommitHook<Output = AptosTransactionOutput>,
+    >(
+        executor_thread_pool: Arc<ThreadPool>,
+        transactions: Vec<Transaction>,
+        state_view: &S,
+        concurrency_level: usize,
+        maybe_block_gas_limit: Option<u64>,
+        transaction_commit_listener: Option<L>,
+        execution_mode_selector: Option<Arc<ExecutionModeSelector>>,
     ) -> Result<Vec<TransactionOutput>, VMStatus> {
         let _timer = BLOCK_EXECUTOR_EXECUTE_BLOCK_SECONDS.start_timer();
         // Verify the signatures of all the transactions in parallel.
@@ -210,6 +237,11 @@ impl BlockAptosVM {
             executor_thread_pool.install(|| Self::verify_transactions(transactions));
         drop(signature_verification_timer);
 
+        let estimated_accesses = (concurrency_level > 1 && AptosVM::get_access_summary_hints())
+            .then(|| {
+                AccessSummaryResolver::new(state_view).estimate_accesses(&signature_verified_block)
+            });
+
         let num_txns = signature_verified_block.len();
         if state_view.id() != StateViewId::Miscellaneous {
             // Speculation is disabled in Miscellaneous context, which is used by testing and
@@ -218,7 +250,7 @@ impl BlockAptosVM {
         }
 
         BLOCK_EXECUTOR_CONCURRENCY.set(concurrency_level as i64);
-        let executor = BlockExecutor::<
+        let mut executor = BlockExecutor::<
             PreprocessedTransaction,
             AptosExecutorTask<S>,
             S,
@@ -230,6 +262,12 @@ impl BlockAptosVM {
             maybe_block_gas_limit,
             transaction_commit_listener,
         );
+        if let Some(execution_mode_selector) = execution_mode_selector {
+            executor = executor.with_execution_mode_selector(execution_mode_selector);
+        }
+        if let Some(estimated_accesses) = estimated_accesses {
+            executor = executor.with_estimated_accesses(estimated_accesses);
+        }
 
         let ret = executor.execute_block(state_view, signature_verified_block, state_view);
         match ret {
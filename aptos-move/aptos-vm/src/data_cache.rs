@@ -31,7 +31,7 @@ use move_core_types::{
 };
 use std::{cell::RefCell, collections::BTreeMap};
 
-pub(crate) fn get_resource_group_from_metadata(
+pub fn get_resource_group_from_metadata(
     struct_tag: &StructTag,
     metadata: &[Metadata],
 ) -> Option<StructTag> {
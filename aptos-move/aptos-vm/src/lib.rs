@@ -102,6 +102,7 @@
 //! ```
 
 mod access_path_cache;
+pub mod access_summaries;
 #[macro_use]
 pub mod counters;
 pub mod data_cache;
@@ -6,6 +6,8 @@
 mod resolver;
 mod respawned_session;
 mod session;
+#[cfg(feature = "instruction-tracing")]
+pub mod trace;
 mod vm;
 
 pub use crate::move_vm_ext::{
@@ -0,0 +1,198 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Bytecode level tracing of transactions, for tools replaying them. Only compiled with the
+//! `instruction-tracing` feature, and never enabled on validators.
+
+use crate::{
+    aptos_vm::RAYON_EXEC_POOL,
+    block_executor::{AptosTransactionOutput, BlockAptosVM},
+};
+use aptos_block_executor::txn_commit_hook::TransactionCommitHook;
+use aptos_infallible::Mutex;
+use aptos_mvhashmap::types::TxnIndex;
+use aptos_state_view::StateView;
+use aptos_types::transaction::{Transaction, TransactionOutput};
+use move_core_types::{
+    account_address::AccountAddress,
+    gas_algebra::NumBytes,
+    language_storage::{ModuleId, TypeTag},
+    vm_status::VMStatus,
+};
+use move_vm_runtime::tracing::{set_execution_tracer, ExecutionTracer, InstructionStep};
+use move_vm_types::values::debug::{print_each_local, print_value};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// What is recorded for every executed instruction.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum TraceDetail {
+    /// Only the instruction and where it is executed, which keeps traces compact.
+    #[default]
+    Instructions,
+    /// The instruction along with the locals and the operand stack of its frame, printed
+    /// before executing it. This can make traces orders of magnitude larger.
+    Values,
+}
+
+/// The values of a frame before executing an instruction.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct FrameValues {
+    pub locals: Vec<String>,
+    pub operand_stack: Vec<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum TraceEvent {
+    /// An instruction about to be executed.
+    Instruction {
+        /// Number of frames below this one, restarting at 0 for every entry point (e.g. the
+        /// prologue, the payload and the epilogue).
+        call_depth: u64,
+        /// `None` for scripts.
+        module: Option<ModuleId>,
+        function: String,
+        function_index: u16,
+        pc: u16,
+        instruction: String,
+        /// Only recorded with [`TraceDetail::Values`].
+        values: Option<FrameValues>,
+    },
+    /// A resource read from storage, the first time the transaction accessed it.
+    ResourceRead {
+        address: AccountAddress,
+        resource: TypeTag,
+        bytes: u64,
+        exists: bool,
+    },
+}
+
+/// Everything the Move VM did while executing a transaction, in order.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct TransactionTrace {
+    pub events: Vec<TraceEvent>,
+}
+
+#[derive(Clone)]
+struct TraceCollector {
+    detail: TraceDetail,
+    events: Arc<Mutex<Vec<TraceEvent>>>,
+}
+
+impl TraceCollector {
+    fn new(detail: TraceDetail) -> Self {
+        Self {
+            detail,
+            events: Arc::new(Mutex::new(vec![])),
+        }
+    }
+
+    fn take(&self) -> TransactionTrace {
+        TransactionTrace {
+            events: std::mem::take(&mut *self.events.lock()),
+        }
+    }
+}
+
+impl ExecutionTracer for TraceCollector {
+    fn instruction(&mut self, step: InstructionStep) {
+        let values = match self.detail {
+            TraceDetail::Instructions => None,
+            // Printing only fails on values the VM can't hold in a frame to begin with.
+            TraceDetail::Values => Some(FrameValues {
+                locals: print_each_local(step.locals).unwrap_or_default(),
+                operand_stack: step
+                    .operand_stack
+                    .iter()
+                    .map(|value| {
+                        let mut buf = String::new();
+                        print_value(&mut buf, value).map_or_else(|_| "?".to_string(), |_| buf)
+                    })
+                    .collect(),
+            }),
+        };
+
+        self.events.lock().push(TraceEvent::Instruction {
+            call_depth: step.call_depth as u64,
+            module: step.module.cloned(),
+            function: step.function.to_string(),
+            function_index: step.function_index.0,
+            pc: step.pc,
+            instruction: format!("{:?}", step.instruction),
+            values,
+        });
+    }
+
+    fn resource_read(
+        &mut self,
+        address: AccountAddress,
+        ty: &TypeTag,
+        bytes_loaded: NumBytes,
+        exists: bool,
+    ) {
+        self.events.lock().push(TraceEvent::ResourceRead {
+            address,
+            resource: ty.clone(),
+            bytes: bytes_loaded.into(),
+            exists,
+        });
+    }
+}
+
+/// Cuts the collected events into the traces of the individual transactions, as they are
+/// executed one after the other.
+struct TraceCommitHook {
+    collector: TraceCollector,
+    traces: Arc<Mutex<Vec<TransactionTrace>>>,
+}
+
+impl TraceCommitHook {
+    fn cut_trace(&self) {
+        self.traces.lock().push(self.collector.take());
+    }
+}
+
+impl TransactionCommitHook for TraceCommitHook {
+    type Output = AptosTransactionOutput;
+
+    fn on_transaction_committed(&self, _txn_idx: TxnIndex, _output: &Self::Output) {
+        self.cut_trace();
+    }
+
+    fn on_execution_aborted(&self, _txn_idx: TxnIndex) {
+        self.cut_trace();
+    }
+}
+
+/// Executes a block like `AptosVM::execute_block`, but sequentially on the current thread so
+/// that the trace of every transaction can be recorded (with the given detail).
+pub fn execute_block_with_trace(
+    transactions: Vec<Transaction>,
+    state_view: &(impl StateView + Sync),
+    maybe_block_gas_limit: Option<u64>,
+    detail: TraceDetail,
+) -> Result<Vec<(TransactionOutput, TransactionTrace)>, VMStatus> {
+    let collector = TraceCollector::new(detail);
+    let traces = Arc::new(Mutex::new(vec![]));
+    let commit_hook = TraceCommitHook {
+        collector: collector.clone(),
+        traces: traces.clone(),
+    };
+
+    let previous_tracer = set_execution_tracer(Some(Box::new(collector)));
+    let result = BlockAptosVM::execute_block(
+        Arc::clone(&RAYON_EXEC_POOL),
+        transactions,
+        state_view,
+        1, /* concurrency_level */
+        maybe_block_gas_limit,
+        Some(commit_hook),
+    );
+    set_execution_tracer(previous_tracer);
+
+    let outputs = result?;
+    let mut traces = std::mem::take(&mut *traces.lock());
+    // Transactions after the block got cut short are not executed.
+    traces.resize_with(outputs.len(), TransactionTrace::default);
+    Ok(outputs.into_iter().zip(traces).collect())
+}
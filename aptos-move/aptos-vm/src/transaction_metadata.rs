@@ -7,10 +7,24 @@ use aptos_gas_algebra::{FeePerGasUnit, Gas, NumBytes};
 use aptos_types::{
     account_address::AccountAddress,
     chain_id::ChainId,
-    transaction::{authenticator::AuthenticationKey, SignedTransaction, TransactionPayload},
+    transaction::{
+        authenticator::{AccountAuthenticator, AuthenticationKey},
+        SignedTransaction, TransactionPayload,
+    },
 };
 use std::convert::TryFrom;
 
+/// The authentication key passed to the prologue for a signer. Signers using account abstraction
+/// have no key to check against: the prologue instead requires that their account has an
+/// authentication function registered, which the VM runs before the prologue.
+fn authentication_key(authenticator: &AccountAuthenticator) -> Vec<u8> {
+    if authenticator.is_abstraction() {
+        vec![]
+    } else {
+        authenticator.authentication_key().to_vec()
+    }
+}
+
 pub struct TransactionMetadata {
     pub sender: AccountAddress,
     pub authentication_key: Vec<u8>,
@@ -32,20 +46,20 @@ impl TransactionMetadata {
     pub fn new(txn: &SignedTransaction) -> Self {
         Self {
             sender: txn.sender(),
-            authentication_key: txn.authenticator().sender().authentication_key().to_vec(),
+            authentication_key: authentication_key(&txn.authenticator().sender()),
             secondary_signers: txn.authenticator().secondary_signer_addreses(),
             secondary_authentication_keys: txn
                 .authenticator()
                 .secondary_signers()
                 .iter()
-                .map(|account_auth| account_auth.authentication_key().to_vec())
+                .map(authentication_key)
                 .collect(),
             sequence_number: txn.sequence_number(),
             fee_payer: txn.authenticator_ref().fee_payer_address(),
             fee_payer_authentication_key: txn
                 .authenticator()
                 .fee_payer_signer()
-                .map(|signer| signer.authentication_key().to_vec()),
+                .map(|signer| authentication_key(&signer)),
             max_gas_amount: txn.max_gas_amount().into(),
             gas_unit_price: txn.gas_unit_price().into(),
             transaction_size: (txn.raw_txn_bytes_len() as u64).into(),
@@ -72,6 +72,25 @@ pub static SPECULATIVE_ABORT_COUNT: Lazy<IntCounter> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Count of blocks executed in each mode, as picked by the execution mode selector.
+pub static BLOCK_EXECUTION_MODE_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_execution_block_execution_mode_count",
+        "Count of blocks executed in each mode (sequential or parallel)",
+        &["mode"]
+    )
+    .unwrap()
+});
+
+/// Aborts per transaction of the blocks executed in parallel, as sampled for mode selection.
+pub static PARALLEL_BLOCK_ABORT_RATE: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        "aptos_execution_parallel_block_abort_rate",
+        "Speculative aborts per transaction of blocks executed in parallel"
+    )
+    .unwrap()
+});
+
 /// Count of times the BlockSTM is early halted due to exceeding the per-block gas limit.
 pub static EXCEED_PER_BLOCK_GAS_LIMIT_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
     register_int_counter_vec!(
@@ -0,0 +1,169 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Picks how blocks are executed (sequentially or with Block-STM) from the conflicts observed
+//! while executing previous blocks in parallel. Both modes produce the same outputs, so the
+//! selection may depend on what a node observed locally. Modes that change the outputs, such as
+//! partitioning blocks across shards, must not be picked this way.
+
+use crate::counters::{Mode, BLOCK_EXECUTION_MODE_COUNT, PARALLEL_BLOCK_ABORT_RATE};
+use aptos_infallible::Mutex;
+use std::collections::VecDeque;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BlockExecutionMode {
+    Sequential,
+    Parallel,
+}
+
+impl BlockExecutionMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Sequential => Mode::SEQUENTIAL,
+            Self::Parallel => Mode::PARALLEL,
+        }
+    }
+
+    /// Records that a block is executed in this mode.
+    pub fn observe(&self) {
+        BLOCK_EXECUTION_MODE_COUNT
+            .with_label_values(&[self.as_str()])
+            .inc();
+    }
+}
+
+/// Conflicts observed while executing a block in parallel.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct BlockExecutionStats {
+    pub num_txns: usize,
+    /// Incarnations aborted after a failed validation, each leading to a re-execution.
+    pub num_aborts: usize,
+    /// Executions suspended on a read dependency.
+    pub num_dependency_waits: usize,
+    /// Whether parallel execution was given up for sequential execution, due to modules being
+    /// read and published in the block.
+    pub module_publishing_fallback: bool,
+}
+
+impl BlockExecutionStats {
+    /// Transactions executed again per transaction of the block. A fallback to sequential
+    /// execution re-executes all of them.
+    pub fn abort_rate(&self) -> f64 {
+        if self.num_txns == 0 {
+            return 0.0;
+        }
+        let num_aborts = if self.module_publishing_fallback {
+            self.num_aborts + self.num_txns
+        } else {
+            self.num_aborts
+        };
+        num_aborts as f64 / self.num_txns as f64
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ExecutionModeSelectorConfig {
+    /// Executes every block in this mode, instead of adapting it to the observed conflicts.
+    pub mode_override: Option<BlockExecutionMode>,
+    /// Number of most recent blocks executed in parallel the abort rate is averaged over.
+    pub window_size: usize,
+    /// Abort rate at or above which blocks are executed sequentially.
+    pub sequential_abort_rate: f64,
+    /// Number of blocks executed without sampling conflicts (e.g. sequentially), after which a
+    /// block is executed in parallel again, to sample the current workload.
+    pub probe_interval: usize,
+}
+
+impl Default for ExecutionModeSelectorConfig {
+    fn default() -> Self {
+        Self {
+            mode_override: None,
+            window_size: 10,
+            sequential_abort_rate: 1.0,
+            probe_interval: 20,
+        }
+    }
+}
+
+#[derive(Default)]
+struct SelectorState {
+    samples: VecDeque<BlockExecutionStats>,
+    blocks_since_sample: usize,
+}
+
+impl SelectorState {
+    fn abort_rate(&self) -> f64 {
+        let num_txns: usize = self.samples.iter().map(|stats| stats.num_txns).sum();
+        if num_txns == 0 {
+            return 0.0;
+        }
+        let num_aborts: f64 = self
+            .samples
+            .iter()
+            .map(|stats| stats.abort_rate() * stats.num_txns as f64)
+            .sum();
+        num_aborts / num_txns as f64
+    }
+}
+
+/// Picks the execution mode of every block from the conflict rates sampled on the previous
+/// blocks executed in parallel. Selecting is side effect free, the outcome of executing a block
+/// has to be recorded separately.
+pub struct ExecutionModeSelector {
+    config: ExecutionModeSelectorConfig,
+    state: Mutex<SelectorState>,
+}
+
+impl ExecutionModeSelector {
+    pub fn new(config: ExecutionModeSelectorConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(SelectorState::default()),
+        }
+    }
+
+    pub fn config(&self) -> &ExecutionModeSelectorConfig {
+        &self.config
+    }
+
+    /// Returns the mode to execute the next block in.
+    pub fn select(&self) -> BlockExecutionMode {
+        if let Some(mode) = self.config.mode_override {
+            return mode;
+        }
+
+        let state = self.state.lock();
+        // Nothing is known about the workload yet, or it may have changed since it was last
+        // sampled: execute in parallel to find out.
+        if state.samples.is_empty() || state.blocks_since_sample >= self.config.probe_interval {
+            return BlockExecutionMode::Parallel;
+        }
+
+        if state.abort_rate() >= self.config.sequential_abort_rate {
+            BlockExecutionMode::Sequential
+        } else {
+            BlockExecutionMode::Parallel
+        }
+    }
+
+    /// Records the conflicts observed while executing a block in parallel.
+    pub fn record_parallel(&self, stats: BlockExecutionStats) {
+        PARALLEL_BLOCK_ABORT_RATE.observe(stats.abort_rate());
+
+        let mut state = self.state.lock();
+        if state.blocks_since_sample >= self.config.probe_interval {
+            // The samples are stale.
+            state.samples.clear();
+        }
+        state.blocks_since_sample = 0;
+        state.samples.push_back(stats);
+        while state.samples.len() > self.config.window_size.max(1) {
+            state.samples.pop_front();
+        }
+    }
+
+    /// Records a block executed without sampling its conflicts.
+    pub fn record_unsampled(&self) {
+        self.state.lock().blocks_since_sample += 1;
+    }
+}
@@ -9,8 +9,11 @@ use crate::{
         TASK_VALIDATE_SECONDS, VM_INIT_SECONDS, WORK_WITH_TASK_SECONDS,
     },
     errors::*,
-    scheduler::{DependencyStatus, ExecutionTaskType, Scheduler, SchedulerTask, Wave},
-    task::{ExecutionStatus, ExecutorTask, Transaction, TransactionOutput},
+    execution_mode::{BlockExecutionStats, ExecutionModeSelector},
+    scheduler::{
+        DependencyResult, DependencyStatus, ExecutionTaskType, Scheduler, SchedulerTask, Wave,
+    },
+    task::{Accesses, ExecutionStatus, ExecutorTask, Transaction, TransactionOutput},
     txn_commit_hook::TransactionCommitHook,
     txn_last_input_output::TxnLastInputOutput,
     view::{LatestView, ParallelState, SequentialState, ViewState},
@@ -29,6 +32,7 @@ use num_cpus;
 use rayon::ThreadPool;
 use std::{
     collections::HashMap,
+    hash::Hash,
     marker::PhantomData,
     sync::{
         atomic::AtomicU32,
@@ -77,6 +81,9 @@ pub struct BlockExecutor<T, E, S, L, X> {
     executor_thread_pool: Arc<ThreadPool>,
     maybe_block_gas_limit: Option<u64>,
     transaction_commit_hook: Option<L>,
+    execution_mode_selector: Option<Arc<ExecutionModeSelector>>,
+    /// For each transaction, the closest preceding one estimated to write a key it accesses.
+    estimated_dependencies: Vec<Option<TxnIndex>>,
     phantom: PhantomData<(T, E, S, L, X)>,
 }
 
@@ -106,12 +113,56 @@ where
             executor_thread_pool,
             maybe_block_gas_limit,
             transaction_commit_hook,
+            execution_mode_selector: None,
+            estimated_dependencies: vec![],
             phantom: PhantomData,
         }
     }
 
+    /// Samples the conflicts of the executed blocks into the selector, so it can pick the
+    /// execution mode of the next ones.
+    pub fn with_execution_mode_selector(
+        mut self,
+        execution_mode_selector: Arc<ExecutionModeSelector>,
+    ) -> Self {
+        self.execution_mode_selector = Some(execution_mode_selector);
+        self
+    }
+
+    /// Executes every transaction once the closest preceding transaction estimated to write a
+    /// key it accesses is executed, instead of speculatively executing it (and likely aborting
+    /// it). Transactions whose accesses are unknown are executed speculatively.
+    pub fn with_estimated_accesses(mut self, accesses: Vec<Option<Accesses<T::Key>>>) -> Self {
+        self.estimated_dependencies = estimated_dependencies(accesses);
+        self
+    }
+
+    /// Waits for a transaction estimated to write a key the transaction reads. Returns false if
+    /// the execution got halted.
+    fn wait_for_estimated_dependency(
+        scheduler: &Scheduler,
+        txn_idx: TxnIndex,
+        dep_idx: TxnIndex,
+    ) -> bool {
+        match scheduler.wait_for_dependency(txn_idx, dep_idx) {
+            DependencyResult::Dependency(dep_condition) => {
+                let _timer = counters::DEPENDENCY_WAIT_SECONDS.start_timer();
+                // Same as waiting on a read dependency, see `LatestView`.
+                let (lock, cvar) = &*dep_condition;
+                let mut dep_resolved = lock.lock();
+                while let DependencyStatus::Unresolved = *dep_resolved {
+                    dep_resolved = cvar.wait(dep_resolved).unwrap();
+                }
+                !matches!(*dep_resolved, DependencyStatus::ExecutionHalted)
+            },
+            DependencyResult::ExecutionHalted => false,
+            DependencyResult::Resolved => true,
+        }
+    }
+
     fn execute(
         version: Version,
+        estimated_dependency: Option<TxnIndex>,
         signature_verified_block: &[T],
         last_input_output: &TxnLastInputOutput<T::Key, E::Output, E::Error>,
         versioned_cache: &MVHashMap<T::Key, T::Value, X>,
@@ -124,6 +175,13 @@ where
         let (idx_to_execute, incarnation) = version;
         let txn = &signature_verified_block[idx_to_execute as usize];
 
+        // Only the first incarnation waits, re-executions follow actual conflicts.
+        if let (0, Some(dep_idx)) = (incarnation, estimated_dependency) {
+            if !Self::wait_for_estimated_dependency(scheduler, idx_to_execute, dep_idx) {
+                return SchedulerTask::NoTask;
+            }
+        }
+
         // VM execution.
         let sync_view = LatestView::new(base_view, ViewState::Sync(latest_view), idx_to_execute);
         let execute_result = executor.execute_transaction(&sync_view, txn, idx_to_execute, false);
@@ -477,6 +535,10 @@ where
                 SchedulerTask::ExecutionTask(version_to_execute, ExecutionTaskType::Execution) => {
                     Self::execute(
                         version_to_execute,
+                        self.estimated_dependencies
+                            .get(version_to_execute.0 as usize)
+                            .copied()
+                            .flatten(),
                         block,
                         last_input_output,
                         versioned_cache,
@@ -573,6 +635,15 @@ where
         });
         drop(timer);
 
+        if let Some(execution_mode_selector) = &self.execution_mode_selector {
+            execution_mode_selector.record_parallel(BlockExecutionStats {
+                num_txns: num_txns as usize,
+                num_aborts: scheduler.num_aborts() as usize,
+                num_dependency_waits: scheduler.num_dependency_waits() as usize,
+                module_publishing_fallback: last_input_output.module_publishing_may_race(),
+            });
+        }
+
         let num_txns = num_txns as usize;
         // TODO: for large block sizes and many cores, extract outputs in parallel.
         let mut final_results = Vec::with_capacity(num_txns);
@@ -738,6 +809,9 @@ where
                 base_view,
             )
         } else {
+            if let Some(execution_mode_selector) = &self.execution_mode_selector {
+                execution_mode_selector.record_unsampled();
+            }
             self.execute_transactions_sequential(
                 executor_arguments,
                 &signature_verified_block,
@@ -765,3 +839,28 @@ where
         ret
     }
 }
+
+/// Returns, for each transaction, the closest preceding transaction estimated to write a key it
+/// reads or writes.
+pub(crate) fn estimated_dependencies<K: Hash + Eq>(
+    accesses: Vec<Option<Accesses<K>>>,
+) -> Vec<Option<TxnIndex>> {
+    let mut last_writers: HashMap<K, TxnIndex> = HashMap::new();
+    accesses
+        .into_iter()
+        .enumerate()
+        .map(|(idx, accesses)| {
+            let accesses = accesses?;
+            let dependency = accesses
+                .keys_read
+                .iter()
+                .chain(accesses.keys_written.iter())
+                .filter_map(|key| last_writers.get(key).copied())
+                .max();
+            for key in accesses.keys_written {
+                last_writers.insert(key, idx as TxnIndex);
+            }
+            dependency
+        })
+        .collect()
+}
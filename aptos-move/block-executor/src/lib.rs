@@ -138,6 +138,7 @@ subsequent incarnation to finish.
 **/
 pub mod counters;
 pub mod errors;
+pub mod execution_mode;
 pub mod executor;
 #[cfg(any(test, feature = "fuzzing"))]
 pub mod proptest_types;
@@ -238,6 +238,11 @@ pub struct Scheduler {
 
     /// Shared marker that is set when a thread detects that all txns can be committed.
     done_marker: CachePadded<AtomicBool>,
+
+    /// Number of incarnations aborted due to a failed validation, i.e. of re-executions.
+    num_aborts: AtomicU32,
+    /// Number of times an execution was suspended on a read dependency.
+    num_dependency_waits: AtomicU32,
 }
 
 /// Public Interfaces for the Scheduler
@@ -263,6 +268,8 @@ impl Scheduler {
             execution_idx: AtomicU32::new(0),
             validation_idx: AtomicU64::new(0),
             done_marker: CachePadded::new(AtomicBool::new(false)),
+            num_aborts: AtomicU32::new(0),
+            num_dependency_waits: AtomicU32::new(0),
         }
     }
 
@@ -270,6 +277,16 @@ impl Scheduler {
         self.num_txns
     }
 
+    /// Number of successful calls to try_abort so far.
+    pub fn num_aborts(&self) -> u32 {
+        self.num_aborts.load(Ordering::Relaxed)
+    }
+
+    /// Number of dependencies waited on so far.
+    pub fn num_dependency_waits(&self) -> u32 {
+        self.num_dependency_waits.load(Ordering::Relaxed)
+    }
+
     /// If successful, returns Some(TxnIndex), the index of committed transaction.
     /// The current implementation has one dedicated thread to try_commit.
     /// Should not be called after the last transaction is committed.
@@ -335,6 +352,7 @@ impl Scheduler {
 
         if *status == ExecutionStatus::Executed(incarnation) {
             *status = ExecutionStatus::Aborting(incarnation);
+            self.num_aborts.fetch_add(1, Ordering::Relaxed);
             true
         } else {
             false
@@ -431,6 +449,7 @@ impl Scheduler {
         if !self.suspend(txn_idx, dep_condvar.clone()) {
             return DependencyResult::ExecutionHalted;
         }
+        self.num_dependency_waits.fetch_add(1, Ordering::Relaxed);
 
         // Safe to add dependency here (still holding the lock) - finish_execution of txn
         // dep_txn_idx is guaranteed to acquire the same lock later and clear the dependency.
@@ -3,7 +3,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    executor::BlockExecutor,
+    execution_mode::{
+        BlockExecutionMode, BlockExecutionStats, ExecutionModeSelector, ExecutionModeSelectorConfig,
+    },
+    executor::{estimated_dependencies, BlockExecutor},
     proptest_types::{
         baseline::BaselineOutput,
         types::{
@@ -12,6 +15,7 @@ use crate::{
         },
     },
     scheduler::{DependencyResult, ExecutionTaskType, Scheduler, SchedulerTask},
+    task::Accesses,
     txn_commit_hook::NoOpTransactionCommitHook,
 };
 use aptos_aggregator::delta_change_set::{delta_add, delta_sub, DeltaOp, DeltaUpdate};
@@ -373,6 +377,7 @@ fn scheduler_tasks() {
     // unsuccessful aborts
     assert!(!s.try_abort(1, 0));
     assert!(!s.try_abort(3, 0));
+    assert_eq!(s.num_aborts(), 3);
 
     assert!(matches!(
         s.finish_abort(4, 0),
@@ -906,3 +911,99 @@ fn no_conflict_task_count() {
         assert!(matches!(s.next_task(false), SchedulerTask::Done));
     }
 }
+
+fn parallel_block_stats(num_txns: usize, num_aborts: usize) -> BlockExecutionStats {
+    BlockExecutionStats {
+        num_txns,
+        num_aborts,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn execution_mode_from_abort_rate() {
+    let selector = ExecutionModeSelector::new(ExecutionModeSelectorConfig {
+        window_size: 2,
+        ..Default::default()
+    });
+    // Without samples, blocks are executed in parallel.
+    assert_eq!(selector.select(), BlockExecutionMode::Parallel);
+
+    // Few conflicts.
+    selector.record_parallel(parallel_block_stats(1000, 10));
+    assert_eq!(selector.select(), BlockExecutionMode::Parallel);
+
+    // Averaged over the window.
+    selector.record_parallel(parallel_block_stats(1000, 1000));
+    assert_eq!(selector.select(), BlockExecutionMode::Parallel);
+    selector.record_parallel(parallel_block_stats(1000, 2000));
+    assert_eq!(selector.select(), BlockExecutionMode::Sequential);
+
+    // A fallback to sequential execution re-executes the whole block.
+    selector.record_parallel(parallel_block_stats(1000, 0));
+    selector.record_parallel(parallel_block_stats(1000, 0));
+    assert_eq!(selector.select(), BlockExecutionMode::Parallel);
+    for _ in 0..2 {
+        selector.record_parallel(BlockExecutionStats {
+            num_txns: 1000,
+            module_publishing_fallback: true,
+            ..Default::default()
+        });
+    }
+    assert_eq!(selector.select(), BlockExecutionMode::Sequential);
+}
+
+#[test]
+fn execution_mode_probes_parallel() {
+    let selector = ExecutionModeSelector::new(ExecutionModeSelectorConfig {
+        probe_interval: 3,
+        ..Default::default()
+    });
+    selector.record_parallel(parallel_block_stats(100, 500));
+    for _ in 0..3 {
+        assert_eq!(selector.select(), BlockExecutionMode::Sequential);
+        selector.record_unsampled();
+    }
+    assert_eq!(selector.select(), BlockExecutionMode::Parallel);
+
+    // The stale samples are dropped.
+    selector.record_parallel(parallel_block_stats(100, 0));
+    assert_eq!(selector.select(), BlockExecutionMode::Parallel);
+}
+
+#[test]
+fn execution_mode_override() {
+    let selector = ExecutionModeSelector::new(ExecutionModeSelectorConfig {
+        mode_override: Some(BlockExecutionMode::Parallel),
+        ..Default::default()
+    });
+    selector.record_parallel(parallel_block_stats(100, 500));
+    assert_eq!(selector.select(), BlockExecutionMode::Parallel);
+}
+
+fn accesses(keys_read: Vec<u32>, keys_written: Vec<u32>) -> Option<Accesses<u32>> {
+    Some(Accesses {
+        keys_read,
+        keys_written,
+    })
+}
+
+#[test]
+fn estimated_dependencies_on_last_writer() {
+    let dependencies = estimated_dependencies(vec![
+        accesses(vec![], vec![1]),
+        accesses(vec![1], vec![2]),
+        None,
+        accesses(vec![3], vec![1]),
+        accesses(vec![1, 2], vec![]),
+        accesses(vec![4], vec![5]),
+    ]);
+    assert_eq!(dependencies, vec![
+        None,
+        Some(0),
+        None,
+        Some(0),
+        Some(3),
+        None
+    ]);
+}
@@ -0,0 +1,91 @@
+/// Authenticates transactions of an account with an Ed25519 session key, which signs the digest of the signing
+/// message of the transaction.
+module 0xcafe::session_key {
+    use std::signer;
+    use std::string;
+    use aptos_std::ed25519;
+    use aptos_framework::account_abstraction;
+
+    const EINVALID_SIGNATURE: u64 = 1;
+
+    struct SessionKey has key {
+        public_key: vector<u8>,
+    }
+
+    /// Written by `authenticate_and_record`, so it must never be committed.
+    struct Authenticated has key {
+        signing_message_digest: vector<u8>,
+    }
+
+    public entry fun register(account: &signer, public_key: vector<u8>) acquires SessionKey {
+        let account_addr = signer::address_of(account);
+        if (exists<SessionKey>(account_addr)) {
+            borrow_global_mut<SessionKey>(account_addr).public_key = public_key;
+        } else {
+            move_to(account, SessionKey { public_key });
+        };
+        account_abstraction::register_authentication_function(
+            account,
+            @0xcafe,
+            string::utf8(b"session_key"),
+            string::utf8(b"authenticate"),
+        );
+    }
+
+    fun authenticate(
+        account: signer,
+        signing_message_digest: vector<u8>,
+        authenticator: vector<u8>,
+    ) acquires SessionKey {
+        let session_key = borrow_global<SessionKey>(signer::address_of(&account));
+        assert!(
+            ed25519::signature_verify_strict(
+                &ed25519::new_signature_from_bytes(authenticator),
+                &ed25519::new_unvalidated_public_key_from_bytes(session_key.public_key),
+                signing_message_digest,
+            ),
+            EINVALID_SIGNATURE,
+        );
+    }
+
+    /// Authenticates like `authenticate`, after a loop that uses more gas.
+    fun authenticate_after_loop(
+        account: signer,
+        signing_message_digest: vector<u8>,
+        authenticator: vector<u8>,
+    ) acquires SessionKey {
+        let i = 0;
+        while (i < 1000) {
+            i = i + 1;
+        };
+        authenticate(account, signing_message_digest, authenticator);
+    }
+
+    /// Authenticates like `authenticate`, after recording the authentication in the account's storage.
+    fun authenticate_and_record(
+        account: signer,
+        signing_message_digest: vector<u8>,
+        authenticator: vector<u8>,
+    ) acquires SessionKey {
+        move_to(&account, Authenticated { signing_message_digest });
+        authenticate(account, signing_message_digest, authenticator);
+    }
+
+    /// Returns whether the authenticator is valid instead of aborting, so it must not be accepted as an authentication
+    /// function.
+    fun authenticate_with_result(
+        account: signer,
+        signing_message_digest: vector<u8>,
+        authenticator: vector<u8>,
+    ): bool acquires SessionKey {
+        let session_key = borrow_global<SessionKey>(signer::address_of(&account));
+        ed25519::signature_verify_strict(
+            &ed25519::new_signature_from_bytes(authenticator),
+            &ed25519::new_unvalidated_public_key_from_bytes(session_key.public_key),
+            signing_message_digest,
+        )
+    }
+
+    /// Ignores the authenticator, so it must not be accepted as an authentication function.
+    fun authenticate_without_authenticator(_account: signer, _signing_message_digest: vector<u8>) {}
+}
@@ -0,0 +1,200 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{assert_success, tests::common, MoveHarness};
+use aptos_cached_packages::aptos_stdlib;
+use aptos_crypto::{
+    ed25519::{Ed25519PrivateKey, Ed25519PublicKey},
+    traits::signing_message,
+    HashValue, SigningKey, Uniform, ValidCryptoMaterial,
+};
+use aptos_language_e2e_tests::{account::Account, transaction_status_eq};
+use aptos_types::{
+    chain_id::ChainId,
+    on_chain_config::FeatureFlag,
+    transaction::{RawTransaction, SignedTransaction, TransactionStatus},
+};
+use move_core_types::{account_address::AccountAddress, vm_status::StatusCode};
+
+fn create_raw_transfer(
+    h: &MoveHarness,
+    sender: AccountAddress,
+    receiver: AccountAddress,
+) -> RawTransaction {
+    RawTransaction::new(
+        sender,
+        h.sequence_number(&sender),
+        aptos_stdlib::aptos_account_transfer(receiver, 1),
+        100_000,
+        100,
+        u64::MAX,
+        ChainId::test(),
+    )
+}
+
+/// Signs the digest of the transaction's signing message with the session key, as expected by
+/// the `session_key::authenticate` function of the test package.
+fn create_session_key_transfer(
+    h: &MoveHarness,
+    session_key: &Ed25519PrivateKey,
+    sender: AccountAddress,
+    receiver: AccountAddress,
+) -> SignedTransaction {
+    let raw_txn = create_raw_transfer(h, sender, receiver);
+    let digest = HashValue::sha3_256_of(&signing_message(&raw_txn).unwrap());
+    let signature = session_key.sign_arbitrary_message(digest.as_ref());
+    SignedTransaction::new_abstraction(raw_txn, signature.to_bytes().to_vec())
+}
+
+/// Publishes the test package at 0xcafe and registers `session_key` as its authentication
+/// function.
+fn setup(h: &mut MoveHarness, session_key: &Ed25519PrivateKey) -> Account {
+    let account = h.new_account_at(AccountAddress::from_hex_literal("0xcafe").unwrap());
+    assert_success!(h.publish_package(
+        &account,
+        &common::test_dir_path("account_abstraction.data/pack"),
+    ));
+
+    let public_key = Ed25519PublicKey::from(session_key);
+    assert_success!(h.run_entry_function(
+        &account,
+        str::parse("0xcafe::session_key::register").unwrap(),
+        vec![],
+        vec![bcs::to_bytes(&public_key.to_bytes().to_vec()).unwrap()],
+    ));
+    account
+}
+
+/// Registers `0xcafe::session_key::<function_name>` as the authentication function of `account`.
+fn register_function(h: &mut MoveHarness, account: &Account, function_name: &str) {
+    assert_success!(h.run_entry_function(
+        account,
+        str::parse("0x1::account_abstraction::register_authentication_function").unwrap(),
+        vec![],
+        vec![
+            bcs::to_bytes(account.address()).unwrap(),
+            bcs::to_bytes("session_key").unwrap(),
+            bcs::to_bytes(function_name).unwrap(),
+        ],
+    ));
+}
+
+#[test]
+fn test_account_abstraction() {
+    let mut h = MoveHarness::new_with_features(vec![FeatureFlag::ACCOUNT_ABSTRACTION], vec![]);
+    let alice = h.new_account_at(AccountAddress::from_hex_literal("0xa11ce").unwrap());
+    let session_key = Ed25519PrivateKey::generate_for_testing();
+    let account = setup(&mut h, &session_key);
+    let sequence_number = h.sequence_number(account.address());
+
+    // Transactions signed by the session key are accepted by the registered function
+    let txn = create_session_key_transfer(&h, &session_key, *account.address(), *alice.address());
+    assert_success!(h.run(txn));
+    assert_eq!(h.sequence_number(account.address()), sequence_number + 1);
+
+    // Signatures over a different transaction are rejected
+    let raw_txn = create_raw_transfer(&h, *account.address(), *alice.address());
+    let other_txn =
+        create_session_key_transfer(&h, &session_key, *account.address(), *account.address());
+    let txn = SignedTransaction::new_abstraction(
+        raw_txn,
+        other_txn
+            .authenticator()
+            .sender()
+            .signature_bytes()
+            .unwrap(),
+    );
+    assert!(matches!(h.run(txn), TransactionStatus::Discard(_)));
+
+    // Signatures by a different key are rejected
+    let other_session_key = Ed25519PrivateKey::generate_for_testing();
+    let txn =
+        create_session_key_transfer(&h, &other_session_key, *account.address(), *alice.address());
+    assert!(matches!(h.run(txn), TransactionStatus::Discard(_)));
+
+    // Accounts without a registered function cannot use account abstraction
+    let txn = create_session_key_transfer(&h, &session_key, *alice.address(), *account.address());
+    assert!(matches!(h.run(txn), TransactionStatus::Discard(_)));
+
+    // The authentication key of the account can still be used
+    assert_success!(h.run_transaction_payload(
+        &account,
+        aptos_stdlib::aptos_account_transfer(*alice.address(), 1),
+    ));
+}
+
+#[test]
+fn test_account_abstraction_gas_and_writes() {
+    let mut h = MoveHarness::new_with_features(vec![FeatureFlag::ACCOUNT_ABSTRACTION], vec![]);
+    let alice = h.new_account_at(AccountAddress::from_hex_literal("0xa11ce").unwrap());
+    let session_key = Ed25519PrivateKey::generate_for_testing();
+    let account = setup(&mut h, &session_key);
+    let run_transfer = |h: &mut MoveHarness| {
+        let txn =
+            create_session_key_transfer(h, &session_key, *account.address(), *alice.address());
+        let output = h.run_raw(txn);
+        assert_success!(output.status().clone());
+        output.gas_used()
+    };
+
+    // The gas used by the authentication function is charged to the transaction
+    let gas_used = run_transfer(&mut h);
+    register_function(&mut h, &account, "authenticate_after_loop");
+    let gas_used_after_loop = run_transfer(&mut h);
+    assert!(
+        gas_used_after_loop > gas_used,
+        "{} <= {}",
+        gas_used_after_loop,
+        gas_used
+    );
+
+    // The writes of the authentication function are discarded
+    register_function(&mut h, &account, "authenticate_and_record");
+    run_transfer(&mut h);
+    run_transfer(&mut h);
+    assert!(!h.exists_resource(
+        account.address(),
+        str::parse("0xcafe::session_key::Authenticated").unwrap(),
+    ));
+}
+
+#[test]
+fn test_account_abstraction_disabled() {
+    let mut h = MoveHarness::new_with_features(vec![FeatureFlag::ACCOUNT_ABSTRACTION], vec![]);
+    let alice = h.new_account_at(AccountAddress::from_hex_literal("0xa11ce").unwrap());
+    let session_key = Ed25519PrivateKey::generate_for_testing();
+    let account = setup(&mut h, &session_key);
+
+    h.enable_features(vec![], vec![FeatureFlag::ACCOUNT_ABSTRACTION]);
+    let txn = create_session_key_transfer(&h, &session_key, *account.address(), *alice.address());
+    assert!(transaction_status_eq(
+        &h.run(txn),
+        &TransactionStatus::Discard(StatusCode::FEATURE_UNDER_GATING)
+    ));
+}
+
+#[test]
+fn test_account_abstraction_invalid_function_signature() {
+    let mut h = MoveHarness::new_with_features(vec![FeatureFlag::ACCOUNT_ABSTRACTION], vec![]);
+    let alice = h.new_account_at(AccountAddress::from_hex_literal("0xa11ce").unwrap());
+    let session_key = Ed25519PrivateKey::generate_for_testing();
+    let account = setup(&mut h, &session_key);
+
+    for function_name in [
+        "authenticate_with_result",
+        "authenticate_without_authenticator",
+        "missing_function",
+    ] {
+        register_function(&mut h, &account, function_name);
+        let txn =
+            create_session_key_transfer(&h, &session_key, *account.address(), *alice.address());
+        assert!(
+            transaction_status_eq(
+                &h.run(txn),
+                &TransactionStatus::Discard(StatusCode::INVALID_SIGNATURE)
+            ),
+            "{} must not authenticate transactions",
+            function_name
+        );
+    }
+}
@@ -0,0 +1,112 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{assert_success, MoveHarness};
+use aptos_cached_packages::aptos_stdlib;
+use aptos_types::{
+    account_address::AccountAddress,
+    account_config::CoinStoreResource,
+    transaction::{Transaction, TransactionOutput},
+};
+use aptos_vm::move_vm_ext::trace::{
+    execute_block_with_trace, TraceDetail, TraceEvent, TransactionTrace,
+};
+use move_core_types::{language_storage::TypeTag, move_resource::MoveStructType};
+
+fn trace_transfer(detail: TraceDetail) -> (TransactionOutput, TransactionTrace) {
+    let mut h = MoveHarness::new();
+    let alice = h.new_account_at(AccountAddress::from_hex_literal("0xa11ce").unwrap());
+    let bob = h.new_account_at(AccountAddress::from_hex_literal("0xb0b").unwrap());
+
+    let txn = h.create_transaction_payload(
+        &alice,
+        aptos_stdlib::aptos_account_transfer(*bob.address(), 100),
+    );
+    let mut results = execute_block_with_trace(
+        vec![Transaction::UserTransaction(txn)],
+        h.executor.get_state_view(),
+        None,
+        detail,
+    )
+    .unwrap();
+    assert_eq!(results.len(), 1);
+    results.pop().unwrap()
+}
+
+fn instructions_in<'a>(
+    trace: &'a TransactionTrace,
+    module_name: &'a str,
+    function_name: &'a str,
+) -> impl Iterator<Item = &'a TraceEvent> {
+    trace.events.iter().filter(move |event| {
+        matches!(
+            event,
+            TraceEvent::Instruction { module: Some(module), function, .. }
+                if module.address() == &AccountAddress::ONE
+                    && module.name().as_str() == module_name
+                    && function == function_name
+        )
+    })
+}
+
+#[test]
+fn test_trace_records_instructions_and_resource_reads() {
+    let (output, trace) = trace_transfer(TraceDetail::Instructions);
+    assert_success!(output.status().to_owned());
+
+    // The entry function and the coin transfer it makes are both traced, at increasing depth.
+    let entry_depth = instructions_in(&trace, "aptos_account", "transfer")
+        .map(|event| match event {
+            TraceEvent::Instruction { call_depth, .. } => *call_depth,
+            TraceEvent::ResourceRead { .. } => unreachable!(),
+        })
+        .min()
+        .expect("aptos_account::transfer must be traced");
+    assert!(
+        instructions_in(&trace, "coin", "transfer").all(|event| matches!(
+            event,
+            TraceEvent::Instruction { call_depth, .. } if *call_depth > entry_depth
+        ))
+    );
+    assert!(instructions_in(&trace, "coin", "transfer").next().is_some());
+
+    // The coin stores of both accounts are read.
+    let coin_store = TypeTag::Struct(Box::new(CoinStoreResource::struct_tag()));
+    for address in [
+        AccountAddress::from_hex_literal("0xa11ce").unwrap(),
+        AccountAddress::from_hex_literal("0xb0b").unwrap(),
+    ] {
+        assert!(trace.events.iter().any(|event| matches!(
+            event,
+            TraceEvent::ResourceRead { address: read, resource, bytes, exists: true }
+                if read == &address && resource == &coin_store && *bytes > 0
+        )));
+    }
+
+    // Values are only recorded when asked for.
+    assert!(trace.events.iter().all(|event| matches!(
+        event,
+        TraceEvent::Instruction { values: None, .. } | TraceEvent::ResourceRead { .. }
+    )));
+}
+
+#[test]
+fn test_trace_records_values() {
+    let (output, trace) = trace_transfer(TraceDetail::Values);
+    assert_success!(output.status().to_owned());
+
+    // `aptos_account::transfer(source, to, amount)` starts with its arguments in its locals.
+    let values = instructions_in(&trace, "aptos_account", "transfer")
+        .find_map(|event| match event {
+            TraceEvent::Instruction {
+                pc: 0,
+                values: Some(values),
+                ..
+            } => Some(values),
+            _ => None,
+        })
+        .expect("aptos_account::transfer must be traced with values");
+    assert!(values.locals.len() >= 3);
+    assert!(values.locals[2].contains("100"));
+    assert!(values.operand_stack.is_empty());
+}
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod access_path_test;
+mod account_abstraction;
 mod aggregator;
 mod attributes;
 mod chain_id;
@@ -16,6 +17,7 @@ mod generate_upgrade_script;
 mod governance_updates;
 mod infinite_loop;
 mod init_module;
+mod instruction_trace;
 mod lazy_natives;
 mod max_loop_depth;
 mod memory_quota;
@@ -31,6 +33,7 @@ mod per_category_gas_limits;
 mod resource_groups;
 mod rotate_auth_key;
 mod scripts;
+mod secp256k1_ecdsa_authenticator;
 mod simple_defi;
 mod smart_data_structures;
 mod stake;
@@ -43,3 +46,4 @@ mod transaction_fee;
 mod type_too_large;
 mod vector_numeric_address;
 mod vote;
+mod webauthn_authenticator;
@@ -0,0 +1,92 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{assert_success, MoveHarness};
+use aptos_cached_packages::aptos_stdlib;
+use aptos_crypto::{
+    secp256k1_ecdsa::{Secp256k1EcdsaPrivateKey, Secp256k1EcdsaPublicKey},
+    Uniform,
+};
+use aptos_language_e2e_tests::transaction_status_eq;
+use aptos_types::{
+    chain_id::ChainId,
+    on_chain_config::FeatureFlag,
+    transaction::{
+        authenticator::AuthenticationKey, RawTransaction, SignedTransaction, TransactionStatus,
+    },
+};
+use move_core_types::{account_address::AccountAddress, vm_status::StatusCode};
+
+fn create_secp256k1_ecdsa_transfer(
+    h: &MoveHarness,
+    private_key: &Secp256k1EcdsaPrivateKey,
+    receiver: AccountAddress,
+) -> SignedTransaction {
+    let public_key = Secp256k1EcdsaPublicKey::from(private_key);
+    let sender = AuthenticationKey::secp256k1_ecdsa(&public_key).derived_address();
+    RawTransaction::new(
+        sender,
+        h.sequence_number(&sender),
+        aptos_stdlib::aptos_account_transfer(receiver, 1),
+        100_000,
+        100,
+        u64::MAX,
+        ChainId::test(),
+    )
+    .sign_secp256k1_ecdsa(private_key, public_key)
+    .unwrap()
+    .into_inner()
+}
+
+#[test]
+fn test_secp256k1_ecdsa_authenticator() {
+    let mut h =
+        MoveHarness::new_with_features(vec![FeatureFlag::SECP256K1_ECDSA_AUTHENTICATOR], vec![]);
+    let alice = h.new_account_at(AccountAddress::from_hex_literal("0xa11ce").unwrap());
+
+    // Fund the account derived from the secp256k1 public key (this also creates it)
+    let private_key = Secp256k1EcdsaPrivateKey::generate_for_testing();
+    let public_key = Secp256k1EcdsaPublicKey::from(&private_key);
+    let address = AuthenticationKey::secp256k1_ecdsa(&public_key).derived_address();
+    assert_success!(h.run_transaction_payload(
+        &alice,
+        aptos_stdlib::aptos_account_transfer(address, 100_000_000),
+    ));
+
+    // The account can now send transactions authenticated by its secp256k1 key
+    let txn = create_secp256k1_ecdsa_transfer(&h, &private_key, *alice.address());
+    assert_success!(h.run(txn));
+    assert_eq!(h.sequence_number(&address), 1);
+
+    // Transactions signed by a different key are rejected
+    let other_private_key = Secp256k1EcdsaPrivateKey::generate_for_testing();
+    let other_public_key = Secp256k1EcdsaPublicKey::from(&other_private_key);
+    let raw_txn =
+        create_secp256k1_ecdsa_transfer(&h, &private_key, *alice.address()).into_raw_transaction();
+    let txn = raw_txn
+        .sign_secp256k1_ecdsa(&other_private_key, other_public_key)
+        .unwrap()
+        .into_inner();
+    assert!(matches!(h.run(txn), TransactionStatus::Discard(_)));
+}
+
+#[test]
+fn test_secp256k1_ecdsa_authenticator_disabled() {
+    let mut h =
+        MoveHarness::new_with_features(vec![], vec![FeatureFlag::SECP256K1_ECDSA_AUTHENTICATOR]);
+    let alice = h.new_account_at(AccountAddress::from_hex_literal("0xa11ce").unwrap());
+
+    let private_key = Secp256k1EcdsaPrivateKey::generate_for_testing();
+    let public_key = Secp256k1EcdsaPublicKey::from(&private_key);
+    let address = AuthenticationKey::secp256k1_ecdsa(&public_key).derived_address();
+    assert_success!(h.run_transaction_payload(
+        &alice,
+        aptos_stdlib::aptos_account_transfer(address, 100_000_000),
+    ));
+
+    let txn = create_secp256k1_ecdsa_transfer(&h, &private_key, *alice.address());
+    assert!(transaction_status_eq(
+        &h.run(txn),
+        &TransactionStatus::Discard(StatusCode::FEATURE_UNDER_GATING)
+    ));
+}
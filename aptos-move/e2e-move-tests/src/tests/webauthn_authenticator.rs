@@ -0,0 +1,108 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{assert_success, MoveHarness};
+use aptos_cached_packages::aptos_stdlib;
+use aptos_crypto::{
+    secp256r1_ecdsa::{Secp256r1EcdsaPrivateKey, Secp256r1EcdsaPublicKey},
+    Uniform,
+};
+use aptos_language_e2e_tests::{account::Account, transaction_status_eq};
+use aptos_types::{
+    chain_id::ChainId,
+    on_chain_config::FeatureFlag,
+    transaction::{
+        authenticator::AuthenticationKey, webauthn::PartialAuthenticatorAssertionResponse,
+        RawTransaction, SignedTransaction, TransactionStatus,
+    },
+};
+use move_core_types::{account_address::AccountAddress, vm_status::StatusCode};
+
+fn create_webauthn_raw_transfer(
+    h: &MoveHarness,
+    public_key: &Secp256r1EcdsaPublicKey,
+    receiver: AccountAddress,
+) -> RawTransaction {
+    let sender = AuthenticationKey::webauthn(public_key).derived_address();
+    RawTransaction::new(
+        sender,
+        h.sequence_number(&sender),
+        aptos_stdlib::aptos_account_transfer(receiver, 1),
+        100_000,
+        100,
+        u64::MAX,
+        ChainId::test(),
+    )
+}
+
+fn create_webauthn_transfer(
+    h: &MoveHarness,
+    private_key: &Secp256r1EcdsaPrivateKey,
+    receiver: AccountAddress,
+) -> SignedTransaction {
+    let public_key = Secp256r1EcdsaPublicKey::from(private_key);
+    let raw_txn = create_webauthn_raw_transfer(h, &public_key, receiver);
+    let assertion = PartialAuthenticatorAssertionResponse::sign_for_testing(private_key, &raw_txn);
+    SignedTransaction::new_webauthn(raw_txn, public_key, assertion)
+}
+
+fn fund_webauthn_account(
+    h: &mut MoveHarness,
+    funder: &Account,
+    public_key: &Secp256r1EcdsaPublicKey,
+) -> AccountAddress {
+    let address = AuthenticationKey::webauthn(public_key).derived_address();
+    assert_success!(h.run_transaction_payload(
+        funder,
+        aptos_stdlib::aptos_account_transfer(address, 100_000_000),
+    ));
+    address
+}
+
+#[test]
+fn test_webauthn_authenticator() {
+    let mut h = MoveHarness::new_with_features(vec![FeatureFlag::WEBAUTHN_AUTHENTICATOR], vec![]);
+    let alice = h.new_account_at(AccountAddress::from_hex_literal("0xa11ce").unwrap());
+
+    // Fund the account derived from the passkey's public key (this also creates it)
+    let private_key = Secp256r1EcdsaPrivateKey::generate_for_testing();
+    let public_key = Secp256r1EcdsaPublicKey::from(&private_key);
+    let address = fund_webauthn_account(&mut h, &alice, &public_key);
+
+    // The account can now send transactions authenticated by WebAuthn assertions
+    let txn = create_webauthn_transfer(&h, &private_key, *alice.address());
+    assert_success!(h.run(txn));
+    assert_eq!(h.sequence_number(&address), 1);
+
+    // Assertions made over a different transaction are rejected
+    let raw_txn = create_webauthn_raw_transfer(&h, &public_key, *alice.address());
+    let other_raw_txn = create_webauthn_raw_transfer(&h, &public_key, address);
+    let assertion =
+        PartialAuthenticatorAssertionResponse::sign_for_testing(&private_key, &other_raw_txn);
+    let txn = SignedTransaction::new_webauthn(raw_txn, public_key.clone(), assertion);
+    assert!(matches!(h.run(txn), TransactionStatus::Discard(_)));
+
+    // Assertions made by a different passkey are rejected
+    let other_private_key = Secp256r1EcdsaPrivateKey::generate_for_testing();
+    let raw_txn = create_webauthn_raw_transfer(&h, &public_key, *alice.address());
+    let assertion =
+        PartialAuthenticatorAssertionResponse::sign_for_testing(&other_private_key, &raw_txn);
+    let txn = SignedTransaction::new_webauthn(raw_txn, public_key, assertion);
+    assert!(matches!(h.run(txn), TransactionStatus::Discard(_)));
+}
+
+#[test]
+fn test_webauthn_authenticator_disabled() {
+    let mut h = MoveHarness::new_with_features(vec![], vec![FeatureFlag::WEBAUTHN_AUTHENTICATOR]);
+    let alice = h.new_account_at(AccountAddress::from_hex_literal("0xa11ce").unwrap());
+
+    let private_key = Secp256r1EcdsaPrivateKey::generate_for_testing();
+    let public_key = Secp256r1EcdsaPublicKey::from(&private_key);
+    fund_webauthn_account(&mut h, &alice, &public_key);
+
+    let txn = create_webauthn_transfer(&h, &private_key, *alice.address());
+    assert!(transaction_status_eq(
+        &h.run(txn),
+        &TransactionStatus::Discard(StatusCode::FEATURE_UNDER_GATING)
+    ));
+}
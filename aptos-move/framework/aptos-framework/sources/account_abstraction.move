@@ -0,0 +1,117 @@
+/// This module allows an account to register a Move function that authenticates its transactions, in addition to the
+/// authentication key of the account. This enables custom authentication logic (e.g., session keys, spending limits or
+/// social recovery) without new native signature schemes.
+///
+/// A transaction signer using account abstraction provides an opaque authenticator instead of a signature. During
+/// validation, the VM calls the function registered by the signer's account with:
+/// 1. The signer of the account.
+/// 2. The SHA3-256 digest of the transaction's signing message (which also covers the secondary signers and the fee
+/// payer, if any).
+/// 3. The authenticator provided in the transaction.
+///
+/// The function must have the signature
+/// `fun authenticate(account: signer, signing_message_digest: vector<u8>, authenticator: vector<u8>)`
+/// and abort if the authenticator is not valid. The gas it uses is charged to the transaction, and is capped by the
+/// `max_aa_gas` gas parameter. Changes it makes to global storage are discarded, so it should only read state.
+///
+/// Registering a function does not disable the authentication key of the account, which can still be used to sign
+/// transactions (e.g., to unregister a faulty function).
+module aptos_framework::account_abstraction {
+    use std::error;
+    use std::features;
+    use std::option::{Self, Option};
+    use std::signer;
+    use std::string::String;
+
+    /// Account abstraction is not enabled.
+    const EACCOUNT_ABSTRACTION_NOT_ENABLED: u64 = 1;
+    /// The account has no authentication function registered.
+    const EDISPATCHABLE_AUTHENTICATOR_NOT_FOUND: u64 = 2;
+
+    /// The Move function that authenticates transactions for the account it is stored under.
+    struct DispatchableAuthenticator has key, copy, drop, store {
+        module_address: address,
+        module_name: String,
+        function_name: String,
+    }
+
+    /// Registers `module_address::module_name::function_name` as the authentication function of `account`, replacing
+    /// any previously registered function.
+    public entry fun register_authentication_function(
+        account: &signer,
+        module_address: address,
+        module_name: String,
+        function_name: String,
+    ) acquires DispatchableAuthenticator {
+        assert!(features::account_abstraction_enabled(), error::invalid_state(EACCOUNT_ABSTRACTION_NOT_ENABLED));
+
+        let account_addr = signer::address_of(account);
+        if (exists<DispatchableAuthenticator>(account_addr)) {
+            move_from<DispatchableAuthenticator>(account_addr);
+        };
+        move_to(account, DispatchableAuthenticator { module_address, module_name, function_name });
+    }
+
+    /// Unregisters the authentication function of `account`. Its transactions can then only be authenticated with the
+    /// authentication key of the account.
+    public entry fun unregister_authentication_function(account: &signer) acquires DispatchableAuthenticator {
+        let account_addr = signer::address_of(account);
+        assert!(
+            exists<DispatchableAuthenticator>(account_addr),
+            error::not_found(EDISPATCHABLE_AUTHENTICATOR_NOT_FOUND),
+        );
+        move_from<DispatchableAuthenticator>(account_addr);
+    }
+
+    #[view]
+    /// Returns whether `account_addr` has an authentication function registered.
+    public fun using_dispatchable_authenticator(account_addr: address): bool {
+        exists<DispatchableAuthenticator>(account_addr)
+    }
+
+    #[view]
+    /// Returns the authentication function registered by `account_addr`, if any.
+    public fun dispatchable_authenticator(
+        account_addr: address,
+    ): Option<DispatchableAuthenticator> acquires DispatchableAuthenticator {
+        if (exists<DispatchableAuthenticator>(account_addr)) {
+            option::some(*borrow_global<DispatchableAuthenticator>(account_addr))
+        } else {
+            option::none()
+        }
+    }
+
+    #[test_only]
+    use std::string;
+
+    #[test(aptos_framework = @aptos_framework, user = @0xcafe)]
+    fun test_register_and_unregister(
+        aptos_framework: &signer,
+        user: &signer,
+    ) acquires DispatchableAuthenticator {
+        features::change_feature_flags(aptos_framework, vector[features::get_account_abstraction_feature()], vector[]);
+        let user_addr = signer::address_of(user);
+        assert!(!using_dispatchable_authenticator(user_addr), 0);
+
+        register_authentication_function(user, @0xcafe, string::utf8(b"auth"), string::utf8(b"authenticate"));
+        assert!(using_dispatchable_authenticator(user_addr), 1);
+        register_authentication_function(user, @0xcafe, string::utf8(b"auth"), string::utf8(b"authenticate_v2"));
+        let authenticator = option::destroy_some(dispatchable_authenticator(user_addr));
+        assert!(authenticator.function_name == string::utf8(b"authenticate_v2"), 2);
+
+        unregister_authentication_function(user);
+        assert!(option::is_none(&dispatchable_authenticator(user_addr)), 3);
+    }
+
+    #[test(user = @0xcafe)]
+    #[expected_failure(abort_code = 0x30001, location = Self)]
+    fun test_register_when_disabled(user: &signer) acquires DispatchableAuthenticator {
+        register_authentication_function(user, @0xcafe, string::utf8(b"auth"), string::utf8(b"authenticate"));
+    }
+
+    #[test(user = @0xcafe)]
+    #[expected_failure(abort_code = 0x60002, location = Self)]
+    fun test_unregister_without_function(user: &signer) acquires DispatchableAuthenticator {
+        unregister_authentication_function(user);
+    }
+}
@@ -5,6 +5,7 @@ module aptos_framework::transaction_validation {
     use std::vector;
 
     use aptos_framework::account;
+    use aptos_framework::account_abstraction;
     use aptos_framework::aptos_coin::AptosCoin;
     use aptos_framework::chain_id;
     use aptos_framework::coin;
@@ -84,10 +85,7 @@ module aptos_framework::transaction_validation {
 
         let transaction_sender = signer::address_of(&sender);
         assert!(account::exists_at(transaction_sender), error::invalid_argument(PROLOGUE_EACCOUNT_DOES_NOT_EXIST));
-        assert!(
-            txn_authentication_key == account::get_authentication_key(transaction_sender),
-            error::invalid_argument(PROLOGUE_EINVALID_ACCOUNT_AUTH_KEY),
-        );
+        check_authentication_key(transaction_sender, txn_authentication_key);
 
         assert!(
             txn_sequence_number < (1u64 << 63),
@@ -184,8 +182,7 @@ module aptos_framework::transaction_validation {
                 invariant i <= num_secondary_signers;
                 invariant forall j in 0..i:
                     account::exists_at(secondary_signer_addresses[j])
-                    && secondary_signer_public_key_hashes[j]
-                       == account::get_authentication_key(secondary_signer_addresses[j]);
+                    && spec_is_authenticated(secondary_signer_addresses[j], secondary_signer_public_key_hashes[j]);
             };
             (i < num_secondary_signers)
         }) {
@@ -193,10 +190,7 @@ module aptos_framework::transaction_validation {
             assert!(account::exists_at(secondary_address), error::invalid_argument(PROLOGUE_EACCOUNT_DOES_NOT_EXIST));
 
             let signer_public_key_hash = *vector::borrow(&secondary_signer_public_key_hashes, i);
-            assert!(
-                signer_public_key_hash == account::get_authentication_key(secondary_address),
-                error::invalid_argument(PROLOGUE_EINVALID_ACCOUNT_AUTH_KEY),
-            );
+            check_authentication_key(secondary_address, signer_public_key_hash);
             i = i + 1;
         }
     }
@@ -226,10 +220,24 @@ module aptos_framework::transaction_validation {
             chain_id,
         );
         multi_agent_common_prologue(secondary_signer_addresses, secondary_signer_public_key_hashes);
-        assert!(
-            fee_payer_public_key_hash == account::get_authentication_key(fee_payer_address),
-            error::invalid_argument(PROLOGUE_EINVALID_ACCOUNT_AUTH_KEY),
-        );
+        check_authentication_key(fee_payer_address, fee_payer_public_key_hash);
+    }
+
+    /// Checks that a signer of the transaction is authenticated for `account`. An empty authentication key means that
+    /// the signer uses account abstraction: the VM has then already run the authentication function registered by the
+    /// account, which must still be registered.
+    fun check_authentication_key(account: address, txn_authentication_key: vector<u8>) {
+        if (vector::is_empty(&txn_authentication_key)) {
+            assert!(
+                account::exists_at(account) && account_abstraction::using_dispatchable_authenticator(account),
+                error::invalid_argument(PROLOGUE_EINVALID_ACCOUNT_AUTH_KEY),
+            );
+        } else {
+            assert!(
+                txn_authentication_key == account::get_authentication_key(account),
+                error::invalid_argument(PROLOGUE_EINVALID_ACCOUNT_AUTH_KEY),
+            );
+        }
     }
 
     /// Epilogue function is run after a transaction is successfully executed.
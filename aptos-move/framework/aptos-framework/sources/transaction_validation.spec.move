@@ -21,6 +21,21 @@ spec aptos_framework::transaction_validation {
         ensures exists<TransactionValidation>(addr);
    }
 
+    /// Whether the signer of `account` is authenticated by `txn_authentication_key`. An empty key stands for a signer
+    /// using account abstraction.
+    spec fun spec_is_authenticated(account: address, txn_authentication_key: vector<u8>): bool {
+        if (len(txn_authentication_key) == 0) {
+            exists<account_abstraction::DispatchableAuthenticator>(account)
+        } else {
+            txn_authentication_key == account::get_authentication_key(account)
+        }
+    }
+
+    spec check_authentication_key(account: address, txn_authentication_key: vector<u8>) {
+        aborts_if !account::exists_at(account);
+        aborts_if !spec_is_authenticated(account, txn_authentication_key);
+    }
+
     /// Create a schema to reuse some code.
     /// Give some constraints that may abort according to the conditions.
     spec schema PrologueCommonAbortsIf {
@@ -45,7 +60,7 @@ spec aptos_framework::transaction_validation {
         let transaction_sender = signer::address_of(sender);
         aborts_if !account::exists_at(transaction_sender);
         aborts_if !(txn_sequence_number >= global<Account>(transaction_sender).sequence_number);
-        aborts_if !(txn_authentication_key == global<Account>(transaction_sender).authentication_key);
+        aborts_if !spec_is_authenticated(transaction_sender, txn_authentication_key);
         aborts_if !(txn_sequence_number < (1u64 << 63));
 
         let max_transaction_fee = txn_gas_price * txn_max_gas_units;
@@ -112,14 +127,12 @@ spec aptos_framework::transaction_validation {
         // property 2: All secondary signer addresses are verified to be authentic through a validation process.
         aborts_if exists i in 0..num_secondary_signers:
             !account::exists_at(secondary_signer_addresses[i])
-                || secondary_signer_public_key_hashes[i] !=
-                account::get_authentication_key(secondary_signer_addresses[i]);
+                || !spec_is_authenticated(secondary_signer_addresses[i], secondary_signer_public_key_hashes[i]);
 
         // By the end, all secondary signers account should exist and public key hash should match.
         ensures forall i in 0..num_secondary_signers:
             account::exists_at(secondary_signer_addresses[i])
-                && secondary_signer_public_key_hashes[i] ==
-                    account::get_authentication_key(secondary_signer_addresses[i]);
+                && spec_is_authenticated(secondary_signer_addresses[i], secondary_signer_public_key_hashes[i]);
     }
 
     spec multi_agent_common_prologue(
@@ -186,7 +199,7 @@ spec aptos_framework::transaction_validation {
         };
 
         aborts_if !account::exists_at(gas_payer);
-        aborts_if !(fee_payer_public_key_hash == account::get_authentication_key(gas_payer));
+        aborts_if !spec_is_authenticated(gas_payer, fee_payer_public_key_hash);
         aborts_if !features::spec_fee_payer_enabled();
     }
 
@@ -237,6 +237,51 @@ module std::features {
         is_enabled(MODULE_EVENT)
     }
 
+    /// Whether transactions can be authenticated with Secp256k1 ECDSA signatures.
+    ///
+    /// Lifetime: transient
+    const SECP256K1_ECDSA_AUTHENTICATOR: u64 = 27;
+
+    public fun get_secp256k1_ecdsa_authenticator_feature(): u64 { SECP256K1_ECDSA_AUTHENTICATOR }
+
+    public fun secp256k1_ecdsa_authenticator_enabled(): bool acquires Features {
+        is_enabled(SECP256K1_ECDSA_AUTHENTICATOR)
+    }
+
+    /// Whether transactions can be authenticated with WebAuthn (passkey) assertions.
+    ///
+    /// Lifetime: transient
+    const WEBAUTHN_AUTHENTICATOR: u64 = 28;
+
+    public fun get_webauthn_authenticator_feature(): u64 { WEBAUTHN_AUTHENTICATOR }
+
+    public fun webauthn_authenticator_enabled(): bool acquires Features {
+        is_enabled(WEBAUTHN_AUTHENTICATOR)
+    }
+
+    /// Whether accounts can register a Move function to authenticate their transactions.
+    ///
+    /// Lifetime: transient
+    const ACCOUNT_ABSTRACTION: u64 = 29;
+
+    public fun get_account_abstraction_feature(): u64 { ACCOUNT_ABSTRACTION }
+
+    public fun account_abstraction_enabled(): bool acquires Features {
+        is_enabled(ACCOUNT_ABSTRACTION)
+    }
+
+    /// Whether modules can be published with summaries of the resources their entry functions
+    /// access, which are used as hints when scheduling transactions.
+    ///
+    /// Lifetime: transient
+    const ACCESS_SUMMARY_METADATA: u64 = 30;
+
+    public fun get_access_summary_metadata_feature(): u64 { ACCESS_SUMMARY_METADATA }
+
+    public fun access_summary_metadata_enabled(): bool acquires Features {
+        is_enabled(ACCESS_SUMMARY_METADATA)
+    }
+
     // ============================================================================================
     // Feature Flag Implementation
 
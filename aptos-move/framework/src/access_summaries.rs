@@ -0,0 +1,428 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Derives, for every entry function of the target modules, a conservative summary of the
+//! resources it may read and write. Global storage operations are collected over all the
+//! transitively called functions, tracking whether the address they operate on is a signer, an
+//! `address` argument or a constant of the entry function. Any other address is summarized by
+//! a wildcard. Functions whose accesses can't be bounded, e.g. because they are recursive or call
+//! natives operating on storage (tables, aggregators, ...), get no summary.
+
+use crate::extended_checks::{RESOURCE_GROUP_MEMBER, RESOURCE_GROUP_NAME};
+use aptos_types::transaction::access_summary::{
+    AccessAddress, AccessStructTag, AccessSummary, AccessType, ResourceAccess,
+};
+use move_core_types::{account_address::AccountAddress, language_storage::ModuleId};
+use move_model::{
+    ast::{Address, Attribute, AttributeValue},
+    model::{FunId, FunctionEnv, GlobalEnv, Parameter, QualifiedId, StructEnv},
+    ty::{PrimitiveType, ReferenceKind, Type},
+};
+use move_stackless_bytecode::{
+    function_target::{FunctionData, FunctionTarget},
+    stackless_bytecode::{Bytecode, Constant, Operation},
+    stackless_bytecode_generator::StacklessBytecodeGenerator,
+};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    rc::Rc,
+};
+
+/// Modules of the framework with natives reading or writing global storage, besides
+/// `object::exists_at` which is summarized like `exists`.
+const STORAGE_NATIVE_MODULES: [&str; 6] = [
+    "aggregator",
+    "aggregator_factory",
+    "aggregator_v2",
+    "code",
+    "object",
+    "table",
+];
+const OBJECT_EXISTS_AT: &str = "0x1::object::exists_at";
+
+/// Maximal depth of the analyzed call chains.
+const MAX_CALL_DEPTH: usize = 64;
+
+/// Returns the summaries of the entry functions of the target modules in the environment, by
+/// module and function name. This is invoked after general build succeeds.
+pub fn derive_access_summaries(
+    env: &GlobalEnv,
+) -> BTreeMap<ModuleId, BTreeMap<String, AccessSummary>> {
+    let mut analyzer = AccessAnalyzer::new(env);
+    let mut output = BTreeMap::new();
+    for module in env.get_modules() {
+        if !module.is_target() {
+            continue;
+        }
+        let module_id = match module.get_identifier() {
+            Some(name) => ModuleId::new(module.get_name().addr().expect_numerical(), name),
+            None => continue,
+        };
+        let mut summaries = BTreeMap::new();
+        for fun in module.get_functions() {
+            if !fun.is_entry() || fun.is_native() {
+                continue;
+            }
+            if let Some(summary) = analyzer.summarize_entry_function(&fun) {
+                summaries.insert(fun.get_name_str(), summary);
+            }
+        }
+        if !summaries.is_empty() {
+            output.insert(module_id, summaries);
+        }
+    }
+    output
+}
+
+/// A resource, instantiated in terms of the type parameters of the entry function, and the
+/// address it is accessed at.
+type Access = (Type, AccessAddress);
+
+#[derive(Clone, Default)]
+struct Accesses {
+    reads: BTreeSet<Access>,
+    writes: BTreeSet<Access>,
+}
+
+impl Accesses {
+    fn extend(&mut self, other: &Accesses) {
+        self.reads.extend(other.reads.iter().cloned());
+        self.writes.extend(other.writes.iter().cloned());
+    }
+}
+
+/// A function, its type instantiation and the addresses passed as its parameters, if known.
+type CallContext = (QualifiedId<FunId>, Vec<Type>, Vec<Option<AccessAddress>>);
+
+struct AccessAnalyzer<'a> {
+    env: &'a GlobalEnv,
+    /// Stackless bytecode of the analyzed functions.
+    function_data: BTreeMap<QualifiedId<FunId>, Rc<FunctionData>>,
+    /// Accesses of the analyzed calls, `None` if they can't be bounded.
+    calls: BTreeMap<CallContext, Option<Rc<Accesses>>>,
+    /// Calls being analyzed.
+    call_stack: Vec<CallContext>,
+}
+
+impl<'a> AccessAnalyzer<'a> {
+    fn new(env: &'a GlobalEnv) -> Self {
+        Self {
+            env,
+            function_data: BTreeMap::new(),
+            calls: BTreeMap::new(),
+            call_stack: vec![],
+        }
+    }
+
+    fn summarize_entry_function(&mut self, fun: &FunctionEnv) -> Option<AccessSummary> {
+        let mut num_signers = 0;
+        let mut num_args = 0;
+        let params = fun
+            .get_parameters()
+            .into_iter()
+            .map(|Parameter(_, ty)| {
+                if is_signer(&ty) {
+                    num_signers += 1;
+                    Some(AccessAddress::Signer(num_signers - 1))
+                } else {
+                    num_args += 1;
+                    (ty == Type::Primitive(PrimitiveType::Address))
+                        .then_some(AccessAddress::Argument(num_args - 1))
+                }
+            })
+            .collect();
+        let type_params = (0..fun.get_type_parameter_count() as u16)
+            .map(Type::TypeParameter)
+            .collect();
+        let accesses = self.analyze_call((fun.get_qualified_id(), type_params, params))?;
+
+        let mut summary = AccessSummary::default();
+        for (ty, address) in &accesses.reads {
+            summary.reads.insert(self.resource_access(ty, address)?);
+        }
+        for (ty, address) in &accesses.writes {
+            summary.writes.insert(self.resource_access(ty, address)?);
+        }
+        Some(summary)
+    }
+
+    fn analyze_call(&mut self, call: CallContext) -> Option<Rc<Accesses>> {
+        if let Some(accesses) = self.calls.get(&call) {
+            return accesses.clone();
+        }
+        // Recursive calls may access an unbounded number of addresses.
+        if self.call_stack.len() >= MAX_CALL_DEPTH || self.call_stack.contains(&call) {
+            return None;
+        }
+        self.call_stack.push(call.clone());
+        let accesses = self.analyze_function(&call).map(Rc::new);
+        self.call_stack.pop();
+        self.calls.insert(call, accesses.clone());
+        accesses
+    }
+
+    fn analyze_function(&mut self, call: &CallContext) -> Option<Accesses> {
+        let (fun_id, type_inst, params) = call;
+        let env = self.env;
+        let fun = env.get_function(*fun_id);
+        if fun.is_native() {
+            let module = &fun.module_env;
+            let accesses_storage = module.self_address()
+                == &Address::Numerical(AccountAddress::ONE)
+                && module
+                    .get_identifier()
+                    .map_or(true, |name| STORAGE_NATIVE_MODULES.contains(&name.as_str()));
+            return (!accesses_storage).then(Accesses::default);
+        }
+
+        let data = self.get_function_data(&fun);
+        let target = FunctionTarget::new(&fun, &data);
+        let addresses = local_addresses(&target, params);
+        let address_of = |temp: usize| addresses[temp].clone().unwrap_or(AccessAddress::Any);
+        let resource =
+            |mid, sid, inst: &[Type]| Type::Struct(mid, sid, inst.to_vec()).instantiate(type_inst);
+
+        let mut accesses = Accesses::default();
+        for bc in target.get_bytecode() {
+            if let Bytecode::Call(_, dests, op, srcs, _) = bc {
+                match op {
+                    Operation::MoveTo(mid, sid, inst) => {
+                        accesses
+                            .writes
+                            .insert((resource(*mid, *sid, inst), address_of(srcs[1])));
+                    },
+                    Operation::MoveFrom(mid, sid, inst) => {
+                        accesses
+                            .writes
+                            .insert((resource(*mid, *sid, inst), address_of(srcs[0])));
+                    },
+                    Operation::BorrowGlobal(mid, sid, inst) => {
+                        let access = (resource(*mid, *sid, inst), address_of(srcs[0]));
+                        if matches!(
+                            target.get_local_type(dests[0]),
+                            Type::Reference(ReferenceKind::Mutable, _)
+                        ) {
+                            accesses.writes.insert(access);
+                        } else {
+                            accesses.reads.insert(access);
+                        }
+                    },
+                    Operation::Exists(mid, sid, inst) | Operation::GetGlobal(mid, sid, inst) => {
+                        accesses
+                            .reads
+                            .insert((resource(*mid, *sid, inst), address_of(srcs[0])));
+                    },
+                    Operation::Function(mid, fid, inst)
+                        if env
+                            .get_function(mid.qualified(*fid))
+                            .get_full_name_with_address()
+                            == OBJECT_EXISTS_AT =>
+                    {
+                        accesses
+                            .reads
+                            .insert((inst[0].instantiate(type_inst), address_of(srcs[0])));
+                    },
+                    Operation::Function(mid, fid, inst) => {
+                        let callee = (
+                            mid.qualified(*fid),
+                            inst.iter().map(|ty| ty.instantiate(type_inst)).collect(),
+                            srcs.iter().map(|temp| addresses[*temp].clone()).collect(),
+                        );
+                        accesses.extend(&*self.analyze_call(callee)?);
+                    },
+                    _ => {},
+                }
+            }
+        }
+        Some(accesses)
+    }
+
+    fn get_function_data(&mut self, fun: &FunctionEnv) -> Rc<FunctionData> {
+        self.function_data
+            .entry(fun.get_qualified_id())
+            .or_insert_with(|| Rc::new(StacklessBytecodeGenerator::new(fun).generate_function()))
+            .clone()
+    }
+
+    fn resource_access(&self, ty: &Type, address: &AccessAddress) -> Option<ResourceAccess> {
+        let (mid, sid) = match ty {
+            Type::Struct(mid, sid, _) => (*mid, *sid),
+            _ => return None,
+        };
+        let struct_env = self.env.get_struct(mid.qualified(sid));
+        Some(match self.resource_group(&struct_env) {
+            Some(group) => ResourceAccess {
+                resource: self.struct_tag(&Type::Struct(group.0, group.1, vec![]))?,
+                resource_group: true,
+                address: address.clone(),
+            },
+            None => ResourceAccess {
+                resource: self.struct_tag(ty)?,
+                resource_group: false,
+                address: address.clone(),
+            },
+        })
+    }
+
+    /// Returns the group of a resource group member, which is where it is stored.
+    fn resource_group(
+        &self,
+        struct_env: &StructEnv,
+    ) -> Option<(move_model::model::ModuleId, move_model::model::StructId)> {
+        let attributes = struct_env
+            .get_attributes()
+            .iter()
+            .find_map(|attr| match attr {
+                Attribute::Apply(_, name, attributes)
+                    if self.env.symbol_pool().string(*name).as_str() == RESOURCE_GROUP_MEMBER =>
+                {
+                    Some(attributes)
+                },
+                _ => None,
+            })?;
+        let (module_name, container_name) = attributes.iter().find_map(|attr| match attr {
+            Attribute::Assign(_, name, AttributeValue::Name(_, Some(module), container))
+                if self.env.symbol_pool().string(*name).as_str() == RESOURCE_GROUP_NAME =>
+            {
+                Some((module, container))
+            },
+            _ => None,
+        })?;
+        let container = self
+            .env
+            .find_module(module_name)?
+            .find_struct(*container_name)?;
+        Some((container.module_env.get_id(), container.get_id()))
+    }
+
+    fn struct_tag(&self, ty: &Type) -> Option<AccessStructTag> {
+        match self.access_type(ty)? {
+            AccessType::Struct(tag) => Some(tag),
+            _ => None,
+        }
+    }
+
+    fn access_type(&self, ty: &Type) -> Option<AccessType> {
+        Some(match ty {
+            Type::Primitive(PrimitiveType::Bool) => AccessType::Bool,
+            Type::Primitive(PrimitiveType::U8) => AccessType::U8,
+            Type::Primitive(PrimitiveType::U16) => AccessType::U16,
+            Type::Primitive(PrimitiveType::U32) => AccessType::U32,
+            Type::Primitive(PrimitiveType::U64) => AccessType::U64,
+            Type::Primitive(PrimitiveType::U128) => AccessType::U128,
+            Type::Primitive(PrimitiveType::U256) => AccessType::U256,
+            Type::Primitive(PrimitiveType::Address) => AccessType::Address,
+            Type::Primitive(PrimitiveType::Signer) => AccessType::Signer,
+            Type::Vector(ty) => AccessType::Vector(Box::new(self.access_type(ty)?)),
+            Type::Struct(mid, sid, inst) => {
+                let struct_env = self.env.get_struct(mid.qualified(*sid));
+                let module_env = &struct_env.module_env;
+                AccessType::Struct(AccessStructTag {
+                    address: module_env.get_name().addr().expect_numerical(),
+                    module: module_env.get_identifier()?,
+                    name: struct_env.get_identifier()?,
+                    type_args: inst
+                        .iter()
+                        .map(|ty| self.access_type(ty))
+                        .collect::<Option<_>>()?,
+                })
+            },
+            Type::TypeParameter(idx) => AccessType::TypeParameter(*idx),
+            _ => return None,
+        })
+    }
+}
+
+pub(crate) fn is_signer(ty: &Type) -> bool {
+    match ty {
+        Type::Primitive(PrimitiveType::Signer) => true,
+        Type::Reference(_, ty) => matches!(**ty, Type::Primitive(PrimitiveType::Signer)),
+        _ => false,
+    }
+}
+
+fn join(current: &mut Option<AccessAddress>, value: Option<AccessAddress>) -> bool {
+    let joined = match (&*current, value) {
+        (_, None) => return false,
+        (None, value) => value,
+        (Some(current), Some(value)) if *current == value => return false,
+        (Some(AccessAddress::Any), _) => return false,
+        (Some(_), Some(_)) => Some(AccessAddress::Any),
+    };
+    *current = joined;
+    true
+}
+
+/// Computes the address held by each local (or referenced by it), given the addresses passed as
+/// parameters. The analysis is flow insensitive: a local assigned different addresses, or one
+/// which can't be tracked, holds `AccessAddress::Any`. Locals which are never assigned an address
+/// are `None`.
+fn local_addresses(
+    target: &FunctionTarget,
+    params: &[Option<AccessAddress>],
+) -> Vec<Option<AccessAddress>> {
+    let mut addresses = vec![None; target.get_local_count()];
+    for (idx, param) in params.iter().enumerate() {
+        addresses[idx] = param.clone();
+    }
+    let any = Some(AccessAddress::Any);
+    // Locals borrowed mutably may be written through the reference, here or in a callee.
+    for bc in target.get_bytecode() {
+        if let Bytecode::Call(_, dests, Operation::BorrowLoc, srcs, _) = bc {
+            if matches!(
+                target.get_local_type(dests[0]),
+                Type::Reference(ReferenceKind::Mutable, _)
+            ) {
+                addresses[srcs[0]] = any.clone();
+            }
+        }
+    }
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for bc in target.get_bytecode() {
+            match bc {
+                Bytecode::Assign(_, dest, src, _) => {
+                    let value = addresses[*src].clone();
+                    changed |= join(&mut addresses[*dest], value);
+                },
+                Bytecode::Load(_, dest, constant) => {
+                    let value = match constant {
+                        Constant::Address(Address::Numerical(address)) => {
+                            Some(AccessAddress::Constant(*address))
+                        },
+                        _ => any.clone(),
+                    };
+                    changed |= join(&mut addresses[*dest], value);
+                },
+                Bytecode::Call(_, dests, op, srcs, _) => {
+                    let value = match op {
+                        Operation::BorrowLoc | Operation::ReadRef | Operation::FreezeRef => {
+                            addresses[srcs[0]].clone()
+                        },
+                        Operation::Function(mid, fid, _)
+                            if is_signer_address_function(target, mid.qualified(*fid)) =>
+                        {
+                            addresses[srcs[0]].clone()
+                        },
+                        _ => any.clone(),
+                    };
+                    for dest in dests {
+                        changed |= join(&mut addresses[*dest], value.clone());
+                    }
+                },
+                _ => {},
+            }
+        }
+    }
+    addresses
+}
+
+/// Whether the function returns the address of the signer it is passed.
+fn is_signer_address_function(target: &FunctionTarget, fun_id: QualifiedId<FunId>) -> bool {
+    let name = target
+        .global_env()
+        .get_function(fun_id)
+        .get_full_name_with_address();
+    name == "0x1::signer::address_of" || name == "0x1::signer::borrow_address"
+}
@@ -105,9 +105,11 @@ impl ReleaseTarget {
                 with_abis: true,
                 with_source_maps: false,
                 with_error_map: true,
+                with_access_summaries: false,
                 named_addresses: Default::default(),
                 install_dir: None,
                 with_docs: true,
+                lint: false,
                 docgen_options: Some(DocgenOptions {
                     include_impl: true,
                     include_specs: true,
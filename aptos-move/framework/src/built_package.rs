@@ -2,14 +2,19 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    access_summaries,
     docgen::DocgenOptions,
     extended_checks,
+    lint::{self, LintConfig},
     natives::code::{ModuleMetadata, MoveOption, PackageDep, PackageMetadata, UpgradePolicy},
-    zip_metadata, zip_metadata_str, RuntimeModuleMetadataV1, APTOS_METADATA_KEY,
-    APTOS_METADATA_KEY_V1, METADATA_V1_MIN_FILE_FORMAT_VERSION,
+    zip_metadata, zip_metadata_str, RuntimeModuleMetadataV1, APTOS_ACCESS_SUMMARY_KEY,
+    APTOS_METADATA_KEY, APTOS_METADATA_KEY_V1, METADATA_V1_MIN_FILE_FORMAT_VERSION,
 };
 use anyhow::bail;
-use aptos_types::{account_address::AccountAddress, transaction::EntryABI};
+use aptos_types::{
+    account_address::AccountAddress,
+    transaction::{access_summary::AccessSummary, EntryABI},
+};
 use clap::Parser;
 use codespan_reporting::{
     diagnostic::Severity,
@@ -55,8 +60,15 @@ pub struct BuildOptions {
     pub with_source_maps: bool,
     #[clap(long, default_value_t = true)]
     pub with_error_map: bool,
+    /// Publish summaries of the resources accessed by the entry functions, used as hints when
+    /// scheduling transactions. Requires the access summary metadata feature to be enabled.
+    #[clap(long)]
+    pub with_access_summaries: bool,
     #[clap(long)]
     pub with_docs: bool,
+    /// Run the lints of the package, with the levels set in the `[lint]` section of its manifest.
+    #[clap(long)]
+    pub lint: bool,
     /// Installation directory for compiled artifacts. Defaults to `<package>/build`.
     #[clap(long, value_parser)]
     pub install_dir: Option<PathBuf>,
@@ -84,7 +96,9 @@ impl Default for BuildOptions {
             with_abis: false,
             with_source_maps: false,
             with_error_map: true,
+            with_access_summaries: false,
             with_docs: false,
+            lint: false,
             install_dir: None,
             named_addresses: Default::default(),
             docgen_options: None,
@@ -175,6 +189,9 @@ impl BuiltPackage {
             options.known_attributes.clone(),
         )?;
         let runtime_metadata = extended_checks::run_extended_checks(model);
+        if options.lint {
+            lint::run_lints(model, &LintConfig::from_package(&package_path)?);
+        }
         if model.diag_count(Severity::Warning) > 0 {
             let mut error_writer = StandardStream::stderr(ColorChoice::Auto);
             model.report_diag(&mut error_writer, Severity::Warning);
@@ -190,6 +207,17 @@ impl BuiltPackage {
             runtime_metadata,
             bytecode_version,
         )?;
+        if options.with_access_summaries {
+            let access_summaries = access_summaries::derive_access_summaries(model);
+            inject_access_summaries(
+                package_path
+                    .join(CompiledPackageLayout::Root.path())
+                    .join(package.compiled_package_info.package_name.as_str()),
+                &mut package,
+                access_summaries,
+                bytecode_version,
+            )?;
+        }
 
         // If enabled generate docs.
         if options.with_docs {
@@ -383,6 +411,35 @@ fn extract_custom_fields(toml: &str) -> anyhow::Result<BTreeMap<String, String>>
         .collect())
 }
 
+fn inject_access_summaries(
+    package_path: PathBuf,
+    pack: &mut CompiledPackage,
+    access_summaries: BTreeMap<ModuleId, BTreeMap<String, AccessSummary>>,
+    bytecode_version: Option<u32>,
+) -> anyhow::Result<()> {
+    for unit_with_source in pack.root_compiled_units.iter_mut() {
+        if let CompiledUnit::Module(named_module) = &mut unit_with_source.unit {
+            if let Some(summaries) = access_summaries.get(&named_module.module.self_id()) {
+                named_module.module.metadata.push(Metadata {
+                    key: APTOS_ACCESS_SUMMARY_KEY.to_vec(),
+                    value: bcs::to_bytes(summaries).expect("BCS for AccessSummary"),
+                });
+
+                // Also need to update the .mv file on disk.
+                let path = package_path
+                    .join(CompiledPackageLayout::CompiledModules.path())
+                    .join(named_module.name.as_str())
+                    .with_extension(MOVE_COMPILED_EXTENSION);
+                if path.is_file() {
+                    let bytes = unit_with_source.unit.serialize(bytecode_version);
+                    std::fs::write(path, bytes)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
 fn inject_runtime_metadata(
     package_path: PathBuf,
     pack: &mut CompiledPackage,
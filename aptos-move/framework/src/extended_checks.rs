@@ -37,10 +37,10 @@ const LEGACY_ENTRY_FUN_ATTRIBUTE: &str = "legacy_entry_fun";
 const ERROR_PREFIX: &str = "E";
 const EVENT_STRUCT_ATTRIBUTE: &str = "event";
 const RESOURCE_GROUP: &str = "resource_group";
-const RESOURCE_GROUP_MEMBER: &str = "resource_group_member";
-const RESOURCE_GROUP_NAME: &str = "group";
+pub(crate) const RESOURCE_GROUP_MEMBER: &str = "resource_group_member";
+pub(crate) const RESOURCE_GROUP_NAME: &str = "group";
 const RESOURCE_GROUP_SCOPE: &str = "scope";
-const VIEW_FUN_ATTRIBUTE: &str = "view";
+pub(crate) const VIEW_FUN_ATTRIBUTE: &str = "view";
 
 // top-level attribute names, only.
 pub fn get_all_attribute_names() -> &'static BTreeSet<String> {
@@ -207,19 +207,23 @@ impl<'a> ExtendedChecker<'a> {
     }
 
     fn is_allowed_input_struct(&self, qid: QualifiedId<StructId>) -> bool {
-        let name = self.env.get_struct(qid).get_full_name_with_address();
-        // TODO(gerben) find a nice way to keep this in sync with allowed_structs in aptos-vm
-        matches!(
-            name.as_str(),
-            "0x1::string::String"
-                | "0x1::object::Object"
-                | "0x1::option::Option"
-                | "0x1::fixed_point32::FixedPoint32"
-                | "0x1::fixed_point64::FixedPoint64"
-        )
+        is_allowed_input_struct_name(&self.env.get_struct(qid).get_full_name_with_address())
     }
 }
 
+/// Returns true if structs with the given full name can be passed as transaction arguments.
+pub(crate) fn is_allowed_input_struct_name(name: &str) -> bool {
+    // TODO(gerben) find a nice way to keep this in sync with allowed_structs in aptos-vm
+    matches!(
+        name,
+        "0x1::string::String"
+            | "0x1::object::Object"
+            | "0x1::option::Option"
+            | "0x1::fixed_point32::FixedPoint32"
+            | "0x1::fixed_point64::FixedPoint64"
+    )
+}
+
 // ----------------------------------------------------------------------------------
 // Resource Group Functions
 
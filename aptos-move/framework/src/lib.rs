@@ -18,9 +18,11 @@ pub use module_metadata::*;
 pub mod natives;
 mod release_builder;
 pub use release_builder::*;
+pub mod access_summaries;
 pub mod docgen;
 pub mod extended_checks;
 pub use extended_checks::ResourceGroupScope;
+pub mod lint;
 pub mod prover;
 mod release_bundle;
 mod released_framework;
@@ -0,0 +1,555 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Lints over the model of a package, flagging code which compiles but is likely to be a bug or
+//! to miss a convention of Aptos Move. Lints are reported as warnings, unless their level is set
+//! otherwise in the `[lint]` section of the package manifest:
+//!
+//! ```toml
+//! [lint]
+//! unchecked_coin_arithmetic = "error"
+//! missing_view = "allow"
+//! ```
+
+use crate::{
+    access_summaries::is_signer,
+    extended_checks::{is_allowed_input_struct_name, VIEW_FUN_ATTRIBUTE},
+};
+use anyhow::{anyhow, bail};
+use codespan_reporting::diagnostic::Severity;
+use move_binary_format::file_format::Visibility;
+use move_core_types::account_address::AccountAddress;
+use move_model::{
+    ast::{Address, Attribute},
+    model::{FunId, FunctionEnv, GlobalEnv, Loc, ModuleEnv, Parameter, QualifiedId},
+    ty::{PrimitiveType, ReferenceKind, Type},
+};
+use move_package::source_package::{
+    layout::SourcePackageLayout, manifest_parser::parse_move_manifest_string,
+};
+use move_stackless_bytecode::{
+    function_target::FunctionTarget,
+    stackless_bytecode::{Bytecode, Operation},
+    stackless_bytecode_generator::StacklessBytecodeGenerator,
+};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    path::Path,
+    str::FromStr,
+};
+
+/// Section of the package manifest setting the levels of the lints.
+pub const LINT_MANIFEST_SECTION: &str = "lint";
+
+/// Framework modules whose functions move coins or fungible assets around.
+const COIN_MODULES: [&str; 5] = [
+    "aptos_account",
+    "aptos_coin",
+    "coin",
+    "fungible_asset",
+    "primary_fungible_store",
+];
+/// Framework modules with natives changing the state of the transaction, besides global storage.
+const STATE_CHANGING_NATIVE_MODULES: [&str; 2] = ["event", "transaction_context"];
+/// Framework modules with natives reading global storage, e.g. `object::exists_at`.
+const STATE_READING_NATIVE_MODULES: [&str; 1] = ["object"];
+/// Framework structs granting control over objects and fungible assets.
+const CAPABILITY_STRUCTS: [&str; 8] = [
+    "0x1::object::ConstructorRef",
+    "0x1::object::DeleteRef",
+    "0x1::object::DeriveRef",
+    "0x1::object::ExtendRef",
+    "0x1::object::TransferRef",
+    "0x1::fungible_asset::BurnRef",
+    "0x1::fungible_asset::MintRef",
+    "0x1::fungible_asset::TransferRef",
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Lint {
+    /// A `u64` product is divided, e.g. `amount * numerator / denominator`, in a function
+    /// handling coins: the product may overflow although the quotient fits.
+    UncheckedCoinArithmetic,
+    /// A public function returns a signer or a capability without taking a signer, so that any
+    /// module can obtain it.
+    PublicCapabilityReturn,
+    /// A public function only reads global state but can't be called with the view API.
+    MissingView,
+    /// An entry function takes a signer which it doesn't use.
+    UnusedSigner,
+}
+
+impl Lint {
+    pub const ALL: [Lint; 4] = [
+        Lint::UncheckedCoinArithmetic,
+        Lint::PublicCapabilityReturn,
+        Lint::MissingView,
+        Lint::UnusedSigner,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Lint::UncheckedCoinArithmetic => "unchecked_coin_arithmetic",
+            Lint::PublicCapabilityReturn => "public_capability_return",
+            Lint::MissingView => "missing_view",
+            Lint::UnusedSigner => "unused_signer",
+        }
+    }
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Lint {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Lint::ALL
+            .into_iter()
+            .find(|lint| lint.name() == s)
+            .ok_or_else(|| {
+                anyhow!(
+                    "unknown lint `{}`, expected one of {}",
+                    s,
+                    Lint::ALL
+                        .iter()
+                        .map(|lint| format!("`{}`", lint))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LintLevel {
+    Allow,
+    Warning,
+    Error,
+}
+
+impl FromStr for LintLevel {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "allow" => LintLevel::Allow,
+            "warning" => LintLevel::Warning,
+            "error" => LintLevel::Error,
+            _ => bail!(
+                "unknown lint level `{}`, expected `allow`, `warning` or `error`",
+                s
+            ),
+        })
+    }
+}
+
+/// Levels of the lints, as set in the package manifest.
+#[derive(Clone, Debug, Default)]
+pub struct LintConfig {
+    levels: BTreeMap<Lint, LintLevel>,
+}
+
+impl LintConfig {
+    /// Reads the `[lint]` section of the manifest of the package at the given path.
+    pub fn from_package(package_path: &Path) -> anyhow::Result<Self> {
+        let manifest =
+            std::fs::read_to_string(package_path.join(SourcePackageLayout::Manifest.path()))?;
+        Self::from_manifest(&manifest)
+    }
+
+    pub fn from_manifest(manifest: &str) -> anyhow::Result<Self> {
+        let manifest = parse_move_manifest_string(manifest.to_owned())?;
+        let mut config = Self::default();
+        if let Some(section) = manifest.get(LINT_MANIFEST_SECTION) {
+            let section = section.as_table().ok_or_else(|| {
+                anyhow!(
+                    "`[{}]` section of the manifest must be a table",
+                    LINT_MANIFEST_SECTION
+                )
+            })?;
+            for (name, level) in section {
+                let level = level
+                    .as_str()
+                    .ok_or_else(|| anyhow!("level of lint `{}` must be a string", name))?;
+                config
+                    .levels
+                    .insert(name.parse::<Lint>()?, level.parse::<LintLevel>()?);
+            }
+        }
+        Ok(config)
+    }
+
+    pub fn level(&self, lint: Lint) -> LintLevel {
+        self.levels
+            .get(&lint)
+            .copied()
+            .unwrap_or(LintLevel::Warning)
+    }
+}
+
+/// Runs the lints on the target modules in the environment. Lints are reported to `env` as
+/// warnings or errors, depending on their level.
+pub fn run_lints(env: &GlobalEnv, config: &LintConfig) {
+    let mut linter = Linter::new(env, config);
+    for module in env.get_modules() {
+        if !module.is_target() {
+            continue;
+        }
+        for fun in module.get_functions() {
+            if fun.is_inline() || fun.is_native() {
+                continue;
+            }
+            linter.lint_function(&fun);
+        }
+    }
+}
+
+/// Whether a function, or any function it calls, reads or writes global state.
+#[derive(Clone, Copy, Default)]
+struct StateAccess {
+    reads: bool,
+    writes: bool,
+}
+
+struct Linter<'a> {
+    env: &'a GlobalEnv,
+    config: &'a LintConfig,
+    /// State accesses of the analyzed functions.
+    state_accesses: BTreeMap<QualifiedId<FunId>, StateAccess>,
+}
+
+impl<'a> Linter<'a> {
+    fn new(env: &'a GlobalEnv, config: &'a LintConfig) -> Self {
+        Self {
+            env,
+            config,
+            state_accesses: BTreeMap::new(),
+        }
+    }
+
+    fn lint_function(&mut self, fun: &FunctionEnv) {
+        let data = StacklessBytecodeGenerator::new(fun).generate_function();
+        let target = FunctionTarget::new(fun, &data);
+        if self.is_enabled(Lint::UncheckedCoinArithmetic) {
+            self.check_coin_arithmetic(&target);
+        }
+        if self.is_enabled(Lint::PublicCapabilityReturn) {
+            self.check_capability_return(fun);
+        }
+        if self.is_enabled(Lint::MissingView) {
+            self.check_missing_view(fun, &target);
+        }
+        if self.is_enabled(Lint::UnusedSigner) {
+            self.check_unused_signers(fun, &target);
+        }
+    }
+
+    fn is_enabled(&self, lint: Lint) -> bool {
+        self.config.level(lint) != LintLevel::Allow
+    }
+
+    fn report(&self, lint: Lint, loc: &Loc, msg: &str) {
+        let severity = match self.config.level(lint) {
+            LintLevel::Allow => return,
+            LintLevel::Warning => Severity::Warning,
+            LintLevel::Error => Severity::Error,
+        };
+        self.env.diag_with_notes(severity, loc, msg, vec![format!(
+            "lint `{}`, its level can be set in the `[{}]` section of Move.toml",
+            lint, LINT_MANIFEST_SECTION
+        )]);
+    }
+}
+
+// ----------------------------------------------------------------------------------
+// Coin Arithmetic
+
+impl<'a> Linter<'a> {
+    fn check_coin_arithmetic(&self, target: &FunctionTarget) {
+        let code = target.get_bytecode();
+        let handles_coins = code.iter().any(|bc| {
+            matches!(bc, Bytecode::Call(_, _, Operation::Function(mid, _, _), _, _)
+                if is_framework_module(&self.env.get_module(*mid), &COIN_MODULES))
+        });
+        if !handles_coins {
+            return;
+        }
+
+        // Locals holding the product of a `u64` multiplication
+        let mut products = BTreeSet::new();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for bc in code {
+                match bc {
+                    Bytecode::Call(_, dests, Operation::Mul, _, _)
+                        if target.get_local_type(dests[0])
+                            == &Type::Primitive(PrimitiveType::U64) =>
+                    {
+                        changed |= products.insert(dests[0]);
+                    },
+                    Bytecode::Assign(_, dest, src, _) if products.contains(src) => {
+                        changed |= products.insert(*dest);
+                    },
+                    _ => {},
+                }
+            }
+        }
+        for bc in code {
+            if let Bytecode::Call(attr_id, _, Operation::Div, srcs, _) = bc {
+                if products.contains(&srcs[0]) {
+                    self.report(
+                        Lint::UncheckedCoinArithmetic,
+                        &target.get_bytecode_loc(*attr_id),
+                        "the `u64` product divided here may overflow although the quotient \
+                         fits; compute it as `u128` or with `math64::mul_div`",
+                    );
+                }
+            }
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------------
+// Capabilities
+
+impl<'a> Linter<'a> {
+    fn check_capability_return(&self, fun: &FunctionEnv) {
+        // Functions taking a signer return capabilities to authorized callers only
+        if fun.visibility() != Visibility::Public || fun.get_parameter_types().iter().any(is_signer)
+        {
+            return;
+        }
+        for ty in fun.get_result_type().flatten() {
+            if self.is_capability(&ty) {
+                self.report(
+                    Lint::PublicCapabilityReturn,
+                    &fun.get_loc(),
+                    &format!(
+                        "public function `{}` returns `{}` without taking a signer, any module \
+                         can call it to obtain the capability",
+                        fun.get_simple_name_string(),
+                        ty.display(&self.env.get_type_display_ctx())
+                    ),
+                );
+            }
+        }
+    }
+
+    fn is_capability(&self, ty: &Type) -> bool {
+        match ty {
+            Type::Primitive(PrimitiveType::Signer) => true,
+            Type::Reference(_, ty) | Type::Vector(ty) => self.is_capability(ty),
+            Type::Struct(mid, sid, inst) => {
+                let struct_env = self.env.get_struct(mid.qualified(*sid));
+                let full_name = struct_env.get_full_name_with_address();
+                let name = self.env.symbol_pool().string(struct_env.get_name());
+                CAPABILITY_STRUCTS.contains(&full_name.as_str())
+                    || name.ends_with("Capability")
+                    || name.ends_with("Cap")
+                    || (full_name == "0x1::option::Option" && self.is_capability(&inst[0]))
+            },
+            _ => false,
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------------
+// View Functions
+
+impl<'a> Linter<'a> {
+    fn check_missing_view(&mut self, fun: &FunctionEnv, target: &FunctionTarget) {
+        if fun.visibility() != Visibility::Public
+            || fun.is_entry()
+            || self.has_attribute(fun, VIEW_FUN_ATTRIBUTE)
+        {
+            return;
+        }
+        let results = fun.get_result_type().flatten();
+        if results.is_empty()
+            || results.iter().any(|ty| ty.is_reference())
+            || !fun
+                .get_parameter_types()
+                .iter()
+                .all(|ty| self.is_view_argument(ty))
+        {
+            return;
+        }
+        let access = self.body_state_access(target);
+        if access.reads && !access.writes {
+            self.report(
+                Lint::MissingView,
+                &fun.get_loc(),
+                &format!(
+                    "public function `{}` only reads global state, mark it `#[view]` so that it \
+                     can be called through the view API",
+                    fun.get_simple_name_string()
+                ),
+            );
+        }
+    }
+
+    fn is_view_argument(&self, ty: &Type) -> bool {
+        match ty {
+            Type::Primitive(PrimitiveType::Signer) => false,
+            Type::Primitive(_) | Type::TypeParameter(_) => true,
+            Type::Vector(ty) => self.is_view_argument(ty),
+            Type::Struct(mid, sid, _) => is_allowed_input_struct_name(
+                &self
+                    .env
+                    .get_struct(mid.qualified(*sid))
+                    .get_full_name_with_address(),
+            ),
+            _ => false,
+        }
+    }
+
+    fn state_access(&mut self, fun: &FunctionEnv) -> StateAccess {
+        let id = fun.get_qualified_id();
+        if let Some(access) = self.state_accesses.get(&id) {
+            return *access;
+        }
+        // Recursive calls add no accesses to the ones of the function being analyzed
+        self.state_accesses.insert(id, StateAccess::default());
+        let access = if fun.is_native() {
+            StateAccess {
+                reads: is_framework_module(&fun.module_env, &STATE_READING_NATIVE_MODULES),
+                writes: is_framework_module(&fun.module_env, &STATE_CHANGING_NATIVE_MODULES),
+            }
+        } else {
+            let data = StacklessBytecodeGenerator::new(fun).generate_function();
+            self.body_state_access(&FunctionTarget::new(fun, &data))
+        };
+        self.state_accesses.insert(id, access);
+        access
+    }
+
+    fn body_state_access(&mut self, target: &FunctionTarget) -> StateAccess {
+        let mut access = StateAccess::default();
+        for bc in target.get_bytecode() {
+            if let Bytecode::Call(_, dests, op, _, _) = bc {
+                match op {
+                    Operation::MoveTo(..) | Operation::MoveFrom(..) => access.writes = true,
+                    Operation::BorrowGlobal(..) => {
+                        if matches!(
+                            target.get_local_type(dests[0]),
+                            Type::Reference(ReferenceKind::Mutable, _)
+                        ) {
+                            access.writes = true
+                        } else {
+                            access.reads = true
+                        }
+                    },
+                    Operation::Exists(..) | Operation::GetGlobal(..) => access.reads = true,
+                    Operation::Function(mid, fid, _) => {
+                        let callee = self.env.get_function(mid.qualified(*fid));
+                        let callee_access = self.state_access(&callee);
+                        access.reads |= callee_access.reads;
+                        access.writes |= callee_access.writes;
+                    },
+                    _ => {},
+                }
+            }
+        }
+        access
+    }
+}
+
+// ----------------------------------------------------------------------------------
+// Signers
+
+impl<'a> Linter<'a> {
+    fn check_unused_signers(&self, fun: &FunctionEnv, target: &FunctionTarget) {
+        if !fun.is_entry() {
+            return;
+        }
+        let mut used = BTreeSet::new();
+        for bc in target.get_bytecode() {
+            match bc {
+                Bytecode::Call(_, _, Operation::Destroy, _, _) => {},
+                Bytecode::Call(_, _, _, srcs, _) | Bytecode::Ret(_, srcs) => {
+                    used.extend(srcs.iter().copied())
+                },
+                Bytecode::Assign(_, _, src, _)
+                | Bytecode::Branch(_, _, _, src)
+                | Bytecode::Abort(_, src) => {
+                    used.insert(*src);
+                },
+                _ => {},
+            }
+        }
+        for (idx, Parameter(name, ty)) in fun.get_parameters().into_iter().enumerate() {
+            if is_signer(&ty) && !used.contains(&idx) {
+                self.report(
+                    Lint::UnusedSigner,
+                    &fun.get_loc(),
+                    &format!(
+                        "signer `{}` of entry function `{}` is never used, callers sign for \
+                         nothing",
+                        self.env.symbol_pool().string(name),
+                        fun.get_simple_name_string()
+                    ),
+                );
+            }
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------------
+// Helpers
+
+impl<'a> Linter<'a> {
+    fn has_attribute(&self, fun: &FunctionEnv, attr_name: &str) -> bool {
+        fun.get_attributes().iter().any(|attr| {
+            if let Attribute::Apply(_, name, _) = attr {
+                self.env.symbol_pool().string(*name).as_str() == attr_name
+            } else {
+                false
+            }
+        })
+    }
+}
+
+fn is_framework_module(module: &ModuleEnv, names: &[&str]) -> bool {
+    module.self_address() == &Address::Numerical(AccountAddress::ONE)
+        && module
+            .get_identifier()
+            .map_or(false, |name| names.contains(&name.as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Lint, LintConfig, LintLevel};
+
+    #[test]
+    fn test_lint_config_from_manifest() {
+        let config = LintConfig::from_manifest(
+            r#"
+            [package]
+            name = "Test"
+            version = "0.0.0"
+
+            [lint]
+            unchecked_coin_arithmetic = "error"
+            missing_view = "allow"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.level(Lint::UncheckedCoinArithmetic),
+            LintLevel::Error
+        );
+        assert_eq!(config.level(Lint::MissingView), LintLevel::Allow);
+        assert_eq!(config.level(Lint::UnusedSigner), LintLevel::Warning);
+
+        let unknown_lint = "[package]\nname = \"Test\"\n[lint]\nunused = \"error\"";
+        assert!(LintConfig::from_manifest(unknown_lint).is_err());
+        let unknown_level = "[package]\nname = \"Test\"\n[lint]\nmissing_view = \"deny\"";
+        assert!(LintConfig::from_manifest(unknown_level).is_err());
+    }
+}
@@ -2,8 +2,12 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::extended_checks::ResourceGroupScope;
-use aptos_types::{on_chain_config::Features, transaction::AbortInfo};
+use aptos_types::{
+    on_chain_config::Features,
+    transaction::{access_summary::AccessSummary, AbortInfo},
+};
 use move_binary_format::{
+    access::ModuleAccess,
     file_format::{Ability, AbilitySet, CompiledScript},
     normalized::{Function, Struct},
     CompiledModule,
@@ -27,6 +31,9 @@ pub const METADATA_V1_MIN_FILE_FORMAT_VERSION: u32 = 6;
 /// Aptos specific metadata (`aptos::` here).
 pub static APTOS_METADATA_KEY: &[u8] = "aptos::metadata_v0".as_bytes();
 pub static APTOS_METADATA_KEY_V1: &[u8] = "aptos::metadata_v1".as_bytes();
+/// The key of the summaries of the resources accessed by the entry functions of a module, by
+/// function name.
+pub static APTOS_ACCESS_SUMMARY_KEY: &[u8] = "aptos::access_summary_v0".as_bytes();
 
 /// Aptos specific metadata attached to the metadata section of file_format.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -160,11 +167,29 @@ pub fn get_vm_metadata_v0(vm: &MoveVM, module_id: &ModuleId) -> Option<RuntimeMo
     vm.with_module_metadata(module_id, get_metadata_v0)
 }
 
+/// Extract the summaries of the resources accessed by the entry functions of a module
+pub fn get_access_summaries(md: &[Metadata]) -> Option<BTreeMap<String, AccessSummary>> {
+    let data = md.iter().find(|md| md.key == APTOS_ACCESS_SUMMARY_KEY)?;
+    bcs::from_bytes(&data.value).ok()
+}
+
 /// Check if the metadata has unknown key/data types
-pub fn check_metadata_format(module: &CompiledModule) -> Result<(), MalformedError> {
+pub fn check_metadata_format(
+    module: &CompiledModule,
+    features: &Features,
+) -> Result<(), MalformedError> {
     let mut exist = false;
+    let mut access_summaries_exist = false;
     for data in module.metadata.iter() {
-        if data.key == *APTOS_METADATA_KEY || data.key == *APTOS_METADATA_KEY_V1 {
+        if data.key == *APTOS_ACCESS_SUMMARY_KEY && features.is_access_summary_metadata_enabled() {
+            if access_summaries_exist {
+                return Err(MalformedError::DuplicateKey);
+            }
+            access_summaries_exist = true;
+
+            bcs::from_bytes::<BTreeMap<String, AccessSummary>>(&data.value)
+                .map_err(|e| MalformedError::DeserializedError(data.key.clone(), e))?;
+        } else if data.key == *APTOS_METADATA_KEY || data.key == *APTOS_METADATA_KEY_V1 {
             if exist {
                 return Err(MalformedError::DuplicateKey);
             }
@@ -270,6 +295,8 @@ pub enum MalformedError {
     DeserializedError(Vec<u8>, bcs::Error),
     #[error("Duplicate key for metadata")]
     DuplicateKey,
+    #[error("Access summary for unknown entry function: {0}")]
+    UnknownAccessSummaryFunction(String),
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Error)]
@@ -341,7 +368,23 @@ pub fn verify_module_metadata(
     features: &Features,
 ) -> Result<(), MetaDataValidationError> {
     if features.are_resource_groups_enabled() {
-        check_metadata_format(module)?;
+        check_metadata_format(module, features)?;
+    }
+    if features.is_access_summary_metadata_enabled() {
+        if let Some(summaries) = get_access_summaries(&module.metadata) {
+            for fun in summaries.keys() {
+                let is_entry = module.function_defs.iter().any(|func_def| {
+                    func_def.is_entry
+                        && module
+                            .identifier_at(module.function_handle_at(func_def.function).name)
+                            .as_str()
+                            == fun
+                });
+                if !is_entry {
+                    return Err(MalformedError::UnknownAccessSummaryFunction(fun.clone()).into());
+                }
+            }
+        }
     }
     let metadata = if let Some(metadata) = get_metadata_from_compiled_module(module) {
         metadata
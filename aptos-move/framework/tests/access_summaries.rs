@@ -0,0 +1,88 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_framework::{get_access_summaries, path_in_crate, BuildOptions, BuiltPackage};
+use aptos_types::transaction::access_summary::{
+    AccessAddress, AccessStructTag, AccessSummary, AccessType, ResourceAccess,
+};
+use move_core_types::{account_address::AccountAddress, identifier::Identifier};
+use std::collections::{BTreeMap, BTreeSet};
+use tempfile::TempDir;
+
+/// Builds the package with access summaries and returns the summaries published with its module.
+fn build_summaries(name: &str) -> BTreeMap<String, AccessSummary> {
+    let install_dir = TempDir::new().unwrap();
+    let package = BuiltPackage::build(
+        path_in_crate(format!("tests/access_summaries/{}", name)),
+        BuildOptions {
+            with_access_summaries: true,
+            install_dir: Some(install_dir.path().to_path_buf()),
+            ..BuildOptions::default()
+        },
+    )
+    .unwrap();
+    let module = package.modules().next().unwrap();
+    get_access_summaries(&module.metadata).unwrap()
+}
+
+fn resource(name: &str, type_args: Vec<AccessType>) -> AccessStructTag {
+    AccessStructTag {
+        address: AccountAddress::from_hex_literal("0xcafe").unwrap(),
+        module: Identifier::new("basic").unwrap(),
+        name: Identifier::new(name).unwrap(),
+        type_args,
+    }
+}
+
+fn access(resource: AccessStructTag, address: AccessAddress) -> ResourceAccess {
+    ResourceAccess {
+        resource,
+        resource_group: false,
+        address,
+    }
+}
+
+#[test]
+fn test_access_summaries() {
+    let summaries = build_summaries("basic");
+    let balance = |ty| resource("Balance", vec![ty]);
+    let config = resource("Config", vec![]);
+
+    assert_eq!(summaries["transfer"], AccessSummary {
+        reads: BTreeSet::from([access(
+            balance(AccessType::TypeParameter(0)),
+            AccessAddress::Argument(1),
+        )]),
+        writes: BTreeSet::from([access(
+            balance(AccessType::TypeParameter(0)),
+            AccessAddress::Signer(0),
+        )]),
+    });
+    assert_eq!(summaries["init"], AccessSummary {
+        reads: BTreeSet::from([access(
+            config.clone(),
+            AccessAddress::Constant(AccountAddress::from_hex_literal("0xcafe").unwrap()),
+        )]),
+        writes: BTreeSet::from([access(config.clone(), AccessAddress::Signer(0))]),
+    });
+    assert_eq!(summaries["reset"], AccessSummary {
+        reads: BTreeSet::from([access(
+            config,
+            AccessAddress::Constant(AccountAddress::from_hex_literal("0xcafe").unwrap()),
+        )]),
+        writes: BTreeSet::from([access(balance(AccessType::U64), AccessAddress::Any)]),
+    });
+    assert_eq!(summaries["set_member"], AccessSummary {
+        reads: BTreeSet::new(),
+        writes: BTreeSet::from([ResourceAccess {
+            resource: resource("Group", vec![]),
+            resource_group: true,
+            address: AccessAddress::Signer(0),
+        }]),
+    });
+
+    // Recursive functions and functions calling storage natives are not summarized.
+    assert!(!summaries.contains_key("clear"));
+    assert!(!summaries.contains_key("register"));
+    assert_eq!(summaries.len(), 4);
+}
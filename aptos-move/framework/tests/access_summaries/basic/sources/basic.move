@@ -0,0 +1,70 @@
+/// Entry functions whose access summaries are checked by `tests/access_summaries.rs`.
+module summaries::basic {
+    use aptos_std::table::{Self, Table};
+    use std::signer;
+
+    struct Balance<phantom T> has key {
+        value: u64,
+    }
+
+    struct Config has key {
+        admin: address,
+    }
+
+    struct Registry has key {
+        entries: Table<u64, u64>,
+    }
+
+    #[resource_group(scope = global)]
+    struct Group {}
+
+    #[resource_group_member(group = summaries::basic::Group)]
+    struct Member has key {
+        value: u64,
+    }
+
+    /// Writes the balance of the sender, passed through a helper, and reads the one of `to`.
+    public entry fun transfer<T>(sender: &signer, amount: u64, to: address) acquires Balance {
+        withdraw<T>(signer::address_of(sender), amount);
+        assert!(borrow_global<Balance<T>>(to).value < amount, 1);
+    }
+
+    fun withdraw<T>(account: address, amount: u64) acquires Balance {
+        let balance = borrow_global_mut<Balance<T>>(account);
+        balance.value = balance.value - amount;
+    }
+
+    /// Publishes the config at the sender, and reads the config at a constant address.
+    public entry fun init(admin: &signer) {
+        assert!(!exists<Config>(@summaries), 1);
+        move_to(admin, Config { admin: signer::address_of(admin) });
+    }
+
+    /// Writes a balance at an address read from storage, which is summarized by a wildcard.
+    public entry fun reset() acquires Balance, Config {
+        let admin = borrow_global<Config>(@summaries).admin;
+        borrow_global_mut<Balance<u64>>(admin).value = 0;
+    }
+
+    /// Writes a resource group member, accessed through its group.
+    public entry fun set_member(sender: &signer, value: u64) acquires Member {
+        borrow_global_mut<Member>(signer::address_of(sender)).value = value;
+    }
+
+    /// Not summarized: the recursion may access an unbounded number of addresses.
+    public entry fun clear(sender: &signer, count: u64) acquires Balance {
+        clear_from(signer::address_of(sender), count);
+    }
+
+    fun clear_from(account: address, count: u64) acquires Balance {
+        if (count > 0) {
+            borrow_global_mut<Balance<u64>>(account).value = 0;
+            clear_from(account, count - 1);
+        }
+    }
+
+    /// Not summarized: table natives access storage.
+    public entry fun register(sender: &signer, key: u64) acquires Registry {
+        table::add(&mut borrow_global_mut<Registry>(signer::address_of(sender)).entries, key, 0);
+    }
+}
@@ -0,0 +1,152 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_framework::{
+    build_model, extended_checks,
+    lint::{run_lints, Lint, LintConfig},
+    path_in_crate, BuildOptions, BuiltPackage,
+};
+use codespan_reporting::{diagnostic::Severity, term::termcolor::Buffer};
+use std::{collections::BTreeMap, fs, path::PathBuf};
+use tempfile::TempDir;
+
+/// Each package under this directory has functions firing a lint, prefixed with `fires_`, and
+/// functions it must stay quiet on, prefixed with `quiet_`.
+const LINT_PACKAGES_DIR: &str = "tests/lint";
+/// Note attached to every lint diagnostic.
+const LINT_NOTE: &str = "its level can be set in the `[lint]` section of Move.toml";
+
+fn package_path(name: &str) -> PathBuf {
+    path_in_crate(format!("{}/{}", LINT_PACKAGES_DIR, name))
+}
+
+/// Runs the lints on the package and returns the rendered diagnostics.
+fn lint_package(name: &str) -> String {
+    let package_path = package_path(name);
+    let env = build_model(
+        false,
+        &package_path,
+        BTreeMap::new(),
+        None,
+        None,
+        false,
+        extended_checks::get_all_attribute_names().clone(),
+    )
+    .unwrap();
+    run_lints(&env, &LintConfig::from_package(&package_path).unwrap());
+    let mut buffer = Buffer::no_color();
+    env.report_diag(&mut buffer, Severity::Warning);
+    String::from_utf8(buffer.into_inner()).unwrap()
+}
+
+/// Asserts that `lint` fired once for each of the messages, and no other lint fired.
+fn assert_lints(output: &str, lint: Lint, messages: &[&str]) {
+    let lint_note = format!("lint `{}`, {}", lint, LINT_NOTE);
+    assert_eq!(
+        output.matches(&lint_note).count(),
+        messages.len(),
+        "{}",
+        output
+    );
+    assert_eq!(
+        output.matches(LINT_NOTE).count(),
+        messages.len(),
+        "{}",
+        output
+    );
+    for message in messages {
+        assert!(
+            output.contains(message),
+            "`{}` not in:\n{}",
+            message,
+            output
+        );
+    }
+}
+
+/// Copies the package to a temporary directory, appending `lint_section` to its manifest.
+fn copy_package(name: &str, lint_section: &str) -> TempDir {
+    let source = package_path(name);
+    let framework_path = path_in_crate("aptos-framework");
+    let manifest = fs::read_to_string(source.join("Move.toml"))
+        .unwrap()
+        .replace("../../../aptos-framework", framework_path.to_str().unwrap());
+
+    let dir = TempDir::new().unwrap();
+    fs::write(
+        dir.path().join("Move.toml"),
+        format!("{}\n{}", manifest, lint_section),
+    )
+    .unwrap();
+    fs::create_dir(dir.path().join("sources")).unwrap();
+    for entry in fs::read_dir(source.join("sources")).unwrap() {
+        let entry = entry.unwrap();
+        fs::copy(
+            entry.path(),
+            dir.path().join("sources").join(entry.file_name()),
+        )
+        .unwrap();
+    }
+    dir
+}
+
+#[test]
+fn test_unchecked_coin_arithmetic() {
+    let output = lint_package("unchecked_coin_arithmetic");
+    assert_lints(&output, Lint::UncheckedCoinArithmetic, &[
+        "let fee = amount * fee_bps / 10000;",
+        "product / denominator",
+    ]);
+}
+
+#[test]
+fn test_public_capability_return() {
+    let output = lint_package("public_capability_return");
+    assert_lints(&output, Lint::PublicCapabilityReturn, &[
+        "public function `fires_mint_capability` returns `",
+        "public function `fires_optional_mint_capability` returns `",
+        "public function `fires_object_signer` returns `signer`",
+    ]);
+}
+
+#[test]
+fn test_missing_view() {
+    let output = lint_package("missing_view");
+    assert_lints(&output, Lint::MissingView, &[
+        "public function `fires_value` only reads global state",
+        "public function `fires_balance` only reads global state",
+    ]);
+}
+
+#[test]
+fn test_unused_signer() {
+    let output = lint_package("unused_signer");
+    assert_lints(&output, Lint::UnusedSigner, &[
+        "signer `_account` of entry function `fires_set` is never used",
+    ]);
+}
+
+/// `aptos move compile --lint` builds with `BuildOptions::lint`, failing on lints set to `error`.
+#[test]
+fn test_error_level_fails_build() {
+    let build = |level: &str, lint: bool| {
+        let package = copy_package(
+            "unused_signer",
+            &format!("[lint]\nunused_signer = \"{}\"\n", level),
+        );
+        BuiltPackage::build(package.path().to_path_buf(), BuildOptions {
+            lint,
+            ..BuildOptions::default()
+        })
+        .map(|_| ())
+    };
+    assert!(build("allow", true).is_ok());
+    assert!(build("warning", true).is_ok());
+    assert!(build("error", false).is_ok());
+    let err = build("error", true).unwrap_err();
+    assert!(
+        err.to_string().contains("extended checks failed"),
+        "{}",
+        err
+    );
+}
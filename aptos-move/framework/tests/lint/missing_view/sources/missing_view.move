@@ -0,0 +1,44 @@
+/// Public functions reading global state, which the `missing_view` lint checks.
+module lint::missing_view {
+    use std::signer;
+    use std::string::String;
+    use aptos_framework::aptos_coin::AptosCoin;
+    use aptos_framework::coin;
+
+    struct Config has key {
+        name: String,
+        value: u64,
+    }
+
+    /// Fires: the function only reads `Config`.
+    public fun fires_value(addr: address): u64 acquires Config {
+        borrow_global<Config>(addr).value
+    }
+
+    /// Fires: the function reads global state through a framework function.
+    public fun fires_balance(owner: address): u64 {
+        coin::balance<AptosCoin>(owner)
+    }
+
+    #[view]
+    /// Quiet: the function is a view function already.
+    public fun quiet_view(addr: address): bool {
+        exists<Config>(addr)
+    }
+
+    /// Quiet: the function writes global state.
+    public fun quiet_set_value(addr: address, value: u64): u64 acquires Config {
+        borrow_global_mut<Config>(addr).value = value;
+        value
+    }
+
+    /// Quiet: a signer cannot be passed to a view function.
+    public fun quiet_own_value(account: &signer): u64 acquires Config {
+        borrow_global<Config>(signer::address_of(account)).value
+    }
+
+    /// Quiet: the function does not access global state.
+    public fun quiet_pure(value: u64): u64 {
+        value + 1
+    }
+}
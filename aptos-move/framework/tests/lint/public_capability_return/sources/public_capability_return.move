@@ -0,0 +1,48 @@
+/// Functions returning capabilities, which the `public_capability_return` lint checks.
+module lint::public_capability_return {
+    use std::option::{Self, Option};
+    use aptos_framework::object::{Self, ExtendRef};
+
+    friend lint::public_capability_return_friend;
+
+    struct MintCapability has store {}
+
+    /// Fires: any module can obtain a mint capability.
+    public fun fires_mint_capability(): MintCapability {
+        MintCapability {}
+    }
+
+    /// Fires: the capability is wrapped in an option.
+    public fun fires_optional_mint_capability(): Option<MintCapability> {
+        option::some(MintCapability {})
+    }
+
+    /// Fires: any module can obtain the signer of the object.
+    public fun fires_object_signer(extend_ref: &ExtendRef): signer {
+        object::generate_signer_for_extending(extend_ref)
+    }
+
+    /// Quiet: only callers holding the signer obtain the capability.
+    public fun quiet_with_signer(_account: &signer): MintCapability {
+        MintCapability {}
+    }
+
+    /// Quiet: only friends can obtain the capability.
+    public(friend) fun quiet_friend(): MintCapability {
+        MintCapability {}
+    }
+
+    /// Quiet: no capability is returned.
+    public fun quiet_no_capability(): u64 {
+        0
+    }
+}
+
+module lint::public_capability_return_friend {
+    use lint::public_capability_return::{Self, MintCapability};
+
+    /// Quiet: the function is private.
+    fun quiet_private(): MintCapability {
+        public_capability_return::quiet_friend()
+    }
+}
@@ -0,0 +1,37 @@
+/// Functions handling coins, whose `u64` arithmetic the `unchecked_coin_arithmetic` lint checks.
+module lint::unchecked_coin_arithmetic {
+    use aptos_framework::aptos_account;
+    use aptos_framework::aptos_coin::AptosCoin;
+    use aptos_framework::coin;
+    use aptos_std::math64;
+
+    /// Fires: `amount * fee_bps` may overflow before it is divided.
+    public entry fun fires_pay_fee(sender: &signer, to: address, amount: u64, fee_bps: u64) {
+        let fee = amount * fee_bps / 10000;
+        aptos_account::transfer(sender, to, fee);
+    }
+
+    #[view]
+    /// Fires: the product is divided after being stored in a local.
+    public fun fires_share_of_balance(owner: address, numerator: u64, denominator: u64): u64 {
+        let product = coin::balance<AptosCoin>(owner) * numerator;
+        product / denominator
+    }
+
+    /// Quiet: `math64::mul_div` computes the product as `u128`.
+    public entry fun quiet_pay_fee_mul_div(sender: &signer, to: address, amount: u64, fee_bps: u64) {
+        let fee = math64::mul_div(amount, fee_bps, 10000);
+        aptos_account::transfer(sender, to, fee);
+    }
+
+    /// Quiet: the product is computed as `u128`.
+    public entry fun quiet_pay_fee_u128(sender: &signer, to: address, amount: u64, fee_bps: u64) {
+        let fee = ((amount as u128) * (fee_bps as u128) / 10000 as u64);
+        aptos_account::transfer(sender, to, fee);
+    }
+
+    /// Quiet: no coins are handled.
+    public fun quiet_no_coins(a: u64, b: u64, c: u64): u64 {
+        a * b / c
+    }
+}
@@ -0,0 +1,19 @@
+/// Entry functions taking signers, which the `unused_signer` lint checks.
+module lint::unused_signer {
+    struct Counter has key {
+        value: u64,
+    }
+
+    /// Fires: `_account` signs for nothing.
+    public entry fun fires_set(_account: &signer, value: u64) acquires Counter {
+        borrow_global_mut<Counter>(@lint).value = value;
+    }
+
+    /// Quiet: the signer publishes the counter.
+    public entry fun quiet_publish(account: &signer, value: u64) {
+        move_to(account, Counter { value });
+    }
+
+    /// Quiet: the function is not an entry function.
+    public fun quiet_not_entry(_account: &signer) {}
+}
@@ -420,6 +420,10 @@ pub fn default_features() -> Vec<FeatureFlag> {
         FeatureFlag::GAS_PAYER_ENABLED,
         FeatureFlag::BULLETPROOFS_NATIVES,
         FeatureFlag::MODULE_EVENT,
+        FeatureFlag::SECP256K1_ECDSA_AUTHENTICATOR,
+        FeatureFlag::WEBAUTHN_AUTHENTICATOR,
+        FeatureFlag::ACCOUNT_ABSTRACTION,
+        FeatureFlag::ACCESS_SUMMARY_METADATA,
     ]
 }
 
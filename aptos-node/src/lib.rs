@@ -15,10 +15,10 @@ pub mod utils;
 #[cfg(test)]
 mod tests;
 
-use anyhow::anyhow;
+use anyhow::{anyhow, bail};
 use aptos_api::bootstrap as bootstrap_api;
 use aptos_build_info::build_information;
-use aptos_config::config::{merge_node_config, NodeConfig, PersistableConfig};
+use aptos_config::config::{merge_node_config, ForkConfig, NodeConfig, PersistableConfig};
 use aptos_framework::ReleaseBundle;
 use aptos_logger::{prelude::*, telemetry_log_writer::TelemetryLog, Level, LoggerFilterUpdater};
 use aptos_state_sync_driver::driver_factory::StateSyncRuntimes;
@@ -126,6 +126,7 @@ impl AptosNodeArgs {
                 self.test_dir,
                 self.random_ports,
                 self.lazy,
+                None,
                 &genesis_framework,
                 rng,
             )
@@ -226,13 +227,15 @@ pub fn start(
     Ok(())
 }
 
-/// Creates a simple test environment and starts the node
+/// Creates a simple test environment and starts the node. If `fork_config` is set, the new chain
+/// runs on top of the state of another network.
 pub fn setup_test_environment_and_start_node<R>(
     config_path: Option<PathBuf>,
     test_config_override_path: Option<PathBuf>,
     test_dir: Option<PathBuf>,
     random_ports: bool,
     enable_lazy_mode: bool,
+    fork_config: Option<ForkConfig>,
     framework: &ReleaseBundle,
     rng: R,
 ) -> anyhow::Result<()>
@@ -253,15 +256,23 @@ where
 
     // If there's already a config, use it. Otherwise create a test one.
     let config = if validator_config_path.exists() {
-        NodeConfig::load_from_path(&validator_config_path)
-            .map_err(|error| anyhow!("Unable to load config: {:?}", error))?
+        let config = NodeConfig::load_from_path(&validator_config_path)
+            .map_err(|error| anyhow!("Unable to load config: {:?}", error))?;
+        if fork_config.is_some() && fork_config != config.storage.fork {
+            bail!(
+                "The test dir {:?} already has a chain that doesn't fork the requested state, a new chain must be created to fork it",
+                test_dir
+            );
+        }
+        config
     } else {
         // Create a test only config for a single validator node
-        let node_config = create_single_node_test_config(
+        let mut node_config = create_single_node_test_config(
             config_path.clone(),
             test_config_override_path.clone(),
             enable_lazy_mode,
         )?;
+        node_config.storage.fork = fork_config;
 
         // Build genesis and the validator node
         let builder = aptos_genesis::builder::Builder::new(&test_dir, framework.clone())?
@@ -331,6 +342,12 @@ where
     if enable_lazy_mode {
         println!("\tLazy mode is enabled");
     }
+    if let Some(fork_config) = &config.storage.fork {
+        println!(
+            "\tForked state: {} at version {}",
+            fork_config.source, fork_config.version
+        );
+    }
     println!("\nAptos is running, press ctrl-c to exit\n");
 
     start(config, Some(log_file), false)
@@ -500,6 +517,7 @@ pub fn setup_environment_and_start_node(
     let (
         network_runtimes,
         consensus_network_interfaces,
+        consensus_observer_network_interfaces,
         mempool_network_interfaces,
         peer_monitoring_service_network_interfaces,
         storage_service_network_interfaces,
@@ -544,22 +562,42 @@ pub fn setup_environment_and_start_node(
         );
 
     // Create the consensus runtime (this blocks on state sync first)
-    let consensus_runtime = consensus_network_interfaces.map(|consensus_network_interfaces| {
+    let consensus_runtime = if let Some(consensus_network_interfaces) = consensus_network_interfaces
+    {
         // Wait until state sync has been initialized
         debug!("Waiting until state sync is initialized!");
         state_sync_runtimes.block_until_initialized();
         debug!("State sync initialization complete.");
 
         // Initialize and start consensus
-        services::start_consensus_runtime(
+        Some(services::start_consensus_runtime(
             &mut node_config,
             db_rw,
             consensus_reconfig_subscription,
             consensus_network_interfaces,
+            consensus_observer_network_interfaces,
             consensus_notifier,
             consensus_to_mempool_sender,
-        )
-    });
+        ))
+    } else if node_config.consensus_observer.observer_enabled {
+        // Wait until state sync has been initialized
+        debug!("Waiting until state sync is initialized!");
+        state_sync_runtimes.block_until_initialized();
+        debug!("State sync initialization complete.");
+
+        // Initialize and start the consensus observer
+        Some(services::start_consensus_observer_runtime(
+            &node_config,
+            db_rw,
+            consensus_reconfig_subscription,
+            consensus_observer_network_interfaces
+                .expect("The consensus observer requires network interfaces!"),
+            consensus_notifier,
+            consensus_to_mempool_sender,
+        ))
+    } else {
+        None
+    };
 
     Ok(AptosHandle {
         _api_runtime: api_runtime,
@@ -6,7 +6,10 @@ use aptos_config::{
     config::{NetworkConfig, NodeConfig},
     network_id::NetworkId,
 };
-use aptos_consensus::network_interface::{ConsensusMsg, DIRECT_SEND, RPC};
+use aptos_consensus::{
+    consensus_observer::network_message::ConsensusObserverMessage,
+    network_interface::{ConsensusMsg, DIRECT_SEND, RPC},
+};
 use aptos_event_notifications::EventSubscriptionService;
 use aptos_logger::debug;
 use aptos_mempool::network::MempoolSyncMsg;
@@ -63,6 +66,32 @@ pub fn consensus_network_configuration(node_config: &NodeConfig) -> NetworkAppli
     NetworkApplicationConfig::new(network_client_config, network_service_config)
 }
 
+/// Returns the network application config for the consensus observer client and service
+pub fn consensus_observer_network_configuration(
+    node_config: &NodeConfig,
+) -> NetworkApplicationConfig {
+    let direct_send_protocols = vec![ProtocolId::ConsensusObserver];
+    let rpc_protocols = vec![]; // The consensus observer does not use RPC
+    let max_network_channel_size = node_config.consensus_observer.max_network_channel_size as usize;
+
+    let network_client_config =
+        NetworkClientConfig::new(direct_send_protocols.clone(), rpc_protocols.clone());
+    let network_service_config = NetworkServiceConfig::new(
+        direct_send_protocols,
+        rpc_protocols,
+        aptos_channel::Config::new(max_network_channel_size)
+            .queue_style(QueueStyle::FIFO)
+            .counters(&aptos_consensus::counters::PENDING_CONSENSUS_OBSERVER_NETWORK_EVENTS),
+    );
+    NetworkApplicationConfig::new(network_client_config, network_service_config)
+}
+
+/// Returns true iff the consensus observer (or publisher) is enabled on the node
+fn is_consensus_observer_enabled(node_config: &NodeConfig) -> bool {
+    node_config.consensus_observer.observer_enabled
+        || node_config.consensus_observer.publisher_enabled
+}
+
 /// Returns the network application config for the mempool client and service
 pub fn mempool_network_configuration(node_config: &NodeConfig) -> NetworkApplicationConfig {
     let direct_send_protocols = vec![ProtocolId::MempoolDirectSend];
@@ -160,6 +189,7 @@ pub fn setup_networks_and_get_interfaces(
 ) -> (
     Vec<Runtime>,
     Option<ApplicationNetworkInterfaces<ConsensusMsg>>,
+    Option<ApplicationNetworkInterfaces<ConsensusObserverMessage>>,
     ApplicationNetworkInterfaces<MempoolSyncMsg>,
     ApplicationNetworkInterfaces<PeerMonitoringServiceMessage>,
     ApplicationNetworkInterfaces<StorageServiceMessage>,
@@ -170,6 +200,7 @@ pub fn setup_networks_and_get_interfaces(
     // Create each network and register the application handles
     let mut network_runtimes = vec![];
     let mut consensus_network_handle = None;
+    let mut consensus_observer_network_handles = vec![];
     let mut mempool_network_handles = vec![];
     let mut peer_monitoring_service_network_handles = vec![];
     let mut storage_service_network_handles = vec![];
@@ -206,6 +237,18 @@ pub fn setup_networks_and_get_interfaces(
             }
         }
 
+        // Register the consensus observer (both client and server) with the fullnode
+        // networks. Validators publish to their fullnodes over the VFN network.
+        if !network_id.is_validator_network() && is_consensus_observer_enabled(node_config) {
+            let consensus_observer_network_handle = register_client_and_service_with_network(
+                &mut network_builder,
+                network_id,
+                &network_config,
+                consensus_observer_network_configuration(node_config),
+            );
+            consensus_observer_network_handles.push(consensus_observer_network_handle);
+        }
+
         // Register mempool (both client and server) with the network
         let mempool_network_handle = register_client_and_service_with_network(
             &mut network_builder,
@@ -246,12 +289,14 @@ pub fn setup_networks_and_get_interfaces(
     // Transform all network handles into application interfaces
     let (
         consensus_interfaces,
+        consensus_observer_interfaces,
         mempool_interfaces,
         peer_monitoring_service_interfaces,
         storage_service_interfaces,
     ) = transform_network_handles_into_interfaces(
         node_config,
         consensus_network_handle,
+        consensus_observer_network_handles,
         mempool_network_handles,
         peer_monitoring_service_network_handles,
         storage_service_network_handles,
@@ -261,6 +306,7 @@ pub fn setup_networks_and_get_interfaces(
     (
         network_runtimes,
         consensus_interfaces,
+        consensus_observer_interfaces,
         mempool_interfaces,
         peer_monitoring_service_interfaces,
         storage_service_interfaces,
@@ -305,6 +351,7 @@ fn register_client_and_service_with_network<
 fn transform_network_handles_into_interfaces(
     node_config: &NodeConfig,
     consensus_network_handle: Option<ApplicationNetworkHandle<ConsensusMsg>>,
+    consensus_observer_network_handles: Vec<ApplicationNetworkHandle<ConsensusObserverMessage>>,
     mempool_network_handles: Vec<ApplicationNetworkHandle<MempoolSyncMsg>>,
     peer_monitoring_service_network_handles: Vec<
         ApplicationNetworkHandle<PeerMonitoringServiceMessage>,
@@ -313,6 +360,7 @@ fn transform_network_handles_into_interfaces(
     peers_and_metadata: Arc<PeersAndMetadata>,
 ) -> (
     Option<ApplicationNetworkInterfaces<ConsensusMsg>>,
+    Option<ApplicationNetworkInterfaces<ConsensusObserverMessage>>,
     ApplicationNetworkInterfaces<MempoolSyncMsg>,
     ApplicationNetworkInterfaces<PeerMonitoringServiceMessage>,
     ApplicationNetworkInterfaces<StorageServiceMessage>,
@@ -324,6 +372,15 @@ fn transform_network_handles_into_interfaces(
             peers_and_metadata.clone(),
         )
     });
+    let consensus_observer_interfaces = if consensus_observer_network_handles.is_empty() {
+        None
+    } else {
+        Some(create_network_interfaces(
+            consensus_observer_network_handles,
+            consensus_observer_network_configuration(node_config),
+            peers_and_metadata.clone(),
+        ))
+    };
     let mempool_interfaces = create_network_interfaces(
         mempool_network_handles,
         mempool_network_configuration(node_config),
@@ -342,6 +399,7 @@ fn transform_network_handles_into_interfaces(
 
     (
         consensus_interfaces,
+        consensus_observer_interfaces,
         mempool_interfaces,
         peer_monitoring_service_interfaces,
         storage_service_interfaces,
@@ -4,7 +4,12 @@
 use crate::{bootstrap_api, indexer, mpsc::Receiver, network::ApplicationNetworkInterfaces};
 use aptos_build_info::build_information;
 use aptos_config::config::NodeConfig;
-use aptos_consensus::network_interface::ConsensusMsg;
+use aptos_consensus::{
+    consensus_observer::{
+        network_message::ConsensusObserverMessage, publisher::ConsensusPublisher,
+    },
+    network_interface::ConsensusMsg,
+};
 use aptos_consensus_notifications::ConsensusNotifier;
 use aptos_event_notifications::{DbBackedOnChainConfig, ReconfigNotificationListener};
 use aptos_indexer_grpc_fullnode::runtime::bootstrap as bootstrap_indexer_grpc;
@@ -81,9 +86,19 @@ pub fn start_consensus_runtime(
     db_rw: DbReaderWriter,
     consensus_reconfig_subscription: Option<ReconfigNotificationListener<DbBackedOnChainConfig>>,
     consensus_network_interfaces: ApplicationNetworkInterfaces<ConsensusMsg>,
+    consensus_observer_network_interfaces: Option<
+        ApplicationNetworkInterfaces<ConsensusObserverMessage>,
+    >,
     consensus_notifier: ConsensusNotifier,
     consensus_to_mempool_sender: Sender<QuorumStoreRequest>,
 ) -> Runtime {
+    // Create the consensus publisher (if enabled)
+    let consensus_publisher = create_consensus_publisher(
+        node_config,
+        db_rw.reader.clone(),
+        consensus_observer_network_interfaces.as_ref(),
+    );
+
     let instant = Instant::now();
     let consensus_runtime = aptos_consensus::consensus_provider::start_consensus(
         node_config,
@@ -94,11 +109,77 @@ pub fn start_consensus_runtime(
         db_rw,
         consensus_reconfig_subscription
             .expect("Consensus requires a reconfiguration subscription!"),
+        consensus_publisher.clone(),
     );
     debug!("Consensus started in {} ms", instant.elapsed().as_millis());
+
+    // Start serving subscription requests (if the publisher is enabled)
+    if let (Some(consensus_publisher), Some(network_interfaces)) =
+        (consensus_publisher, consensus_observer_network_interfaces)
+    {
+        consensus_runtime
+            .spawn(consensus_publisher.start(network_interfaces.network_service_events));
+    }
     consensus_runtime
 }
 
+/// Creates the consensus observer runtime and starts the observer
+pub fn start_consensus_observer_runtime(
+    node_config: &NodeConfig,
+    db_rw: DbReaderWriter,
+    consensus_reconfig_subscription: Option<ReconfigNotificationListener<DbBackedOnChainConfig>>,
+    consensus_observer_network_interfaces: ApplicationNetworkInterfaces<ConsensusObserverMessage>,
+    consensus_notifier: ConsensusNotifier,
+    consensus_to_mempool_sender: Sender<QuorumStoreRequest>,
+) -> Runtime {
+    // Create the consensus publisher (if enabled), so that verified
+    // messages can be forwarded to downstream observers.
+    let consensus_publisher = create_consensus_publisher(
+        node_config,
+        db_rw.reader.clone(),
+        Some(&consensus_observer_network_interfaces),
+    );
+
+    let instant = Instant::now();
+    let consensus_observer_runtime = aptos_consensus::consensus_provider::start_consensus_observer(
+        node_config,
+        consensus_observer_network_interfaces.network_client,
+        consensus_observer_network_interfaces.network_service_events,
+        consensus_publisher,
+        Arc::new(consensus_notifier),
+        consensus_to_mempool_sender,
+        db_rw,
+        consensus_reconfig_subscription
+            .expect("The consensus observer requires a reconfiguration subscription!"),
+    );
+    debug!(
+        "Consensus observer started in {} ms",
+        instant.elapsed().as_millis()
+    );
+    consensus_observer_runtime
+}
+
+/// Creates the consensus publisher (if it is enabled in the node config)
+fn create_consensus_publisher(
+    node_config: &NodeConfig,
+    db_reader: Arc<dyn DbReader>,
+    consensus_observer_network_interfaces: Option<
+        &ApplicationNetworkInterfaces<ConsensusObserverMessage>,
+    >,
+) -> Option<Arc<ConsensusPublisher>> {
+    if !node_config.consensus_observer.publisher_enabled {
+        return None;
+    }
+
+    consensus_observer_network_interfaces.map(|network_interfaces| {
+        Arc::new(ConsensusPublisher::new(
+            node_config.consensus_observer,
+            network_interfaces.network_client.clone(),
+            db_reader,
+        ))
+    })
+}
+
 /// Create the mempool runtime and start mempool
 pub fn start_mempool_runtime_and_get_consensus_sender(
     node_config: &mut NodeConfig,
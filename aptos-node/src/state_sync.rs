@@ -56,7 +56,10 @@ pub fn create_event_subscription_service(
         .expect("Mempool must subscribe to reconfigurations");
 
     // Create a reconfiguration subscription for consensus (if this is a validator)
-    let consensus_reconfig_subscription = if node_config.base.role.is_validator() {
+    // or for the consensus observer (if it is enabled on this fullnode).
+    let consensus_reconfig_subscription = if node_config.base.role.is_validator()
+        || node_config.consensus_observer.observer_enabled
+    {
         Some(
             event_subscription_service
                 .subscribe_to_reconfigurations()
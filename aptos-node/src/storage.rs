@@ -2,12 +2,19 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use anyhow::anyhow;
-use aptos_config::{config::NodeConfig, utils::get_genesis_txn};
+use aptos_config::{
+    config::{ForkSource, NodeConfig},
+    utils::get_genesis_txn,
+};
 use aptos_db::AptosDB;
 use aptos_executor::db_bootstrapper::maybe_bootstrap;
 use aptos_logger::{debug, info};
+use aptos_rest_client::Client;
 use aptos_storage_interface::{DbReader, DbReaderWriter};
 use aptos_types::waypoint::Waypoint;
+use aptos_validator_interface::{
+    AptosValidatorInterface, DBDebuggerInterface, ForkedDb, RestDebuggerInterface,
+};
 use aptos_vm::AptosVM;
 use std::{fs, net::SocketAddr, path::Path, sync::Arc, time::Instant};
 use tokio::runtime::Runtime;
@@ -41,6 +48,38 @@ pub(crate) fn bootstrap_db(
     (aptos_db, db_rw, None)
 }
 
+/// If the node forks another network, wraps the DB so that state values never written locally
+/// are read from the forked network instead.
+fn maybe_fork_db(
+    node_config: &NodeConfig,
+    db_rw: DbReaderWriter,
+) -> anyhow::Result<DbReaderWriter> {
+    let fork_config = match &node_config.storage.fork {
+        Some(fork_config) => fork_config,
+        None => return Ok(db_rw),
+    };
+
+    let remote: Arc<dyn AptosValidatorInterface + Send> = match &fork_config.source {
+        ForkSource::Rest { url } => Arc::new(RestDebuggerInterface::new(Client::new(url.clone()))),
+        ForkSource::Db { path } => Arc::new(
+            DBDebuggerInterface::open(path)
+                .map_err(|err| anyhow!("Forked DB failed to open {}", err))?,
+        ),
+    };
+    info!(
+        "Forking the state of the {} at version {}",
+        fork_config.source, fork_config.version
+    );
+
+    Ok(DbReaderWriter::new(ForkedDb::new(
+        db_rw,
+        remote,
+        fork_config.version,
+        fork_config.cache_size,
+        &node_config.storage.dir(),
+    )?))
+}
+
 /// Creates a RocksDb checkpoint for the consensus_db, state_sync_db,
 /// ledger_db and state_merkle_db and saves it to the checkpoint_path.
 /// Also, changes the working directory to run the node on the new path,
@@ -106,8 +145,9 @@ pub fn initialize_database_and_checkpoints(
         node_config.storage.max_num_nodes_per_lru_cache_shard,
     )
     .map_err(|err| anyhow!("DB failed to open {}", err))?;
-    let (aptos_db, db_rw, backup_service) =
+    let (_aptos_db, db_rw, backup_service) =
         bootstrap_db(aptos_db, node_config.storage.backup_service_address);
+    let db_rw = maybe_fork_db(node_config, db_rw)?;
 
     // TODO: handle non-genesis waypoints for state sync!
     // If there's a genesis txn and waypoint, commit it if the result matches.
@@ -125,5 +165,10 @@ pub fn initialize_database_and_checkpoints(
         instant.elapsed().as_millis()
     );
 
-    Ok((aptos_db, db_rw, backup_service, genesis_waypoint))
+    Ok((
+        db_rw.reader.clone(),
+        db_rw,
+        backup_service,
+        genesis_waypoint,
+    ))
 }
@@ -2,7 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use anyhow::anyhow;
-use aptos_config::config::NodeConfig;
+use aptos_block_executor::execution_mode::{BlockExecutionMode, ExecutionModeSelectorConfig};
+use aptos_config::config::{
+    BlockExecutionMode as ConfigBlockExecutionMode, BlockExecutionModeConfig, NodeConfig,
+};
 use aptos_state_view::account_with_state_view::AsAccountWithStateView;
 use aptos_storage_interface::{state_view::LatestDbStateCheckpointView, DbReaderWriter};
 use aptos_types::{
@@ -50,9 +53,13 @@ pub fn fetch_chain_id(db: &DbReaderWriter) -> anyhow::Result<ChainId> {
 pub fn set_aptos_vm_configurations(node_config: &NodeConfig) {
     AptosVM::set_paranoid_type_checks(node_config.execution.paranoid_type_verification);
     AptosVM::set_concurrency_level_once(node_config.execution.concurrency_level as usize);
+    AptosVM::set_execution_mode_selector_config_once(execution_mode_selector_config(
+        &node_config.execution.block_execution_mode,
+    ));
     AptosVM::set_num_proof_reading_threads_once(
         node_config.execution.num_proof_reading_threads as usize,
     );
+    AptosVM::set_access_summary_hints_once(node_config.execution.use_access_summary_hints);
 
     if node_config
         .execution
@@ -61,3 +68,19 @@ pub fn set_aptos_vm_configurations(node_config: &NodeConfig) {
         AptosVM::set_processed_transactions_detailed_counters();
     }
 }
+
+fn execution_mode_selector_config(
+    config: &BlockExecutionModeConfig,
+) -> ExecutionModeSelectorConfig {
+    ExecutionModeSelectorConfig {
+        mode_override: match config.mode_override {
+            Some(ConfigBlockExecutionMode::Sequential) => Some(BlockExecutionMode::Sequential),
+            Some(ConfigBlockExecutionMode::Parallel) => Some(BlockExecutionMode::Parallel),
+            None if !config.enabled => Some(BlockExecutionMode::Parallel),
+            None => None,
+        },
+        window_size: config.sample_window_blocks as usize,
+        sequential_abort_rate: config.sequential_abort_rate_percent as f64 / 100.0,
+        probe_interval: config.probe_interval_blocks as usize,
+    }
+}
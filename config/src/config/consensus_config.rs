@@ -35,6 +35,16 @@ pub struct ConsensusConfig {
     pub round_initial_timeout_ms: u64,
     pub round_timeout_backoff_exponent_base: f64,
     pub round_timeout_backoff_max_exponent: usize,
+    // Whether the base round timeout adapts to the round latencies observed over a sliding
+    // window (instead of always being round_initial_timeout_ms)
+    pub round_timeout_adaptive_enabled: bool,
+    pub round_timeout_adaptive_min_ms: u64,
+    pub round_timeout_adaptive_max_ms: u64,
+    // The base round timeout is the average observed round latency times this multiplier
+    pub round_timeout_adaptive_latency_multiplier: f64,
+    pub round_timeout_adaptive_window_ms: u64,
+    // The minimum number of latencies in the window before the base round timeout is adapted
+    pub round_timeout_adaptive_min_num_latencies: usize,
     pub safety_rules: SafetyRulesConfig,
     // Only sync committed transactions but not vote for any pending blocks. This is useful when
     // validators coordinate on the latest version to apply a manual transaction.
@@ -114,6 +124,13 @@ impl Default for ConsensusConfig {
             // Timeout goes from initial_timeout to initial_timeout*3 in 6 steps
             round_timeout_backoff_exponent_base: 1.2,
             round_timeout_backoff_max_exponent: 6,
+            // disable adaptive round timeouts until fully tested
+            round_timeout_adaptive_enabled: false,
+            round_timeout_adaptive_min_ms: 1000,
+            round_timeout_adaptive_max_ms: 3000,
+            round_timeout_adaptive_latency_multiplier: 3.0,
+            round_timeout_adaptive_window_ms: 60_000, // 1 minute
+            round_timeout_adaptive_min_num_latencies: 20,
             safety_rules: SafetyRulesConfig::default(),
             sync_only: false,
             channel_size: 30, // hard-coded
@@ -283,6 +300,37 @@ impl ConsensusConfig {
         Ok(())
     }
 
+    fn sanitize_adaptive_round_timeout(
+        sanitizer_name: &str,
+        config: &ConsensusConfig,
+    ) -> Result<(), Error> {
+        if !config.round_timeout_adaptive_enabled {
+            return Ok(());
+        }
+
+        if config.round_timeout_adaptive_min_ms == 0
+            || config.round_timeout_adaptive_min_ms > config.round_timeout_adaptive_max_ms
+        {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name.to_owned(),
+                format!(
+                    "Invalid adaptive round timeout bounds: min {} ms, max {} ms",
+                    config.round_timeout_adaptive_min_ms, config.round_timeout_adaptive_max_ms
+                ),
+            ));
+        }
+        if config.round_timeout_adaptive_latency_multiplier < 1.0 {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name.to_owned(),
+                format!(
+                    "The adaptive round timeout latency multiplier must be >= 1, got {}",
+                    config.round_timeout_adaptive_latency_multiplier
+                ),
+            ));
+        }
+        Ok(())
+    }
+
     fn sanitize_batch_block_limits(
         sanitizer_name: &str,
         config: &ConsensusConfig,
@@ -373,6 +421,8 @@ impl ConfigSanitizer for ConsensusConfig {
         Self::sanitize_send_recv_block_limits(&sanitizer_name, &node_config.consensus)?;
         // Quorum store batches must be <= consensus blocks
         Self::sanitize_batch_block_limits(&sanitizer_name, &node_config.consensus)?;
+        // Adaptive round timeouts must have valid bounds
+        Self::sanitize_adaptive_round_timeout(&sanitizer_name, &node_config.consensus)?;
 
         Ok(())
     }
@@ -400,4 +450,36 @@ mod test {
 
         serde_yaml::from_str::<ConsensusConfig>(&s).unwrap();
     }
+
+    #[test]
+    fn test_adaptive_round_timeout_bounds() {
+        // Create a node config with inverted adaptive round timeout bounds
+        let mut node_config = NodeConfig {
+            consensus: ConsensusConfig {
+                round_timeout_adaptive_enabled: true,
+                round_timeout_adaptive_min_ms: 2000,
+                round_timeout_adaptive_max_ms: 1000,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Verify that the config fails sanitization
+        let error = ConsensusConfig::sanitize(
+            &mut node_config,
+            NodeType::ValidatorFullnode,
+            ChainId::testnet(),
+        )
+        .unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+
+        // Verify that the config passes sanitization when adaptive timeouts are disabled
+        node_config.consensus.round_timeout_adaptive_enabled = false;
+        ConsensusConfig::sanitize(
+            &mut node_config,
+            NodeType::ValidatorFullnode,
+            ChainId::testnet(),
+        )
+        .unwrap();
+    }
 }
@@ -0,0 +1,125 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::config::{
+    config_sanitizer::ConfigSanitizer, node_config_loader::NodeType, Error, NodeConfig,
+};
+use aptos_types::chain_id::ChainId;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConsensusObserverConfig {
+    /// Whether the node runs the observer (i.e., subscribes to ordered blocks and executes them)
+    pub observer_enabled: bool,
+    /// Whether the node runs the publisher (i.e., pushes ordered blocks to subscribers)
+    pub publisher_enabled: bool,
+
+    pub max_network_channel_size: u64, // Max num of pending network messages
+    pub max_num_pending_blocks: u64,   // Max num of ordered blocks buffered by the observer
+    pub max_num_subscribers: u64,      // Max num of peers the publisher will serve at once
+    pub max_num_subscriptions: u64,    // Max num of peers the observer will subscribe to at once
+    pub subscription_refresh_interval_ms: u64, // The interval (ms) between subscription checks
+    pub subscription_timeout_ms: u64, // The time (ms) without messages before a subscription is dropped
+}
+
+impl Default for ConsensusObserverConfig {
+    fn default() -> Self {
+        Self {
+            observer_enabled: false,
+            publisher_enabled: false,
+            max_network_channel_size: 1000,
+            max_num_pending_blocks: 100,
+            max_num_subscribers: 20,
+            max_num_subscriptions: 2,
+            subscription_refresh_interval_ms: 5_000, // 5 seconds
+            subscription_timeout_ms: 15_000,         // 15 seconds
+        }
+    }
+}
+
+impl ConfigSanitizer for ConsensusObserverConfig {
+    fn sanitize(
+        node_config: &mut NodeConfig,
+        node_type: NodeType,
+        _chain_id: ChainId,
+    ) -> Result<(), Error> {
+        let sanitizer_name = Self::get_sanitizer_name();
+        let observer_config = &node_config.consensus_observer;
+
+        // Validators already participate in consensus, so they cannot observe it
+        if node_type.is_validator() && observer_config.observer_enabled {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "The consensus observer cannot be enabled on validators!".into(),
+            ));
+        }
+
+        // The observer needs at least one subscription to make progress
+        if observer_config.observer_enabled && observer_config.max_num_subscriptions == 0 {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "The max number of subscriptions must be non-zero when the observer is enabled!"
+                    .into(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_observer_on_validator() {
+        // Create a node config with the observer enabled
+        let mut node_config = NodeConfig {
+            consensus_observer: ConsensusObserverConfig {
+                observer_enabled: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Verify that the config fails sanitization for validators
+        let error = ConsensusObserverConfig::sanitize(
+            &mut node_config,
+            NodeType::Validator,
+            ChainId::testnet(),
+        )
+        .unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+
+        // Verify that the config passes sanitization for fullnodes
+        ConsensusObserverConfig::sanitize(
+            &mut node_config,
+            NodeType::PublicFullnode,
+            ChainId::testnet(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_sanitize_zero_subscriptions() {
+        // Create a node config with the observer enabled and no subscriptions
+        let mut node_config = NodeConfig {
+            consensus_observer: ConsensusObserverConfig {
+                observer_enabled: true,
+                max_num_subscriptions: 0,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Verify that the config fails sanitization
+        let error = ConsensusObserverConfig::sanitize(
+            &mut node_config,
+            NodeType::ValidatorFullnode,
+            ChainId::testnet(),
+        )
+        .unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+    }
+}
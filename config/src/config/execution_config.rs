@@ -34,6 +34,51 @@ pub struct ExecutionConfig {
     pub paranoid_hot_potato_verification: bool,
     /// Enables enhanced metrics around processed transactions
     pub processed_transactions_detailed_counters: bool,
+    /// How blocks are executed: sequentially or in parallel
+    pub block_execution_mode: BlockExecutionModeConfig,
+    /// Schedules the transactions of blocks executed in parallel using the summaries of the
+    /// resources accessed by the entry functions they call, when published with their modules.
+    /// Disabled by default.
+    pub use_access_summary_hints: bool,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BlockExecutionMode {
+    Sequential,
+    Parallel,
+}
+
+/// If enabled, the execution mode of every block is picked from the rate of speculative aborts
+/// observed while executing the previous blocks in parallel. Both modes produce the same outputs,
+/// so nodes picking different modes still agree on the executed blocks.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BlockExecutionModeConfig {
+    /// Picks the mode of every block from the observed aborts. If disabled (the default), blocks
+    /// are executed in parallel unless `mode_override` is set
+    pub enabled: bool,
+    /// Executes all blocks in this mode, instead of picking it from the observed aborts
+    pub mode_override: Option<BlockExecutionMode>,
+    /// Number of most recent blocks executed in parallel the abort rate is averaged over
+    pub sample_window_blocks: u16,
+    /// Aborts per 100 transactions at or above which blocks are executed sequentially
+    pub sequential_abort_rate_percent: u16,
+    /// Number of blocks executed sequentially after which a block is executed in parallel
+    /// again, to sample the aborts of the current workload
+    pub probe_interval_blocks: u16,
+}
+
+impl Default for BlockExecutionModeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            mode_override: None,
+            sample_window_blocks: 10,
+            sequential_abort_rate_percent: 100,
+            probe_interval_blocks: 20,
+        }
+    }
 }
 
 impl std::fmt::Debug for ExecutionConfig {
@@ -63,6 +108,8 @@ impl Default for ExecutionConfig {
             paranoid_type_verification: true,
             paranoid_hot_potato_verification: true,
             processed_transactions_detailed_counters: false,
+            block_execution_mode: BlockExecutionModeConfig::default(),
+            use_access_summary_hints: false,
         }
     }
 }
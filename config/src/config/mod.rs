@@ -8,6 +8,7 @@ mod base_config;
 mod config_optimizer;
 mod config_sanitizer;
 mod consensus_config;
+mod consensus_observer_config;
 mod error;
 mod execution_config;
 mod gas_estimation_config;
@@ -33,6 +34,7 @@ mod utils;
 pub use api_config::*;
 pub use base_config::*;
 pub use consensus_config::*;
+pub use consensus_observer_config::*;
 pub use error::*;
 pub use execution_config::*;
 pub use gas_estimation_config::*;
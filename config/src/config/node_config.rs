@@ -4,10 +4,10 @@
 use crate::{
     config::{
         node_config_loader::NodeConfigLoader, persistable_config::PersistableConfig,
-        utils::RootPath, ApiConfig, BaseConfig, ConsensusConfig, Error, ExecutionConfig,
-        IndexerConfig, IndexerGrpcConfig, InspectionServiceConfig, LoggerConfig, MempoolConfig,
-        NetworkConfig, PeerMonitoringServiceConfig, SafetyRulesTestConfig, StateSyncConfig,
-        StorageConfig,
+        utils::RootPath, ApiConfig, BaseConfig, ConsensusConfig, ConsensusObserverConfig, Error,
+        ExecutionConfig, IndexerConfig, IndexerGrpcConfig, InspectionServiceConfig, LoggerConfig,
+        MempoolConfig, NetworkConfig, PeerMonitoringServiceConfig, SafetyRulesTestConfig,
+        StateSyncConfig, StorageConfig,
     },
     network_id::NetworkId,
 };
@@ -35,6 +35,8 @@ pub struct NodeConfig {
     #[serde(default)]
     pub consensus: ConsensusConfig,
     #[serde(default)]
+    pub consensus_observer: ConsensusObserverConfig,
+    #[serde(default)]
     pub execution: ExecutionConfig,
     #[serde(default)]
     pub failpoints: Option<HashMap<String, String>>,
@@ -48,6 +48,9 @@ pub struct QuorumStoreConfig {
     pub batch_generation_max_interval_ms: usize,
     pub sender_max_batch_txns: usize,
     pub sender_max_batch_bytes: usize,
+    /// Max number of txns from a single account in a batch created by this node. Once an account
+    /// reaches the limit, its remaining pulled txns are left in mempool for a later pull.
+    pub sender_max_batch_txns_per_account: usize,
     pub sender_max_num_batches: usize,
     pub sender_max_total_txns: usize,
     pub sender_max_total_bytes: usize,
@@ -69,6 +72,9 @@ pub struct QuorumStoreConfig {
     pub back_pressure: QuorumStoreBackPressureConfig,
     pub num_workers_for_remote_batches: usize,
     pub batch_buckets: Vec<u64>,
+    /// When the proof backlog is above the back pressure txn limit, pull proofs into blocks in
+    /// descending gas bucket order across all validators, instead of round-robin by validator.
+    pub proof_pull_by_gas_bucket_under_back_pressure: bool,
 }
 
 impl Default for QuorumStoreConfig {
@@ -81,6 +87,8 @@ impl Default for QuorumStoreConfig {
             batch_generation_max_interval_ms: 250,
             sender_max_batch_txns: 250,
             sender_max_batch_bytes: 1024 * 1024,
+            // same as sender_max_batch_txns, i.e., no per account limit by default
+            sender_max_batch_txns_per_account: 250,
             sender_max_num_batches: 20,
             sender_max_total_txns: 2000,
             sender_max_total_bytes: 4 * 1024 * 1024,
@@ -102,6 +110,7 @@ impl Default for QuorumStoreConfig {
             // number of batch coordinators to handle QS batch messages, should be >= 1
             num_workers_for_remote_batches: 10,
             batch_buckets: DEFAULT_BUCKETS.to_vec(),
+            proof_pull_by_gas_bucket_under_back_pressure: false,
         }
     }
 }
@@ -180,6 +189,19 @@ impl QuorumStoreConfig {
         }
         Ok(())
     }
+
+    fn sanitize_per_account_batch_limit(
+        sanitizer_name: &str,
+        config: &QuorumStoreConfig,
+    ) -> Result<(), Error> {
+        if config.sender_max_batch_txns_per_account == 0 {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name.to_owned(),
+                "Failed sender_max_batch_txns_per_account: must be positive".to_owned(),
+            ));
+        }
+        Ok(())
+    }
 }
 
 impl ConfigSanitizer for QuorumStoreConfig {
@@ -195,6 +217,10 @@ impl ConfigSanitizer for QuorumStoreConfig {
             &node_config.consensus.quorum_store,
         )?;
         Self::sanitize_batch_total_limits(&sanitizer_name, &node_config.consensus.quorum_store)?;
+        Self::sanitize_per_account_batch_limit(
+            &sanitizer_name,
+            &node_config.consensus.quorum_store,
+        )?;
         Ok(())
     }
 }
@@ -119,6 +119,45 @@ pub struct StorageConfig {
     /// since genesis. To recover operation after data loss, or to bootstrap a node in fast sync
     /// mode, the indexer db needs to be copied in from another node.
     pub enable_indexer: bool,
+    /// Fork the state of another network. State values that were never written locally are
+    /// lazily read from the forked network, as of the configured version. Test networks only.
+    pub fork: Option<ForkConfig>,
+}
+
+/// Where and as of which version a forked node reads the state it doesn't have locally.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ForkConfig {
+    pub source: ForkSource,
+    /// Version of the forked network to read state values at
+    pub version: u64,
+    /// Maximum number of state values read from the forked network that are cached in memory
+    #[serde(default = "default_fork_cache_size")]
+    pub cache_size: usize,
+}
+
+pub const DEFAULT_FORK_CACHE_SIZE: usize = 1024 * 1024;
+
+fn default_fork_cache_size() -> usize {
+    DEFAULT_FORK_CACHE_SIZE
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ForkSource {
+    /// REST API of a fullnode of the forked network
+    Rest { url: url::Url },
+    /// Local AptosDB of the forked network, e.g. restored from a backup
+    Db { path: PathBuf },
+}
+
+impl std::fmt::Display for ForkSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ForkSource::Rest { url } => write!(f, "REST API at {}", url),
+            ForkSource::Db { path } => write!(f, "DB at {}", path.display()),
+        }
+    }
 }
 
 pub const NO_OP_STORAGE_PRUNER_CONFIG: PrunerConfig = PrunerConfig {
@@ -267,6 +306,7 @@ impl Default for StorageConfig {
             enable_indexer: false,
             buffered_state_target_items: BUFFERED_STATE_TARGET_ITEMS,
             max_num_nodes_per_lru_cache_shard: DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+            fork: None,
         }
     }
 }
@@ -294,11 +334,18 @@ impl ConfigSanitizer for StorageConfig {
     fn sanitize(
         node_config: &mut NodeConfig,
         _node_type: NodeType,
-        _chain_id: ChainId,
+        chain_id: ChainId,
     ) -> Result<(), Error> {
         let sanitizer_name = Self::get_sanitizer_name();
         let config = &node_config.storage;
 
+        if config.fork.is_some() && (chain_id.is_mainnet() || chain_id.is_testnet()) {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "Forking the state of another network is only allowed on test networks!".into(),
+            ));
+        }
+
         let ledger_prune_window = config
             .storage_pruner_config
             .ledger_pruner_config
@@ -344,7 +391,11 @@ impl ConfigSanitizer for StorageConfig {
 
 #[cfg(test)]
 mod test {
-    use crate::config::PrunerConfig;
+    use crate::config::{
+        config_sanitizer::ConfigSanitizer, node_config_loader::NodeType, Error, ForkConfig,
+        ForkSource, NodeConfig, PrunerConfig, StorageConfig,
+    };
+    use aptos_types::chain_id::ChainId;
 
     #[test]
     pub fn test_default_prune_window() {
@@ -355,4 +406,31 @@ mod test {
         assert!(config.state_merkle_pruner_config.prune_window >= 100_000);
         assert!(config.epoch_snapshot_pruner_config.prune_window > 50_000_000);
     }
+
+    #[test]
+    fn test_sanitize_fork_on_mainnet() {
+        // Create a node config that forks the state of another network
+        let mut node_config = NodeConfig {
+            storage: StorageConfig {
+                fork: Some(ForkConfig {
+                    source: ForkSource::Db {
+                        path: "/opt/aptos/fork".into(),
+                    },
+                    version: 100,
+                    cache_size: DEFAULT_FORK_CACHE_SIZE,
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Sanitize the config and verify that it succeeds on a test network
+        StorageConfig::sanitize(&mut node_config, NodeType::Validator, ChainId::test()).unwrap();
+
+        // Verify that it fails on mainnet
+        let error =
+            StorageConfig::sanitize(&mut node_config, NodeType::Validator, ChainId::mainnet())
+                .unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+    }
 }
@@ -0,0 +1,445 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+use crate::{
+    counters::{NUM_SENDERS_IN_BLOCK, TXN_SHUFFLE_CONFLICTS_DEFERRED, TXN_SHUFFLE_SECONDS},
+    transaction_shuffler::TransactionShuffler,
+};
+use aptos_types::transaction::{
+    analyzed_transaction::{AnalyzedTransaction, StorageLocation},
+    SignedTransaction, Transaction,
+};
+use move_core_types::account_address::AccountAddress;
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet, VecDeque},
+};
+
+/// An implementation of transaction shuffler, which uses the read/write hints of an
+/// `AnalyzedTransaction` to spread conflicting transactions across the block. It works in two
+/// phases:
+/// 1. (Optional) Order transactions by descending gas unit price. Transactions from the same sender
+/// are never reordered relative to each other, so a sender's cheap transaction delays its own later
+/// expensive transactions, but not transactions from other senders.
+/// 2. Maintain a sliding window of the read/write hints of the last `conflict_window_size`
+/// transactions added to the block. A transaction conflicts with the window if it writes a location
+/// read or written in the window, or reads a location written in the window. Conflicting
+/// transactions are deferred until they no longer conflict, or until there is nothing else to add.
+///
+/// Every user transaction writes the sender's account resource (sequence number), so transactions
+/// from the same sender always conflict with each other, which makes this a strict generalization of
+/// the `SenderAwareShuffler`.
+pub struct ConflictAwareShuffler {
+    conflict_window_size: usize,
+    order_by_gas_price: bool,
+}
+
+impl TransactionShuffler for ConflictAwareShuffler {
+    fn shuffle(&self, txns: Vec<SignedTransaction>) -> Vec<SignedTransaction> {
+        let _timer = TXN_SHUFFLE_SECONDS.start_timer();
+
+        // Early return for performance reason if there are no transactions to shuffle
+        if txns.is_empty() {
+            return txns;
+        }
+
+        let txns = if self.order_by_gas_price {
+            Self::order_by_gas_price(txns)
+        } else {
+            txns
+        };
+
+        // handle the corner case of conflict window being 0, in which case we don't do any spreading
+        if self.conflict_window_size == 0 {
+            return txns;
+        }
+
+        self.spread_conflicts(txns)
+    }
+}
+
+impl ConflictAwareShuffler {
+    pub fn new(conflict_window_size: usize, order_by_gas_price: bool) -> Self {
+        Self {
+            conflict_window_size,
+            order_by_gas_price,
+        }
+    }
+
+    /// Merges the per-sender transaction queues by always picking the head with the highest gas
+    /// unit price, breaking ties by the original position in the block.
+    fn order_by_gas_price(txns: Vec<SignedTransaction>) -> Vec<SignedTransaction> {
+        let num_txns = txns.len();
+        let mut txns_by_sender: HashMap<AccountAddress, VecDeque<(usize, SignedTransaction)>> =
+            HashMap::new();
+        for (index, txn) in txns.into_iter().enumerate() {
+            txns_by_sender
+                .entry(txn.sender())
+                .or_insert_with(VecDeque::new)
+                .push_back((index, txn));
+        }
+
+        let mut heads = BinaryHeap::with_capacity(txns_by_sender.len());
+        for (sender, queue) in &txns_by_sender {
+            let (index, txn) = queue.front().expect("Queue must not be empty");
+            heads.push((txn.gas_unit_price(), Reverse(*index), *sender));
+        }
+
+        let mut ordered_txns = Vec::with_capacity(num_txns);
+        while let Some((_, _, sender)) = heads.pop() {
+            let queue = txns_by_sender
+                .get_mut(&sender)
+                .expect("Sender must have a queue");
+            let (_, txn) = queue.pop_front().expect("Queue must not be empty");
+            ordered_txns.push(txn);
+            if let Some((index, next_txn)) = queue.front() {
+                heads.push((next_txn.gas_unit_price(), Reverse(*index), sender));
+            }
+        }
+        ordered_txns
+    }
+
+    fn spread_conflicts(&self, txns: Vec<SignedTransaction>) -> Vec<SignedTransaction> {
+        let num_transactions = txns.len();
+        let mut window = ConflictWindow::new(self.conflict_window_size, num_transactions);
+        let mut pending_txns = PendingTransactions::new();
+        let mut orig_txns: VecDeque<AnalyzedTransaction> = txns
+            .into_iter()
+            .map(AnalyzedTransaction::analyzed_transaction_with_fallback_hints)
+            .collect();
+
+        while window.num_txns() < num_transactions {
+            // First try to find a pending transaction that doesn't conflict with the window any
+            // more. The scan is bounded by the window size to keep shuffling linear in block size.
+            if let Some(txn) =
+                pending_txns.remove_first_non_conflicting(&window, self.conflict_window_size)
+            {
+                window.add_transaction(txn);
+                continue;
+            }
+
+            // Then iterate through the original transactions and try to find the next candidate.
+            let mut candidate = None;
+            while let Some(txn) = orig_txns.pop_front() {
+                if !pending_txns.has_sender(&txn) && !window.has_conflict(&txn) {
+                    candidate = Some(txn);
+                    break;
+                }
+                TXN_SHUFFLE_CONFLICTS_DEFERRED.inc();
+                pending_txns.add_transaction(txn);
+            }
+
+            // Lastly, add the first pending transaction in order if no other candidate is found.
+            let txn = candidate.unwrap_or_else(|| {
+                pending_txns
+                    .remove_first_pending()
+                    .expect("Pending transactions must not be empty")
+            });
+            window.add_transaction(txn);
+        }
+        window.finalize()
+    }
+}
+
+fn user_txn_sender(txn: &AnalyzedTransaction) -> AccountAddress {
+    txn.sender().expect("Only user transactions are shuffled")
+}
+
+/// Transactions deferred because they conflicted with the window at the time they were considered,
+/// kept in the order they were deferred in. For a particular sender, only the earliest pending
+/// transaction is eligible to be added to the block, which preserves the per-sender ordering.
+struct PendingTransactions {
+    txns: VecDeque<AnalyzedTransaction>,
+    num_pending_by_sender: HashMap<AccountAddress, usize>,
+}
+
+impl PendingTransactions {
+    pub fn new() -> Self {
+        Self {
+            txns: VecDeque::new(),
+            num_pending_by_sender: HashMap::new(),
+        }
+    }
+
+    pub fn add_transaction(&mut self, txn: AnalyzedTransaction) {
+        *self
+            .num_pending_by_sender
+            .entry(user_txn_sender(&txn))
+            .or_insert(0) += 1;
+        self.txns.push_back(txn);
+    }
+
+    pub fn has_sender(&self, txn: &AnalyzedTransaction) -> bool {
+        self.num_pending_by_sender
+            .get(&user_txn_sender(txn))
+            .map_or(false, |count| *count != 0)
+    }
+
+    pub fn remove_first_non_conflicting(
+        &mut self,
+        window: &ConflictWindow,
+        max_scan: usize,
+    ) -> Option<AnalyzedTransaction> {
+        let mut senders_seen = HashSet::new();
+        let position = self.txns.iter().take(max_scan).position(|txn| {
+            // A later transaction of an already seen sender must wait for the earlier one.
+            senders_seen.insert(user_txn_sender(txn)) && !window.has_conflict(txn)
+        })?;
+        self.remove_at(position)
+    }
+
+    pub fn remove_first_pending(&mut self) -> Option<AnalyzedTransaction> {
+        self.remove_at(0)
+    }
+
+    fn remove_at(&mut self, position: usize) -> Option<AnalyzedTransaction> {
+        let txn = self.txns.remove(position)?;
+        self.num_pending_by_sender
+            .entry(user_txn_sender(&txn))
+            .and_modify(|count| *count -= 1);
+        Some(txn)
+    }
+}
+
+/// Sliding window over the read/write hints of the last `window_size` transactions added to the
+/// block.
+struct ConflictWindow {
+    window_size: usize,
+    reads_in_window: HashMap<StorageLocation, usize>,
+    writes_in_window: HashMap<StorageLocation, usize>,
+    senders: HashSet<AccountAddress>,
+    // Partially ordered transactions, needs to be updated every time add_transaction is called.
+    txns: Vec<AnalyzedTransaction>,
+}
+
+impl ConflictWindow {
+    pub fn new(window_size: usize, num_txns: usize) -> Self {
+        Self {
+            window_size,
+            reads_in_window: HashMap::new(),
+            writes_in_window: HashMap::new(),
+            senders: HashSet::new(),
+            txns: Vec::with_capacity(num_txns),
+        }
+    }
+
+    pub fn add_transaction(&mut self, txn: AnalyzedTransaction) {
+        if self.txns.len() >= self.window_size {
+            let dropped = &self.txns[self.txns.len() - self.window_size];
+            Self::remove_locations(&mut self.reads_in_window, dropped.read_hints());
+            Self::remove_locations(&mut self.writes_in_window, dropped.write_hints());
+        }
+        Self::add_locations(&mut self.reads_in_window, txn.read_hints());
+        Self::add_locations(&mut self.writes_in_window, txn.write_hints());
+        self.senders.insert(user_txn_sender(&txn));
+        self.txns.push(txn);
+    }
+
+    pub fn has_conflict(&self, txn: &AnalyzedTransaction) -> bool {
+        let in_window = |map: &HashMap<StorageLocation, usize>, location: &StorageLocation| {
+            map.get(location).map_or(false, |count| *count != 0)
+        };
+        txn.write_hints().iter().any(|location| {
+            in_window(&self.writes_in_window, location)
+                || in_window(&self.reads_in_window, location)
+        }) || txn
+            .read_hints()
+            .iter()
+            .any(|location| in_window(&self.writes_in_window, location))
+    }
+
+    pub fn num_txns(&self) -> usize {
+        self.txns.len()
+    }
+
+    pub fn finalize(self) -> Vec<SignedTransaction> {
+        NUM_SENDERS_IN_BLOCK.set(self.senders.len() as f64);
+        self.txns
+            .into_iter()
+            .map(|txn| match txn.into_txn() {
+                Transaction::UserTransaction(signed_txn) => signed_txn,
+                _ => unreachable!("Only user transactions are shuffled"),
+            })
+            .collect()
+    }
+
+    fn add_locations(map: &mut HashMap<StorageLocation, usize>, locations: &[StorageLocation]) {
+        for location in locations {
+            *map.entry(location.clone()).or_insert(0) += 1;
+        }
+    }
+
+    fn remove_locations(map: &mut HashMap<StorageLocation, usize>, locations: &[StorageLocation]) {
+        for location in locations {
+            if let Some(count) = map.get_mut(location) {
+                *count -= 1;
+                if *count == 0 {
+                    map.remove(location);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        conflict_aware_shuffler::ConflictAwareShuffler, transaction_shuffler::TransactionShuffler,
+    };
+    use aptos_crypto::{ed25519::Ed25519PrivateKey, PrivateKey, SigningKey, Uniform};
+    use aptos_types::{
+        chain_id::ChainId,
+        transaction::{EntryFunction, RawTransaction, SignedTransaction, TransactionPayload},
+    };
+    use move_core_types::{account_address::AccountAddress, ident_str, language_storage::ModuleId};
+    use rand::{rngs::OsRng, Rng};
+    use std::collections::HashMap;
+
+    fn create_transfers(
+        receiver: AccountAddress,
+        gas_unit_prices: &[u64],
+    ) -> Vec<SignedTransaction> {
+        let private_key = Ed25519PrivateKey::generate_for_testing();
+        let public_key = private_key.public_key();
+        let sender = AccountAddress::random();
+
+        gas_unit_prices
+            .iter()
+            .enumerate()
+            .map(|(i, gas_unit_price)| {
+                let transaction_payload = TransactionPayload::EntryFunction(EntryFunction::new(
+                    ModuleId::new(AccountAddress::ONE, ident_str!("coin").to_owned()),
+                    ident_str!("transfer").to_owned(),
+                    vec![],
+                    vec![
+                        bcs::to_bytes(&receiver).unwrap(),
+                        bcs::to_bytes(&1u64).unwrap(),
+                    ],
+                ));
+                let raw_transaction = RawTransaction::new(
+                    sender,
+                    i as u64,
+                    transaction_payload,
+                    0,
+                    *gas_unit_price,
+                    0,
+                    ChainId::new(10),
+                );
+                SignedTransaction::new(
+                    raw_transaction.clone(),
+                    public_key.clone(),
+                    private_key.sign(&raw_transaction).unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    fn group_by_sender(
+        txns: Vec<SignedTransaction>,
+    ) -> HashMap<AccountAddress, Vec<SignedTransaction>> {
+        let mut txns_by_sender = HashMap::new();
+        for txn in txns {
+            txns_by_sender
+                .entry(txn.sender())
+                .or_insert_with(Vec::new)
+                .push(txn);
+        }
+        txns_by_sender
+    }
+
+    #[test]
+    fn test_single_sender_order_unchanged() {
+        for num_txns in [1, 5, 50] {
+            let txns = create_transfers(AccountAddress::random(), &vec![100; num_txns]);
+            let txn_shuffler = ConflictAwareShuffler::new(10, true);
+            assert_eq!(txn_shuffler.shuffle(txns.clone()), txns);
+        }
+    }
+
+    #[test]
+    fn test_non_conflicting_order_unchanged() {
+        let mut txns = Vec::new();
+        for _ in 0..50 {
+            txns.extend(create_transfers(AccountAddress::random(), &[100]));
+        }
+        let txn_shuffler = ConflictAwareShuffler::new(10, true);
+        assert_eq!(txn_shuffler.shuffle(txns.clone()), txns);
+    }
+
+    #[test]
+    // S1 -> R1, S2 -> R1, S3 -> R2, S4 -> R3
+    // with conflict_window_size=1, the transfers to the hot receiver R1 are spread apart:
+    // S1 -> R1, S3 -> R2, S2 -> R1, S4 -> R3
+    fn test_hot_receiver_spreading() {
+        let hot_receiver = AccountAddress::random();
+        let txn1 = create_transfers(hot_receiver, &[100]);
+        let txn2 = create_transfers(hot_receiver, &[100]);
+        let txn3 = create_transfers(AccountAddress::random(), &[100]);
+        let txn4 = create_transfers(AccountAddress::random(), &[100]);
+        let orig_txns = [&txn1, &txn2, &txn3, &txn4]
+            .into_iter()
+            .flatten()
+            .cloned()
+            .collect();
+        let txn_shuffler = ConflictAwareShuffler::new(1, false);
+        let optimized_txns = txn_shuffler.shuffle(orig_txns);
+        assert_eq!(optimized_txns, vec![
+            txn1[0].clone(),
+            txn3[0].clone(),
+            txn2[0].clone(),
+            txn4[0].clone()
+        ]);
+    }
+
+    #[test]
+    fn test_gas_price_ordering() {
+        let cheap = create_transfers(AccountAddress::random(), &[100, 100]);
+        let expensive = create_transfers(AccountAddress::random(), &[1000, 1000]);
+        let mut orig_txns = cheap.clone();
+        orig_txns.extend(expensive.clone());
+
+        let txn_shuffler = ConflictAwareShuffler::new(0, true);
+        let optimized_txns = txn_shuffler.shuffle(orig_txns);
+        assert_eq!(optimized_txns, vec![
+            expensive[0].clone(),
+            expensive[1].clone(),
+            cheap[0].clone(),
+            cheap[1].clone()
+        ]);
+    }
+
+    #[test]
+    fn test_gas_price_ordering_keeps_sender_order() {
+        // A later transaction of the same sender with a higher gas price cannot jump ahead.
+        let sender_txns = create_transfers(AccountAddress::random(), &[100, 1000]);
+        let other = create_transfers(AccountAddress::random(), &[500]);
+        let mut orig_txns = sender_txns.clone();
+        orig_txns.extend(other.clone());
+
+        let txn_shuffler = ConflictAwareShuffler::new(0, true);
+        let optimized_txns = txn_shuffler.shuffle(orig_txns);
+        assert_eq!(optimized_txns, vec![
+            other[0].clone(),
+            sender_txns[0].clone(),
+            sender_txns[1].clone()
+        ]);
+    }
+
+    #[test]
+    fn test_random_shuffling_preserves_sender_order() {
+        let mut rng = OsRng;
+        let hot_receivers: Vec<_> = (0..3).map(|_| AccountAddress::random()).collect();
+        let mut orig_txns = Vec::new();
+        for _ in 0..rng.gen_range(1, 50) {
+            let receiver = hot_receivers[rng.gen_range(0, hot_receivers.len())];
+            let gas_unit_prices: Vec<_> = (0..rng.gen_range(1, 20))
+                .map(|_| rng.gen_range(100, 1000))
+                .collect();
+            orig_txns.extend(create_transfers(receiver, &gas_unit_prices));
+        }
+
+        let txn_shuffler = ConflictAwareShuffler::new(8, true);
+        let optimized_txns = txn_shuffler.shuffle(orig_txns.clone());
+        assert_eq!(orig_txns.len(), optimized_txns.len());
+        assert_eq!(group_by_sender(orig_txns), group_by_sender(optimized_txns));
+    }
+}
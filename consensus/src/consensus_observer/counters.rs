@@ -0,0 +1,65 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_metrics_core::{
+    register_int_counter, register_int_counter_vec, register_int_gauge, IntCounter, IntCounterVec,
+    IntGauge,
+};
+use once_cell::sync::Lazy;
+
+pub const RECEIVED_LABEL: &str = "received";
+pub const SENT_LABEL: &str = "sent";
+pub const SEND_FAILED_LABEL: &str = "send_failed";
+
+/// Count of the consensus observer messages, by direction and message type
+pub static OBSERVER_MESSAGES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_consensus_observer_messages",
+        "Count of the consensus observer messages, by direction and message type",
+        &["direction", "message_type"]
+    )
+    .unwrap()
+});
+
+/// Number of peers currently subscribed to this node's consensus publisher
+pub static PUBLISHER_NUM_SUBSCRIBERS: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_consensus_publisher_num_subscribers",
+        "Number of peers currently subscribed to this node's consensus publisher"
+    )
+    .unwrap()
+});
+
+/// Number of peers this node's consensus observer is currently subscribed to
+pub static OBSERVER_NUM_SUBSCRIPTIONS: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_consensus_observer_num_subscriptions",
+        "Number of peers this node's consensus observer is currently subscribed to"
+    )
+    .unwrap()
+});
+
+/// Number of blocks executed speculatively by the consensus observer
+pub static OBSERVER_EXECUTED_BLOCKS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_consensus_observer_executed_blocks",
+        "Number of blocks executed speculatively by the consensus observer"
+    )
+    .unwrap()
+});
+
+/// Number of times the consensus observer fell back to state sync
+pub static OBSERVER_STATE_SYNC_FALLBACKS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_consensus_observer_state_sync_fallbacks",
+        "Number of times the consensus observer fell back to state sync"
+    )
+    .unwrap()
+});
+
+/// Increments the message counter for the given direction and message type
+pub fn increment_message_counter(direction: &str, message_type: &str) {
+    OBSERVER_MESSAGES
+        .with_label_values(&[direction, message_type])
+        .inc();
+}
@@ -0,0 +1,16 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! The consensus observer lets fullnodes follow consensus with low latency: validators
+//! (and optionally fullnodes) publish ordered blocks and commit decisions to subscribed
+//! observers, which execute the blocks speculatively and commit them once the commit
+//! decision arrives, falling back to state sync whenever they fall behind.
+
+mod counters;
+/// The messages exchanged between consensus publishers and observers
+pub mod network_message;
+pub(crate) mod observer;
+/// The publisher that pushes ordered blocks and commit decisions to observers
+pub mod publisher;
+#[cfg(test)]
+mod test_utils;
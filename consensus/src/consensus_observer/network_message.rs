@@ -0,0 +1,448 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::quorum_store::types::BatchPayload;
+use anyhow::ensure;
+use aptos_consensus_types::{block::Block, common::Payload};
+use aptos_crypto::hash::CryptoHash;
+use aptos_types::{
+    epoch_change::EpochChangeProof, epoch_state::EpochState, ledger_info::LedgerInfoWithSignatures,
+    transaction::SignedTransaction,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+/// Types of messages that can be exchanged between consensus publishers and observers
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum ConsensusObserverMessage {
+    /// Sent by an observer to start receiving ordered blocks and commit decisions
+    Subscribe,
+    /// Sent by an observer to stop receiving ordered blocks and commit decisions
+    Unsubscribe,
+    /// A chain of ordered (but not yet committed) blocks, alongside their transactions
+    OrderedBlock(OrderedBlock),
+    /// A commit decision for previously ordered blocks
+    CommitDecision(CommitDecision),
+    /// Sent by an observer to request the epoch-ending ledger infos for
+    /// the epochs [start_epoch, end_epoch) (e.g., to verify a commit
+    /// decision from a future epoch).
+    EpochChangeProofRequest(EpochChangeProofRequest),
+    /// The epoch-ending ledger infos requested by an observer
+    EpochChangeProof(EpochChangeProof),
+}
+
+impl ConsensusObserverMessage {
+    /// Returns a summary label for the message (e.g., for logging and metrics)
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            ConsensusObserverMessage::Subscribe => "subscribe",
+            ConsensusObserverMessage::Unsubscribe => "unsubscribe",
+            ConsensusObserverMessage::OrderedBlock(_) => "ordered_block",
+            ConsensusObserverMessage::CommitDecision(_) => "commit_decision",
+            ConsensusObserverMessage::EpochChangeProofRequest(_) => "epoch_change_proof_request",
+            ConsensusObserverMessage::EpochChangeProof(_) => "epoch_change_proof",
+        }
+    }
+}
+
+impl Display for ConsensusObserverMessage {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            ConsensusObserverMessage::OrderedBlock(ordered_block) => write!(
+                f,
+                "OrderedBlock [epoch: {}, round: {}, num blocks: {}]",
+                ordered_block.ordered_proof.ledger_info().epoch(),
+                ordered_block.ordered_proof.ledger_info().round(),
+                ordered_block.blocks.len()
+            ),
+            ConsensusObserverMessage::CommitDecision(commit_decision) => write!(
+                f,
+                "CommitDecision [epoch: {}, round: {}]",
+                commit_decision.commit_proof.ledger_info().epoch(),
+                commit_decision.commit_proof.ledger_info().round()
+            ),
+            ConsensusObserverMessage::EpochChangeProofRequest(request) => write!(
+                f,
+                "EpochChangeProofRequest [start epoch: {}, end epoch: {}]",
+                request.start_epoch, request.end_epoch
+            ),
+            ConsensusObserverMessage::EpochChangeProof(proof) => write!(
+                f,
+                "EpochChangeProof [num ledger infos: {}]",
+                proof.ledger_info_with_sigs.len()
+            ),
+            message => write!(f, "{}", message.get_label()),
+        }
+    }
+}
+
+/// A block and the transactions it contains. The transactions are shipped
+/// alongside the block because observers cannot fetch quorum store batches.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct BlockWithTransactions {
+    /// The ordered block
+    pub block: Block,
+    /// The transactions in the block payload (before deduplication and shuffling)
+    pub transactions: Vec<SignedTransaction>,
+}
+
+impl BlockWithTransactions {
+    /// Creates a new block with transactions
+    pub fn new(block: Block, transactions: Vec<SignedTransaction>) -> Self {
+        Self {
+            block,
+            transactions,
+        }
+    }
+
+    /// Verifies that the transactions are exactly the ones referenced by the block
+    /// payload. For quorum store payloads, the transactions of each (non-expired)
+    /// proof must hash to the digest of the batch that was certified.
+    pub fn verify_transactions(&self) -> anyhow::Result<()> {
+        match self.block.payload() {
+            None => ensure!(
+                self.transactions.is_empty(),
+                "Block {} has no payload, but {} transactions were sent!",
+                self.block.id(),
+                self.transactions.len()
+            ),
+            Some(Payload::DirectMempool(transactions)) => ensure!(
+                transactions == &self.transactions,
+                "The transactions do not match the payload of block {}!",
+                self.block.id()
+            ),
+            Some(Payload::InQuorumStore(proof_with_data)) => {
+                // Expired proofs are skipped by the payload manager, so they carry no transactions
+                let block_timestamp = self.block.timestamp_usecs();
+                let mut remaining_transactions = self.transactions.as_slice();
+                for proof in proof_with_data
+                    .proofs
+                    .iter()
+                    .filter(|proof| block_timestamp <= proof.expiration())
+                {
+                    let num_txns = proof.num_txns() as usize;
+                    ensure!(
+                        remaining_transactions.len() >= num_txns,
+                        "Missing transactions for batch {} in block {}!",
+                        proof.digest(),
+                        self.block.id()
+                    );
+                    let (batch_transactions, rest) = remaining_transactions.split_at(num_txns);
+                    let batch_digest =
+                        BatchPayload::new(proof.author(), batch_transactions.to_vec()).hash();
+                    ensure!(
+                        batch_digest == *proof.digest(),
+                        "The transactions do not match batch {} in block {}!",
+                        proof.digest(),
+                        self.block.id()
+                    );
+                    remaining_transactions = rest;
+                }
+                ensure!(
+                    remaining_transactions.is_empty(),
+                    "Block {} has {} unexpected transactions!",
+                    self.block.id(),
+                    remaining_transactions.len()
+                );
+            },
+        }
+        Ok(())
+    }
+}
+
+/// A chain of ordered blocks and the proof that they were ordered
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct OrderedBlock {
+    /// The ordered blocks (from oldest to newest)
+    pub blocks: Vec<BlockWithTransactions>,
+    /// The ordering proof for the last block in the chain
+    pub ordered_proof: LedgerInfoWithSignatures,
+}
+
+impl OrderedBlock {
+    /// Verifies that the blocks form a chain ending in the ordered proof, that the
+    /// ordered proof is signed by the given epoch's validators, and that the
+    /// transactions of each block match its payload.
+    pub fn verify(&self, epoch_state: &EpochState) -> anyhow::Result<()> {
+        ensure!(!self.blocks.is_empty(), "Ordered block has no blocks!");
+        for pair in self.blocks.windows(2) {
+            ensure!(
+                pair[0].block.id() == pair[1].block.parent_id(),
+                "Ordered blocks do not form a chain! Block {} is not the parent of block {}",
+                pair[0].block.id(),
+                pair[1].block.id()
+            );
+        }
+
+        let last_block = &self
+            .blocks
+            .last()
+            .expect("Blocks should not be empty!")
+            .block;
+        let ordered_info = self.ordered_proof.commit_info();
+        ensure!(
+            last_block.id() == ordered_info.id(),
+            "The last block {} does not match the ordered proof {}",
+            last_block.id(),
+            ordered_info.id()
+        );
+        verify_ledger_info(&self.ordered_proof, epoch_state)?;
+        for block in &self.blocks {
+            block.verify_transactions()?;
+        }
+        Ok(())
+    }
+}
+
+/// The commit decision for previously ordered blocks
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CommitDecision {
+    /// The commit proof (i.e., the ledger info signed by a quorum of validators)
+    pub commit_proof: LedgerInfoWithSignatures,
+}
+
+impl CommitDecision {
+    /// Verifies that the commit proof is signed by the given epoch's validators
+    pub fn verify(&self, epoch_state: &EpochState) -> anyhow::Result<()> {
+        verify_ledger_info(&self.commit_proof, epoch_state)
+    }
+}
+
+/// A request for the epoch-ending ledger infos of the epochs [start_epoch, end_epoch)
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct EpochChangeProofRequest {
+    pub start_epoch: u64,
+    pub end_epoch: u64,
+}
+
+/// Verifies the ledger info belongs to the given epoch and carries a valid quorum signature
+fn verify_ledger_info(
+    ledger_info: &LedgerInfoWithSignatures,
+    epoch_state: &EpochState,
+) -> anyhow::Result<()> {
+    ensure!(
+        ledger_info.ledger_info().epoch() == epoch_state.epoch,
+        "Ledger info epoch {} does not match the current epoch {}",
+        ledger_info.ledger_info().epoch(),
+        epoch_state.epoch
+    );
+    ledger_info
+        .verify_signatures(&epoch_state.verifier)
+        .map_err(|error| anyhow::anyhow!("Invalid ledger info signatures: {:?}", error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        consensus_observer::test_utils::{create_block, create_ledger_info, create_transactions},
+        quorum_store::types::Batch,
+    };
+    use aptos_consensus_types::{
+        common::ProofWithData,
+        proof_of_store::{BatchId, ProofOfStore},
+    };
+    use aptos_crypto::HashValue;
+    use aptos_types::{
+        aggregate_signature::AggregateSignature, block_info::BlockInfo,
+        validator_verifier::random_validator_verifier, PeerId,
+    };
+
+    #[test]
+    fn test_verify_ordered_block() {
+        // Create the epoch state and a block
+        let (signers, verifier) = random_validator_verifier(4, None, false);
+        let block = Block::make_genesis_block();
+        let epoch_state = EpochState {
+            epoch: block.epoch(),
+            verifier: verifier.clone(),
+        };
+
+        // Verify that a correctly signed ordered block passes verification
+        let ordered_proof = create_ledger_info(
+            &signers,
+            &verifier,
+            block.gen_block_info(HashValue::zero(), 0, None),
+        );
+        let ordered_block = OrderedBlock {
+            blocks: vec![BlockWithTransactions::new(block.clone(), vec![])],
+            ordered_proof,
+        };
+        ordered_block.verify(&epoch_state).unwrap();
+
+        // Verify that an ordered block with no blocks fails verification
+        let empty_ordered_block = OrderedBlock {
+            blocks: vec![],
+            ordered_proof: ordered_block.ordered_proof.clone(),
+        };
+        empty_ordered_block.verify(&epoch_state).unwrap_err();
+
+        // Verify that a proof for a different block fails verification
+        let mismatched_proof = create_ledger_info(
+            &signers,
+            &verifier,
+            BlockInfo::random_with_epoch(block.epoch(), block.round()),
+        );
+        let mismatched_ordered_block = OrderedBlock {
+            blocks: ordered_block.blocks.clone(),
+            ordered_proof: mismatched_proof,
+        };
+        mismatched_ordered_block.verify(&epoch_state).unwrap_err();
+
+        // Verify that a proof from a different epoch fails verification
+        let next_epoch_state = EpochState {
+            epoch: block.epoch() + 1,
+            verifier,
+        };
+        ordered_block.verify(&next_epoch_state).unwrap_err();
+    }
+
+    #[test]
+    fn test_verify_commit_decision() {
+        // Create the epoch state and a commit decision
+        let (signers, verifier) = random_validator_verifier(4, None, false);
+        let epoch = 10;
+        let commit_decision = CommitDecision {
+            commit_proof: create_ledger_info(
+                &signers,
+                &verifier,
+                BlockInfo::random_with_epoch(epoch, 5),
+            ),
+        };
+
+        // Verify that the commit decision passes verification
+        let epoch_state = EpochState {
+            epoch,
+            verifier: verifier.clone(),
+        };
+        commit_decision.verify(&epoch_state).unwrap();
+
+        // Verify that a commit decision without a quorum fails verification
+        let insufficient_commit_decision = CommitDecision {
+            commit_proof: create_ledger_info(
+                &signers[0..1],
+                &verifier,
+                BlockInfo::random_with_epoch(epoch, 6),
+            ),
+        };
+        insufficient_commit_decision
+            .verify(&epoch_state)
+            .unwrap_err();
+    }
+
+    #[test]
+    fn test_verify_direct_mempool_transactions() {
+        // Create a block with a direct mempool payload
+        let transactions = create_transactions(3);
+        let genesis_block = Block::make_genesis_block();
+        let block = create_block(
+            &genesis_block,
+            1,
+            1,
+            Payload::DirectMempool(transactions.clone()),
+        );
+
+        // Verify that the payload transactions pass verification
+        BlockWithTransactions::new(block.clone(), transactions.clone())
+            .verify_transactions()
+            .unwrap();
+
+        // Verify that missing, reordered and forged transactions fail verification
+        BlockWithTransactions::new(block.clone(), transactions[..2].to_vec())
+            .verify_transactions()
+            .unwrap_err();
+        let mut reordered_transactions = transactions.clone();
+        reordered_transactions.swap(0, 1);
+        BlockWithTransactions::new(block.clone(), reordered_transactions)
+            .verify_transactions()
+            .unwrap_err();
+        let mut forged_transactions = transactions;
+        forged_transactions[2] = create_transactions(1).remove(0);
+        BlockWithTransactions::new(block, forged_transactions)
+            .verify_transactions()
+            .unwrap_err();
+
+        // Verify that transactions for a block without a payload fail verification
+        BlockWithTransactions::new(genesis_block.clone(), vec![])
+            .verify_transactions()
+            .unwrap();
+        BlockWithTransactions::new(genesis_block, create_transactions(1))
+            .verify_transactions()
+            .unwrap_err();
+    }
+
+    #[test]
+    fn test_verify_quorum_store_transactions() {
+        // Create three batches (the second expires before the block timestamp)
+        let author = PeerId::random();
+        let block_timestamp = 50;
+        let batches: Vec<_> = [(1, 2, 100), (2, 1, 10), (3, 3, 100)]
+            .into_iter()
+            .map(|(batch_id, num_txns, expiration)| {
+                Batch::new(
+                    BatchId::new_for_test(batch_id),
+                    create_transactions(num_txns),
+                    1,
+                    expiration,
+                    author,
+                    0,
+                )
+            })
+            .collect();
+        let proofs = batches
+            .iter()
+            .map(|batch| ProofOfStore::new(batch.batch_info().clone(), AggregateSignature::empty()))
+            .collect();
+        let block = create_block(
+            &Block::make_genesis_block(),
+            1,
+            block_timestamp,
+            Payload::InQuorumStore(ProofWithData::new(proofs)),
+        );
+        let batch_transactions: Vec<_> = batches
+            .into_iter()
+            .map(|batch| batch.into_transactions())
+            .collect();
+
+        // Verify that the transactions of the non-expired batches pass verification
+        let transactions: Vec<_> = [&batch_transactions[0], &batch_transactions[2]]
+            .into_iter()
+            .flatten()
+            .cloned()
+            .collect();
+        BlockWithTransactions::new(block.clone(), transactions.clone())
+            .verify_transactions()
+            .unwrap();
+
+        // Verify that the transactions of the expired batch fail verification
+        let all_transactions = batch_transactions.concat();
+        BlockWithTransactions::new(block.clone(), all_transactions)
+            .verify_transactions()
+            .unwrap_err();
+
+        // Verify that missing and additional transactions fail verification
+        BlockWithTransactions::new(block.clone(), transactions[..4].to_vec())
+            .verify_transactions()
+            .unwrap_err();
+        let mut additional_transactions = transactions.clone();
+        additional_transactions.extend(create_transactions(1));
+        BlockWithTransactions::new(block.clone(), additional_transactions)
+            .verify_transactions()
+            .unwrap_err();
+
+        // Verify that reordered batches and forged transactions fail verification
+        let reordered_transactions = [&batch_transactions[2], &batch_transactions[0]]
+            .into_iter()
+            .flatten()
+            .cloned()
+            .collect();
+        BlockWithTransactions::new(block.clone(), reordered_transactions)
+            .verify_transactions()
+            .unwrap_err();
+        let mut forged_transactions = transactions;
+        forged_transactions[0] = create_transactions(1).remove(0);
+        BlockWithTransactions::new(block, forged_transactions)
+            .verify_transactions()
+            .unwrap_err();
+    }
+}
@@ -0,0 +1,1117 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    consensus_observer::{
+        counters,
+        network_message::{
+            BlockWithTransactions, CommitDecision, ConsensusObserverMessage,
+            EpochChangeProofRequest, OrderedBlock,
+        },
+        publisher::ConsensusPublisher,
+    },
+    error::StateSyncError,
+    payload_manager::PayloadManager,
+    state_computer::ExecutionProxy,
+    state_replication::StateComputer,
+    transaction_deduper::create_transaction_deduper,
+    transaction_shuffler::create_transaction_shuffler,
+};
+use aptos_config::{
+    config::ConsensusObserverConfig,
+    network_id::{NetworkId, PeerNetworkId},
+};
+use aptos_consensus_types::{block::Block, common::Round, executed_block::ExecutedBlock};
+use aptos_crypto::HashValue;
+use aptos_event_notifications::ReconfigNotificationListener;
+use aptos_infallible::Mutex;
+use aptos_logger::prelude::*;
+use aptos_netcore::transport::ConnectionOrigin;
+use aptos_network::{
+    application::interface::{NetworkClient, NetworkClientInterface, NetworkServiceEvents},
+    protocols::network::Event,
+};
+use aptos_storage_interface::DbReader;
+use aptos_types::{
+    epoch_change::EpochChangeProof,
+    epoch_state::EpochState,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    on_chain_config::{OnChainConfigProvider, OnChainExecutionConfig, ValidatorSet},
+    transaction::SignedTransaction,
+};
+use futures::{
+    channel::mpsc::{self, UnboundedReceiver, UnboundedSender},
+    stream::select_all,
+    StreamExt,
+};
+use rand::seq::SliceRandom;
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// The block that the next ordered block must extend
+#[derive(Clone, Copy, Debug)]
+struct OrderedTip {
+    epoch: u64,
+    round: Round,
+    block_id: HashValue,
+}
+
+impl OrderedTip {
+    /// Returns the tip for the given committed ledger info. If the ledger info
+    /// ends the epoch, the next block extends the genesis block of the new epoch.
+    fn from_committed(ledger_info: &LedgerInfo) -> Self {
+        if ledger_info.ends_epoch() {
+            let genesis_block = Block::make_genesis_block_from_ledger_info(ledger_info);
+            Self {
+                epoch: genesis_block.epoch(),
+                round: genesis_block.round(),
+                block_id: genesis_block.id(),
+            }
+        } else {
+            Self {
+                epoch: ledger_info.epoch(),
+                round: ledger_info.round(),
+                block_id: ledger_info.consensus_block_id(),
+            }
+        }
+    }
+}
+
+/// The action to take for a commit decision
+#[derive(Debug, PartialEq)]
+enum CommitDecisionAction {
+    /// The commit decision is stale or invalid, and should be dropped
+    Ignore,
+    /// The commit decision was verified against the current epoch
+    Commit,
+    /// The commit decision is from a future epoch. It cannot be verified until
+    /// the epoch change has been proven (so, an epoch change proof is required).
+    RequestEpochChangeProof,
+}
+
+/// Notifies the observer loop that a state sync task has finished
+struct StateSyncNotification {
+    target: LedgerInfoWithSignatures,
+    result: Result<(), StateSyncError>,
+}
+
+/// Follows consensus on a fullnode: subscribes to ordered blocks and commit decisions
+/// from upstream publishers, executes the ordered blocks speculatively with the regular
+/// execution pipeline, and commits them once the commit decision arrives. Whenever the
+/// observer cannot extend its local chain (e.g., it missed a message), it falls back to
+/// state sync using the latest verified commit decision as the target. Commit decisions
+/// from future epochs are only used once the epoch changes have been verified with an
+/// epoch change proof (fetched from the publisher that sent the commit decision).
+pub struct ConsensusObserver<P: OnChainConfigProvider> {
+    consensus_observer_config: ConsensusObserverConfig,
+    network_client: NetworkClient<ConsensusObserverMessage>,
+    execution_proxy: Arc<ExecutionProxy>,
+    db_reader: Arc<dyn DbReader>,
+    reconfig_events: ReconfigNotificationListener<P>,
+    // Forwards verified messages downstream (if this node also publishes)
+    consensus_publisher: Option<Arc<ConsensusPublisher>>,
+
+    // The active subscriptions and the time of the last message from each
+    active_subscriptions: HashMap<PeerNetworkId, Instant>,
+    epoch_state: Option<Arc<EpochState>>,
+    // The latest committed ledger info
+    root: Option<LedgerInfoWithSignatures>,
+    // The block that the next ordered block must extend
+    ordered_tip: Option<OrderedTip>,
+    // Executed (but not yet committed) blocks by (epoch, round)
+    executed_blocks: BTreeMap<(u64, Round), Arc<ExecutedBlock>>,
+    // The transactions of all uncommitted blocks (shared with the payload manager)
+    block_transactions: Arc<Mutex<HashMap<HashValue, Vec<SignedTransaction>>>>,
+    // The time of the last epoch change proof request (used for throttling)
+    last_epoch_change_proof_request: Option<Instant>,
+    // The target of the state sync task in flight (if any)
+    state_sync_target: Option<LedgerInfoWithSignatures>,
+    // Sends a notification to the observer loop when a state sync task finishes
+    state_sync_notification_sender: UnboundedSender<StateSyncNotification>,
+    state_sync_notification_listener: Option<UnboundedReceiver<StateSyncNotification>>,
+}
+
+impl<P: OnChainConfigProvider> ConsensusObserver<P> {
+    /// Creates a new consensus observer
+    pub fn new(
+        consensus_observer_config: ConsensusObserverConfig,
+        network_client: NetworkClient<ConsensusObserverMessage>,
+        execution_proxy: Arc<ExecutionProxy>,
+        db_reader: Arc<dyn DbReader>,
+        reconfig_events: ReconfigNotificationListener<P>,
+        consensus_publisher: Option<Arc<ConsensusPublisher>>,
+    ) -> Self {
+        let (state_sync_notification_sender, state_sync_notification_listener) = mpsc::unbounded();
+        Self {
+            consensus_observer_config,
+            network_client,
+            execution_proxy,
+            db_reader,
+            reconfig_events,
+            consensus_publisher,
+            active_subscriptions: HashMap::new(),
+            epoch_state: None,
+            root: None,
+            ordered_tip: None,
+            executed_blocks: BTreeMap::new(),
+            block_transactions: Arc::new(Mutex::new(HashMap::new())),
+            last_epoch_change_proof_request: None,
+            state_sync_target: None,
+            state_sync_notification_sender,
+            state_sync_notification_listener: Some(state_sync_notification_listener),
+        }
+    }
+
+    /// Starts the observer loop (this never returns unless the network stream ends)
+    pub async fn start(
+        mut self,
+        network_service_events: NetworkServiceEvents<ConsensusObserverMessage>,
+    ) {
+        let network_events: Vec<_> = network_service_events
+            .into_network_and_events()
+            .into_iter()
+            .map(|(network_id, events)| events.map(move |event| (network_id, event)))
+            .collect();
+        let mut network_events = select_all(network_events).fuse();
+        let mut state_sync_notifications = self
+            .state_sync_notification_listener
+            .take()
+            .expect("The state sync notification listener should only be taken once!");
+        let mut subscription_interval = tokio::time::interval(Duration::from_millis(
+            self.consensus_observer_config
+                .subscription_refresh_interval_ms,
+        ));
+
+        // Wait for the initial epoch state before processing any messages
+        info!("Starting the consensus observer!");
+        self.wait_for_epoch_start(0).await;
+
+        loop {
+            tokio::select! {
+                Some((network_id, event)) = network_events.next() => {
+                    self.handle_network_event(network_id, event).await;
+                }
+                Some(state_sync_notification) = state_sync_notifications.next() => {
+                    self.process_state_sync_notification(state_sync_notification).await;
+                }
+                _ = subscription_interval.tick() => {
+                    self.refresh_subscriptions();
+                }
+                else => break,
+            }
+        }
+        info!("The consensus observer has stopped!");
+    }
+
+    /// Returns the current epoch state
+    fn get_epoch_state(&self) -> Arc<EpochState> {
+        self.epoch_state
+            .clone()
+            .expect("The epoch state should be set once the observer has started!")
+    }
+
+    /// Returns the latest committed ledger info
+    fn get_root(&self) -> &LedgerInfoWithSignatures {
+        self.root
+            .as_ref()
+            .expect("The root should be set once the observer has started!")
+    }
+
+    /// Returns the block that the next ordered block must extend
+    fn get_ordered_tip(&self) -> OrderedTip {
+        self.ordered_tip
+            .expect("The ordered tip should be set once the observer has started!")
+    }
+
+    /// Waits for a reconfiguration notification with at least the given epoch and
+    /// starts the new epoch. All uncommitted state is reset to the latest ledger info.
+    async fn wait_for_epoch_start(&mut self, min_epoch: u64) {
+        let payload = loop {
+            let reconfig_notification = self
+                .reconfig_events
+                .next()
+                .await
+                .expect("Reconfig sender dropped, unable to start new epoch");
+            let payload = reconfig_notification.on_chain_configs;
+            if payload.epoch() >= min_epoch {
+                break payload;
+            }
+        };
+
+        let validator_set: ValidatorSet = payload
+            .get()
+            .expect("failed to get ValidatorSet from payload");
+        let epoch_state = Arc::new(EpochState {
+            epoch: payload.epoch(),
+            verifier: (&validator_set).into(),
+        });
+        let execution_config = payload
+            .get::<OnChainExecutionConfig>()
+            .unwrap_or_else(|error| {
+                error!("Failed to read on-chain execution config {}", error);
+                OnChainExecutionConfig::default_if_missing()
+            });
+
+        // Reset all uncommitted state
+        self.executed_blocks.clear();
+        self.block_transactions.lock().clear();
+        let root = self
+            .db_reader
+            .get_latest_ledger_info()
+            .expect("Failed to read the latest ledger info from storage!");
+        self.ordered_tip = Some(OrderedTip::from_committed(root.ledger_info()));
+        self.root = Some(root);
+
+        self.execution_proxy.new_epoch(
+            &epoch_state,
+            Arc::new(PayloadManager::ConsensusObserver(
+                self.block_transactions.clone(),
+            )),
+            create_transaction_shuffler(execution_config.transaction_shuffler_type()),
+            execution_config.block_gas_limit(),
+            create_transaction_deduper(execution_config.transaction_deduper_type()),
+        );
+        info!(
+            epoch = epoch_state.epoch,
+            "Consensus observer started a new epoch"
+        );
+        self.epoch_state = Some(epoch_state);
+    }
+
+    /// Handles a single network event
+    async fn handle_network_event(
+        &mut self,
+        network_id: NetworkId,
+        event: Event<ConsensusObserverMessage>,
+    ) {
+        match event {
+            Event::Message(peer_id, message) => {
+                let peer_network_id = PeerNetworkId::new(network_id, peer_id);
+                self.handle_message(peer_network_id, message).await;
+            },
+            Event::LostPeer(metadata) => {
+                let peer_network_id = PeerNetworkId::new(network_id, metadata.remote_peer_id);
+                if self.active_subscriptions.remove(&peer_network_id).is_some() {
+                    info!(
+                        "Lost consensus observer subscription to peer {}",
+                        peer_network_id
+                    );
+                }
+                if let Some(consensus_publisher) = &self.consensus_publisher {
+                    consensus_publisher.handle_network_event(network_id, Event::LostPeer(metadata));
+                }
+            },
+            _ => {}, // Ignore everything else
+        }
+    }
+
+    /// Handles a single message from the given peer
+    async fn handle_message(
+        &mut self,
+        peer_network_id: PeerNetworkId,
+        message: ConsensusObserverMessage,
+    ) {
+        match message {
+            ConsensusObserverMessage::Subscribe
+            | ConsensusObserverMessage::Unsubscribe
+            | ConsensusObserverMessage::EpochChangeProofRequest(_) => {
+                // Requests from downstream observers are served by the publisher
+                if let Some(consensus_publisher) = &self.consensus_publisher {
+                    consensus_publisher.handle_network_event(
+                        peer_network_id.network_id(),
+                        Event::Message(peer_network_id.peer_id(), message),
+                    );
+                }
+            },
+            message => {
+                counters::increment_message_counter(counters::RECEIVED_LABEL, message.get_label());
+                match self.active_subscriptions.get_mut(&peer_network_id) {
+                    Some(last_message_time) => *last_message_time = Instant::now(),
+                    None => {
+                        debug!(
+                            "Ignoring {} from unsubscribed peer {}",
+                            message, peer_network_id
+                        );
+                        return;
+                    },
+                }
+
+                match message {
+                    ConsensusObserverMessage::OrderedBlock(ordered_block) => {
+                        self.process_ordered_block(ordered_block).await
+                    },
+                    ConsensusObserverMessage::CommitDecision(commit_decision) => {
+                        self.process_commit_decision(peer_network_id, commit_decision)
+                            .await
+                    },
+                    ConsensusObserverMessage::EpochChangeProof(epoch_change_proof) => {
+                        self.process_epoch_change_proof(epoch_change_proof)
+                    },
+                    _ => unreachable!("Requests from observers are handled above!"),
+                }
+            },
+        }
+    }
+
+    /// Verifies and executes the given ordered block (if it extends the local chain)
+    async fn process_ordered_block(&mut self, ordered_block: OrderedBlock) {
+        // Drop ordered blocks until the state sync task finishes (the ordered tip is stale)
+        if self.state_sync_target.is_some() {
+            return;
+        }
+        if !is_executable_ordered_block(
+            &ordered_block,
+            &self.get_epoch_state(),
+            &self.get_ordered_tip(),
+        ) {
+            return;
+        }
+
+        let first_block = &ordered_block.blocks[0].block;
+        let max_num_pending_blocks = self.consensus_observer_config.max_num_pending_blocks as usize;
+        if self.executed_blocks.len() + ordered_block.blocks.len() > max_num_pending_blocks {
+            warn!(
+                "Too many pending blocks ({})! Dropping ordered block {}.",
+                self.executed_blocks.len(),
+                first_block.id()
+            );
+            return;
+        }
+
+        if let Some(consensus_publisher) = &self.consensus_publisher {
+            consensus_publisher.publish_message(ConsensusObserverMessage::OrderedBlock(
+                ordered_block.clone(),
+            ));
+        }
+
+        for BlockWithTransactions {
+            block,
+            transactions,
+        } in ordered_block.blocks
+        {
+            let parent_block_id = self.get_ordered_tip().block_id;
+            self.block_transactions
+                .lock()
+                .insert(block.id(), transactions);
+            match self.execution_proxy.compute(&block, parent_block_id).await {
+                Ok(compute_result) => {
+                    counters::OBSERVER_EXECUTED_BLOCKS.inc();
+                    self.ordered_tip = Some(OrderedTip {
+                        epoch: block.epoch(),
+                        round: block.round(),
+                        block_id: block.id(),
+                    });
+                    self.executed_blocks.insert(
+                        (block.epoch(), block.round()),
+                        Arc::new(ExecutedBlock::new(block, compute_result)),
+                    );
+                },
+                Err(error) => {
+                    error!("Failed to execute block {}! Error: {:?}", block.id(), error);
+                    self.block_transactions.lock().remove(&block.id());
+                    return;
+                },
+            }
+        }
+    }
+
+    /// Verifies the given commit decision and commits the executed blocks (or
+    /// falls back to state sync if the blocks are missing or diverge).
+    async fn process_commit_decision(
+        &mut self,
+        peer_network_id: PeerNetworkId,
+        commit_decision: CommitDecision,
+    ) {
+        // Drop commit decisions until the state sync task finishes (the observer
+        // catches up from the first commit decision that arrives afterwards)
+        if self.state_sync_target.is_some() {
+            return;
+        }
+        let epoch_state = self.get_epoch_state();
+        match get_commit_decision_action(&commit_decision, &epoch_state, self.get_root()) {
+            CommitDecisionAction::Ignore => {},
+            CommitDecisionAction::Commit => {
+                if let Some(consensus_publisher) = &self.consensus_publisher {
+                    consensus_publisher.publish_message(ConsensusObserverMessage::CommitDecision(
+                        commit_decision.clone(),
+                    ));
+                }
+                if !self
+                    .commit_executed_blocks(&commit_decision.commit_proof)
+                    .await
+                {
+                    self.fallback_to_state_sync(commit_decision.commit_proof);
+                }
+            },
+            CommitDecisionAction::RequestEpochChangeProof => {
+                let end_epoch = commit_decision.commit_proof.commit_info().epoch();
+                self.request_epoch_change_proof(peer_network_id, epoch_state.epoch, end_epoch);
+            },
+        }
+    }
+
+    /// Requests the epoch-ending ledger infos for the epochs [start_epoch, end_epoch)
+    /// from the given peer. Requests are throttled to one per subscription refresh.
+    fn request_epoch_change_proof(
+        &mut self,
+        peer_network_id: PeerNetworkId,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) {
+        let request_interval = Duration::from_millis(
+            self.consensus_observer_config
+                .subscription_refresh_interval_ms,
+        );
+        if let Some(last_request_time) = self.last_epoch_change_proof_request {
+            if last_request_time.elapsed() < request_interval {
+                return;
+            }
+        }
+
+        info!(
+            "Requesting an epoch change proof from peer {} for epochs [{}, {})",
+            peer_network_id, start_epoch, end_epoch
+        );
+        self.last_epoch_change_proof_request = Some(Instant::now());
+        self.send_message(
+            peer_network_id,
+            ConsensusObserverMessage::EpochChangeProofRequest(EpochChangeProofRequest {
+                start_epoch,
+                end_epoch,
+            }),
+        );
+    }
+
+    /// Verifies the given epoch change proof against the current epoch state and
+    /// syncs to the last epoch-ending ledger info (if it is ahead of the root).
+    fn process_epoch_change_proof(&mut self, epoch_change_proof: EpochChangeProof) {
+        if self.state_sync_target.is_some() {
+            return;
+        }
+        if let Some(sync_target) = get_epoch_change_sync_target(
+            &epoch_change_proof,
+            &self.get_epoch_state(),
+            self.get_root(),
+        ) {
+            self.last_epoch_change_proof_request = None;
+            self.fallback_to_state_sync(sync_target);
+        }
+    }
+
+    /// Commits the executed blocks up to the given commit proof. Returns false
+    /// iff the blocks are missing locally or their execution results diverge.
+    async fn commit_executed_blocks(&mut self, commit_proof: &LedgerInfoWithSignatures) -> bool {
+        let blocks = match take_committed_blocks(&mut self.executed_blocks, commit_proof) {
+            Some(blocks) => blocks,
+            None => return false,
+        };
+        let commit_info = commit_proof.commit_info();
+        if let Err(error) = self
+            .execution_proxy
+            .commit(&blocks, commit_proof.clone(), Box::new(|_, _| {}))
+            .await
+        {
+            error!("Failed to commit blocks! Error: {:?}", error);
+            return false;
+        }
+
+        {
+            let mut block_transactions = self.block_transactions.lock();
+            for block in &blocks {
+                block_transactions.remove(&block.id());
+            }
+        }
+        self.root = Some(commit_proof.clone());
+
+        if commit_proof.ledger_info().ends_epoch() {
+            self.execution_proxy.end_epoch();
+            self.wait_for_epoch_start(commit_info.epoch() + 1).await;
+        }
+        true
+    }
+
+    /// Drops all uncommitted state and spawns a task that asks state sync to sync
+    /// to the given (verified) target. The observer loop is notified once the task
+    /// finishes (see `process_state_sync_notification`).
+    fn fallback_to_state_sync(&mut self, commit_proof: LedgerInfoWithSignatures) {
+        counters::OBSERVER_STATE_SYNC_FALLBACKS.inc();
+        warn!(
+            "Falling back to state sync! Target: {}",
+            commit_proof.commit_info()
+        );
+
+        self.executed_blocks.clear();
+        self.block_transactions.lock().clear();
+        self.state_sync_target = Some(commit_proof.clone());
+
+        let execution_proxy = self.execution_proxy.clone();
+        let state_sync_notification_sender = self.state_sync_notification_sender.clone();
+        tokio::spawn(async move {
+            let result = execution_proxy.sync_to(commit_proof.clone()).await;
+            let notification = StateSyncNotification {
+                target: commit_proof,
+                result,
+            };
+            if state_sync_notification_sender
+                .unbounded_send(notification)
+                .is_err()
+            {
+                warn!("The consensus observer stopped before state sync finished!");
+            }
+        });
+    }
+
+    /// Moves the root and the ordered tip to the state sync target (once the
+    /// state sync task has finished), and starts the new epoch (if required).
+    async fn process_state_sync_notification(&mut self, notification: StateSyncNotification) {
+        let StateSyncNotification { target, result } = notification;
+        self.state_sync_target = None;
+        if let Err(error) = result {
+            error!("Failed to sync to the commit decision! Error: {:?}", error);
+            return;
+        }
+
+        let ledger_info = target.ledger_info();
+        let target_epoch = if ledger_info.ends_epoch() {
+            ledger_info.epoch() + 1
+        } else {
+            ledger_info.epoch()
+        };
+        self.ordered_tip = Some(OrderedTip::from_committed(ledger_info));
+        self.root = Some(target);
+
+        if target_epoch > self.get_epoch_state().epoch {
+            self.execution_proxy.end_epoch();
+            self.wait_for_epoch_start(target_epoch).await;
+        }
+    }
+
+    /// Drops silent subscriptions and subscribes to new upstream peers (i.e.,
+    /// peers that we dialed) until the max number of subscriptions is reached.
+    fn refresh_subscriptions(&mut self) {
+        let available_peers = match self.network_client.get_available_peers() {
+            Ok(available_peers) => available_peers,
+            Err(error) => {
+                warn!("Failed to get the available peers! Error: {:?}", error);
+                return;
+            },
+        };
+
+        // Drop subscriptions to disconnected or silent peers
+        let subscription_timeout =
+            Duration::from_millis(self.consensus_observer_config.subscription_timeout_ms);
+        let mut timed_out_peers = vec![];
+        self.active_subscriptions
+            .retain(|peer_network_id, last_message_time| {
+                let timed_out = last_message_time.elapsed() > subscription_timeout;
+                if timed_out {
+                    timed_out_peers.push(*peer_network_id);
+                }
+                available_peers.contains(peer_network_id) && !timed_out
+            });
+        for peer_network_id in &timed_out_peers {
+            info!(
+                "Consensus observer subscription to peer {} timed out",
+                peer_network_id
+            );
+            self.send_message(*peer_network_id, ConsensusObserverMessage::Unsubscribe);
+        }
+
+        // Subscribe to new upstream peers
+        let peers_and_metadata = self.network_client.get_peers_and_metadata();
+        let mut candidate_peers: Vec<_> = available_peers
+            .into_iter()
+            .filter(|peer_network_id| {
+                !self.active_subscriptions.contains_key(peer_network_id)
+                    && !timed_out_peers.contains(peer_network_id)
+                    && peers_and_metadata
+                        .get_metadata_for_peer(*peer_network_id)
+                        .map(|metadata| {
+                            metadata.get_connection_metadata().origin == ConnectionOrigin::Outbound
+                        })
+                        .unwrap_or(false)
+            })
+            .collect();
+        candidate_peers.shuffle(&mut rand::thread_rng());
+        let max_num_subscriptions = self.consensus_observer_config.max_num_subscriptions as usize;
+        for peer_network_id in candidate_peers {
+            if self.active_subscriptions.len() >= max_num_subscriptions {
+                break;
+            }
+            info!("Subscribing to consensus publisher {}", peer_network_id);
+            self.active_subscriptions
+                .insert(peer_network_id, Instant::now());
+        }
+
+        // (Re-)send the subscription requests, in case a publisher restarted
+        let active_peers: Vec<_> = self.active_subscriptions.keys().copied().collect();
+        for peer_network_id in active_peers {
+            self.send_message(peer_network_id, ConsensusObserverMessage::Subscribe);
+        }
+        counters::OBSERVER_NUM_SUBSCRIPTIONS.set(self.active_subscriptions.len() as i64);
+    }
+
+    /// Sends the given message to the specified peer
+    fn send_message(&self, peer_network_id: PeerNetworkId, message: ConsensusObserverMessage) {
+        let message_label = message.get_label();
+        match self.network_client.send_to_peer(message, peer_network_id) {
+            Ok(()) => counters::increment_message_counter(counters::SENT_LABEL, message_label),
+            Err(error) => {
+                counters::increment_message_counter(counters::SEND_FAILED_LABEL, message_label);
+                warn!(
+                    "Failed to send {} message to peer {}! Error: {:?}",
+                    message_label, peer_network_id, error
+                );
+            },
+        }
+    }
+}
+
+/// Returns true iff the given ordered block is from the current epoch, is newer than
+/// the ordered tip, passes verification, and extends the ordered tip. Blocks that don't
+/// extend the local chain are not executed (we wait for the next commit decision instead).
+fn is_executable_ordered_block(
+    ordered_block: &OrderedBlock,
+    epoch_state: &EpochState,
+    ordered_tip: &OrderedTip,
+) -> bool {
+    // Ignore blocks from other epochs and blocks we've already seen
+    let ordered_info = ordered_block.ordered_proof.commit_info();
+    if ordered_info.epoch() != epoch_state.epoch
+        || (ordered_info.epoch(), ordered_info.round()) <= (ordered_tip.epoch, ordered_tip.round)
+    {
+        return false;
+    }
+
+    if let Err(error) = ordered_block.verify(epoch_state) {
+        warn!("Failed to verify ordered block! Error: {:?}", error);
+        return false;
+    }
+
+    let first_block = &ordered_block.blocks[0].block;
+    if first_block.parent_id() != ordered_tip.block_id {
+        warn!(
+            "Ordered block {} does not extend the ordered tip {:?}! Waiting for a commit decision.",
+            first_block.id(),
+            ordered_tip
+        );
+        return false;
+    }
+    true
+}
+
+/// Returns the action to take for the given commit decision
+fn get_commit_decision_action(
+    commit_decision: &CommitDecision,
+    epoch_state: &EpochState,
+    root: &LedgerInfoWithSignatures,
+) -> CommitDecisionAction {
+    // Ignore stale commit decisions
+    let root_info = root.commit_info();
+    let commit_info = commit_decision.commit_proof.commit_info();
+    if (commit_info.epoch(), commit_info.round()) <= (root_info.epoch(), root_info.round()) {
+        return CommitDecisionAction::Ignore;
+    }
+
+    if commit_info.epoch() > epoch_state.epoch {
+        return CommitDecisionAction::RequestEpochChangeProof;
+    }
+    match commit_decision.verify(epoch_state) {
+        Ok(()) => CommitDecisionAction::Commit,
+        Err(error) => {
+            warn!("Failed to verify commit decision! Error: {:?}", error);
+            CommitDecisionAction::Ignore
+        },
+    }
+}
+
+/// Verifies the given epoch change proof and returns the last epoch-ending
+/// ledger info as the sync target (if it is ahead of the given root).
+fn get_epoch_change_sync_target(
+    epoch_change_proof: &EpochChangeProof,
+    epoch_state: &EpochState,
+    root: &LedgerInfoWithSignatures,
+) -> Option<LedgerInfoWithSignatures> {
+    let ledger_info = match epoch_change_proof.verify(epoch_state) {
+        Ok(ledger_info) => ledger_info,
+        Err(error) => {
+            warn!("Failed to verify epoch change proof! Error: {:?}", error);
+            return None;
+        },
+    };
+
+    let root_info = root.commit_info();
+    let target_info = ledger_info.commit_info();
+    if (target_info.epoch(), target_info.round()) <= (root_info.epoch(), root_info.round()) {
+        return None;
+    }
+    Some(ledger_info.clone())
+}
+
+/// Removes and returns all executed blocks up to (and including) the block of the given
+/// commit proof. Returns None (and leaves the blocks untouched) if the committed block
+/// was not executed locally, or if its execution result differs from the commit proof.
+fn take_committed_blocks(
+    executed_blocks: &mut BTreeMap<(u64, Round), Arc<ExecutedBlock>>,
+    commit_proof: &LedgerInfoWithSignatures,
+) -> Option<Vec<Arc<ExecutedBlock>>> {
+    let commit_info = commit_proof.commit_info();
+    match executed_blocks.get(&(commit_info.epoch(), commit_info.round())) {
+        Some(executed_block) if executed_block.block_info() == *commit_info => {},
+        Some(executed_block) => {
+            warn!(
+                "Execution result diverged! Local: {}, commit decision: {}",
+                executed_block.block_info(),
+                commit_info
+            );
+            return None;
+        },
+        None => return None,
+    }
+
+    let uncommitted_blocks =
+        executed_blocks.split_off(&(commit_info.epoch(), commit_info.round() + 1));
+    Some(
+        std::mem::replace(executed_blocks, uncommitted_blocks)
+            .into_values()
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consensus_observer::test_utils::{
+        create_block, create_ledger_info, create_transactions,
+    };
+    use aptos_consensus_types::common::Payload;
+    use aptos_executor_types::StateComputeResult;
+    use aptos_types::{
+        block_info::BlockInfo,
+        validator_signer::ValidatorSigner,
+        validator_verifier::{random_validator_verifier, ValidatorVerifier},
+    };
+
+    #[test]
+    fn test_ordered_block_then_commit_decision() {
+        // Create the epoch state and the root
+        let (signers, verifier, genesis_block, epoch_state) = create_epoch_state();
+        let root = create_ledger_info(
+            &signers,
+            &verifier,
+            genesis_block.gen_block_info(HashValue::zero(), 0, None),
+        );
+        let ordered_tip = OrderedTip::from_committed(root.ledger_info());
+
+        // Verify that an ordered block extending the root can be executed
+        let ordered_block = create_ordered_block(&signers, &verifier, &genesis_block, 1, 2);
+        assert!(is_executable_ordered_block(
+            &ordered_block,
+            &epoch_state,
+            &ordered_tip
+        ));
+
+        // Execute the blocks and move the ordered tip
+        let mut executed_blocks = execute_blocks(&ordered_block);
+        let last_block = &ordered_block.blocks[1].block;
+        let ordered_tip = OrderedTip {
+            epoch: last_block.epoch(),
+            round: last_block.round(),
+            block_id: last_block.id(),
+        };
+
+        // Verify that the same ordered block is now ignored
+        assert!(!is_executable_ordered_block(
+            &ordered_block,
+            &epoch_state,
+            &ordered_tip
+        ));
+
+        // Verify that the commit decision for the first block commits only the first block
+        let first_commit_decision =
+            create_commit_decision(&signers, &verifier, &executed_blocks, 1);
+        assert_eq!(
+            get_commit_decision_action(&first_commit_decision, &epoch_state, &root),
+            CommitDecisionAction::Commit
+        );
+        let committed_blocks =
+            take_committed_blocks(&mut executed_blocks, &first_commit_decision.commit_proof)
+                .unwrap();
+        assert_eq!(committed_blocks.len(), 1);
+        assert_eq!(committed_blocks[0].round(), 1);
+        assert_eq!(executed_blocks.len(), 1);
+        let root = first_commit_decision.commit_proof;
+
+        // Verify that the commit decision for the second block commits the remaining block
+        let second_commit_decision =
+            create_commit_decision(&signers, &verifier, &executed_blocks, 2);
+        assert_eq!(
+            get_commit_decision_action(&second_commit_decision, &epoch_state, &root),
+            CommitDecisionAction::Commit
+        );
+        let committed_blocks =
+            take_committed_blocks(&mut executed_blocks, &second_commit_decision.commit_proof)
+                .unwrap();
+        assert_eq!(committed_blocks.len(), 1);
+        assert_eq!(committed_blocks[0].round(), 2);
+        assert!(executed_blocks.is_empty());
+        let root = second_commit_decision.commit_proof.clone();
+
+        // Verify that commit decisions for committed blocks are ignored
+        assert_eq!(
+            get_commit_decision_action(&second_commit_decision, &epoch_state, &root),
+            CommitDecisionAction::Ignore
+        );
+    }
+
+    #[test]
+    fn test_forged_ordered_blocks() {
+        // Create the epoch state and the ordered tip
+        let (signers, verifier, genesis_block, epoch_state) = create_epoch_state();
+        let root = create_ledger_info(
+            &signers,
+            &verifier,
+            genesis_block.gen_block_info(HashValue::zero(), 0, None),
+        );
+        let ordered_tip = OrderedTip::from_committed(root.ledger_info());
+
+        // Verify that an ordered block without a quorum is rejected
+        let ordered_block = create_ordered_block(&signers[0..1], &verifier, &genesis_block, 1, 1);
+        assert!(!is_executable_ordered_block(
+            &ordered_block,
+            &epoch_state,
+            &ordered_tip
+        ));
+
+        // Verify that an ordered block signed by other validators is rejected
+        let (other_signers, other_verifier) = random_validator_verifier(4, None, false);
+        let ordered_block =
+            create_ordered_block(&other_signers, &other_verifier, &genesis_block, 1, 1);
+        assert!(!is_executable_ordered_block(
+            &ordered_block,
+            &epoch_state,
+            &ordered_tip
+        ));
+
+        // Verify that an ordered block with forged transactions is rejected
+        let mut ordered_block = create_ordered_block(&signers, &verifier, &genesis_block, 1, 1);
+        ordered_block.blocks[0].transactions = create_transactions(1);
+        assert!(!is_executable_ordered_block(
+            &ordered_block,
+            &epoch_state,
+            &ordered_tip
+        ));
+
+        // Verify that an ordered block that doesn't extend the ordered tip is rejected
+        let missing_block = create_block(&genesis_block, 1, 1, Payload::DirectMempool(vec![]));
+        let ordered_block = create_ordered_block(&signers, &verifier, &missing_block, 2, 2);
+        assert!(!is_executable_ordered_block(
+            &ordered_block,
+            &epoch_state,
+            &ordered_tip
+        ));
+
+        // Verify that an ordered block from a different epoch is rejected
+        let next_epoch_state = EpochState {
+            epoch: epoch_state.epoch + 1,
+            verifier,
+        };
+        let ordered_block =
+            create_ordered_block(&signers, &next_epoch_state.verifier, &genesis_block, 1, 1);
+        assert!(!is_executable_ordered_block(
+            &ordered_block,
+            &next_epoch_state,
+            &ordered_tip
+        ));
+    }
+
+    #[test]
+    fn test_forged_and_diverged_commit_decisions() {
+        // Create the epoch state and execute an ordered block
+        let (signers, verifier, genesis_block, epoch_state) = create_epoch_state();
+        let root = create_ledger_info(
+            &signers,
+            &verifier,
+            genesis_block.gen_block_info(HashValue::zero(), 0, None),
+        );
+        let ordered_block = create_ordered_block(&signers, &verifier, &genesis_block, 1, 2);
+        let mut executed_blocks = execute_blocks(&ordered_block);
+
+        // Verify that commit decisions without a quorum (or from other validators) are ignored
+        let commit_decision =
+            create_commit_decision(&signers[0..1], &verifier, &executed_blocks, 2);
+        assert_eq!(
+            get_commit_decision_action(&commit_decision, &epoch_state, &root),
+            CommitDecisionAction::Ignore
+        );
+        let (other_signers, other_verifier) = random_validator_verifier(4, None, false);
+        let commit_decision =
+            create_commit_decision(&other_signers, &other_verifier, &executed_blocks, 2);
+        assert_eq!(
+            get_commit_decision_action(&commit_decision, &epoch_state, &root),
+            CommitDecisionAction::Ignore
+        );
+
+        // Verify that a commit decision with a different execution result commits nothing
+        let executed_block = executed_blocks.get(&(epoch_state.epoch, 2)).unwrap();
+        let diverged_commit_info =
+            executed_block
+                .block()
+                .gen_block_info(HashValue::random(), 0, None);
+        let diverged_commit_proof = create_ledger_info(&signers, &verifier, diverged_commit_info);
+        assert!(take_committed_blocks(&mut executed_blocks, &diverged_commit_proof).is_none());
+        assert_eq!(executed_blocks.len(), 2);
+
+        // Verify that a commit decision for a block that wasn't executed commits nothing
+        let missing_commit_info = BlockInfo::random_with_epoch(epoch_state.epoch, 3);
+        let missing_commit_proof = create_ledger_info(&signers, &verifier, missing_commit_info);
+        assert!(take_committed_blocks(&mut executed_blocks, &missing_commit_proof).is_none());
+        assert_eq!(executed_blocks.len(), 2);
+    }
+
+    #[test]
+    fn test_future_epoch_commit_decision() {
+        // Create the epoch state and the root
+        let (signers, verifier, genesis_block, epoch_state) = create_epoch_state();
+        let root = create_ledger_info(
+            &signers,
+            &verifier,
+            genesis_block.gen_block_info(HashValue::zero(), 0, None),
+        );
+
+        // Verify that a commit decision from the next epoch (signed by
+        // unknown validators) requires an epoch change proof.
+        let (next_signers, next_verifier) = random_validator_verifier(4, None, false);
+        let next_epoch = epoch_state.epoch + 1;
+        let commit_decision = CommitDecision {
+            commit_proof: create_ledger_info(
+                &next_signers,
+                &next_verifier,
+                BlockInfo::random_with_epoch(next_epoch, 5),
+            ),
+        };
+        assert_eq!(
+            get_commit_decision_action(&commit_decision, &epoch_state, &root),
+            CommitDecisionAction::RequestEpochChangeProof
+        );
+
+        // Verify that a valid epoch change proof returns the epoch-ending ledger info
+        let next_epoch_state = EpochState {
+            epoch: next_epoch,
+            verifier: next_verifier,
+        };
+        let epoch_ending_info = BlockInfo::new(
+            epoch_state.epoch,
+            10,
+            HashValue::random(),
+            HashValue::random(),
+            100,
+            100,
+            Some(next_epoch_state.clone()),
+        );
+        let epoch_ending_ledger_info =
+            create_ledger_info(&signers, &verifier, epoch_ending_info.clone());
+        let epoch_change_proof =
+            EpochChangeProof::new(vec![epoch_ending_ledger_info.clone()], false);
+        let sync_target =
+            get_epoch_change_sync_target(&epoch_change_proof, &epoch_state, &root).unwrap();
+        assert_eq!(sync_target, epoch_ending_ledger_info);
+
+        // Verify that a forged epoch change proof is rejected
+        let forged_ledger_info =
+            create_ledger_info(&next_signers, &next_epoch_state.verifier, epoch_ending_info);
+        let forged_epoch_change_proof = EpochChangeProof::new(vec![forged_ledger_info], false);
+        assert!(
+            get_epoch_change_sync_target(&forged_epoch_change_proof, &epoch_state, &root).is_none()
+        );
+
+        // Verify that an epoch change proof behind the root is ignored
+        assert!(get_epoch_change_sync_target(
+            &epoch_change_proof,
+            &epoch_state,
+            &epoch_ending_ledger_info
+        )
+        .is_none());
+
+        // Verify that a stale epoch change proof is rejected
+        assert!(
+            get_epoch_change_sync_target(&epoch_change_proof, &next_epoch_state, &root).is_none()
+        );
+    }
+
+    /// Creates the validators, the genesis block and the epoch state for the tests
+    fn create_epoch_state() -> (Vec<ValidatorSigner>, ValidatorVerifier, Block, EpochState) {
+        let (signers, verifier) = random_validator_verifier(4, None, false);
+        let genesis_block = Block::make_genesis_block();
+        let epoch_state = EpochState {
+            epoch: genesis_block.epoch(),
+            verifier: verifier.clone(),
+        };
+        (signers, verifier, genesis_block, epoch_state)
+    }
+
+    /// Creates an ordered block with a chain of (empty) blocks for the
+    /// given rounds that extends the parent block.
+    fn create_ordered_block(
+        signers: &[ValidatorSigner],
+        verifier: &ValidatorVerifier,
+        parent_block: &Block,
+        first_round: Round,
+        last_round: Round,
+    ) -> OrderedBlock {
+        let mut blocks: Vec<BlockWithTransactions> = vec![];
+        for round in first_round..=last_round {
+            let parent_block = blocks
+                .last()
+                .map(|block| &block.block)
+                .unwrap_or(parent_block);
+            let block = create_block(parent_block, round, round, Payload::DirectMempool(vec![]));
+            blocks.push(BlockWithTransactions::new(block, vec![]));
+        }
+
+        let last_block = &blocks.last().unwrap().block;
+        let ordered_proof = create_ledger_info(
+            signers,
+            verifier,
+            last_block.gen_block_info(HashValue::zero(), 0, None),
+        );
+        OrderedBlock {
+            blocks,
+            ordered_proof,
+        }
+    }
+
+    /// Returns the (dummy) executed blocks for the given ordered block
+    fn execute_blocks(ordered_block: &OrderedBlock) -> BTreeMap<(u64, Round), Arc<ExecutedBlock>> {
+        ordered_block
+            .blocks
+            .iter()
+            .map(|block| {
+                let block = block.block.clone();
+                (
+                    (block.epoch(), block.round()),
+                    Arc::new(ExecutedBlock::new(block, StateComputeResult::new_dummy())),
+                )
+            })
+            .collect()
+    }
+
+    /// Creates a commit decision for the executed block at the given round
+    fn create_commit_decision(
+        signers: &[ValidatorSigner],
+        verifier: &ValidatorVerifier,
+        executed_blocks: &BTreeMap<(u64, Round), Arc<ExecutedBlock>>,
+        round: Round,
+    ) -> CommitDecision {
+        let executed_block = executed_blocks
+            .values()
+            .find(|executed_block| executed_block.round() == round)
+            .unwrap();
+        CommitDecision {
+            commit_proof: create_ledger_info(signers, verifier, executed_block.block_info()),
+        }
+    }
+}
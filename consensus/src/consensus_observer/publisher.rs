@@ -0,0 +1,580 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::consensus_observer::{
+    counters,
+    network_message::{
+        BlockWithTransactions, CommitDecision, ConsensusObserverMessage, EpochChangeProofRequest,
+        OrderedBlock,
+    },
+};
+use aptos_config::{
+    config::ConsensusObserverConfig,
+    network_id::{NetworkId, PeerNetworkId},
+};
+use aptos_consensus_types::{block::Block, common::Round, executed_block::ExecutedBlock};
+use aptos_crypto::HashValue;
+use aptos_infallible::Mutex;
+use aptos_logger::prelude::*;
+use aptos_network::{
+    application::interface::{NetworkClient, NetworkClientInterface, NetworkServiceEvents},
+    protocols::network::Event,
+};
+use aptos_storage_interface::DbReader;
+use aptos_types::{ledger_info::LedgerInfoWithSignatures, transaction::SignedTransaction};
+use futures::{stream::select_all, StreamExt};
+use std::{
+    collections::{BTreeMap, HashSet, VecDeque},
+    sync::Arc,
+};
+
+/// Publishes ordered blocks and commit decisions to subscribed consensus observers.
+///
+/// Ordered blocks are only known to be complete once their transactions have been fetched
+/// for execution, so each ordered block is held back until the transactions of all of its
+/// blocks have been cached (see `cache_block_transactions`).
+pub struct ConsensusPublisher {
+    consensus_observer_config: ConsensusObserverConfig,
+    network_client: NetworkClient<ConsensusObserverMessage>,
+    // Used to serve epoch change proofs to subscribers
+    db_reader: Arc<dyn DbReader>,
+    active_subscribers: Mutex<HashSet<PeerNetworkId>>,
+    // Block transactions by (epoch, round), alongside the block id
+    block_transactions: Mutex<BTreeMap<(u64, Round), (HashValue, Vec<SignedTransaction>)>>,
+    // Ordered blocks waiting for their transactions, alongside their ordered proof
+    pending_ordered_blocks: Mutex<VecDeque<(Vec<Block>, LedgerInfoWithSignatures)>>,
+}
+
+impl ConsensusPublisher {
+    /// Creates a new consensus publisher
+    pub fn new(
+        consensus_observer_config: ConsensusObserverConfig,
+        network_client: NetworkClient<ConsensusObserverMessage>,
+        db_reader: Arc<dyn DbReader>,
+    ) -> Self {
+        Self {
+            consensus_observer_config,
+            network_client,
+            db_reader,
+            active_subscribers: Mutex::new(HashSet::new()),
+            block_transactions: Mutex::new(BTreeMap::new()),
+            pending_ordered_blocks: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Returns true iff at least one peer is subscribed to the publisher
+    fn has_subscribers(&self) -> bool {
+        !self.active_subscribers.lock().is_empty()
+    }
+
+    /// Caches the transactions of the given block (as fetched from the payload,
+    /// before deduplication and shuffling) and publishes any ordered blocks
+    /// that are now complete.
+    pub(crate) fn cache_block_transactions(
+        &self,
+        block: &Block,
+        transactions: &[SignedTransaction],
+    ) {
+        if !self.has_subscribers() && self.pending_ordered_blocks.lock().is_empty() {
+            return; // Nobody is listening
+        }
+
+        self.block_transactions.lock().insert(
+            (block.epoch(), block.round()),
+            (block.id(), transactions.to_vec()),
+        );
+        self.publish_ready_ordered_blocks();
+    }
+
+    /// Queues the given ordered blocks for publishing. They are published once
+    /// the transactions of every block have been cached.
+    pub(crate) fn publish_ordered_blocks(
+        &self,
+        blocks: &[Arc<ExecutedBlock>],
+        ordered_proof: LedgerInfoWithSignatures,
+    ) {
+        if !self.has_subscribers() {
+            return; // Nobody is listening
+        }
+
+        let blocks = blocks.iter().map(|block| block.block().clone()).collect();
+        self.pending_ordered_blocks
+            .lock()
+            .push_back((blocks, ordered_proof));
+        self.publish_ready_ordered_blocks();
+    }
+
+    /// Publishes the given commit decision and garbage collects all
+    /// cached state for the committed rounds.
+    pub(crate) fn publish_commit_decision(&self, commit_proof: &LedgerInfoWithSignatures) {
+        let committed_epoch = commit_proof.ledger_info().epoch();
+        let committed_round = commit_proof.ledger_info().round();
+
+        // Anything ordered at or below the committed round can no longer be published.
+        // Observers that missed those blocks will fall back to state sync.
+        self.pending_ordered_blocks
+            .lock()
+            .retain(|(_, ordered_proof)| {
+                let ledger_info = ordered_proof.ledger_info();
+                (ledger_info.epoch(), ledger_info.round()) > (committed_epoch, committed_round)
+            });
+        {
+            let mut block_transactions = self.block_transactions.lock();
+            *block_transactions =
+                block_transactions.split_off(&(committed_epoch, committed_round + 1));
+        }
+
+        if self.has_subscribers() {
+            self.publish_message(ConsensusObserverMessage::CommitDecision(CommitDecision {
+                commit_proof: commit_proof.clone(),
+            }));
+        }
+    }
+
+    /// Drops all pending ordered blocks and cached transactions (e.g., when
+    /// the node falls back to state sync and the pipeline is reset).
+    pub(crate) fn reset(&self) {
+        self.pending_ordered_blocks.lock().clear();
+        self.block_transactions.lock().clear();
+    }
+
+    /// Publishes (in order) all pending ordered blocks whose transactions are cached
+    fn publish_ready_ordered_blocks(&self) {
+        let mut pending_ordered_blocks = self.pending_ordered_blocks.lock();
+        let block_transactions = self.block_transactions.lock();
+
+        while let Some((blocks, _)) = pending_ordered_blocks.front() {
+            let transactions_ready = blocks
+                .iter()
+                .all(|block| get_block_transactions(&block_transactions, block).is_some());
+            if !transactions_ready {
+                break;
+            }
+
+            let (blocks, ordered_proof) = pending_ordered_blocks
+                .pop_front()
+                .expect("Pending ordered blocks should not be empty!");
+            let blocks = blocks
+                .into_iter()
+                .map(|block| {
+                    let transactions = get_block_transactions(&block_transactions, &block)
+                        .expect("Block transactions should be cached!")
+                        .clone();
+                    BlockWithTransactions::new(block, transactions)
+                })
+                .collect();
+            self.publish_message(ConsensusObserverMessage::OrderedBlock(OrderedBlock {
+                blocks,
+                ordered_proof,
+            }));
+        }
+    }
+
+    /// Sends the given message to all active subscribers
+    pub(crate) fn publish_message(&self, message: ConsensusObserverMessage) {
+        let subscribers: Vec<_> = self.active_subscribers.lock().iter().copied().collect();
+        if subscribers.is_empty() {
+            return;
+        }
+
+        let message_label = message.get_label();
+        match self.network_client.send_to_peers(message, &subscribers) {
+            Ok(()) => counters::increment_message_counter(counters::SENT_LABEL, message_label),
+            Err(error) => {
+                counters::increment_message_counter(counters::SEND_FAILED_LABEL, message_label);
+                warn!(
+                    "Failed to publish {} message to subscribers {:?}! Error: {:?}",
+                    message_label, subscribers, error
+                );
+            },
+        }
+    }
+
+    /// Handles subscription requests from observers until the network stream ends
+    pub async fn start(
+        self: Arc<Self>,
+        network_service_events: NetworkServiceEvents<ConsensusObserverMessage>,
+    ) {
+        let network_events: Vec<_> = network_service_events
+            .into_network_and_events()
+            .into_iter()
+            .map(|(network_id, events)| events.map(move |event| (network_id, event)))
+            .collect();
+        let mut network_events = select_all(network_events).fuse();
+
+        info!("Starting the consensus publisher!");
+        while let Some((network_id, event)) = network_events.next().await {
+            self.handle_network_event(network_id, event);
+        }
+        info!("The consensus publisher has stopped!");
+    }
+
+    /// Handles a network event from a (potential) subscriber
+    pub(crate) fn handle_network_event(
+        &self,
+        network_id: NetworkId,
+        event: Event<ConsensusObserverMessage>,
+    ) {
+        match event {
+            Event::Message(peer_id, message) => {
+                let peer_network_id = PeerNetworkId::new(network_id, peer_id);
+                self.handle_observer_message(peer_network_id, message);
+            },
+            Event::LostPeer(metadata) => {
+                let peer_network_id = PeerNetworkId::new(network_id, metadata.remote_peer_id);
+                self.remove_subscriber(&peer_network_id);
+            },
+            _ => {}, // Ignore everything else
+        }
+    }
+
+    /// Handles a subscription or epoch change proof request from the given peer
+    fn handle_observer_message(
+        &self,
+        peer_network_id: PeerNetworkId,
+        message: ConsensusObserverMessage,
+    ) {
+        counters::increment_message_counter(counters::RECEIVED_LABEL, message.get_label());
+        match message {
+            ConsensusObserverMessage::Subscribe => {
+                let mut active_subscribers = self.active_subscribers.lock();
+                let max_num_subscribers = self.consensus_observer_config.max_num_subscribers;
+                if active_subscribers.len() as u64 >= max_num_subscribers
+                    && !active_subscribers.contains(&peer_network_id)
+                {
+                    warn!(
+                        "Rejecting subscription from peer {}! Max subscribers reached: {}",
+                        peer_network_id, max_num_subscribers
+                    );
+                    return;
+                }
+                if active_subscribers.insert(peer_network_id) {
+                    info!(
+                        "New consensus observer subscription from peer {}",
+                        peer_network_id
+                    );
+                }
+                counters::PUBLISHER_NUM_SUBSCRIBERS.set(active_subscribers.len() as i64);
+            },
+            ConsensusObserverMessage::Unsubscribe => self.remove_subscriber(&peer_network_id),
+            ConsensusObserverMessage::EpochChangeProofRequest(request) => {
+                self.handle_epoch_change_proof_request(peer_network_id, request)
+            },
+            message => warn!(
+                "Unexpected message from peer {}: {}",
+                peer_network_id, message
+            ),
+        }
+    }
+
+    /// Responds to the epoch change proof request of the given subscriber
+    fn handle_epoch_change_proof_request(
+        &self,
+        peer_network_id: PeerNetworkId,
+        request: EpochChangeProofRequest,
+    ) {
+        if !self.active_subscribers.lock().contains(&peer_network_id) {
+            warn!(
+                "Ignoring epoch change proof request from unsubscribed peer {}",
+                peer_network_id
+            );
+            return;
+        }
+        if request.start_epoch >= request.end_epoch {
+            warn!(
+                "Ignoring invalid epoch change proof request from peer {}: {:?}",
+                peer_network_id, request
+            );
+            return;
+        }
+
+        let epoch_change_proof = match self
+            .db_reader
+            .get_epoch_ending_ledger_infos(request.start_epoch, request.end_epoch)
+        {
+            Ok(epoch_change_proof) => epoch_change_proof,
+            Err(error) => {
+                warn!(
+                    "Failed to read the epoch change proof for request {:?}! Error: {:?}",
+                    request, error
+                );
+                return;
+            },
+        };
+
+        let message = ConsensusObserverMessage::EpochChangeProof(epoch_change_proof);
+        let message_label = message.get_label();
+        match self.network_client.send_to_peer(message, peer_network_id) {
+            Ok(()) => counters::increment_message_counter(counters::SENT_LABEL, message_label),
+            Err(error) => {
+                counters::increment_message_counter(counters::SEND_FAILED_LABEL, message_label);
+                warn!(
+                    "Failed to send {} message to peer {}! Error: {:?}",
+                    message_label, peer_network_id, error
+                );
+            },
+        }
+    }
+
+    /// Removes the given peer from the set of active subscribers (if it was subscribed)
+    fn remove_subscriber(&self, peer_network_id: &PeerNetworkId) {
+        let mut active_subscribers = self.active_subscribers.lock();
+        if active_subscribers.remove(peer_network_id) {
+            info!(
+                "Removed consensus observer subscription for peer {}",
+                peer_network_id
+            );
+        }
+        counters::PUBLISHER_NUM_SUBSCRIBERS.set(active_subscribers.len() as i64);
+    }
+}
+
+/// Returns the cached transactions for the given block (if they exist)
+fn get_block_transactions<'a>(
+    block_transactions: &'a BTreeMap<(u64, Round), (HashValue, Vec<SignedTransaction>)>,
+    block: &Block,
+) -> Option<&'a Vec<SignedTransaction>> {
+    block_transactions
+        .get(&(block.epoch(), block.round()))
+        .and_then(|(block_id, transactions)| (*block_id == block.id()).then_some(transactions))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consensus_observer::test_utils::{
+        create_block, create_ledger_info, create_transactions,
+    };
+    use aptos_consensus_types::common::Payload;
+    use aptos_executor_types::StateComputeResult;
+    use aptos_network::application::storage::PeersAndMetadata;
+    use aptos_types::{
+        epoch_change::EpochChangeProof, validator_verifier::random_validator_verifier, PeerId,
+    };
+    use std::{
+        collections::HashMap,
+        sync::atomic::{AtomicU64, Ordering},
+    };
+
+    #[test]
+    fn test_subscriptions() {
+        // Create a publisher that serves at most two subscribers
+        let consensus_observer_config = ConsensusObserverConfig {
+            max_num_subscribers: 2,
+            ..ConsensusObserverConfig::default()
+        };
+        let (consensus_publisher, _) = create_consensus_publisher(consensus_observer_config);
+
+        // Subscribe three peers and verify that the last one is rejected
+        let peers: Vec<_> = (0..3).map(|_| create_peer()).collect();
+        for peer in &peers {
+            handle_message(
+                &consensus_publisher,
+                peer,
+                ConsensusObserverMessage::Subscribe,
+            );
+        }
+        let active_subscribers = consensus_publisher.active_subscribers.lock().clone();
+        assert_eq!(active_subscribers, HashSet::from([peers[0], peers[1]]));
+
+        // Verify that duplicate subscriptions are accepted
+        handle_message(
+            &consensus_publisher,
+            &peers[0],
+            ConsensusObserverMessage::Subscribe,
+        );
+        assert_eq!(consensus_publisher.active_subscribers.lock().len(), 2);
+
+        // Unsubscribe a peer and verify that the next one can subscribe
+        handle_message(
+            &consensus_publisher,
+            &peers[0],
+            ConsensusObserverMessage::Unsubscribe,
+        );
+        handle_message(
+            &consensus_publisher,
+            &peers[2],
+            ConsensusObserverMessage::Subscribe,
+        );
+        let active_subscribers = consensus_publisher.active_subscribers.lock().clone();
+        assert_eq!(active_subscribers, HashSet::from([peers[1], peers[2]]));
+    }
+
+    #[test]
+    fn test_ordered_blocks_then_commit_decision() {
+        // Create a publisher with a single subscriber
+        let (consensus_publisher, _) =
+            create_consensus_publisher(ConsensusObserverConfig::default());
+        handle_message(
+            &consensus_publisher,
+            &create_peer(),
+            ConsensusObserverMessage::Subscribe,
+        );
+
+        // Create a chain of two ordered blocks
+        let (signers, verifier) = random_validator_verifier(4, None, false);
+        let genesis_block = Block::make_genesis_block();
+        let block_1 = create_block(&genesis_block, 1, 1, Payload::DirectMempool(vec![]));
+        let block_2 = create_block(&block_1, 2, 2, Payload::DirectMempool(vec![]));
+        let executed_blocks: Vec<_> = [&block_1, &block_2]
+            .into_iter()
+            .map(|block| {
+                Arc::new(ExecutedBlock::new(
+                    block.clone(),
+                    StateComputeResult::new_dummy(),
+                ))
+            })
+            .collect();
+        let ordered_proof = create_ledger_info(
+            &signers,
+            &verifier,
+            block_2.gen_block_info(HashValue::zero(), 0, None),
+        );
+
+        // Verify that the ordered blocks are held back until all transactions are cached
+        consensus_publisher.publish_ordered_blocks(&executed_blocks, ordered_proof);
+        assert_eq!(consensus_publisher.pending_ordered_blocks.lock().len(), 1);
+        consensus_publisher.cache_block_transactions(&block_1, &create_transactions(2));
+        assert_eq!(consensus_publisher.pending_ordered_blocks.lock().len(), 1);
+
+        // Verify that transactions cached for a different block are not used
+        let forked_block = create_block(&block_1, 2, 3, Payload::DirectMempool(vec![]));
+        consensus_publisher.cache_block_transactions(&forked_block, &create_transactions(1));
+        assert_eq!(consensus_publisher.pending_ordered_blocks.lock().len(), 1);
+
+        // Verify that the ordered blocks are published once all transactions are cached
+        consensus_publisher.cache_block_transactions(&block_2, &create_transactions(1));
+        assert!(consensus_publisher.pending_ordered_blocks.lock().is_empty());
+        assert_eq!(consensus_publisher.block_transactions.lock().len(), 2);
+
+        // Verify that the commit decision garbage collects the cached transactions
+        let commit_proof = create_ledger_info(&signers, &verifier, executed_blocks[1].block_info());
+        consensus_publisher.publish_commit_decision(&commit_proof);
+        assert!(consensus_publisher.block_transactions.lock().is_empty());
+    }
+
+    #[test]
+    fn test_stale_ordered_blocks() {
+        // Create a publisher with a single subscriber
+        let (consensus_publisher, _) =
+            create_consensus_publisher(ConsensusObserverConfig::default());
+        handle_message(
+            &consensus_publisher,
+            &create_peer(),
+            ConsensusObserverMessage::Subscribe,
+        );
+
+        // Queue an ordered block whose transactions are never cached
+        let (signers, verifier) = random_validator_verifier(4, None, false);
+        let block = create_block(
+            &Block::make_genesis_block(),
+            1,
+            1,
+            Payload::DirectMempool(vec![]),
+        );
+        let executed_block = Arc::new(ExecutedBlock::new(
+            block.clone(),
+            StateComputeResult::new_dummy(),
+        ));
+        let ordered_proof = create_ledger_info(
+            &signers,
+            &verifier,
+            block.gen_block_info(HashValue::zero(), 0, None),
+        );
+        consensus_publisher.publish_ordered_blocks(&[executed_block.clone()], ordered_proof);
+        assert_eq!(consensus_publisher.pending_ordered_blocks.lock().len(), 1);
+
+        // Verify that the commit decision drops the stale ordered block
+        let commit_proof = create_ledger_info(&signers, &verifier, executed_block.block_info());
+        consensus_publisher.publish_commit_decision(&commit_proof);
+        assert!(consensus_publisher.pending_ordered_blocks.lock().is_empty());
+
+        // Verify that transactions are not cached once there is nothing to publish to
+        let consensus_publisher = create_consensus_publisher(ConsensusObserverConfig::default()).0;
+        consensus_publisher.cache_block_transactions(&block, &create_transactions(1));
+        assert!(consensus_publisher.block_transactions.lock().is_empty());
+    }
+
+    #[test]
+    fn test_epoch_change_proof_requests() {
+        let (consensus_publisher, db_reader) =
+            create_consensus_publisher(ConsensusObserverConfig::default());
+        let peer = create_peer();
+        let request = ConsensusObserverMessage::EpochChangeProofRequest(EpochChangeProofRequest {
+            start_epoch: 1,
+            end_epoch: 3,
+        });
+
+        // Verify that requests from unsubscribed peers are ignored
+        handle_message(&consensus_publisher, &peer, request.clone());
+        assert_eq!(db_reader.num_requests.load(Ordering::Relaxed), 0);
+
+        // Verify that invalid requests are ignored
+        handle_message(
+            &consensus_publisher,
+            &peer,
+            ConsensusObserverMessage::Subscribe,
+        );
+        let invalid_request =
+            ConsensusObserverMessage::EpochChangeProofRequest(EpochChangeProofRequest {
+                start_epoch: 3,
+                end_epoch: 3,
+            });
+        handle_message(&consensus_publisher, &peer, invalid_request);
+        assert_eq!(db_reader.num_requests.load(Ordering::Relaxed), 0);
+
+        // Verify that requests from subscribers are served from storage
+        handle_message(&consensus_publisher, &peer, request);
+        assert_eq!(db_reader.num_requests.load(Ordering::Relaxed), 1);
+    }
+
+    /// A mock database that counts the epoch change proof requests
+    #[derive(Default)]
+    struct MockDbReader {
+        num_requests: AtomicU64,
+    }
+
+    impl DbReader for MockDbReader {
+        fn get_epoch_ending_ledger_infos(
+            &self,
+            _start_epoch: u64,
+            _end_epoch: u64,
+        ) -> anyhow::Result<EpochChangeProof> {
+            self.num_requests.fetch_add(1, Ordering::Relaxed);
+            Ok(EpochChangeProof::new(vec![], false))
+        }
+    }
+
+    /// Creates a publisher (without any network senders) and its mock database
+    fn create_consensus_publisher(
+        consensus_observer_config: ConsensusObserverConfig,
+    ) -> (ConsensusPublisher, Arc<MockDbReader>) {
+        let network_client = NetworkClient::new(
+            vec![],
+            vec![],
+            HashMap::new(),
+            PeersAndMetadata::new(&[NetworkId::Public]),
+        );
+        let db_reader = Arc::new(MockDbReader::default());
+        let consensus_publisher =
+            ConsensusPublisher::new(consensus_observer_config, network_client, db_reader.clone());
+        (consensus_publisher, db_reader)
+    }
+
+    /// Creates a random peer on the public network
+    fn create_peer() -> PeerNetworkId {
+        PeerNetworkId::new(NetworkId::Public, PeerId::random())
+    }
+
+    /// Handles the given message from the specified peer
+    fn handle_message(
+        consensus_publisher: &ConsensusPublisher,
+        peer_network_id: &PeerNetworkId,
+        message: ConsensusObserverMessage,
+    ) {
+        consensus_publisher.handle_network_event(
+            peer_network_id.network_id(),
+            Event::Message(peer_network_id.peer_id(), message),
+        );
+    }
+}
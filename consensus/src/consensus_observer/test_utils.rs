@@ -0,0 +1,86 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_consensus_types::{
+    block::Block,
+    common::{Payload, Round},
+    quorum_cert::QuorumCert,
+    vote_data::VoteData,
+};
+use aptos_crypto::{
+    ed25519::{Ed25519PrivateKey, Ed25519Signature},
+    HashValue, PrivateKey, Uniform,
+};
+use aptos_types::{
+    account_address::AccountAddress,
+    aggregate_signature::{AggregateSignature, PartialSignatures},
+    block_info::BlockInfo,
+    chain_id::ChainId,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    transaction::{RawTransaction, Script, SignedTransaction, TransactionPayload},
+    validator_signer::ValidatorSigner,
+    validator_verifier::ValidatorVerifier,
+};
+
+/// Creates a proposal block (with the given payload) that extends the given parent
+pub(crate) fn create_block(
+    parent: &Block,
+    round: Round,
+    timestamp_usecs: u64,
+    payload: Payload,
+) -> Block {
+    let parent_info = parent.gen_block_info(HashValue::zero(), 0, None);
+    let quorum_cert = QuorumCert::new(
+        VoteData::new(parent_info.clone(), parent_info),
+        LedgerInfoWithSignatures::new(
+            LedgerInfo::new(BlockInfo::empty(), HashValue::zero()),
+            AggregateSignature::empty(),
+        ),
+    );
+    Block::new_proposal(
+        payload,
+        round,
+        timestamp_usecs,
+        quorum_cert,
+        &ValidatorSigner::random(None),
+        vec![],
+    )
+    .unwrap()
+}
+
+/// Creates a ledger info for the given commit info, signed by the given signers
+pub(crate) fn create_ledger_info(
+    signers: &[ValidatorSigner],
+    verifier: &ValidatorVerifier,
+    commit_info: BlockInfo,
+) -> LedgerInfoWithSignatures {
+    let ledger_info = LedgerInfo::new(commit_info, HashValue::zero());
+    let mut partial_signatures = PartialSignatures::empty();
+    for signer in signers {
+        partial_signatures.add_signature(signer.author(), signer.sign(&ledger_info).unwrap());
+    }
+    let aggregate_signature = verifier.aggregate_signatures(&partial_signatures).unwrap();
+    LedgerInfoWithSignatures::new(ledger_info, aggregate_signature)
+}
+
+/// Creates the given number of (unique) test transactions
+pub(crate) fn create_transactions(num_transactions: usize) -> Vec<SignedTransaction> {
+    (0..num_transactions)
+        .map(|_| {
+            let raw_transaction = RawTransaction::new(
+                AccountAddress::random(),
+                0,
+                TransactionPayload::Script(Script::new(vec![], vec![], vec![])),
+                0,
+                0,
+                0,
+                ChainId::new(10),
+            );
+            SignedTransaction::new(
+                raw_transaction,
+                Ed25519PrivateKey::generate_for_testing().public_key(),
+                Ed25519Signature::dummy_signature(),
+            )
+        })
+        .collect()
+}
@@ -3,6 +3,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    consensus_observer::{
+        network_message::ConsensusObserverMessage, observer::ConsensusObserver,
+        publisher::ConsensusPublisher,
+    },
     counters,
     epoch_manager::EpochManager,
     network::NetworkTask,
@@ -36,6 +40,7 @@ pub fn start_consensus(
     consensus_to_mempool_sender: mpsc::Sender<QuorumStoreRequest>,
     aptos_db: DbReaderWriter,
     reconfig_events: ReconfigNotificationListener<DbBackedOnChainConfig>,
+    consensus_publisher: Option<Arc<ConsensusPublisher>>,
 ) -> Runtime {
     let runtime = aptos_runtimes::spawn_named_runtime("consensus".into(), None);
     let storage = Arc::new(StorageWriteProxy::new(node_config, aptos_db.reader.clone()));
@@ -51,6 +56,7 @@ pub fn start_consensus(
         txn_notifier,
         state_sync_notifier,
         runtime.handle(),
+        consensus_publisher.clone(),
     ));
 
     let time_service = Arc::new(ClockTimeService::new(runtime.handle().clone()));
@@ -73,6 +79,7 @@ pub fn start_consensus(
         quorum_store_db,
         reconfig_events,
         bounded_executor,
+        consensus_publisher,
     );
 
     let (network_task, network_receiver) = NetworkTask::new(network_service_events, self_receiver);
@@ -83,3 +90,44 @@ pub fn start_consensus(
     debug!("Consensus started.");
     runtime
 }
+
+/// Helper function to start the consensus observer (on fullnodes) and return the runtime
+pub fn start_consensus_observer(
+    node_config: &NodeConfig,
+    network_client: NetworkClient<ConsensusObserverMessage>,
+    network_service_events: NetworkServiceEvents<ConsensusObserverMessage>,
+    consensus_publisher: Option<Arc<ConsensusPublisher>>,
+    state_sync_notifier: Arc<dyn ConsensusNotificationSender>,
+    consensus_to_mempool_sender: mpsc::Sender<QuorumStoreRequest>,
+    aptos_db: DbReaderWriter,
+    reconfig_events: ReconfigNotificationListener<DbBackedOnChainConfig>,
+) -> Runtime {
+    let runtime = aptos_runtimes::spawn_named_runtime("observer".into(), None);
+
+    let txn_notifier = Arc::new(MempoolNotifier::new(
+        consensus_to_mempool_sender,
+        node_config.consensus.mempool_executed_txn_timeout_ms,
+    ));
+
+    let db_reader = aptos_db.reader.clone();
+    let execution_proxy = Arc::new(ExecutionProxy::new(
+        Arc::new(BlockExecutor::<AptosVM>::new(aptos_db)),
+        txn_notifier,
+        state_sync_notifier,
+        runtime.handle(),
+        None,
+    ));
+
+    let consensus_observer = ConsensusObserver::new(
+        node_config.consensus_observer,
+        network_client,
+        execution_proxy,
+        db_reader,
+        reconfig_events,
+        consensus_publisher,
+    );
+    runtime.spawn(consensus_observer.start(network_service_events));
+
+    debug!("Consensus observer started.");
+    runtime
+}
@@ -12,7 +12,8 @@ use aptos_consensus_types::{block::Block, quorum_cert::QuorumCert};
 use aptos_crypto::HashValue;
 use aptos_logger::prelude::*;
 use aptos_schemadb::{
-    schema::Schema, Options, ReadOptions, SchemaBatch, DB, DEFAULT_COLUMN_FAMILY_NAME,
+    schema::Schema, ColumnFamilyName, Options, ReadOptions, SchemaBatch, DB,
+    DEFAULT_COLUMN_FAMILY_NAME,
 };
 pub use schema::{
     block::BlockSchema,
@@ -49,18 +50,23 @@ pub struct ConsensusDB {
     db: DB,
 }
 
+/// Returns the column families of the consensus db
+fn consensus_db_column_families() -> Vec<ColumnFamilyName> {
+    vec![
+        /* UNUSED CF = */ DEFAULT_COLUMN_FAMILY_NAME,
+        BLOCK_CF_NAME,
+        QC_CF_NAME,
+        SINGLE_ENTRY_CF_NAME,
+        NODE_CF_NAME,
+        CERTIFIED_NODE_CF_NAME,
+        DAG_VOTE_CF_NAME,
+        ORDERED_ANCHOR_ID_CF_NAME,
+    ]
+}
+
 impl ConsensusDB {
     pub fn new<P: AsRef<Path> + Clone>(db_root_path: P) -> Self {
-        let column_families = vec![
-            /* UNUSED CF = */ DEFAULT_COLUMN_FAMILY_NAME,
-            BLOCK_CF_NAME,
-            QC_CF_NAME,
-            SINGLE_ENTRY_CF_NAME,
-            NODE_CF_NAME,
-            CERTIFIED_NODE_CF_NAME,
-            DAG_VOTE_CF_NAME,
-            ORDERED_ANCHOR_ID_CF_NAME,
-        ];
+        let column_families = consensus_db_column_families();
 
         let path = db_root_path.as_ref().join(CONSENSUS_DB_NAME);
         let instant = Instant::now();
@@ -79,6 +85,19 @@ impl ConsensusDB {
         Self { db }
     }
 
+    /// Opens an existing ConsensusDB in read-only mode (e.g., for offline inspection)
+    #[cfg(feature = "db-tool")]
+    pub fn new_readonly<P: AsRef<Path>>(db_root_path: P) -> Result<Self> {
+        let path = db_root_path.as_ref().join(CONSENSUS_DB_NAME);
+        let db = DB::open_cf_readonly(
+            &Options::default(),
+            path,
+            "consensus",
+            consensus_db_column_families(),
+        )?;
+        Ok(Self { db })
+    }
+
     pub fn get_data(
         &self,
     ) -> Result<(
@@ -128,6 +128,15 @@ pub static TXN_SHUFFLE_SECONDS: Lazy<Histogram> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Number of transactions deferred by the conflict aware shuffler due to conflicting read/write hints
+pub static TXN_SHUFFLE_CONFLICTS_DEFERRED: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_execution_transaction_shuffle_conflicts_deferred",
+        "Number of transactions deferred by the conflict aware shuffler due to conflicts"
+    )
+    .unwrap()
+});
+
 /// Transaction dedup call latency
 pub static TXN_DEDUP_SECONDS: Lazy<Histogram> = Lazy::new(|| {
     register_histogram!(
@@ -508,6 +517,25 @@ pub static ROUND_TIMEOUT_MS: Lazy<IntGauge> = Lazy::new(|| {
     .unwrap()
 });
 
+/// The base round timeout (i.e., before backoff), as adapted to the observed round latencies.
+pub static ADAPTIVE_ROUND_TIMEOUT_BASE_MS: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_consensus_adaptive_round_timeout_base_ms",
+        "The base round timeout (i.e., before backoff), as adapted to the observed round latencies."
+    )
+    .unwrap()
+});
+
+/// The observed latency (in seconds) between a round starting and a QC forming for it.
+pub static OBSERVED_ROUND_LATENCY: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        "aptos_consensus_observed_round_latency_s",
+        "The observed latency (in seconds) between a round starting and a QC forming for it.",
+        exponential_buckets(/*start=*/ 0.05, /*factor=*/ 1.5, /*count=*/ 15).unwrap(),
+    )
+    .unwrap()
+});
+
 ////////////////////////
 // SYNC MANAGER COUNTERS
 ////////////////////////
@@ -640,6 +668,16 @@ pub static PENDING_CONSENSUS_NETWORK_EVENTS: Lazy<IntCounterVec> = Lazy::new(||
     .unwrap()
 });
 
+/// Counter of pending network events to the consensus observer (and publisher)
+pub static PENDING_CONSENSUS_OBSERVER_NETWORK_EVENTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_consensus_observer_pending_network_events",
+        "Counters(queued,dequeued,dropped) related to pending network notifications to the consensus observer",
+        &["state"]
+    )
+    .unwrap()
+});
+
 /// Count of the pending state sync notification.
 pub static PENDING_STATE_SYNC_NOTIFICATION: Lazy<IntGauge> = Lazy::new(|| {
     register_int_gauge!(
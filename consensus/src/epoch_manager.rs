@@ -7,6 +7,7 @@ use crate::{
         tracing::{observe_block, BlockStage},
         BlockStore,
     },
+    consensus_observer::publisher::ConsensusPublisher,
     counters,
     error::{error_kind, DbError},
     experimental::{
@@ -26,7 +27,9 @@ use crate::{
         proposer_election::ProposerElection,
         rotating_proposer_election::{choose_leader, RotatingProposer},
         round_proposer_election::RoundProposer,
-        round_state::{ExponentialTimeInterval, RoundState},
+        round_state::{
+            AdaptiveTimeInterval, ExponentialTimeInterval, RoundState, RoundTimeInterval,
+        },
     },
     logging::{LogEvent, LogSchema},
     metrics_safety_rules::MetricsSafetyRules,
@@ -140,6 +143,8 @@ pub struct EpochManager<P: OnChainConfigProvider> {
     bounded_executor: BoundedExecutor,
     // recovery_mode is set to true when the recovery manager is spawned
     recovery_mode: bool,
+    // publishes ordered blocks to consensus observers (if enabled)
+    consensus_publisher: Option<Arc<ConsensusPublisher>>,
 }
 
 impl<P: OnChainConfigProvider> EpochManager<P> {
@@ -155,6 +160,7 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
         quorum_store_storage: Arc<dyn QuorumStoreStorage>,
         reconfig_events: ReconfigNotificationListener<P>,
         bounded_executor: BoundedExecutor,
+        consensus_publisher: Option<Arc<ConsensusPublisher>>,
     ) -> Self {
         let author = node_config.validator_network.as_ref().unwrap().peer_id();
         let config = node_config.consensus.clone();
@@ -186,6 +192,7 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
             batch_retrieval_tx: None,
             bounded_executor,
             recovery_mode: false,
+            consensus_publisher,
         }
     }
 
@@ -204,11 +211,25 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
         time_service: Arc<dyn TimeService>,
         timeout_sender: aptos_channels::Sender<Round>,
     ) -> RoundState {
-        let time_interval = Box::new(ExponentialTimeInterval::new(
-            Duration::from_millis(self.config.round_initial_timeout_ms),
-            self.config.round_timeout_backoff_exponent_base,
-            self.config.round_timeout_backoff_max_exponent,
-        ));
+        let time_interval: Box<dyn RoundTimeInterval> =
+            if self.config.round_timeout_adaptive_enabled {
+                Box::new(AdaptiveTimeInterval::new(
+                    Duration::from_millis(self.config.round_initial_timeout_ms),
+                    Duration::from_millis(self.config.round_timeout_adaptive_min_ms),
+                    Duration::from_millis(self.config.round_timeout_adaptive_max_ms),
+                    self.config.round_timeout_adaptive_latency_multiplier,
+                    Duration::from_millis(self.config.round_timeout_adaptive_window_ms),
+                    self.config.round_timeout_adaptive_min_num_latencies,
+                    self.config.round_timeout_backoff_exponent_base,
+                    self.config.round_timeout_backoff_max_exponent,
+                ))
+            } else {
+                Box::new(ExponentialTimeInterval::new(
+                    Duration::from_millis(self.config.round_initial_timeout_ms),
+                    self.config.round_timeout_backoff_exponent_base,
+                    self.config.round_timeout_backoff_max_exponent,
+                ))
+            };
         RoundState::new(time_interval, time_service, timeout_sender)
     }
 
@@ -523,7 +544,12 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
         tokio::spawn(persisting_phase.start());
         tokio::spawn(buffer_manager.start());
 
-        OrderingStateComputer::new(block_tx, self.commit_state_computer.clone(), reset_tx)
+        OrderingStateComputer::new(
+            block_tx,
+            self.commit_state_computer.clone(),
+            reset_tx,
+            self.consensus_publisher.clone(),
+        )
     }
 
     async fn shutdown_current_processor(&mut self) {
@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    consensus_observer::publisher::ConsensusPublisher,
     error::StateSyncError,
     experimental::{
         buffer_manager::{OrderedBlocks, ResetAck, ResetRequest},
@@ -35,6 +36,8 @@ pub struct OrderingStateComputer {
     executor_channel: UnboundedSender<OrderedBlocks>,
     state_computer_for_sync: Arc<dyn StateComputer>,
     reset_event_channel_tx: UnboundedSender<ResetRequest>,
+    // publishes the ordered blocks to consensus observers (if enabled)
+    consensus_publisher: Option<Arc<ConsensusPublisher>>,
 }
 
 impl OrderingStateComputer {
@@ -42,11 +45,13 @@ impl OrderingStateComputer {
         executor_channel: UnboundedSender<OrderedBlocks>,
         state_computer_for_sync: Arc<dyn StateComputer>,
         reset_event_channel_tx: UnboundedSender<ResetRequest>,
+        consensus_publisher: Option<Arc<ConsensusPublisher>>,
     ) -> Self {
         Self {
             executor_channel,
             state_computer_for_sync,
             reset_event_channel_tx,
+            consensus_publisher,
         }
     }
 }
@@ -77,6 +82,10 @@ impl StateComputer for OrderingStateComputer {
     ) -> Result<(), ExecutionError> {
         assert!(!blocks.is_empty());
 
+        if let Some(consensus_publisher) = &self.consensus_publisher {
+            consensus_publisher.publish_ordered_blocks(blocks, finality_proof.clone());
+        }
+
         if self
             .executor_channel
             .clone()
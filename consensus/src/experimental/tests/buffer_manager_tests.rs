@@ -126,6 +126,7 @@ pub fn prepare_buffer_manager() -> (
         result_tx,
         Arc::new(EmptyStateComputer),
         reset_tx,
+        None,
     ));
 
     let (block_tx, block_rx) = create_channel::<OrderedBlocks>();
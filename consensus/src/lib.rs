@@ -16,6 +16,7 @@
 extern crate core;
 
 mod block_storage;
+mod conflict_aware_shuffler;
 mod consensusdb;
 mod dag;
 mod epoch_manager;
@@ -42,6 +43,8 @@ mod twins;
 mod txn_notifier;
 mod util;
 
+/// Consensus observer for fullnodes
+pub mod consensus_observer;
 /// AptosBFT implementation
 pub mod consensus_provider;
 /// Required by the telemetry service
@@ -61,6 +64,9 @@ pub use consensusdb::CONSENSUS_DB_NAME;
 pub use quorum_store::quorum_store_db::QUORUM_STORE_DB_NAME;
 #[cfg(feature = "fuzzing")]
 pub use round_manager::round_manager_fuzzing;
+/// Required by the db tool
+#[cfg(feature = "db-tool")]
+pub use util::db_tool;
 
 struct IntGaugeGuard {
     gauge: IntGauge,
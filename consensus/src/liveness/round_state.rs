@@ -13,6 +13,7 @@ use aptos_consensus_types::{
 };
 use aptos_crypto::HashValue;
 use aptos_logger::{prelude::*, Schema};
+use aptos_moving_average::MovingAverage;
 use aptos_types::{
     ledger_info::LedgerInfoWithPartialSignatures, validator_verifier::ValidatorVerifier,
 };
@@ -72,6 +73,10 @@ pub trait RoundTimeInterval: Send + Sync + 'static {
     /// to calculate the round duration of round 6 and the highest committed round is 3 (meaning
     /// the highest round to commit a block is round 5, then the round index is 0.
     fn get_round_duration(&self, round_index_after_committed_qc: usize) -> Duration;
+
+    /// Records the observed latency of a successful round (i.e., the time between the round
+    /// starting locally and a QC forming for it). `now` is the time of the observation.
+    fn record_round_latency(&mut self, _now: Duration, _latency: Duration) {}
 }
 
 /// Round durations increase exponentially
@@ -123,6 +128,85 @@ impl RoundTimeInterval for ExponentialTimeInterval {
     }
 }
 
+/// Round durations increase exponentially (as in `ExponentialTimeInterval`), but the base
+/// duration adapts to the round latencies observed over a sliding window, i.e.,
+/// base = clamp(avg_latency * latency_multiplier, min_base, max_base).
+/// Until enough latencies have been observed, the initial base duration is used.
+pub struct AdaptiveTimeInterval {
+    // The exponential backoff on top of the adapted base duration
+    backoff: ExponentialTimeInterval,
+    // Bounds for the adapted base duration
+    min_base_ms: u64,
+    max_base_ms: u64,
+    // The base duration is the average observed latency times this multiplier
+    latency_multiplier: f64,
+    // The minimum number of latencies in the window before the base duration is adapted
+    min_num_latencies: usize,
+    // The observed round latencies (in ms) over the sliding window
+    round_latencies: MovingAverage,
+}
+
+impl AdaptiveTimeInterval {
+    pub fn new(
+        initial_base: Duration,
+        min_base: Duration,
+        max_base: Duration,
+        latency_multiplier: f64,
+        window: Duration,
+        min_num_latencies: usize,
+        exponent_base: f64,
+        max_exponent: usize,
+    ) -> Self {
+        assert!(
+            min_base <= max_base,
+            "min_base for AdaptiveTimeInterval should be <= max_base"
+        );
+        assert!(
+            latency_multiplier >= 1.0,
+            "latency_multiplier for AdaptiveTimeInterval should be >= 1"
+        );
+        counters::ADAPTIVE_ROUND_TIMEOUT_BASE_MS.set(initial_base.as_millis() as i64);
+
+        AdaptiveTimeInterval {
+            backoff: ExponentialTimeInterval::new(initial_base, exponent_base, max_exponent),
+            min_base_ms: min_base.as_millis() as u64,
+            max_base_ms: max_base.as_millis() as u64,
+            latency_multiplier,
+            min_num_latencies,
+            round_latencies: MovingAverage::new(window.as_millis() as u64),
+        }
+    }
+
+    /// Returns the current base duration (i.e., the duration of round index 0)
+    #[cfg(test)]
+    pub fn base_duration(&self) -> Duration {
+        Duration::from_millis(self.backoff.base_ms)
+    }
+}
+
+impl RoundTimeInterval for AdaptiveTimeInterval {
+    fn get_round_duration(&self, round_index_after_committed_qc: usize) -> Duration {
+        self.backoff
+            .get_round_duration(round_index_after_committed_qc)
+    }
+
+    fn record_round_latency(&mut self, now: Duration, latency: Duration) {
+        counters::OBSERVED_ROUND_LATENCY.observe(latency.as_secs_f64());
+        self.round_latencies
+            .tick(now.as_millis() as u64, latency.as_millis() as u64);
+
+        // Only adapt once there are enough latencies to be representative
+        let num_latencies = self.round_latencies.len();
+        if num_latencies < self.min_num_latencies.max(1) {
+            return;
+        }
+        let average_latency_ms = self.round_latencies.sum() as f64 / num_latencies as f64;
+        let base_ms = (average_latency_ms * self.latency_multiplier).ceil() as u64;
+        self.backoff.base_ms = base_ms.clamp(self.min_base_ms, self.max_base_ms);
+        counters::ADAPTIVE_ROUND_TIMEOUT_BASE_MS.set(self.backoff.base_ms as i64);
+    }
+}
+
 /// `RoundState` contains information about a specific round and moves forward when
 /// receives new certificates.
 ///
@@ -147,6 +231,9 @@ pub struct RoundState {
     highest_committed_round: Round,
     // Current round is max{highest_qc, highest_tc} + 1.
     current_round: Round,
+    // The time at which the current round started locally.
+    // Represents as Duration since UNIX_EPOCH.
+    current_round_start_time: Duration,
     // The deadline for the next local timeout event. It is reset every time a new round start, or
     // a previous deadline expires.
     // Represents as Duration since UNIX_EPOCH.
@@ -200,6 +287,7 @@ impl RoundState {
             time_interval,
             highest_committed_round: 0,
             current_round: 0,
+            current_round_start_time: time_service.get_current_timestamp(),
             current_round_deadline: time_service.get_current_timestamp(),
             time_service,
             timeout_sender,
@@ -246,11 +334,6 @@ impl RoundState {
         if new_round > self.current_round {
             let (prev_round_votes, prev_round_timeout_votes) = self.pending_votes.drain_votes();
 
-            // Start a new round.
-            self.current_round = new_round;
-            self.pending_votes = PendingVotes::new();
-            self.vote_sent = None;
-            let timeout = self.setup_timeout(1);
             // The new round reason is QCReady in case both QC.round + 1 == new_round, otherwise
             // it's Timeout and TC.round + 1 == new_round.
             let new_round_reason = if sync_info.highest_certified_round() + 1 == new_round {
@@ -258,6 +341,23 @@ impl RoundState {
             } else {
                 NewRoundReason::Timeout
             };
+
+            // Only a QC for the round we were in shows how long a full round takes locally
+            let now = self.time_service.get_current_timestamp();
+            if self.current_round > 0
+                && new_round == self.current_round + 1
+                && new_round_reason == NewRoundReason::QCReady
+            {
+                let round_latency = now.saturating_sub(self.current_round_start_time);
+                self.time_interval.record_round_latency(now, round_latency);
+            }
+
+            // Start a new round.
+            self.current_round = new_round;
+            self.current_round_start_time = now;
+            self.pending_votes = PendingVotes::new();
+            self.vote_sent = None;
+            let timeout = self.setup_timeout(1);
             let new_round_event = NewRoundEvent {
                 round: self.current_round,
                 reason: new_round_reason,
@@ -4,9 +4,10 @@
 
 use crate::{
     liveness::round_state::{
-        ExponentialTimeInterval, NewRoundEvent, NewRoundReason, RoundState, RoundTimeInterval,
+        AdaptiveTimeInterval, ExponentialTimeInterval, NewRoundEvent, NewRoundReason, RoundState,
+        RoundTimeInterval,
     },
-    util::mock_time_service::SimulatedTimeService,
+    util::{mock_time_service::SimulatedTimeService, time_service::TimeService},
 };
 use aptos_consensus_types::{
     common::Round,
@@ -82,6 +83,86 @@ fn test_round_event_generation() {
     );
 }
 
+#[test]
+fn test_adaptive_round_time_interval() {
+    let mut interval = AdaptiveTimeInterval::new(
+        Duration::from_millis(1000),   /* initial base */
+        Duration::from_millis(500),    /* min base */
+        Duration::from_millis(2000),   /* max base */
+        2.0,                           /* latency multiplier */
+        Duration::from_millis(10_000), /* window */
+        3,                             /* min num latencies */
+        1.5,
+        2,
+    );
+
+    // The initial base is used until enough latencies are observed
+    interval.record_round_latency(Duration::from_millis(1000), Duration::from_millis(400));
+    interval.record_round_latency(Duration::from_millis(2000), Duration::from_millis(400));
+    assert_eq!(1000, interval.get_round_duration(0).as_millis());
+
+    // The base adapts to the average latency, and the backoff applies on top of it
+    interval.record_round_latency(Duration::from_millis(3000), Duration::from_millis(1000));
+    assert_eq!(
+        1200, /* (400+400+1000)/3*2 */
+        interval.get_round_duration(0).as_millis()
+    );
+    assert_eq!(1800, interval.get_round_duration(1).as_millis());
+    assert_eq!(2700, interval.get_round_duration(2).as_millis());
+    assert_eq!(2700, interval.get_round_duration(1000).as_millis());
+
+    // The base never exceeds the max
+    interval.record_round_latency(Duration::from_millis(4000), Duration::from_millis(10_000));
+    assert_eq!(2000, interval.base_duration().as_millis());
+
+    // Latencies outside of the window are dropped, so the base is kept until
+    // enough new latencies are observed
+    interval.record_round_latency(Duration::from_millis(20_000), Duration::from_millis(100));
+    interval.record_round_latency(Duration::from_millis(20_001), Duration::from_millis(100));
+    assert_eq!(2000, interval.base_duration().as_millis());
+
+    // The base never goes below the min
+    interval.record_round_latency(Duration::from_millis(20_002), Duration::from_millis(100));
+    assert_eq!(500, interval.base_duration().as_millis());
+}
+
+#[tokio::test]
+/// Verify that RoundState records the latencies of rounds that end with a QC
+async fn test_round_latency_recording() {
+    let time_service = Arc::new(SimulatedTimeService::new());
+    let time_interval = Box::new(AdaptiveTimeInterval::new(
+        Duration::from_millis(500),  /* initial base */
+        Duration::from_millis(10),   /* min base */
+        Duration::from_millis(1000), /* max base */
+        2.0,                         /* latency multiplier */
+        Duration::from_secs(60),     /* window */
+        1,                           /* min num latencies */
+        1.0,
+        0,
+    ));
+    let (timeout_tx, _timeout_rx) = aptos_channels::new_test(1_024);
+    let mut pm = RoundState::new(time_interval, time_service.clone(), timeout_tx);
+
+    // The first round uses the initial base
+    let event = pm.process_certificates(generate_sync_info(Some(0), None, None));
+    assert_eq!(Duration::from_millis(500), event.unwrap().timeout);
+
+    // A QC for the current round records its latency
+    time_service.sleep(Duration::from_millis(100)).await;
+    let event = pm.process_certificates(generate_sync_info(Some(1), None, None));
+    assert_eq!(Duration::from_millis(200), event.unwrap().timeout);
+
+    // A TC for the current round doesn't record a latency
+    time_service.sleep(Duration::from_millis(300)).await;
+    let event = pm.process_certificates(generate_sync_info(None, Some(2), None));
+    assert_eq!(Duration::from_millis(200), event.unwrap().timeout);
+
+    // A QC for a future round doesn't record a latency
+    time_service.sleep(Duration::from_millis(300)).await;
+    let event = pm.process_certificates(generate_sync_info(Some(5), None, None));
+    assert_eq!(Duration::from_millis(200), event.unwrap().timeout);
+}
+
 fn make_round_state() -> (RoundState, aptos_channels::Receiver<Round>) {
     let time_interval = Box::new(ExponentialTimeInterval::fixed(Duration::from_millis(2)));
     let simulated_time = SimulatedTimeService::auto_advance_until(Duration::from_millis(4));
@@ -16,17 +16,20 @@ use aptos_consensus_types::{
 };
 use aptos_crypto::HashValue;
 use aptos_executor_types::{Error::DataNotFound, *};
+use aptos_infallible::Mutex;
 use aptos_logger::prelude::*;
 use aptos_types::transaction::SignedTransaction;
 use futures::{channel::mpsc::Sender, SinkExt};
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 use tokio::sync::oneshot;
 
 /// Responsible to extract the transactions out of the payload and notify QuorumStore about commits.
 /// If QuorumStore is enabled, has to ask BatchReader for the transaction behind the proofs of availability in the payload.
+/// Consensus observers receive the transactions alongside the ordered blocks, so they look them up by block id.
 pub enum PayloadManager {
     DirectMempool,
     InQuorumStore(Arc<BatchStore<NetworkSender>>, Sender<CoordinatorCommand>),
+    ConsensusObserver(Arc<Mutex<HashMap<HashValue, Vec<SignedTransaction>>>>),
 }
 
 impl PayloadManager {
@@ -58,7 +61,7 @@ impl PayloadManager {
     ///Pass commit information to BatchReader and QuorumStore wrapper for their internal cleanups.
     pub async fn notify_commit(&self, block_timestamp: u64, payloads: Vec<Payload>) {
         match self {
-            PayloadManager::DirectMempool => {},
+            PayloadManager::DirectMempool | PayloadManager::ConsensusObserver(_) => {},
             PayloadManager::InQuorumStore(batch_store, coordinator_tx) => {
                 // TODO: move this to somewhere in quorum store, so this can be a batch reader
                 batch_store
@@ -101,7 +104,7 @@ impl PayloadManager {
             None => return,
         };
         match self {
-            PayloadManager::DirectMempool => {},
+            PayloadManager::DirectMempool | PayloadManager::ConsensusObserver(_) => {},
             PayloadManager::InQuorumStore(batch_store, _) => match payload {
                 Payload::InQuorumStore(proof_with_status) => {
                     if proof_with_status.status.lock().is_none() {
@@ -133,6 +136,11 @@ impl PayloadManager {
         };
 
         match (self, payload) {
+            (PayloadManager::ConsensusObserver(block_transactions), _) => block_transactions
+                .lock()
+                .get(&block.id())
+                .cloned()
+                .ok_or(DataNotFound(block.id())),
             (PayloadManager::DirectMempool, Payload::DirectMempool(txns)) => Ok(txns.clone()),
             (
                 PayloadManager::InQuorumStore(batch_store, _),
@@ -20,7 +20,7 @@ use aptos_mempool::QuorumStoreRequest;
 use aptos_types::{transaction::SignedTransaction, PeerId};
 use futures_channel::mpsc::Sender;
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     sync::Arc,
     time::{Duration, Instant},
 };
@@ -136,7 +136,9 @@ impl BatchGenerator {
     }
 
     /// Push num_txns from txns into batches. If num_txns is larger than max size, then multiple
-    /// batches are pushed.
+    /// batches are pushed. An account can have at most `sender_max_batch_txns_per_account` txns in
+    /// a batch; once it reaches the limit, all its remaining txns are skipped in this pull (so that
+    /// later sequence numbers don't get ahead of skipped ones) and left in mempool.
     fn push_bucket_to_batches(
         &mut self,
         batches: &mut Vec<Batch>,
@@ -144,17 +146,37 @@ impl BatchGenerator {
         num_txns_in_bucket: usize,
         expiry_time: u64,
         bucket_start: u64,
+        capped_accounts: &mut HashSet<PeerId>,
     ) -> bool {
-        let mut remaining_txns = num_txns_in_bucket;
-        while remaining_txns > 0 {
+        let mut bucket_txns = txns.drain(0..num_txns_in_bucket).peekable();
+        while bucket_txns.peek().is_some() {
             if batches.len() == self.config.sender_max_num_batches {
                 return false;
             }
-            let num_batch_txns = std::cmp::min(self.config.sender_max_batch_txns, remaining_txns);
-            let batch_txns: Vec<_> = txns.drain(0..num_batch_txns).collect();
-            let batch = self.create_new_batch(batch_txns, expiry_time, bucket_start);
-            batches.push(batch);
-            remaining_txns -= num_batch_txns;
+            let mut batch_txns = Vec::new();
+            let mut txns_per_account: HashMap<PeerId, usize> = HashMap::new();
+            while batch_txns.len() < self.config.sender_max_batch_txns {
+                let txn = match bucket_txns.next() {
+                    Some(txn) => txn,
+                    None => break,
+                };
+                if capped_accounts.contains(&txn.sender()) {
+                    counters::BATCH_PER_ACCOUNT_LIMIT_SKIPPED_TXNS.inc();
+                    continue;
+                }
+                let count = txns_per_account.entry(txn.sender()).or_insert(0);
+                if *count == self.config.sender_max_batch_txns_per_account {
+                    capped_accounts.insert(txn.sender());
+                    counters::BATCH_PER_ACCOUNT_LIMIT_SKIPPED_TXNS.inc();
+                    continue;
+                }
+                *count += 1;
+                batch_txns.push(txn);
+            }
+            if !batch_txns.is_empty() {
+                let batch = self.create_new_batch(batch_txns, expiry_time, bucket_start);
+                batches.push(batch);
+            }
         }
         true
     }
@@ -177,6 +199,7 @@ impl BatchGenerator {
             .cloned()
             .collect();
         let mut batches = vec![];
+        let mut capped_accounts = HashSet::new();
         for bucket_start in &reverse_buckets_excluding_zero {
             if pulled_txns.is_empty() {
                 break;
@@ -200,6 +223,7 @@ impl BatchGenerator {
                 num_txns_in_bucket,
                 expiry_time,
                 *bucket_start,
+                &mut capped_accounts,
             );
             if !batches_space_remaining {
                 return batches;
@@ -212,6 +236,7 @@ impl BatchGenerator {
                 pulled_txns.len(),
                 expiry_time,
                 0,
+                &mut capped_accounts,
             );
         }
         batches
@@ -300,6 +300,15 @@ pub static BATCH_PULL_FULL_TXNS: Lazy<Histogram> = Lazy::new(|| {
     )
 });
 
+/// Count of the proof pulls for a block done in descending gas bucket order, due to back pressure.
+pub static PROOF_PULL_BY_GAS_BUCKET_COUNT: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "quorum_store_proof_pull_by_gas_bucket_count",
+        "Count of the proof pulls for a block done in descending gas bucket order."
+    )
+    .unwrap()
+});
+
 /// Histogram for the number of txns excluded on pull for batches.
 pub static BATCH_PULL_EXCLUDED_TXNS: Lazy<Histogram> = Lazy::new(|| {
     register_histogram!(
@@ -319,6 +328,15 @@ pub static CREATED_BATCHES_COUNT: Lazy<IntCounter> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Count of the pulled txns left out of batches due to the per account batch limit.
+pub static BATCH_PER_ACCOUNT_LIMIT_SKIPPED_TXNS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "quorum_store_batch_per_account_limit_skipped_txns",
+        "Count of the pulled txns left out of batches due to the per account batch limit."
+    )
+    .unwrap()
+});
+
 /// Count of the created empty batches since last restart.
 pub static CREATED_EMPTY_BATCHES_COUNT: Lazy<IntCounter> = Lazy::new(|| {
     register_int_counter!(
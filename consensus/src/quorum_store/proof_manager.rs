@@ -29,6 +29,7 @@ pub struct ProofManager {
     remaining_total_txn_num: u64,
     back_pressure_total_proof_limit: u64,
     remaining_total_proof_num: u64,
+    pull_by_gas_bucket_under_back_pressure: bool,
 }
 
 impl ProofManager {
@@ -36,6 +37,7 @@ impl ProofManager {
         my_peer_id: PeerId,
         back_pressure_total_txn_limit: u64,
         back_pressure_total_proof_limit: u64,
+        pull_by_gas_bucket_under_back_pressure: bool,
     ) -> Self {
         Self {
             proofs_for_consensus: ProofQueue::new(my_peer_id),
@@ -43,6 +45,7 @@ impl ProofManager {
             remaining_total_txn_num: 0,
             back_pressure_total_proof_limit,
             remaining_total_proof_num: 0,
+            pull_by_gas_bucket_under_back_pressure,
         }
     }
 
@@ -88,11 +91,16 @@ impl ProofManager {
                     PayloadFilter::InQuorumStore(proofs) => proofs,
                 };
 
+                // When the backlog is high, not everything can make it into the next blocks, so
+                // prefer proofs of higher gas buckets regardless of which validator created them.
+                let order_by_gas_bucket = self.pull_by_gas_bucket_under_back_pressure
+                    && self.qs_back_pressure().txn_count;
                 let proof_block = self.proofs_for_consensus.pull_proofs(
                     &excluded_batches,
                     max_txns,
                     max_bytes,
                     return_non_full,
+                    order_by_gas_bucket,
                 );
 
                 let res = GetPayloadResponse::GetPayloadResponse(
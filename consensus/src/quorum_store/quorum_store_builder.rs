@@ -341,6 +341,7 @@ impl InnerBuilder {
                 .back_pressure
                 .backlog_per_validator_batch_limit_count
                 * self.num_validators,
+            self.config.proof_pull_by_gas_bucket_under_back_pressure,
         );
         spawn_named!(
             "proof_manager",
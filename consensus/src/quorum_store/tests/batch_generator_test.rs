@@ -6,8 +6,8 @@ use crate::quorum_store::{
     batch_generator::BatchGenerator,
     quorum_store_db::MockQuorumStoreDB,
     tests::utils::{
-        create_signed_transaction, create_vec_signed_transactions,
-        create_vec_signed_transactions_with_gas,
+        create_signed_transaction, create_signed_transaction_for_account,
+        create_vec_signed_transactions, create_vec_signed_transactions_with_gas,
     },
 };
 use aptos_config::config::QuorumStoreConfig;
@@ -417,6 +417,65 @@ async fn test_last_bucketed_batch() {
         .unwrap();
 }
 
+#[tokio::test]
+async fn test_max_batch_txns_per_account() {
+    let (quorum_store_to_mempool_tx, mut quorum_store_to_mempool_rx) = channel(1_024);
+    let (batch_coordinator_cmd_tx, mut batch_coordinator_cmd_rx) = TokioChannel(100);
+
+    let config = QuorumStoreConfig {
+        sender_max_batch_txns: 10,
+        sender_max_batch_txns_per_account: 3,
+        ..Default::default()
+    };
+    let max_batch_bytes = config.sender_max_batch_bytes;
+
+    let mut batch_generator = BatchGenerator::new(
+        0,
+        AccountAddress::random(),
+        config,
+        Arc::new(MockQuorumStoreDB::new()),
+        quorum_store_to_mempool_tx,
+        1000,
+    );
+
+    let join_handle = tokio::spawn(async move {
+        let busy_account = AccountAddress::random();
+        let mut signed_txns: Vec<_> = (0..5)
+            .map(|i| create_signed_transaction_for_account(busy_account, i, 1))
+            .collect();
+        signed_txns.append(&mut create_vec_signed_transactions(4));
+        queue_mempool_batch_response(
+            signed_txns.clone(),
+            max_batch_bytes,
+            &mut quorum_store_to_mempool_rx,
+        )
+        .await;
+
+        let quorum_store_command = batch_coordinator_cmd_rx.recv().await.unwrap();
+        if let BatchCoordinatorCommand::NewBatches(result) = quorum_store_command {
+            // The last two txns of the busy account are left in mempool
+            assert_eq!(result.len(), 1);
+            assert_eq!(result[0].num_txns(), 7);
+            let mut expected = signed_txns[0..3].to_vec();
+            expected.extend_from_slice(&signed_txns[5..]);
+            assert_eq!(result[0].clone().into_transactions(), expected);
+        } else {
+            panic!("Unexpected variant")
+        }
+    });
+
+    let result = batch_generator.handle_scheduled_pull(300).await;
+    batch_coordinator_cmd_tx
+        .send(BatchCoordinatorCommand::NewBatches(result))
+        .await
+        .unwrap();
+
+    timeout(Duration::from_millis(10_000), join_handle)
+        .await
+        .unwrap()
+        .unwrap();
+}
+
 #[tokio::test]
 async fn test_sender_max_num_batches_single_bucket() {
     let (quorum_store_to_mempool_tx, mut quorum_store_to_mempool_rx) = channel(1_024);
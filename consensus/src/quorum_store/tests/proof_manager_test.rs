@@ -13,7 +13,7 @@ use futures::channel::oneshot;
 use std::collections::HashSet;
 
 fn create_proof_manager() -> ProofManager {
-    ProofManager::new(PeerId::random(), 10, 10)
+    ProofManager::new(PeerId::random(), 10, 10, false)
 }
 
 fn create_proof(author: PeerId, expiration: u64, batch_sequence: u64) -> ProofOfStore {
@@ -177,6 +177,38 @@ async fn test_proposal_fairness() {
     get_proposal_and_assert(&mut proof_manager, 2, &filter, &peer0_proofs[3..4]).await;
 }
 
+#[tokio::test]
+async fn test_proposal_gas_bucket_priority_under_back_pressure() {
+    for back_pressure_total_txn_limit in [2, 10] {
+        let mut proof_manager =
+            ProofManager::new(PeerId::random(), back_pressure_total_txn_limit, 10, true);
+        let peer0 = PeerId::random();
+        let peer1 = PeerId::random();
+
+        let mut peer0_proofs = vec![];
+        for i in 0..3 {
+            let proof = create_proof_with_gas(peer0, 10, 1 + i, 1000);
+            proof_manager.receive_proofs(vec![proof.clone()]);
+            peer0_proofs.push(proof);
+        }
+        let peer1_proof = create_proof_with_gas(peer1, 10, 1, 0);
+        proof_manager.receive_proofs(vec![peer1_proof.clone()]);
+
+        if back_pressure_total_txn_limit == 2 {
+            // The backlog is high, so the proofs of the higher gas bucket are all pulled first
+            get_proposal_and_assert(&mut proof_manager, 3, &[], &peer0_proofs).await;
+        } else {
+            // Without back pressure, proofs are taken fairly from each peer
+            get_proposal_and_assert(&mut proof_manager, 3, &[], &[
+                peer0_proofs[0].clone(),
+                peer0_proofs[1].clone(),
+                peer1_proof.clone(),
+            ])
+            .await;
+        }
+    }
+}
+
 #[tokio::test]
 async fn test_duplicate_batches_on_commit() {
     let mut proof_manager = create_proof_manager();
@@ -13,13 +13,22 @@ use aptos_types::{
 
 // Creates a single test transaction for a random account
 pub(crate) fn create_signed_transaction(gas_unit_price: u64) -> SignedTransaction {
+    create_signed_transaction_for_account(AccountAddress::random(), 0, gas_unit_price)
+}
+
+// Creates a single test transaction for the given account and sequence number
+pub(crate) fn create_signed_transaction_for_account(
+    sender: AccountAddress,
+    sequence_number: u64,
+    gas_unit_price: u64,
+) -> SignedTransaction {
     let private_key = Ed25519PrivateKey::generate_for_testing();
     let public_key = private_key.public_key();
 
     let transaction_payload = TransactionPayload::Script(Script::new(vec![], vec![], vec![]));
     let raw_transaction = RawTransaction::new(
-        AccountAddress::random(),
-        0,
+        sender,
+        sequence_number,
         transaction_payload,
         0,
         gas_unit_price,
@@ -277,12 +277,15 @@ impl ProofQueue {
 
     // gets excluded and iterates over the vector returning non excluded or expired entries.
     // return the vector of pulled PoS, and the size of the remaining PoS
+    // If order_by_gas_bucket is set, proofs are pulled in descending gas bucket order across all
+    // authors (round-robin between authors within a bucket), otherwise round-robin between authors.
     pub(crate) fn pull_proofs(
         &mut self,
         excluded_batches: &HashSet<BatchInfo>,
         max_txns: u64,
         max_bytes: u64,
         return_non_full: bool,
+        order_by_gas_bucket: bool,
     ) -> Vec<ProofOfStore> {
         let mut ret = vec![];
         let mut cur_bytes = 0;
@@ -290,44 +293,61 @@ impl ProofQueue {
         let mut excluded_txns = 0;
         let mut full = false;
 
-        let mut iters = vec![];
-        for (_, batches) in self.author_to_batches.iter() {
-            iters.push(batches.iter().rev());
-        }
-
-        while !iters.is_empty() {
-            iters.shuffle(&mut thread_rng());
-            iters.retain_mut(|iter| {
-                if full {
+        // Returns false once the limit for requested bytes or number of transactions is reached.
+        let mut try_add_batch = |sort_key: &BatchSortKey, batch: &BatchInfo| -> bool {
+            if excluded_batches.contains(batch) {
+                excluded_txns += batch.num_txns();
+            } else if let Some(Some((proof, insertion_time))) =
+                self.batch_to_proof.get(&sort_key.batch_key)
+            {
+                cur_bytes += batch.num_bytes();
+                cur_txns += batch.num_txns();
+                if cur_bytes > max_bytes || cur_txns > max_txns {
+                    // Exceeded the limit for requested bytes or number of transactions.
                     return false;
                 }
-                if let Some((sort_key, batch)) = iter.next() {
-                    if excluded_batches.contains(batch) {
-                        excluded_txns += batch.num_txns();
-                    } else if let Some(Some((proof, insertion_time))) =
-                        self.batch_to_proof.get(&sort_key.batch_key)
-                    {
-                        cur_bytes += batch.num_bytes();
-                        cur_txns += batch.num_txns();
-                        if cur_bytes > max_bytes || cur_txns > max_txns {
-                            // Exceeded the limit for requested bytes or number of transactions.
-                            full = true;
-                            return false;
-                        }
-                        let bucket = proof.gas_bucket_start();
-                        ret.push(proof.clone());
-                        counters::pos_to_pull(bucket, insertion_time.elapsed().as_secs_f64());
-                        if cur_bytes == max_bytes || cur_txns == max_txns {
-                            // Exactly the limit for requested bytes or number of transactions.
+                let bucket = proof.gas_bucket_start();
+                ret.push(proof.clone());
+                counters::pos_to_pull(bucket, insertion_time.elapsed().as_secs_f64());
+                if cur_bytes == max_bytes || cur_txns == max_txns {
+                    // Exactly the limit for requested bytes or number of transactions.
+                    return false;
+                }
+            }
+            true
+        };
+
+        if order_by_gas_bucket {
+            counters::PROOF_PULL_BY_GAS_BUCKET_COUNT.inc();
+            for (sort_key, batch) in Self::batches_by_gas_bucket(&self.author_to_batches) {
+                if !try_add_batch(sort_key, batch) {
+                    full = true;
+                    break;
+                }
+            }
+        } else {
+            let mut iters = vec![];
+            for (_, batches) in self.author_to_batches.iter() {
+                iters.push(batches.iter().rev());
+            }
+
+            while !iters.is_empty() {
+                iters.shuffle(&mut thread_rng());
+                iters.retain_mut(|iter| {
+                    if full {
+                        return false;
+                    }
+                    if let Some((sort_key, batch)) = iter.next() {
+                        if !try_add_batch(sort_key, batch) {
                             full = true;
                             return false;
                         }
+                        true
+                    } else {
+                        false
                     }
-                    true
-                } else {
-                    false
-                }
-            })
+                })
+            }
         }
         info!(
             // before non full check
@@ -350,6 +370,42 @@ impl ProofQueue {
         }
     }
 
+    /// Orders the batches of all authors by descending gas bucket. Within a bucket, authors are
+    /// visited round-robin in a random order, each in its own descending priority order.
+    fn batches_by_gas_bucket(
+        author_to_batches: &HashMap<PeerId, BTreeMap<BatchSortKey, BatchInfo>>,
+    ) -> Vec<(&BatchSortKey, &BatchInfo)> {
+        let mut authors: Vec<_> = author_to_batches.values().collect();
+        authors.shuffle(&mut thread_rng());
+
+        let mut candidates = vec![];
+        for (author_index, batches) in authors.into_iter().enumerate() {
+            let mut current_bucket = None;
+            let mut index_in_bucket = 0;
+            for (sort_key, batch) in batches.iter().rev() {
+                if current_bucket != Some(sort_key.gas_bucket_start) {
+                    current_bucket = Some(sort_key.gas_bucket_start);
+                    index_in_bucket = 0;
+                }
+                candidates.push((
+                    (
+                        Reverse(sort_key.gas_bucket_start),
+                        index_in_bucket,
+                        author_index,
+                    ),
+                    sort_key,
+                    batch,
+                ));
+                index_in_bucket += 1;
+            }
+        }
+        candidates.sort_by_key(|(order, _, _)| *order);
+        candidates
+            .into_iter()
+            .map(|(_, sort_key, batch)| (sort_key, batch))
+            .collect()
+    }
+
     pub(crate) fn handle_updated_block_timestamp(&mut self, block_timestamp: u64) {
         assert!(
             self.latest_block_timestamp <= block_timestamp,
@@ -4,6 +4,7 @@
 
 use crate::{
     block_storage::tracing::{observe_block, BlockStage},
+    consensus_observer::publisher::ConsensusPublisher,
     counters,
     error::StateSyncError,
     monitor,
@@ -60,6 +61,7 @@ pub struct ExecutionProxy {
     transaction_shuffler: Mutex<Option<Arc<dyn TransactionShuffler>>>,
     maybe_block_gas_limit: Mutex<Option<u64>>,
     transaction_deduper: Mutex<Option<Arc<dyn TransactionDeduper>>>,
+    consensus_publisher: Option<Arc<ConsensusPublisher>>,
 }
 
 impl ExecutionProxy {
@@ -68,6 +70,7 @@ impl ExecutionProxy {
         txn_notifier: Arc<dyn TxnNotifier>,
         state_sync_notifier: Arc<dyn ConsensusNotificationSender>,
         handle: &tokio::runtime::Handle,
+        consensus_publisher: Option<Arc<ConsensusPublisher>>,
     ) -> Self {
         let (tx, mut rx) =
             aptos_channels::new::<NotificationType>(10, &counters::PENDING_STATE_SYNC_NOTIFICATION);
@@ -95,6 +98,7 @@ impl ExecutionProxy {
             transaction_shuffler: Mutex::new(None),
             maybe_block_gas_limit: Mutex::new(None),
             transaction_deduper: Mutex::new(None),
+            consensus_publisher,
         }
     }
 }
@@ -125,6 +129,9 @@ impl StateComputer for ExecutionProxy {
         let txn_deduper = self.transaction_deduper.lock().as_ref().unwrap().clone();
         let txn_shuffler = self.transaction_shuffler.lock().as_ref().unwrap().clone();
         let txns = payload_manager.get_transactions(block).await?;
+        if let Some(consensus_publisher) = &self.consensus_publisher {
+            consensus_publisher.cache_block_transactions(block, &txns);
+        }
 
         let deduped_txns = txn_deduper.dedup(txns);
         let shuffled_txns = txn_shuffler.shuffle(deduped_txns);
@@ -225,6 +232,10 @@ impl StateComputer for ExecutionProxy {
         )
         .expect("spawn_blocking failed");
 
+        if let Some(consensus_publisher) = &self.consensus_publisher {
+            consensus_publisher.publish_commit_decision(&finality_proof);
+        }
+
         let blocks = blocks.to_vec();
         let wrapped_callback = move || {
             callback(&blocks, finality_proof);
@@ -253,6 +264,11 @@ impl StateComputer for ExecutionProxy {
         // held by BlockExecutor to prevent memory leak.
         self.executor.finish();
 
+        // Any blocks the publisher is still holding will never be executed
+        if let Some(consensus_publisher) = &self.consensus_publisher {
+            consensus_publisher.reset();
+        }
+
         // The pipeline phase already committed beyond the target block timestamp, just return.
         if *latest_logical_time >= logical_time {
             warn!(
@@ -433,6 +449,7 @@ async fn test_commit_sync_race() {
         recorded_commit.clone(),
         recorded_commit.clone(),
         &tokio::runtime::Handle::current(),
+        None,
     );
     executor.new_epoch(
         &EpochState::empty(),
@@ -1,12 +1,16 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::sender_aware_shuffler::SenderAwareShuffler;
+use crate::{
+    conflict_aware_shuffler::ConflictAwareShuffler, sender_aware_shuffler::SenderAwareShuffler,
+};
 use aptos_logger::info;
 use aptos_types::{
     on_chain_config::{
         TransactionShufflerType,
-        TransactionShufflerType::{DeprecatedSenderAwareV1, NoShuffling, SenderAwareV2},
+        TransactionShufflerType::{
+            ConflictAwareV1, DeprecatedSenderAwareV1, NoShuffling, SenderAwareV2,
+        },
     },
     transaction::SignedTransaction,
 };
@@ -45,5 +49,18 @@ pub fn create_transaction_shuffler(
             );
             Arc::new(SenderAwareShuffler::new(confict_window_size as usize))
         },
+        ConflictAwareV1 {
+            conflict_window_size,
+            order_by_gas_price,
+        } => {
+            info!(
+                "Using conflict aware transaction shuffling with conflict window size {} and gas price ordering {}",
+                conflict_window_size, order_by_gas_price
+            );
+            Arc::new(ConflictAwareShuffler::new(
+                conflict_window_size as usize,
+                order_by_gas_price,
+            ))
+        },
     }
 }
@@ -0,0 +1,585 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Offline inspection of the consensus databases (i.e., the consensus db and the
+//! quorum store db). This is useful for debugging stalled nodes, as it shows the
+//! persisted block tree and the state the node would recover into on restart.
+
+use crate::{
+    consensusdb::{create_checkpoint, ConsensusDB},
+    epoch_manager::LivenessStorageData,
+    persistent_liveness_storage::{PersistentLivenessStorage, StorageWriteProxy},
+    quorum_store::{
+        quorum_store_db::{QuorumStoreDB, QuorumStoreStorage},
+        types::StorageMode,
+    },
+};
+use anyhow::Result;
+use aptos_config::config::{
+    NodeConfig, RocksdbConfigs, BUFFERED_STATE_TARGET_ITEMS,
+    DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD, NO_OP_STORAGE_PRUNER_CONFIG,
+};
+use aptos_consensus_types::{block::Block, common::Payload, quorum_cert::QuorumCert};
+use aptos_crypto::HashValue;
+use aptos_db::AptosDB;
+use aptos_storage_interface::DbReader;
+use aptos_temppath::TempPath;
+use aptos_types::ledger_info::LedgerInfoWithSignatures;
+use clap::Parser;
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+    sync::Arc,
+};
+
+/// Inspect the consensus db and replay consensus recovery offline
+#[derive(Parser)]
+pub enum Command {
+    /// Print the persisted block tree, alongside QCs and commit status
+    PrintBlockTree(PrintBlockTree),
+    /// Print the batches persisted by quorum store
+    PrintBatches(PrintBatches),
+    /// Replay consensus recovery and print the state the node would restart into
+    ReplayRecovery(ReplayRecovery),
+}
+
+impl Command {
+    /// Runs the command
+    pub fn run(self) -> Result<()> {
+        match self {
+            Command::PrintBlockTree(cmd) => cmd.run(),
+            Command::PrintBatches(cmd) => cmd.run(),
+            Command::ReplayRecovery(cmd) => cmd.run(),
+        }
+    }
+}
+
+/// The storage directory of the node (i.e., the parent of the consensus, quorum store and ledger dbs)
+#[derive(Parser)]
+pub struct DbDir {
+    #[clap(long, value_parser)]
+    db_dir: PathBuf,
+}
+
+impl DbDir {
+    fn open_consensus_db(&self) -> Result<ConsensusDB> {
+        ConsensusDB::new_readonly(&self.db_dir)
+    }
+
+    fn open_quorum_store_db(&self) -> Result<QuorumStoreDB> {
+        QuorumStoreDB::new_readonly(&self.db_dir)
+    }
+
+    fn open_aptos_db(&self) -> Result<AptosDB> {
+        AptosDB::open(
+            &self.db_dir,
+            true, /* readonly */
+            NO_OP_STORAGE_PRUNER_CONFIG,
+            RocksdbConfigs::default(),
+            false, /* indexer */
+            BUFFERED_STATE_TARGET_ITEMS,
+            DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+        )
+    }
+}
+
+/// Prints the blocks in the consensus db as a tree (rooted at the blocks whose
+/// parents are not persisted), annotating each block with its status.
+#[derive(Parser)]
+pub struct PrintBlockTree {
+    #[clap(flatten)]
+    db_dir: DbDir,
+
+    /// Also print the quorum certificate of each certified block
+    #[clap(long)]
+    print_qcs: bool,
+}
+
+impl PrintBlockTree {
+    fn run(self) -> Result<()> {
+        let consensus_db = self.db_dir.open_consensus_db()?;
+        let (_, _, blocks, quorum_certs) = consensus_db.get_data()?;
+        let latest_ledger_info = self.db_dir.open_aptos_db()?.get_latest_ledger_info()?;
+        let available_batches = self
+            .db_dir
+            .open_quorum_store_db()?
+            .get_all_batches()?
+            .into_keys()
+            .collect();
+
+        println!("Latest ledger info: {}", latest_ledger_info);
+        println!(
+            "Found {} blocks and {} quorum certs.",
+            blocks.len(),
+            quorum_certs.len()
+        );
+
+        let block_tree = BlockTreeSummary::new(blocks, quorum_certs, &latest_ledger_info);
+        block_tree.print(&available_batches, self.print_qcs);
+        Ok(())
+    }
+}
+
+/// Prints all batches persisted by quorum store
+#[derive(Parser)]
+pub struct PrintBatches {
+    #[clap(flatten)]
+    db_dir: DbDir,
+}
+
+impl PrintBatches {
+    fn run(self) -> Result<()> {
+        let mut batches: Vec<_> = self
+            .db_dir
+            .open_quorum_store_db()?
+            .get_all_batches()?
+            .into_values()
+            .collect();
+        batches.sort_by_key(|batch| (batch.epoch(), batch.author(), batch.batch_id()));
+
+        println!("Found {} batches.", batches.len());
+        for batch in batches {
+            let payload_status = match batch.payload_storage_mode() {
+                StorageMode::MemoryAndPersisted => "with payload",
+                StorageMode::PersistedOnly => "without payload",
+            };
+            println!(
+                "digest: {}, author: {}, epoch: {}, batch_id: {}, expiration: {}, num_txns: {}, num_bytes: {} ({})",
+                batch.digest(),
+                batch.author(),
+                batch.epoch(),
+                batch.batch_id(),
+                batch.expiration(),
+                batch.num_txns(),
+                batch.num_bytes(),
+                payload_status,
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Replays consensus recovery (i.e., `PersistentLivenessStorage::start`) against a
+/// checkpoint of the consensus db, so that the persisted data is left untouched. The
+/// node must be stopped, as the checkpoint requires opening the consensus db.
+#[derive(Parser)]
+pub struct ReplayRecovery {
+    #[clap(flatten)]
+    db_dir: DbDir,
+}
+
+impl ReplayRecovery {
+    fn run(self) -> Result<()> {
+        // Recovery prunes blocks and cleans up stale votes, so it runs against a checkpoint
+        let checkpoint_dir = TempPath::new();
+        checkpoint_dir.create_as_dir()?;
+        create_checkpoint(&self.db_dir.db_dir, &checkpoint_dir.path().to_path_buf())?;
+        let (_, _, persisted_blocks, _) =
+            ConsensusDB::new_readonly(checkpoint_dir.path())?.get_data()?;
+
+        let aptos_db: Arc<dyn DbReader> = Arc::new(self.db_dir.open_aptos_db()?);
+        println!("Latest ledger info: {}", aptos_db.get_latest_ledger_info()?);
+
+        let mut node_config = NodeConfig::default();
+        node_config.storage.dir = checkpoint_dir.path().to_path_buf();
+        let liveness_storage_data = StorageWriteProxy::new(&node_config, aptos_db).start();
+
+        match liveness_storage_data {
+            LivenessStorageData::FullRecoveryData(recovery_data) => {
+                println!("Consensus would start with full recovery data.");
+                println!("Root block: {}", recovery_data.root_block());
+                println!(
+                    "Last vote: {}",
+                    recovery_data
+                        .last_vote()
+                        .map_or("None".to_string(), |vote| vote.to_string())
+                );
+                println!(
+                    "Highest timeout certificate: {}",
+                    recovery_data
+                        .highest_2chain_timeout_certificate()
+                        .map_or("None".to_string(), |tc| tc.to_string())
+                );
+
+                let (_, _, remaining_blocks, _) =
+                    ConsensusDB::new_readonly(checkpoint_dir.path())?.get_data()?;
+                let remaining_block_ids: HashSet<_> =
+                    remaining_blocks.iter().map(|block| block.id()).collect();
+                let pruned_blocks: Vec<_> = persisted_blocks
+                    .iter()
+                    .filter(|block| !remaining_block_ids.contains(&block.id()))
+                    .collect();
+                println!("Blocks that would be pruned: {}", pruned_blocks.len());
+                for block in pruned_blocks {
+                    println!("\t{}", block);
+                }
+
+                let (root, _, blocks, quorum_certs) = recovery_data.take();
+                println!("Root quorum cert: {}", root.1);
+                println!("Root ordered cert: {}", root.2);
+                println!("Root commit cert: {}", root.3);
+                println!("Recovered blocks: {}", blocks.len());
+                for block in blocks {
+                    println!("\t{}", block);
+                }
+                println!("Recovered quorum certs: {}", quorum_certs.len());
+                for quorum_cert in quorum_certs {
+                    println!("\t{}", quorum_cert);
+                }
+            },
+            LivenessStorageData::PartialRecoveryData(ledger_recovery_data) => {
+                println!(
+                    "Consensus would start with partial recovery data (i.e., in recovery mode, \
+                    waiting to sync from peers). Committed round: {}",
+                    ledger_recovery_data.committed_round()
+                );
+            },
+        }
+        Ok(())
+    }
+}
+
+/// The status of a persisted block, relative to the latest ledger info
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum BlockStatus {
+    Committed, // The block has been committed to the ledger
+    Ordered,   // The block has been ordered (but not yet committed)
+    Certified, // The block has a quorum cert
+    Pending,   // The block has no quorum cert
+    Orphaned,  // The block is not a descendant of the committed root
+}
+
+/// A lightweight view of the persisted block tree (i.e., without any execution state)
+struct BlockTreeSummary {
+    blocks: HashMap<HashValue, Block>,
+    children: HashMap<HashValue, Vec<HashValue>>,
+    quorum_certs: HashMap<HashValue, QuorumCert>,
+    committed_blocks: HashSet<HashValue>,
+    ordered_blocks: HashSet<HashValue>,
+    root_descendants: HashSet<HashValue>,
+}
+
+impl BlockTreeSummary {
+    fn new(
+        blocks: Vec<Block>,
+        quorum_certs: Vec<QuorumCert>,
+        latest_ledger_info: &LedgerInfoWithSignatures,
+    ) -> Self {
+        let blocks: HashMap<_, _> = blocks
+            .into_iter()
+            .map(|block| (block.id(), block))
+            .collect();
+        let mut children: HashMap<HashValue, Vec<HashValue>> = HashMap::new();
+        for block in blocks.values() {
+            children
+                .entry(block.parent_id())
+                .or_default()
+                .push(block.id());
+        }
+        for block_ids in children.values_mut() {
+            block_ids.sort_by_key(|block_id| blocks[block_id].round());
+        }
+        let quorum_certs: HashMap<_, _> = quorum_certs
+            .into_iter()
+            .map(|qc| (qc.certified_block().id(), qc))
+            .collect();
+
+        // If the ledger ends the epoch, the next epoch starts from a virtual genesis block
+        let ledger_info = latest_ledger_info.ledger_info();
+        let committed_id = ledger_info.commit_info().id();
+        let root_id = if ledger_info.ends_epoch() {
+            Block::make_genesis_block_from_ledger_info(ledger_info).id()
+        } else {
+            committed_id
+        };
+
+        // Everything ordered by the highest QC and committed by the ledger
+        let ordered_id = quorum_certs
+            .values()
+            .max_by_key(|qc| (qc.commit_info().epoch(), qc.commit_info().round()))
+            .map(|qc| qc.commit_info().id());
+        let committed_blocks = get_ancestors(&blocks, Some(committed_id));
+        let ordered_blocks = get_ancestors(&blocks, ordered_id);
+
+        // Everything that would survive pruning on recovery
+        let mut root_descendants = HashSet::new();
+        let mut to_visit = vec![root_id];
+        while let Some(block_id) = to_visit.pop() {
+            root_descendants.insert(block_id);
+            if let Some(child_ids) = children.get(&block_id) {
+                to_visit.extend(child_ids);
+            }
+        }
+
+        Self {
+            blocks,
+            children,
+            quorum_certs,
+            committed_blocks,
+            ordered_blocks,
+            root_descendants,
+        }
+    }
+
+    fn get_status(&self, block_id: &HashValue) -> BlockStatus {
+        if self.committed_blocks.contains(block_id) {
+            BlockStatus::Committed
+        } else if !self.root_descendants.contains(block_id) {
+            BlockStatus::Orphaned
+        } else if self.ordered_blocks.contains(block_id) {
+            BlockStatus::Ordered
+        } else if self.quorum_certs.contains_key(block_id) {
+            BlockStatus::Certified
+        } else {
+            BlockStatus::Pending
+        }
+    }
+
+    /// Returns the ids of the persisted batches referenced by the block
+    /// that are missing from the quorum store db.
+    fn get_missing_batches(
+        &self,
+        block: &Block,
+        available_batches: &HashSet<HashValue>,
+    ) -> Vec<HashValue> {
+        match block.payload() {
+            Some(Payload::InQuorumStore(proof_with_data)) => proof_with_data
+                .proofs
+                .iter()
+                .map(|proof| *proof.info().digest())
+                .filter(|digest| !available_batches.contains(digest))
+                .collect(),
+            _ => vec![],
+        }
+    }
+
+    fn print(&self, available_batches: &HashSet<HashValue>, print_qcs: bool) {
+        // The tree roots are the blocks whose parents are not persisted
+        let mut tree_roots: Vec<_> = self
+            .blocks
+            .values()
+            .filter(|block| !self.blocks.contains_key(&block.parent_id()))
+            .collect();
+        tree_roots.sort_by_key(|block| (block.epoch(), block.round()));
+
+        for tree_root in tree_roots {
+            let mut to_visit = vec![(tree_root.id(), 0)];
+            while let Some((block_id, depth)) = to_visit.pop() {
+                let block = &self.blocks[&block_id];
+                let indent = "  ".repeat(depth);
+                println!("{}{} {:?}", indent, block, self.get_status(&block_id));
+
+                let missing_batches = self.get_missing_batches(block, available_batches);
+                if !missing_batches.is_empty() {
+                    println!("{}  missing batches: {:?}", indent, missing_batches);
+                }
+                if print_qcs {
+                    if let Some(quorum_cert) = self.quorum_certs.get(&block_id) {
+                        println!("{}  {}", indent, quorum_cert);
+                    }
+                }
+
+                // Visit the children in round order
+                if let Some(child_ids) = self.children.get(&block_id) {
+                    to_visit.extend(child_ids.iter().rev().map(|id| (*id, depth + 1)));
+                }
+            }
+        }
+    }
+}
+
+/// Returns the given block and all of its persisted ancestors
+fn get_ancestors(
+    blocks: &HashMap<HashValue, Block>,
+    block_id: Option<HashValue>,
+) -> HashSet<HashValue> {
+    let mut ancestors = HashSet::new();
+    let mut next_block = block_id.and_then(|block_id| blocks.get(&block_id));
+    while let Some(block) = next_block {
+        ancestors.insert(block.id());
+        next_block = blocks.get(&block.parent_id());
+    }
+    ancestors
+}
+
+#[test]
+fn verify_tool() {
+    use clap::CommandFactory;
+    Command::command().debug_assert()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_consensus_types::{
+        common::{Payload, ProofWithData},
+        proof_of_store::{BatchId, BatchInfo, ProofOfStore},
+        vote_data::VoteData,
+    };
+    use aptos_crypto::hash::CryptoHash;
+    use aptos_types::{
+        aggregate_signature::AggregateSignature, block_info::BlockInfo, epoch_state::EpochState,
+        ledger_info::LedgerInfo, validator_signer::ValidatorSigner, PeerId,
+    };
+
+    #[test]
+    fn test_block_status() {
+        // Create a chain of blocks on top of the committed root: root <- a <- b <- c,
+        // alongside a fork (root <- d) and an orphan (whose parent isn't persisted).
+        let root = Block::make_genesis_block();
+        let block_a = create_block(&root, 1, Payload::DirectMempool(vec![]));
+        let block_b = create_block(&block_a, 2, Payload::DirectMempool(vec![]));
+        let block_c = create_block(&block_b, 3, Payload::DirectMempool(vec![]));
+        let block_d = create_block(&root, 2, Payload::DirectMempool(vec![]));
+        let missing_block = create_block(&root, 4, Payload::DirectMempool(vec![]));
+        let orphaned_block = create_block(&missing_block, 5, Payload::DirectMempool(vec![]));
+
+        // Certify a and b (the QC for b orders a)
+        let quorum_certs = vec![
+            create_quorum_cert(&block_a, None),
+            create_quorum_cert(&block_b, Some(&block_a)),
+        ];
+        let blocks = vec![
+            root.clone(),
+            block_a.clone(),
+            block_b.clone(),
+            block_c.clone(),
+            block_d.clone(),
+            orphaned_block.clone(),
+        ];
+        let block_tree = BlockTreeSummary::new(blocks, quorum_certs, &create_ledger_info(&root));
+
+        // Verify the status of each block
+        for (block, expected_status) in [
+            (&root, BlockStatus::Committed),
+            (&block_a, BlockStatus::Ordered),
+            (&block_b, BlockStatus::Certified),
+            (&block_c, BlockStatus::Pending),
+            (&block_d, BlockStatus::Pending),
+            (&orphaned_block, BlockStatus::Orphaned),
+        ] {
+            assert_eq!(block_tree.get_status(&block.id()), expected_status);
+        }
+
+        // Verify that the children are sorted by round
+        assert_eq!(block_tree.children[&root.id()], vec![
+            block_a.id(),
+            block_d.id()
+        ]);
+    }
+
+    #[test]
+    fn test_block_status_after_epoch_change() {
+        // Create a chain of blocks, and commit the first block with an epoch-ending ledger info
+        let root = Block::make_genesis_block();
+        let block_a = create_block(&root, 1, Payload::DirectMempool(vec![]));
+        let block_b = create_block(&block_a, 2, Payload::DirectMempool(vec![]));
+        let epoch_ending_info =
+            block_a.gen_block_info(HashValue::zero(), 0, Some(EpochState::empty()));
+        let latest_ledger_info = LedgerInfoWithSignatures::new(
+            LedgerInfo::new(epoch_ending_info, HashValue::zero()),
+            AggregateSignature::empty(),
+        );
+
+        // Create the first block of the new epoch (extending the virtual genesis block)
+        let genesis_block =
+            Block::make_genesis_block_from_ledger_info(latest_ledger_info.ledger_info());
+        let block_c = create_block(&genesis_block, 1, Payload::DirectMempool(vec![]));
+
+        // Verify that the suffix of the old epoch is orphaned
+        let blocks = vec![
+            root.clone(),
+            block_a.clone(),
+            block_b.clone(),
+            block_c.clone(),
+        ];
+        let block_tree = BlockTreeSummary::new(blocks, vec![], &latest_ledger_info);
+        for (block, expected_status) in [
+            (&root, BlockStatus::Committed),
+            (&block_a, BlockStatus::Committed),
+            (&block_b, BlockStatus::Orphaned),
+            (&block_c, BlockStatus::Pending),
+        ] {
+            assert_eq!(block_tree.get_status(&block.id()), expected_status);
+        }
+    }
+
+    #[test]
+    fn test_missing_batches() {
+        // Create a block with two batches, only one of which is persisted
+        let author = PeerId::random();
+        let batch_infos: Vec<_> = (0..2)
+            .map(|batch_id| {
+                BatchInfo::new(
+                    author,
+                    BatchId::new_for_test(batch_id),
+                    1,
+                    100,
+                    HashValue::random(),
+                    1,
+                    1,
+                    0,
+                )
+            })
+            .collect();
+        let proofs = batch_infos
+            .iter()
+            .map(|batch_info| ProofOfStore::new(batch_info.clone(), AggregateSignature::empty()))
+            .collect();
+        let root = Block::make_genesis_block();
+        let block = create_block(&root, 1, Payload::InQuorumStore(ProofWithData::new(proofs)));
+        let block_tree = BlockTreeSummary::new(
+            vec![root.clone(), block.clone()],
+            vec![],
+            &create_ledger_info(&root),
+        );
+
+        // Verify that only the missing batch is reported
+        let available_batches = HashSet::from([*batch_infos[0].digest()]);
+        assert_eq!(
+            block_tree.get_missing_batches(&block, &available_batches),
+            vec![*batch_infos[1].digest()]
+        );
+        assert!(block_tree
+            .get_missing_batches(&root, &available_batches)
+            .is_empty());
+    }
+
+    /// Creates a proposal block (with the given payload) that extends the given parent
+    fn create_block(parent: &Block, round: u64, payload: Payload) -> Block {
+        Block::new_proposal(
+            payload,
+            round,
+            round,
+            create_quorum_cert(parent, None),
+            &ValidatorSigner::random(None),
+            vec![],
+        )
+        .unwrap()
+    }
+
+    /// Creates a quorum cert for the given block (that optionally orders another block)
+    fn create_quorum_cert(certified_block: &Block, ordered_block: Option<&Block>) -> QuorumCert {
+        let certified_info = certified_block.gen_block_info(HashValue::zero(), 0, None);
+        let ordered_info = ordered_block.map_or_else(BlockInfo::empty, |block| {
+            block.gen_block_info(HashValue::zero(), 0, None)
+        });
+        let vote_data = VoteData::new(certified_info.clone(), certified_info);
+        let ledger_info = LedgerInfo::new(ordered_info, vote_data.hash());
+        QuorumCert::new(
+            vote_data,
+            LedgerInfoWithSignatures::new(ledger_info, AggregateSignature::empty()),
+        )
+    }
+
+    /// Creates a ledger info that commits the given block
+    fn create_ledger_info(block: &Block) -> LedgerInfoWithSignatures {
+        LedgerInfoWithSignatures::new(
+            LedgerInfo::new(
+                block.gen_block_info(HashValue::zero(), 0, None),
+                HashValue::zero(),
+            ),
+            AggregateSignature::empty(),
+        )
+    }
+}
@@ -2,6 +2,8 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+#[cfg(feature = "db-tool")]
+pub mod db_tool;
 #[cfg(any(test, feature = "fuzzing"))]
 pub mod mock_time_service;
 pub mod time_service;
@@ -0,0 +1,265 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! This file implements traits for Secp256k1 ECDSA private keys and public keys.
+
+#[cfg(any(test, feature = "fuzzing"))]
+use crate::test_utils::{self, KeyPair};
+use crate::{
+    hash::{CryptoHash, HashValue},
+    secp256k1_ecdsa::{
+        Secp256k1EcdsaSignature, SECP256K1_ECDSA_PRIVATE_KEY_LENGTH,
+        SECP256K1_ECDSA_PUBLIC_KEY_LENGTH,
+    },
+    traits::*,
+};
+use aptos_crypto_derive::{DeserializeKey, SerializeKey, SilentDebug, SilentDisplay};
+use core::convert::TryFrom;
+#[cfg(any(test, feature = "fuzzing"))]
+use proptest::prelude::*;
+use serde::Serialize;
+use std::fmt;
+
+/// A Secp256k1 ECDSA private key
+#[derive(DeserializeKey, SerializeKey, SilentDebug, SilentDisplay)]
+pub struct Secp256k1EcdsaPrivateKey(pub(crate) libsecp256k1::SecretKey);
+
+#[cfg(feature = "assert-private-keys-not-cloneable")]
+static_assertions::assert_not_impl_any!(Secp256k1EcdsaPrivateKey: Clone);
+
+#[cfg(any(test, feature = "cloneable-private-keys"))]
+impl Clone for Secp256k1EcdsaPrivateKey {
+    fn clone(&self) -> Self {
+        let serialized: &[u8] = &(self.to_bytes());
+        Secp256k1EcdsaPrivateKey::try_from(serialized).unwrap()
+    }
+}
+
+/// A Secp256k1 ECDSA public key (in its uncompressed form)
+#[derive(DeserializeKey, Clone, SerializeKey)]
+pub struct Secp256k1EcdsaPublicKey(pub(crate) libsecp256k1::PublicKey);
+
+impl Secp256k1EcdsaPrivateKey {
+    /// The length of the Secp256k1EcdsaPrivateKey
+    pub const LENGTH: usize = SECP256K1_ECDSA_PRIVATE_KEY_LENGTH;
+
+    /// Serialize a Secp256k1EcdsaPrivateKey.
+    pub fn to_bytes(&self) -> [u8; SECP256K1_ECDSA_PRIVATE_KEY_LENGTH] {
+        self.0.serialize()
+    }
+
+    /// Private function aimed at minimizing code duplication between sign
+    /// methods of the SigningKey implementation. This should remain private.
+    ///
+    /// The message is hashed with SHA3-256 and the resulting signature is normalized to its
+    /// low-S form.
+    fn sign_arbitrary_message(&self, message: &[u8]) -> Secp256k1EcdsaSignature {
+        let digest = HashValue::sha3_256_of(message);
+        let message = libsecp256k1::Message::parse(digest.as_ref());
+        let (mut signature, _) = libsecp256k1::sign(&message, &self.0);
+        signature.normalize_s();
+        Secp256k1EcdsaSignature(signature)
+    }
+}
+
+impl Secp256k1EcdsaPublicKey {
+    /// Serialize a Secp256k1EcdsaPublicKey (in its uncompressed form).
+    pub fn to_bytes(&self) -> [u8; SECP256K1_ECDSA_PUBLIC_KEY_LENGTH] {
+        self.0.serialize()
+    }
+}
+
+///////////////////////
+// PrivateKey Traits //
+///////////////////////
+
+impl PrivateKey for Secp256k1EcdsaPrivateKey {
+    type PublicKeyMaterial = Secp256k1EcdsaPublicKey;
+}
+
+impl SigningKey for Secp256k1EcdsaPrivateKey {
+    type SignatureMaterial = Secp256k1EcdsaSignature;
+    type VerifyingKeyMaterial = Secp256k1EcdsaPublicKey;
+
+    fn sign<T: CryptoHash + Serialize>(
+        &self,
+        message: &T,
+    ) -> Result<Secp256k1EcdsaSignature, CryptoMaterialError> {
+        Ok(Secp256k1EcdsaPrivateKey::sign_arbitrary_message(
+            self,
+            signing_message(message)?.as_ref(),
+        ))
+    }
+
+    #[cfg(any(test, feature = "fuzzing"))]
+    fn sign_arbitrary_message(&self, message: &[u8]) -> Secp256k1EcdsaSignature {
+        Secp256k1EcdsaPrivateKey::sign_arbitrary_message(self, message)
+    }
+}
+
+impl Uniform for Secp256k1EcdsaPrivateKey {
+    fn generate<R>(rng: &mut R) -> Self
+    where
+        R: ::rand::RngCore + ::rand::CryptoRng + ::rand_core::CryptoRng + ::rand_core::RngCore,
+    {
+        // Not every 32 byte string is a valid scalar (e.g., zero, or values above the
+        // curve order), so sample until we find one. This almost always succeeds first time.
+        loop {
+            let mut bytes = [0u8; SECP256K1_ECDSA_PRIVATE_KEY_LENGTH];
+            rng.fill_bytes(&mut bytes);
+            if let Ok(secret_key) = libsecp256k1::SecretKey::parse(&bytes) {
+                return Secp256k1EcdsaPrivateKey(secret_key);
+            }
+        }
+    }
+}
+
+impl PartialEq<Self> for Secp256k1EcdsaPrivateKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_bytes() == other.to_bytes()
+    }
+}
+
+impl Eq for Secp256k1EcdsaPrivateKey {}
+
+impl TryFrom<&[u8]> for Secp256k1EcdsaPrivateKey {
+    type Error = CryptoMaterialError;
+
+    /// Deserialize a Secp256k1EcdsaPrivateKey. This method will check for private key validity:
+    /// i.e., correct key length and a non-zero scalar smaller than the curve order.
+    fn try_from(
+        bytes: &[u8],
+    ) -> std::result::Result<Secp256k1EcdsaPrivateKey, CryptoMaterialError> {
+        if bytes.len() != SECP256K1_ECDSA_PRIVATE_KEY_LENGTH {
+            return Err(CryptoMaterialError::WrongLengthError);
+        }
+        libsecp256k1::SecretKey::parse_slice(bytes)
+            .map(Secp256k1EcdsaPrivateKey)
+            .map_err(|_| CryptoMaterialError::DeserializationError)
+    }
+}
+
+impl Length for Secp256k1EcdsaPrivateKey {
+    fn length(&self) -> usize {
+        Self::LENGTH
+    }
+}
+
+impl ValidCryptoMaterial for Secp256k1EcdsaPrivateKey {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes().to_vec()
+    }
+}
+
+impl Genesis for Secp256k1EcdsaPrivateKey {
+    fn genesis() -> Self {
+        let mut buf = [0u8; SECP256K1_ECDSA_PRIVATE_KEY_LENGTH];
+        buf[SECP256K1_ECDSA_PRIVATE_KEY_LENGTH - 1] = 1;
+        Self::try_from(buf.as_ref()).unwrap()
+    }
+}
+
+//////////////////////
+// PublicKey Traits //
+//////////////////////
+
+// Implementing From<&PrivateKey<...>> allows to derive a public key in a more elegant fashion
+impl From<&Secp256k1EcdsaPrivateKey> for Secp256k1EcdsaPublicKey {
+    fn from(private_key: &Secp256k1EcdsaPrivateKey) -> Self {
+        Secp256k1EcdsaPublicKey(libsecp256k1::PublicKey::from_secret_key(&private_key.0))
+    }
+}
+
+// We deduce PublicKey from this
+impl PublicKey for Secp256k1EcdsaPublicKey {
+    type PrivateKeyMaterial = Secp256k1EcdsaPrivateKey;
+}
+
+impl std::hash::Hash for Secp256k1EcdsaPublicKey {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        let encoded_pubkey = self.to_bytes();
+        state.write(&encoded_pubkey);
+    }
+}
+
+// Those are required by the implementation of hash above
+impl PartialEq for Secp256k1EcdsaPublicKey {
+    fn eq(&self, other: &Secp256k1EcdsaPublicKey) -> bool {
+        self.to_bytes() == other.to_bytes()
+    }
+}
+
+impl Eq for Secp256k1EcdsaPublicKey {}
+
+// We deduce VerifyingKey from pointing to the signature material
+// we get the ability to do `pubkey.validate(msg, signature)`
+impl VerifyingKey for Secp256k1EcdsaPublicKey {
+    type SignatureMaterial = Secp256k1EcdsaSignature;
+    type SigningKeyMaterial = Secp256k1EcdsaPrivateKey;
+}
+
+impl fmt::Display for Secp256k1EcdsaPublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", hex::encode(self.to_bytes()))
+    }
+}
+
+impl fmt::Debug for Secp256k1EcdsaPublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Secp256k1EcdsaPublicKey({})", self)
+    }
+}
+
+impl TryFrom<&[u8]> for Secp256k1EcdsaPublicKey {
+    type Error = CryptoMaterialError;
+
+    /// Deserialize a Secp256k1EcdsaPublicKey. Only the uncompressed (65 byte) encoding is
+    /// accepted, and the point must lie on the curve.
+    fn try_from(bytes: &[u8]) -> std::result::Result<Secp256k1EcdsaPublicKey, CryptoMaterialError> {
+        if bytes.len() != SECP256K1_ECDSA_PUBLIC_KEY_LENGTH {
+            return Err(CryptoMaterialError::WrongLengthError);
+        }
+        libsecp256k1::PublicKey::parse_slice(bytes, Some(libsecp256k1::PublicKeyFormat::Full))
+            .map(Secp256k1EcdsaPublicKey)
+            .map_err(|_| CryptoMaterialError::DeserializationError)
+    }
+}
+
+impl Length for Secp256k1EcdsaPublicKey {
+    fn length(&self) -> usize {
+        SECP256K1_ECDSA_PUBLIC_KEY_LENGTH
+    }
+}
+
+impl ValidCryptoMaterial for Secp256k1EcdsaPublicKey {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes().to_vec()
+    }
+}
+
+/////////////
+// Fuzzing //
+/////////////
+
+/// Produces a uniformly random Secp256k1 ECDSA keypair from a seed
+#[cfg(any(test, feature = "fuzzing"))]
+pub fn keypair_strategy(
+) -> impl Strategy<Value = KeyPair<Secp256k1EcdsaPrivateKey, Secp256k1EcdsaPublicKey>> {
+    test_utils::uniform_keypair_strategy::<Secp256k1EcdsaPrivateKey, Secp256k1EcdsaPublicKey>()
+}
+
+/// Produces a uniformly random Secp256k1 ECDSA public key
+#[cfg(any(test, feature = "fuzzing"))]
+impl proptest::arbitrary::Arbitrary for Secp256k1EcdsaPublicKey {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        crate::test_utils::uniform_keypair_strategy::<
+            Secp256k1EcdsaPrivateKey,
+            Secp256k1EcdsaPublicKey,
+        >()
+        .prop_map(|v| v.public_key)
+        .boxed()
+    }
+}
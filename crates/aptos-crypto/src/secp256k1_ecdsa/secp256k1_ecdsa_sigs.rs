@@ -0,0 +1,153 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! This file implements traits for Secp256k1 ECDSA signatures.
+
+use crate::{
+    hash::{CryptoHash, HashValue},
+    secp256k1_ecdsa::{
+        Secp256k1EcdsaPrivateKey, Secp256k1EcdsaPublicKey, SECP256K1_ECDSA_SIGNATURE_LENGTH,
+    },
+    traits::*,
+};
+use anyhow::{anyhow, Result};
+use aptos_crypto_derive::{DeserializeKey, SerializeKey};
+use core::convert::TryFrom;
+use serde::Serialize;
+use std::fmt;
+
+/// A Secp256k1 ECDSA signature (r || s), where s is always in its low form
+#[derive(DeserializeKey, Clone, SerializeKey)]
+pub struct Secp256k1EcdsaSignature(pub(crate) libsecp256k1::Signature);
+
+impl Secp256k1EcdsaSignature {
+    /// The length of the Secp256k1EcdsaSignature
+    pub const LENGTH: usize = SECP256K1_ECDSA_SIGNATURE_LENGTH;
+
+    /// Serialize a Secp256k1EcdsaSignature.
+    pub fn to_bytes(&self) -> [u8; SECP256K1_ECDSA_SIGNATURE_LENGTH] {
+        self.0.serialize()
+    }
+
+    /// Deserialize a Secp256k1EcdsaSignature without any malleability checks apart from
+    /// expected signature size and r, s being smaller than the curve order.
+    pub(crate) fn from_bytes_unchecked(
+        bytes: &[u8],
+    ) -> std::result::Result<Secp256k1EcdsaSignature, CryptoMaterialError> {
+        if bytes.len() != SECP256K1_ECDSA_SIGNATURE_LENGTH {
+            return Err(CryptoMaterialError::WrongLengthError);
+        }
+        libsecp256k1::Signature::parse_standard_slice(bytes)
+            .map(Secp256k1EcdsaSignature)
+            .map_err(|_| CryptoMaterialError::DeserializationError)
+    }
+
+    /// return an all-zero signature (for test only)
+    #[cfg(any(test, feature = "fuzzing"))]
+    pub fn dummy_signature() -> Self {
+        Self::from_bytes_unchecked(&[0u8; Self::LENGTH]).unwrap()
+    }
+
+    /// Check for signature malleability. For any valid ECDSA signature (r, s), the signature
+    /// (r, n - s) is also valid for the same message and key, where n is the curve order. To
+    /// prevent third-parties from mauling signatures, we only accept the low-S form (i.e.,
+    /// s <= n / 2), which is also the form produced by our signing methods.
+    pub fn check_s_malleability(&self) -> std::result::Result<(), CryptoMaterialError> {
+        if self.0.s.is_high() {
+            return Err(CryptoMaterialError::CanonicalRepresentationError);
+        }
+        Ok(())
+    }
+}
+
+//////////////////////
+// Signature Traits //
+//////////////////////
+
+impl Signature for Secp256k1EcdsaSignature {
+    type SigningKeyMaterial = Secp256k1EcdsaPrivateKey;
+    type VerifyingKeyMaterial = Secp256k1EcdsaPublicKey;
+
+    /// Verifies that the provided signature is valid for the provided message (hashed with
+    /// SHA3-256) and rejects signatures that are not in their low-S form.
+    fn verify<T: CryptoHash + Serialize>(
+        &self,
+        message: &T,
+        public_key: &Secp256k1EcdsaPublicKey,
+    ) -> Result<()> {
+        Self::verify_arbitrary_msg(self, &signing_message(message)?, public_key)
+    }
+
+    /// Checks that `self` is valid for an arbitrary &[u8] `message` using `public_key`.
+    /// The message is hashed with SHA3-256 before verification.
+    fn verify_arbitrary_msg(
+        &self,
+        message: &[u8],
+        public_key: &Secp256k1EcdsaPublicKey,
+    ) -> Result<()> {
+        self.check_s_malleability()?;
+
+        let digest = HashValue::sha3_256_of(message);
+        let message = libsecp256k1::Message::parse(digest.as_ref());
+        if libsecp256k1::verify(&message, &self.0, &public_key.0) {
+            Ok(())
+        } else {
+            Err(anyhow!("Secp256k1 ECDSA signature verification failed"))
+        }
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes().to_vec()
+    }
+}
+
+impl Length for Secp256k1EcdsaSignature {
+    fn length(&self) -> usize {
+        SECP256K1_ECDSA_SIGNATURE_LENGTH
+    }
+}
+
+impl ValidCryptoMaterial for Secp256k1EcdsaSignature {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes().to_vec()
+    }
+}
+
+impl std::hash::Hash for Secp256k1EcdsaSignature {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        let encoded_signature = self.to_bytes();
+        state.write(&encoded_signature);
+    }
+}
+
+impl TryFrom<&[u8]> for Secp256k1EcdsaSignature {
+    type Error = CryptoMaterialError;
+
+    fn try_from(bytes: &[u8]) -> std::result::Result<Secp256k1EcdsaSignature, CryptoMaterialError> {
+        // Mauled (high-S) signatures are rejected early, and again during verification
+        let signature = Secp256k1EcdsaSignature::from_bytes_unchecked(bytes)?;
+        signature.check_s_malleability()?;
+        Ok(signature)
+    }
+}
+
+// Those are required by the implementation of hash above
+impl PartialEq for Secp256k1EcdsaSignature {
+    fn eq(&self, other: &Secp256k1EcdsaSignature) -> bool {
+        self.to_bytes()[..] == other.to_bytes()[..]
+    }
+}
+
+impl Eq for Secp256k1EcdsaSignature {}
+
+impl fmt::Display for Secp256k1EcdsaSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", hex::encode(&self.to_bytes()[..]))
+    }
+}
+
+impl fmt::Debug for Secp256k1EcdsaSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Secp256k1EcdsaSignature({})", self)
+    }
+}
@@ -0,0 +1,156 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! This file implements traits for Secp256r1 ECDSA signatures.
+
+use crate::{
+    hash::CryptoHash,
+    secp256r1_ecdsa::{
+        Secp256r1EcdsaPrivateKey, Secp256r1EcdsaPublicKey, SECP256R1_ECDSA_SIGNATURE_LENGTH,
+    },
+    traits::*,
+};
+use anyhow::{anyhow, Result};
+use aptos_crypto_derive::{DeserializeKey, SerializeKey};
+use core::convert::TryFrom;
+use p256::ecdsa::signature::Verifier;
+use serde::Serialize;
+use std::{cmp::Ordering, fmt};
+
+/// Half of the order of the secp256r1 curve (rounded down), in big-endian form. A signature
+/// (r, s) is in its low-S form iff s <= HALF_ORDER.
+const HALF_ORDER: [u8; 32] = [
+    0x7F, 0xFF, 0xFF, 0xFF, 0x80, 0x00, 0x00, 0x00, 0x7F, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+    0xDE, 0x73, 0x7D, 0x56, 0xD3, 0x8B, 0xCF, 0x42, 0x79, 0xDC, 0xE5, 0x61, 0x7E, 0x31, 0x92, 0xA8,
+];
+
+/// A Secp256r1 ECDSA signature (r || s), where s is always in its low form
+#[derive(DeserializeKey, Clone, SerializeKey)]
+pub struct Secp256r1EcdsaSignature(pub(crate) p256::ecdsa::Signature);
+
+impl Secp256r1EcdsaSignature {
+    /// The length of the Secp256r1EcdsaSignature
+    pub const LENGTH: usize = SECP256R1_ECDSA_SIGNATURE_LENGTH;
+
+    /// Serialize a Secp256r1EcdsaSignature.
+    pub fn to_bytes(&self) -> [u8; SECP256R1_ECDSA_SIGNATURE_LENGTH] {
+        let mut bytes = [0u8; SECP256R1_ECDSA_SIGNATURE_LENGTH];
+        bytes.copy_from_slice(&self.0.to_bytes());
+        bytes
+    }
+
+    /// Deserialize a Secp256r1EcdsaSignature without any malleability checks apart from
+    /// expected signature size and r, s being non-zero and smaller than the curve order.
+    pub(crate) fn from_bytes_unchecked(
+        bytes: &[u8],
+    ) -> std::result::Result<Secp256r1EcdsaSignature, CryptoMaterialError> {
+        if bytes.len() != SECP256R1_ECDSA_SIGNATURE_LENGTH {
+            return Err(CryptoMaterialError::WrongLengthError);
+        }
+        p256::ecdsa::Signature::from_slice(bytes)
+            .map(Secp256r1EcdsaSignature)
+            .map_err(|_| CryptoMaterialError::DeserializationError)
+    }
+
+    /// Check for signature malleability. For any valid ECDSA signature (r, s), the signature
+    /// (r, n - s) is also valid for the same message and key, where n is the curve order. To
+    /// prevent third-parties from mauling signatures, we only accept the low-S form (i.e.,
+    /// s <= n / 2), which is also the form produced by our signing methods.
+    pub fn check_s_malleability(bytes: &[u8]) -> std::result::Result<(), CryptoMaterialError> {
+        if bytes.len() != SECP256R1_ECDSA_SIGNATURE_LENGTH {
+            return Err(CryptoMaterialError::WrongLengthError);
+        }
+        if bytes[32..].cmp(&HALF_ORDER[..]) == Ordering::Greater {
+            return Err(CryptoMaterialError::CanonicalRepresentationError);
+        }
+        Ok(())
+    }
+}
+
+//////////////////////
+// Signature Traits //
+//////////////////////
+
+impl Signature for Secp256r1EcdsaSignature {
+    type SigningKeyMaterial = Secp256r1EcdsaPrivateKey;
+    type VerifyingKeyMaterial = Secp256r1EcdsaPublicKey;
+
+    /// Verifies that the provided signature is valid for the provided message (hashed with
+    /// SHA2-256) and rejects signatures that are not in their low-S form.
+    fn verify<T: CryptoHash + Serialize>(
+        &self,
+        message: &T,
+        public_key: &Secp256r1EcdsaPublicKey,
+    ) -> Result<()> {
+        Self::verify_arbitrary_msg(self, &signing_message(message)?, public_key)
+    }
+
+    /// Checks that `self` is valid for an arbitrary &[u8] `message` using `public_key`.
+    /// The message is hashed with SHA2-256 before verification.
+    fn verify_arbitrary_msg(
+        &self,
+        message: &[u8],
+        public_key: &Secp256r1EcdsaPublicKey,
+    ) -> Result<()> {
+        Secp256r1EcdsaSignature::check_s_malleability(&self.to_bytes())?;
+
+        public_key
+            .0
+            .verify(message, &self.0)
+            .map_err(|e| anyhow!("Secp256r1 ECDSA signature verification failed: {}", e))
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes().to_vec()
+    }
+}
+
+impl Length for Secp256r1EcdsaSignature {
+    fn length(&self) -> usize {
+        SECP256R1_ECDSA_SIGNATURE_LENGTH
+    }
+}
+
+impl ValidCryptoMaterial for Secp256r1EcdsaSignature {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes().to_vec()
+    }
+}
+
+impl std::hash::Hash for Secp256r1EcdsaSignature {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        let encoded_signature = self.to_bytes();
+        state.write(&encoded_signature);
+    }
+}
+
+impl TryFrom<&[u8]> for Secp256r1EcdsaSignature {
+    type Error = CryptoMaterialError;
+
+    fn try_from(bytes: &[u8]) -> std::result::Result<Secp256r1EcdsaSignature, CryptoMaterialError> {
+        // Mauled (high-S) signatures are rejected early, and again during verification
+        Secp256r1EcdsaSignature::check_s_malleability(bytes)?;
+        Secp256r1EcdsaSignature::from_bytes_unchecked(bytes)
+    }
+}
+
+// Those are required by the implementation of hash above
+impl PartialEq for Secp256r1EcdsaSignature {
+    fn eq(&self, other: &Secp256r1EcdsaSignature) -> bool {
+        self.to_bytes()[..] == other.to_bytes()[..]
+    }
+}
+
+impl Eq for Secp256r1EcdsaSignature {}
+
+impl fmt::Display for Secp256r1EcdsaSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", hex::encode(&self.to_bytes()[..]))
+    }
+}
+
+impl fmt::Debug for Secp256r1EcdsaSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Secp256r1EcdsaSignature({})", self)
+    }
+}
@@ -0,0 +1,113 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    secp256k1_ecdsa::{
+        Secp256k1EcdsaPrivateKey, Secp256k1EcdsaPublicKey, Secp256k1EcdsaSignature,
+        SECP256K1_ECDSA_PRIVATE_KEY_LENGTH, SECP256K1_ECDSA_PUBLIC_KEY_LENGTH,
+        SECP256K1_ECDSA_SIGNATURE_LENGTH,
+    },
+    test_utils::{random_serializable_struct, uniform_keypair_strategy, TestAptosCrypto},
+    traits::*,
+};
+use core::convert::TryFrom;
+use proptest::prelude::*;
+
+proptest! {
+    #[test]
+    fn test_sign_and_verify(
+        message in random_serializable_struct(),
+        keypair in uniform_keypair_strategy::<Secp256k1EcdsaPrivateKey, Secp256k1EcdsaPublicKey>()
+    ) {
+        let signature = keypair.private_key.sign(&message).unwrap();
+        prop_assert_eq!(signature.to_bytes().len(), SECP256K1_ECDSA_SIGNATURE_LENGTH);
+        prop_assert!(signature.verify(&message, &keypair.public_key).is_ok());
+
+        // The signature should not verify against a different message
+        let other_message = TestAptosCrypto(format!("{:?}!", message.0));
+        prop_assert!(signature.verify(&other_message, &keypair.public_key).is_err());
+    }
+
+    #[test]
+    fn test_verify_wrong_key_fails(
+        message in random_serializable_struct(),
+        keypair in uniform_keypair_strategy::<Secp256k1EcdsaPrivateKey, Secp256k1EcdsaPublicKey>(),
+        other_keypair in uniform_keypair_strategy::<Secp256k1EcdsaPrivateKey, Secp256k1EcdsaPublicKey>()
+    ) {
+        prop_assume!(keypair.public_key != other_keypair.public_key);
+        let signature = keypair.private_key.sign(&message).unwrap();
+        prop_assert!(signature.verify(&message, &other_keypair.public_key).is_err());
+    }
+
+    #[test]
+    fn test_key_and_signature_serialization(
+        message in random_serializable_struct(),
+        keypair in uniform_keypair_strategy::<Secp256k1EcdsaPrivateKey, Secp256k1EcdsaPublicKey>()
+    ) {
+        let private_key_bytes = keypair.private_key.to_bytes();
+        prop_assert_eq!(private_key_bytes.len(), SECP256K1_ECDSA_PRIVATE_KEY_LENGTH);
+        let private_key = Secp256k1EcdsaPrivateKey::try_from(&private_key_bytes[..]).unwrap();
+        prop_assert_eq!(&private_key, &keypair.private_key);
+
+        let public_key_bytes = keypair.public_key.to_bytes();
+        prop_assert_eq!(public_key_bytes.len(), SECP256K1_ECDSA_PUBLIC_KEY_LENGTH);
+        let public_key = Secp256k1EcdsaPublicKey::try_from(&public_key_bytes[..]).unwrap();
+        prop_assert_eq!(&public_key, &keypair.public_key);
+        prop_assert_eq!(Secp256k1EcdsaPublicKey::from(&private_key), public_key);
+
+        let signature = private_key.sign(&message).unwrap();
+        let signature_bytes = signature.to_bytes();
+        let deserialized = Secp256k1EcdsaSignature::try_from(&signature_bytes[..]).unwrap();
+        prop_assert_eq!(deserialized, signature);
+    }
+
+    #[test]
+    fn test_high_s_signature_rejected(
+        message in random_serializable_struct(),
+        keypair in uniform_keypair_strategy::<Secp256k1EcdsaPrivateKey, Secp256k1EcdsaPublicKey>()
+    ) {
+        let signature = keypair.private_key.sign(&message).unwrap();
+        prop_assert!(signature.check_s_malleability().is_ok());
+
+        // Flip s to n - s, which is an equally valid ECDSA signature
+        let mut mauled_signature = signature.0;
+        mauled_signature.s = -mauled_signature.s;
+        let mauled_signature = Secp256k1EcdsaSignature(mauled_signature);
+        prop_assert_eq!(
+            mauled_signature.check_s_malleability(),
+            Err(CryptoMaterialError::CanonicalRepresentationError)
+        );
+        prop_assert!(mauled_signature.verify(&message, &keypair.public_key).is_err());
+        prop_assert_eq!(
+            Secp256k1EcdsaSignature::try_from(&mauled_signature.to_bytes()[..]),
+            Err(CryptoMaterialError::CanonicalRepresentationError)
+        );
+    }
+}
+
+#[test]
+fn test_invalid_key_and_signature_lengths() {
+    let private_key = Secp256k1EcdsaPrivateKey::generate_for_testing();
+    let public_key = Secp256k1EcdsaPublicKey::from(&private_key);
+
+    // Compressed public keys are not accepted
+    let compressed_public_key = public_key.0.serialize_compressed();
+    assert_eq!(
+        Secp256k1EcdsaPublicKey::try_from(&compressed_public_key[..]),
+        Err(CryptoMaterialError::WrongLengthError)
+    );
+    assert_eq!(
+        Secp256k1EcdsaPrivateKey::try_from(&[1u8; SECP256K1_ECDSA_PRIVATE_KEY_LENGTH - 1][..]),
+        Err(CryptoMaterialError::WrongLengthError)
+    );
+    assert_eq!(
+        Secp256k1EcdsaSignature::try_from(&[1u8; SECP256K1_ECDSA_SIGNATURE_LENGTH + 1][..]),
+        Err(CryptoMaterialError::WrongLengthError)
+    );
+
+    // The zero scalar is not a valid private key
+    assert_eq!(
+        Secp256k1EcdsaPrivateKey::try_from(&[0u8; SECP256K1_ECDSA_PRIVATE_KEY_LENGTH][..]),
+        Err(CryptoMaterialError::DeserializationError)
+    );
+}
@@ -0,0 +1,123 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    secp256r1_ecdsa::{
+        Secp256r1EcdsaPrivateKey, Secp256r1EcdsaPublicKey, Secp256r1EcdsaSignature,
+        SECP256R1_ECDSA_PRIVATE_KEY_LENGTH, SECP256R1_ECDSA_PUBLIC_KEY_LENGTH,
+        SECP256R1_ECDSA_SIGNATURE_LENGTH,
+    },
+    test_utils::{random_serializable_struct, uniform_keypair_strategy, TestAptosCrypto},
+    traits::*,
+};
+use core::convert::TryFrom;
+use proptest::prelude::*;
+
+/// The order of the secp256r1 curve, in big-endian form.
+const ORDER: [u8; 32] = [
+    0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+    0xBC, 0xE6, 0xFA, 0xAD, 0xA7, 0x17, 0x9E, 0x84, 0xF3, 0xB9, 0xCA, 0xC2, 0xFC, 0x63, 0x25, 0x51,
+];
+
+/// Returns the signature (r, n - s), which is also valid but in its high-S form.
+fn maul_signature(signature: &Secp256r1EcdsaSignature) -> [u8; SECP256R1_ECDSA_SIGNATURE_LENGTH] {
+    let mut bytes = signature.to_bytes();
+    let mut borrow = 0i16;
+    for i in (0..32).rev() {
+        let mut diff = ORDER[i] as i16 - bytes[32 + i] as i16 - borrow;
+        borrow = if diff < 0 {
+            diff += 256;
+            1
+        } else {
+            0
+        };
+        bytes[32 + i] = diff as u8;
+    }
+    bytes
+}
+
+proptest! {
+    #[test]
+    fn test_sign_and_verify(
+        message in random_serializable_struct(),
+        keypair in uniform_keypair_strategy::<Secp256r1EcdsaPrivateKey, Secp256r1EcdsaPublicKey>()
+    ) {
+        let signature = keypair.private_key.sign(&message).unwrap();
+        prop_assert_eq!(signature.to_bytes().len(), SECP256R1_ECDSA_SIGNATURE_LENGTH);
+        prop_assert!(signature.verify(&message, &keypair.public_key).is_ok());
+
+        // The signature should not verify against a different message
+        let other_message = TestAptosCrypto(format!("{:?}!", message.0));
+        prop_assert!(signature.verify(&other_message, &keypair.public_key).is_err());
+    }
+
+    #[test]
+    fn test_key_and_signature_serialization(
+        message in random_serializable_struct(),
+        keypair in uniform_keypair_strategy::<Secp256r1EcdsaPrivateKey, Secp256r1EcdsaPublicKey>()
+    ) {
+        let private_key_bytes = keypair.private_key.to_bytes();
+        prop_assert_eq!(private_key_bytes.len(), SECP256R1_ECDSA_PRIVATE_KEY_LENGTH);
+        let private_key = Secp256r1EcdsaPrivateKey::try_from(&private_key_bytes[..]).unwrap();
+        prop_assert_eq!(&private_key, &keypair.private_key);
+
+        let public_key_bytes = keypair.public_key.to_bytes();
+        prop_assert_eq!(public_key_bytes.len(), SECP256R1_ECDSA_PUBLIC_KEY_LENGTH);
+        let public_key = Secp256r1EcdsaPublicKey::try_from(&public_key_bytes[..]).unwrap();
+        prop_assert_eq!(&public_key, &keypair.public_key);
+        prop_assert_eq!(Secp256r1EcdsaPublicKey::from(&private_key), public_key);
+
+        let signature = private_key.sign(&message).unwrap();
+        let signature_bytes = signature.to_bytes();
+        let deserialized = Secp256r1EcdsaSignature::try_from(&signature_bytes[..]).unwrap();
+        prop_assert_eq!(deserialized, signature);
+    }
+
+    #[test]
+    fn test_high_s_signature_rejected(
+        message in random_serializable_struct(),
+        keypair in uniform_keypair_strategy::<Secp256r1EcdsaPrivateKey, Secp256r1EcdsaPublicKey>()
+    ) {
+        let signature = keypair.private_key.sign(&message).unwrap();
+        prop_assert!(Secp256r1EcdsaSignature::check_s_malleability(&signature.to_bytes()).is_ok());
+
+        let mauled_bytes = maul_signature(&signature);
+        prop_assert_eq!(
+            Secp256r1EcdsaSignature::check_s_malleability(&mauled_bytes),
+            Err(CryptoMaterialError::CanonicalRepresentationError)
+        );
+        prop_assert_eq!(
+            Secp256r1EcdsaSignature::try_from(&mauled_bytes[..]),
+            Err(CryptoMaterialError::CanonicalRepresentationError)
+        );
+        let mauled_signature = Secp256r1EcdsaSignature::from_bytes_unchecked(&mauled_bytes).unwrap();
+        prop_assert!(mauled_signature.verify(&message, &keypair.public_key).is_err());
+    }
+}
+
+#[test]
+fn test_invalid_key_and_signature_lengths() {
+    let private_key = Secp256r1EcdsaPrivateKey::generate_for_testing();
+    let public_key = Secp256r1EcdsaPublicKey::from(&private_key);
+
+    // Compressed public keys are not accepted
+    let compressed_public_key = public_key.0.to_encoded_point(true);
+    assert_eq!(
+        Secp256r1EcdsaPublicKey::try_from(compressed_public_key.as_bytes()),
+        Err(CryptoMaterialError::WrongLengthError)
+    );
+    assert_eq!(
+        Secp256r1EcdsaPrivateKey::try_from(&[1u8; SECP256R1_ECDSA_PRIVATE_KEY_LENGTH - 1][..]),
+        Err(CryptoMaterialError::WrongLengthError)
+    );
+    assert_eq!(
+        Secp256r1EcdsaSignature::try_from(&[1u8; SECP256R1_ECDSA_SIGNATURE_LENGTH + 1][..]),
+        Err(CryptoMaterialError::WrongLengthError)
+    );
+
+    // The zero scalar is not a valid private key
+    assert_eq!(
+        Secp256r1EcdsaPrivateKey::try_from(&[0u8; SECP256R1_ECDSA_PRIVATE_KEY_LENGTH][..]),
+        Err(CryptoMaterialError::DeserializationError)
+    );
+}
@@ -4,6 +4,7 @@
 use aptos_crypto::{
     bls12381,
     ed25519::{Ed25519PrivateKey, Ed25519PublicKey},
+    secp256k1_ecdsa::Secp256k1EcdsaPrivateKey,
     x25519, CryptoMaterialError, PrivateKey, Uniform,
 };
 use aptos_types::{account_address::AccountAddress, transaction::authenticator::AuthenticationKey};
@@ -40,6 +41,11 @@ impl KeyGen {
         bls12381::PrivateKey::generate(&mut self.0)
     }
 
+    /// Generate a Secp256k1 ECDSA private key.
+    pub fn generate_secp256k1_ecdsa_private_key(&mut self) -> Secp256k1EcdsaPrivateKey {
+        Secp256k1EcdsaPrivateKey::generate(&mut self.0)
+    }
+
     /// Generate an Ed25519 key pair.
     pub fn generate_ed25519_keypair(&mut self) -> (Ed25519PrivateKey, Ed25519PublicKey) {
         let private_key = self.generate_ed25519_private_key();
@@ -394,6 +394,46 @@ impl Client {
         Ok(response.and_then(|bytes| bcs::from_bytes(&bytes))?)
     }
 
+    /// Simulates the transactions in order, each of them observing the writes of the previous
+    /// ones. Nothing is committed.
+    pub async fn simulate_bundle(
+        &self,
+        txns: &[SignedTransaction],
+    ) -> AptosResult<Response<Vec<UserTransaction>>> {
+        let txn_payload = bcs::to_bytes(&txns.to_vec())?;
+        let url = self.build_path("transactions/simulate_bundle")?;
+
+        let response = self
+            .inner
+            .post(url)
+            .header(CONTENT_TYPE, BCS_CONTENT_TYPE)
+            .body(txn_payload)
+            .send()
+            .await?;
+
+        self.json(response).await
+    }
+
+    pub async fn simulate_bundle_bcs(
+        &self,
+        txns: &[SignedTransaction],
+    ) -> AptosResult<Response<Vec<TransactionOnChainData>>> {
+        let txn_payload = bcs::to_bytes(&txns.to_vec())?;
+        let url = self.build_path("transactions/simulate_bundle")?;
+
+        let response = self
+            .inner
+            .post(url)
+            .header(CONTENT_TYPE, BCS_CONTENT_TYPE)
+            .header(ACCEPT, BCS)
+            .body(txn_payload)
+            .send()
+            .await?;
+
+        let response = self.check_and_parse_bcs_response(response).await?;
+        Ok(response.and_then(|bytes| bcs::from_bytes(&bytes))?)
+    }
+
     pub async fn submit(
         &self,
         txn: &SignedTransaction,
@@ -0,0 +1,431 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::types::{
+    CliCommand, CliError, CliTypedResult, OutputOptions, ProfileOptions, RestOptions, TableRow,
+};
+use aptos_rest_client::{
+    aptos_api_types::{
+        AptosErrorCode, MoveResource, MoveStructTag, Transaction, UserTransaction, WriteSetChange,
+    },
+    error::{AptosErrorResponse, RestError},
+    Client,
+};
+use aptos_types::account_address::{create_derived_object_address, AccountAddress};
+use async_trait::async_trait;
+use clap::Parser;
+use move_core_types::language_storage::{TypeTag, CORE_CODE_ADDRESS};
+use reqwest::Url;
+use serde::Serialize;
+use serde_json::json;
+use std::{collections::BTreeSet, str::FromStr};
+
+/// Number of transactions fetched per request when going through the history of an account
+pub(crate) const TRANSACTIONS_PER_PAGE: u64 = 100;
+
+/// GraphQL query of the indexer listing the fungible stores owned by an account
+const FUNGIBLE_STORES_QUERY: &str = "query FungibleStores($owner: String) { \
+    current_fungible_asset_balances(where: { owner_address: { _eq: $owner }, \
+    token_standard: { _eq: \"v2\" } }) { storage_id } }";
+
+/// Options to find the fungible stores owned by an account
+#[derive(Debug, Parser)]
+pub struct FungibleStoreOptions {
+    /// Addresses of fungible asset metadata objects, whose primary store is checked
+    #[clap(long, num_args = 0.., value_parser = crate::common::types::load_account_arg)]
+    pub(crate) fungible_assets: Vec<AccountAddress>,
+
+    /// URL of an indexer GraphQL API, to find every fungible store owned by the account
+    ///
+    /// Unlike the fullnode API, the indexer looks up stores by owner, so this also finds the
+    /// primary stores the account only ever received assets in.
+    #[clap(long)]
+    pub(crate) indexer_url: Option<Url>,
+}
+
+impl FungibleStoreOptions {
+    /// Returns the addresses of the fungible stores the account may own: the primary stores of
+    /// the given assets, and the stores owned by the account according to the indexer
+    pub(crate) async fn stores(
+        &self,
+        account: AccountAddress,
+    ) -> CliTypedResult<BTreeSet<AccountAddress>> {
+        let mut stores: BTreeSet<_> = self
+            .fungible_assets
+            .iter()
+            .map(|metadata| create_derived_object_address(account, *metadata))
+            .collect();
+        if let Some(indexer_url) = &self.indexer_url {
+            stores.extend(get_indexed_fungible_stores(indexer_url, account).await?);
+        }
+        Ok(stores)
+    }
+}
+
+/// List the balances of every coin and fungible asset held by an account
+///
+/// Coins are found from the `CoinStore`s of the account. As the fullnode API can't look up objects
+/// by owner, fungible assets are found from the primary stores of the assets given with
+/// `--fungible-assets`, from the stores owned by the account according to the indexer given with
+/// `--indexer-url`, and with `--scan-transactions` from the fungible stores written by the
+/// transactions sent by the account.
+#[derive(Debug, Parser)]
+pub struct ListBalances {
+    /// Address of the account, defaults to the account of the profile
+    #[clap(long, value_parser = crate::common::types::load_account_arg)]
+    pub(crate) account: Option<AccountAddress>,
+
+    #[clap(flatten)]
+    pub(crate) fungible_store_options: FungibleStoreOptions,
+
+    /// Also check the fungible stores written by the transactions sent by the account
+    ///
+    /// This pages through the whole history of the account, making one request per 100
+    /// transactions.
+    #[clap(long)]
+    pub(crate) scan_transactions: bool,
+
+    #[clap(flatten)]
+    pub(crate) output_options: OutputOptions,
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+}
+
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AssetKind {
+    Coin,
+    FungibleAsset,
+}
+
+/// Balance of a coin or fungible asset held by an account
+#[derive(Debug, Serialize)]
+pub struct AssetBalance {
+    pub kind: AssetKind,
+    /// Coin type, or address of the fungible asset metadata
+    pub asset: String,
+    /// Address of the fungible store, for fungible assets
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub store: Option<AccountAddress>,
+    pub name: Option<String>,
+    pub symbol: Option<String>,
+    pub decimals: Option<u8>,
+    /// Balance, in the smallest unit of the asset
+    pub amount: u64,
+    pub frozen: bool,
+}
+
+impl TableRow for AssetBalance {
+    const HEADERS: &'static [&'static str] = &["KIND", "ASSET", "SYMBOL", "BALANCE", "FROZEN"];
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            match self.kind {
+                AssetKind::Coin => "coin",
+                AssetKind::FungibleAsset => "fungible asset",
+            }
+            .to_string(),
+            self.asset.clone(),
+            self.symbol.clone().unwrap_or_default(),
+            format_amount(self.amount, self.decimals),
+            self.frozen.to_string(),
+        ]
+    }
+}
+
+#[async_trait]
+impl CliCommand<Vec<AssetBalance>> for ListBalances {
+    fn command_name(&self) -> &'static str {
+        "ListBalances"
+    }
+
+    async fn execute(self) -> CliTypedResult<Vec<AssetBalance>> {
+        let account = match self.account {
+            Some(account) => account,
+            None => self.profile_options.account_address()?,
+        };
+        let client = self.rest_options.client(&self.profile_options)?;
+
+        let mut balances = vec![];
+        for resource in get_coin_stores(&client, account).await? {
+            let Some(coin_type) = resource.resource_type.type_params.first() else {
+                continue;
+            };
+            let info = match coin_type {
+                TypeTag::Struct(coin) => {
+                    get_resource(
+                        &client,
+                        coin.address,
+                        &format!("0x1::coin::CoinInfo<{}>", coin_type),
+                    )
+                    .await?
+                },
+                _ => None,
+            };
+            balances.push(AssetBalance {
+                kind: AssetKind::Coin,
+                asset: coin_type.to_string(),
+                store: None,
+                name: info.as_ref().and_then(|info| json_string(&info["name"])),
+                symbol: info.as_ref().and_then(|info| json_string(&info["symbol"])),
+                decimals: info.as_ref().and_then(|info| json_u8(&info["decimals"])),
+                amount: json_u64(&resource.data["coin"]["value"]).unwrap_or_default(),
+                frozen: resource.data["frozen"].as_bool().unwrap_or_default(),
+            });
+        }
+
+        let mut stores = self.fungible_store_options.stores(account).await?;
+        if self.scan_transactions {
+            stores.extend(get_written_fungible_stores(&client, account).await?);
+        }
+
+        for store in stores {
+            let Some(owner) = get_resource(&client, store, "0x1::object::ObjectCore").await? else {
+                continue;
+            };
+            if json_address(&owner["owner"]) != Some(account) {
+                continue;
+            }
+            let Some(fungible_store) =
+                get_resource(&client, store, "0x1::fungible_asset::FungibleStore").await?
+            else {
+                continue;
+            };
+            let metadata_address = json_address(&fungible_store["metadata"]["inner"]);
+            let metadata = match metadata_address {
+                Some(address) => {
+                    get_resource(&client, address, "0x1::fungible_asset::Metadata").await?
+                },
+                None => None,
+            };
+            balances.push(AssetBalance {
+                kind: AssetKind::FungibleAsset,
+                asset: metadata_address
+                    .map(|address| address.to_hex_literal())
+                    .unwrap_or_default(),
+                store: Some(store),
+                name: metadata.as_ref().and_then(|m| json_string(&m["name"])),
+                symbol: metadata.as_ref().and_then(|m| json_string(&m["symbol"])),
+                decimals: metadata.as_ref().and_then(|m| json_u8(&m["decimals"])),
+                amount: json_u64(&fungible_store["balance"]).unwrap_or_default(),
+                frozen: fungible_store["frozen"].as_bool().unwrap_or_default(),
+            });
+        }
+        Ok(balances)
+    }
+}
+
+/// Fetches the `CoinStore`s of an account
+pub(crate) async fn get_coin_stores(
+    client: &Client,
+    account: AccountAddress,
+) -> CliTypedResult<Vec<MoveResource>> {
+    let resources = match client.get_account_resources(account).await {
+        Ok(response) => response.into_inner(),
+        Err(err) if is_not_found(&err) => vec![],
+        Err(err) => return Err(err.into()),
+    };
+    Ok(resources
+        .into_iter()
+        .filter(|resource| {
+            let tag = &resource.resource_type;
+            tag.address == CORE_CODE_ADDRESS
+                && tag.module.as_str() == "coin"
+                && tag.name.as_str() == "CoinStore"
+        })
+        .collect())
+}
+
+/// Fetches the addresses of the fungible stores owned by an account from the indexer
+async fn get_indexed_fungible_stores(
+    indexer_url: &Url,
+    account: AccountAddress,
+) -> CliTypedResult<BTreeSet<AccountAddress>> {
+    let query_error =
+        |err: reqwest::Error| CliError::ApiError(format!("Failed to query the indexer: {:#}", err));
+    let response: serde_json::Value = reqwest::Client::new()
+        .post(indexer_url.clone())
+        .json(&json!({
+            "query": FUNGIBLE_STORES_QUERY,
+            "variables": { "owner": format!("0x{}", account.to_hex()) },
+        }))
+        .send()
+        .await
+        .map_err(query_error)?
+        .json()
+        .await
+        .map_err(query_error)?;
+    parse_indexed_fungible_stores(&response)
+}
+
+/// Reads the addresses of the stores from the response to `FUNGIBLE_STORES_QUERY`
+fn parse_indexed_fungible_stores(
+    response: &serde_json::Value,
+) -> CliTypedResult<BTreeSet<AccountAddress>> {
+    if let Some(errors) = response.get("errors") {
+        return Err(CliError::ApiError(format!(
+            "Failed to query the indexer: {}",
+            errors
+        )));
+    }
+    let unexpected_response =
+        || CliError::UnexpectedError(format!("Unexpected response of the indexer: {}", response));
+    response["data"]["current_fungible_asset_balances"]
+        .as_array()
+        .ok_or_else(unexpected_response)?
+        .iter()
+        .map(|balance| json_address(&balance["storage_id"]).ok_or_else(unexpected_response))
+        .collect()
+}
+
+/// Fetches the addresses of the fungible stores written by all the transactions sent by an account
+async fn get_written_fungible_stores(
+    client: &Client,
+    account: AccountAddress,
+) -> CliTypedResult<BTreeSet<AccountAddress>> {
+    let mut stores = BTreeSet::new();
+    let mut start = 0;
+    loop {
+        let transactions =
+            get_user_transactions(client, account, start, TRANSACTIONS_PER_PAGE).await?;
+        for txn in &transactions {
+            stores.extend(txn.info.changes.iter().filter_map(|change| match change {
+                WriteSetChange::WriteResource(write)
+                    if is_framework_struct(&write.data.typ, "fungible_asset", "FungibleStore") =>
+                {
+                    Some(AccountAddress::from(write.address))
+                },
+                _ => None,
+            }));
+        }
+        if (transactions.len() as u64) < TRANSACTIONS_PER_PAGE {
+            return Ok(stores);
+        }
+        start += TRANSACTIONS_PER_PAGE;
+    }
+}
+
+/// Fetches up to `limit` transactions sent by an account, from its sequence number `start`
+pub(crate) async fn get_user_transactions(
+    client: &Client,
+    account: AccountAddress,
+    start: u64,
+    limit: u64,
+) -> CliTypedResult<Vec<UserTransaction>> {
+    let transactions = match client
+        .get_account_transactions(account, Some(start), Some(limit))
+        .await
+    {
+        Ok(response) => response.into_inner(),
+        Err(err) if is_not_found(&err) => vec![],
+        Err(err) => return Err(err.into()),
+    };
+    Ok(transactions
+        .into_iter()
+        .filter_map(|txn| match txn {
+            Transaction::UserTransaction(txn) => Some(*txn),
+            _ => None,
+        })
+        .collect())
+}
+
+/// Fetches the data of a resource, or `None` if the account or resource doesn't exist
+pub(crate) async fn get_resource(
+    client: &Client,
+    address: AccountAddress,
+    resource_type: &str,
+) -> CliTypedResult<Option<serde_json::Value>> {
+    match client.get_account_resource(address, resource_type).await {
+        Ok(response) => Ok(response.into_inner().map(|resource| resource.data)),
+        Err(err) if is_not_found(&err) => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+fn is_not_found(err: &RestError) -> bool {
+    matches!(
+        err,
+        RestError::Api(AptosErrorResponse { error, .. })
+            if matches!(
+                error.error_code,
+                AptosErrorCode::ResourceNotFound | AptosErrorCode::AccountNotFound
+            )
+    )
+}
+
+pub(crate) fn is_framework_struct(tag: &MoveStructTag, module: &str, name: &str) -> bool {
+    *tag.address.inner() == CORE_CODE_ADDRESS
+        && tag.module.as_str() == module
+        && tag.name.as_str() == name
+}
+
+/// Reads a `u64`, which the API encodes as a string
+pub(crate) fn json_u64(value: &serde_json::Value) -> Option<u64> {
+    value.as_str()?.parse().ok()
+}
+
+pub(crate) fn json_address(value: &serde_json::Value) -> Option<AccountAddress> {
+    AccountAddress::from_str(value.as_str()?).ok()
+}
+
+fn json_u8(value: &serde_json::Value) -> Option<u8> {
+    u8::try_from(value.as_u64()?).ok()
+}
+
+fn json_string(value: &serde_json::Value) -> Option<String> {
+    value.as_str().map(str::to_string)
+}
+
+/// Formats an amount in the smallest unit of an asset with its decimals, e.g. `150000000` with
+/// 8 decimals as `1.5`
+pub(crate) fn format_amount(amount: u64, decimals: Option<u8>) -> String {
+    let decimals = match decimals {
+        Some(decimals) if decimals > 0 => decimals as u32,
+        _ => return amount.to_string(),
+    };
+    let Some(unit) = 10u128.checked_pow(decimals) else {
+        return amount.to_string();
+    };
+    let (whole, fraction) = (amount as u128 / unit, amount as u128 % unit);
+    if fraction == 0 {
+        return whole.to_string();
+    }
+    let fraction = format!("{:0width$}", fraction, width = decimals as usize);
+    format!("{}.{}", whole, fraction.trim_end_matches('0'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_amount, parse_indexed_fungible_stores};
+    use aptos_types::account_address::AccountAddress;
+    use serde_json::json;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn test_format_amount() {
+        assert_eq!(format_amount(150_000_000, Some(8)), "1.5");
+        assert_eq!(format_amount(100_000_000, Some(8)), "1");
+        assert_eq!(format_amount(1, Some(8)), "0.00000001");
+        assert_eq!(format_amount(42, Some(0)), "42");
+        assert_eq!(format_amount(42, None), "42");
+        assert_eq!(format_amount(u64::MAX, Some(255)), u64::MAX.to_string());
+    }
+
+    #[test]
+    fn test_parse_indexed_fungible_stores() {
+        let store = "0x000000000000000000000000000000000000000000000000000000000000a1b2";
+        let response = json!({
+            "data": { "current_fungible_asset_balances": [{ "storage_id": store }] },
+        });
+        assert_eq!(
+            parse_indexed_fungible_stores(&response).unwrap(),
+            BTreeSet::from([AccountAddress::from_hex_literal("0xa1b2").unwrap()])
+        );
+
+        let errors = json!({ "errors": [{ "message": "field not found" }] });
+        assert!(parse_indexed_fungible_stores(&errors).is_err());
+        assert!(parse_indexed_fungible_stores(&json!({ "data": {} })).is_err());
+    }
+}
@@ -0,0 +1,635 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    account::balances::{
+        get_coin_stores, get_resource, get_user_transactions, is_framework_struct, json_address,
+        json_u64, FungibleStoreOptions, TRANSACTIONS_PER_PAGE,
+    },
+    common::types::{
+        CliCommand, CliError, CliTypedResult, OutputOptions, ProfileOptions, RestOptions, TableRow,
+    },
+};
+use aptos_rest_client::{
+    aptos_api_types::{
+        HashValue, MoveType, Transaction, TransactionPayload, UserTransaction, WriteSetChange,
+    },
+    Client,
+};
+use aptos_types::account_address::AccountAddress;
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use clap::Parser;
+use itertools::Itertools;
+use move_core_types::language_storage::CORE_CODE_ADDRESS;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Number of events fetched per request when going through the deposits of a store
+const EVENTS_PER_PAGE: u16 = 100;
+
+/// List the transactions sent by an account, and those depositing to it, with the coins and
+/// fungible assets they transferred
+///
+/// Transactions are listed from the oldest to the newest. The transactions sent by the account are
+/// paged by sequence number. Along with them, the transactions of other accounts depositing to
+/// its coin stores, or to the fungible stores found with `--fungible-assets` and `--indexer-url`,
+/// are listed from the deposit events of the stores, from after the previous transaction sent by
+/// the account up to the last one listed (or the latest one).
+///
+/// Transfers are decoded from the deposit and withdraw events of the coin and fungible stores of
+/// the account, along with the accounts on the other side of them in the same transaction.
+#[derive(Debug, Parser)]
+pub struct ListHistory {
+    /// Address of the account, defaults to the account of the profile
+    #[clap(long, value_parser = crate::common::types::load_account_arg)]
+    pub(crate) account: Option<AccountAddress>,
+
+    /// Sequence number of the first transaction to list
+    ///
+    /// Defaults to listing the latest transactions of the account.
+    #[clap(long)]
+    pub(crate) start: Option<u64>,
+
+    /// Maximum number of transactions sent by the account to list, and of transactions
+    /// depositing to it
+    #[clap(long, default_value_t = 25)]
+    pub(crate) limit: u64,
+
+    #[clap(flatten)]
+    pub(crate) fungible_store_options: FungibleStoreOptions,
+
+    #[clap(flatten)]
+    pub(crate) output_options: OutputOptions,
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+}
+
+/// A transaction sent by an account, or depositing to it
+#[derive(Debug, Serialize)]
+pub struct AccountTransaction {
+    pub version: u64,
+    pub hash: HashValue,
+    pub sender: AccountAddress,
+    /// Sequence number of the transaction in the account of its sender
+    pub sequence_number: u64,
+    pub timestamp_us: u64,
+    /// Entry function called by the transaction, or `script`
+    pub function: String,
+    pub success: bool,
+    pub vm_status: String,
+    /// Gas fee paid by the sender, in octas, if the account sent the transaction
+    pub gas_fee: Option<u64>,
+    pub transfers: Vec<Transfer>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferDirection {
+    In,
+    Out,
+}
+
+/// Coins or fungible assets deposited to or withdrawn from the account by a transaction
+#[derive(Debug, Serialize)]
+pub struct Transfer {
+    pub direction: TransferDirection,
+    /// Coin type, or address of the fungible asset metadata
+    pub asset: String,
+    /// Amount, in the smallest unit of the asset
+    pub amount: u64,
+    /// Accounts the asset was withdrawn from, or deposited to, by the same transaction
+    pub counterparties: Vec<AccountAddress>,
+}
+
+impl TableRow for AccountTransaction {
+    const HEADERS: &'static [&'static str] = &[
+        "VERSION",
+        "TIME (UTC)",
+        "FUNCTION",
+        "STATUS",
+        "GAS FEE",
+        "TRANSFERS",
+    ];
+
+    fn cells(&self) -> Vec<String> {
+        let time = NaiveDateTime::from_timestamp_micros(self.timestamp_us as i64)
+            .map(|time| time.format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_default();
+        let transfers = self
+            .transfers
+            .iter()
+            .map(|transfer| {
+                let sign = match transfer.direction {
+                    TransferDirection::In => '+',
+                    TransferDirection::Out => '-',
+                };
+                format!("{}{} {}", sign, transfer.amount, transfer.asset)
+            })
+            .join(", ");
+        vec![
+            self.version.to_string(),
+            time,
+            self.function.clone(),
+            self.vm_status.clone(),
+            self.gas_fee
+                .map(|gas_fee| gas_fee.to_string())
+                .unwrap_or_default(),
+            transfers,
+        ]
+    }
+}
+
+#[async_trait]
+impl CliCommand<Vec<AccountTransaction>> for ListHistory {
+    fn command_name(&self) -> &'static str {
+        "ListHistory"
+    }
+
+    async fn execute(self) -> CliTypedResult<Vec<AccountTransaction>> {
+        if self.limit == 0 {
+            return Err(CliError::CommandArgumentError(
+                "Limit must be greater than 0".to_string(),
+            ));
+        }
+        let account = match self.account {
+            Some(account) => account,
+            None => self.profile_options.account_address()?,
+        };
+        let client = self.rest_options.client(&self.profile_options)?;
+
+        let start = match self.start {
+            Some(start) => start,
+            None => {
+                let sequence_number = get_resource(&client, account, "0x1::account::Account")
+                    .await?
+                    .and_then(|resource| json_u64(&resource["sequence_number"]))
+                    .unwrap_or_default();
+                sequence_number.saturating_sub(self.limit)
+            },
+        };
+
+        let mut transactions = vec![];
+        while (transactions.len() as u64) < self.limit {
+            let limit = std::cmp::min(
+                self.limit - transactions.len() as u64,
+                TRANSACTIONS_PER_PAGE,
+            );
+            let page =
+                get_user_transactions(&client, account, start + transactions.len() as u64, limit)
+                    .await?;
+            let done = (page.len() as u64) < limit;
+            transactions.extend(page);
+            if done {
+                break;
+            }
+        }
+        let reaches_latest = self.start.is_none() || (transactions.len() as u64) < self.limit;
+
+        // The deposits are listed from after the transaction sent before the listed ones, up to
+        // the last one listed, so that consecutive pages don't overlap nor leave gaps
+        let after_version = match start.checked_sub(1) {
+            Some(previous) => get_user_transactions(&client, account, previous, 1)
+                .await?
+                .first()
+                .map(|txn| txn.info.version.0),
+            None => None,
+        };
+        let up_to_version = match transactions.last() {
+            Some(txn) if !reaches_latest => Some(txn.info.version.0),
+            _ => None,
+        };
+
+        let owned_stores = get_owned_stores(
+            &client,
+            account,
+            self.fungible_store_options.stores(account).await?,
+        )
+        .await?;
+        let mut deposit_handles = vec![];
+        for coin_store in get_coin_stores(&client, account).await? {
+            deposit_handles.push((
+                account,
+                coin_store.resource_type.to_string(),
+                json_u64(&coin_store.data["deposit_events"]["counter"]),
+            ));
+        }
+        for store in &owned_stores {
+            let events =
+                get_resource(&client, *store, "0x1::fungible_asset::FungibleAssetEvents").await?;
+            deposit_handles.push((
+                *store,
+                "0x1::fungible_asset::FungibleAssetEvents".to_string(),
+                events.and_then(|events| json_u64(&events["deposit_events"]["counter"])),
+            ));
+        }
+
+        let mut deposit_versions = BTreeSet::new();
+        for (address, struct_tag, counter) in deposit_handles {
+            let Some(counter) = counter else {
+                continue;
+            };
+            deposit_versions.extend(
+                get_deposit_versions(
+                    &client,
+                    address,
+                    &struct_tag,
+                    counter,
+                    after_version,
+                    up_to_version,
+                    self.limit,
+                )
+                .await?,
+            );
+        }
+        let sent_versions: BTreeSet<_> =
+            transactions.iter().map(|txn| txn.info.version.0).collect();
+        let received_versions: Vec<_> = deposit_versions
+            .difference(&sent_versions)
+            .rev()
+            .take(self.limit as usize)
+            .copied()
+            .collect();
+        for version in received_versions {
+            if let Transaction::UserTransaction(txn) = client
+                .get_transaction_by_version(version)
+                .await?
+                .into_inner()
+            {
+                transactions.push(*txn);
+            }
+        }
+        transactions.sort_by_key(|txn| txn.info.version.0);
+
+        Ok(transactions
+            .iter()
+            .map(|txn| AccountTransaction {
+                version: txn.info.version.0,
+                hash: txn.info.hash,
+                sender: *txn.request.sender.inner(),
+                sequence_number: txn.request.sequence_number.0,
+                timestamp_us: txn.timestamp.0,
+                function: match &txn.request.payload {
+                    TransactionPayload::EntryFunctionPayload(payload) => {
+                        payload.function.to_string()
+                    },
+                    TransactionPayload::ScriptPayload(_) => "script".to_string(),
+                    TransactionPayload::ModuleBundlePayload(_) => "module bundle".to_string(),
+                    TransactionPayload::MultisigPayload(payload) => {
+                        format!("multisig {}", payload.multisig_address)
+                    },
+                },
+                success: txn.info.success,
+                vm_status: txn.info.vm_status.clone(),
+                gas_fee: (*txn.request.sender.inner() == account)
+                    .then(|| txn.info.gas_used.0 * txn.request.gas_unit_price.0),
+                transfers: decode_transfers(txn, account, &owned_stores),
+            })
+            .collect())
+    }
+}
+
+/// Keeps the fungible stores which are owned by the account
+async fn get_owned_stores(
+    client: &Client,
+    account: AccountAddress,
+    stores: BTreeSet<AccountAddress>,
+) -> CliTypedResult<BTreeSet<AccountAddress>> {
+    let mut owned_stores = BTreeSet::new();
+    for store in stores {
+        let owner = get_resource(client, store, "0x1::object::ObjectCore")
+            .await?
+            .and_then(|object| json_address(&object["owner"]));
+        if owner == Some(account) {
+            owned_stores.insert(store);
+        }
+    }
+    Ok(owned_stores)
+}
+
+/// Fetches the versions of the latest deposit events of a store, at most `limit`, from after
+/// `after_version` and up to `up_to_version`. `counter` is the number of events of the handle.
+async fn get_deposit_versions(
+    client: &Client,
+    address: AccountAddress,
+    struct_tag: &str,
+    counter: u64,
+    after_version: Option<u64>,
+    up_to_version: Option<u64>,
+    limit: u64,
+) -> CliTypedResult<Vec<u64>> {
+    let mut versions = vec![];
+    let mut end = counter;
+    while end > 0 {
+        let start = end.saturating_sub(EVENTS_PER_PAGE as u64);
+        let events = client
+            .get_account_events(
+                address,
+                struct_tag,
+                "deposit_events",
+                Some(start),
+                Some((end - start) as u16),
+            )
+            .await?
+            .into_inner();
+        for event in events.iter().rev() {
+            let version = event.version.0;
+            if after_version.map_or(false, |after_version| version <= after_version) {
+                return Ok(versions);
+            }
+            if up_to_version.map_or(true, |up_to_version| version <= up_to_version) {
+                versions.push(version);
+                if versions.len() as u64 >= limit {
+                    return Ok(versions);
+                }
+            }
+        }
+        end = start;
+    }
+    Ok(versions)
+}
+
+/// Decodes the transfers of a transaction from the deposit and withdraw events of coin and
+/// fungible stores, whose asset and owner are read from the resources the transaction wrote. The
+/// fungible stores known to be owned by the account don't need their owner to be written.
+fn decode_transfers(
+    txn: &UserTransaction,
+    account: AccountAddress,
+    owned_stores: &BTreeSet<AccountAddress>,
+) -> Vec<Transfer> {
+    // Coin types by the event handles of their `CoinStore`
+    let mut coin_handles = BTreeMap::new();
+    // Fungible asset metadata, and owners, of the objects holding fungible stores
+    let mut store_assets = BTreeMap::new();
+    let mut owners = BTreeMap::new();
+    for change in &txn.info.changes {
+        let WriteSetChange::WriteResource(write) = change else {
+            continue;
+        };
+        let address = AccountAddress::from(write.address);
+        let tag = &write.data.typ;
+        let data = serde_json::to_value(&write.data.data).unwrap_or_default();
+        if is_framework_struct(tag, "coin", "CoinStore") {
+            let Some(coin_type) = tag.generic_type_params.first() else {
+                continue;
+            };
+            for handle in ["deposit_events", "withdraw_events"] {
+                if let Some(creation_number) = json_u64(&data[handle]["guid"]["id"]["creation_num"])
+                {
+                    coin_handles.insert((address, creation_number), coin_type.to_string());
+                }
+            }
+        } else if is_framework_struct(tag, "fungible_asset", "FungibleStore") {
+            if let Some(metadata) = json_address(&data["metadata"]["inner"]) {
+                store_assets.insert(address, metadata.to_hex_literal());
+            }
+        } else if is_framework_struct(tag, "object", "ObjectCore") {
+            if let Some(owner) = json_address(&data["owner"]) {
+                owners.insert(address, owner);
+            }
+        }
+    }
+
+    // Owner, asset, direction and amount of every deposit and withdrawal
+    let mut movements = vec![];
+    for event in &txn.events {
+        let MoveType::Struct(tag) = &event.typ else {
+            continue;
+        };
+        if *tag.address.inner() != CORE_CODE_ADDRESS {
+            continue;
+        }
+        let module = tag.module.as_str();
+        let direction = match (module, tag.name.as_str()) {
+            ("coin" | "fungible_asset", "DepositEvent") => TransferDirection::In,
+            ("coin" | "fungible_asset", "WithdrawEvent") => TransferDirection::Out,
+            _ => continue,
+        };
+        let Some(amount) = json_u64(&event.data["amount"]) else {
+            continue;
+        };
+        let handle_address = AccountAddress::from(event.guid.account_address);
+        let owner_and_asset = if module == "coin" {
+            coin_handles
+                .get(&(handle_address, event.guid.creation_number.0))
+                .map(|coin_type| (handle_address, coin_type))
+        } else {
+            let owner = match owners.get(&handle_address) {
+                Some(owner) => Some(*owner),
+                None => owned_stores.contains(&handle_address).then_some(account),
+            };
+            owner.zip(store_assets.get(&handle_address))
+        };
+        if let Some((owner, asset)) = owner_and_asset {
+            movements.push((owner, asset.clone(), direction, amount));
+        }
+    }
+
+    let mut transfers: BTreeMap<_, (u64, BTreeSet<AccountAddress>)> = BTreeMap::new();
+    for (owner, asset, direction, amount) in &movements {
+        if *owner != account {
+            continue;
+        }
+        let transfer = transfers.entry((*direction, asset.clone())).or_default();
+        transfer.0 += amount;
+        transfer.1.extend(
+            movements
+                .iter()
+                .filter(|(other, other_asset, other_direction, _)| {
+                    *other != account && other_asset == asset && other_direction != direction
+                })
+                .map(|(other, ..)| *other),
+        );
+    }
+    transfers
+        .into_iter()
+        .map(|((direction, asset), (amount, counterparties))| Transfer {
+            direction,
+            asset,
+            amount,
+            counterparties: counterparties.into_iter().collect(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_transfers, TransferDirection};
+    use aptos_rest_client::aptos_api_types::{HashValue, UserTransaction};
+    use aptos_types::account_address::AccountAddress;
+    use serde_json::{json, Value};
+    use std::{collections::BTreeSet, str::FromStr};
+
+    fn address(address: &str) -> AccountAddress {
+        AccountAddress::from_str(address).unwrap()
+    }
+
+    fn user_transaction(changes: Vec<Value>, events: Vec<Value>) -> UserTransaction {
+        let hash = HashValue::zero();
+        serde_json::from_value(json!({
+            "version": "1",
+            "hash": hash,
+            "state_change_hash": hash,
+            "event_root_hash": hash,
+            "state_checkpoint_hash": null,
+            "gas_used": "10",
+            "success": true,
+            "vm_status": "Executed successfully",
+            "accumulator_root_hash": hash,
+            "changes": changes,
+            "sender": "0xa",
+            "sequence_number": "0",
+            "max_gas_amount": "1000",
+            "gas_unit_price": "100",
+            "expiration_timestamp_secs": "0",
+            "payload": {
+                "type": "entry_function_payload",
+                "function": "0x1::aptos_account::transfer",
+                "type_arguments": [],
+                "arguments": [],
+            },
+            "events": events,
+            "timestamp": "0",
+        }))
+        .unwrap()
+    }
+
+    fn write_resource(address: &str, resource_type: &str, data: Value) -> Value {
+        json!({
+            "type": "write_resource",
+            "address": address,
+            "state_key_hash": "0x0",
+            "data": { "type": resource_type, "data": data },
+        })
+    }
+
+    fn coin_store(address: &str) -> Value {
+        let handle = |creation_number: &str| {
+            json!({
+                "counter": "1",
+                "guid": { "id": { "addr": address, "creation_num": creation_number } },
+            })
+        };
+        write_resource(
+            address,
+            "0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>",
+            json!({
+                "coin": { "value": "1000" },
+                "frozen": false,
+                "deposit_events": handle("2"),
+                "withdraw_events": handle("3"),
+            }),
+        )
+    }
+
+    fn event(account_address: &str, creation_number: &str, event_type: &str, amount: u64) -> Value {
+        json!({
+            "guid": { "creation_number": creation_number, "account_address": account_address },
+            "sequence_number": "0",
+            "type": event_type,
+            "data": { "amount": amount.to_string() },
+        })
+    }
+
+    #[test]
+    fn test_decode_coin_transfers() {
+        let txn = user_transaction(vec![coin_store("0xa"), coin_store("0xb")], vec![
+            event("0xa", "3", "0x1::coin::WithdrawEvent", 100),
+            event("0xb", "2", "0x1::coin::DepositEvent", 100),
+            // Not the event of a framework coin store
+            event("0xb", "2", "0xcafe::coin::DepositEvent", 7),
+            // Not the event handle of a coin store written by the transaction
+            event("0xa", "5", "0x1::coin::DepositEvent", 7),
+        ]);
+
+        let transfers = decode_transfers(&txn, address("0xa"), &BTreeSet::new());
+        assert_eq!(transfers.len(), 1);
+        assert_eq!(transfers[0].direction, TransferDirection::Out);
+        assert_eq!(transfers[0].asset, "0x1::aptos_coin::AptosCoin");
+        assert_eq!(transfers[0].amount, 100);
+        assert_eq!(transfers[0].counterparties, vec![address("0xb")]);
+
+        let transfers = decode_transfers(&txn, address("0xb"), &BTreeSet::new());
+        assert_eq!(transfers.len(), 1);
+        assert_eq!(transfers[0].direction, TransferDirection::In);
+        assert_eq!(transfers[0].amount, 100);
+        assert_eq!(transfers[0].counterparties, vec![address("0xa")]);
+
+        assert!(decode_transfers(&txn, address("0xc"), &BTreeSet::new()).is_empty());
+    }
+
+    #[test]
+    fn test_decode_fungible_asset_transfers() {
+        let store = |store: &str, owner: &str| {
+            vec![
+                write_resource(
+                    store,
+                    "0x1::fungible_asset::FungibleStore",
+                    json!({ "metadata": { "inner": "0xfa" }, "balance": "1000", "frozen": false }),
+                ),
+                write_resource(store, "0x1::object::ObjectCore", json!({ "owner": owner })),
+            ]
+        };
+        let changes = [
+            store("0xa1", "0xa"),
+            store("0xb1", "0xb"),
+            store("0xc1", "0xc"),
+        ]
+        .concat();
+        let txn = user_transaction(changes, vec![
+            event("0xa1", "0", "0x1::fungible_asset::WithdrawEvent", 30),
+            event("0xb1", "0", "0x1::fungible_asset::DepositEvent", 20),
+            event("0xc1", "0", "0x1::fungible_asset::DepositEvent", 10),
+            event("0xa1", "0", "0x1::fungible_asset::DepositEvent", 5),
+            event("0xa1", "0", "0x1::fungible_asset::WithdrawEvent", 5),
+            // Not a store written by the transaction
+            event("0xd1", "0", "0x1::fungible_asset::DepositEvent", 7),
+        ]);
+
+        let transfers = decode_transfers(&txn, address("0xa"), &BTreeSet::new());
+        assert_eq!(transfers.len(), 2);
+        assert_eq!(transfers[0].direction, TransferDirection::In);
+        assert_eq!(transfers[0].asset, "0xfa");
+        assert_eq!(transfers[0].amount, 5);
+        assert!(transfers[0].counterparties.is_empty());
+        assert_eq!(transfers[1].direction, TransferDirection::Out);
+        assert_eq!(transfers[1].amount, 35);
+        assert_eq!(transfers[1].counterparties, vec![
+            address("0xb"),
+            address("0xc")
+        ]);
+    }
+
+    #[test]
+    fn test_decode_deposit_to_owned_store() {
+        let fungible_store = |store: &str| {
+            write_resource(
+                store,
+                "0x1::fungible_asset::FungibleStore",
+                json!({ "metadata": { "inner": "0xfa" }, "balance": "1000", "frozen": false }),
+            )
+        };
+        // The owner of the receiving store isn't written by the deposit.
+        let txn = user_transaction(
+            vec![
+                fungible_store("0xa1"),
+                write_resource("0xa1", "0x1::object::ObjectCore", json!({ "owner": "0xa" })),
+                fungible_store("0xb1"),
+            ],
+            vec![
+                event("0xa1", "0", "0x1::fungible_asset::WithdrawEvent", 30),
+                event("0xb1", "0", "0x1::fungible_asset::DepositEvent", 30),
+            ],
+        );
+
+        assert!(decode_transfers(&txn, address("0xb"), &BTreeSet::new()).is_empty());
+        let transfers = decode_transfers(&txn, address("0xb"), &BTreeSet::from([address("0xb1")]));
+        assert_eq!(transfers.len(), 1);
+        assert_eq!(transfers[0].direction, TransferDirection::In);
+        assert_eq!(transfers[0].asset, "0xfa");
+        assert_eq!(transfers[0].amount, 30);
+        assert_eq!(transfers[0].counterparties, vec![address("0xa")]);
+    }
+}
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::common::{
+    encrypted_key::{read_new_passphrase, EncryptedPrivateKey, PASSPHRASE_ENV_VAR},
     types::{
         account_address_from_auth_key, account_address_from_public_key,
         AuthenticationKeyInputOptions, CliCommand, CliConfig, CliError, CliTypedResult,
@@ -36,7 +37,9 @@ use std::{collections::BTreeMap, path::PathBuf};
 /// private key.  You must provide a new private key.  Once it is
 /// rotated you will need to use the original account address, with the
 /// new private key.  There is an interactive prompt to help you add it
-/// to a new profile.
+/// to a new profile.  If the private key of the profile is encrypted, the
+/// new one is saved encrypted too, with a passphrase read from
+/// `APTOS_PROFILE_PASSPHRASE` or prompted for.
 #[derive(Debug, Parser)]
 pub struct RotateKey {
     #[clap(flatten)]
@@ -222,12 +225,12 @@ impl CliCommand<RotateSummary> for RotateKey {
             return Err(CliError::AbortedError);
         }
 
-        let mut profile_config = ProfileConfig {
-            private_key: Some(new_private_key.clone()),
-            public_key: Some(new_private_key.public_key()),
-            account: Some(sender_address),
-            ..self.txn_options.profile_options.profile()?
-        };
+        let mut profile_config = rotated_profile_config(
+            self.txn_options.profile_options.profile()?,
+            &new_private_key,
+            sender_address,
+            || read_new_passphrase(&profile_name, PASSPHRASE_ENV_VAR),
+        )?;
 
         if let Some(url) = self.txn_options.rest_options.url {
             profile_config.rest_url = Some(url.into());
@@ -253,6 +256,32 @@ impl CliCommand<RotateSummary> for RotateKey {
     }
 }
 
+/// The profile to save a rotated key to, based on the profile it was rotated from. The new key is
+/// encrypted if the previous one was, with the passphrase returned by `read_passphrase`.
+fn rotated_profile_config(
+    profile: ProfileConfig,
+    new_private_key: &Ed25519PrivateKey,
+    account: AccountAddress,
+    read_passphrase: impl FnOnce() -> CliTypedResult<String>,
+) -> CliTypedResult<ProfileConfig> {
+    let (private_key, encrypted_private_key) = if profile.encrypted_private_key.is_some() {
+        let passphrase = read_passphrase()?;
+        (
+            None,
+            Some(EncryptedPrivateKey::encrypt(new_private_key, &passphrase)?),
+        )
+    } else {
+        (Some(new_private_key.clone()), None)
+    };
+    Ok(ProfileConfig {
+        private_key,
+        encrypted_private_key,
+        public_key: Some(new_private_key.public_key()),
+        account: Some(account),
+        ..profile
+    })
+}
+
 /// Lookup the account address through the on-chain lookup table
 ///
 /// If the account is rotated, it will provide the address accordingly.  If the account was not
@@ -364,3 +393,65 @@ pub struct OriginatingResource {
 pub struct Table {
     pub handle: AccountAddress,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::rotated_profile_config;
+    use crate::common::{encrypted_key::EncryptedPrivateKey, types::ProfileConfig};
+    use aptos_crypto::PrivateKey;
+    use aptos_keygen::KeyGen;
+    use aptos_types::account_address::AccountAddress;
+
+    #[test]
+    fn test_rotated_key_of_encrypted_profile_is_encrypted() {
+        let mut keygen = KeyGen::from_os_rng();
+        let old_private_key = keygen.generate_ed25519_private_key();
+        let new_private_key = keygen.generate_ed25519_private_key();
+        let profile = ProfileConfig {
+            encrypted_private_key: Some(
+                EncryptedPrivateKey::encrypt(&old_private_key, "old passphrase").unwrap(),
+            ),
+            public_key: Some(old_private_key.public_key()),
+            rest_url: Some("http://localhost:8080".to_string()),
+            ..Default::default()
+        };
+
+        let rotated =
+            rotated_profile_config(profile, &new_private_key, AccountAddress::ONE, || {
+                Ok("new passphrase".to_string())
+            })
+            .unwrap();
+        assert!(rotated.private_key.is_none());
+        assert_eq!(
+            rotated
+                .encrypted_private_key
+                .unwrap()
+                .decrypt("new passphrase")
+                .unwrap(),
+            new_private_key
+        );
+        assert_eq!(rotated.public_key, Some(new_private_key.public_key()));
+        assert_eq!(rotated.account, Some(AccountAddress::ONE));
+        assert_eq!(rotated.rest_url.as_deref(), Some("http://localhost:8080"));
+    }
+
+    #[test]
+    fn test_rotated_key_of_plaintext_profile_is_plaintext() {
+        let mut keygen = KeyGen::from_os_rng();
+        let old_private_key = keygen.generate_ed25519_private_key();
+        let new_private_key = keygen.generate_ed25519_private_key();
+        let profile = ProfileConfig {
+            private_key: Some(old_private_key.clone()),
+            public_key: Some(old_private_key.public_key()),
+            ..Default::default()
+        };
+
+        let rotated =
+            rotated_profile_config(profile, &new_private_key, AccountAddress::ONE, || {
+                panic!("No passphrase is needed")
+            })
+            .unwrap();
+        assert_eq!(rotated.private_key, Some(new_private_key));
+        assert!(rotated.encrypted_private_key.is_none());
+    }
+}
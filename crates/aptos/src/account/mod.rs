@@ -1,13 +1,18 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::common::types::{CliCommand, CliResult};
+use crate::common::{
+    types::{CliCommand, CliResult},
+    utils::execute_with_output,
+};
 use clap::Subcommand;
 
+pub mod balances;
 pub mod create;
 pub mod create_resource_account;
 pub mod derive_resource_account;
 pub mod fund;
+pub mod history;
 pub mod key_rotation;
 pub mod list;
 pub mod multisig_account;
@@ -19,10 +24,12 @@ pub mod transfer;
 /// account's resources, and transfer resources between accounts.
 #[derive(Debug, Subcommand)]
 pub enum AccountTool {
+    Balances(balances::ListBalances),
     Create(create::CreateAccount),
     CreateResourceAccount(create_resource_account::CreateResourceAccount),
     DeriveResourceAccountAddress(derive_resource_account::DeriveResourceAccount),
     FundWithFaucet(fund::FundWithFaucet),
+    History(history::ListHistory),
     List(list::ListAccount),
     LookupAddress(key_rotation::LookupAddress),
     RotateKey(key_rotation::RotateKey),
@@ -32,10 +39,18 @@ pub enum AccountTool {
 impl AccountTool {
     pub async fn execute(self) -> CliResult {
         match self {
+            AccountTool::Balances(tool) => {
+                let output = tool.output_options.output;
+                execute_with_output(tool, output).await
+            },
             AccountTool::Create(tool) => tool.execute_serialized().await,
             AccountTool::CreateResourceAccount(tool) => tool.execute_serialized().await,
             AccountTool::DeriveResourceAccountAddress(tool) => tool.execute_serialized().await,
             AccountTool::FundWithFaucet(tool) => tool.execute_serialized().await,
+            AccountTool::History(tool) => {
+                let output = tool.output_options.output;
+                execute_with_output(tool, output).await
+            },
             AccountTool::List(tool) => tool.execute_serialized().await,
             AccountTool::LookupAddress(tool) => tool.execute_serialized().await,
             AccountTool::RotateKey(tool) => tool.execute_serialized().await,
@@ -0,0 +1,179 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Passphrase encryption of the private keys stored in CLI profiles.
+
+use crate::common::types::{CliError, CliTypedResult};
+use aptos_crypto::ed25519::Ed25519PrivateKey;
+use argon2::{Algorithm, Argon2, Params, Version};
+use ring::{
+    aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN},
+    rand::{SecureRandom, SystemRandom},
+};
+use serde::{Deserialize, Serialize};
+
+/// Environment variable holding the passphrase of encrypted profiles, checked before prompting
+pub const PASSPHRASE_ENV_VAR: &str = "APTOS_PROFILE_PASSPHRASE";
+/// Environment variable holding the new passphrase when changing the passphrase of a profile
+pub const NEW_PASSPHRASE_ENV_VAR: &str = "APTOS_PROFILE_NEW_PASSPHRASE";
+
+const SALT_LEN: usize = 16;
+const KEY_LEN: usize = 32;
+/// Binds the ciphertext to its use, so it can't be passed off as another kind of secret
+const AAD: &[u8] = b"aptos-cli::profile-private-key";
+
+/// A private key encrypted with AES-256-GCM, under a key derived from a passphrase with Argon2id
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EncryptedPrivateKey {
+    /// Argon2id memory cost, in KiB
+    pub memory_cost: u32,
+    /// Argon2id number of passes
+    pub time_cost: u32,
+    /// Argon2id degree of parallelism
+    pub parallelism: u32,
+    /// Hex encoded salt of the key derivation
+    pub salt: String,
+    /// Hex encoded AES-GCM nonce
+    pub nonce: String,
+    /// Hex encoded encrypted key, followed by its authentication tag
+    pub ciphertext: String,
+}
+
+impl EncryptedPrivateKey {
+    pub fn encrypt(private_key: &Ed25519PrivateKey, passphrase: &str) -> CliTypedResult<Self> {
+        let rng = SystemRandom::new();
+        let mut salt = [0u8; SALT_LEN];
+        let mut nonce = [0u8; NONCE_LEN];
+        rng.fill(&mut salt)
+            .and_then(|_| rng.fill(&mut nonce))
+            .map_err(|_| CliError::UnexpectedError("Failed to generate randomness".to_string()))?;
+
+        let params = Params::default();
+        let key = derive_key(
+            passphrase,
+            &salt,
+            params.m_cost(),
+            params.t_cost(),
+            params.p_cost(),
+        )?;
+        let mut ciphertext = private_key.to_bytes().to_vec();
+        key.seal_in_place_append_tag(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::from(AAD),
+            &mut ciphertext,
+        )
+        .map_err(|_| CliError::UnexpectedError("Failed to encrypt private key".to_string()))?;
+
+        Ok(Self {
+            memory_cost: params.m_cost(),
+            time_cost: params.t_cost(),
+            parallelism: params.p_cost(),
+            salt: hex::encode(salt),
+            nonce: hex::encode(nonce),
+            ciphertext: hex::encode(ciphertext),
+        })
+    }
+
+    pub fn decrypt(&self, passphrase: &str) -> CliTypedResult<Ed25519PrivateKey> {
+        let salt = hex::decode(&self.salt)?;
+        let nonce = Nonce::try_assume_unique_for_key(&hex::decode(&self.nonce)?)
+            .map_err(|_| CliError::UnableToParse("nonce", self.nonce.clone()))?;
+        let mut ciphertext = hex::decode(&self.ciphertext)?;
+
+        let key = derive_key(
+            passphrase,
+            &salt,
+            self.memory_cost,
+            self.time_cost,
+            self.parallelism,
+        )?;
+        let plaintext = key
+            .open_in_place(nonce, Aad::from(AAD), &mut ciphertext)
+            .map_err(|_| {
+                CliError::CommandArgumentError(
+                    "Unable to decrypt private key, the passphrase is incorrect".to_string(),
+                )
+            })?;
+        Ok(Ed25519PrivateKey::try_from(&*plaintext)?)
+    }
+}
+
+fn derive_key(
+    passphrase: &str,
+    salt: &[u8],
+    memory_cost: u32,
+    time_cost: u32,
+    parallelism: u32,
+) -> CliTypedResult<LessSafeKey> {
+    let params =
+        Params::new(memory_cost, time_cost, parallelism, Some(KEY_LEN)).map_err(|err| {
+            CliError::UnexpectedError(format!("Invalid key derivation parameters: {}", err))
+        })?;
+    let mut key = [0u8; KEY_LEN];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|err| CliError::UnexpectedError(format!("Failed to derive key: {}", err)))?;
+    let key = UnboundKey::new(&AES_256_GCM, &key)
+        .map_err(|_| CliError::UnexpectedError("Invalid encryption key".to_string()))?;
+    Ok(LessSafeKey::new(key))
+}
+
+/// Reads the passphrase of a profile from `APTOS_PROFILE_PASSPHRASE`, or prompts for it
+pub fn read_passphrase(profile: &str) -> CliTypedResult<String> {
+    if let Ok(passphrase) = std::env::var(PASSPHRASE_ENV_VAR) {
+        return Ok(passphrase);
+    }
+    rpassword::prompt_password(format!("Enter the passphrase of profile {}: ", profile))
+        .map_err(|err| CliError::IO("passphrase".to_string(), err))
+}
+
+/// Reads a new passphrase for a profile from the given environment variable, or prompts for it
+/// twice to catch typos
+pub fn read_new_passphrase(profile: &str, env_var: &str) -> CliTypedResult<String> {
+    let passphrase = if let Ok(passphrase) = std::env::var(env_var) {
+        passphrase
+    } else {
+        let passphrase =
+            rpassword::prompt_password(format!("Enter a new passphrase for profile {}: ", profile))
+                .map_err(|err| CliError::IO("passphrase".to_string(), err))?;
+        let confirmation = rpassword::prompt_password("Confirm the passphrase: ")
+            .map_err(|err| CliError::IO("passphrase".to_string(), err))?;
+        if passphrase != confirmation {
+            return Err(CliError::CommandArgumentError(
+                "Passphrases do not match".to_string(),
+            ));
+        }
+        passphrase
+    };
+
+    if passphrase.is_empty() {
+        return Err(CliError::CommandArgumentError(
+            "Passphrase must not be empty".to_string(),
+        ));
+    }
+    Ok(passphrase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EncryptedPrivateKey;
+    use aptos_keygen::KeyGen;
+
+    #[test]
+    fn test_encrypt_decrypt_private_key() {
+        let private_key = KeyGen::from_os_rng().generate_ed25519_private_key();
+        let encrypted = EncryptedPrivateKey::encrypt(&private_key, "correct horse").unwrap();
+
+        assert_eq!(encrypted.decrypt("correct horse").unwrap(), private_key);
+        assert!(encrypted.decrypt("battery staple").is_err());
+
+        // Tampering with the ciphertext is detected
+        let mut ciphertext = hex::decode(&encrypted.ciphertext).unwrap();
+        ciphertext[0] ^= 1;
+        let tampered = EncryptedPrivateKey {
+            ciphertext: hex::encode(ciphertext),
+            ..encrypted
+        };
+        assert!(tampered.decrypt("correct horse").is_err());
+    }
+}
@@ -139,11 +139,11 @@ impl CliCommand<()> for InitTool {
             eprintln!("Using command line argument for private key");
             private_key
         } else {
-            eprintln!("Enter your private key as a hex literal (0x...) [Current: {} | No input: Generate new key (or keep one if present)]", profile_config.private_key.as_ref().map(|_| "Redacted").unwrap_or("None"));
+            eprintln!("Enter your private key as a hex literal (0x...) [Current: {} | No input: Generate new key (or keep one if present)]", if profile_config.private_key.is_some() || profile_config.encrypted_private_key.is_some() { "Redacted" } else { "None" });
             let input = read_line("Private key")?;
             let input = input.trim();
             if input.is_empty() {
-                if let Some(private_key) = profile_config.private_key {
+                if let Some(private_key) = profile_config.decrypted_private_key(profile_name)? {
                     eprintln!("No key given, keeping existing key...");
                     private_key
                 } else {
@@ -174,7 +174,13 @@ impl CliCommand<()> for InitTool {
         let derived_address = account_address_from_public_key(&public_key);
         let address = lookup_address(&client, derived_address, false).await?;
 
-        profile_config.private_key = Some(private_key);
+        // An encrypted key that is kept stays encrypted, a new key replaces it
+        if profile_config.encrypted_private_key.is_none()
+            || profile_config.public_key.as_ref() != Some(&public_key)
+        {
+            profile_config.private_key = Some(private_key);
+            profile_config.encrypted_private_key = None;
+        }
         profile_config.public_key = Some(public_key);
         profile_config.account = Some(address);
 
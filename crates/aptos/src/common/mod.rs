@@ -1,6 +1,7 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod encrypted_key;
 pub mod init;
 pub mod types;
 pub mod utils;
@@ -3,18 +3,20 @@
 
 use crate::{
     common::{
+        encrypted_key::{read_passphrase, EncryptedPrivateKey},
         init::Network,
         utils::{
             check_if_file_exists, create_dir_if_not_exist, dir_default_to_current,
             get_account_with_state, get_auth_key, get_sequence_number, parse_json_file,
             prompt_yes_with_override, read_from_file, start_logger, to_common_result,
-            to_common_success_result, write_to_file, write_to_file_with_opts,
+            to_common_success_result, write_gas_reports, write_to_file, write_to_file_with_opts,
             write_to_user_only_file,
         },
     },
     config::GlobalConfig,
     genesis::git::from_yaml,
     move_tool::{ArgWithType, FunctionArgType, MemberId},
+    transaction::OfflineTransaction,
 };
 use anyhow::Context;
 use aptos_crypto::{
@@ -35,8 +37,9 @@ use aptos_sdk::{transaction_builder::TransactionFactory, types::LocalAccount};
 use aptos_types::{
     chain_id::ChainId,
     transaction::{
-        authenticator::AuthenticationKey, EntryFunction, MultisigTransactionPayload, Script,
-        SignedTransaction, TransactionArgument, TransactionPayload, TransactionStatus,
+        authenticator::{AccountAuthenticator, AuthenticationKey},
+        EntryFunction, MultisigTransactionPayload, RawTransaction, Script, SignedTransaction,
+        TransactionArgument, TransactionPayload, TransactionStatus,
     },
 };
 use async_trait::async_trait;
@@ -105,6 +108,8 @@ pub enum CliError {
     SimulationError(String),
     #[error("Coverage failed with status: {0}")]
     CoverageError(String),
+    #[error("Gas usage regressed {0}")]
+    GasRegressionError(String),
 }
 
 impl CliError {
@@ -125,6 +130,7 @@ impl CliError {
             CliError::UnexpectedError(_) => "UnexpectedError",
             CliError::SimulationError(_) => "SimulationError",
             CliError::CoverageError(_) => "CoverageError",
+            CliError::GasRegressionError(_) => "GasRegressionError",
         }
     }
 }
@@ -209,6 +215,9 @@ pub struct ProfileConfig {
     /// Private key for commands.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub private_key: Option<Ed25519PrivateKey>,
+    /// Private key for commands, encrypted with a passphrase
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encrypted_private_key: Option<EncryptedPrivateKey>,
     /// Public key for commands
     #[serde(skip_serializing_if = "Option::is_none")]
     pub public_key: Option<Ed25519PublicKey>,
@@ -227,6 +236,7 @@ pub struct ProfileConfig {
 #[derive(Debug, Serialize)]
 pub struct ProfileSummary {
     pub has_private_key: bool,
+    pub private_key_encrypted: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub public_key: Option<Ed25519PublicKey>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -240,7 +250,8 @@ pub struct ProfileSummary {
 impl From<&ProfileConfig> for ProfileSummary {
     fn from(config: &ProfileConfig) -> Self {
         ProfileSummary {
-            has_private_key: config.private_key.is_some(),
+            has_private_key: config.private_key.is_some() || config.encrypted_private_key.is_some(),
+            private_key_encrypted: config.encrypted_private_key.is_some(),
             public_key: config.public_key.clone(),
             account: config.account,
             rest_url: config.rest_url.clone(),
@@ -249,6 +260,23 @@ impl From<&ProfileConfig> for ProfileSummary {
     }
 }
 
+impl ProfileConfig {
+    /// The private key of the profile, prompting for its passphrase if it is encrypted
+    pub fn decrypted_private_key(
+        &self,
+        profile: &str,
+    ) -> CliTypedResult<Option<Ed25519PrivateKey>> {
+        match (&self.private_key, &self.encrypted_private_key) {
+            (Some(private_key), _) => Ok(Some(private_key.clone())),
+            (None, Some(encrypted_private_key)) => {
+                let passphrase = read_passphrase(profile)?;
+                encrypted_private_key.decrypt(&passphrase).map(Some)
+            },
+            (None, None) => Ok(None),
+        }
+    }
+}
+
 impl Default for CliConfig {
     fn default() -> Self {
         CliConfig {
@@ -367,6 +395,8 @@ pub enum KeyType {
     X25519,
     /// A BLS12381 key for consensus
     Bls12381,
+    /// Secp256k1 ECDSA key used for signing
+    Secp256k1Ecdsa,
 }
 
 impl Display for KeyType {
@@ -375,6 +405,7 @@ impl Display for KeyType {
             KeyType::Ed25519 => "ed25519",
             KeyType::X25519 => "x25519",
             KeyType::Bls12381 => "bls12381",
+            KeyType::Secp256k1Ecdsa => "secp256k1-ecdsa",
         };
         write!(f, "{}", str)
     }
@@ -388,7 +419,10 @@ impl FromStr for KeyType {
             "ed25519" => Ok(KeyType::Ed25519),
             "x25519" => Ok(KeyType::X25519),
             "bls12381" => Ok(KeyType::Bls12381),
-            _ => Err("Invalid key type: Must be one of [ed25519, x25519]"),
+            "secp256k1-ecdsa" | "secp256k1ecdsa" => Ok(KeyType::Secp256k1Ecdsa),
+            _ => {
+                Err("Invalid key type: Must be one of [ed25519, x25519, bls12381, secp256k1-ecdsa]")
+            },
         }
     }
 }
@@ -792,7 +826,11 @@ impl PrivateKeyInputOptions {
             profile.profile_name(),
             ConfigSearchMode::CurrentDirAndParents,
         )?
-        .map(|p| (p.private_key, p.account))
+        .map(|p| {
+            p.decrypted_private_key(profile.profile_name().unwrap_or(DEFAULT_PROFILE))
+                .map(|key| (key, p.account))
+        })
+        .transpose()?
         {
             match (maybe_address, maybe_config_address) {
                 (Some(address), _) => Ok((key, address)),
@@ -821,7 +859,8 @@ impl PrivateKeyInputOptions {
             profile.profile_name(),
             ConfigSearchMode::CurrentDirAndParents,
         )?
-        .map(|p| p.private_key)
+        .map(|p| p.decrypted_private_key(profile.profile_name().unwrap_or(DEFAULT_PROFILE)))
+        .transpose()?
         {
             Ok(private_key)
         } else {
@@ -1041,6 +1080,94 @@ impl FromStr for AccountAddressWrapper {
     }
 }
 
+/// Loads the private key of a profile, and its account address
+fn load_profile_signer(profile: &str) -> CliTypedResult<(Ed25519PrivateKey, AccountAddress)> {
+    let profile_config =
+        CliConfig::load_profile(Some(profile), ConfigSearchMode::CurrentDirAndParents)?
+            .ok_or_else(|| {
+                CliError::CommandArgumentError(format!("Profile {} not found", profile))
+            })?;
+    let private_key = profile_config
+        .decrypted_private_key(profile)?
+        .ok_or_else(|| {
+            CliError::CommandArgumentError(format!("Profile {} has no private key", profile))
+        })?;
+    let address = profile_config
+        .account
+        .unwrap_or_else(|| account_address_from_public_key(&private_key.public_key()));
+    Ok((private_key, address))
+}
+
+/// Signers of a transaction besides its sender, for multi-agent and fee payer transactions
+struct AdditionalSigners {
+    secondary_signers: Vec<(Ed25519PrivateKey, AccountAddress)>,
+    fee_payer: Option<(Ed25519PrivateKey, AccountAddress)>,
+}
+
+impl AdditionalSigners {
+    fn secondary_signer_addresses(&self) -> Vec<AccountAddress> {
+        self.secondary_signers
+            .iter()
+            .map(|(_, address)| *address)
+            .collect()
+    }
+
+    /// Builds the transaction with invalid signatures from all signers, to be simulated
+    fn simulation_transaction(
+        &self,
+        raw_txn: RawTransaction,
+        sender_public_key: Ed25519PublicKey,
+    ) -> SignedTransaction {
+        let invalid_signature = || Ed25519Signature::try_from([0u8; 64].as_ref()).unwrap();
+        let authenticator =
+            |public_key| AccountAuthenticator::ed25519(public_key, invalid_signature());
+        let secondary_signers = self
+            .secondary_signers
+            .iter()
+            .map(|(private_key, _)| authenticator(private_key.public_key()))
+            .collect();
+
+        match &self.fee_payer {
+            Some((private_key, address)) => SignedTransaction::new_fee_payer(
+                raw_txn,
+                authenticator(sender_public_key),
+                self.secondary_signer_addresses(),
+                secondary_signers,
+                *address,
+                authenticator(private_key.public_key()),
+            ),
+            None if self.secondary_signers.is_empty() => {
+                SignedTransaction::new(raw_txn, sender_public_key, invalid_signature())
+            },
+            None => SignedTransaction::new_multi_agent(
+                raw_txn,
+                authenticator(sender_public_key),
+                self.secondary_signer_addresses(),
+                secondary_signers,
+            ),
+        }
+    }
+
+    /// Signs the transaction with the keys of the sender and of all other signers
+    fn sign(
+        &self,
+        raw_txn: RawTransaction,
+        sender_address: AccountAddress,
+        sender_key: &Ed25519PrivateKey,
+    ) -> CliTypedResult<SignedTransaction> {
+        let mut transaction = OfflineTransaction::new(
+            raw_txn,
+            self.secondary_signer_addresses(),
+            self.fee_payer.as_ref().map(|(_, address)| *address),
+        );
+        transaction.sign(sender_address, sender_key)?;
+        for (private_key, address) in self.secondary_signers.iter().chain(&self.fee_payer) {
+            transaction.sign(*address, private_key)?;
+        }
+        transaction.into_signed_transaction()
+    }
+}
+
 /// Loads an account arg and allows for naming based on profiles
 pub fn load_account_arg(str: &str) -> Result<AccountAddress, CliError> {
     if str.starts_with("0x") {
@@ -1054,11 +1181,12 @@ pub fn load_account_arg(str: &str) -> Result<AccountAddress, CliError> {
             .map(|p| p.account)
     {
         Ok(account_address)
-    } else if let Some(Some(private_key)) =
-        CliConfig::load_profile(Some(str), ConfigSearchMode::CurrentDirAndParents)?
-            .map(|p| p.private_key)
+    } else if let Some(Some(public_key)) =
+        CliConfig::load_profile(Some(str), ConfigSearchMode::CurrentDirAndParents)?.map(|p| {
+            p.public_key
+                .or_else(|| p.private_key.map(|key| key.public_key()))
+        })
     {
-        let public_key = private_key.public_key();
         Ok(account_address_from_public_key(&public_key))
     } else {
         Err(CliError::CommandArgumentError(
@@ -1095,11 +1223,12 @@ pub fn load_manifest_account_arg(str: &str) -> Result<Option<AccountAddress>, Cl
             })
     } else if let Ok(account_address) = AccountAddress::from_str(str) {
         Ok(Some(account_address))
-    } else if let Some(Some(private_key)) =
-        CliConfig::load_profile(Some(str), ConfigSearchMode::CurrentDirAndParents)?
-            .map(|p| p.private_key)
+    } else if let Some(Some(public_key)) =
+        CliConfig::load_profile(Some(str), ConfigSearchMode::CurrentDirAndParents)?.map(|p| {
+            p.public_key
+                .or_else(|| p.private_key.map(|key| key.public_key()))
+        })
     {
-        let public_key = private_key.public_key();
         Ok(Some(account_address_from_public_key(&public_key)))
     } else {
         Err(CliError::CommandArgumentError(
@@ -1147,10 +1276,50 @@ pub trait CliCommand<T: Serialize + Send>: Sized + Send {
     }
 }
 
+/// Formats commands listing items can print their result in
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// The common JSON output
+    #[default]
+    Json,
+    /// A table with a row per item, for reading in a terminal
+    Table,
+}
+
+impl Display for OutputFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let str = match self {
+            OutputFormat::Json => "json",
+            OutputFormat::Table => "table",
+        };
+        write!(f, "{}", str)
+    }
+}
+
+#[derive(Debug, Default, Parser)]
+pub struct OutputOptions {
+    /// Format of the output: [json, table]
+    ///
+    /// Errors are always output as JSON.
+    #[clap(long, value_enum, ignore_case = true, default_value_t = OutputFormat::Json)]
+    pub(crate) output: OutputFormat,
+}
+
+/// An item of a command result, which can be printed as a row of a table
+pub trait TableRow {
+    /// Headers of the columns of the table
+    const HEADERS: &'static [&'static str];
+
+    /// Cells of the row, one per header
+    fn cells(&self) -> Vec<String>;
+}
+
 /// A shortened transaction output
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct TransactionSummary {
-    pub transaction_hash: HashValue,
+    /// Not known for transactions which have not been signed yet
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction_hash: Option<HashValue>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub gas_used: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1180,7 +1349,7 @@ impl From<&Transaction> for TransactionSummary {
     fn from(transaction: &Transaction) -> Self {
         match transaction {
             Transaction::PendingTransaction(txn) => TransactionSummary {
-                transaction_hash: txn.hash,
+                transaction_hash: Some(txn.hash),
                 pending: Some(true),
                 sender: Some(*txn.request.sender.inner()),
                 sequence_number: Some(txn.request.sequence_number.0),
@@ -1192,7 +1361,7 @@ impl From<&Transaction> for TransactionSummary {
                 timestamp_us: None,
             },
             Transaction::UserTransaction(txn) => TransactionSummary {
-                transaction_hash: txn.info.hash,
+                transaction_hash: Some(txn.info.hash),
                 sender: Some(*txn.request.sender.inner()),
                 gas_used: Some(txn.info.gas_used.0),
                 gas_unit_price: Some(txn.request.gas_unit_price.0),
@@ -1204,7 +1373,7 @@ impl From<&Transaction> for TransactionSummary {
                 pending: None,
             },
             Transaction::GenesisTransaction(txn) => TransactionSummary {
-                transaction_hash: txn.info.hash,
+                transaction_hash: Some(txn.info.hash),
                 success: Some(txn.info.success),
                 version: Some(txn.info.version.0),
                 vm_status: Some(txn.info.vm_status.clone()),
@@ -1216,7 +1385,7 @@ impl From<&Transaction> for TransactionSummary {
                 timestamp_us: None,
             },
             Transaction::BlockMetadataTransaction(txn) => TransactionSummary {
-                transaction_hash: txn.info.hash,
+                transaction_hash: Some(txn.info.hash),
                 success: Some(txn.info.success),
                 version: Some(txn.info.version.0),
                 vm_status: Some(txn.info.vm_status.clone()),
@@ -1228,7 +1397,7 @@ impl From<&Transaction> for TransactionSummary {
                 sequence_number: None,
             },
             Transaction::StateCheckpointTransaction(txn) => TransactionSummary {
-                transaction_hash: txn.info.hash,
+                transaction_hash: Some(txn.info.hash),
                 success: Some(txn.info.success),
                 version: Some(txn.info.version.0),
                 vm_status: Some(txn.info.vm_status.clone()),
@@ -1360,9 +1529,50 @@ pub struct TransactionOptions {
     pub(crate) prompt_options: PromptOptions,
 
     /// If this option is set, simulate the transaction locally using the debugger and generate
-    /// flamegraphs that reflect the gas usage.
+    /// flamegraphs and an HTML report that reflect the gas usage.
     #[clap(long)]
     pub(crate) profile_gas: bool,
+
+    /// Gas profile (.json) saved by an earlier run with `--profile-gas`, to compare the gas
+    /// usage against, e.g. before a code change or a gas schedule update
+    #[clap(long, value_parser, requires = "profile_gas")]
+    pub(crate) profile_gas_baseline: Option<PathBuf>,
+
+    /// Write the transaction unsigned to this file instead of submitting it
+    ///
+    /// The sequence number, gas unit price, expiration and chain id are filled in from the
+    /// connected node, and the private key of the sender is not needed. The transaction can then
+    /// be signed offline with `aptos transaction sign`, and submitted with
+    /// `aptos transaction submit`.
+    ///
+    /// Commands which can only submit their transaction, such as `aptos account transfer`, fail
+    /// with this option.
+    #[clap(long, value_parser, conflicts_with = "profile_gas")]
+    pub(crate) unsigned_output_file: Option<PathBuf>,
+
+    /// Secondary signers of the unsigned transaction, for multi-agent transactions
+    #[clap(long, num_args = 0.., requires = "unsigned_output_file", value_parser = crate::common::types::load_account_arg)]
+    pub(crate) secondary_signer_addresses: Vec<AccountAddress>,
+
+    /// Account paying the gas of the unsigned transaction, for fee payer transactions
+    #[clap(long, requires = "unsigned_output_file", value_parser = crate::common::types::load_account_arg)]
+    pub(crate) fee_payer_address: Option<AccountAddress>,
+
+    /// Profiles of the secondary signers, to submit a multi-agent transaction
+    ///
+    /// The transaction is signed with the private key of every profile. To collect the
+    /// signatures of accounts whose keys are not on this machine, use `--unsigned-output-file`
+    /// with `--secondary-signer-addresses` instead.
+    #[clap(long, num_args = 0.., conflicts_with_all = ["profile_gas", "unsigned_output_file"])]
+    pub(crate) secondary_signer_profiles: Vec<String>,
+
+    /// Profile of the account paying the gas of the transaction, to submit a fee payer
+    /// transaction
+    ///
+    /// To have the gas paid by an account whose key is not on this machine, use
+    /// `--unsigned-output-file` with `--fee-payer-address` instead.
+    #[clap(long, conflicts_with_all = ["profile_gas", "unsigned_output_file"])]
+    pub(crate) fee_payer_profile: Option<String>,
 }
 
 impl TransactionOptions {
@@ -1385,6 +1595,23 @@ impl TransactionOptions {
         Ok(self.get_key_and_address()?.1)
     }
 
+    /// Retrieves the keys and addresses of the secondary signers and of the fee payer, from their
+    /// profiles
+    fn additional_signers(&self) -> CliTypedResult<AdditionalSigners> {
+        Ok(AdditionalSigners {
+            secondary_signers: self
+                .secondary_signer_profiles
+                .iter()
+                .map(|profile| load_profile_signer(profile))
+                .collect::<CliTypedResult<_>>()?,
+            fee_payer: self
+                .fee_payer_profile
+                .as_deref()
+                .map(load_profile_signer)
+                .transpose()?,
+        })
+    }
+
     /// Gets the auth key by account address. We need to fetch the auth key from Rest API rather than creating an
     /// auth key out of the public key.
     pub(crate) async fn auth_key(
@@ -1410,8 +1637,17 @@ impl TransactionOptions {
         &self,
         payload: TransactionPayload,
     ) -> CliTypedResult<Transaction> {
+        // Only the commands going through `profile_or_submit` can write the transaction unsigned
+        if self.unsigned_output_file.is_some() {
+            return Err(CliError::CommandArgumentError(
+                "`--unsigned-output-file` is not supported by this command, which always submits \
+                the transaction"
+                    .to_string(),
+            ));
+        }
         let client = self.rest_client()?;
         let (sender_key, sender_address) = self.get_key_and_address()?;
+        let additional_signers = self.additional_signers()?;
 
         // Ask to confirm price if the gas unit price is estimated above the lowest value when
         // it is automatically estimated
@@ -1465,11 +1701,8 @@ impl TransactionOptions {
                 .expiration_timestamp_secs(expiration_time_secs)
                 .build();
 
-            let signed_transaction = SignedTransaction::new(
-                unsigned_transaction,
-                sender_key.public_key(),
-                Ed25519Signature::try_from([0u8; 64].as_ref()).unwrap(),
-            );
+            let signed_transaction = additional_signers
+                .simulation_transaction(unsigned_transaction, sender_key.public_key());
 
             let txns = client
                 .simulate_with_gas_estimation(&signed_transaction, true, false)
@@ -1506,9 +1739,12 @@ impl TransactionOptions {
             .with_gas_unit_price(gas_unit_price)
             .with_max_gas_amount(max_gas)
             .with_transaction_expiration_time(self.gas_options.expiration_secs);
-        let sender_account = &mut LocalAccount::new(sender_address, sender_key, sequence_number);
-        let transaction =
-            sender_account.sign_with_transaction_builder(transaction_factory.payload(payload));
+        let raw_txn = transaction_factory
+            .payload(payload)
+            .sender(sender_address)
+            .sequence_number(sequence_number)
+            .build();
+        let transaction = additional_signers.sign(raw_txn, sender_address, &sender_key)?;
         let response = client
             .submit_and_wait(&transaction)
             .await
@@ -1517,6 +1753,148 @@ impl TransactionOptions {
         Ok(response.into_inner())
     }
 
+    /// Max gas for transactions that can't be simulated to estimate it: the one given, otherwise
+    /// what `payer_address` can afford at `version`, capped to a default
+    async fn max_gas_without_simulation(
+        &self,
+        client: &Client,
+        payer_address: AccountAddress,
+        gas_unit_price: u64,
+        version: u64,
+    ) -> CliTypedResult<u64> {
+        const DEFAULT_MAX_GAS: u64 = 2_000_000;
+
+        if let Some(max_gas) = self.gas_options.max_gas {
+            return Ok(max_gas);
+        }
+        if gas_unit_price == 0 {
+            return Ok(DEFAULT_MAX_GAS);
+        }
+        let balance = client
+            .get_account_balance_at_version(payer_address, version)
+            .await
+            .map_err(|err| CliError::ApiError(err.to_string()))?
+            .into_inner();
+        Ok(std::cmp::min(
+            balance.coin.value.0 / gas_unit_price,
+            DEFAULT_MAX_GAS,
+        ))
+    }
+
+    /// Write the transaction unsigned to a file, to be signed offline. Only the address of the
+    /// sender is needed, its private key may not even be on this machine.
+    pub async fn write_unsigned_transaction(
+        &self,
+        payload: TransactionPayload,
+        output_file: &Path,
+    ) -> CliTypedResult<TransactionSummary> {
+        let client = self.rest_client()?;
+        let sender_address = match self.sender_account {
+            Some(sender_address) => sender_address,
+            None => self.profile_options.account_address()?,
+        };
+        let gas_unit_price = if let Some(gas_unit_price) = self.gas_options.gas_unit_price {
+            gas_unit_price
+        } else {
+            client.estimate_gas_price().await?.into_inner().gas_estimate
+        };
+        let (account, state) = get_account_with_state(&client, sender_address).await?;
+
+        // The transaction can't be simulated without its signatures, so the max gas defaults to
+        // what the account paying for it can afford
+        let payer_address = self.fee_payer_address.unwrap_or(sender_address);
+        let max_gas = self
+            .max_gas_without_simulation(&client, payer_address, gas_unit_price, state.version)
+            .await?;
+
+        let raw_txn = TransactionFactory::new(ChainId::new(state.chain_id))
+            .with_gas_unit_price(gas_unit_price)
+            .with_max_gas_amount(max_gas)
+            .with_transaction_expiration_time(self.gas_options.expiration_secs)
+            .payload(payload)
+            .sender(sender_address)
+            .sequence_number(account.sequence_number)
+            .build();
+        let transaction = OfflineTransaction::new(
+            raw_txn,
+            self.secondary_signer_addresses.clone(),
+            self.fee_payer_address,
+        );
+        write_to_file(
+            output_file,
+            &output_file.display().to_string(),
+            &bcs::to_bytes(&transaction)?,
+        )?;
+        eprintln!("Unsigned transaction saved to {}", output_file.display());
+
+        Ok(TransactionSummary {
+            transaction_hash: None,
+            gas_used: None,
+            gas_unit_price: Some(gas_unit_price),
+            pending: None,
+            sender: Some(sender_address),
+            sequence_number: Some(account.sequence_number),
+            success: None,
+            timestamp_us: None,
+            version: None,
+            vm_status: None,
+        })
+    }
+
+    /// Simulate transactions from the sender in order, each of them observing the writes of the
+    /// previous ones. Nothing is submitted.
+    pub async fn simulate_bundle(
+        &self,
+        payloads: Vec<TransactionPayload>,
+    ) -> CliTypedResult<Vec<TransactionSummary>> {
+        let client = self.rest_client()?;
+        let (sender_key, sender_address) = self.get_key_and_address()?;
+        let gas_unit_price = if let Some(gas_unit_price) = self.gas_options.gas_unit_price {
+            gas_unit_price
+        } else {
+            client.estimate_gas_price().await?.into_inner().gas_estimate
+        };
+        let (account, state) = get_account_with_state(&client, sender_address).await?;
+        let chain_id = ChainId::new(state.chain_id);
+
+        // Every transaction must be able to pay for its maximum gas on its own
+        let max_gas = self
+            .max_gas_without_simulation(&client, sender_address, gas_unit_price, state.version)
+            .await?;
+
+        // Transactions are simulated with an invalid signature and consecutive sequence numbers
+        let transaction_factory = TransactionFactory::new(chain_id)
+            .with_gas_unit_price(gas_unit_price)
+            .with_max_gas_amount(max_gas)
+            .with_transaction_expiration_time(self.gas_options.expiration_secs);
+        let transactions: Vec<_> = payloads
+            .into_iter()
+            .enumerate()
+            .map(|(index, payload)| {
+                let unsigned_transaction = transaction_factory
+                    .payload(payload)
+                    .sender(sender_address)
+                    .sequence_number(account.sequence_number + index as u64)
+                    .build();
+                SignedTransaction::new(
+                    unsigned_transaction,
+                    sender_key.public_key(),
+                    Ed25519Signature::try_from([0u8; 64].as_ref()).unwrap(),
+                )
+            })
+            .collect();
+
+        let simulated_txns = client
+            .simulate_bundle(&transactions)
+            .await
+            .map_err(|err| CliError::ApiError(err.to_string()))?
+            .into_inner();
+        Ok(simulated_txns
+            .into_iter()
+            .map(|txn| TransactionSummary::from(Transaction::UserTransaction(Box::new(txn))))
+            .collect())
+    }
+
     /// Simulate the transaction locally using the debugger, with the gas profiler enabled.
     pub async fn profile_gas(
         &self,
@@ -1660,6 +2038,15 @@ impl TransactionOptions {
             },
         }
 
+        // Generate the HTML report.
+        write_gas_reports(
+            &gas_log,
+            dir,
+            &raw_file_name,
+            &format!("Transaction {} -- Gas Report", hash),
+            self.profile_gas_baseline.as_deref(),
+        )?;
+
         println!();
 
         // Generate the transaction summary
@@ -1671,7 +2058,7 @@ impl TransactionOptions {
         };
 
         Ok(TransactionSummary {
-            transaction_hash: hash.into(),
+            transaction_hash: Some(hash.into()),
             gas_used: Some(output.gas_used()),
             gas_unit_price: Some(gas_unit_price),
             pending: None,
@@ -2020,3 +2407,65 @@ impl TryInto<ScriptFunctionArguments> for ScriptFunctionArgumentsJSON {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{account_address_from_public_key, AdditionalSigners};
+    use aptos_crypto::{ed25519::Ed25519PrivateKey, PrivateKey};
+    use aptos_keygen::KeyGen;
+    use aptos_types::{
+        account_address::AccountAddress,
+        chain_id::ChainId,
+        transaction::{RawTransaction, Script, TransactionPayload},
+    };
+
+    fn generate_signer(keygen: &mut KeyGen) -> (Ed25519PrivateKey, AccountAddress) {
+        let private_key = keygen.generate_ed25519_private_key();
+        let address = account_address_from_public_key(&private_key.public_key());
+        (private_key, address)
+    }
+
+    #[test]
+    fn test_multi_agent_fee_payer_transaction() {
+        let mut keygen = KeyGen::from_os_rng();
+        let (sender_key, sender_address) = generate_signer(&mut keygen);
+        let additional_signers = AdditionalSigners {
+            secondary_signers: vec![generate_signer(&mut keygen), generate_signer(&mut keygen)],
+            fee_payer: Some(generate_signer(&mut keygen)),
+        };
+        let secondary_signer_addresses = additional_signers.secondary_signer_addresses();
+        let fee_payer_address = additional_signers.fee_payer.as_ref().map(|(_, a)| *a);
+        let raw_txn = RawTransaction::new(
+            sender_address,
+            0,
+            TransactionPayload::Script(Script::new(vec![], vec![], vec![])),
+            1000,
+            100,
+            u64::MAX,
+            ChainId::test(),
+        );
+
+        let signed_txn = additional_signers
+            .sign(raw_txn.clone(), sender_address, &sender_key)
+            .unwrap();
+        signed_txn.check_signature().unwrap();
+        let authenticator = signed_txn.authenticator();
+        assert_eq!(
+            authenticator.secondary_signer_addreses(),
+            secondary_signer_addresses
+        );
+        assert_eq!(authenticator.fee_payer_address(), fee_payer_address);
+
+        // The simulated transaction has the same signers, but can't pass for a signed one
+        let simulation_txn =
+            additional_signers.simulation_transaction(raw_txn, sender_key.public_key());
+        assert!(simulation_txn.check_signature().is_err());
+        let authenticator = simulation_txn.authenticator();
+        assert_eq!(
+            authenticator.secondary_signer_addreses(),
+            secondary_signer_addresses
+        );
+        assert_eq!(authenticator.fee_payer_address(), fee_payer_address);
+        assert_eq!(simulation_txn.sender(), sender_address);
+    }
+}
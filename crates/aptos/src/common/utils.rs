@@ -3,14 +3,15 @@
 
 use crate::{
     common::types::{
-        account_address_from_public_key, CliError, CliTypedResult, PromptOptions,
-        TransactionOptions, TransactionSummary,
+        account_address_from_public_key, CliCommand, CliError, CliTypedResult, OutputFormat,
+        PromptOptions, TableRow, TransactionOptions, TransactionSummary,
     },
     config::GlobalConfig,
     CliResult,
 };
 use aptos_build_info::build_information;
 use aptos_crypto::ed25519::{Ed25519PrivateKey, Ed25519PublicKey};
+use aptos_gas_profiling::{GasProfileSummary, TransactionGasLog};
 use aptos_keygen::KeyGen;
 use aptos_logger::{debug, Level};
 use aptos_rest_client::{aptos_api_types::HashValue, Account, Client, State};
@@ -476,7 +477,51 @@ pub fn start_logger(level: Level) {
     logger.build();
 }
 
-/// For transaction payload and options, either get gas profile or submit for execution.
+/// Executes a command listing items, and prints them as a table if requested instead of the
+/// common JSON output
+pub async fn execute_with_output<C, R>(command: C, output: OutputFormat) -> CliResult
+where
+    C: CliCommand<Vec<R>>,
+    R: TableRow + Serialize + Send,
+{
+    start_logger(Level::Warn);
+    let command_name = command.command_name();
+    let start_time = Instant::now();
+    let result = command.execute().await;
+    let table = match (&result, output) {
+        (Ok(rows), OutputFormat::Table) => Some(render_table(rows)),
+        _ => None,
+    };
+    let json = to_common_result(command_name, start_time, result).await?;
+    Ok(table.unwrap_or(json))
+}
+
+/// Renders rows as a table, with columns padded to their widest cell
+pub fn render_table<R: TableRow>(rows: &[R]) -> String {
+    let rows: Vec<Vec<String>> =
+        std::iter::once(R::HEADERS.iter().map(|h| h.to_string()).collect())
+            .chain(rows.iter().map(TableRow::cells))
+            .collect();
+    let mut widths = vec![0; R::HEADERS.len()];
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = std::cmp::max(*width, cell.chars().count());
+        }
+    }
+    rows.iter()
+        .map(|row| {
+            row.iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+                .join("  ")
+                .trim_end()
+                .to_string()
+        })
+        .join("\n")
+}
+
+/// For transaction payload and options, either get gas profile, write it unsigned or submit for
+/// execution.
 pub async fn profile_or_submit(
     payload: TransactionPayload,
     txn_options_ref: &TransactionOptions,
@@ -484,6 +529,11 @@ pub async fn profile_or_submit(
     // Profile gas if needed.
     if txn_options_ref.profile_gas {
         txn_options_ref.profile_gas(payload).await
+    } else if let Some(output_file) = &txn_options_ref.unsigned_output_file {
+        // Or leave signing and submitting it to `aptos transaction sign / submit`.
+        txn_options_ref
+            .write_unsigned_transaction(payload, output_file)
+            .await
     } else {
         // Otherwise submit the transaction.
         txn_options_ref
@@ -493,6 +543,56 @@ pub async fn profile_or_submit(
     }
 }
 
+/// Writes the HTML gas report of a transaction to `<dir>/<file_name>.html`, along with the summary
+/// it is rendered from to `<dir>/<file_name>.json`. If the summary saved by an earlier run is
+/// given as a baseline, the differences to it are written to `<dir>/<file_name>.diff.html`.
+pub fn write_gas_reports(
+    gas_log: &TransactionGasLog,
+    dir: &Path,
+    file_name: &str,
+    title: &str,
+    baseline: Option<&Path>,
+) -> CliTypedResult<()> {
+    create_dir_if_not_exist(dir)?;
+    let summary = gas_log.summarize();
+
+    let report_path = dir.join(format!("{}.html", file_name));
+    write_to_file(
+        &report_path,
+        &report_path.display().to_string(),
+        summary.to_html_report(title)?.as_bytes(),
+    )?;
+    println!("Gas report saved to {}", report_path.display());
+
+    let summary_path = dir.join(format!("{}.json", file_name));
+    let summary_json = serde_json::to_string_pretty(&summary).map_err(|err| {
+        CliError::UnexpectedError(format!("Failed to serialize gas profile: {}", err))
+    })?;
+    write_to_file(
+        &summary_path,
+        &summary_path.display().to_string(),
+        summary_json.as_bytes(),
+    )?;
+    println!("Gas profile saved to {}", summary_path.display());
+
+    if let Some(baseline) = baseline {
+        let baseline_summary: GasProfileSummary = parse_json_file(baseline)?;
+        let diff = summary.to_html_diff_report(
+            &baseline_summary,
+            &format!("{} -- compared to {}", title, baseline.display()),
+        )?;
+        let diff_path = dir.join(format!("{}.diff.html", file_name));
+        write_to_file(
+            &diff_path,
+            &diff_path.display().to_string(),
+            diff.as_bytes(),
+        )?;
+        println!("Gas report diff saved to {}", diff_path.display());
+    }
+
+    Ok(())
+}
+
 /// Try parsing JSON in file at path into a specified type.
 pub fn parse_json_file<T: for<'a> Deserialize<'a>>(path_ref: &Path) -> CliTypedResult<T> {
     serde_json::from_slice::<T>(&read_from_file(path_ref)?).map_err(|err| {
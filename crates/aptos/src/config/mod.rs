@@ -3,9 +3,13 @@
 
 use crate::{
     common::{
+        encrypted_key::{
+            read_new_passphrase, read_passphrase, EncryptedPrivateKey, NEW_PASSPHRASE_ENV_VAR,
+            PASSPHRASE_ENV_VAR,
+        },
         types::{
             CliCommand, CliConfig, CliError, CliResult, CliTypedResult, ConfigSearchMode,
-            ProfileSummary, CONFIG_FOLDER,
+            ProfileConfig, ProfileSummary, CONFIG_FOLDER, DEFAULT_PROFILE,
         },
         utils::{create_dir_if_not_exist, current_dir, read_from_file, write_to_user_only_file},
     },
@@ -25,6 +29,9 @@ use std::{collections::BTreeMap, fmt::Formatter, path::PathBuf, str::FromStr};
 /// default configuration, and user specific settings.
 #[derive(Parser)]
 pub enum ConfigTool {
+    ChangeProfilePassphrase(ChangeProfilePassphrase),
+    DecryptProfile(DecryptProfile),
+    EncryptProfile(EncryptProfile),
     GenerateShellCompletions(GenerateShellCompletions),
     SetGlobalConfig(SetGlobalConfig),
     ShowGlobalConfig(ShowGlobalConfig),
@@ -34,6 +41,9 @@ pub enum ConfigTool {
 impl ConfigTool {
     pub async fn execute(self) -> CliResult {
         match self {
+            ConfigTool::ChangeProfilePassphrase(tool) => tool.execute_serialized().await,
+            ConfigTool::DecryptProfile(tool) => tool.execute_serialized().await,
+            ConfigTool::EncryptProfile(tool) => tool.execute_serialized().await,
             ConfigTool::GenerateShellCompletions(tool) => tool.execute_serialized_success().await,
             ConfigTool::SetGlobalConfig(tool) => tool.execute_serialized().await,
             ConfigTool::ShowGlobalConfig(tool) => tool.execute_serialized().await,
@@ -150,6 +160,136 @@ impl CliCommand<BTreeMap<String, ProfileSummary>> for ShowProfiles {
     }
 }
 
+/// Encrypt the private key of a profile with a passphrase
+///
+/// The key is encrypted with AES-256-GCM, under a key derived from the passphrase with Argon2id.
+/// Commands using the profile then prompt for the passphrase, or read it from the
+/// `APTOS_PROFILE_PASSPHRASE` environment variable, which is also where the new passphrase is
+/// read from if set.
+#[derive(Parser, Debug)]
+pub struct EncryptProfile {
+    /// Profile to encrypt, defaults to `default`
+    #[clap(long)]
+    profile: Option<String>,
+}
+
+#[async_trait]
+impl CliCommand<ProfileSummary> for EncryptProfile {
+    fn command_name(&self) -> &'static str {
+        "EncryptProfile"
+    }
+
+    async fn execute(self) -> CliTypedResult<ProfileSummary> {
+        let profile_name = self.profile.as_deref().unwrap_or(DEFAULT_PROFILE);
+        update_profile(profile_name, |profile| {
+            let private_key = match (&profile.private_key, &profile.encrypted_private_key) {
+                (Some(private_key), _) => private_key,
+                (None, Some(_)) => {
+                    return Err(CliError::CommandArgumentError(format!(
+                        "Profile {} is already encrypted, use `aptos config change-profile-passphrase` to change its passphrase",
+                        profile_name
+                    )))
+                },
+                (None, None) => {
+                    return Err(CliError::CommandArgumentError(format!(
+                        "Profile {} has no private key",
+                        profile_name
+                    )))
+                },
+            };
+            let passphrase = read_new_passphrase(profile_name, PASSPHRASE_ENV_VAR)?;
+            profile.encrypted_private_key =
+                Some(EncryptedPrivateKey::encrypt(private_key, &passphrase)?);
+            profile.private_key = None;
+            Ok(())
+        })
+    }
+}
+
+/// Decrypt the private key of a profile, storing it in plaintext again
+#[derive(Parser, Debug)]
+pub struct DecryptProfile {
+    /// Profile to decrypt, defaults to `default`
+    #[clap(long)]
+    profile: Option<String>,
+}
+
+#[async_trait]
+impl CliCommand<ProfileSummary> for DecryptProfile {
+    fn command_name(&self) -> &'static str {
+        "DecryptProfile"
+    }
+
+    async fn execute(self) -> CliTypedResult<ProfileSummary> {
+        let profile_name = self.profile.as_deref().unwrap_or(DEFAULT_PROFILE);
+        update_profile(profile_name, |profile| {
+            let encrypted_private_key = encrypted_private_key(profile, profile_name)?;
+            let private_key = encrypted_private_key.decrypt(&read_passphrase(profile_name)?)?;
+            profile.private_key = Some(private_key);
+            profile.encrypted_private_key = None;
+            Ok(())
+        })
+    }
+}
+
+/// Change the passphrase of an encrypted profile
+///
+/// The current passphrase can be given with `APTOS_PROFILE_PASSPHRASE`, and the new one with
+/// `APTOS_PROFILE_NEW_PASSPHRASE`.
+#[derive(Parser, Debug)]
+pub struct ChangeProfilePassphrase {
+    /// Profile to change the passphrase of, defaults to `default`
+    #[clap(long)]
+    profile: Option<String>,
+}
+
+#[async_trait]
+impl CliCommand<ProfileSummary> for ChangeProfilePassphrase {
+    fn command_name(&self) -> &'static str {
+        "ChangeProfilePassphrase"
+    }
+
+    async fn execute(self) -> CliTypedResult<ProfileSummary> {
+        let profile_name = self.profile.as_deref().unwrap_or(DEFAULT_PROFILE);
+        update_profile(profile_name, |profile| {
+            let encrypted_private_key = encrypted_private_key(profile, profile_name)?;
+            let private_key = encrypted_private_key.decrypt(&read_passphrase(profile_name)?)?;
+            let passphrase = read_new_passphrase(profile_name, NEW_PASSPHRASE_ENV_VAR)?;
+            profile.encrypted_private_key =
+                Some(EncryptedPrivateKey::encrypt(&private_key, &passphrase)?);
+            Ok(())
+        })
+    }
+}
+
+fn encrypted_private_key<'a>(
+    profile: &'a ProfileConfig,
+    profile_name: &str,
+) -> CliTypedResult<&'a EncryptedPrivateKey> {
+    profile.encrypted_private_key.as_ref().ok_or_else(|| {
+        CliError::CommandArgumentError(format!("Profile {} is not encrypted", profile_name))
+    })
+}
+
+/// Applies a change to a profile of the current directory's config, and saves it
+fn update_profile(
+    profile_name: &str,
+    update: impl FnOnce(&mut ProfileConfig) -> CliTypedResult<()>,
+) -> CliTypedResult<ProfileSummary> {
+    let mut config = CliConfig::load(ConfigSearchMode::CurrentDir)?;
+    let profile = config
+        .profiles
+        .as_mut()
+        .and_then(|profiles| profiles.get_mut(profile_name))
+        .ok_or_else(|| {
+            CliError::CommandArgumentError(format!("Profile {} not found", profile_name))
+        })?;
+    update(profile)?;
+    let summary = ProfileSummary::from(&*profile);
+    config.save()?;
+    Ok(summary)
+}
+
 /// Shows the properties in the global config
 #[derive(Parser, Debug)]
 pub struct ShowGlobalConfig {}
@@ -0,0 +1,333 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    common::{
+        types::{
+            load_account_arg, CliCommand, CliError, CliTypedResult, EntryFunctionArguments,
+            EntryFunctionArgumentsJSON, MovePackageDir,
+        },
+        utils::{parse_json_file, write_to_file},
+    },
+    move_tool::IncludedArtifacts,
+};
+use aptos_cached_packages::aptos_stdlib;
+use aptos_framework::BuiltPackage;
+use aptos_gas_profiling::{GasProfileSummary, TransactionGasLog};
+use aptos_language_e2e_tests::{account::Account, executor::FakeExecutor};
+use aptos_types::{
+    account_address::AccountAddress,
+    transaction::{EntryFunction, ExecutionStatus, TransactionPayload, TransactionStatus},
+};
+use async_trait::async_trait;
+use clap::Parser;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{btree_map, BTreeMap},
+    path::PathBuf,
+};
+
+const GAS_UNIT_PRICE: u64 = 100;
+const MAX_GAS_AMOUNT: u64 = 2_000_000;
+
+/// Benchmarks the gas usage of entry functions of a Move package
+///
+/// The package is published in a local environment, started from the genesis of the framework
+/// and gas schedule built into the CLI, and the entry functions of the benchmark file are run
+/// in order with the gas profiler. Calls see the state left by earlier ones, so a benchmark can
+/// depend on setup calls listed before it. The file lists benchmarks as:
+///
+/// `{"benchmarks": [{"name": "mint", "function_id": "0xcafe::token::mint", "type_args": [],
+/// "args": [{"type": "u64", "value": 100}]}]}`
+///
+/// where `sender` can be set to an address or profile, and defaults to the address of the
+/// package. The execution and IO gas, and the storage fee, of every call are compared against
+/// a baseline saved by `--save-baseline`, failing if any of them regressed beyond a threshold.
+#[derive(Parser)]
+pub struct BenchPackage {
+    /// JSON file listing the entry functions to benchmark, each in the format of
+    /// `aptos move run --json-file` along with a `name`
+    #[clap(long, value_parser)]
+    pub(crate) benchmarks: PathBuf,
+
+    /// Results saved by an earlier run with `--save-baseline`, to compare the gas usage against
+    #[clap(long, value_parser)]
+    pub(crate) baseline: Option<PathBuf>,
+
+    /// Save the results to this file, to be used as a baseline by later runs
+    ///
+    /// When compared against a baseline, the results are only saved if there is no regression.
+    #[clap(long, value_parser)]
+    pub(crate) save_baseline: Option<PathBuf>,
+
+    /// Increase of the execution gas, IO gas or storage fee over the baseline, in percent,
+    /// beyond which a benchmark regressed
+    #[clap(long, default_value_t = 5.0)]
+    pub(crate) threshold: f64,
+
+    #[clap(flatten)]
+    pub(crate) move_options: MovePackageDir,
+}
+
+/// JSON file format for the benchmarks
+#[derive(Deserialize)]
+struct BenchmarksJSON {
+    benchmarks: Vec<BenchmarkJSON>,
+}
+
+#[derive(Deserialize)]
+struct BenchmarkJSON {
+    name: String,
+    sender: Option<String>,
+    #[serde(flatten)]
+    function: EntryFunctionArgumentsJSON,
+}
+
+/// Gas usage of a benchmarked entry function call
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct BenchmarkResult {
+    pub name: String,
+    pub function: String,
+    /// Total gas charged, in gas units
+    pub gas_used: u64,
+    /// Execution gas, including the intrinsic cost of the transaction, in gas units
+    pub execution_gas: u64,
+    /// IO gas of the storage reads and writes, in gas units
+    pub io_gas: u64,
+    /// Storage fee, in octas
+    pub storage_fee: u64,
+}
+
+impl BenchmarkResult {
+    fn new(name: String, gas_used: u64, summary: &GasProfileSummary) -> Self {
+        let io: u64 = summary
+            .storage_reads
+            .iter()
+            .chain(summary.storage_writes.iter())
+            .map(|item| item.cost)
+            .sum();
+        let scaling_factor = summary.gas_scaling_factor.max(1);
+        Self {
+            name,
+            function: summary.entry_point.clone(),
+            gas_used,
+            execution_gas: summary.execution_and_io.saturating_sub(io) / scaling_factor,
+            io_gas: io / scaling_factor,
+            storage_fee: summary.storage_fee,
+        }
+    }
+}
+
+#[async_trait]
+impl CliCommand<Vec<BenchmarkResult>> for BenchPackage {
+    fn command_name(&self) -> &'static str {
+        "BenchPackage"
+    }
+
+    async fn execute(self) -> CliTypedResult<Vec<BenchmarkResult>> {
+        if self.threshold < 0.0 {
+            return Err(CliError::CommandArgumentError(
+                "Threshold must not be negative".to_string(),
+            ));
+        }
+        let benchmarks: BenchmarksJSON = parse_json_file(&self.benchmarks)?;
+        let baseline: Option<Vec<BenchmarkResult>> = self
+            .baseline
+            .as_ref()
+            .map(|path| parse_json_file(path))
+            .transpose()?;
+
+        let build_options = IncludedArtifacts::None.build_options(
+            self.move_options.dev,
+            self.move_options.skip_fetch_latest_git_deps,
+            self.move_options.named_addresses(),
+            self.move_options.bytecode_version,
+            self.move_options.skip_attribute_checks,
+        );
+        let package = BuiltPackage::build(self.move_options.get_package_path()?, build_options)
+            .map_err(|e| CliError::MoveCompilationError(format!("{:#}", e)))?;
+        let package_address = *package
+            .modules()
+            .next()
+            .ok_or_else(|| {
+                CliError::CommandArgumentError("Package has no modules to benchmark".to_string())
+            })?
+            .self_id()
+            .address();
+
+        let mut executor = LocalExecutor::new();
+        let metadata = package.extract_metadata()?;
+        let publish = aptos_stdlib::code_publish_package_txn(
+            bcs::to_bytes(&metadata).map_err(|err| CliError::BCS("PackageMetadata", err))?,
+            package.extract_code(),
+        );
+        executor
+            .run(package_address, publish)
+            .map_err(|err| prefix_error("Failed to publish the package", err))?;
+
+        let mut results = vec![];
+        for benchmark in benchmarks.benchmarks {
+            let sender = match &benchmark.sender {
+                Some(sender) => load_account_arg(sender)?,
+                None => package_address,
+            };
+            let function: EntryFunctionArguments = benchmark.function.try_into()?;
+            let function: EntryFunction = function.try_into()?;
+            let (gas_used, gas_log) = executor
+                .run(sender, TransactionPayload::EntryFunction(function))
+                .map_err(|err| prefix_error(&format!("Benchmark `{}`", benchmark.name), err))?;
+            results.push(BenchmarkResult::new(
+                benchmark.name,
+                gas_used,
+                &gas_log.summarize(),
+            ));
+        }
+
+        if let Some(baseline) = baseline {
+            let regressions = find_regressions(&results, &baseline, self.threshold);
+            if !regressions.is_empty() {
+                return Err(CliError::GasRegressionError(format!(
+                    "beyond {}% of the baseline:\n  - {}",
+                    self.threshold,
+                    regressions.join("\n  - ")
+                )));
+            }
+        }
+        if let Some(path) = &self.save_baseline {
+            let json = serde_json::to_string_pretty(&results).map_err(|err| {
+                CliError::UnexpectedError(format!("Failed to serialize results: {}", err))
+            })?;
+            write_to_file(path, &path.display().to_string(), json.as_bytes())?;
+        }
+        Ok(results)
+    }
+}
+
+/// In-memory chain the benchmarks are run on
+struct LocalExecutor {
+    executor: FakeExecutor,
+    accounts: BTreeMap<AccountAddress, Account>,
+}
+
+impl LocalExecutor {
+    fn new() -> Self {
+        Self {
+            executor: FakeExecutor::from_head_genesis(),
+            accounts: BTreeMap::new(),
+        }
+    }
+
+    /// Runs a transaction with the gas profiler and applies its changes, returning the gas used
+    fn run(
+        &mut self,
+        sender: AccountAddress,
+        payload: TransactionPayload,
+    ) -> CliTypedResult<(u64, TransactionGasLog)> {
+        // Accounts are funded on first use
+        let account = match self.accounts.entry(sender) {
+            btree_map::Entry::Occupied(entry) => entry.into_mut(),
+            btree_map::Entry::Vacant(entry) => entry.insert(self.executor.new_account_at(sender)),
+        };
+        let sequence_number = self
+            .executor
+            .read_account_resource_at_address(&sender)
+            .map(|resource| resource.sequence_number())
+            .unwrap_or_default();
+        let txn = account
+            .transaction()
+            .sequence_number(sequence_number)
+            .max_gas_amount(MAX_GAS_AMOUNT)
+            .gas_unit_price(GAS_UNIT_PRICE)
+            .payload(payload)
+            .sign();
+
+        let (output, gas_log) = self
+            .executor
+            .execute_transaction_with_gas_profiler(txn)
+            .map_err(|err| CliError::SimulationError(format!("{:#}", err)))?;
+        match output.status() {
+            TransactionStatus::Keep(ExecutionStatus::Success) => {
+                self.executor.apply_write_set(output.write_set());
+                Ok((output.gas_used(), gas_log))
+            },
+            status => Err(CliError::SimulationError(format!("{:?}", status))),
+        }
+    }
+}
+
+fn prefix_error(prefix: &str, err: CliError) -> CliError {
+    match err {
+        CliError::SimulationError(status) => {
+            CliError::SimulationError(format!("{}: {}", prefix, status))
+        },
+        err => err,
+    }
+}
+
+/// Lists the gas components of the results which increased beyond the threshold, in percent,
+/// over the baseline result of the same name
+fn find_regressions(
+    results: &[BenchmarkResult],
+    baseline: &[BenchmarkResult],
+    threshold: f64,
+) -> Vec<String> {
+    let mut regressions = vec![];
+    for result in results {
+        let Some(base) = baseline.iter().find(|base| base.name == result.name) else {
+            continue;
+        };
+        for (component, new, old) in [
+            ("execution gas", result.execution_gas, base.execution_gas),
+            ("IO gas", result.io_gas, base.io_gas),
+            ("storage fee", result.storage_fee, base.storage_fee),
+        ] {
+            if new as f64 > old as f64 * (1.0 + threshold / 100.0) {
+                regressions.push(format!(
+                    "`{}`: {} increased from {} to {}",
+                    result.name, component, old, new
+                ));
+            }
+        }
+    }
+    regressions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_regressions, BenchmarkResult};
+
+    fn result(name: &str, execution_gas: u64, io_gas: u64, storage_fee: u64) -> BenchmarkResult {
+        BenchmarkResult {
+            name: name.to_string(),
+            function: "0xcafe::token::mint".to_string(),
+            gas_used: execution_gas + io_gas + storage_fee / 100,
+            execution_gas,
+            io_gas,
+            storage_fee,
+        }
+    }
+
+    #[test]
+    fn test_find_regressions() {
+        let baseline = vec![result("mint", 100, 20, 5000), result("burn", 50, 10, 0)];
+        let results = vec![
+            // Within the threshold
+            result("mint", 104, 20, 5000),
+            // New storage is a regression, whatever the threshold
+            result("burn", 40, 30, 100),
+            // Not in the baseline
+            result("transfer", 1000, 1000, 1000),
+        ];
+
+        assert_eq!(find_regressions(&results, &baseline, 5.0), vec![
+            "`burn`: IO gas increased from 10 to 30".to_string(),
+            "`burn`: storage fee increased from 0 to 100".to_string(),
+        ]);
+        assert_eq!(find_regressions(&results, &baseline, 500.0), vec![
+            "`burn`: storage fee increased from 0 to 100".to_string(),
+        ]);
+        assert!(find_regressions(&results[..1], &baseline, 0.0)
+            .iter()
+            .all(|regression| regression.contains("execution gas")));
+    }
+}
@@ -0,0 +1,207 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    common::{
+        types::{
+            CliCommand, CliError, CliTypedResult, MovePackageDir, ProfileOptions, RestOptions,
+        },
+        utils::get_feature_flag,
+    },
+    move_tool::IncludedArtifacts,
+};
+use aptos_framework::{
+    natives::code::{PackageMetadata, PackageRegistry, UpgradePolicy},
+    BuiltPackage,
+};
+use aptos_rest_client::{
+    aptos_api_types::AptosErrorCode,
+    error::{AptosErrorResponse, RestError},
+};
+use aptos_types::{account_address::AccountAddress, on_chain_config::FeatureFlag};
+use async_trait::async_trait;
+use clap::Parser;
+use move_binary_format::{compatibility::Compatibility, normalized, CompiledModule};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Check that a package can be published as an upgrade of its version on chain
+///
+/// The package is compiled and compared against the package with the same name at the given
+/// address, with the rules applied when publishing it: the upgrade policy can't be weakened, no
+/// module can be removed or clash with a module of another package, and every module must be
+/// compatible with its previous version, i.e. keep its struct layouts, public function
+/// signatures and friends. Every violation is reported, instead of the single
+/// `BACKWARD_INCOMPATIBLE_MODULE_UPDATE` returned by a failed publication.
+#[derive(Parser)]
+pub struct CheckUpgrade {
+    /// Address of the account the package is published at
+    ///
+    /// Defaults to the account of the profile
+    #[clap(long, value_parser = crate::common::types::load_account_arg)]
+    pub(crate) account: Option<AccountAddress>,
+
+    #[clap(flatten)]
+    pub(crate) move_options: MovePackageDir,
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UpgradeCheckSummary {
+    pub package: String,
+    pub account: AccountAddress,
+    /// Upgrade number of the version on chain, if the package is already published
+    pub upgrade_number: Option<u64>,
+    pub new_modules: Vec<String>,
+}
+
+#[async_trait]
+impl CliCommand<UpgradeCheckSummary> for CheckUpgrade {
+    fn command_name(&self) -> &'static str {
+        "CheckUpgrade"
+    }
+
+    async fn execute(self) -> CliTypedResult<UpgradeCheckSummary> {
+        let account = match self.account {
+            Some(account) => account,
+            None => self.profile_options.account_address()?,
+        };
+        let build_options = IncludedArtifacts::None.build_options(
+            self.move_options.dev,
+            self.move_options.skip_fetch_latest_git_deps,
+            self.move_options.named_addresses(),
+            self.move_options.bytecode_version,
+            self.move_options.skip_attribute_checks,
+        );
+        let package = BuiltPackage::build(self.move_options.get_package_path()?, build_options)
+            .map_err(|e| CliError::MoveCompilationError(format!("{:#}", e)))?;
+        let metadata = package.extract_metadata()?;
+
+        let client = self.rest_options.client(&self.profile_options)?;
+        let registry = match client
+            .get_account_resource_bcs::<PackageRegistry>(account, "0x1::code::PackageRegistry")
+            .await
+        {
+            Ok(response) => response.into_inner(),
+            Err(RestError::Api(AptosErrorResponse { error, .. }))
+                if matches!(
+                    error.error_code,
+                    AptosErrorCode::ResourceNotFound | AptosErrorCode::AccountNotFound
+                ) =>
+            {
+                PackageRegistry { packages: vec![] }
+            },
+            Err(err) => return Err(err.into()),
+        };
+        let old_metadata = registry
+            .packages
+            .iter()
+            .find(|old_metadata| old_metadata.name == metadata.name);
+
+        let mut violations = package_violations(&registry, old_metadata, &metadata);
+        if old_metadata.is_some() {
+            let old_modules = client
+                .get_account_modules_bcs(account)
+                .await?
+                .into_inner()
+                .into_iter()
+                .map(|(id, bytes)| (id.name.to_string(), bytes))
+                .collect::<BTreeMap<_, _>>();
+            // The VM checks friend functions unless they are treated as private functions
+            let compatibility = Compatibility::new(
+                true,
+                true,
+                !get_feature_flag(&client, FeatureFlag::TREAT_FRIEND_AS_PRIVATE).await?,
+            );
+            for module in package.modules() {
+                let name = module.self_id().name().to_string();
+                if let Some(old_bytes) = old_modules.get(&name) {
+                    let old_module = CompiledModule::deserialize(old_bytes).map_err(|err| {
+                        CliError::UnexpectedError(format!(
+                            "Failed to deserialize module {} on chain: {}",
+                            name, err
+                        ))
+                    })?;
+                    violations.extend(
+                        compatibility
+                            .incompatible_changes(
+                                &normalized::Module::new(&old_module),
+                                &normalized::Module::new(module),
+                            )
+                            .into_iter()
+                            .map(|change| format!("module `{}`: {}", name, change)),
+                    );
+                }
+            }
+        }
+
+        if !violations.is_empty() {
+            return Err(CliError::CommandArgumentError(format!(
+                "Package `{}` can't be published as an upgrade at {}:\n  - {}",
+                metadata.name,
+                account.to_hex_literal(),
+                violations.join("\n  - ")
+            )));
+        }
+
+        let old_module_names: Vec<_> = old_metadata
+            .map(|old_metadata| old_metadata.modules.iter().map(|m| &m.name).collect())
+            .unwrap_or_default();
+        Ok(UpgradeCheckSummary {
+            package: metadata.name.clone(),
+            account,
+            upgrade_number: old_metadata.map(|old_metadata| old_metadata.upgrade_number),
+            new_modules: metadata
+                .modules
+                .iter()
+                .filter(|module| !old_module_names.contains(&&module.name))
+                .map(|module| module.name.clone())
+                .collect(),
+        })
+    }
+}
+
+/// Checks the package level rules of `0x1::code`, i.e. the upgrade policy and module names
+fn package_violations(
+    registry: &PackageRegistry,
+    old_metadata: Option<&PackageMetadata>,
+    metadata: &PackageMetadata,
+) -> Vec<String> {
+    let mut violations = vec![];
+
+    // Modules can't clash with the modules of other packages at the same address
+    for other_package in &registry.packages {
+        if other_package.name == metadata.name {
+            continue;
+        }
+        for module in &metadata.modules {
+            if other_package.modules.iter().any(|m| m.name == module.name) {
+                violations.push(format!(
+                    "module `{}` is already published by package `{}`",
+                    module.name, other_package.name
+                ));
+            }
+        }
+    }
+
+    if let Some(old_metadata) = old_metadata {
+        if old_metadata.upgrade_policy.policy >= UpgradePolicy::immutable().policy {
+            violations.push("the package is immutable".to_string());
+        }
+        if metadata.upgrade_policy.policy < old_metadata.upgrade_policy.policy {
+            violations.push(format!(
+                "upgrade policy is weakened from `{}` to `{}`",
+                old_metadata.upgrade_policy, metadata.upgrade_policy
+            ));
+        }
+        for old_module in &old_metadata.modules {
+            if !metadata.modules.iter().any(|m| m.name == old_module.name) {
+                violations.push(format!("module `{}` was removed", old_module.name));
+            }
+        }
+    }
+    violations
+}
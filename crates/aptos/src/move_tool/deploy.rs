@@ -0,0 +1,771 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    account::derive_resource_account::{ResourceAccountSeed, SeedEncoding},
+    common::{
+        types::{
+            CliCommand, CliError, CliTypedResult, ProfileOptions, TransactionOptions,
+            TransactionSummary,
+        },
+        utils::{read_from_file, write_to_file},
+    },
+    move_tool::{IncludedArtifactsArgs, MAX_PUBLISH_PACKAGE_SIZE},
+};
+use aptos_cached_packages::aptos_stdlib;
+use aptos_framework::{
+    natives::code::{PackageMetadata, PackageRegistry},
+    BuiltPackage,
+};
+use aptos_rest_client::{
+    aptos_api_types::{AptosErrorCode, HashValue},
+    error::{AptosErrorResponse, RestError},
+};
+use aptos_types::{
+    account_address::{create_resource_address, AccountAddress},
+    transaction::TransactionPayload,
+};
+use async_trait::async_trait;
+use clap::Parser;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+/// Publishes the Move packages of a deployment manifest, in the order of their dependencies
+///
+/// The manifest is a YAML file listing the packages of a project:
+///
+/// ```yaml
+/// packages:
+///   - name: core
+///     path: core
+///     address_name: core
+///     resource_account:
+///       seed: core
+///       seed_encoding: utf8
+///   - name: app
+///     path: app
+///     address_name: app
+///     profile: app-deployer
+///     depends_on: [core]
+/// named_addresses:
+///   admin: "0xcafe"
+/// ```
+///
+/// Each package is published by the account of its profile, defaulting to `--profile`, or
+/// under a resource account of it. The named address `address_name` of every package is bound
+/// to the address it's published at when building all packages, so dependencies are wired
+/// without editing their `Move.toml`. Packages whose sources match the version on chain are
+/// skipped. Published packages are recorded in a state file, so a failed deployment can be
+/// resumed by running the command again.
+///
+/// Packages with a `profile` are signed with the key of their profile, so they can't be combined
+/// with `--private-key` or `--sender-account`. Publish transactions can't be written unsigned,
+/// profiled, or signed by secondary signers or a fee payer.
+#[derive(Parser)]
+pub struct DeployPackages {
+    /// Deployment manifest (.yaml)
+    #[clap(value_parser)]
+    pub(crate) manifest: PathBuf,
+
+    /// File recording the packages published by the deployment
+    ///
+    /// Defaults to the manifest file with a `.state.yaml` extension.
+    #[clap(long, value_parser)]
+    pub(crate) state_file: Option<PathBuf>,
+
+    /// Whether to override the check for maximal size of published data
+    #[clap(long)]
+    pub(crate) override_size_check: bool,
+
+    /// Skip pulling the latest git dependencies
+    ///
+    /// If you don't have a network connection, the compiler may fail due
+    /// to no ability to pull git dependencies.  This will allow overriding
+    /// this for local development.
+    #[clap(long)]
+    pub(crate) skip_fetch_latest_git_deps: bool,
+
+    #[clap(flatten)]
+    pub(crate) included_artifacts_args: IncludedArtifactsArgs,
+    #[clap(flatten)]
+    pub(crate) txn_options: TransactionOptions,
+}
+
+/// Deployment manifest
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DeployManifest {
+    pub packages: Vec<ManifestPackage>,
+    /// Named addresses used to build every package
+    #[serde(default)]
+    pub named_addresses: BTreeMap<String, AccountAddress>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ManifestPackage {
+    /// Name of the package in the manifest, which `depends_on` refers to
+    pub name: String,
+    /// Directory of the package, relative to the manifest
+    pub path: PathBuf,
+    /// Named address bound to the address the package is published at
+    pub address_name: Option<String>,
+    /// Profile of the account publishing the package, defaults to `--profile`
+    pub profile: Option<String>,
+    /// Publish the package under a resource account of the profile account
+    pub resource_account: Option<ManifestResourceAccount>,
+    /// Packages of the manifest to publish before this one
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ManifestResourceAccount {
+    pub seed: String,
+    /// One of `bcs`, `utf8` or `hex`, as for `--seed-encoding`
+    #[serde(default)]
+    pub seed_encoding: Option<String>,
+}
+
+/// Packages published by a deployment, by name in the manifest
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct DeployState {
+    pub packages: BTreeMap<String, DeployedPackage>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct DeployedPackage {
+    pub address: AccountAddress,
+    pub source_digest: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction: Option<TransactionSummary>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeployResult {
+    pub name: String,
+    pub address: AccountAddress,
+    /// Whether the package was published, or skipped as unchanged
+    pub published: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction_hash: Option<HashValue>,
+}
+
+/// Account and address a package of the manifest is published at
+#[derive(Debug, PartialEq, Eq)]
+struct DeployTarget {
+    /// Profile of the publishing account, `None` for the default profile
+    profile: Option<String>,
+    address: AccountAddress,
+    /// Seed of the resource account the package is published under
+    seed: Option<Vec<u8>>,
+}
+
+#[async_trait]
+impl CliCommand<Vec<DeployResult>> for DeployPackages {
+    fn command_name(&self) -> &'static str {
+        "DeployPackages"
+    }
+
+    async fn execute(mut self) -> CliTypedResult<Vec<DeployResult>> {
+        let manifest: DeployManifest = serde_yaml::from_slice(&read_from_file(&self.manifest)?)
+            .map_err(|err| CliError::UnableToParse("deployment manifest", format!("{:#}", err)))?;
+        self.check_options(&manifest)?;
+        let order = deploy_order(&manifest.packages)?;
+        let manifest_dir = self
+            .manifest
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        let state_file = self
+            .state_file
+            .clone()
+            .unwrap_or_else(|| self.manifest.with_extension("state.yaml"));
+        let mut state = DeployState::load(&state_file)?;
+
+        let mut default_account = None;
+        let (named_addresses, targets) = resolve_targets(&manifest, |profile| match profile {
+            Some(profile) => ProfileOptions {
+                profile: Some(profile.to_string()),
+            }
+            .account_address(),
+            None => match default_account {
+                Some(account) => Ok(account),
+                None => {
+                    let account = self.default_account()?;
+                    default_account = Some(account);
+                    Ok(account)
+                },
+            },
+        })?;
+
+        // Build every package before publishing any, to catch compilation errors early
+        let mut built = vec![];
+        for &index in &order {
+            let package = &manifest.packages[index];
+            let options = self
+                .included_artifacts_args
+                .included_artifacts
+                .build_options(
+                    false,
+                    self.skip_fetch_latest_git_deps,
+                    named_addresses.clone(),
+                    None,
+                    false,
+                );
+            let built_package = BuiltPackage::build(manifest_dir.join(&package.path), options)
+                .map_err(|err| {
+                    CliError::MoveCompilationError(format!("package `{}`: {:#}", package.name, err))
+                })?;
+            built.push((index, built_package));
+        }
+
+        let default_profile = self.txn_options.profile_options.profile.clone();
+        let mut results = vec![];
+        for (index, built_package) in built {
+            let package = &manifest.packages[index];
+            let target = &targets[index];
+            let metadata = built_package.extract_metadata()?;
+            let mut result = DeployResult {
+                name: package.name.clone(),
+                address: target.address,
+                published: false,
+                transaction_hash: None,
+            };
+
+            // Resume from the state file, then compare with the package on chain
+            if state.is_deployed(&package.name, target.address, &metadata.source_digest) {
+                results.push(result);
+                continue;
+            }
+            self.txn_options.profile_options.profile =
+                target.profile.clone().or_else(|| default_profile.clone());
+            let on_chain = self
+                .package_on_chain(target.address, &metadata.name)
+                .await?;
+            let on_chain_digest = on_chain
+                .as_ref()
+                .map(|on_chain| on_chain.source_digest.as_str());
+            let mut transaction = None;
+            if needs_publish(
+                &package.name,
+                target,
+                on_chain_digest,
+                &metadata.source_digest,
+            )? {
+                let metadata_bytes = bcs::to_bytes(&metadata).expect("PackageMetadata has BCS");
+                let payload = match &target.seed {
+                    Some(seed) => {
+                        aptos_stdlib::resource_account_create_resource_account_and_publish_package(
+                            seed.clone(),
+                            metadata_bytes,
+                            built_package.extract_code(),
+                        )
+                    },
+                    None => aptos_stdlib::code_publish_package_txn(
+                        metadata_bytes,
+                        built_package.extract_code(),
+                    ),
+                };
+                let summary = self.publish(&package.name, payload).await?;
+                result.published = true;
+                result.transaction_hash = summary.transaction_hash;
+                transaction = Some(summary);
+            }
+            state
+                .packages
+                .insert(package.name.clone(), DeployedPackage {
+                    address: target.address,
+                    source_digest: metadata.source_digest.clone(),
+                    transaction,
+                });
+            state.save(&state_file)?;
+            results.push(result);
+        }
+        Ok(results)
+    }
+}
+
+impl DeployPackages {
+    /// Rejects the options which can't be honored when publishing the packages one transaction
+    /// at a time, each signed by the account of its profile
+    fn check_options(&self, manifest: &DeployManifest) -> CliTypedResult<()> {
+        let txn_options = &self.txn_options;
+        for (is_set, option) in [
+            (
+                txn_options.unsigned_output_file.is_some(),
+                "--unsigned-output-file",
+            ),
+            (txn_options.profile_gas, "--profile-gas"),
+            (
+                !txn_options.secondary_signer_profiles.is_empty(),
+                "--secondary-signer-profiles",
+            ),
+            (
+                txn_options.fee_payer_profile.is_some(),
+                "--fee-payer-profile",
+            ),
+        ] {
+            if is_set {
+                return Err(CliError::CommandArgumentError(format!(
+                    "`{}` is not supported when deploying packages",
+                    option
+                )));
+            }
+        }
+        if let Some(package) = manifest
+            .packages
+            .iter()
+            .find(|package| package.profile.is_some())
+        {
+            if txn_options.sender_account.is_some() || self.has_cli_private_key()? {
+                return Err(CliError::CommandArgumentError(format!(
+                    "Package `{}` is published by the account of a profile, so the packages \
+                    can't be published with `--private-key`, `--private-key-file` or \
+                    `--sender-account`",
+                    package.name
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn has_cli_private_key(&self) -> CliTypedResult<bool> {
+        Ok(self
+            .txn_options
+            .private_key_options
+            .extract_private_key_cli(self.txn_options.encoding_options.encoding)?
+            .is_some())
+    }
+
+    /// Address of the account publishing the packages without a profile in the manifest
+    fn default_account(&self) -> CliTypedResult<AccountAddress> {
+        if self.txn_options.sender_account.is_some() || self.has_cli_private_key()? {
+            self.txn_options.sender_address()
+        } else {
+            self.txn_options.profile_options.account_address()
+        }
+    }
+
+    /// Fetches the metadata of the package with the given name published at an address
+    async fn package_on_chain(
+        &self,
+        address: AccountAddress,
+        name: &str,
+    ) -> CliTypedResult<Option<PackageMetadata>> {
+        let client = self
+            .txn_options
+            .rest_options
+            .client(&self.txn_options.profile_options)?;
+        let registry = match client
+            .get_account_resource_bcs::<PackageRegistry>(address, "0x1::code::PackageRegistry")
+            .await
+        {
+            Ok(response) => response.into_inner(),
+            Err(RestError::Api(AptosErrorResponse { error, .. }))
+                if matches!(
+                    error.error_code,
+                    AptosErrorCode::ResourceNotFound | AptosErrorCode::AccountNotFound
+                ) =>
+            {
+                return Ok(None)
+            },
+            Err(err) => return Err(err.into()),
+        };
+        Ok(registry
+            .packages
+            .into_iter()
+            .find(|package| package.name == name))
+    }
+
+    async fn publish(
+        &self,
+        name: &str,
+        payload: TransactionPayload,
+    ) -> CliTypedResult<TransactionSummary> {
+        let size = bcs::serialized_size(&payload)?;
+        if !self.override_size_check && size > MAX_PUBLISH_PACKAGE_SIZE {
+            return Err(CliError::UnexpectedError(format!(
+                "Package `{}` is larger than {} bytes ({} bytes)! To lower the size \
+                you may want to include fewer artifacts via `--included-artifacts`. \
+                You can also override this check with `--override-size-check",
+                name, MAX_PUBLISH_PACKAGE_SIZE, size
+            )));
+        }
+        println!("Publishing package `{}` ({} bytes)", name, size);
+        let summary = TransactionSummary::from(self.txn_options.submit_transaction(payload).await?);
+        if summary.success == Some(false) {
+            return Err(CliError::UnexpectedError(format!(
+                "Publishing package `{}` failed: {}",
+                name,
+                summary.vm_status.unwrap_or_default()
+            )));
+        }
+        Ok(summary)
+    }
+}
+
+impl DeployState {
+    /// Reads the state file, if the deployment was started already
+    fn load(path: &Path) -> CliTypedResult<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        serde_yaml::from_slice(&read_from_file(path)?)
+            .map_err(|err| CliError::UnableToParse("deployment state", format!("{:#}", err)))
+    }
+
+    fn save(&self, path: &Path) -> CliTypedResult<()> {
+        write_to_file(
+            path,
+            &path.display().to_string(),
+            serde_yaml::to_string(self)?.as_bytes(),
+        )
+    }
+
+    /// Whether the package was recorded as deployed at the address with the same sources
+    fn is_deployed(&self, name: &str, address: AccountAddress, source_digest: &str) -> bool {
+        self.packages.get(name).map_or(false, |deployed| {
+            deployed.address == address && deployed.source_digest == source_digest
+        })
+    }
+}
+
+/// Resolves the account and address every package is published at, given the account of a
+/// profile of the manifest, or of the default profile for `None`. Addresses are known before
+/// publishing anything, so the named addresses returned build every package with the addresses
+/// of all others.
+fn resolve_targets(
+    manifest: &DeployManifest,
+    mut account_of: impl FnMut(Option<&str>) -> CliTypedResult<AccountAddress>,
+) -> CliTypedResult<(BTreeMap<String, AccountAddress>, Vec<DeployTarget>)> {
+    let mut named_addresses = manifest.named_addresses.clone();
+    let mut targets = vec![];
+    for package in &manifest.packages {
+        let account = account_of(package.profile.as_deref())?;
+        let seed = package
+            .resource_account
+            .as_ref()
+            .map(|resource_account| {
+                ResourceAccountSeed {
+                    seed: resource_account.seed.clone(),
+                    seed_encoding: match &resource_account.seed_encoding {
+                        Some(encoding) => SeedEncoding::from_str(encoding)?,
+                        None => SeedEncoding::default(),
+                    },
+                }
+                .seed()
+            })
+            .transpose()?;
+        let address = match &seed {
+            Some(seed) => create_resource_address(account, seed),
+            None => account,
+        };
+        if let Some(address_name) = &package.address_name {
+            named_addresses.insert(address_name.clone(), address);
+        }
+        targets.push(DeployTarget {
+            profile: package.profile.clone(),
+            address,
+            seed,
+        });
+    }
+    Ok((named_addresses, targets))
+}
+
+/// Whether the package has to be published, given the source digest of its version on chain
+fn needs_publish(
+    name: &str,
+    target: &DeployTarget,
+    on_chain_digest: Option<&str>,
+    source_digest: &str,
+) -> CliTypedResult<bool> {
+    match on_chain_digest {
+        None => Ok(true),
+        Some(on_chain_digest) if on_chain_digest == source_digest => Ok(false),
+        Some(_) if target.seed.is_some() => Err(CliError::CommandArgumentError(format!(
+            "Package `{}` is published under a resource account at {}, and can only be \
+            upgraded by its own modules",
+            name,
+            target.address.to_hex_literal()
+        ))),
+        Some(_) => Ok(true),
+    }
+}
+
+/// Orders the packages so that every package comes after the packages it depends on, keeping
+/// the order of the manifest otherwise
+fn deploy_order(packages: &[ManifestPackage]) -> CliTypedResult<Vec<usize>> {
+    let mut indices = BTreeMap::new();
+    for (index, package) in packages.iter().enumerate() {
+        if indices.insert(package.name.as_str(), index).is_some() {
+            return Err(CliError::CommandArgumentError(format!(
+                "Package `{}` is listed more than once in the manifest",
+                package.name
+            )));
+        }
+    }
+    let mut dependencies = vec![];
+    for package in packages {
+        let mut package_dependencies = BTreeSet::new();
+        for dependency in &package.depends_on {
+            let index = indices.get(dependency.as_str()).ok_or_else(|| {
+                CliError::CommandArgumentError(format!(
+                    "Package `{}` depends on `{}`, which is not in the manifest",
+                    package.name, dependency
+                ))
+            })?;
+            package_dependencies.insert(*index);
+        }
+        dependencies.push(package_dependencies);
+    }
+
+    let mut order = vec![];
+    let mut ordered = vec![false; packages.len()];
+    while order.len() < packages.len() {
+        let next = (0..packages.len()).find(|&index| {
+            !ordered[index]
+                && dependencies[index]
+                    .iter()
+                    .all(|&dependency| ordered[dependency])
+        });
+        match next {
+            Some(index) => {
+                ordered[index] = true;
+                order.push(index);
+            },
+            None => {
+                let cycle: Vec<_> = (0..packages.len())
+                    .filter(|&index| !ordered[index])
+                    .map(|index| packages[index].name.as_str())
+                    .collect();
+                return Err(CliError::CommandArgumentError(format!(
+                    "Packages depend on each other in a cycle: {}",
+                    cycle.join(", ")
+                )));
+            },
+        }
+    }
+    Ok(order)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        deploy_order, needs_publish, resolve_targets, DeployManifest, DeployPackages, DeployState,
+        DeployTarget, DeployedPackage,
+    };
+    use aptos_crypto::ValidCryptoMaterialStringExt;
+    use aptos_keygen::KeyGen;
+    use aptos_types::account_address::{create_resource_address, AccountAddress};
+    use clap::Parser;
+    use std::collections::BTreeMap;
+    use tempfile::TempDir;
+
+    fn manifest(yaml: &str) -> DeployManifest {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn test_deploy_order() {
+        let manifest = manifest(
+            r#"
+packages:
+  - name: app
+    path: app
+    depends_on: [core, utils]
+  - name: core
+    path: core
+    depends_on: [utils]
+    resource_account:
+      seed: core
+  - name: utils
+    path: utils
+  - name: standalone
+    path: standalone
+named_addresses:
+  admin: "0xcafe"
+"#,
+        );
+        assert_eq!(deploy_order(&manifest.packages).unwrap(), vec![2, 1, 0, 3]);
+
+        let cycle = manifest(
+            r#"
+packages:
+  - name: a
+    path: a
+    depends_on: [b]
+  - name: b
+    path: b
+    depends_on: [a]
+"#,
+        );
+        assert!(deploy_order(&cycle.packages).is_err());
+
+        let unknown = manifest(
+            r#"
+packages:
+  - name: a
+    path: a
+    depends_on: [c]
+"#,
+        );
+        assert!(deploy_order(&unknown.packages).is_err());
+    }
+
+    #[test]
+    fn test_check_options() {
+        let profile_package = manifest(
+            r#"
+packages:
+  - name: app
+    path: app
+    profile: app-deployer
+"#,
+        );
+        let default_package = manifest(
+            r#"
+packages:
+  - name: app
+    path: app
+"#,
+        );
+        let private_key = KeyGen::from_os_rng()
+            .generate_ed25519_private_key()
+            .to_encoded_string()
+            .unwrap();
+        let options = |args: &[&str]| {
+            DeployPackages::try_parse_from(["deploy", "deploy.yaml"].iter().chain(args)).unwrap()
+        };
+
+        assert!(options(&[]).check_options(&profile_package).is_ok());
+        for args in [
+            vec!["--unsigned-output-file", "publish.bcs"],
+            vec!["--profile-gas"],
+            vec!["--secondary-signer-profiles", "alice"],
+            vec!["--fee-payer-profile", "bob"],
+        ] {
+            assert!(options(&args).check_options(&default_package).is_err());
+        }
+
+        // A global key or sender can't sign for the profiles of the manifest
+        let with_private_key = options(&["--private-key", &private_key]);
+        assert!(with_private_key.check_options(&profile_package).is_err());
+        assert!(with_private_key.check_options(&default_package).is_ok());
+        let with_sender = options(&["--sender-account", "0xcafe"]);
+        assert!(with_sender.check_options(&profile_package).is_err());
+        assert!(with_sender.check_options(&default_package).is_ok());
+    }
+
+    #[test]
+    fn test_resolve_targets() {
+        let manifest = manifest(
+            r#"
+packages:
+  - name: core
+    path: core
+    address_name: core
+    resource_account:
+      seed: core
+      seed_encoding: utf8
+  - name: app
+    path: app
+    address_name: app
+    profile: app-deployer
+    depends_on: [core]
+  - name: scripts
+    path: scripts
+named_addresses:
+  admin: "0xcafe"
+  app: "0x1"
+"#,
+        );
+        let default_account = AccountAddress::from_hex_literal("0xa").unwrap();
+        let app_account = AccountAddress::from_hex_literal("0xb").unwrap();
+        let admin = AccountAddress::from_hex_literal("0xcafe").unwrap();
+
+        let (named_addresses, targets) = resolve_targets(&manifest, |profile| match profile {
+            None => Ok(default_account),
+            Some("app-deployer") => Ok(app_account),
+            Some(profile) => panic!("unexpected profile `{}`", profile),
+        })
+        .unwrap();
+
+        let core_address = create_resource_address(default_account, b"core");
+        assert_eq!(targets, vec![
+            DeployTarget {
+                profile: None,
+                address: core_address,
+                seed: Some(b"core".to_vec()),
+            },
+            DeployTarget {
+                profile: Some("app-deployer".to_string()),
+                address: app_account,
+                seed: None,
+            },
+            DeployTarget {
+                profile: None,
+                address: default_account,
+                seed: None,
+            },
+        ]);
+        // The addresses of the packages override the named addresses of the manifest
+        assert_eq!(
+            named_addresses,
+            BTreeMap::from([
+                ("admin".to_string(), admin),
+                ("app".to_string(), app_account),
+                ("core".to_string(), core_address),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_deploy_state_resume() {
+        let dir = TempDir::new().unwrap();
+        let state_file = dir.path().join("deploy.state.yaml");
+        let address = AccountAddress::from_hex_literal("0xcafe").unwrap();
+
+        let mut state = DeployState::load(&state_file).unwrap();
+        assert!(state.packages.is_empty());
+        state.packages.insert("core".to_string(), DeployedPackage {
+            address,
+            source_digest: "digest".to_string(),
+            transaction: None,
+        });
+        state.save(&state_file).unwrap();
+
+        // A package is skipped when resuming only if neither its address nor its sources changed
+        let state = DeployState::load(&state_file).unwrap();
+        assert!(state.is_deployed("core", address, "digest"));
+        assert!(!state.is_deployed("core", address, "changed"));
+        assert!(!state.is_deployed("core", AccountAddress::ONE, "digest"));
+        assert!(!state.is_deployed("app", address, "digest"));
+    }
+
+    #[test]
+    fn test_needs_publish() {
+        let account = DeployTarget {
+            profile: None,
+            address: AccountAddress::from_hex_literal("0xcafe").unwrap(),
+            seed: None,
+        };
+        let resource_account = DeployTarget {
+            profile: None,
+            address: account.address,
+            seed: Some(b"core".to_vec()),
+        };
+        for target in [&account, &resource_account] {
+            assert!(needs_publish("core", target, None, "digest").unwrap());
+            assert!(!needs_publish("core", target, Some("digest"), "digest").unwrap());
+        }
+        assert!(needs_publish("core", &account, Some("old"), "digest").unwrap());
+        // Packages under resource accounts can only be upgraded by their own modules
+        assert!(needs_publish("core", &resource_account, Some("old"), "digest").is_err());
+    }
+}
@@ -0,0 +1,491 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::{
+    types::{CliCommand, CliError, CliTypedResult, MovePackageDir},
+    utils::write_to_file,
+};
+use anyhow::{anyhow, bail};
+use async_trait::async_trait;
+use clap::Parser;
+use move_command_line_common::files::{find_move_filenames, FileHash};
+use move_compiler::{
+    diagnostics::{report_diagnostics_to_buffer, FilesSourceText},
+    parser::{
+        lexer::{Lexer, Tok},
+        parse_file_string,
+    },
+    shared::{CompilationEnv, Flags},
+};
+use move_package::source_package::layout::SourcePackageLayout;
+use move_symbol_pool::Symbol;
+use serde::Deserialize;
+use std::{collections::BTreeSet, path::Path};
+
+/// Section of `Move.toml` with the formatting options
+pub const FMT_MANIFEST_SECTION: &str = "fmt";
+/// File with formatting options, taking precedence over the `[fmt]` section of `Move.toml`
+const FMT_CONFIG_FILE: &str = ".movefmt.toml";
+
+/// Formats the Move sources of a package
+///
+/// Sources have to parse to be formatted. Indentation, blank lines and the spacing between
+/// tokens are normalized, while comments and the line breaks of the code are kept. Options are
+/// read from the `[fmt]` section of `Move.toml`, and from a `.movefmt.toml` file in the package
+/// directory, e.g. `indent_width = 2` or `max_blank_lines = 1`.
+#[derive(Parser)]
+pub struct FmtPackage {
+    /// Check that the sources are formatted, without changing them
+    ///
+    /// Fails listing the files which are not formatted, e.g. to check formatting in CI.
+    #[clap(long)]
+    pub(crate) check: bool,
+
+    #[clap(flatten)]
+    pub(crate) move_options: MovePackageDir,
+}
+
+#[async_trait]
+impl CliCommand<Vec<String>> for FmtPackage {
+    fn command_name(&self) -> &'static str {
+        "FmtPackage"
+    }
+
+    async fn execute(self) -> CliTypedResult<Vec<String>> {
+        let package_path = self.move_options.get_package_path()?;
+        let config = FmtConfig::load(&package_path)?;
+        let source_dirs: Vec<_> = [
+            SourcePackageLayout::Sources,
+            SourcePackageLayout::Scripts,
+            SourcePackageLayout::Tests,
+            SourcePackageLayout::Examples,
+            SourcePackageLayout::Specifications,
+        ]
+        .iter()
+        .map(|layout| package_path.join(layout.path()))
+        .filter(|dir| dir.is_dir())
+        .collect();
+        let files = find_move_filenames(&source_dirs, false).map_err(|err| {
+            CliError::UnexpectedError(format!("Failed to find Move sources: {:#}", err))
+        })?;
+
+        // Files which were not formatted
+        let mut unformatted = vec![];
+        for file in files {
+            let source =
+                std::fs::read_to_string(&file).map_err(|err| CliError::IO(file.clone(), err))?;
+            let formatted = format_move_source(&file, &source, &config)
+                .map_err(|err| CliError::MoveCompilationError(format!("{:#}", err)))?;
+            if formatted != source {
+                if !self.check {
+                    write_to_file(Path::new(&file), &file, formatted.as_bytes())?;
+                }
+                unformatted.push(file);
+            }
+        }
+
+        if self.check && !unformatted.is_empty() {
+            return Err(CliError::CommandArgumentError(format!(
+                "Move sources are not formatted, run `aptos move fmt` to format them:\n  {}",
+                unformatted.join("\n  ")
+            )));
+        }
+        Ok(unformatted)
+    }
+}
+
+/// Formatting options
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct FmtConfig {
+    /// Number of spaces per indentation level
+    pub indent_width: usize,
+    /// Maximal number of consecutive blank lines, further ones are removed
+    pub max_blank_lines: usize,
+}
+
+impl Default for FmtConfig {
+    fn default() -> Self {
+        Self {
+            indent_width: 4,
+            max_blank_lines: 1,
+        }
+    }
+}
+
+impl FmtConfig {
+    /// Reads the options of the package, from `Move.toml` and `.movefmt.toml`
+    pub fn load(package_path: &Path) -> CliTypedResult<Self> {
+        let mut options = toml::Table::new();
+        let manifest = read_toml_file(&package_path.join(SourcePackageLayout::Manifest.path()))?;
+        if let Some(section) = manifest.get(FMT_MANIFEST_SECTION) {
+            let section = section.as_table().ok_or_else(|| {
+                CliError::UnableToParse(
+                    "Move.toml",
+                    format!("`[{}]` must be a table", FMT_MANIFEST_SECTION),
+                )
+            })?;
+            options.extend(section.clone());
+        }
+        let config_file = package_path.join(FMT_CONFIG_FILE);
+        if config_file.exists() {
+            options.extend(read_toml_file(&config_file)?);
+        }
+        toml::Value::Table(options)
+            .try_into()
+            .map_err(|err| CliError::UnableToParse("formatting options", err.to_string()))
+    }
+}
+
+fn read_toml_file(path: &Path) -> CliTypedResult<toml::Table> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|err| CliError::IO(path.display().to_string(), err))?;
+    toml::from_str(&contents)
+        .map_err(|err| CliError::UnableToParse("TOML", format!("{}: {}", path.display(), err)))
+}
+
+/// Formats a Move source file, which has to parse.
+pub fn format_move_source(
+    file_name: &str,
+    source: &str,
+    config: &FmtConfig,
+) -> anyhow::Result<String> {
+    let file_hash = FileHash::new(source);
+    let mut env = CompilationEnv::new(Flags::empty(), BTreeSet::new());
+    if let Err(diags) = parse_file_string(&mut env, file_hash, source) {
+        let files =
+            FilesSourceText::from([(file_hash, (Symbol::from(file_name), source.to_string()))]);
+        bail!(
+            "{}",
+            String::from_utf8_lossy(&report_diagnostics_to_buffer(&files, diags))
+        );
+    }
+
+    let elements = lex(source)?;
+    let formatted = Formatter::new(config).format(&elements);
+    // Only whitespace may change, which guards against formatting bugs changing the code
+    let formatted_elements = lex(&formatted)?;
+    if elements.len() != formatted_elements.len()
+        || elements
+            .iter()
+            .zip(&formatted_elements)
+            .any(|(element, formatted_element)| element.item != formatted_element.item)
+    {
+        bail!("Formatting {} would change its code", file_name);
+    }
+    Ok(formatted)
+}
+
+/// A token or a comment of a source file
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Item<'a> {
+    Token(Tok, &'a str),
+    Comment(&'a str),
+}
+
+/// An item, with the whitespace preceding it in the source
+#[derive(Debug)]
+struct Element<'a> {
+    item: Item<'a>,
+    newlines: usize,
+    space: bool,
+}
+
+fn lex(source: &str) -> anyhow::Result<Vec<Element>> {
+    let mut lexer = Lexer::new(source, FileHash::new(source));
+    let mut elements = vec![];
+    let mut end = 0;
+    loop {
+        lexer
+            .advance()
+            .map_err(|diag| anyhow!("Failed to tokenize source: {:?}", diag))?;
+        let (newlines, space) = lex_gap(&source[end..lexer.start_loc()], &mut elements);
+        if lexer.peek() == Tok::EOF {
+            return Ok(elements);
+        }
+        elements.push(Element {
+            item: Item::Token(lexer.peek(), lexer.content()),
+            newlines,
+            space,
+        });
+        end = lexer.start_loc() + lexer.content().len();
+    }
+}
+
+/// Adds the comments between two tokens, and returns the whitespace preceding the second one.
+fn lex_gap<'a>(mut gap: &'a str, elements: &mut Vec<Element<'a>>) -> (usize, bool) {
+    let mut newlines = 0;
+    let mut space = false;
+    loop {
+        let rest = gap.trim_start();
+        let whitespace = &gap[..gap.len() - rest.len()];
+        newlines += whitespace.matches('\n').count();
+        space |= !whitespace.is_empty();
+        gap = rest;
+
+        let len = if gap.starts_with("//") {
+            gap.find('\n').unwrap_or(gap.len())
+        } else if gap.starts_with("/*") {
+            block_comment_len(gap)
+        } else {
+            return (newlines, space);
+        };
+        elements.push(Element {
+            item: Item::Comment(gap[..len].trim_end()),
+            newlines,
+            space,
+        });
+        gap = &gap[len..];
+        newlines = 0;
+        space = false;
+    }
+}
+
+/// Returns the length of the block comment starting `text`, where block comments can be nested.
+fn block_comment_len(text: &str) -> usize {
+    let bytes = text.as_bytes();
+    let mut depth = 0;
+    let mut idx = 0;
+    while idx < bytes.len() {
+        if bytes[idx..].starts_with(b"/*") {
+            depth += 1;
+            idx += 2;
+        } else if bytes[idx..].starts_with(b"*/") {
+            depth -= 1;
+            idx += 2;
+            if depth == 0 {
+                return idx;
+            }
+        } else {
+            idx += 1;
+        }
+    }
+    text.len()
+}
+
+struct Formatter<'a> {
+    config: &'a FmtConfig,
+    output: String,
+    /// Open brackets, with the indentation level of the line opening them
+    open_brackets: Vec<(Tok, usize)>,
+    /// Indentation level of the current line
+    indent: usize,
+    last_item: Option<Item<'a>>,
+    last_token: Option<Tok>,
+}
+
+impl<'a> Formatter<'a> {
+    fn new(config: &'a FmtConfig) -> Self {
+        Self {
+            config,
+            output: String::new(),
+            open_brackets: vec![],
+            indent: 0,
+            last_item: None,
+            last_token: None,
+        }
+    }
+
+    fn format(mut self, elements: &[Element<'a>]) -> String {
+        for element in elements {
+            if let Some(last_item) = self.last_item {
+                if element.newlines > 0 {
+                    self.new_line(element);
+                } else if space_between(last_item, element) {
+                    self.output.push(' ');
+                }
+            }
+            match element.item {
+                Item::Token(tok, text) => {
+                    self.output.push_str(text);
+                    match tok {
+                        Tok::LParen | Tok::LBracket | Tok::LBrace => {
+                            self.open_brackets.push((tok, self.indent))
+                        },
+                        Tok::RParen | Tok::RBracket | Tok::RBrace => {
+                            self.open_brackets.pop();
+                        },
+                        _ => {},
+                    }
+                    self.last_token = Some(tok);
+                },
+                Item::Comment(text) => self.output.push_str(text),
+            }
+            self.last_item = Some(element.item);
+        }
+        if !self.output.is_empty() {
+            self.output.push('\n');
+        }
+        self.output
+    }
+
+    fn new_line(&mut self, element: &Element) {
+        let closes_block = matches!(
+            element.item,
+            Item::Token(Tok::RParen | Tok::RBracket | Tok::RBrace, _)
+        );
+        let follows_opener = matches!(
+            self.last_item,
+            Some(Item::Token(Tok::LParen | Tok::LBracket | Tok::LBrace, _))
+        );
+        // No blank lines at the start or the end of a block
+        let blank_lines = if closes_block || follows_opener {
+            0
+        } else {
+            (element.newlines - 1).min(self.config.max_blank_lines)
+        };
+
+        // Lines are indented one level more than the line opening their block, closing
+        // brackets are aligned with it
+        let opening_indent = self.open_brackets.last().map(|(_, indent)| *indent);
+        self.indent = match opening_indent {
+            Some(indent) if closes_block => indent,
+            _ => {
+                let block_indent = opening_indent.map_or(0, |indent| indent + 1);
+                if self.is_continuation(&element.item) {
+                    block_indent + 1
+                } else {
+                    block_indent
+                }
+            },
+        };
+        self.output.push_str(&"\n".repeat(blank_lines + 1));
+        self.output
+            .push_str(&" ".repeat(self.indent * self.config.indent_width));
+    }
+
+    /// Whether the item continues an expression or a signature on a new line, e.g. after `=`.
+    /// Lines in parentheses and brackets are only indented relative to the opening line.
+    fn is_continuation(&self, item: &Item) -> bool {
+        if !matches!(self.open_brackets.last(), None | Some((Tok::LBrace, _))) {
+            return false;
+        }
+        let starts_continuation = match item {
+            Item::Token(tok, _) => {
+                is_binary_operator(*tok)
+                    || matches!(tok, Tok::PipePipe | Tok::Period | Tok::Acquires)
+            },
+            Item::Comment(_) => false,
+        };
+        starts_continuation
+            || matches!(self.last_token, Some(tok) if is_binary_operator(tok) || tok == Tok::PipePipe)
+    }
+}
+
+/// Whether two items on the same line are separated by a space.
+fn space_between(last: Item, element: &Element) -> bool {
+    use Tok::*;
+    let (last, next) = match (last, element.item) {
+        (Item::Token(last, _), Item::Token(next, _)) => (last, next),
+        // Comments keep their spacing, e.g. when commenting an argument
+        _ => return element.space,
+    };
+    match (last, next) {
+        (_, Comma | Semicolon | RParen | RBracket | Period | ColonColon | Colon) => false,
+        (LParen | LBracket | Period | ColonColon | AtSign | NumSign | Exclaim, _) => false,
+        // Macro calls, e.g. `assert!`
+        (Identifier, Exclaim) => false,
+        (Comma | Semicolon | Colon, _) => true,
+        (LBrace, RBrace) => false,
+        (LBrace, _) | (_, LBrace | RBrace) => true,
+        (Identifier, LParen | LBracket) => false,
+        // The role of these tokens depends on the context, e.g. `<` in `a < b` and `vector<u8>`,
+        // or `&` and `*` as unary and binary operators, so their spacing is kept
+        _ if is_ambiguous(last) || is_ambiguous(next) => element.space,
+        _ if is_binary_operator(last) || is_binary_operator(next) => true,
+        (_, LParen) | (AmpMut, _) => true,
+        _ => element.space,
+    }
+}
+
+fn is_ambiguous(tok: Tok) -> bool {
+    use Tok::*;
+    matches!(
+        tok,
+        Less | Greater | GreaterGreater | Amp | Star | Pipe | PipePipe | PeriodPeriod
+    )
+}
+
+fn is_binary_operator(tok: Tok) -> bool {
+    use Tok::*;
+    matches!(
+        tok,
+        Equal
+            | EqualEqual
+            | ExclaimEqual
+            | LessEqual
+            | GreaterEqual
+            | AmpAmp
+            | Plus
+            | Minus
+            | Slash
+            | Percent
+            | Caret
+            | LessLess
+            | EqualEqualGreater
+            | LessEqualEqualGreater
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_move_source, FmtConfig};
+
+    fn format(source: &str) -> String {
+        format_move_source("test.move", source, &FmtConfig::default()).unwrap()
+    }
+
+    #[test]
+    fn test_format_module() {
+        let source = r#"module 0x1::example {
+  use std::signer;
+    /// A counter
+  struct Counter has key { value: u64 }
+
+
+
+  public entry fun increment(account:&signer) acquires Counter {
+      let counter = borrow_global_mut<Counter>(signer::address_of(account)) ;
+    counter.value = counter.value+1; // overflow aborts
+      assert!(counter.value<100 , 1);
+  }
+}"#;
+        let expected = r#"module 0x1::example {
+    use std::signer;
+    /// A counter
+    struct Counter has key { value: u64 }
+
+    public entry fun increment(account: &signer) acquires Counter {
+        let counter = borrow_global_mut<Counter>(signer::address_of(account));
+        counter.value = counter.value + 1; // overflow aborts
+        assert!(counter.value<100, 1);
+    }
+}
+"#;
+        assert_eq!(format(source), expected);
+        assert_eq!(format(expected), expected);
+    }
+
+    #[test]
+    fn test_format_continuation_lines() {
+        let source = "script {\nfun main() {\nlet x = 1 +\n2;\n/* keep */ foo(x,\nx);\n}\n}\n";
+        let expected = r#"script {
+    fun main() {
+        let x = 1 +
+            2;
+        /* keep */ foo(x,
+            x);
+    }
+}
+"#;
+        assert_eq!(format(source), expected);
+    }
+
+    #[test]
+    fn test_format_invalid_source() {
+        assert!(
+            format_move_source("test.move", "module 0x1::m { fun }", &FmtConfig::default())
+                .is_err()
+        );
+    }
+}
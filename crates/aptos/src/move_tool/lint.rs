@@ -0,0 +1,75 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::types::{CliCommand, CliError, CliTypedResult, MovePackageDir};
+use aptos_framework::{
+    build_model, extended_checks,
+    lint::{run_lints, LintConfig},
+};
+use async_trait::async_trait;
+use clap::Parser;
+use codespan_reporting::{
+    diagnostic::Severity,
+    term::termcolor::{ColorChoice, StandardStream},
+};
+
+/// Lints a Move package
+///
+/// Lints flag code which compiles, but is likely to be a bug or to miss a convention:
+///
+/// - `unchecked_coin_arithmetic`: a `u64` product is divided in a function handling coins, where
+///   the product may overflow although the quotient fits
+///
+/// - `public_capability_return`: a public function returns a signer or a capability, such as
+///   `MintCapability` or `ExtendRef`, without taking a signer
+///
+/// - `missing_view`: a public function only reads global state, but is not a `#[view]` function
+///
+/// - `unused_signer`: an entry function takes a signer it doesn't use
+///
+/// Lints are reported as warnings. Their level can be set to `allow`, `warning` or `error` in the
+/// `[lint]` section of `Move.toml`, e.g. `missing_view = "allow"`. The same lints are run by
+/// `aptos move compile --lint`.
+#[derive(Parser)]
+pub struct LintPackage {
+    #[clap(flatten)]
+    pub(crate) move_options: MovePackageDir,
+}
+
+#[async_trait]
+impl CliCommand<&'static str> for LintPackage {
+    fn command_name(&self) -> &'static str {
+        "LintPackage"
+    }
+
+    async fn execute(self) -> CliTypedResult<&'static str> {
+        let package_path = self.move_options.get_package_path()?;
+        let config = LintConfig::from_package(&package_path)
+            .map_err(|err| CliError::UnableToParse("Move.toml", format!("{:#}", err)))?;
+        let model = &build_model(
+            self.move_options.dev,
+            package_path.as_path(),
+            self.move_options.named_addresses(),
+            None,
+            self.move_options.bytecode_version,
+            self.move_options.skip_attribute_checks,
+            extended_checks::get_all_attribute_names().clone(),
+        )?;
+        let mut error_writer = StandardStream::stderr(ColorChoice::Auto);
+        if model.has_errors() {
+            model.report_diag(&mut error_writer, Severity::Warning);
+            return Err(CliError::MoveCompilationError(
+                "compilation failed".to_string(),
+            ));
+        }
+
+        run_lints(model, &config);
+        if model.diag_count(Severity::Warning) > 0 {
+            model.report_diag(&mut error_writer, Severity::Warning);
+            if model.has_errors() {
+                return Err(CliError::MoveCompilationError("lints failed".to_string()));
+            }
+        }
+        Ok("succeeded")
+    }
+}
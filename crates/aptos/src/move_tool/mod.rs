@@ -2,10 +2,20 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod aptos_debug_natives;
+#[cfg(feature = "bench")]
+mod bench;
+mod check_upgrade;
 pub mod coverage;
+mod deploy;
 mod disassembler;
+mod fmt;
+mod lint;
 mod manifest;
 pub mod package_hooks;
+mod repl;
+mod replay;
+#[cfg(feature = "instruction-tracing")]
+mod replay_trace;
 mod show;
 pub mod stored_package;
 mod transactional_tests_runner;
@@ -20,7 +30,7 @@ use crate::{
             SaveFile, ScriptFunctionArguments, TransactionOptions, TransactionSummary,
         },
         utils::{
-            check_if_file_exists, create_dir_if_not_exist, dir_default_to_current,
+            check_if_file_exists, create_dir_if_not_exist, dir_default_to_current, parse_json_file,
             profile_or_submit, prompt_yes_with_override, write_to_file,
         },
     },
@@ -78,24 +88,33 @@ use transactional_tests_runner::TransactionalTestOpts;
 /// about this code.
 #[derive(Subcommand)]
 pub enum MoveTool {
+    #[cfg(feature = "bench")]
+    Bench(bench::BenchPackage),
     BuildPublishPayload(BuildPublishPayload),
+    CheckUpgrade(check_upgrade::CheckUpgrade),
     Clean(CleanPackage),
     Compile(CompilePackage),
     CompileScript(CompileScript),
     #[clap(subcommand)]
     Coverage(coverage::CoveragePackage),
     CreateResourceAccountAndPublishPackage(CreateResourceAccountAndPublishPackage),
+    Deploy(deploy::DeployPackages),
     Disassemble(Disassemble),
     Document(DocumentPackage),
     Download(DownloadPackage),
+    Fmt(fmt::FmtPackage),
     Init(InitPackage),
+    Lint(lint::LintPackage),
     List(ListPackage),
     Prove(ProvePackage),
     Publish(PublishPackage),
+    Repl(repl::Repl),
+    Replay(replay::Replay),
     Run(RunFunction),
     RunScript(RunScript),
     #[clap(subcommand, hide = true)]
     Show(show::ShowTool),
+    SimulateBundle(SimulateBundle),
     Test(TestPackage),
     TransactionalTest(TransactionalTestOpts),
     VerifyPackage(VerifyPackage),
@@ -105,7 +124,10 @@ pub enum MoveTool {
 impl MoveTool {
     pub async fn execute(self) -> CliResult {
         match self {
+            #[cfg(feature = "bench")]
+            MoveTool::Bench(tool) => tool.execute_serialized().await,
             MoveTool::BuildPublishPayload(tool) => tool.execute_serialized().await,
+            MoveTool::CheckUpgrade(tool) => tool.execute_serialized().await,
             MoveTool::Clean(tool) => tool.execute_serialized().await,
             MoveTool::Compile(tool) => tool.execute_serialized().await,
             MoveTool::CompileScript(tool) => tool.execute_serialized().await,
@@ -113,16 +135,22 @@ impl MoveTool {
             MoveTool::CreateResourceAccountAndPublishPackage(tool) => {
                 tool.execute_serialized_success().await
             },
+            MoveTool::Deploy(tool) => tool.execute_serialized().await,
             MoveTool::Disassemble(tool) => tool.execute_serialized().await,
             MoveTool::Document(tool) => tool.execute_serialized().await,
             MoveTool::Download(tool) => tool.execute_serialized().await,
+            MoveTool::Fmt(tool) => tool.execute_serialized().await,
             MoveTool::Init(tool) => tool.execute_serialized_success().await,
+            MoveTool::Lint(tool) => tool.execute_serialized().await,
             MoveTool::List(tool) => tool.execute_serialized().await,
             MoveTool::Prove(tool) => tool.execute_serialized().await,
             MoveTool::Publish(tool) => tool.execute_serialized().await,
+            MoveTool::Repl(tool) => tool.execute_serialized().await,
+            MoveTool::Replay(tool) => tool.execute_serialized().await,
             MoveTool::Run(tool) => tool.execute_serialized().await,
             MoveTool::RunScript(tool) => tool.execute_serialized().await,
             MoveTool::Show(tool) => tool.execute_serialized().await,
+            MoveTool::SimulateBundle(tool) => tool.execute_serialized().await,
             MoveTool::Test(tool) => tool.execute_serialized().await,
             MoveTool::TransactionalTest(tool) => tool.execute_serialized_success().await,
             MoveTool::VerifyPackage(tool) => tool.execute_serialized().await,
@@ -295,6 +323,12 @@ pub struct CompilePackage {
     #[clap(long)]
     pub(crate) save_metadata: bool,
 
+    /// Run the lints of `aptos move lint` on the package
+    ///
+    /// Lints at the `error` level in the `[lint]` section of `Move.toml` fail the compilation.
+    #[clap(long)]
+    pub(crate) lint: bool,
+
     #[clap(flatten)]
     pub(crate) included_artifacts_args: IncludedArtifactsArgs,
     #[clap(flatten)]
@@ -310,6 +344,7 @@ impl CliCommand<Vec<String>> for CompilePackage {
     async fn execute(self) -> CliTypedResult<Vec<String>> {
         let build_options = BuildOptions {
             install_dir: self.move_options.output_dir.clone(),
+            lint: self.lint,
             ..self
                 .included_artifacts_args
                 .included_artifacts
@@ -620,7 +655,9 @@ impl CliCommand<&'static str> for DocumentPackage {
             with_abis: false,
             with_source_maps: false,
             with_error_map: false,
+            with_access_summaries: false,
             with_docs: true,
+            lint: false,
             install_dir: None,
             named_addresses: move_options.named_addresses(),
             docgen_options: Some(docgen_options),
@@ -656,6 +693,13 @@ pub struct PublishPackage {
     #[clap(long)]
     pub(crate) override_size_check: bool,
 
+    /// Include summaries of the resources accessed by the entry functions in the modules
+    ///
+    /// The summaries are used by validators as hints to schedule transactions calling these
+    /// functions. Publishing fails unless the access summary metadata feature is enabled.
+    #[clap(long)]
+    pub(crate) with_access_summaries: bool,
+
     #[clap(flatten)]
     pub(crate) included_artifacts_args: IncludedArtifactsArgs,
     #[clap(flatten)]
@@ -685,16 +729,19 @@ impl TryInto<PackagePublicationData> for &PublishPackage {
 
     fn try_into(self) -> Result<PackagePublicationData, Self::Error> {
         let package_path = self.move_options.get_package_path()?;
-        let options = self
-            .included_artifacts_args
-            .included_artifacts
-            .build_options(
-                self.move_options.dev,
-                self.move_options.skip_fetch_latest_git_deps,
-                self.move_options.named_addresses(),
-                self.move_options.bytecode_version,
-                self.move_options.skip_attribute_checks,
-            );
+        let options = BuildOptions {
+            with_access_summaries: self.with_access_summaries,
+            ..self
+                .included_artifacts_args
+                .included_artifacts
+                .build_options(
+                    self.move_options.dev,
+                    self.move_options.skip_fetch_latest_git_deps,
+                    self.move_options.named_addresses(),
+                    self.move_options.bytecode_version,
+                    self.move_options.skip_attribute_checks,
+                )
+        };
         let package = BuiltPackage::build(package_path, options)
             .map_err(|e| CliError::MoveCompilationError(format!("{:#}", e)))?;
         let compiled_units = package.extract_code();
@@ -1293,6 +1340,45 @@ impl CliCommand<TransactionSummary> for RunScript {
     }
 }
 
+/// Simulate a bundle of Move functions
+///
+/// The functions are called in order by the sender, each of them observing the effects of the
+/// previous ones, e.g. to check that an approval followed by a swap succeeds. Nothing is
+/// submitted to the chain.
+#[derive(Parser)]
+pub struct SimulateBundle {
+    /// JSON file with a list of entry function calls, in the format of `aptos move run --json-file`
+    #[clap(long, value_parser)]
+    pub(crate) json_file: PathBuf,
+    #[clap(flatten)]
+    pub(crate) txn_options: TransactionOptions,
+}
+
+#[async_trait]
+impl CliCommand<Vec<TransactionSummary>> for SimulateBundle {
+    fn command_name(&self) -> &'static str {
+        "SimulateBundle"
+    }
+
+    async fn execute(self) -> CliTypedResult<Vec<TransactionSummary>> {
+        let payloads = parse_json_file::<Vec<EntryFunctionArgumentsJSON>>(&self.json_file)?
+            .into_iter()
+            .map(|json| {
+                let entry_function_args: EntryFunctionArguments = json.try_into()?;
+                Ok(TransactionPayload::EntryFunction(
+                    entry_function_args.try_into()?,
+                ))
+            })
+            .collect::<CliTypedResult<Vec<_>>>()?;
+        if payloads.is_empty() {
+            return Err(CliError::CommandArgumentError(
+                "No functions to simulate".to_string(),
+            ));
+        }
+        self.txn_options.simulate_bundle(payloads).await
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub(crate) enum FunctionArgType {
     Address,
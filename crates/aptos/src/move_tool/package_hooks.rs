@@ -1,8 +1,11 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{common::types::load_account_arg, move_tool::CachedPackageRegistry};
-use aptos_framework::UPGRADE_POLICY_CUSTOM_FIELD;
+use crate::{
+    common::types::load_account_arg,
+    move_tool::{fmt::FMT_MANIFEST_SECTION, CachedPackageRegistry},
+};
+use aptos_framework::{lint::LINT_MANIFEST_SECTION, UPGRADE_POLICY_CUSTOM_FIELD};
 use futures::executor::block_on;
 use move_package::{
     compilation::package_layout::CompiledPackageLayout, package_hooks::PackageHooks,
@@ -22,6 +25,13 @@ impl PackageHooks for AptosPackageHooks {
         vec![UPGRADE_POLICY_CUSTOM_FIELD.to_string()]
     }
 
+    fn custom_manifest_sections(&self) -> Vec<String> {
+        vec![
+            FMT_MANIFEST_SECTION.to_string(),
+            LINT_MANIFEST_SECTION.to_string(),
+        ]
+    }
+
     fn custom_dependency_key(&self) -> Option<String> {
         Some("aptos".to_string())
     }
@@ -0,0 +1,1206 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    common::types::{
+        CliCommand, CliError, CliTypedResult, MovePackageDir, ProfileOptions, RestOptions,
+    },
+    move_tool::IncludedArtifacts,
+};
+use aptos_framework::BuiltPackage;
+use aptos_gas_schedule::{MiscGasParameters, NativeGasParameters, LATEST_GAS_FEATURE_VERSION};
+use aptos_state_view::{StateView, TStateView};
+use aptos_types::{
+    access_path::{AccessPath, Path},
+    account_address::AccountAddress,
+    chain_id::ChainId,
+    on_chain_config::{Features, OnChainConfig, TimedFeatures},
+    state_store::{
+        state_key::{StateKey, StateKeyInner},
+        state_storage_usage::StateStorageUsage,
+        state_value::StateValue,
+    },
+    transaction::Version,
+};
+use aptos_validator_interface::{
+    AptosValidatorInterface, DBDebuggerInterface, DebuggerStateView, RestDebuggerInterface,
+};
+use aptos_vm::{
+    data_cache::StorageAdapter,
+    move_vm_ext::{MoveVmExt, SessionExt, SessionId},
+};
+use aptos_vm_types::{change_set::VMChangeSet, storage::ChangeSetConfigs};
+use async_trait::async_trait;
+use clap::Parser;
+use move_binary_format::{
+    access::ModuleAccess,
+    errors::{VMError, VMResult},
+    file_format::{SignatureToken, StructHandleIndex},
+    CompiledModule,
+};
+use move_core_types::{
+    identifier::Identifier,
+    language_storage::{ModuleId, StructTag, TypeTag, CORE_CODE_ADDRESS},
+    resolver::MoveResolver,
+    u256::U256,
+    value::{MoveStruct, MoveValue},
+};
+use move_resource_viewer::MoveValueAnnotator;
+use move_vm_types::gas::UnmeteredGasMeter;
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fmt::{Display, Formatter},
+    io::Write,
+    path::PathBuf,
+    sync::Arc,
+};
+
+const HELP: &str = "\
+<function call>              Call a function, e.g. `0x1::coin::balance<0x1::aptos_coin::AptosCoin>(@0x1)`
+                             Only single calls with literal arguments are supported, not `let`
+                             bindings, nested calls or other expressions
+:resource <address> <type>   Show a resource, e.g. `:resource 0x1 0x1::account::Account`
+:reset                       Drop the changes made since the start of the session
+:help                        Show this message
+:quit                        Exit the session";
+
+/// Start an interactive session to call the functions of a Move package against on-chain state
+///
+/// The package is compiled and loaded on top of the state of a network at `--version`, read
+/// from its REST API, or from a local DB with `--db-path` such as the one of a local testnet.
+/// Its dependencies are read from the network, unless they aren't published there yet.
+///
+/// Functions are called, whatever their visibility, one call per line such as
+/// `0x1::coin::transfer<0x1::aptos_coin::AptosCoin>(@0xcafe, @0xbeef, 100)`, where `@address`
+/// is passed to `signer` parameters and the first signer sends the call. Only single calls with
+/// literal arguments are supported: not `let` bindings, nested calls or other expressions, which
+/// would need the Move compiler to evaluate. The values returned, the state written and the
+/// events emitted by every call are shown, and its changes are seen by the calls after it.
+/// Nothing is ever submitted to the network.
+#[derive(Parser)]
+pub struct Repl {
+    /// Path to a local DB to read the state from, instead of the REST API of the network
+    #[clap(long, value_parser)]
+    pub(crate) db_path: Option<PathBuf>,
+
+    /// Version of the network to read the state at, defaults to the latest version
+    #[clap(long)]
+    pub(crate) version: Option<Version>,
+
+    #[clap(flatten)]
+    pub(crate) move_options: MovePackageDir,
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+}
+
+#[async_trait]
+impl CliCommand<()> for Repl {
+    fn command_name(&self) -> &'static str {
+        "Repl"
+    }
+
+    async fn execute(self) -> CliTypedResult<()> {
+        let db: Arc<dyn AptosValidatorInterface + Send> = match &self.db_path {
+            Some(path) => Arc::new(DBDebuggerInterface::open(path).map_err(|err| {
+                CliError::UnexpectedError(format!(
+                    "Failed to open DB at {}: {}",
+                    path.display(),
+                    err
+                ))
+            })?),
+            None => Arc::new(RestDebuggerInterface::new(
+                self.rest_options.client(&self.profile_options)?,
+            )),
+        };
+        let version = match self.version {
+            Some(version) => version,
+            None => db.get_latest_version().await.map_err(|err| {
+                CliError::UnexpectedError(format!("Failed to get the latest version: {}", err))
+            })?,
+        };
+
+        // State reads block on the runtime, as do reads from stdin
+        tokio::task::spawn_blocking(move || self.run(db, version))
+            .await
+            .map_err(|err| CliError::UnexpectedError(err.to_string()))?
+    }
+}
+
+impl Repl {
+    fn run(
+        self,
+        db: Arc<dyn AptosValidatorInterface + Send>,
+        version: Version,
+    ) -> CliTypedResult<()> {
+        let build_options = IncludedArtifacts::None.build_options(
+            self.move_options.dev,
+            self.move_options.skip_fetch_latest_git_deps,
+            self.move_options.named_addresses(),
+            self.move_options.bytecode_version,
+            self.move_options.skip_attribute_checks,
+        );
+        let package = BuiltPackage::build(self.move_options.get_package_path()?, build_options)
+            .map_err(|e| CliError::MoveCompilationError(format!("{:#}", e)))?;
+
+        let mut named_addresses: BTreeMap<_, _> = ["std", "aptos_std", "aptos_framework"]
+            .into_iter()
+            .map(|name| (name.to_string(), CORE_CODE_ADDRESS))
+            .collect();
+        named_addresses.extend(self.move_options.named_addresses());
+        // The state view reads the state as of right before its version
+        let mut session =
+            ReplSession::new(DebuggerStateView::new(db, version + 1), named_addresses);
+        let published = session.load_package(&package)?;
+        println!(
+            "Loaded {} modules of package {} on top of version {}, type `:help` for help",
+            published,
+            package.name(),
+            version
+        );
+
+        let stdin = std::io::stdin();
+        loop {
+            print!("> ");
+            std::io::stdout()
+                .flush()
+                .map_err(|err| CliError::IO("stdout".to_string(), err))?;
+            let mut line = String::new();
+            if stdin
+                .read_line(&mut line)
+                .map_err(|err| CliError::IO("stdin".to_string(), err))?
+                == 0
+            {
+                return Ok(());
+            }
+            let command = match parse_command(line.trim(), &session.named_addresses) {
+                Ok(Some(command)) => command,
+                Ok(None) => continue,
+                Err(err) => {
+                    eprintln!("{}", err);
+                    continue;
+                },
+            };
+            let output = match command {
+                ReplCommand::Call(call) => session.call(&call),
+                ReplCommand::Resource(address, tag) => session.view_resource(address, &tag),
+                ReplCommand::Reset => {
+                    session.state.writes.clear();
+                    session
+                        .load_package(&package)
+                        .map(|_| "Dropped the changes of the session".to_string())
+                },
+                ReplCommand::Help => Ok(HELP.to_string()),
+                ReplCommand::Quit => return Ok(()),
+            };
+            match output {
+                Ok(output) => println!("{}", output),
+                Err(err) => eprintln!("{}", err),
+            }
+        }
+    }
+}
+
+/// State of the network, with the changes made by the session on top of it
+struct ReplState<S> {
+    base: S,
+    writes: HashMap<StateKey, Option<StateValue>>,
+}
+
+impl<S> ReplState<S> {
+    fn apply(&mut self, change_set: &VMChangeSet) {
+        for (key, op) in change_set.write_set_iter() {
+            let value = match (op.bytes(), op.metadata()) {
+                (Some(bytes), Some(metadata)) => Some(StateValue::new_with_metadata(
+                    bytes.to_vec(),
+                    metadata.clone(),
+                )),
+                (Some(bytes), None) => Some(StateValue::new_legacy(bytes.to_vec())),
+                (None, _) => None,
+            };
+            self.writes.insert(key.clone(), value);
+        }
+    }
+}
+
+impl<S: StateView> TStateView for ReplState<S> {
+    type Key = StateKey;
+
+    fn get_state_value(&self, state_key: &StateKey) -> anyhow::Result<Option<StateValue>> {
+        match self.writes.get(state_key) {
+            Some(value) => Ok(value.clone()),
+            None => self.base.get_state_value(state_key),
+        }
+    }
+
+    fn get_usage(&self) -> anyhow::Result<StateStorageUsage> {
+        Ok(StateStorageUsage::new_untracked())
+    }
+}
+
+struct ReplSession<S> {
+    state: ReplState<S>,
+    named_addresses: BTreeMap<String, AccountAddress>,
+    /// Number of sessions run, so that each of them gets unique table handles and object
+    /// addresses
+    sessions: u64,
+}
+
+impl<S: StateView> ReplSession<S> {
+    fn new(base: S, named_addresses: BTreeMap<String, AccountAddress>) -> Self {
+        Self {
+            state: ReplState {
+                base,
+                writes: HashMap::new(),
+            },
+            named_addresses,
+            sessions: 0,
+        }
+    }
+
+    /// Publishes the modules of the package, and its dependencies which aren't on chain, and
+    /// returns how many were published
+    fn load_package(&mut self, package: &BuiltPackage) -> CliTypedResult<usize> {
+        let root_modules: BTreeSet<_> = package.modules().map(|module| module.self_id()).collect();
+        // Modules are published in dependency order, in bundles of modules at the same address
+        let mut bundles: Vec<(AccountAddress, Vec<Vec<u8>>)> = vec![];
+        for module in package.all_modules() {
+            let id = module.self_id();
+            if !root_modules.contains(&id) && self.get_module(&id)?.is_some() {
+                continue;
+            }
+            let mut bytes = vec![];
+            module
+                .serialize(&mut bytes)
+                .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+            match bundles.last_mut() {
+                Some((address, modules)) if address == id.address() => modules.push(bytes),
+                _ => bundles.push((*id.address(), vec![bytes])),
+            }
+        }
+
+        let published = bundles.iter().map(|(_, modules)| modules.len()).sum();
+        self.run_session(AccountAddress::ZERO, |session| {
+            for (address, modules) in bundles {
+                // Modules already on chain are replaced whatever their compatibility, to try out
+                // changes not published yet
+                session.publish_module_bundle_relax_compatibility(
+                    modules,
+                    address,
+                    &mut UnmeteredGasMeter,
+                )?;
+            }
+            Ok(())
+        })?;
+        Ok(published)
+    }
+
+    /// Calls a function, keeping its changes, and describes its results
+    fn call(&mut self, call: &Call) -> CliTypedResult<String> {
+        let PreparedCall {
+            sender,
+            args,
+            return_types,
+        } = self.prepare_call(call)?;
+        let (returned, change_set) = self.run_session(sender, |session| {
+            session.execute_function_bypass_visibility(
+                &call.module,
+                call.function.as_ident_str(),
+                call.type_args.clone(),
+                args,
+                &mut UnmeteredGasMeter,
+            )
+        })?;
+
+        let resolver = StorageAdapter::new(&self.state);
+        let annotator = MoveValueAnnotator::new(&resolver);
+        let mut output = vec![];
+        for (tag, (bytes, _)) in return_types.iter().zip(&returned.return_values) {
+            match annotator.view_value(tag, bytes) {
+                Ok(value) => output.push(value.to_string()),
+                Err(_) => output.push(format!("0x{}", hex::encode(bytes))),
+            }
+        }
+        if change_set.write_set_iter().next().is_some() {
+            output.push("Changes:".to_string());
+        }
+        for (key, op) in change_set.write_set_iter().collect::<BTreeMap<_, _>>() {
+            let action = if op.is_deletion() { "Deleted" } else { "Wrote" };
+            output.push(indent(&describe_write(&annotator, action, key, op.bytes())));
+        }
+        if !change_set.events().is_empty() {
+            output.push("Events:".to_string());
+        }
+        for event in change_set.events() {
+            let value = annotator
+                .view_value(event.type_tag(), event.event_data())
+                .map(|value| value.to_string())
+                .unwrap_or_else(|_| format!("0x{}", hex::encode(event.event_data())));
+            output.push(indent(&format!("{} {}", event.type_tag(), value)));
+        }
+        if output.is_empty() {
+            output.push("Done".to_string());
+        }
+        Ok(output.join("\n"))
+    }
+
+    /// Checks the arguments of a call against the signature of the function, and serializes them
+    fn prepare_call(&self, call: &Call) -> CliTypedResult<PreparedCall> {
+        let module = self.get_module(&call.module)?.ok_or_else(|| {
+            CliError::CommandArgumentError(format!("Module {} not found", call.module))
+        })?;
+        let handle = module
+            .function_defs()
+            .iter()
+            .map(|definition| module.function_handle_at(definition.function))
+            .find(|handle| module.identifier_at(handle.name) == call.function.as_ident_str())
+            .ok_or_else(|| {
+                CliError::CommandArgumentError(format!(
+                    "Function {}::{} not found",
+                    call.module, call.function
+                ))
+            })?;
+        if handle.type_parameters.len() != call.type_args.len() {
+            return Err(CliError::CommandArgumentError(format!(
+                "Expected {} type arguments, got {}",
+                handle.type_parameters.len(),
+                call.type_args.len()
+            )));
+        }
+        let parameters = &module.signature_at(handle.parameters).0;
+        if parameters.len() != call.args.len() {
+            return Err(CliError::CommandArgumentError(format!(
+                "Expected {} arguments, got {}",
+                parameters.len(),
+                call.args.len()
+            )));
+        }
+
+        // The first signer sends the call, as for transactions
+        let mut sender = None;
+        let mut args = vec![];
+        for (parameter, arg) in parameters.iter().zip(&call.args) {
+            let tag = signature_type_tag(&module, parameter, &call.type_args)?;
+            let value = arg.to_move_value(&tag)?;
+            if let (TypeTag::Signer, MoveValue::Signer(address)) = (&tag, &value) {
+                sender.get_or_insert(*address);
+            }
+            args.push(value.simple_serialize().ok_or_else(|| {
+                CliError::UnexpectedError(format!("Failed to serialize {}", arg))
+            })?);
+        }
+        let return_types = module
+            .signature_at(handle.return_)
+            .0
+            .iter()
+            .map(|token| signature_type_tag(&module, token, &call.type_args))
+            .collect::<CliTypedResult<Vec<_>>>()?;
+        Ok(PreparedCall {
+            sender: sender.unwrap_or(AccountAddress::ZERO),
+            args,
+            return_types,
+        })
+    }
+
+    fn view_resource(&self, address: AccountAddress, tag: &StructTag) -> CliTypedResult<String> {
+        let resolver = StorageAdapter::new(&self.state);
+        let bytes = resolver
+            .get_resource(&address, tag)
+            .map_err(|err| CliError::UnexpectedError(err.to_string()))?
+            .ok_or_else(|| {
+                CliError::CommandArgumentError(format!(
+                    "Resource {} not found at {}",
+                    tag,
+                    address.to_hex_literal()
+                ))
+            })?;
+        MoveValueAnnotator::new(&resolver)
+            .view_resource(tag, &bytes)
+            .map(|value| value.to_string())
+            .map_err(|err| CliError::UnexpectedError(err.to_string()))
+    }
+
+    fn get_module(&self, id: &ModuleId) -> CliTypedResult<Option<CompiledModule>> {
+        let key = StateKey::access_path(AccessPath::code_access_path(id.clone()));
+        let bytes = self
+            .state
+            .get_state_value_bytes(&key)
+            .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+        bytes
+            .map(|bytes| CompiledModule::deserialize(&bytes))
+            .transpose()
+            .map_err(|err| CliError::UnexpectedError(format!("Failed to load {}: {}", id, err)))
+    }
+
+    /// Runs a session of the VM on top of the state, and applies its changes to the state
+    fn run_session<T>(
+        &mut self,
+        sender: AccountAddress,
+        f: impl FnOnce(&mut SessionExt) -> VMResult<T>,
+    ) -> CliTypedResult<(T, VMChangeSet)> {
+        self.sessions += 1;
+        let (result, change_set) = {
+            let resolver = StorageAdapter::new(&self.state);
+            let move_vm = MoveVmExt::new(
+                NativeGasParameters::zeros(),
+                MiscGasParameters::zeros(),
+                LATEST_GAS_FEATURE_VERSION,
+                ChainId::test().id(),
+                Features::fetch_config(&resolver).unwrap_or_default(),
+                TimedFeatures::enable_all(),
+            )
+            .map_err(vm_error)?;
+            let mut session = move_vm.new_session(&resolver, SessionId::Txn {
+                sender,
+                sequence_number: self.sessions,
+                script_hash: vec![],
+            });
+            let result = f(&mut session).map_err(vm_error)?;
+            let change_set = session
+                .finish(
+                    &mut (),
+                    &ChangeSetConfigs::unlimited_at_gas_feature_version(LATEST_GAS_FEATURE_VERSION),
+                )
+                .map_err(vm_error)?
+                .try_materialize(&self.state)
+                .map_err(|status| CliError::SimulationError(format!("{:?}", status)))?;
+            (result, change_set)
+        };
+        self.state.apply(&change_set);
+        Ok((result, change_set))
+    }
+}
+
+/// Call whose arguments were checked against the signature of its function
+struct PreparedCall {
+    sender: AccountAddress,
+    args: Vec<Vec<u8>>,
+    return_types: Vec<TypeTag>,
+}
+
+fn vm_error(err: VMError) -> CliError {
+    CliError::SimulationError(format!("{:?}", err.into_vm_status()))
+}
+
+/// Describes a write to the state, decoding resources with their layout
+fn describe_write<T: MoveResolver>(
+    annotator: &MoveValueAnnotator<T>,
+    action: &str,
+    key: &StateKey,
+    bytes: Option<&[u8]>,
+) -> String {
+    let resource = |tag: &StructTag, bytes: &[u8]| {
+        annotator
+            .view_resource(tag, bytes)
+            .map(|value| format!("\n{}", indent(&value.to_string())))
+            .unwrap_or_default()
+    };
+    match key.inner() {
+        StateKeyInner::AccessPath(path) => {
+            let address = path.address.to_hex_literal();
+            match path.get_path() {
+                Path::Code(id) => format!("{} module {}", action, id),
+                Path::Resource(tag) => format!(
+                    "{} resource {} at {}{}",
+                    action,
+                    tag,
+                    address,
+                    bytes.map(|bytes| resource(&tag, bytes)).unwrap_or_default()
+                ),
+                Path::ResourceGroup(tag) => {
+                    let members: BTreeMap<StructTag, Vec<u8>> = bytes
+                        .and_then(|bytes| bcs::from_bytes(bytes).ok())
+                        .unwrap_or_default();
+                    let mut description =
+                        format!("{} resource group {} at {}", action, tag, address);
+                    for (tag, bytes) in members {
+                        description.push_str(&indent(&format!(
+                            "\n{}{}",
+                            tag,
+                            resource(&tag, &bytes)
+                        )));
+                    }
+                    description
+                },
+            }
+        },
+        StateKeyInner::TableItem { handle, key } => format!(
+            "{} item 0x{} of table {}{}",
+            action,
+            hex::encode(key),
+            handle.0.to_hex_literal(),
+            bytes
+                .map(|bytes| format!(": 0x{}", hex::encode(bytes)))
+                .unwrap_or_default()
+        ),
+        StateKeyInner::Raw(raw) => format!("{} 0x{}", action, hex::encode(raw)),
+    }
+}
+
+fn indent(text: &str) -> String {
+    text.lines()
+        .map(|line| format!("  {}", line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Type of a parameter or return value of a function, for the given type arguments
+fn signature_type_tag(
+    module: &CompiledModule,
+    token: &SignatureToken,
+    type_args: &[TypeTag],
+) -> CliTypedResult<TypeTag> {
+    let struct_tag = |index: StructHandleIndex, type_params: Vec<TypeTag>| {
+        let handle = module.struct_handle_at(index);
+        let module_handle = module.module_handle_at(handle.module);
+        Box::new(StructTag {
+            address: *module.address_identifier_at(module_handle.address),
+            module: module.identifier_at(module_handle.name).to_owned(),
+            name: module.identifier_at(handle.name).to_owned(),
+            type_params,
+        })
+    };
+    Ok(match token {
+        SignatureToken::Bool => TypeTag::Bool,
+        SignatureToken::U8 => TypeTag::U8,
+        SignatureToken::U16 => TypeTag::U16,
+        SignatureToken::U32 => TypeTag::U32,
+        SignatureToken::U64 => TypeTag::U64,
+        SignatureToken::U128 => TypeTag::U128,
+        SignatureToken::U256 => TypeTag::U256,
+        SignatureToken::Address => TypeTag::Address,
+        SignatureToken::Signer => TypeTag::Signer,
+        SignatureToken::Vector(inner) => {
+            TypeTag::Vector(Box::new(signature_type_tag(module, inner, type_args)?))
+        },
+        SignatureToken::Struct(index) => TypeTag::Struct(struct_tag(*index, vec![])),
+        SignatureToken::StructInstantiation(index, tokens) => {
+            let type_params = tokens
+                .iter()
+                .map(|token| signature_type_tag(module, token, type_args))
+                .collect::<CliTypedResult<_>>()?;
+            TypeTag::Struct(struct_tag(*index, type_params))
+        },
+        SignatureToken::Reference(inner) | SignatureToken::MutableReference(inner) => {
+            signature_type_tag(module, inner, type_args)?
+        },
+        SignatureToken::TypeParameter(index) => type_args
+            .get(*index as usize)
+            .cloned()
+            .ok_or_else(|| CliError::UnexpectedError(format!("Missing type argument {}", index)))?,
+    })
+}
+
+enum ReplCommand {
+    Call(Call),
+    Resource(AccountAddress, StructTag),
+    Reset,
+    Help,
+    Quit,
+}
+
+/// Parses a line of input, `None` if there is nothing to run
+fn parse_command(
+    line: &str,
+    named_addresses: &BTreeMap<String, AccountAddress>,
+) -> CliTypedResult<Option<ReplCommand>> {
+    let (command, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let mut parser = InputParser::new(rest, named_addresses);
+    let command = match command {
+        "" => return Ok(None),
+        ":resource" => {
+            let address = parser.address()?;
+            ReplCommand::Resource(address, parser.struct_tag()?)
+        },
+        ":reset" => ReplCommand::Reset,
+        ":help" => ReplCommand::Help,
+        ":quit" | ":exit" => ReplCommand::Quit,
+        command if command.starts_with(':') => {
+            return Err(CliError::CommandArgumentError(format!(
+                "Unknown command `{}`, type `:help` for help",
+                command
+            )))
+        },
+        _ => {
+            let mut parser = InputParser::new(line, named_addresses);
+            let call = parser.call()?;
+            parser.finish()?;
+            return Ok(Some(ReplCommand::Call(call)));
+        },
+    };
+    parser.finish()?;
+    Ok(Some(command))
+}
+
+/// Call of a function, e.g. `0x1::coin::balance<0x1::aptos_coin::AptosCoin>(@0x1)`
+#[derive(Debug, PartialEq)]
+struct Call {
+    module: ModuleId,
+    function: Identifier,
+    type_args: Vec<TypeTag>,
+    args: Vec<Literal>,
+}
+
+/// Move literal, whose type is given by the parameter it is passed to
+#[derive(Debug, PartialEq)]
+enum Literal {
+    Bool(bool),
+    /// Digits, in decimal or `0x` prefixed hex, and type suffix, e.g. `100u64`
+    Integer(String, Option<String>),
+    Address(AccountAddress),
+    Bytes(Vec<u8>),
+    String(String),
+    Vector(Vec<Literal>),
+}
+
+impl Literal {
+    fn to_move_value(&self, tag: &TypeTag) -> CliTypedResult<MoveValue> {
+        Ok(match (tag, self) {
+            (TypeTag::Bool, Literal::Bool(value)) => MoveValue::Bool(*value),
+            (TypeTag::U8, Literal::Integer(..)) => MoveValue::U8(self.integer("u8")?),
+            (TypeTag::U16, Literal::Integer(..)) => MoveValue::U16(self.integer("u16")?),
+            (TypeTag::U32, Literal::Integer(..)) => MoveValue::U32(self.integer("u32")?),
+            (TypeTag::U64, Literal::Integer(..)) => MoveValue::U64(self.integer("u64")?),
+            (TypeTag::U128, Literal::Integer(..)) => MoveValue::U128(self.integer("u128")?),
+            (TypeTag::U256, Literal::Integer(..)) => MoveValue::U256(self.integer("u256")?),
+            (TypeTag::Address, Literal::Address(address)) => MoveValue::Address(*address),
+            (TypeTag::Signer, Literal::Address(address)) => MoveValue::Signer(*address),
+            (TypeTag::Vector(inner), Literal::Bytes(bytes)) if **inner == TypeTag::U8 => {
+                MoveValue::vector_u8(bytes.clone())
+            },
+            (TypeTag::Vector(inner), Literal::Vector(items)) => MoveValue::Vector(
+                items
+                    .iter()
+                    .map(|item| item.to_move_value(inner))
+                    .collect::<CliTypedResult<_>>()?,
+            ),
+            (TypeTag::Struct(tag), Literal::String(string))
+                if is_std_struct(tag, "string", "String") =>
+            {
+                MoveValue::Struct(MoveStruct::Runtime(vec![MoveValue::vector_u8(
+                    string.as_bytes().to_vec(),
+                )]))
+            },
+            (TypeTag::Struct(tag), Literal::Address(address))
+                if is_std_struct(tag, "object", "Object") =>
+            {
+                MoveValue::Struct(MoveStruct::Runtime(vec![MoveValue::Address(*address)]))
+            },
+            _ => {
+                return Err(CliError::CommandArgumentError(format!(
+                    "Expected a value of type {}, got {}",
+                    tag, self
+                )))
+            },
+        })
+    }
+
+    fn integer<T: TryFrom<U256>>(&self, typ: &str) -> CliTypedResult<T> {
+        let Literal::Integer(digits, suffix) = self else {
+            unreachable!("Only called on integers")
+        };
+        if suffix.as_deref().map_or(false, |suffix| suffix != typ) {
+            return Err(CliError::CommandArgumentError(format!(
+                "Expected a value of type {}, got {}",
+                typ, self
+            )));
+        }
+        let digits = digits.replace('_', "");
+        let value = match digits.strip_prefix("0x") {
+            Some(hex) => U256::from_str_radix(hex, 16),
+            None => U256::from_str_radix(&digits, 10),
+        }
+        .map_err(|err| CliError::UnableToParse("integer", format!("{}: {}", self, err)))?;
+        T::try_from(value).map_err(|_| {
+            CliError::CommandArgumentError(format!("{} doesn't fit in a {}", self, typ))
+        })
+    }
+}
+
+impl Display for Literal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Literal::Bool(value) => write!(f, "{}", value),
+            Literal::Integer(digits, suffix) => {
+                write!(f, "{}{}", digits, suffix.as_deref().unwrap_or_default())
+            },
+            Literal::Address(address) => write!(f, "@{}", address.to_hex_literal()),
+            Literal::Bytes(bytes) => write!(f, "x\"{}\"", hex::encode(bytes)),
+            Literal::String(string) => write!(f, "{:?}", string),
+            Literal::Vector(items) => {
+                let items: Vec<_> = items.iter().map(|item| item.to_string()).collect();
+                write!(f, "[{}]", items.join(", "))
+            },
+        }
+    }
+}
+
+fn is_std_struct(tag: &StructTag, module: &str, name: &str) -> bool {
+    tag.address == CORE_CODE_ADDRESS && tag.module.as_str() == module && tag.name.as_str() == name
+}
+
+/// Parser of the input of the REPL, where addresses can be named
+struct InputParser<'a> {
+    input: &'a str,
+    position: usize,
+    named_addresses: &'a BTreeMap<String, AccountAddress>,
+}
+
+impl<'a> InputParser<'a> {
+    fn new(input: &'a str, named_addresses: &'a BTreeMap<String, AccountAddress>) -> Self {
+        Self {
+            input,
+            position: 0,
+            named_addresses,
+        }
+    }
+
+    fn rest(&mut self) -> &'a str {
+        let rest = &self.input[self.position..];
+        let trimmed = rest.trim_start();
+        self.position += rest.len() - trimmed.len();
+        trimmed
+    }
+
+    fn error(&mut self, expected: &str) -> CliError {
+        let rest = self.rest();
+        let found = if rest.is_empty() {
+            "the end of the input".to_string()
+        } else {
+            format!("`{}`", rest)
+        };
+        CliError::CommandArgumentError(format!("Expected {}, found {}", expected, found))
+    }
+
+    /// Consumes the token if it comes next
+    fn eat(&mut self, token: &str) -> bool {
+        let found = self.rest().starts_with(token);
+        if found {
+            self.position += token.len();
+        }
+        found
+    }
+
+    fn expect(&mut self, token: &str) -> CliTypedResult<()> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("`{}`", token)))
+        }
+    }
+
+    fn finish(&mut self) -> CliTypedResult<()> {
+        if self.rest().is_empty() {
+            Ok(())
+        } else {
+            Err(self.error("the end of the input"))
+        }
+    }
+
+    fn word(&mut self, expected: &str) -> CliTypedResult<&'a str> {
+        let rest = self.rest();
+        let length = rest
+            .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+            .unwrap_or(rest.len());
+        if length == 0 {
+            return Err(self.error(expected));
+        }
+        self.position += length;
+        Ok(&rest[..length])
+    }
+
+    fn identifier(&mut self) -> CliTypedResult<Identifier> {
+        let word = self.word("an identifier")?;
+        Identifier::new(word).map_err(|err| CliError::UnableToParse("identifier", err.to_string()))
+    }
+
+    fn address(&mut self) -> CliTypedResult<AccountAddress> {
+        let word = self.word("an address")?;
+        if word.starts_with("0x") {
+            AccountAddress::from_hex_literal(word)
+                .map_err(|err| CliError::UnableToParse("address", err.to_string()))
+        } else {
+            self.named_addresses.get(word).copied().ok_or_else(|| {
+                CliError::CommandArgumentError(format!("Unknown named address `{}`", word))
+            })
+        }
+    }
+
+    fn call(&mut self) -> CliTypedResult<Call> {
+        let address = self.address()?;
+        self.expect("::")?;
+        let module = ModuleId::new(address, self.identifier()?);
+        self.expect("::")?;
+        let function = self.identifier()?;
+        let type_args = self.type_args()?;
+        self.expect("(")?;
+        let args = self.list(")", Self::literal)?;
+        Ok(Call {
+            module,
+            function,
+            type_args,
+            args,
+        })
+    }
+
+    /// Parses the items of a comma separated list, up to its closing token
+    fn list<T>(
+        &mut self,
+        close: &str,
+        item: impl Fn(&mut Self) -> CliTypedResult<T>,
+    ) -> CliTypedResult<Vec<T>> {
+        let mut items = vec![];
+        while !self.eat(close) {
+            items.push(item(self)?);
+            if !self.eat(",") {
+                self.expect(close)?;
+                break;
+            }
+        }
+        Ok(items)
+    }
+
+    fn type_args(&mut self) -> CliTypedResult<Vec<TypeTag>> {
+        if self.eat("<") {
+            self.list(">", Self::type_tag)
+        } else {
+            Ok(vec![])
+        }
+    }
+
+    fn type_tag(&mut self) -> CliTypedResult<TypeTag> {
+        let start = self.position;
+        Ok(match self.word("a type")? {
+            "bool" => TypeTag::Bool,
+            "u8" => TypeTag::U8,
+            "u16" => TypeTag::U16,
+            "u32" => TypeTag::U32,
+            "u64" => TypeTag::U64,
+            "u128" => TypeTag::U128,
+            "u256" => TypeTag::U256,
+            "address" => TypeTag::Address,
+            "signer" => TypeTag::Signer,
+            "vector" => {
+                self.expect("<")?;
+                let inner = self.type_tag()?;
+                self.expect(">")?;
+                TypeTag::Vector(Box::new(inner))
+            },
+            _ => {
+                self.position = start;
+                TypeTag::Struct(Box::new(self.struct_tag()?))
+            },
+        })
+    }
+
+    fn struct_tag(&mut self) -> CliTypedResult<StructTag> {
+        let address = self.address()?;
+        self.expect("::")?;
+        let module = self.identifier()?;
+        self.expect("::")?;
+        let name = self.identifier()?;
+        Ok(StructTag {
+            address,
+            module,
+            name,
+            type_params: self.type_args()?,
+        })
+    }
+
+    fn literal(&mut self) -> CliTypedResult<Literal> {
+        // References are passed by value, as in `&@0x1` for a `&signer`
+        self.eat("&");
+        if self.eat("[") {
+            return Ok(Literal::Vector(self.list("]", Self::literal)?));
+        }
+        if self.eat("@") {
+            return Ok(Literal::Address(self.address()?));
+        }
+        if self.eat("b\"") {
+            return Ok(Literal::Bytes(self.string()?.into_bytes()));
+        }
+        if self.eat("x\"") {
+            let hex = self.string()?;
+            return hex::decode(&hex)
+                .map(Literal::Bytes)
+                .map_err(|err| CliError::UnableToParse("hex string", err.to_string()));
+        }
+        if self.eat("\"") {
+            return Ok(Literal::String(self.string()?));
+        }
+        let word = self.word("a value")?;
+        Ok(match word {
+            "true" => Literal::Bool(true),
+            "false" => Literal::Bool(false),
+            _ if word.starts_with(|c: char| c.is_ascii_digit()) => match word.find('u') {
+                Some(index) => {
+                    Literal::Integer(word[..index].to_string(), Some(word[index..].to_string()))
+                },
+                None => Literal::Integer(word.to_string(), None),
+            },
+            _ => {
+                return Err(CliError::CommandArgumentError(format!(
+                    "Expected a value, found `{}`",
+                    word
+                )))
+            },
+        })
+    }
+
+    /// Parses the rest of a string literal, after its opening quote
+    fn string(&mut self) -> CliTypedResult<String> {
+        let mut string = String::new();
+        let mut chars = self.input[self.position..].char_indices();
+        while let Some((index, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.position += index + 1;
+                    return Ok(string);
+                },
+                '\\' => match chars.next() {
+                    Some((_, 'n')) => string.push('\n'),
+                    Some((_, 't')) => string.push('\t'),
+                    Some((_, c @ ('"' | '\\'))) => string.push(c),
+                    _ => break,
+                },
+                c => string.push(c),
+            }
+        }
+        Err(CliError::UnableToParse(
+            "string",
+            "Invalid escape sequence or missing closing quote".to_string(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_command, Call, Literal, ReplCommand, ReplSession};
+    use crate::move_tool::IncludedArtifacts;
+    use aptos_framework::BuiltPackage;
+    use aptos_state_view::in_memory_state_view::InMemoryStateView;
+    use move_core_types::{
+        account_address::AccountAddress,
+        identifier::Identifier,
+        language_storage::{ModuleId, StructTag, TypeTag, CORE_CODE_ADDRESS},
+        value::{MoveStruct, MoveValue},
+    };
+    use std::{
+        collections::{BTreeMap, HashMap},
+        str::FromStr,
+    };
+    use tempfile::TempDir;
+
+    const COUNTER_MODULE: &str = r#"
+module 0xcafe::counter {
+    struct Counter has key {
+        value: u64,
+    }
+
+    public entry fun publish(account: &signer, value: u64) {
+        move_to(account, Counter { value })
+    }
+
+    public fun value(addr: address): u64 acquires Counter {
+        borrow_global<Counter>(addr).value
+    }
+
+    fun publish_both(first: &signer, second: &signer, value: u64) {
+        publish(first, value);
+        publish(second, value + 1);
+    }
+}
+"#;
+
+    fn parse_call(input: &str) -> Call {
+        let named_addresses = BTreeMap::from([("std".to_string(), CORE_CODE_ADDRESS)]);
+        match parse_command(input, &named_addresses) {
+            Ok(Some(ReplCommand::Call(call))) => call,
+            _ => panic!("Failed to parse `{}`", input),
+        }
+    }
+
+    #[test]
+    fn test_parse_call() {
+        let coin = StructTag::from_str("0x1::aptos_coin::AptosCoin").unwrap();
+        assert_eq!(
+            parse_call("0x1::coin::transfer<0x1::aptos_coin::AptosCoin>(&@0xcafe, @std, 1_000u64)"),
+            Call {
+                module: ModuleId::new(CORE_CODE_ADDRESS, Identifier::new("coin").unwrap()),
+                function: Identifier::new("transfer").unwrap(),
+                type_args: vec![TypeTag::Struct(Box::new(coin))],
+                args: vec![
+                    Literal::Address(AccountAddress::from_hex_literal("0xcafe").unwrap()),
+                    Literal::Address(CORE_CODE_ADDRESS),
+                    Literal::Integer("1_000".to_string(), Some("u64".to_string())),
+                ],
+            }
+        );
+
+        let call = parse_call(
+            "std::m::f<vector<u8>, std::option::Option<u64>>([x\"0aff\", b\"a\\\"b\"], \"s\", true)",
+        );
+        assert_eq!(call.type_args.len(), 2);
+        assert_eq!(call.args, vec![
+            Literal::Vector(vec![
+                Literal::Bytes(vec![0x0A, 0xFF]),
+                Literal::Bytes(b"a\"b".to_vec()),
+            ]),
+            Literal::String("s".to_string()),
+            Literal::Bool(true),
+        ]);
+
+        let named_addresses = BTreeMap::new();
+        for input in [
+            "std::m::f()",
+            "0x1::m::f(1",
+            "0x1::m::f(1) 2",
+            "0x1::m::f(\"s)",
+        ] {
+            assert!(parse_command(input, &named_addresses).is_err(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_literal_to_move_value() {
+        let integer = |digits: &str, suffix: Option<&str>| {
+            Literal::Integer(digits.to_string(), suffix.map(str::to_string))
+        };
+        assert_eq!(
+            integer("0xff", None).to_move_value(&TypeTag::U8).unwrap(),
+            MoveValue::U8(255)
+        );
+        assert!(integer("256", None).to_move_value(&TypeTag::U8).is_err());
+        assert!(integer("1", Some("u64"))
+            .to_move_value(&TypeTag::U8)
+            .is_err());
+        assert_eq!(
+            Literal::Address(CORE_CODE_ADDRESS)
+                .to_move_value(&TypeTag::Signer)
+                .unwrap(),
+            MoveValue::Signer(CORE_CODE_ADDRESS)
+        );
+
+        let string = TypeTag::Struct(Box::new(
+            StructTag::from_str("0x1::string::String").unwrap(),
+        ));
+        assert_eq!(
+            Literal::String("a".to_string())
+                .to_move_value(&string)
+                .unwrap(),
+            MoveValue::Struct(MoveStruct::Runtime(vec![MoveValue::vector_u8(
+                b"a".to_vec()
+            )]))
+        );
+        assert!(Literal::Bool(true)
+            .to_move_value(&TypeTag::Vector(Box::new(string)))
+            .is_err());
+    }
+
+    fn counter_package() -> BuiltPackage {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("Move.toml"),
+            "[package]\nname = \"Counter\"\nversion = \"0.0.0\"\n",
+        )
+        .unwrap();
+        std::fs::create_dir(dir.path().join("sources")).unwrap();
+        std::fs::write(dir.path().join("sources/counter.move"), COUNTER_MODULE).unwrap();
+        let build_options =
+            IncludedArtifacts::None.build_options(false, true, BTreeMap::new(), None, false);
+        BuiltPackage::build(dir.path().to_path_buf(), build_options).unwrap()
+    }
+
+    /// Session on top of an empty state, with the package loaded
+    fn new_session(package: &BuiltPackage) -> ReplSession<InMemoryStateView> {
+        let mut session = ReplSession::new(InMemoryStateView::new(HashMap::new()), BTreeMap::new());
+        assert_eq!(session.load_package(package).unwrap(), 1);
+        session
+    }
+
+    fn run_call(
+        session: &mut ReplSession<InMemoryStateView>,
+        input: &str,
+    ) -> Result<String, String> {
+        session
+            .call(&parse_call(input))
+            .map_err(|err| err.to_string())
+    }
+
+    #[test]
+    fn test_session_calls() {
+        let package = counter_package();
+        let mut session = new_session(&package);
+
+        let output = run_call(
+            &mut session,
+            "0xcafe::counter::publish_both(&@0xa, &@0xb, 1)",
+        )
+        .unwrap();
+        assert!(
+            output.contains("Wrote resource 0xcafe::counter::Counter at 0xa"),
+            "{}",
+            output
+        );
+        assert!(
+            output.contains("Wrote resource 0xcafe::counter::Counter at 0xb"),
+            "{}",
+            output
+        );
+        // Changes are seen by the calls after them
+        assert_eq!(
+            run_call(&mut session, "0xcafe::counter::value(@0xb)").unwrap(),
+            "2"
+        );
+        let counter = StructTag::from_str("0xcafe::counter::Counter").unwrap();
+        let resource = session
+            .view_resource(AccountAddress::from_hex_literal("0xa").unwrap(), &counter)
+            .unwrap();
+        assert!(resource.contains("value: 1"), "{}", resource);
+
+        // A failed call changes nothing
+        assert!(run_call(&mut session, "0xcafe::counter::publish(&@0xa, 3)").is_err());
+        assert_eq!(
+            run_call(&mut session, "0xcafe::counter::value(@0xa)").unwrap(),
+            "1"
+        );
+
+        for input in [
+            "0xcafe::counter::missing()",
+            "0xbeef::counter::value(@0xa)",
+            "0xcafe::counter::value()",
+            "0xcafe::counter::value<u64>(@0xa)",
+            "0xcafe::counter::value(1)",
+        ] {
+            assert!(run_call(&mut session, input).is_err(), "{}", input);
+        }
+
+        // Resetting drops the changes of the session, but keeps the package
+        session.state.writes.clear();
+        session.load_package(&package).unwrap();
+        assert!(run_call(&mut session, "0xcafe::counter::value(@0xa)").is_err());
+    }
+
+    #[test]
+    fn test_first_signer_sends_call() {
+        let session = new_session(&counter_package());
+        let prepared = session
+            .prepare_call(&parse_call(
+                "0xcafe::counter::publish_both(&@0xa, &@0xb, 1)",
+            ))
+            .unwrap();
+        assert_eq!(
+            prepared.sender,
+            AccountAddress::from_hex_literal("0xa").unwrap()
+        );
+        assert_eq!(prepared.args.len(), 3);
+        assert_eq!(prepared.return_types, vec![]);
+
+        let prepared = session
+            .prepare_call(&parse_call("0xcafe::counter::value(@0xa)"))
+            .unwrap();
+        assert_eq!(prepared.sender, AccountAddress::ZERO);
+        assert_eq!(prepared.return_types, vec![TypeTag::U64]);
+    }
+}
@@ -0,0 +1,186 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::{
+    types::{CliCommand, CliError, CliTypedResult, ProfileOptions, RestOptions},
+    utils::write_gas_reports,
+};
+use aptos_debugger::AptosDebugger;
+use aptos_rest_client::aptos_api_types::TransactionData;
+use aptos_types::transaction::{Transaction, Version};
+use async_trait::async_trait;
+use clap::Parser;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// Replay a committed transaction locally, against the state of the chain right before it
+///
+/// With `--trace`, every executed instruction is recorded along with the resources read from
+/// storage, and with `--trace-values` also the locals and the operand stack of every frame. The
+/// trace is rendered against the source maps of the executed modules when they were published
+/// with them. The tracing options are only available if the CLI is built with the
+/// `instruction-tracing` feature.
+///
+/// With `--profile-gas`, the transaction is also replayed with the gas profiler, and an HTML
+/// report of its gas usage is written to the `gas-profiling` directory.
+#[derive(Parser)]
+pub struct Replay {
+    /// Version of the transaction to replay
+    #[clap(long)]
+    pub(crate) txn_id: Version,
+
+    /// Record a bytecode level trace of the transaction
+    #[cfg(feature = "instruction-tracing")]
+    #[clap(long)]
+    pub(crate) trace: bool,
+
+    /// Directory to write the trace to, defaults to the current directory
+    #[cfg(feature = "instruction-tracing")]
+    #[clap(long, value_parser, requires = "trace")]
+    pub(crate) trace_dir: Option<PathBuf>,
+
+    /// Also record the locals and the operand stack before every instruction, which makes the
+    /// trace much larger
+    #[cfg(feature = "instruction-tracing")]
+    #[clap(long, requires = "trace")]
+    pub(crate) trace_values: bool,
+
+    /// Replay the transaction with the gas profiler and write an HTML report of its gas usage
+    #[clap(long)]
+    pub(crate) profile_gas: bool,
+
+    /// Gas profile (.json) saved by an earlier run with `--profile-gas`, to compare the gas
+    /// usage against
+    #[clap(long, value_parser, requires = "profile_gas")]
+    pub(crate) profile_gas_baseline: Option<PathBuf>,
+
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReplaySummary {
+    pub version: Version,
+    pub vm_status: String,
+    pub gas_used: u64,
+    /// Whether replaying produced the same output as the one committed on chain
+    pub matches_committed_output: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trace_file: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rendered_trace_file: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gas_report_file: Option<PathBuf>,
+}
+
+#[async_trait]
+impl CliCommand<ReplaySummary> for Replay {
+    fn command_name(&self) -> &'static str {
+        "Replay"
+    }
+
+    async fn execute(self) -> CliTypedResult<ReplaySummary> {
+        let client = self.rest_options.client(&self.profile_options)?;
+        let (txn, info) = match client
+            .get_transaction_by_version_bcs(self.txn_id)
+            .await
+            .map_err(|err| CliError::ApiError(err.to_string()))?
+            .into_inner()
+        {
+            TransactionData::OnChain(data) => (data.transaction, data.info),
+            TransactionData::Pending(_) => {
+                return Err(CliError::UnexpectedError(format!(
+                    "Transaction {} is not committed yet",
+                    self.txn_id
+                )))
+            },
+        };
+
+        #[cfg(feature = "instruction-tracing")]
+        let trace_dir = match (self.trace, self.trace_dir) {
+            (false, _) => None,
+            (true, Some(trace_dir)) => Some(trace_dir),
+            (true, None) => Some(std::env::current_dir().map_err(|err| {
+                CliError::UnexpectedError(format!("Failed to get current directory: {}", err))
+            })?),
+        };
+
+        let profiled_txn = match &txn {
+            _ if !self.profile_gas => None,
+            Transaction::UserTransaction(signed_txn) => Some(signed_txn.clone()),
+            _ => {
+                return Err(CliError::CommandArgumentError(
+                    "Only user transactions can be profiled".to_string(),
+                ))
+            },
+        };
+
+        let debugger = AptosDebugger::rest_client(client.clone())
+            .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+        #[cfg(feature = "instruction-tracing")]
+        let debugger = match &trace_dir {
+            Some(trace_dir) => {
+                let detail = if self.trace_values {
+                    aptos_debugger::TraceDetail::Values
+                } else {
+                    aptos_debugger::TraceDetail::Instructions
+                };
+                debugger.with_trace_dir(trace_dir.clone(), detail)
+            },
+            None => debugger,
+        };
+        let output = debugger
+            .execute_transactions_at_version(self.txn_id, vec![txn])
+            .map_err(|err| {
+                CliError::UnexpectedError(format!("Failed to replay transaction: {}", err))
+            })?
+            .pop()
+            .ok_or_else(|| CliError::UnexpectedError("No output for transaction".to_string()))?;
+
+        let matches_committed_output = output
+            .ensure_match_transaction_info(self.txn_id, &info, None, None)
+            .is_ok();
+        let mut summary = ReplaySummary {
+            version: self.txn_id,
+            vm_status: format!("{:?}", output.status()),
+            gas_used: output.gas_used(),
+            matches_committed_output,
+            trace_file: None,
+            rendered_trace_file: None,
+            gas_report_file: None,
+        };
+
+        if let Some(signed_txn) = profiled_txn {
+            let (_, _, gas_log) = debugger
+                .execute_transaction_at_version_with_gas_profiler(self.txn_id, signed_txn)
+                .map_err(|err| {
+                    CliError::UnexpectedError(format!(
+                        "Failed to replay transaction with the gas profiler: {}",
+                        err
+                    ))
+                })?;
+            let dir = Path::new("gas-profiling");
+            let file_name = format!("txn-{}", self.txn_id);
+            write_gas_reports(
+                &gas_log,
+                dir,
+                &file_name,
+                &format!("Transaction {} -- Gas Report", self.txn_id),
+                self.profile_gas_baseline.as_deref(),
+            )?;
+            summary.gas_report_file = Some(dir.join(format!("{}.html", file_name)));
+        }
+
+        #[cfg(feature = "instruction-tracing")]
+        if let Some(trace_dir) = trace_dir {
+            let (trace_file, rendered_trace_file) =
+                super::replay_trace::write_rendered_trace(&client, &trace_dir, self.txn_id).await?;
+            summary.trace_file = Some(trace_file);
+            summary.rendered_trace_file = Some(rendered_trace_file);
+        }
+
+        Ok(summary)
+    }
+}
@@ -0,0 +1,199 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Rendering of the traces recorded by `aptos move replay --trace`, only compiled with the
+//! `instruction-tracing` feature.
+
+use crate::common::types::{CliError, CliTypedResult};
+use aptos_debugger::{AptosDebugger, FrameValues, TraceEvent, TransactionTrace};
+use aptos_framework::{natives::code::PackageRegistry, unzip_metadata, unzip_metadata_str};
+use aptos_rest_client::Client;
+use aptos_types::{account_address::AccountAddress, transaction::Version};
+use move_binary_format::file_format::FunctionDefinitionIndex;
+use move_bytecode_source_map::source_map::SourceMap;
+use move_core_types::language_storage::ModuleId;
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Renders the trace of the transaction at `version` written to `trace_dir`, and returns the
+/// files of the trace and of its rendering.
+pub(crate) async fn write_rendered_trace(
+    client: &Client,
+    trace_dir: &Path,
+    version: Version,
+) -> CliTypedResult<(PathBuf, PathBuf)> {
+    let trace_file = AptosDebugger::trace_file(trace_dir, version);
+    let trace: TransactionTrace =
+        bcs::from_bytes(&read_file(&trace_file)?).map_err(|err| CliError::BCS("trace", err))?;
+    let rendered = render_trace(client, version, &trace).await?;
+    let rendered_trace_file = trace_file.with_extension("trace.txt");
+    fs::write(&rendered_trace_file, rendered)
+        .map_err(|err| CliError::IO(rendered_trace_file.display().to_string(), err))?;
+    Ok((trace_file, rendered_trace_file))
+}
+
+fn read_file(path: &Path) -> CliTypedResult<Vec<u8>> {
+    fs::read(path).map_err(|err| CliError::IO(path.display().to_string(), err))
+}
+
+/// Source and source map of a module, as published on chain.
+struct ModuleSource {
+    source: String,
+    source_map: SourceMap,
+}
+
+impl ModuleSource {
+    /// Returns the 1-based line and its text for a byte offset into the source.
+    fn line(&self, offset: usize) -> Option<(usize, &str)> {
+        let offset = offset.min(self.source.len());
+        let line_start = self.source[..offset].rfind('\n').map_or(0, |idx| idx + 1);
+        let line_number = self.source[..offset].matches('\n').count() + 1;
+        let line = self.source[line_start..].lines().next()?;
+        Some((line_number, line.trim()))
+    }
+}
+
+/// Fetches the sources of the modules from the package registries at the state the transaction
+/// was executed against. Modules published without sources or source maps are skipped.
+async fn fetch_module_sources(
+    client: &Client,
+    version: Version,
+    modules: impl Iterator<Item = &ModuleId>,
+) -> BTreeMap<ModuleId, ModuleSource> {
+    let mut registries: BTreeMap<AccountAddress, Option<PackageRegistry>> = BTreeMap::new();
+    let mut sources = BTreeMap::new();
+    for module_id in modules {
+        if sources.contains_key(module_id) {
+            continue;
+        }
+        let address = *module_id.address();
+        if !registries.contains_key(&address) {
+            let registry = client
+                .get_account_resource_at_version_bcs::<PackageRegistry>(
+                    address,
+                    "0x1::code::PackageRegistry",
+                    version.saturating_sub(1),
+                )
+                .await
+                .ok()
+                .map(|response| response.into_inner());
+            registries.insert(address, registry);
+        }
+        let module = registries[&address].as_ref().and_then(|registry| {
+            registry
+                .packages
+                .iter()
+                .flat_map(|package| package.modules.iter())
+                .find(|module| module.name == module_id.name().as_str())
+        });
+        let source = module.and_then(|module| {
+            if module.source.is_empty() || module.source_map.is_empty() {
+                return None;
+            }
+            Some(ModuleSource {
+                source: unzip_metadata_str(&module.source).ok()?,
+                source_map: bcs::from_bytes(&unzip_metadata(&module.source_map).ok()?).ok()?,
+            })
+        });
+        if let Some(source) = source {
+            sources.insert(module_id.clone(), source);
+        }
+    }
+    sources
+}
+
+/// Renders a trace as text, one line per instruction indented by call depth, followed by the
+/// locals and the operand stack before executing it.
+async fn render_trace(
+    client: &Client,
+    version: Version,
+    trace: &TransactionTrace,
+) -> CliTypedResult<String> {
+    let modules = trace.events.iter().filter_map(|event| match event {
+        TraceEvent::Instruction { module, .. } => module.as_ref(),
+        TraceEvent::ResourceRead { .. } => None,
+    });
+    let sources = fetch_module_sources(client, version, modules).await;
+
+    let mut out = String::new();
+    let mut depth = 0;
+    for event in &trace.events {
+        // Writing to a `String` can't fail.
+        match event {
+            TraceEvent::Instruction {
+                call_depth,
+                module,
+                function,
+                function_index,
+                pc,
+                instruction,
+                values,
+            } => {
+                depth = *call_depth as usize;
+                let indent = "  ".repeat(depth);
+                let source = module.as_ref().and_then(|module| sources.get(module));
+                let fdef_idx = FunctionDefinitionIndex(*function_index);
+                let name = match module {
+                    Some(module) => format!("{}::{}", module.short_str_lossless(), function),
+                    None => format!("script::{}", function),
+                };
+                let location = source.and_then(|source| {
+                    let loc = source.source_map.get_code_location(fdef_idx, *pc).ok()?;
+                    let (line_number, line) = source.line(loc.start() as usize)?;
+                    let file = format!("{}.move", module.as_ref()?.name());
+                    Some(format!("  {}:{}  {}", file, line_number, line))
+                });
+                let _ = writeln!(
+                    out,
+                    "{}{}+{} {}{}",
+                    indent,
+                    name,
+                    pc,
+                    instruction,
+                    location.unwrap_or_default()
+                );
+                let Some(FrameValues {
+                    locals,
+                    operand_stack,
+                }) = values
+                else {
+                    continue;
+                };
+                for (idx, value) in locals.iter().enumerate() {
+                    let local_name = source
+                        .and_then(|source| {
+                            source
+                                .source_map
+                                .get_parameter_or_local_name(fdef_idx, idx as u64)
+                        })
+                        .map_or_else(|| format!("local#{}", idx), |(name, _)| name);
+                    let _ = writeln!(out, "{}    {} = {}", indent, local_name, value);
+                }
+                if !operand_stack.is_empty() {
+                    let _ = writeln!(out, "{}    stack: [{}]", indent, operand_stack.join(", "));
+                }
+            },
+            TraceEvent::ResourceRead {
+                address,
+                resource,
+                bytes,
+                exists,
+            } => {
+                let _ = writeln!(
+                    out,
+                    "{}  read {} at {}: {} bytes{}",
+                    "  ".repeat(depth),
+                    resource,
+                    address.to_hex_literal(),
+                    bytes,
+                    if *exists { "" } else { " (not found)" }
+                );
+            },
+        }
+    }
+    Ok(out)
+}
@@ -25,7 +25,7 @@ use aptos_backup_cli::{
     utils::GlobalRestoreOpt,
 };
 use aptos_cached_packages::aptos_stdlib;
-use aptos_config::config::NodeConfig;
+use aptos_config::config::{ForkConfig, ForkSource, NodeConfig, DEFAULT_FORK_CACHE_SIZE};
 use aptos_crypto::{bls12381, bls12381::PublicKey, x25519, ValidCryptoMaterialStringExt};
 use aptos_faucet_core::server::{FunderKeyEnum, RunConfig};
 use aptos_genesis::config::{HostAndPort, OperatorConfiguration};
@@ -46,6 +46,9 @@ use aptos_types::{
     validator_performances::ValidatorPerformances,
     vesting::VestingAdminStore,
 };
+use aptos_validator_interface::{
+    AptosValidatorInterface, DBDebuggerInterface, RestDebuggerInterface,
+};
 use async_trait::async_trait;
 use bcs::Result;
 use chrono::{DateTime, NaiveDateTime, Utc};
@@ -1108,10 +1111,78 @@ pub struct RunLocalTestnet {
     #[clap(long)]
     do_not_delegate: bool,
 
+    /// REST API endpoint of a fullnode of the network to fork, e.g. mainnet
+    ///
+    /// The local testnet still runs its own genesis, but any state it never wrote (e.g. accounts
+    /// and their resources) is lazily read from the forked network, as of `--fork-version`.
+    /// Forking only applies when a new chain is created, see `--force-restart`.
+    #[clap(long, value_parser, conflicts_with("fork_db_path"))]
+    fork_url: Option<Url>,
+
+    /// Path to a local DB of the network to fork, e.g. restored from a backup
+    ///
+    /// Same as `--fork-url`, but doesn't need network access.
+    #[clap(long, value_parser)]
+    fork_db_path: Option<PathBuf>,
+
+    /// Version of the forked network to read its state at
+    ///
+    /// Defaults to the latest version of the forked network.
+    #[clap(long)]
+    fork_version: Option<u64>,
+
+    /// Maximum number of state values read from the forked network that are cached in memory
+    #[clap(long, default_value_t = DEFAULT_FORK_CACHE_SIZE)]
+    fork_cache_size: usize,
+
     #[clap(flatten)]
     prompt_options: PromptOptions,
 }
 
+impl RunLocalTestnet {
+    /// Resolves the state to fork, if any was requested
+    async fn fork_config(&self) -> CliTypedResult<Option<ForkConfig>> {
+        let (source, remote): (ForkSource, Box<dyn AptosValidatorInterface + Send>) =
+            match (&self.fork_url, &self.fork_db_path) {
+                (Some(url), _) => (
+                    ForkSource::Rest { url: url.clone() },
+                    Box::new(RestDebuggerInterface::new(Client::new(url.clone()))),
+                ),
+                (None, Some(path)) => (
+                    ForkSource::Db { path: path.clone() },
+                    Box::new(DBDebuggerInterface::open(path).map_err(|err| {
+                        CliError::UnexpectedError(format!(
+                            "Failed to open DB to fork at {}: {}",
+                            path.display(),
+                            err
+                        ))
+                    })?),
+                ),
+                (None, None) if self.fork_version.is_some() => {
+                    return Err(CliError::CommandArgumentError(
+                        "--fork-version requires either --fork-url or --fork-db-path".to_string(),
+                    ))
+                },
+                (None, None) => return Ok(None),
+            };
+
+        let version = match self.fork_version {
+            Some(version) => version,
+            None => remote.get_latest_version().await.map_err(|err| {
+                CliError::UnexpectedError(format!(
+                    "Failed to get the latest version of the {}: {}",
+                    source, err
+                ))
+            })?,
+        };
+        Ok(Some(ForkConfig {
+            source,
+            version,
+            cache_size: self.fork_cache_size,
+        }))
+    }
+}
+
 #[async_trait]
 impl CliCommand<()> for RunLocalTestnet {
     fn command_name(&self) -> &'static str {
@@ -1143,6 +1214,18 @@ impl CliCommand<()> for RunLocalTestnet {
             })?;
         }
 
+        // An existing chain keeps the state it was created with
+        let fork_config = if test_dir.join("0").join("node.yaml").exists() {
+            if self.fork_url.is_some() || self.fork_db_path.is_some() {
+                eprintln!(
+                    "Ignoring the state to fork, as the existing chain is kept. Use --force-restart to create a new chain"
+                );
+            }
+            None
+        } else {
+            self.fork_config().await?
+        };
+
         // Spawn the node in a separate thread
         let config_path = self.config_path.clone();
         let test_dir_copy = test_dir.clone();
@@ -1153,6 +1236,7 @@ impl CliCommand<()> for RunLocalTestnet {
                 Some(test_dir_copy),
                 false,
                 false,
+                fork_config,
                 aptos_cached_packages::head_release_bundle(),
                 rng,
             );
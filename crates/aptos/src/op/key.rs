@@ -15,10 +15,11 @@ use crate::{
     CliCommand, CliResult,
 };
 use aptos_config::config::{Peer, PeerRole};
-use aptos_crypto::{bls12381, ed25519, x25519, PrivateKey, ValidCryptoMaterial};
+use aptos_crypto::{bls12381, ed25519, secp256k1_ecdsa, x25519, PrivateKey, ValidCryptoMaterial};
 use aptos_genesis::config::HostAndPort;
-use aptos_types::account_address::{
-    create_multisig_account_address, from_identity_public_key, AccountAddress,
+use aptos_types::{
+    account_address::{create_multisig_account_address, from_identity_public_key, AccountAddress},
+    transaction::authenticator::AuthenticationKey,
 };
 use async_trait::async_trait;
 use clap::{Parser, Subcommand};
@@ -161,7 +162,7 @@ impl NetworkKeyInputOptions {
     }
 }
 
-/// Generates a `x25519` or `ed25519` key.
+/// Generates a `x25519`, `ed25519`, `bls12381` or `secp256k1-ecdsa` key.
 ///
 /// This can be used for generating an identity.  Two files will be created
 /// `output_file` and `output_file.pub`.  `output_file` will contain the private
@@ -169,7 +170,7 @@ impl NetworkKeyInputOptions {
 /// key encoded with the `encoding`.
 #[derive(Debug, Parser)]
 pub struct GenerateKey {
-    /// Key type to generate. Must be one of [x25519, ed25519, bls12381]
+    /// Key type to generate. Must be one of [x25519, ed25519, bls12381, secp256k1-ecdsa]
     #[clap(long, default_value_t = KeyType::Ed25519)]
     pub(crate) key_type: KeyType,
     /// Vanity prefix that resultant account address should start with, e.g. 0xaceface or d00d. Each
@@ -256,6 +257,19 @@ impl CliCommand<HashMap<&'static str, PathBuf>> for GenerateKey {
                 let private_key = keygen.generate_bls12381_private_key();
                 self.save_params.save_bls_key(&private_key, "bls12381")
             },
+            KeyType::Secp256k1Ecdsa => {
+                let private_key = keygen.generate_secp256k1_ecdsa_private_key();
+                let account_address = AuthenticationKey::secp256k1_ecdsa(
+                    &secp256k1_ecdsa::Secp256k1EcdsaPublicKey::from(&private_key),
+                )
+                .derived_address();
+                let mut result_map = self.save_params.save_key(&private_key, "secp256k1-ecdsa")?;
+                result_map.insert(
+                    "Account Address:",
+                    PathBuf::from(account_address.to_hex_literal()),
+                );
+                Ok(result_map)
+            },
         }
     }
 }
@@ -830,6 +830,7 @@ impl CliTestFramework {
         CompilePackage {
             move_options: self.move_options(account_strs),
             save_metadata: false,
+            lint: false,
             included_artifacts_args: IncludedArtifactsArgs {
                 included_artifacts: included_artifacts.unwrap_or(IncludedArtifacts::Sparse),
             },
@@ -866,6 +867,7 @@ impl CliTestFramework {
             move_options: self.move_options(account_strs),
             txn_options: self.transaction_options(index, gas_options),
             override_size_check: false,
+            with_access_summaries: false,
             included_artifacts_args: IncludedArtifactsArgs {
                 included_artifacts: included_artifacts.unwrap_or(IncludedArtifacts::Sparse),
             },
@@ -7,6 +7,7 @@ use crate::{
 };
 use clap::Parser;
 use std::str::FromStr;
+use tempfile::TempDir;
 
 /// In order to ensure that there aren't duplicate input arguments for untested CLI commands,
 /// we call help on every command to ensure it at least runs
@@ -119,6 +120,32 @@ async fn ensure_can_parse_args_with_urls() {
     );
 }
 
+/// Commands submitting their transaction directly can't write it unsigned, and must not submit it
+#[tokio::test]
+async fn ensure_unsigned_output_file_is_rejected_by_transfer() {
+    let dir = TempDir::new().unwrap();
+    let output_file = dir.path().join("transfer.bcs");
+    let result = run_cmd(&[
+        "aptos",
+        "account",
+        "transfer",
+        "--account",
+        "0xcafe",
+        "--amount",
+        "1",
+        "--sender-account",
+        "0xbeef",
+        "--url",
+        "http://127.0.0.1:1",
+        "--unsigned-output-file",
+        output_file.to_str().unwrap(),
+    ])
+    .await;
+    let err = result.unwrap_err();
+    assert!(err.contains("--unsigned-output-file"), "{}", err);
+    assert!(!output_file.exists());
+}
+
 async fn assert_cmd_not_panic(args: &[&str]) {
     // When a command fails, it will have a panic in it due to an improperly setup command
     // thread 'main' panicked at 'Command propose: Argument names must be unique, but 'assume-yes' is
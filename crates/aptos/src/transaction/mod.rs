@@ -0,0 +1,240 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::types::{CliCommand, CliError, CliResult, CliTypedResult};
+use aptos_crypto::{ed25519::Ed25519PrivateKey, PrivateKey, SigningKey};
+use aptos_types::{
+    account_address::AccountAddress,
+    transaction::{
+        authenticator::AccountAuthenticator, RawTransaction, RawTransactionWithData,
+        SignedTransaction,
+    },
+};
+use clap::Subcommand;
+use serde::{Deserialize, Serialize};
+
+pub mod sign;
+pub mod submit;
+
+/// Tool for signing and submitting transactions separately
+///
+/// Transactions written with `--unsigned-output-file` can be signed on a machine without network
+/// access, e.g. one holding the keys of a treasury, and then be submitted from another one.
+#[derive(Debug, Subcommand)]
+pub enum TransactionTool {
+    Sign(sign::SignTransaction),
+    Submit(submit::SubmitTransaction),
+}
+
+impl TransactionTool {
+    pub async fn execute(self) -> CliResult {
+        match self {
+            TransactionTool::Sign(tool) => tool.execute_serialized().await,
+            TransactionTool::Submit(tool) => tool.execute_serialized().await,
+        }
+    }
+}
+
+/// A transaction being signed offline, along with the signatures collected so far.
+///
+/// Multi-agent and fee payer transactions need the signatures of several accounts, which can be
+/// added one after the other by passing the partially signed transaction on to the next signer.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct OfflineTransaction {
+    raw_txn: RawTransaction,
+    secondary_signer_addresses: Vec<AccountAddress>,
+    fee_payer_address: Option<AccountAddress>,
+    sender_signature: Option<AccountAuthenticator>,
+    secondary_signatures: Vec<Option<AccountAuthenticator>>,
+    fee_payer_signature: Option<AccountAuthenticator>,
+}
+
+impl OfflineTransaction {
+    pub fn new(
+        raw_txn: RawTransaction,
+        secondary_signer_addresses: Vec<AccountAddress>,
+        fee_payer_address: Option<AccountAddress>,
+    ) -> Self {
+        Self {
+            raw_txn,
+            secondary_signatures: vec![None; secondary_signer_addresses.len()],
+            secondary_signer_addresses,
+            fee_payer_address,
+            sender_signature: None,
+            fee_payer_signature: None,
+        }
+    }
+
+    pub fn raw_txn(&self) -> &RawTransaction {
+        &self.raw_txn
+    }
+
+    fn is_single_signer(&self) -> bool {
+        self.secondary_signer_addresses.is_empty() && self.fee_payer_address.is_none()
+    }
+
+    /// Signs the transaction for every role the signer has in it, i.e. sender, secondary signer
+    /// or fee payer. Returns the number of signatures added.
+    pub fn sign(
+        &mut self,
+        signer: AccountAddress,
+        private_key: &Ed25519PrivateKey,
+    ) -> CliTypedResult<usize> {
+        let signature = if self.is_single_signer() {
+            private_key.sign(&self.raw_txn)?
+        } else if let Some(fee_payer_address) = self.fee_payer_address {
+            private_key.sign(&RawTransactionWithData::new_fee_payer(
+                self.raw_txn.clone(),
+                self.secondary_signer_addresses.clone(),
+                fee_payer_address,
+            ))?
+        } else {
+            private_key.sign(&RawTransactionWithData::new_multi_agent(
+                self.raw_txn.clone(),
+                self.secondary_signer_addresses.clone(),
+            ))?
+        };
+        let authenticator = AccountAuthenticator::ed25519(private_key.public_key(), signature);
+
+        let mut num_signatures = 0;
+        if self.raw_txn.sender() == signer {
+            self.sender_signature = Some(authenticator.clone());
+            num_signatures += 1;
+        }
+        for (address, signature) in self
+            .secondary_signer_addresses
+            .iter()
+            .zip(self.secondary_signatures.iter_mut())
+        {
+            if *address == signer {
+                *signature = Some(authenticator.clone());
+                num_signatures += 1;
+            }
+        }
+        if self.fee_payer_address == Some(signer) {
+            self.fee_payer_signature = Some(authenticator);
+            num_signatures += 1;
+        }
+        Ok(num_signatures)
+    }
+
+    /// Accounts which still have to sign the transaction.
+    pub fn missing_signers(&self) -> Vec<AccountAddress> {
+        let mut missing_signers = vec![];
+        if self.sender_signature.is_none() {
+            missing_signers.push(self.raw_txn.sender());
+        }
+        for (address, signature) in self
+            .secondary_signer_addresses
+            .iter()
+            .zip(self.secondary_signatures.iter())
+        {
+            if signature.is_none() {
+                missing_signers.push(*address);
+            }
+        }
+        if let (Some(address), None) = (self.fee_payer_address, &self.fee_payer_signature) {
+            missing_signers.push(address);
+        }
+        missing_signers
+    }
+
+    /// Builds the signed transaction, once all of its signatures have been collected.
+    pub fn into_signed_transaction(self) -> CliTypedResult<SignedTransaction> {
+        let missing_signers = self.missing_signers();
+        if !missing_signers.is_empty() {
+            return Err(CliError::CommandArgumentError(format!(
+                "Transaction is still missing the signatures of {:?}",
+                missing_signers
+            )));
+        }
+        let sender = self.sender_signature.expect("Checked by missing_signers");
+        let secondary_signers = self.secondary_signatures.into_iter().flatten().collect();
+
+        Ok(match (self.fee_payer_address, self.fee_payer_signature) {
+            (Some(fee_payer_address), Some(fee_payer_signer)) => SignedTransaction::new_fee_payer(
+                self.raw_txn,
+                sender,
+                self.secondary_signer_addresses,
+                secondary_signers,
+                fee_payer_address,
+                fee_payer_signer,
+            ),
+            _ if self.secondary_signer_addresses.is_empty() => match sender {
+                AccountAuthenticator::Ed25519 {
+                    public_key,
+                    signature,
+                } => SignedTransaction::new(self.raw_txn, public_key, signature),
+                _ => {
+                    return Err(CliError::UnexpectedError(
+                        "Only Ed25519 signatures are supported".to_string(),
+                    ))
+                },
+            },
+            _ => SignedTransaction::new_multi_agent(
+                self.raw_txn,
+                sender,
+                self.secondary_signer_addresses,
+                secondary_signers,
+            ),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OfflineTransaction;
+    use crate::common::types::account_address_from_public_key;
+    use aptos_crypto::PrivateKey;
+    use aptos_keygen::KeyGen;
+    use aptos_types::{
+        chain_id::ChainId,
+        transaction::{RawTransaction, Script, TransactionPayload},
+    };
+
+    #[test]
+    fn test_sign_fee_payer_transaction_offline() {
+        let mut keygen = KeyGen::from_os_rng();
+        let keys: Vec<_> = (0..3)
+            .map(|_| keygen.generate_ed25519_private_key())
+            .collect();
+        let addresses: Vec<_> = keys
+            .iter()
+            .map(|key| account_address_from_public_key(&key.public_key()))
+            .collect();
+
+        let raw_txn = RawTransaction::new(
+            addresses[0],
+            0,
+            TransactionPayload::Script(Script::new(vec![], vec![], vec![])),
+            1000,
+            100,
+            u64::MAX,
+            ChainId::test(),
+        );
+        let mut transaction =
+            OfflineTransaction::new(raw_txn, vec![addresses[1]], Some(addresses[2]));
+        assert_eq!(transaction.missing_signers(), addresses);
+
+        // Signers can sign in any order, and unrelated accounts can't sign
+        let unrelated_key = keygen.generate_ed25519_private_key();
+        let unrelated_address = account_address_from_public_key(&unrelated_key.public_key());
+        assert_eq!(
+            transaction.sign(unrelated_address, &unrelated_key).unwrap(),
+            0
+        );
+        for idx in [2, 0] {
+            assert_eq!(transaction.sign(addresses[idx], &keys[idx]).unwrap(), 1);
+        }
+        assert_eq!(transaction.missing_signers(), vec![addresses[1]]);
+        assert!(transaction.clone().into_signed_transaction().is_err());
+
+        transaction.sign(addresses[1], &keys[1]).unwrap();
+        let signed_txn = transaction.into_signed_transaction().unwrap();
+        assert_eq!(
+            signed_txn.authenticator().fee_payer_address(),
+            Some(addresses[2])
+        );
+        signed_txn.check_signature().unwrap();
+    }
+}
@@ -0,0 +1,208 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    common::{
+        types::{
+            CliCommand, CliError, CliTypedResult, EncodingOptions, PrivateKeyInputOptions,
+            ProfileOptions, PromptOptions,
+        },
+        utils::{check_if_file_exists, prompt_yes_with_override, read_from_file, write_to_file},
+    },
+    transaction::OfflineTransaction,
+};
+use aptos_types::account_address::AccountAddress;
+use async_trait::async_trait;
+use clap::Parser;
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Sign a transaction written with `--unsigned-output-file`, without connecting to a node
+///
+/// The transaction is signed for every role the account has in it: sender, secondary signer or
+/// fee payer. Once all signatures are collected, the signed transaction is written to the output
+/// file, ready for `aptos transaction submit`. Until then, the partially signed transaction is
+/// written instead, to be passed on to the remaining signers.
+#[derive(Debug, Parser)]
+pub struct SignTransaction {
+    /// Unsigned or partially signed transaction file
+    #[clap(long, value_parser)]
+    pub(crate) transaction_file: PathBuf,
+
+    /// File to write the signed or partially signed transaction to
+    #[clap(long, value_parser)]
+    pub(crate) output_file: PathBuf,
+
+    /// Address of the signing account
+    ///
+    /// Defaults to the account of the profile, or the one derived from the private key
+    #[clap(long, value_parser = crate::common::types::load_account_arg)]
+    pub(crate) signer_account: Option<AccountAddress>,
+
+    #[clap(flatten)]
+    pub(crate) private_key_options: PrivateKeyInputOptions,
+    #[clap(flatten)]
+    pub(crate) encoding_options: EncodingOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+    #[clap(flatten)]
+    pub(crate) prompt_options: PromptOptions,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SignSummary {
+    pub signer: AccountAddress,
+    /// Whether all signatures have been collected
+    pub complete: bool,
+    pub missing_signers: Vec<AccountAddress>,
+    pub output_file: PathBuf,
+}
+
+#[async_trait]
+impl CliCommand<SignSummary> for SignTransaction {
+    fn command_name(&self) -> &'static str {
+        "SignTransaction"
+    }
+
+    async fn execute(self) -> CliTypedResult<SignSummary> {
+        check_if_file_exists(&self.output_file, self.prompt_options)?;
+        let mut transaction: OfflineTransaction =
+            bcs::from_bytes(&read_from_file(&self.transaction_file)?)
+                .map_err(|err| CliError::BCS("transaction", err))?;
+        let (private_key, signer) = self.private_key_options.extract_private_key_and_address(
+            self.encoding_options.encoding,
+            &self.profile_options,
+            self.signer_account,
+        )?;
+
+        prompt_yes_with_override(
+            &format!(
+                "Do you want to sign the following transaction as {}?\n{:#?}\n",
+                signer,
+                transaction.raw_txn()
+            ),
+            self.prompt_options,
+        )?;
+        if transaction.sign(signer, &private_key)? == 0 {
+            return Err(CliError::CommandArgumentError(format!(
+                "Account {} is not a signer of the transaction",
+                signer
+            )));
+        }
+
+        let missing_signers = transaction.missing_signers();
+        let complete = missing_signers.is_empty();
+        let bytes = if complete {
+            bcs::to_bytes(&transaction.into_signed_transaction()?)?
+        } else {
+            bcs::to_bytes(&transaction)?
+        };
+        write_to_file(
+            &self.output_file,
+            &self.output_file.display().to_string(),
+            &bytes,
+        )?;
+
+        Ok(SignSummary {
+            signer,
+            complete,
+            missing_signers,
+            output_file: self.output_file,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::types::account_address_from_public_key;
+    use aptos_crypto::{ed25519::Ed25519PrivateKey, PrivateKey};
+    use aptos_keygen::KeyGen;
+    use aptos_types::{
+        chain_id::ChainId,
+        transaction::{RawTransaction, Script, SignedTransaction, TransactionPayload},
+    };
+    use tempfile::TempDir;
+
+    fn sign_transaction(
+        transaction_file: PathBuf,
+        output_file: PathBuf,
+        private_key: &Ed25519PrivateKey,
+    ) -> SignTransaction {
+        SignTransaction {
+            transaction_file,
+            output_file,
+            signer_account: None,
+            private_key_options: PrivateKeyInputOptions::from_private_key(private_key).unwrap(),
+            encoding_options: EncodingOptions::default(),
+            profile_options: ProfileOptions::default(),
+            prompt_options: PromptOptions::yes(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sign_writes_partial_then_complete_transaction() {
+        let mut keygen = KeyGen::from_os_rng();
+        let sender_key = keygen.generate_ed25519_private_key();
+        let secondary_key = keygen.generate_ed25519_private_key();
+        let sender = account_address_from_public_key(&sender_key.public_key());
+        let secondary_signer = account_address_from_public_key(&secondary_key.public_key());
+
+        let dir = TempDir::new().unwrap();
+        let unsigned_file = dir.path().join("unsigned.txn");
+        let partial_file = dir.path().join("partial.txn");
+        let signed_file = dir.path().join("signed.txn");
+        let raw_txn = RawTransaction::new(
+            sender,
+            0,
+            TransactionPayload::Script(Script::new(vec![], vec![], vec![])),
+            1000,
+            100,
+            u64::MAX,
+            ChainId::test(),
+        );
+        let transaction = OfflineTransaction::new(raw_txn, vec![secondary_signer], None);
+        write_to_file(
+            &unsigned_file,
+            "unsigned.txn",
+            &bcs::to_bytes(&transaction).unwrap(),
+        )
+        .unwrap();
+
+        // Only the sender signed: the partially signed transaction is written
+        let summary = sign_transaction(unsigned_file.clone(), partial_file.clone(), &sender_key)
+            .execute()
+            .await
+            .unwrap();
+        assert_eq!(summary.signer, sender);
+        assert!(!summary.complete);
+        assert_eq!(summary.missing_signers, vec![secondary_signer]);
+        let partial: OfflineTransaction =
+            bcs::from_bytes(&read_from_file(&partial_file).unwrap()).unwrap();
+        assert_eq!(partial.missing_signers(), vec![secondary_signer]);
+
+        // An account which isn't a signer of the transaction can't sign it
+        let unrelated_key = keygen.generate_ed25519_private_key();
+        assert!(sign_transaction(
+            partial_file.clone(),
+            dir.path().join("unrelated.txn"),
+            &unrelated_key
+        )
+        .execute()
+        .await
+        .is_err());
+
+        // All signatures collected: the signed transaction is written
+        let summary = sign_transaction(partial_file, signed_file.clone(), &secondary_key)
+            .execute()
+            .await
+            .unwrap();
+        assert_eq!(summary.signer, secondary_signer);
+        assert!(summary.complete);
+        assert!(summary.missing_signers.is_empty());
+        let signed_txn: SignedTransaction =
+            bcs::from_bytes(&read_from_file(&signed_file).unwrap()).unwrap();
+        assert_eq!(signed_txn.sender(), sender);
+        signed_txn.check_signature().unwrap();
+    }
+}
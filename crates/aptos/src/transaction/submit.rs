@@ -0,0 +1,45 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::{
+    types::{
+        CliCommand, CliError, CliTypedResult, ProfileOptions, RestOptions, TransactionSummary,
+    },
+    utils::read_from_file,
+};
+use aptos_types::transaction::SignedTransaction;
+use async_trait::async_trait;
+use clap::Parser;
+use std::path::PathBuf;
+
+/// Submit a signed transaction file, e.g. written by `aptos transaction sign`, and wait for it
+#[derive(Debug, Parser)]
+pub struct SubmitTransaction {
+    /// BCS encoded signed transaction file
+    #[clap(long, value_parser)]
+    pub(crate) transaction_file: PathBuf,
+
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+}
+
+#[async_trait]
+impl CliCommand<TransactionSummary> for SubmitTransaction {
+    fn command_name(&self) -> &'static str {
+        "SubmitTransaction"
+    }
+
+    async fn execute(self) -> CliTypedResult<TransactionSummary> {
+        let transaction: SignedTransaction =
+            bcs::from_bytes(&read_from_file(&self.transaction_file)?)
+                .map_err(|err| CliError::BCS("signed transaction", err))?;
+        let client = self.rest_options.client(&self.profile_options)?;
+        let response = client
+            .submit_and_wait(&transaction)
+            .await
+            .map_err(|err| CliError::ApiError(err.to_string()))?;
+        Ok(TransactionSummary::from(response.into_inner()))
+    }
+}
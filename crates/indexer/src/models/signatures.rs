@@ -5,10 +5,12 @@
 use crate::{models::transactions::Transaction, schema::signatures, util::standardize_address};
 use anyhow::{Context, Result};
 use aptos_api_types::{
-    AccountSignature as APIAccountSignature, Ed25519Signature as APIEd25519Signature,
-    FeePayerSignature as APIFeePayerSignature, MultiAgentSignature as APIMultiAgentSignature,
+    AbstractionSignature as APIAbstractionSignature, AccountSignature as APIAccountSignature,
+    Ed25519Signature as APIEd25519Signature, FeePayerSignature as APIFeePayerSignature,
+    MultiAgentSignature as APIMultiAgentSignature,
     MultiEd25519Signature as APIMultiEd25519Signature,
-    TransactionSignature as APITransactionSignature,
+    Secp256k1EcdsaSignature as APISecp256k1EcdsaSignature,
+    TransactionSignature as APITransactionSignature, WebAuthnSignature as APIWebAuthnSignature,
 };
 use aptos_bitvec::BitVec;
 use field_count::FieldCount;
@@ -80,6 +82,39 @@ impl Signature {
                 transaction_version,
                 transaction_block_height,
             ),
+            APITransactionSignature::Secp256k1EcdsaSignature(sig) => {
+                Ok(vec![Self::parse_secp256k1_ecdsa_signature(
+                    sig,
+                    sender,
+                    transaction_version,
+                    transaction_block_height,
+                    true,
+                    0,
+                    None,
+                )])
+            },
+            APITransactionSignature::WebAuthnSignature(sig) => {
+                Ok(vec![Self::parse_webauthn_signature(
+                    sig,
+                    sender,
+                    transaction_version,
+                    transaction_block_height,
+                    true,
+                    0,
+                    None,
+                )])
+            },
+            APITransactionSignature::AbstractionSignature(sig) => {
+                Ok(vec![Self::parse_abstraction_signature(
+                    sig,
+                    sender,
+                    transaction_version,
+                    transaction_block_height,
+                    true,
+                    0,
+                    None,
+                )])
+            },
         }
     }
 
@@ -93,6 +128,13 @@ impl Signature {
                 String::from("multi_agent_signature")
             },
             APITransactionSignature::FeePayerSignature(_) => String::from("fee_payer_signature"),
+            APITransactionSignature::Secp256k1EcdsaSignature(_) => {
+                String::from("secp256k1_ecdsa_signature")
+            },
+            APITransactionSignature::WebAuthnSignature(_) => String::from("web_authn_signature"),
+            APITransactionSignature::AbstractionSignature(_) => {
+                String::from("abstraction_signature")
+            },
         }
     }
 
@@ -121,6 +163,84 @@ impl Signature {
         }
     }
 
+    fn parse_secp256k1_ecdsa_signature(
+        s: &APISecp256k1EcdsaSignature,
+        sender: &String,
+        transaction_version: i64,
+        transaction_block_height: i64,
+        is_sender_primary: bool,
+        multi_agent_index: i64,
+        override_address: Option<&String>,
+    ) -> Self {
+        let signer = standardize_address(override_address.unwrap_or(sender));
+        Self {
+            transaction_version,
+            transaction_block_height,
+            signer,
+            is_sender_primary,
+            type_: String::from("secp256k1_ecdsa_signature"),
+            public_key: s.public_key.to_string(),
+            threshold: 1,
+            public_key_indices: serde_json::Value::Array(vec![]),
+            signature: s.signature.to_string(),
+            multi_agent_index,
+            multi_sig_index: 0,
+        }
+    }
+
+    /// Only the public key and the signature of the assertion are stored, the authenticator and
+    /// client data are dropped.
+    fn parse_webauthn_signature(
+        s: &APIWebAuthnSignature,
+        sender: &String,
+        transaction_version: i64,
+        transaction_block_height: i64,
+        is_sender_primary: bool,
+        multi_agent_index: i64,
+        override_address: Option<&String>,
+    ) -> Self {
+        let signer = standardize_address(override_address.unwrap_or(sender));
+        Self {
+            transaction_version,
+            transaction_block_height,
+            signer,
+            is_sender_primary,
+            type_: String::from("web_authn_signature"),
+            public_key: s.public_key.to_string(),
+            threshold: 1,
+            public_key_indices: serde_json::Value::Array(vec![]),
+            signature: s.signature.to_string(),
+            multi_agent_index,
+            multi_sig_index: 0,
+        }
+    }
+
+    /// Account abstraction has no public key, the authenticator is stored as the signature.
+    fn parse_abstraction_signature(
+        s: &APIAbstractionSignature,
+        sender: &String,
+        transaction_version: i64,
+        transaction_block_height: i64,
+        is_sender_primary: bool,
+        multi_agent_index: i64,
+        override_address: Option<&String>,
+    ) -> Self {
+        let signer = standardize_address(override_address.unwrap_or(sender));
+        Self {
+            transaction_version,
+            transaction_block_height,
+            signer,
+            is_sender_primary,
+            type_: String::from("abstraction_signature"),
+            public_key: String::new(),
+            threshold: 1,
+            public_key_indices: serde_json::Value::Array(vec![]),
+            signature: s.authenticator.to_string(),
+            multi_agent_index,
+            multi_sig_index: 0,
+        }
+    }
+
     fn parse_multi_signature(
         s: &APIMultiEd25519Signature,
         sender: &String,
@@ -271,6 +391,37 @@ impl Signature {
                 multi_agent_index,
                 override_address,
             ),
+            APIAccountSignature::Secp256k1EcdsaSignature(sig) => {
+                vec![Self::parse_secp256k1_ecdsa_signature(
+                    sig,
+                    sender,
+                    transaction_version,
+                    transaction_block_height,
+                    is_sender_primary,
+                    multi_agent_index,
+                    override_address,
+                )]
+            },
+            APIAccountSignature::WebAuthnSignature(sig) => vec![Self::parse_webauthn_signature(
+                sig,
+                sender,
+                transaction_version,
+                transaction_block_height,
+                is_sender_primary,
+                multi_agent_index,
+                override_address,
+            )],
+            APIAccountSignature::AbstractionSignature(sig) => {
+                vec![Self::parse_abstraction_signature(
+                    sig,
+                    sender,
+                    transaction_version,
+                    transaction_block_height,
+                    is_sender_primary,
+                    multi_agent_index,
+                    override_address,
+                )]
+            },
         }
     }
 }
@@ -34,7 +34,8 @@ impl MovingAverage {
             match self.values.front() {
                 None => break,
                 Some((ts, val)) => {
-                    if timestamp_millis - ts > self.window_millis {
+                    // Saturate, in case the clock stepped backwards
+                    if timestamp_millis.saturating_sub(*ts) > self.window_millis {
                         self.sum -= val;
                         self.values.pop_front();
                     } else {
@@ -50,12 +51,50 @@ impl MovingAverage {
         if self.values.len() < 2 {
             0.0
         } else {
-            let elapsed = self.values.back().unwrap().0 - self.values.front().unwrap().0;
-            self.sum as f64 / elapsed as f64
+            let elapsed = self
+                .values
+                .back()
+                .unwrap()
+                .0
+                .saturating_sub(self.values.front().unwrap().0);
+            if elapsed == 0 {
+                0.0
+            } else {
+                self.sum as f64 / elapsed as f64
+            }
         }
     }
 
     pub fn sum(&self) -> u64 {
         self.sum
     }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MovingAverage;
+
+    #[test]
+    fn test_clock_steps_backwards() {
+        let mut moving_average = MovingAverage::new(1_000);
+        moving_average.tick(10_000, 1);
+        moving_average.tick(11_000, 1);
+
+        // A timestamp before the existing values should not panic or evict anything
+        assert_eq!(moving_average.tick(5_000, 1), 0.0);
+        assert_eq!(moving_average.len(), 3);
+        assert_eq!(moving_average.sum(), 3);
+
+        // Once the clock moves forward again, old values are evicted
+        moving_average.tick(12_500, 1);
+        assert_eq!(moving_average.len(), 1);
+    }
 }
@@ -564,20 +564,30 @@ pub fn convert_account_signature(
         AccountSignature::MultiEd25519Signature(_) => {
             transaction::account_signature::Type::MultiEd25519
         },
+        // The protobuf schema has no representation for secp256k1 ecdsa, webauthn and
+        // abstraction signatures yet
+        AccountSignature::Secp256k1EcdsaSignature(_)
+        | AccountSignature::WebAuthnSignature(_)
+        | AccountSignature::AbstractionSignature(_) => {
+            transaction::account_signature::Type::Unspecified
+        },
     };
     let signature = match account_signature {
-        AccountSignature::Ed25519Signature(s) => {
-            transaction::account_signature::Signature::Ed25519(convert_ed25519_signature(s))
-        },
+        AccountSignature::Ed25519Signature(s) => Some(
+            transaction::account_signature::Signature::Ed25519(convert_ed25519_signature(s)),
+        ),
         AccountSignature::MultiEd25519Signature(s) => {
-            transaction::account_signature::Signature::MultiEd25519(
+            Some(transaction::account_signature::Signature::MultiEd25519(
                 convert_multi_ed25519_signature(s),
-            )
+            ))
         },
+        AccountSignature::Secp256k1EcdsaSignature(_)
+        | AccountSignature::WebAuthnSignature(_)
+        | AccountSignature::AbstractionSignature(_) => None,
     };
     transaction::AccountSignature {
         r#type: r#type as i32,
-        signature: Some(signature),
+        signature,
     }
 }
 
@@ -595,16 +605,23 @@ pub fn convert_transaction_signature(
         },
         TransactionSignature::MultiAgentSignature(_) => transaction::signature::Type::MultiAgent,
         TransactionSignature::FeePayerSignature(_) => transaction::signature::Type::FeePayer,
+        // The protobuf schema has no representation for secp256k1 ecdsa, webauthn and
+        // abstraction signatures yet
+        TransactionSignature::Secp256k1EcdsaSignature(_)
+        | TransactionSignature::WebAuthnSignature(_)
+        | TransactionSignature::AbstractionSignature(_) => {
+            transaction::signature::Type::Unspecified
+        },
     };
 
     let signature = match signature {
-        TransactionSignature::Ed25519Signature(s) => {
-            transaction::signature::Signature::Ed25519(convert_ed25519_signature(s))
-        },
-        TransactionSignature::MultiEd25519Signature(s) => {
-            transaction::signature::Signature::MultiEd25519(convert_multi_ed25519_signature(s))
-        },
-        TransactionSignature::MultiAgentSignature(s) => {
+        TransactionSignature::Ed25519Signature(s) => Some(
+            transaction::signature::Signature::Ed25519(convert_ed25519_signature(s)),
+        ),
+        TransactionSignature::MultiEd25519Signature(s) => Some(
+            transaction::signature::Signature::MultiEd25519(convert_multi_ed25519_signature(s)),
+        ),
+        TransactionSignature::MultiAgentSignature(s) => Some(
             transaction::signature::Signature::MultiAgent(transaction::MultiAgentSignature {
                 sender: Some(convert_account_signature(&s.sender)),
                 secondary_signer_addresses: s
@@ -617,9 +634,9 @@ pub fn convert_transaction_signature(
                     .iter()
                     .map(convert_account_signature)
                     .collect(),
-            })
-        },
-        TransactionSignature::FeePayerSignature(s) => {
+            }),
+        ),
+        TransactionSignature::FeePayerSignature(s) => Some(
             transaction::signature::Signature::FeePayer(transaction::FeePayerSignature {
                 sender: Some(convert_account_signature(&s.sender)),
                 secondary_signer_addresses: s
@@ -634,13 +651,16 @@ pub fn convert_transaction_signature(
                     .collect(),
                 fee_payer_address: s.fee_payer_address.to_string(),
                 fee_payer_signer: Some(convert_account_signature(&s.fee_payer_signer)),
-            })
-        },
+            }),
+        ),
+        TransactionSignature::Secp256k1EcdsaSignature(_)
+        | TransactionSignature::WebAuthnSignature(_)
+        | TransactionSignature::AbstractionSignature(_) => None,
     };
 
     Some(transaction::Signature {
         r#type: r#type as i32,
-        signature: Some(signature),
+        signature,
     })
 }
 
@@ -6,7 +6,10 @@ use anyhow::{anyhow, bail, Result};
 use aptos_crypto::{hash::CryptoHash, HashValue};
 use aptos_scratchpad::{FrozenSparseMerkleTree, SparseMerkleTree};
 use aptos_state_view::account_with_state_cache::AsAccountWithStateCache;
-use aptos_storage_interface::{cached_state_view::StateCache, state_delta::StateDelta};
+use aptos_storage_interface::{
+    cached_state_view::StateCache,
+    state_delta::{ForkedDeletions, StateDelta},
+};
 use aptos_types::{
     account_config::CORE_CODE_ADDRESS,
     account_view::AccountView,
@@ -61,6 +64,8 @@ pub struct InMemoryStateCalculator {
     // on the non-frozen SMT.
     latest: FrozenSparseMerkleTree<StateValue>,
     updates_between_checkpoint_and_latest: ShardedStateUpdates,
+    // See `StateDelta::forked_deletions`, `None` if the state isn't forked.
+    forked_deletions: Option<ForkedDeletions>,
 }
 
 impl InMemoryStateCalculator {
@@ -69,6 +74,7 @@ impl InMemoryStateCalculator {
             frozen_base,
             sharded_state_cache,
             proofs,
+            forked_deletions,
         } = state_cache;
         let StateDelta {
             base,
@@ -76,6 +82,7 @@ impl InMemoryStateCalculator {
             current,
             current_version,
             updates_since_base,
+            forked_deletions: _,
         } = base.clone();
 
         // TODO(grao): Rethink the strategy for state sync, and optimize this.
@@ -98,6 +105,7 @@ impl InMemoryStateCalculator {
             checkpoint_version: base_version,
             latest: current.freeze(),
             updates_between_checkpoint_and_latest: updates_since_base,
+            forked_deletions,
         }
     }
 
@@ -146,6 +154,7 @@ impl InMemoryStateCalculator {
             Some(txn),
             &mut self.state_cache,
             &mut self.usage,
+            self.forked_deletions.is_some(),
             txn_output.write_set().clone(),
         )?;
         updated_state_kvs.iter().for_each(|(k, v)| {
@@ -168,6 +177,8 @@ impl InMemoryStateCalculator {
     }
 
     fn make_checkpoint(&mut self) -> Result<HashValue> {
+        self.update_forked_deletions();
+
         // Update SMT.
         let smt_updates: Vec<_> = self
             .updates_after_latest
@@ -206,6 +217,7 @@ impl InMemoryStateCalculator {
     }
 
     fn finish(mut self) -> Result<(StateDelta, HashMap<StateKey, StateValue>)> {
+        self.update_forked_deletions();
         let smt_updates: Vec<_> = self
             .updates_after_latest
             .iter()
@@ -231,6 +243,10 @@ impl InMemoryStateCalculator {
             self.next_version.checked_sub(1),
             self.updates_between_checkpoint_and_latest,
         );
+        let result_state = match self.forked_deletions {
+            Some(forked_deletions) => result_state.with_forked_deletions(forked_deletions),
+            None => result_state,
+        };
 
         Ok((
             result_state,
@@ -253,6 +269,7 @@ impl InMemoryStateCalculator {
                     None,
                     &mut self.state_cache,
                     &mut self.usage,
+                    self.forked_deletions.is_some(),
                     (*write_set).clone(),
                 )?;
                 self.insert_to_latest_updates(state_updates);
@@ -269,6 +286,7 @@ impl InMemoryStateCalculator {
                 None,
                 &mut self.state_cache,
                 &mut self.usage,
+                self.forked_deletions.is_some(),
                 (*write_set).clone(),
             )?;
             self.insert_to_latest_updates(state_updates);
@@ -278,6 +296,13 @@ impl InMemoryStateCalculator {
         Ok((updates_before_last_checkpoint, result_state))
     }
 
+    /// Applies the updates after `latest` to the keys deleted by the forked state, if any.
+    fn update_forked_deletions(&mut self) {
+        if let Some(forked_deletions) = &mut self.forked_deletions {
+            *forked_deletions = forked_deletions.apply(&self.updates_after_latest);
+        }
+    }
+
     fn insert_to_latest_updates(&mut self, state_updates: HashMap<StateKey, Option<StateValue>>) {
         state_updates.into_iter().for_each(|(k, v)| {
             self.updates_after_latest[k.get_shard_id() as usize].insert(k, v);
@@ -288,17 +313,26 @@ impl InMemoryStateCalculator {
 // Checks the write set is a subset of the read set.
 // Updates the `state_cache` to reflect the latest value.
 // Returns all state key-value pair touched.
+// The usage saturates if the state is forked, see `StateStorageUsage::remove_item_saturating`.
 pub fn process_write_set(
     transaction: Option<&Transaction>,
     state_cache: &mut DashMap<StateKey, Option<StateValue>>,
     usage: &mut StateStorageUsage,
+    is_state_forked: bool,
     write_set: WriteSet,
 ) -> Result<HashMap<StateKey, Option<StateValue>>> {
     // Find all keys this transaction touches while processing each write op.
     write_set
         .into_iter()
         .map(|(state_key, write_op)| {
-            process_state_key_write_op(transaction, state_cache, usage, state_key, write_op)
+            process_state_key_write_op(
+                transaction,
+                state_cache,
+                usage,
+                is_state_forked,
+                state_key,
+                write_op,
+            )
         })
         .collect::<Result<_>>()
 }
@@ -307,6 +341,7 @@ fn process_state_key_write_op(
     transaction: Option<&Transaction>,
     state_cache: &mut DashMap<StateKey, Option<StateValue>>,
     usage: &mut StateStorageUsage,
+    is_state_forked: bool,
     state_key: StateKey,
     write_op: WriteOp,
 ) -> Result<(StateKey, Option<StateValue>)> {
@@ -318,7 +353,11 @@ fn process_state_key_write_op(
     let cached = state_cache.insert(state_key.clone(), state_value.clone());
     if let Some(old_value_opt) = cached {
         if let Some(old_value) = old_value_opt {
-            usage.remove_item(key_size + old_value.size());
+            if is_state_forked {
+                usage.remove_item_saturating(key_size + old_value.size());
+            } else {
+                usage.remove_item(key_size + old_value.size());
+            }
         }
     } else if let Some(txn) = transaction {
         ensure_txn_valid_for_vacant_entry(txn)?;
@@ -105,13 +105,18 @@ impl InMemoryStateCalculatorV2 {
             frozen_base: _,
             sharded_state_cache,
             proofs,
+            forked_deletions,
         } = state_cache;
 
         let state_updates_vec = Self::get_sharded_state_updates(to_keep);
         let updates: ShardedStateUpdates = Self::calculate_block_state_updates(&state_updates_vec);
         let latest_checkpoint = base.current.clone();
-        let usage =
-            Self::calculate_usage(latest_checkpoint.usage(), &sharded_state_cache, &updates);
+        let usage = Self::calculate_usage(
+            latest_checkpoint.usage(),
+            &sharded_state_cache,
+            &updates,
+            forked_deletions.is_some(),
+        );
 
         let next_epoch_state = if new_epoch {
             Some(Self::get_epoch_state(&sharded_state_cache, &updates)?)
@@ -147,6 +152,12 @@ impl InMemoryStateCalculatorV2 {
             new_checkpoint_version,
             create_empty_sharded_state_updates(),
         );
+        let result_state = match forked_deletions {
+            Some(forked_deletions) => {
+                result_state.with_forked_deletions(forked_deletions.apply(&updates))
+            },
+            None => result_state,
+        };
 
         Ok((
             state_updates_vec,
@@ -212,10 +223,12 @@ impl InMemoryStateCalculatorV2 {
         updates
     }
 
+    /// Saturates if the state is forked, see `StateStorageUsage::apply_delta_saturating`.
     fn calculate_usage(
         old_usage: StateStorageUsage,
         sharded_state_cache: &ShardedStateCache,
         updates: &ShardedStateUpdates,
+        is_state_forked: bool,
     ) -> StateStorageUsage {
         let _timer = APTOS_EXECUTOR_OTHER_TIMERS_SECONDS
             .with_label_values(&["calculate_usage"])
@@ -250,10 +263,11 @@ impl InMemoryStateCalculatorV2 {
                     (items_now + items_delta, bytes_now + bytes_delta)
                 },
             );
-        StateStorageUsage::new(
-            (old_usage.items() as i64 + items_delta) as usize,
-            (old_usage.bytes() as i64 + bytes_delta) as usize,
-        )
+        if is_state_forked {
+            old_usage.apply_delta_saturating(items_delta, bytes_delta)
+        } else {
+            old_usage.apply_delta(items_delta, bytes_delta)
+        }
     }
 
     fn make_checkpoint(
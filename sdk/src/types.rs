@@ -5,19 +5,27 @@
 use crate::{
     crypto::{
         ed25519::{Ed25519PrivateKey, Ed25519PublicKey},
-        traits::Uniform,
+        hash::CryptoHash,
+        secp256k1_ecdsa::{Secp256k1EcdsaPrivateKey, Secp256k1EcdsaPublicKey},
+        secp256r1_ecdsa::{Secp256r1EcdsaPrivateKey, Secp256r1EcdsaPublicKey},
+        traits::{SigningKey, Uniform},
     },
     transaction_builder::TransactionBuilder,
     types::{
         account_address::AccountAddress,
-        transaction::{authenticator::AuthenticationKey, RawTransaction, SignedTransaction},
+        transaction::{
+            authenticator::{AccountAuthenticator, AuthenticationKey},
+            webauthn::PartialAuthenticatorAssertionResponse,
+            RawTransaction, RawTransactionWithData, SignedTransaction,
+        },
     },
 };
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use aptos_types::event::EventKey;
 pub use aptos_types::*;
 use bip39::{Language, Mnemonic, Seed};
 use ed25519_dalek_bip32::{DerivationPath, ExtendedSecretKey};
+use serde::Serialize;
 use std::str::FromStr;
 
 /// LocalAccount represents an account on the Aptos blockchain. Internally it
@@ -29,7 +37,7 @@ pub struct LocalAccount {
     /// Address of the account.
     address: AccountAddress,
     /// Authentication key of the account.
-    key: AccountKey,
+    key: LocalAccountKey,
     /// Latest known sequence number of the account, it can be different from validator.
     sequence_number: u64,
 }
@@ -38,7 +46,11 @@ impl LocalAccount {
     /// Create a new representation of an account locally. Note: This function
     /// does not actually create an account on the Aptos blockchain, just a
     /// local representation.
-    pub fn new<T: Into<AccountKey>>(address: AccountAddress, key: T, sequence_number: u64) -> Self {
+    pub fn new<T: Into<LocalAccountKey>>(
+        address: AccountAddress,
+        key: T,
+        sequence_number: u64,
+    ) -> Self {
         Self {
             address,
             key: key.into(),
@@ -62,11 +74,7 @@ impl LocalAccount {
         let key = AccountKey::from(Ed25519PrivateKey::try_from(key.as_bytes().as_ref())?);
         let address = key.authentication_key().derived_address();
 
-        Ok(Self {
-            address,
-            key,
-            sequence_number,
-        })
+        Ok(Self::new(address, key, sequence_number))
     }
 
     /// Generate a new account locally. Note: This function does not actually
@@ -82,10 +90,48 @@ impl LocalAccount {
         Self::new(address, key, 0)
     }
 
+    /// Generate a new account locally that signs with a Secp256k1 ECDSA key.
+    /// Note: This function does not actually create an account on the Aptos
+    /// blockchain, it just generates a new account locally.
+    pub fn generate_secp256k1_ecdsa<R>(rng: &mut R) -> Self
+    where
+        R: ::rand_core::RngCore + ::rand_core::CryptoRng,
+    {
+        let key = Secp256k1EcdsaAccountKey::generate(rng);
+        let address = key.authentication_key().derived_address();
+
+        Self::new(address, key, 0)
+    }
+
+    /// Generate a new account locally that signs with a (software) WebAuthn
+    /// credential, with assertions made for the given origin.
+    /// Note: This function does not actually create an account on the Aptos
+    /// blockchain, it just generates a new account locally.
+    pub fn generate_webauthn<R>(rng: &mut R, origin: impl Into<String>) -> Self
+    where
+        R: ::rand_core::RngCore + ::rand_core::CryptoRng,
+    {
+        let key = WebAuthnAccountKey::generate(rng, origin);
+        let address = key.authentication_key().derived_address();
+
+        Self::new(address, key, 0)
+    }
+
     pub fn sign_transaction(&self, txn: RawTransaction) -> SignedTransaction {
-        txn.sign(self.private_key(), self.public_key().clone())
-            .expect("Signing a txn can't fail")
-            .into_inner()
+        match &self.key {
+            LocalAccountKey::Ed25519(key) => txn
+                .sign(key.private_key(), key.public_key().clone())
+                .expect("Signing a txn can't fail")
+                .into_inner(),
+            LocalAccountKey::Secp256k1Ecdsa(key) => txn
+                .sign_secp256k1_ecdsa(key.private_key(), key.public_key().clone())
+                .expect("Signing a txn can't fail")
+                .into_inner(),
+            LocalAccountKey::WebAuthn(key) => {
+                let assertion = key.sign_assertion(&txn).expect("Signing a txn can't fail");
+                SignedTransaction::new_webauthn(txn, key.public_key().clone(), assertion)
+            },
+        }
     }
 
     pub fn sign_with_transaction_builder(
@@ -105,27 +151,32 @@ impl LocalAccount {
         secondary_signers: Vec<&Self>,
         builder: TransactionBuilder,
     ) -> SignedTransaction {
-        let secondary_signer_addresses = secondary_signers
+        let secondary_signer_addresses: Vec<_> = secondary_signers
             .iter()
             .map(|signer| signer.address())
             .collect();
-        let secondary_signer_privkeys = secondary_signers
-            .iter()
-            .map(|signer| signer.private_key())
-            .collect();
         let raw_txn = builder
             .sender(self.address())
             .sequence_number(self.sequence_number())
             .build();
         *self.sequence_number_mut() += 1;
-        raw_txn
-            .sign_multi_agent(
-                self.private_key(),
-                secondary_signer_addresses,
-                secondary_signer_privkeys,
-            )
-            .expect("Signing multi agent txn failed")
-            .into_inner()
+
+        let message = RawTransactionWithData::new_multi_agent(
+            raw_txn.clone(),
+            secondary_signer_addresses.clone(),
+        );
+        let sender_authenticator = self
+            .key
+            .sign_message(&message)
+            .expect("Signing multi agent txn failed");
+        let secondary_authenticators = sign_message_with_all(&secondary_signers, &message)
+            .expect("Signing multi agent txn failed");
+        SignedTransaction::new_multi_agent(
+            raw_txn,
+            sender_authenticator,
+            secondary_signer_addresses,
+            secondary_authenticators,
+        )
     }
 
     pub fn sign_fee_payer_with_transaction_builder(
@@ -134,41 +185,75 @@ impl LocalAccount {
         fee_payer_signer: &Self,
         builder: TransactionBuilder,
     ) -> SignedTransaction {
-        let secondary_signer_addresses = secondary_signers
+        let secondary_signer_addresses: Vec<_> = secondary_signers
             .iter()
             .map(|signer| signer.address())
             .collect();
-        let secondary_signer_privkeys = secondary_signers
-            .iter()
-            .map(|signer| signer.private_key())
-            .collect();
         let raw_txn = builder
             .sender(self.address())
             .sequence_number(self.sequence_number())
             .build();
         *self.sequence_number_mut() += 1;
-        raw_txn
-            .sign_fee_payer(
-                self.private_key(),
-                secondary_signer_addresses,
-                secondary_signer_privkeys,
-                fee_payer_signer.address(),
-                fee_payer_signer.private_key(),
-            )
-            .expect("Signing multi agent txn failed")
-            .into_inner()
+
+        let message = RawTransactionWithData::new_fee_payer(
+            raw_txn.clone(),
+            secondary_signer_addresses.clone(),
+            fee_payer_signer.address(),
+        );
+        let sender_authenticator = self
+            .key
+            .sign_message(&message)
+            .expect("Signing fee payer txn failed");
+        let secondary_authenticators = sign_message_with_all(&secondary_signers, &message)
+            .expect("Signing fee payer txn failed");
+        let fee_payer_authenticator = fee_payer_signer
+            .key
+            .sign_message(&message)
+            .expect("Signing fee payer txn failed");
+        SignedTransaction::new_fee_payer(
+            raw_txn,
+            sender_authenticator,
+            secondary_signer_addresses,
+            secondary_authenticators,
+            fee_payer_signer.address(),
+            fee_payer_authenticator,
+        )
     }
 
     pub fn address(&self) -> AccountAddress {
         self.address
     }
 
+    /// Returns the Ed25519 private key of the account.
+    ///
+    /// Panics if the account signs with a different scheme. Use
+    /// [`LocalAccount::ed25519_private_key`] if the scheme isn't known.
     pub fn private_key(&self) -> &Ed25519PrivateKey {
-        self.key.private_key()
+        self.ed25519_private_key()
+            .unwrap_or_else(|| panic!("Account {} does not sign with Ed25519", self.address))
     }
 
+    /// Returns the Ed25519 public key of the account.
+    ///
+    /// Panics if the account signs with a different scheme. Use
+    /// [`LocalAccount::ed25519_public_key`] if the scheme isn't known.
     pub fn public_key(&self) -> &Ed25519PublicKey {
-        self.key.public_key()
+        self.ed25519_public_key()
+            .unwrap_or_else(|| panic!("Account {} does not sign with Ed25519", self.address))
+    }
+
+    /// Returns the Ed25519 private key of the account (if it signs with Ed25519)
+    pub fn ed25519_private_key(&self) -> Option<&Ed25519PrivateKey> {
+        self.ed25519_key().map(|key| key.private_key())
+    }
+
+    /// Returns the Ed25519 public key of the account (if it signs with Ed25519)
+    pub fn ed25519_public_key(&self) -> Option<&Ed25519PublicKey> {
+        self.ed25519_key().map(|key| key.public_key())
+    }
+
+    pub fn key(&self) -> &LocalAccountKey {
+        &self.key
     }
 
     pub fn authentication_key(&self) -> AuthenticationKey {
@@ -183,7 +268,24 @@ impl LocalAccount {
         &mut self.sequence_number
     }
 
+    /// Rotates the Ed25519 key of the account, returning the old key.
+    ///
+    /// Panics if the account signs with a different scheme. Use
+    /// [`LocalAccount::rotate_local_key`] to rotate keys of any scheme.
     pub fn rotate_key<T: Into<AccountKey>>(&mut self, new_key: T) -> AccountKey {
+        assert!(
+            self.ed25519_key().is_some(),
+            "Account {} does not sign with Ed25519",
+            self.address
+        );
+        match self.rotate_local_key(new_key.into()) {
+            LocalAccountKey::Ed25519(old_key) => old_key,
+            _ => unreachable!("The old key was checked to be an Ed25519 key"),
+        }
+    }
+
+    /// Rotates the key of the account (to a key of any scheme), returning the old key
+    pub fn rotate_local_key<T: Into<LocalAccountKey>>(&mut self, new_key: T) -> LocalAccountKey {
         std::mem::replace(&mut self.key, new_key.into())
     }
 
@@ -194,6 +296,99 @@ impl LocalAccount {
     pub fn sent_event_key(&self) -> EventKey {
         EventKey::new(3, self.address)
     }
+
+    fn ed25519_key(&self) -> Option<&AccountKey> {
+        match &self.key {
+            LocalAccountKey::Ed25519(key) => Some(key),
+            _ => None,
+        }
+    }
+}
+
+/// Signs the given message with the keys of all the given accounts (in order)
+fn sign_message_with_all<T: CryptoHash + Serialize>(
+    accounts: &[&LocalAccount],
+    message: &T,
+) -> Result<Vec<AccountAuthenticator>> {
+    accounts
+        .iter()
+        .map(|account| account.key.sign_message(message))
+        .collect()
+}
+
+/// The signing key held by a [`LocalAccount`]
+#[derive(Debug)]
+pub enum LocalAccountKey {
+    Ed25519(AccountKey),
+    Secp256k1Ecdsa(Secp256k1EcdsaAccountKey),
+    WebAuthn(WebAuthnAccountKey),
+}
+
+impl LocalAccountKey {
+    pub fn authentication_key(&self) -> AuthenticationKey {
+        match self {
+            LocalAccountKey::Ed25519(key) => key.authentication_key(),
+            LocalAccountKey::Secp256k1Ecdsa(key) => key.authentication_key(),
+            LocalAccountKey::WebAuthn(key) => key.authentication_key(),
+        }
+    }
+
+    /// Signs the given message (e.g., a multi-agent transaction), and returns
+    /// the account authenticator for the signature scheme of the key.
+    pub fn sign_message<T: CryptoHash + Serialize>(
+        &self,
+        message: &T,
+    ) -> Result<AccountAuthenticator> {
+        let authenticator = match self {
+            LocalAccountKey::Ed25519(key) => AccountAuthenticator::ed25519(
+                key.public_key().clone(),
+                key.private_key()
+                    .sign(message)
+                    .map_err(|error| anyhow!("Failed to sign message: {}", error))?,
+            ),
+            LocalAccountKey::Secp256k1Ecdsa(key) => AccountAuthenticator::secp256k1_ecdsa(
+                key.public_key().clone(),
+                key.private_key()
+                    .sign(message)
+                    .map_err(|error| anyhow!("Failed to sign message: {}", error))?,
+            ),
+            LocalAccountKey::WebAuthn(key) => AccountAuthenticator::webauthn(
+                key.public_key().clone(),
+                key.sign_assertion(message)?,
+            ),
+        };
+        Ok(authenticator)
+    }
+}
+
+impl From<AccountKey> for LocalAccountKey {
+    fn from(key: AccountKey) -> Self {
+        Self::Ed25519(key)
+    }
+}
+
+impl From<Ed25519PrivateKey> for LocalAccountKey {
+    fn from(private_key: Ed25519PrivateKey) -> Self {
+        Self::Ed25519(AccountKey::from_private_key(private_key))
+    }
+}
+
+impl From<Secp256k1EcdsaAccountKey> for LocalAccountKey {
+    fn from(key: Secp256k1EcdsaAccountKey) -> Self {
+        Self::Secp256k1Ecdsa(key)
+    }
+}
+
+impl From<Secp256k1EcdsaPrivateKey> for LocalAccountKey {
+    fn from(private_key: Secp256k1EcdsaPrivateKey) -> Self {
+        Self::Secp256k1Ecdsa(Secp256k1EcdsaAccountKey::from_private_key(private_key))
+    }
+}
+
+impl From<WebAuthnAccountKey> for LocalAccountKey {
+    fn from(key: WebAuthnAccountKey) -> Self {
+        Self::WebAuthn(key)
+    }
 }
 
 #[derive(Debug)]
@@ -242,6 +437,115 @@ impl From<Ed25519PrivateKey> for AccountKey {
     }
 }
 
+#[derive(Debug)]
+pub struct Secp256k1EcdsaAccountKey {
+    private_key: Secp256k1EcdsaPrivateKey,
+    public_key: Secp256k1EcdsaPublicKey,
+    authentication_key: AuthenticationKey,
+}
+
+impl Secp256k1EcdsaAccountKey {
+    pub fn generate<R>(rng: &mut R) -> Self
+    where
+        R: ::rand_core::RngCore + ::rand_core::CryptoRng,
+    {
+        let private_key = Secp256k1EcdsaPrivateKey::generate(rng);
+        Self::from_private_key(private_key)
+    }
+
+    pub fn from_private_key(private_key: Secp256k1EcdsaPrivateKey) -> Self {
+        let public_key = Secp256k1EcdsaPublicKey::from(&private_key);
+        let authentication_key = AuthenticationKey::secp256k1_ecdsa(&public_key);
+
+        Self {
+            private_key,
+            public_key,
+            authentication_key,
+        }
+    }
+
+    pub fn private_key(&self) -> &Secp256k1EcdsaPrivateKey {
+        &self.private_key
+    }
+
+    pub fn public_key(&self) -> &Secp256k1EcdsaPublicKey {
+        &self.public_key
+    }
+
+    pub fn authentication_key(&self) -> AuthenticationKey {
+        self.authentication_key
+    }
+}
+
+impl From<Secp256k1EcdsaPrivateKey> for Secp256k1EcdsaAccountKey {
+    fn from(private_key: Secp256k1EcdsaPrivateKey) -> Self {
+        Self::from_private_key(private_key)
+    }
+}
+
+/// A software WebAuthn credential (i.e., a Secp256r1 ECDSA key), that makes
+/// assertions for the given origin.
+#[derive(Debug)]
+pub struct WebAuthnAccountKey {
+    private_key: Secp256r1EcdsaPrivateKey,
+    public_key: Secp256r1EcdsaPublicKey,
+    authentication_key: AuthenticationKey,
+    origin: String,
+}
+
+impl WebAuthnAccountKey {
+    pub fn generate<R>(rng: &mut R, origin: impl Into<String>) -> Self
+    where
+        R: ::rand_core::RngCore + ::rand_core::CryptoRng,
+    {
+        let private_key = Secp256r1EcdsaPrivateKey::generate(rng);
+        Self::from_private_key(private_key, origin)
+    }
+
+    pub fn from_private_key(
+        private_key: Secp256r1EcdsaPrivateKey,
+        origin: impl Into<String>,
+    ) -> Self {
+        let public_key = Secp256r1EcdsaPublicKey::from(&private_key);
+        let authentication_key = AuthenticationKey::webauthn(&public_key);
+
+        Self {
+            private_key,
+            public_key,
+            authentication_key,
+            origin: origin.into(),
+        }
+    }
+
+    pub fn private_key(&self) -> &Secp256r1EcdsaPrivateKey {
+        &self.private_key
+    }
+
+    pub fn public_key(&self) -> &Secp256r1EcdsaPublicKey {
+        &self.public_key
+    }
+
+    pub fn authentication_key(&self) -> AuthenticationKey {
+        self.authentication_key
+    }
+
+    pub fn origin(&self) -> &str {
+        &self.origin
+    }
+
+    /// Makes an assertion over the challenge of the given message
+    pub fn sign_assertion<T: CryptoHash + Serialize>(
+        &self,
+        message: &T,
+    ) -> Result<PartialAuthenticatorAssertionResponse> {
+        PartialAuthenticatorAssertionResponse::sign_with_private_key(
+            &self.private_key,
+            message,
+            &self.origin,
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -265,4 +569,126 @@ mod tests {
         // Return an error for empty mnemonic phrase.
         assert!(LocalAccount::from_derive_path(derive_path, "", 0).is_err());
     }
+
+    #[test]
+    fn test_secp256k1_ecdsa_account_signs_transactions() {
+        use crate::types::{chain_id::ChainId, transaction::TransactionPayload};
+        use aptos_types::transaction::{authenticator::TransactionAuthenticator, Script};
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let mut rng = StdRng::from_seed([7u8; 32]);
+        let account = LocalAccount::generate_secp256k1_ecdsa(&mut rng);
+        assert!(matches!(account.key(), LocalAccountKey::Secp256k1Ecdsa(_)));
+        assert_eq!(
+            account.address(),
+            account.authentication_key().derived_address()
+        );
+
+        let raw_txn = RawTransaction::new(
+            account.address(),
+            0,
+            TransactionPayload::Script(Script::new(vec![], vec![], vec![])),
+            0,
+            0,
+            0,
+            ChainId::test(),
+        );
+        let signed_txn = account.sign_transaction(raw_txn);
+        assert!(matches!(
+            signed_txn.authenticator(),
+            TransactionAuthenticator::Secp256k1Ecdsa { .. }
+        ));
+        assert!(signed_txn.check_signature().is_ok());
+    }
+
+    #[test]
+    fn test_mixed_scheme_multi_agent_and_fee_payer_transactions() {
+        use crate::types::{chain_id::ChainId, transaction::TransactionPayload};
+        use aptos_types::transaction::Script;
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let mut rng = StdRng::from_seed([11u8; 32]);
+        let mut sender = LocalAccount::generate_secp256k1_ecdsa(&mut rng);
+        let secondary_ed25519 = LocalAccount::generate(&mut rng);
+        let secondary_secp256k1 = LocalAccount::generate_secp256k1_ecdsa(&mut rng);
+        let fee_payer = LocalAccount::generate_secp256k1_ecdsa(&mut rng);
+        let builder = || {
+            TransactionBuilder::new(
+                TransactionPayload::Script(Script::new(vec![], vec![], vec![])),
+                0,
+                ChainId::test(),
+            )
+        };
+
+        let multi_agent_txn = sender.sign_multi_agent_with_transaction_builder(
+            vec![&secondary_ed25519, &secondary_secp256k1],
+            builder(),
+        );
+        assert!(multi_agent_txn.check_signature().is_ok());
+
+        let fee_payer_txn = sender.sign_fee_payer_with_transaction_builder(
+            vec![&secondary_secp256k1, &secondary_ed25519],
+            &fee_payer,
+            builder(),
+        );
+        assert!(fee_payer_txn.check_signature().is_ok());
+        assert_eq!(sender.sequence_number(), 2);
+    }
+
+    #[test]
+    fn test_rotate_keys() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let mut rng = StdRng::from_seed([13u8; 32]);
+        let mut account = LocalAccount::generate(&mut rng);
+        assert!(account.ed25519_private_key().is_some());
+
+        // Rotating an Ed25519 key returns the old Ed25519 key
+        let old_key = account.public_key().clone();
+        let new_key = AccountKey::generate(&mut rng);
+        let rotated_key = account.rotate_key(new_key);
+        assert_eq!(rotated_key.public_key(), &old_key);
+
+        // Rotating to a different scheme hides the Ed25519 accessors
+        account.rotate_local_key(Secp256k1EcdsaAccountKey::generate(&mut rng));
+        assert!(account.ed25519_private_key().is_none());
+        assert!(account.ed25519_public_key().is_none());
+    }
+
+    #[test]
+    fn test_webauthn_account_signs_transactions() {
+        use crate::types::{chain_id::ChainId, transaction::TransactionPayload};
+        use aptos_types::transaction::{authenticator::TransactionAuthenticator, Script};
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let mut rng = StdRng::from_seed([17u8; 32]);
+        let mut account = LocalAccount::generate_webauthn(&mut rng, "https://aptoslabs.com");
+        let secondary = LocalAccount::generate_webauthn(&mut rng, "https://aptoslabs.com");
+        let fee_payer = LocalAccount::generate(&mut rng);
+        assert_eq!(
+            account.address(),
+            account.authentication_key().derived_address()
+        );
+        let builder = || {
+            TransactionBuilder::new(
+                TransactionPayload::Script(Script::new(vec![], vec![], vec![])),
+                0,
+                ChainId::test(),
+            )
+        };
+
+        let signed_txn = account.sign_with_transaction_builder(builder());
+        assert!(matches!(
+            signed_txn.authenticator(),
+            TransactionAuthenticator::WebAuthn { .. }
+        ));
+        assert!(signed_txn.check_signature().is_ok());
+
+        let fee_payer_txn = account.sign_fee_payer_with_transaction_builder(
+            vec![&secondary],
+            &fee_payer,
+            builder(),
+        );
+        assert!(fee_payer_txn.check_signature().is_ok());
+    }
 }
@@ -53,6 +53,9 @@ pub struct DriverConfiguration {
     // The config file of the driver
     pub config: StateSyncDriverConfig,
 
+    // Whether the consensus observer drives execution (full nodes only)
+    pub consensus_observer_enabled: bool,
+
     // The role of the node
     pub role: RoleType,
 
@@ -61,9 +64,15 @@ pub struct DriverConfiguration {
 }
 
 impl DriverConfiguration {
-    pub fn new(config: StateSyncDriverConfig, role: RoleType, waypoint: Waypoint) -> Self {
+    pub fn new(
+        config: StateSyncDriverConfig,
+        consensus_observer_enabled: bool,
+        role: RoleType,
+        waypoint: Waypoint,
+    ) -> Self {
         Self {
             config,
+            consensus_observer_enabled,
             role,
             waypoint,
         }
@@ -232,9 +241,10 @@ impl<
 
     /// Handles a notification sent by consensus
     async fn handle_consensus_notification(&mut self, notification: ConsensusNotification) {
-        // Verify the notification: full nodes shouldn't receive notifications
-        // and consensus should only send notifications after bootstrapping!
-        let result = if self.driver_configuration.role == RoleType::FullNode {
+        // Verify the notification: full nodes shouldn't receive notifications (unless
+        // the consensus observer is enabled) and consensus should only send notifications
+        // after bootstrapping!
+        let result = if !self.is_consensus_or_observer_enabled() {
             Err(Error::FullNodeConsensusNotification(format!(
                 "Received consensus notification: {:?}",
                 notification
@@ -539,9 +549,16 @@ impl<
         self.driver_configuration.role == RoleType::Validator
     }
 
-    /// Returns true iff consensus is currently executing
+    /// Returns true iff consensus (or the consensus observer) drives execution on this node
+    fn is_consensus_or_observer_enabled(&self) -> bool {
+        self.is_validator() || self.driver_configuration.consensus_observer_enabled
+    }
+
+    /// Returns true iff consensus (or the consensus observer) is currently executing
     fn check_if_consensus_executing(&self) -> bool {
-        self.is_validator() && self.bootstrapper.is_bootstrapped() && !self.active_sync_request()
+        self.is_consensus_or_observer_enabled()
+            && self.bootstrapper.is_bootstrapped()
+            && !self.active_sync_request()
     }
 
     /// Checks if the connection deadline has passed. If so, validators with
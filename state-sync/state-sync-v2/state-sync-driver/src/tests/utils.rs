@@ -70,6 +70,7 @@ pub fn create_full_node_driver_configuration() -> DriverConfiguration {
 
     DriverConfiguration {
         config,
+        consensus_observer_enabled: false,
         role,
         waypoint,
     }
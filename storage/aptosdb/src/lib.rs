@@ -890,6 +890,7 @@ impl AptosDB {
         expected_state_db_usage: StateStorageUsage,
         sharded_state_cache: Option<&ShardedStateCache>,
         skip_index_and_usage: bool,
+        is_state_forked: bool,
     ) -> Result<HashValue> {
         let new_root_hash = thread::scope(|s| {
             let _timer = OTHER_TIMERS_SECONDS
@@ -910,6 +911,7 @@ impl AptosDB {
                     expected_state_db_usage,
                     sharded_state_cache,
                     skip_index_and_usage,
+                    is_state_forked,
                 )
             });
             let t4 = s.spawn(|| self.commit_transaction_infos(txns_to_commit, first_version));
@@ -933,6 +935,7 @@ impl AptosDB {
         expected_state_db_usage: StateStorageUsage,
         sharded_state_cache: Option<&ShardedStateCache>,
         skip_index_and_usage: bool,
+        is_state_forked: bool,
     ) -> Result<()> {
         let _timer = OTHER_TIMERS_SECONDS
             .with_label_values(&["commit_state_kv_and_ledger_metadata"])
@@ -957,6 +960,7 @@ impl AptosDB {
             &state_kv_metadata_batch,
             self.state_store.state_kv_db.enabled_sharding() && !skip_index_and_usage,
             skip_index_and_usage,
+            is_state_forked,
         )?;
 
         let last_version = first_version + txns_to_commit.len() as u64 - 1;
@@ -2054,6 +2058,7 @@ impl DbWriter for AptosDB {
                 latest_in_memory_state.current.usage(),
                 None,
                 /*skip_index_and_usage=*/ false,
+                latest_in_memory_state.forked_deletions.is_some(),
             )?;
 
             {
@@ -2116,6 +2121,7 @@ impl DbWriter for AptosDB {
                 latest_in_memory_state.current.usage(),
                 Some(sharded_state_cache),
                 self.skip_index_and_usage,
+                latest_in_memory_state.forked_deletions.is_some(),
             )?;
 
             let _timer = OTHER_TIMERS_SECONDS
@@ -653,6 +653,7 @@ impl StateStore {
             batch,
             sharded_state_kv_batches,
             /*skip_usage=*/ false,
+            /*is_state_forked=*/ false,
         )?;
 
         self.put_state_values(
@@ -678,6 +679,7 @@ impl StateStore {
         state_kv_metadata_batch: &SchemaBatch,
         put_state_value_indices: bool,
         skip_usage: bool,
+        is_state_forked: bool,
     ) -> Result<()> {
         let _timer = OTHER_TIMERS_SECONDS
             .with_label_values(&["put_value_sets"])
@@ -691,6 +693,7 @@ impl StateStore {
             ledger_batch,
             sharded_state_kv_batches,
             skip_usage,
+            is_state_forked,
         )?;
 
         let _timer = OTHER_TIMERS_SECONDS
@@ -766,6 +769,8 @@ impl StateStore {
     /// exists, a stale index of that old value will be added. Otherwise, it's a no-op. Because
     /// non-existence means either the key never shows up or it got deleted. Neither case needs
     /// extra stale index as 1 cover the latter case.
+    /// If the state is forked (see `DbReader::is_state_forked`), the usage saturates at zero since
+    /// `sharded_state_cache` can hold forked values that were never accounted for.
     pub fn put_stats_and_indices(
         &self,
         value_state_sets: &[&ShardedStateUpdates],
@@ -777,6 +782,7 @@ impl StateStore {
         batch: &SchemaBatch,
         sharded_state_kv_batches: &ShardedStateKvSchemaBatch,
         skip_usage: bool,
+        is_state_forked: bool,
     ) -> Result<()> {
         let _timer = OTHER_TIMERS_SECONDS
             .with_label_values(&["put_stats_and_indices"])
@@ -906,10 +912,11 @@ impl StateStore {
                     items_delta += usage_delta[i].0;
                     bytes_delta += usage_delta[i].1;
                 }
-                usage = StateStorageUsage::new(
-                    (usage.items() as i64 + items_delta) as usize,
-                    (usage.bytes() as i64 + bytes_delta) as usize,
-                );
+                usage = if is_state_forked {
+                    usage.apply_delta_saturating(items_delta, bytes_delta)
+                } else {
+                    usage.apply_delta(items_delta, bytes_delta)
+                };
                 let version = first_version + i as u64;
                 batch
                     .put::<VersionDataSchema>(&version, &usage.into())
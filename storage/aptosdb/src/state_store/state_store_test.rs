@@ -58,6 +58,7 @@ fn put_value_set(
             &state_kv_metadata_batch,
             /*put_state_value_indices=*/ false,
             /*skip_usage=*/ false,
+            /*is_state_forked=*/ false,
         )
         .unwrap();
     state_store
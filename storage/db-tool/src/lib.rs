@@ -27,6 +27,8 @@ pub enum DBTool {
     Debug(debugger::Command),
     #[clap(subcommand)]
     BackupMaintenance(backup_maintenance::Command),
+    #[clap(subcommand)]
+    Consensus(aptos_consensus::db_tool::Command),
 }
 
 impl DBTool {
@@ -37,6 +39,7 @@ impl DBTool {
             DBTool::ReplayVerify(cmd) => cmd.run().await,
             DBTool::BackupMaintenance(cmd) => cmd.run().await,
             DBTool::Debug(cmd) => cmd.run(),
+            DBTool::Consensus(cmd) => cmd.run(),
         }
     }
 }
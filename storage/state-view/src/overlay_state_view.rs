@@ -0,0 +1,71 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+#![forbid(unsafe_code)]
+use crate::{StateViewId, TStateView};
+use anyhow::Result;
+use aptos_types::{
+    state_store::{
+        state_key::StateKey, state_storage_usage::StateStorageUsage, state_value::StateValue,
+    },
+    write_set::{WriteOp, WriteSet},
+};
+use std::collections::HashMap;
+
+// A state view that overrides some of the state values of a base view, without modifying the
+// base view. Overriding a key with `None` makes it appear deleted.
+pub struct OverlayStateView<'a, S> {
+    base: &'a S,
+    overlay: HashMap<StateKey, Option<StateValue>>,
+}
+
+impl<'a, S> OverlayStateView<'a, S> {
+    pub fn new(base: &'a S) -> Self {
+        Self {
+            base,
+            overlay: HashMap::new(),
+        }
+    }
+
+    /// Overrides the value stored under `state_key`, `None` deletes it.
+    pub fn set(&mut self, state_key: StateKey, value: Option<StateValue>) {
+        self.overlay.insert(state_key, value);
+    }
+
+    /// Applies the writes of a transaction on top of the view, so that transactions executed
+    /// against it afterwards observe them.
+    pub fn apply_write_set(&mut self, write_set: &WriteSet) {
+        for (state_key, write_op) in write_set.iter() {
+            let value = match write_op {
+                WriteOp::Creation(data) | WriteOp::Modification(data) => {
+                    Some(StateValue::new_legacy(data.clone()))
+                },
+                WriteOp::CreationWithMetadata { data, metadata }
+                | WriteOp::ModificationWithMetadata { data, metadata } => Some(
+                    StateValue::new_with_metadata(data.clone(), metadata.clone()),
+                ),
+                WriteOp::Deletion | WriteOp::DeletionWithMetadata { .. } => None,
+            };
+            self.set(state_key.clone(), value);
+        }
+    }
+}
+
+impl<'a, S: TStateView<Key = StateKey>> TStateView for OverlayStateView<'a, S> {
+    type Key = StateKey;
+
+    fn id(&self) -> StateViewId {
+        self.base.id()
+    }
+
+    fn get_state_value(&self, state_key: &StateKey) -> Result<Option<StateValue>> {
+        match self.overlay.get(state_key) {
+            Some(value) => Ok(value.clone()),
+            None => self.base.get_state_value(state_key),
+        }
+    }
+
+    fn get_usage(&self) -> Result<StateStorageUsage> {
+        self.base.get_usage()
+    }
+}
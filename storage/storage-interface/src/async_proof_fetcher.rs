@@ -40,17 +40,22 @@ pub struct AsyncProofFetcher {
     data_sender: Sender<Proof>,
     data_receiver: Receiver<Proof>,
     num_proofs_to_read: AtomicUsize,
+    // Values read from a forked state are not in the local state tree, so their proofs can't be
+    // verified against it.
+    verify_proofs: bool,
 }
 
 impl AsyncProofFetcher {
     pub fn new(reader: Arc<dyn DbReader>) -> Self {
         let (data_sender, data_receiver) = unbounded();
+        let verify_proofs = !reader.is_state_forked();
 
         Self {
             reader,
             data_sender,
             data_receiver,
             num_proofs_to_read: AtomicUsize::new(0),
+            verify_proofs,
         }
     }
 
@@ -69,7 +74,7 @@ impl AsyncProofFetcher {
         self.schedule_proof_read(
             state_key.clone(),
             version,
-            root_hash,
+            root_hash.filter(|_| self.verify_proofs),
             version_and_value_opt.as_ref().map(|v| {
                 let state_value = &v.1;
                 state_value.hash()
@@ -2,7 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    async_proof_fetcher::AsyncProofFetcher, metrics::TIMER, state_view::DbStateView, DbReader,
+    async_proof_fetcher::AsyncProofFetcher, metrics::TIMER, state_delta::ForkedDeletions,
+    state_view::DbStateView, DbReader,
 };
 use anyhow::Result;
 use aptos_crypto::{hash::CryptoHash, HashValue};
@@ -96,6 +97,11 @@ pub struct CachedStateView {
     /// in JMT node.
     sharded_state_cache: ShardedStateCache,
     proof_fetcher: Arc<AsyncProofFetcher>,
+
+    /// The keys deleted by `speculative_state` if the DB serves state values that aren't in the
+    /// local state tree (see [`DbReader::is_state_forked`]), `None` otherwise. Those must not be
+    /// read from the forked state again.
+    forked_deletions: Option<ForkedDeletions>,
 }
 
 impl Debug for CachedStateView {
@@ -127,9 +133,19 @@ impl CachedStateView {
             speculative_state,
             sharded_state_cache: arr![DashMap::new(); 16],
             proof_fetcher,
+            forked_deletions: reader.is_state_forked().then(ForkedDeletions::default),
         })
     }
 
+    /// Sets the keys deleted by the speculative state (ignored if the state isn't forked), see
+    /// [`crate::state_delta::StateDelta::forked_deletions`].
+    pub fn with_forked_deletions(mut self, forked_deletions: ForkedDeletions) -> Self {
+        if self.forked_deletions.is_some() {
+            self.forked_deletions = Some(forked_deletions);
+        }
+        self
+    }
+
     pub fn prime_cache_by_write_set<'a, T: IntoIterator<Item = &'a WriteSet> + Send>(
         &self,
         write_sets: T,
@@ -155,6 +171,7 @@ impl CachedStateView {
             frozen_base: self.speculative_state,
             sharded_state_cache: self.sharded_state_cache,
             proofs: self.proof_fetcher.get_proof_cache(),
+            forked_deletions: self.forked_deletions,
         }
     }
 
@@ -164,12 +181,24 @@ impl CachedStateView {
     ) -> Result<(Option<Version>, Option<StateValue>)> {
         // Do most of the work outside the write lock.
         let key_hash = state_key.hash();
+        let is_forked_deletion = self
+            .forked_deletions
+            .as_ref()
+            .map_or(false, |forked_deletions| {
+                forked_deletions.contains(state_key)
+            });
         Ok(match self.speculative_state.get(key_hash) {
             StateStoreStatus::ExistsInScratchPad(value) => (None, Some(value)),
-            StateStoreStatus::DoesNotExist => (None, None),
+            // A key deleted locally (even if not committed yet) must not be read from the DB,
+            // which would serve its forked value.
+            _ if is_forked_deletion => (None, None),
+            // A key absent from the local tree can still exist in the forked state.
+            StateStoreStatus::DoesNotExist if self.forked_deletions.is_none() => (None, None),
             // No matter it is in db or unknown, we have to query from db since even the
             // former case, we don't have the blob data but only its hash.
-            StateStoreStatus::ExistsInDB | StateStoreStatus::Unknown => match self.snapshot {
+            StateStoreStatus::DoesNotExist
+            | StateStoreStatus::ExistsInDB
+            | StateStoreStatus::Unknown => match self.snapshot {
                 Some((version, root_hash)) => {
                     let version_and_value_opt = self
                         .proof_fetcher
@@ -193,6 +222,8 @@ pub struct StateCache {
     pub frozen_base: FrozenSparseMerkleTree<StateValue>,
     pub sharded_state_cache: ShardedStateCache,
     pub proofs: HashMap<HashValue, SparseMerkleProofExt>,
+    /// See [`CachedStateView::with_forked_deletions`].
+    pub forked_deletions: Option<ForkedDeletions>,
 }
 
 impl TStateView for CachedStateView {
@@ -267,3 +298,91 @@ impl TStateView for CachedDbStateView {
         self.db_state_view.get_usage()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockDbReaderWriter;
+    use aptos_crypto::hash::SPARSE_MERKLE_PLACEHOLDER_HASH;
+    use aptos_types::state_store::create_empty_sharded_state_updates;
+
+    /// Serves the values of `MockDbReaderWriter` (for every key), as if they came from a forked
+    /// state if `forked` is set.
+    struct MockForkedDb {
+        forked: bool,
+    }
+
+    impl DbReader for MockForkedDb {
+        fn get_state_snapshot_before(
+            &self,
+            _next_version: Version,
+        ) -> Result<Option<(Version, HashValue)>> {
+            Ok(Some((0, *SPARSE_MERKLE_PLACEHOLDER_HASH)))
+        }
+
+        fn get_state_value_with_version_by_version(
+            &self,
+            state_key: &StateKey,
+            version: Version,
+        ) -> Result<Option<(Version, StateValue)>> {
+            MockDbReaderWriter.get_state_value_with_version_by_version(state_key, version)
+        }
+
+        fn get_state_proof_by_version_ext(
+            &self,
+            state_key: &StateKey,
+            version: Version,
+        ) -> Result<SparseMerkleProofExt> {
+            MockDbReaderWriter.get_state_proof_by_version_ext(state_key, version)
+        }
+
+        fn is_state_forked(&self) -> bool {
+            self.forked
+        }
+    }
+
+    fn create_state_view(forked: bool) -> CachedStateView {
+        let reader: Arc<dyn DbReader> = Arc::new(MockForkedDb { forked });
+        CachedStateView::new(
+            StateViewId::Miscellaneous,
+            reader.clone(),
+            1,
+            SparseMerkleTree::new_empty(),
+            Arc::new(AsyncProofFetcher::new(reader)),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_forked_state_values() {
+        let state_key = StateKey::raw(b"forked_key".to_vec());
+        let expected_value = StateValue::from(b"forked_key".to_vec());
+
+        // Keys absent from the local tree are only read from the DB if the state is forked
+        let state_view = create_state_view(false);
+        assert_eq!(state_view.get_state_value(&state_key).unwrap(), None);
+        let state_view = create_state_view(true);
+        assert_eq!(
+            state_view.get_state_value(&state_key).unwrap(),
+            Some(expected_value)
+        );
+
+        // Keys deleted locally are not read from the forked state again
+        let other_key = StateKey::raw(b"other_key".to_vec());
+        let mut deletion = create_empty_sharded_state_updates();
+        deletion[state_key.get_shard_id() as usize].insert(state_key.clone(), None);
+        let forked_deletions = ForkedDeletions::default().apply(&deletion);
+        let state_view = create_state_view(true).with_forked_deletions(forked_deletions.clone());
+        assert_eq!(state_view.get_state_value(&state_key).unwrap(), None);
+        assert!(state_view.get_state_value(&other_key).unwrap().is_some());
+        assert!(state_view
+            .into_state_cache()
+            .forked_deletions
+            .unwrap()
+            .contains(&state_key));
+
+        // Deletions are ignored if the state isn't forked
+        let state_view = create_state_view(false).with_forked_deletions(forked_deletions);
+        assert!(state_view.into_state_cache().forked_deletions.is_none());
+    }
+}
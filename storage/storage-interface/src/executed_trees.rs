@@ -105,6 +105,10 @@ impl ExecutedTrees {
             self.state.current.clone(),
             proof_fetcher,
         )
+        .map(|view| match &self.state.forked_deletions {
+            Some(forked_deletions) => view.with_forked_deletions(forked_deletions.clone()),
+            None => view,
+        })
     }
 }
 
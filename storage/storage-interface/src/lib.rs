@@ -399,6 +399,12 @@ pub trait DbReader: Send + Sync {
         unimplemented!()
     }
 
+    /// Whether state values returned by this reader can come from a forked network rather than
+    /// the local state tree, in which case they can't be proven against the local state root.
+    fn is_state_forked(&self) -> bool {
+        false
+    }
+
     /// Returns the proof of the given state key and version.
     fn get_state_proof_by_version_ext(
         &self,
@@ -5,12 +5,13 @@ use aptos_crypto::HashValue;
 use aptos_scratchpad::SparseMerkleTree;
 use aptos_types::{
     state_store::{
-        create_empty_sharded_state_updates, state_storage_usage::StateStorageUsage,
-        state_value::StateValue, ShardedStateUpdates,
+        create_empty_sharded_state_updates, state_key::StateKey,
+        state_storage_usage::StateStorageUsage, state_value::StateValue, ShardedStateUpdates,
     },
     transaction::Version,
 };
 use itertools::zip_eq;
+use std::{collections::HashMap, sync::Arc};
 
 /// This represents two state sparse merkle trees at their versions in memory with the updates
 /// reflecting the difference of `current` on top of `base`.
@@ -26,6 +27,9 @@ pub struct StateDelta {
     pub current: SparseMerkleTree<StateValue>,
     pub current_version: Option<Version>,
     pub updates_since_base: ShardedStateUpdates,
+    /// The keys deleted on top of a forked state (see [`crate::DbReader::is_state_forked`]),
+    /// `None` if the state isn't forked or no update was applied to it in memory yet.
+    pub forked_deletions: Option<ForkedDeletions>,
 }
 
 impl StateDelta {
@@ -43,9 +47,15 @@ impl StateDelta {
             current,
             current_version,
             updates_since_base,
+            forked_deletions: None,
         }
     }
 
+    pub fn with_forked_deletions(mut self, forked_deletions: ForkedDeletions) -> Self {
+        self.forked_deletions = Some(forked_deletions);
+        self
+    }
+
     pub fn new_empty() -> Self {
         let smt = SparseMerkleTree::new_empty();
         Self::new(
@@ -84,6 +94,7 @@ impl StateDelta {
 
         self.current = other.current;
         self.current_version = other.current_version;
+        self.forked_deletions = other.forked_deletions;
     }
 
     pub fn follow(&self, other: &StateDelta) -> bool {
@@ -103,3 +114,96 @@ impl StateDelta {
         self.current.root_hash()
     }
 }
+
+/// The keys whose latest update is a deletion, on top of a forked state. Deleting a key that only
+/// exists in the forked state leaves the tree untouched, so without this such a key would be read
+/// from the forked state again.
+///
+/// The updates are kept in immutable layers shared between the states derived from each other,
+/// newest last. A new layer is merged into the ones below it as long as they aren't more than
+/// twice as large, so that there are O(log(n)) layers and applying updates never copies all the
+/// keys deleted so far.
+#[derive(Clone, Debug, Default)]
+pub struct ForkedDeletions {
+    /// Whether the latest update of each key in the layer is a deletion.
+    layers: Vec<Arc<HashMap<StateKey, bool>>>,
+}
+
+impl ForkedDeletions {
+    pub fn contains(&self, state_key: &StateKey) -> bool {
+        self.layers
+            .iter()
+            .rev()
+            .find_map(|layer| layer.get(state_key).copied())
+            .unwrap_or(false)
+    }
+
+    /// Returns the keys deleted after applying `updates` on top of these.
+    pub fn apply(&self, updates: &ShardedStateUpdates) -> Self {
+        let mut layer: HashMap<_, _> = updates
+            .iter()
+            .flatten()
+            .map(|(key, value)| (key.clone(), value.is_none()))
+            .collect();
+        if layer.is_empty() {
+            return self.clone();
+        }
+
+        let mut layers = self.layers.clone();
+        while layers
+            .last()
+            .map_or(false, |below| below.len() <= 2 * layer.len())
+        {
+            let below = layers.pop().expect("Checked above.");
+            let mut merged = Arc::try_unwrap(below).unwrap_or_else(|below| (*below).clone());
+            merged.extend(layer);
+            layer = merged;
+        }
+        // Nothing below the bottom layer, so keys re-created since their deletion can be dropped.
+        if layers.is_empty() {
+            layer.retain(|_, deleted| *deleted);
+        }
+        layers.push(Arc::new(layer));
+        Self { layers }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_types::state_store::create_empty_sharded_state_updates;
+
+    fn updates(deleted: &[u8], created: &[u8]) -> ShardedStateUpdates {
+        let mut updates = create_empty_sharded_state_updates();
+        for (keys, value) in [(deleted, None), (created, Some(StateValue::from(vec![0])))] {
+            for key in keys {
+                let key = StateKey::raw(vec![*key]);
+                updates[key.get_shard_id() as usize].insert(key, value.clone());
+            }
+        }
+        updates
+    }
+
+    #[test]
+    fn test_forked_deletions() {
+        let first = ForkedDeletions::default().apply(&updates(&[1, 2, 3, 4], &[]));
+        let second = first.apply(&updates(&[5], &[]));
+        let third = second.apply(&updates(&[6], &[1, 2]));
+
+        // Each state only sees the updates leading to it
+        let contains =
+            |deletions: &ForkedDeletions, key: u8| deletions.contains(&StateKey::raw(vec![key]));
+        assert!(contains(&first, 1) && contains(&first, 2) && !contains(&first, 5));
+        assert!(contains(&second, 1) && contains(&second, 5) && !contains(&second, 6));
+        assert!(!contains(&third, 1) && !contains(&third, 2) && contains(&third, 6));
+        assert!(contains(&third, 3) && contains(&third, 5) && !contains(&third, 7));
+
+        // Small layers are stacked on larger ones, and merged once they grow comparable
+        assert_eq!(first.layers.len(), 1);
+        assert_eq!(second.layers.len(), 2);
+        assert_eq!(third.layers.len(), 1);
+        // Merging doesn't alter the shared layers, and drops the re-created keys from the bottom
+        assert!(contains(&second, 1) && contains(&second, 2));
+        assert_eq!(third.layers[0].len(), 4);
+    }
+}
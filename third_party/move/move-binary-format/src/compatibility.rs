@@ -6,7 +6,7 @@ use crate::{
     errors::{PartialVMError, PartialVMResult},
     file_format::{AbilitySet, StructTypeParameter, Visibility},
     file_format_common::VERSION_5,
-    normalized::Module,
+    normalized::{Field, Module, Type},
 };
 use move_core_types::vm_status::StatusCode;
 use std::collections::BTreeSet;
@@ -71,13 +71,44 @@ impl Compatibility {
 
     /// Check compatibility for `new_module` relative to old module `old_module`.
     pub fn check(&self, old_module: &Module, new_module: &Module) -> PartialVMResult<()> {
-        let mut struct_and_pub_function_linking = true;
-        let mut struct_layout = true;
-        let mut friend_linking = true;
+        if self.incompatible_changes(old_module, new_module).is_empty() {
+            Ok(())
+        } else {
+            Err(PartialVMError::new(
+                StatusCode::BACKWARD_INCOMPATIBLE_MODULE_UPDATE,
+            ))
+        }
+    }
+
+    /// Returns the changes from `old_module` to `new_module` which break one of the requirements
+    /// checked by this configuration. The upgrade is compatible iff there are none.
+    pub fn incompatible_changes(
+        &self,
+        old_module: &Module,
+        new_module: &Module,
+    ) -> Vec<IncompatibleChange> {
+        use CompatibilityRequirement::*;
+
+        let mut changes = vec![];
+        let mut add = |requirements: &[CompatibilityRequirement], description: String| {
+            changes.push(IncompatibleChange {
+                requirements: requirements.to_vec(),
+                description,
+            })
+        };
 
         // module's name and address are unchanged
         if old_module.address != new_module.address || old_module.name != new_module.name {
-            struct_and_pub_function_linking = false;
+            add(
+                &[StructAndPubFunctionLinking],
+                format!(
+                    "module `{}::{}` was renamed to `{}::{}`",
+                    old_module.address.short_str_lossless(),
+                    old_module.name,
+                    new_module.address.short_str_lossless(),
+                    new_module.name
+                ),
+            );
         }
 
         // old module's structs are a subset of the new module's structs
@@ -88,19 +119,38 @@ impl Compatibility {
                     // Struct not present in new . Existing modules that depend on this struct will fail to link with the new version of the module.
                     // Also, struct layout cannot be guaranteed transitively, because after
                     // removing the struct, it could be re-added later with a different layout.
-                    struct_and_pub_function_linking = false;
-                    struct_layout = false;
-                    break;
+                    add(
+                        &[StructAndPubFunctionLinking, StructLayout],
+                        format!("struct `{}` was removed", name),
+                    );
+                    continue;
                 },
             };
 
-            if !struct_abilities_compatibile(old_struct.abilities, new_struct.abilities)
-                || !struct_type_parameters_compatibile(
-                    &old_struct.type_parameters,
-                    &new_struct.type_parameters,
-                )
-            {
-                struct_and_pub_function_linking = false;
+            if !struct_abilities_compatibile(old_struct.abilities, new_struct.abilities) {
+                add(
+                    &[StructAndPubFunctionLinking],
+                    format!(
+                        "struct `{}` abilities changed from `{}` to `{}`, abilities can only be added",
+                        name,
+                        display_abilities(old_struct.abilities),
+                        display_abilities(new_struct.abilities)
+                    ),
+                );
+            }
+            if !struct_type_parameters_compatibile(
+                &old_struct.type_parameters,
+                &new_struct.type_parameters,
+            ) {
+                add(
+                    &[StructAndPubFunctionLinking],
+                    format!(
+                        "struct `{}` type parameters changed from `<{}>` to `<{}>`",
+                        name,
+                        display_struct_type_parameters(&old_struct.type_parameters),
+                        display_struct_type_parameters(&new_struct.type_parameters)
+                    ),
+                );
             }
             if new_struct.fields != old_struct.fields {
                 // Fields changed. Code in this module will fail at runtime if it tries to
@@ -109,7 +159,15 @@ impl Compatibility {
                 // choose that changing the name (but not position or type) of a field is
                 // compatible. The VM does not care about the name of a field
                 // (it's purely informational), but clients presumably do.
-                struct_layout = false
+                add(
+                    &[StructLayout],
+                    format!(
+                        "struct `{}` fields changed from `{{ {} }}` to `{{ {} }}`",
+                        name,
+                        display_fields(&old_struct.fields),
+                        display_fields(&new_struct.fields)
+                    ),
+                );
             }
         }
 
@@ -128,14 +186,15 @@ impl Compatibility {
         // friend list. But for simplicity, we decided to go to the more restrictive form now and
         // we may revisit this in the future.
         for (name, old_func) in &old_module.exposed_functions {
+            let (requirement, kind) = match old_func.visibility {
+                Visibility::Friend => (FriendLinking, "friend function"),
+                Visibility::Public => (StructAndPubFunctionLinking, "public function"),
+                Visibility::Private => (StructAndPubFunctionLinking, "entry function"),
+            };
             let new_func = match new_module.exposed_functions.get(name) {
                 Some(new_func) => new_func,
                 None => {
-                    if matches!(old_func.visibility, Visibility::Friend) {
-                        friend_linking = false;
-                    } else {
-                        struct_and_pub_function_linking = false;
-                    }
+                    add(&[requirement], format!("{} `{}` was removed", kind, name));
                     continue;
                 },
             };
@@ -161,20 +220,69 @@ impl Compatibility {
                 // If it was not an entry function, it is allowed to become one.
                 !old_func.is_entry || new_func.is_entry
             };
-            if !is_vis_compatible
-                || !is_entry_compatible
-                || old_func.parameters != new_func.parameters
-                || old_func.return_ != new_func.return_
-                || !fun_type_parameters_compatibile(
-                    &old_func.type_parameters,
-                    &new_func.type_parameters,
-                )
-            {
-                if matches!(old_func.visibility, Visibility::Friend) {
-                    friend_linking = false;
-                } else {
-                    struct_and_pub_function_linking = false;
-                }
+
+            if !is_vis_compatible {
+                add(
+                    &[requirement],
+                    format!(
+                        "{} `{}` visibility changed from {:?} to {:?}",
+                        kind, name, old_func.visibility, new_func.visibility
+                    ),
+                );
+            }
+            if !is_entry_compatible {
+                add(
+                    &[requirement],
+                    format!(
+                        "{} `{}` {}",
+                        kind,
+                        name,
+                        if old_func.is_entry {
+                            "is no longer an entry function"
+                        } else {
+                            "became an entry function"
+                        }
+                    ),
+                );
+            }
+            if old_func.parameters != new_func.parameters {
+                add(
+                    &[requirement],
+                    format!(
+                        "{} `{}` parameters changed from `({})` to `({})`",
+                        kind,
+                        name,
+                        display_types(&old_func.parameters),
+                        display_types(&new_func.parameters)
+                    ),
+                );
+            }
+            if old_func.return_ != new_func.return_ {
+                add(
+                    &[requirement],
+                    format!(
+                        "{} `{}` return type changed from `({})` to `({})`",
+                        kind,
+                        name,
+                        display_types(&old_func.return_),
+                        display_types(&new_func.return_)
+                    ),
+                );
+            }
+            if !fun_type_parameters_compatibile(
+                &old_func.type_parameters,
+                &new_func.type_parameters,
+            ) {
+                add(
+                    &[requirement],
+                    format!(
+                        "{} `{}` type parameters changed from `<{}>` to `<{}>`",
+                        kind,
+                        name,
+                        display_fun_type_parameters(&old_func.type_parameters),
+                        display_fun_type_parameters(&new_func.type_parameters)
+                    ),
+                );
             }
         }
 
@@ -183,32 +291,116 @@ impl Compatibility {
         // - additions to the list are allowed
         // - removals are not allowed
         //
-        let old_friend_module_ids: BTreeSet<_> = old_module.friends.iter().cloned().collect();
-        let new_friend_module_ids: BTreeSet<_> = new_module.friends.iter().cloned().collect();
-        if !old_friend_module_ids.is_subset(&new_friend_module_ids) {
-            friend_linking = false;
+        let new_friend_module_ids: BTreeSet<_> = new_module.friends.iter().collect();
+        for friend in old_module.friends.iter().collect::<BTreeSet<_>>() {
+            if !new_friend_module_ids.contains(friend) {
+                add(
+                    &[FriendLinking],
+                    format!("friend `{}` was removed", friend.short_str_lossless()),
+                );
+            }
         }
 
-        if self.check_struct_and_pub_function_linking && !struct_and_pub_function_linking {
-            return Err(PartialVMError::new(
-                StatusCode::BACKWARD_INCOMPATIBLE_MODULE_UPDATE,
-            ));
-        }
-        if self.check_struct_layout && !struct_layout {
-            return Err(PartialVMError::new(
-                StatusCode::BACKWARD_INCOMPATIBLE_MODULE_UPDATE,
-            ));
-        }
-        if self.check_friend_linking && !friend_linking {
-            return Err(PartialVMError::new(
-                StatusCode::BACKWARD_INCOMPATIBLE_MODULE_UPDATE,
-            ));
+        changes.retain(|change| {
+            change
+                .requirements
+                .iter()
+                .any(|requirement| self.checks(*requirement))
+        });
+        changes
+    }
+
+    /// Whether this configuration checks the given requirement
+    pub fn checks(&self, requirement: CompatibilityRequirement) -> bool {
+        match requirement {
+            CompatibilityRequirement::StructAndPubFunctionLinking => {
+                self.check_struct_and_pub_function_linking
+            },
+            CompatibilityRequirement::StructLayout => self.check_struct_layout,
+            CompatibilityRequirement::FriendLinking => self.check_friend_linking,
         }
+    }
+}
 
-        Ok(())
+/// A requirement of a compatible upgrade, which can be checked or not by a `Compatibility`
+#[derive(PartialEq, Eq, Debug, Clone, Copy, PartialOrd, Ord)]
+pub enum CompatibilityRequirement {
+    /// Dependent modules that reference public functions or structs of the module still link
+    StructAndPubFunctionLinking,
+    /// Structs published by the module can still be read
+    StructLayout,
+    /// Friend modules that reference friend functions of the module still link
+    FriendLinking,
+}
+
+/// A change between two versions of a module that breaks compatibility
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct IncompatibleChange {
+    /// Requirements broken by the change
+    pub requirements: Vec<CompatibilityRequirement>,
+    /// Human readable description of the change, e.g. "struct `S` was removed"
+    pub description: String,
+}
+
+impl std::fmt::Display for IncompatibleChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.description)
     }
 }
 
+fn display_abilities(abilities: AbilitySet) -> String {
+    abilities
+        .into_iter()
+        .map(|ability| format!("{:?}", ability).to_lowercase())
+        .collect::<Vec<_>>()
+        .join(" + ")
+}
+
+fn display_struct_type_parameters(type_parameters: &[StructTypeParameter]) -> String {
+    type_parameters
+        .iter()
+        .enumerate()
+        .map(|(idx, type_parameter)| {
+            format!(
+                "{}T{}: {}",
+                if type_parameter.is_phantom {
+                    "phantom "
+                } else {
+                    ""
+                },
+                idx,
+                display_abilities(type_parameter.constraints)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn display_fun_type_parameters(type_parameters: &[AbilitySet]) -> String {
+    type_parameters
+        .iter()
+        .enumerate()
+        .map(|(idx, constraints)| format!("T{}: {}", idx, display_abilities(*constraints)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn display_fields(fields: &[Field]) -> String {
+    fields
+        .iter()
+        .map(|field| format!("{}: {}", field.name, field.type_))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn display_types(types: &[Type]) -> String {
+    types
+        .iter()
+        .map(|ty| ty.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 // When upgrading, the new abilities must be a superset of the old abilities.
 // Adding an ability is fine, but removing an ability could cause existing usages to fail.
 fn struct_abilities_compatibile(old_abilities: AbilitySet, new_abilities: AbilitySet) -> bool {
@@ -114,3 +114,39 @@ fn deprecated_add_script_visibility() {
         .check(&friend_module, &script_module)
         .is_err());
 }
+
+#[test]
+fn incompatible_changes_are_described() {
+    let script_module = mk_module(Visibility::DEPRECATED_SCRIPT);
+    let public_module = mk_module(Visibility::Public as u8);
+    let private_module = mk_module(Visibility::Private as u8);
+    let friend_module = mk_module(Visibility::Friend as u8);
+
+    let descriptions =
+        |old: &normalized::Module, new: &normalized::Module, compatibility: Compatibility| {
+            compatibility
+                .incompatible_changes(old, new)
+                .into_iter()
+                .map(|change| change.to_string())
+                .collect::<Vec<_>>()
+        };
+    assert_eq!(
+        descriptions(&public_module, &script_module, Compatibility::full_check()),
+        vec!["public function `fn` became an entry function"]
+    );
+    assert_eq!(
+        descriptions(&public_module, &private_module, Compatibility::full_check()),
+        vec!["public function `fn` was removed"]
+    );
+    // Changes to friend functions are ignored when friends are treated as private
+    assert_eq!(
+        descriptions(&friend_module, &private_module, Compatibility::full_check()),
+        vec!["friend function `fn` was removed"]
+    );
+    assert!(descriptions(
+        &friend_module,
+        &private_module,
+        Compatibility::new(true, true, false)
+    )
+    .is_empty());
+}
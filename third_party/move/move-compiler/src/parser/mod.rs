@@ -13,7 +13,7 @@ pub(crate) mod syntax;
 use crate::{
     attr_derivation,
     diagnostics::{codes::Severity, Diagnostics, FilesSourceText},
-    parser::{self, ast::PackageDefinition, syntax::parse_file_string},
+    parser::{self, ast::PackageDefinition},
     shared::{CompilationEnv, IndexedPackagePath, NamedAddressMaps},
 };
 use anyhow::anyhow;
@@ -25,6 +25,7 @@ use std::{
     fs::File,
     io::Read,
 };
+pub use syntax::parse_file_string;
 
 pub(crate) fn parse_program(
     compilation_env: &mut CompilationEnv,
@@ -545,6 +545,14 @@ impl Interpreter {
         match data_store.load_resource(loader, addr, ty) {
             Ok((gv, load_res)) => {
                 if let Some(bytes_loaded) = load_res {
+                    #[cfg(any(debug_assertions, feature = "debugging"))]
+                    crate::tracing::trace_resource_read(
+                        loader,
+                        addr,
+                        ty,
+                        bytes_loaded,
+                        gv.view().is_some(),
+                    );
                     gas_meter.charge_load_resource(
                         addr,
                         TypeWithLoader { ty, loader },
@@ -758,6 +766,17 @@ impl Interpreter {
         Ok(())
     }
 
+    /// Number of frames below the one currently executing.
+    #[allow(dead_code)]
+    pub(crate) fn call_depth(&self) -> usize {
+        self.call_stack.0.len()
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn operand_stack_values(&self) -> &[Value] {
+        &self.operand_stack.value
+    }
+
     #[allow(dead_code)]
     pub(crate) fn debug_print_stack_trace<B: Write>(
         &self,
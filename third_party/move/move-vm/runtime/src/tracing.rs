@@ -11,10 +11,19 @@ use crate::{
 };
 #[cfg(any(debug_assertions, feature = "debugging"))]
 use ::{
-    move_binary_format::file_format::Bytecode,
-    move_vm_types::values::Locals,
+    move_binary_format::file_format::{Bytecode, FunctionDefinitionIndex},
+    move_core_types::{
+        account_address::AccountAddress,
+        gas_algebra::NumBytes,
+        language_storage::{ModuleId, TypeTag},
+    },
+    move_vm_types::{
+        loaded_data::runtime_types::Type,
+        values::{Locals, Value},
+    },
     once_cell::sync::Lazy,
     std::{
+        cell::RefCell,
         env,
         fs::{File, OpenOptions},
         io::Write,
@@ -57,6 +66,72 @@ static LOGGING_FILE: Lazy<Mutex<File>> = Lazy::new(|| {
 #[cfg(any(debug_assertions, feature = "debugging"))]
 static DEBUG_CONTEXT: Lazy<Mutex<DebugContext>> = Lazy::new(|| Mutex::new(DebugContext::new()));
 
+#[cfg(any(debug_assertions, feature = "debugging"))]
+thread_local! {
+    static EXECUTION_TRACER: RefCell<Option<Box<dyn ExecutionTracer>>> = RefCell::new(None);
+}
+
+/// The state of the interpreter right before executing an instruction.
+#[cfg(any(debug_assertions, feature = "debugging"))]
+pub struct InstructionStep<'a> {
+    /// Number of frames below the current one, in the current entrypoint invocation.
+    pub call_depth: usize,
+    /// `None` for scripts.
+    pub module: Option<&'a ModuleId>,
+    pub function: &'a str,
+    pub function_index: FunctionDefinitionIndex,
+    pub pc: u16,
+    pub instruction: &'a Bytecode,
+    pub locals: &'a Locals,
+    pub operand_stack: &'a [Value],
+}
+
+/// Observes the execution of the Move VM on the current thread, see [`set_execution_tracer`].
+///
+/// Unlike the tracing enabled by the `MOVE_VM_TRACE` environment variable, this is driven
+/// programmatically, e.g. to trace individual transactions when replaying them.
+#[cfg(any(debug_assertions, feature = "debugging"))]
+pub trait ExecutionTracer {
+    /// Called before an instruction is executed.
+    fn instruction(&mut self, step: InstructionStep);
+
+    /// Called when a resource is read from storage, i.e. the first time it is accessed.
+    fn resource_read(
+        &mut self,
+        address: AccountAddress,
+        ty: &TypeTag,
+        bytes_loaded: NumBytes,
+        exists: bool,
+    );
+}
+
+/// Installs a tracer observing everything executed on the current thread from now on, or
+/// uninstalls it with `None`. Returns the previously installed tracer.
+#[cfg(any(debug_assertions, feature = "debugging"))]
+pub fn set_execution_tracer(
+    tracer: Option<Box<dyn ExecutionTracer>>,
+) -> Option<Box<dyn ExecutionTracer>> {
+    EXECUTION_TRACER.with(|current| current.replace(tracer))
+}
+
+#[cfg(any(debug_assertions, feature = "debugging"))]
+pub(crate) fn trace_resource_read(
+    loader: &Loader,
+    address: AccountAddress,
+    ty: &Type,
+    bytes_loaded: NumBytes,
+    exists: bool,
+) {
+    EXECUTION_TRACER.with(|tracer| {
+        if let Some(tracer) = tracer.borrow_mut().as_mut() {
+            // Types of loaded resources can always be converted.
+            if let Ok(ty) = loader.type_to_type_tag(ty) {
+                tracer.resource_read(address, &ty, bytes_loaded, exists);
+            }
+        }
+    });
+}
+
 // Only include in debug builds
 #[cfg(any(debug_assertions, feature = "debugging"))]
 pub(crate) fn trace(
@@ -80,6 +155,20 @@ pub(crate) fn trace(
         )
         .unwrap();
     }
+    EXECUTION_TRACER.with(|tracer| {
+        if let Some(tracer) = tracer.borrow_mut().as_mut() {
+            tracer.instruction(InstructionStep {
+                call_depth: interp.call_depth(),
+                module: function_desc.module_id(),
+                function: function_desc.name(),
+                function_index: function_desc.index(),
+                pc,
+                instruction: instr,
+                locals,
+                operand_stack: interp.operand_stack_values(),
+            });
+        }
+    });
     if *DEBUGGING_ENABLED {
         DEBUG_CONTEXT
             .lock()
@@ -2910,6 +2910,20 @@ pub mod debug {
     pub fn print_value<B: Write>(buf: &mut B, val: &Value) -> PartialVMResult<()> {
         print_value_impl(buf, &val.0)
     }
+
+    /// Prints every local on its own, invalid (e.g. moved) locals are printed as `-`.
+    pub fn print_each_local(locals: &Locals) -> PartialVMResult<Vec<String>> {
+        locals
+            .0
+            .borrow()
+            .iter()
+            .map(|val| {
+                let mut buf = String::new();
+                print_value_impl(&mut buf, val)?;
+                Ok(buf)
+            })
+            .collect()
+    }
 }
 
 /***************************************************************************************
@@ -15,6 +15,12 @@ pub trait PackageHooks {
     /// Returns custom fields allowed in `PackageInfo`.
     fn custom_package_info_fields(&self) -> Vec<String>;
 
+    /// Returns custom top-level sections allowed in the manifest, e.g. to configure tools
+    /// operating on the package.
+    fn custom_manifest_sections(&self) -> Vec<String> {
+        vec![]
+    }
+
     /// Returns a custom key for dependencies, if available. This is the string used
     /// in dependencies `{ <key> = value, address = addr }`.
     fn custom_dependency_key(&self) -> Option<String>;
@@ -64,3 +70,12 @@ pub(crate) fn custom_package_info_fields() -> Vec<String> {
         vec![]
     }
 }
+
+/// Calls any registered hook to return custom manifest sections.
+pub(crate) fn custom_manifest_sections() -> Vec<String> {
+    if let Some(hooks) = &*HOOKS.lock().unwrap() {
+        hooks.custom_manifest_sections()
+    } else {
+        vec![]
+    }
+}
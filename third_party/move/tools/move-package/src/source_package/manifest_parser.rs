@@ -52,7 +52,13 @@ pub fn parse_source_manifest(tval: TV) -> Result<PM::SourceManifest> {
         TV::Table(mut table) => {
             check_for_required_field_names(&table, REQUIRED_FIELDS)
                 .context("Error parsing package manifest")?;
-            warn_if_unknown_field_names(&table, KNOWN_NAMES);
+            let hook_names = package_hooks::custom_manifest_sections();
+            let known_names = KNOWN_NAMES
+                .iter()
+                .copied()
+                .chain(hook_names.iter().map(|s| s.as_str()))
+                .collect::<Vec<_>>();
+            warn_if_unknown_field_names(&table, known_names.as_slice());
             let addresses = table
                 .remove(ADDRESSES_NAME)
                 .map(parse_addresses)
@@ -0,0 +1,48 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use move_core_types::{
+    account_address::AccountAddress,
+    ident_str,
+    identifier::{IdentStr, Identifier},
+    language_storage::ModuleId,
+    move_resource::{MoveResource, MoveStructType},
+};
+use serde::{Deserialize, Serialize};
+
+/// A Rust representation of DispatchableAuthenticator, the authentication function registered
+/// by an account that uses account abstraction.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct DispatchableAuthenticatorResource {
+    module_address: AccountAddress,
+    module_name: String,
+    function_name: String,
+}
+
+impl DispatchableAuthenticatorResource {
+    pub fn new(module_address: AccountAddress, module_name: String, function_name: String) -> Self {
+        Self {
+            module_address,
+            module_name,
+            function_name,
+        }
+    }
+
+    /// Returns the module and name of the authentication function, or None if the registered
+    /// names are not valid identifiers.
+    pub fn function(&self) -> Option<(ModuleId, Identifier)> {
+        let module_name = Identifier::new(self.module_name.as_str()).ok()?;
+        let function_name = Identifier::new(self.function_name.as_str()).ok()?;
+        Some((
+            ModuleId::new(self.module_address, module_name),
+            function_name,
+        ))
+    }
+}
+
+impl MoveStructType for DispatchableAuthenticatorResource {
+    const MODULE_NAME: &'static IdentStr = ident_str!("account_abstraction");
+    const STRUCT_NAME: &'static IdentStr = ident_str!("DispatchableAuthenticator");
+}
+
+impl MoveResource for DispatchableAuthenticatorResource {}
@@ -34,6 +34,10 @@ pub enum FeatureFlag {
     BULLETPROOFS_NATIVES = 24,
     SIGNER_NATIVE_FORMAT_FIX = 25,
     MODULE_EVENT = 26,
+    SECP256K1_ECDSA_AUTHENTICATOR = 27,
+    WEBAUTHN_AUTHENTICATOR = 28,
+    ACCOUNT_ABSTRACTION = 29,
+    ACCESS_SUMMARY_METADATA = 30,
 }
 
 /// Representation of features on chain as a bitset.
@@ -75,6 +79,22 @@ impl Features {
     pub fn is_module_event_enabled(&self) -> bool {
         self.is_enabled(FeatureFlag::MODULE_EVENT)
     }
+
+    pub fn is_secp256k1_ecdsa_authenticator_enabled(&self) -> bool {
+        self.is_enabled(FeatureFlag::SECP256K1_ECDSA_AUTHENTICATOR)
+    }
+
+    pub fn is_webauthn_authenticator_enabled(&self) -> bool {
+        self.is_enabled(FeatureFlag::WEBAUTHN_AUTHENTICATOR)
+    }
+
+    pub fn is_account_abstraction_enabled(&self) -> bool {
+        self.is_enabled(FeatureFlag::ACCOUNT_ABSTRACTION)
+    }
+
+    pub fn is_access_summary_metadata_enabled(&self) -> bool {
+        self.is_enabled(FeatureFlag::ACCESS_SUMMARY_METADATA)
+    }
 }
 
 // --------------------------------------------------------------------------------------------
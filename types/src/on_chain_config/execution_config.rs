@@ -109,6 +109,12 @@ pub enum TransactionShufflerType {
     NoShuffling,
     DeprecatedSenderAwareV1(u32),
     SenderAwareV2(u32),
+    /// Spreads transactions with overlapping read/write hints apart within `conflict_window_size`,
+    /// and optionally orders transactions by descending gas unit price (per-sender order is kept).
+    ConflictAwareV1 {
+        conflict_window_size: u32,
+        order_by_gas_price: bool,
+    },
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -182,6 +188,31 @@ mod test {
             TransactionShufflerType::SenderAwareV2(32)
         ));
         assert!(matches!(result.block_gas_limit(), None));
+
+        // V3 test with conflict aware shuffler
+        let config = OnChainExecutionConfig::V3(ExecutionConfigV3 {
+            transaction_shuffler_type: TransactionShufflerType::ConflictAwareV1 {
+                conflict_window_size: 16,
+                order_by_gas_price: true,
+            },
+            block_gas_limit: None,
+            transaction_deduper_type: TransactionDeduperType::TxnHashAndAuthenticatorV1,
+        });
+
+        let s = serde_yaml::to_string(&config).unwrap();
+        let result = serde_yaml::from_str::<OnChainExecutionConfig>(&s).unwrap();
+        assert_eq!(
+            result.transaction_shuffler_type(),
+            TransactionShufflerType::ConflictAwareV1 {
+                conflict_window_size: 16,
+                order_by_gas_price: true,
+            }
+        );
+        let s = bcs::to_bytes(&config).unwrap();
+        assert_eq!(
+            bcs::from_bytes::<OnChainExecutionConfig>(&s).unwrap(),
+            config
+        );
     }
 
     #[test]
@@ -41,6 +41,30 @@ impl StateStorageUsage {
         }
     }
 
+    /// Returns the (tracked) usage after adding the given deltas.
+    ///
+    /// Panics if the usage would become negative.
+    pub fn apply_delta(&self, items_delta: i64, bytes_delta: i64) -> Self {
+        Self::new(
+            self.items()
+                .checked_add_signed(items_delta as isize)
+                .expect("State storage items must not underflow."),
+            self.bytes()
+                .checked_add_signed(bytes_delta as isize)
+                .expect("State storage bytes must not underflow."),
+        )
+    }
+
+    /// Like [`Self::apply_delta`], but saturates at zero. Only meant for a forked state, whose
+    /// deltas can remove items that were never accounted for (its values predate the local
+    /// state).
+    pub fn apply_delta_saturating(&self, items_delta: i64, bytes_delta: i64) -> Self {
+        Self::new(
+            self.items().saturating_add_signed(items_delta as isize),
+            self.bytes().saturating_add_signed(bytes_delta as isize),
+        )
+    }
+
     pub fn add_item(&mut self, bytes_delta: usize) {
         match self {
             Self::Tracked {
@@ -66,4 +90,18 @@ impl StateStorageUsage {
             Self::Untracked => (),
         }
     }
+
+    /// Like [`Self::remove_item`], but saturates at zero, see [`Self::apply_delta_saturating`].
+    pub fn remove_item_saturating(&mut self, bytes_delta: usize) {
+        match self {
+            Self::Tracked {
+                ref mut items,
+                ref mut bytes,
+            } => {
+                *items = items.saturating_sub(1);
+                *bytes = bytes.saturating_sub(bytes_delta);
+            },
+            Self::Untracked => (),
+        }
+    }
 }
@@ -0,0 +1,316 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Summaries of the resources an entry function may read and write, derived from its bytecode
+//! when the module is built and published as part of the module metadata. A summary is
+//! conservative: every resource the function accesses is covered, possibly by a wildcard over
+//! all addresses. It is instantiated with the signers and arguments of a transaction calling the
+//! function to get the read and write hints of the transaction.
+
+use crate::{
+    access_path::AccessPath, state_store::state_key::StateKey,
+    transaction::analyzed_transaction::StorageLocation,
+};
+use move_core_types::{
+    account_address::AccountAddress,
+    identifier::Identifier,
+    language_storage::{StructTag, TypeTag},
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// The address a resource is accessed at, relative to the call of the entry function.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub enum AccessAddress {
+    /// The address of the i-th signer parameter: the sender, then the secondary signers.
+    Signer(u16),
+    /// The i-th non-signer parameter, i.e. the i-th argument of the transaction.
+    Argument(u16),
+    Constant(AccountAddress),
+    /// Any address, e.g. when it is computed or read from storage.
+    Any,
+}
+
+/// A type, which can refer to the type parameters of the entry function.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub enum AccessType {
+    Bool,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    U256,
+    Address,
+    Signer,
+    Vector(Box<AccessType>),
+    Struct(AccessStructTag),
+    TypeParameter(u16),
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub struct AccessStructTag {
+    pub address: AccountAddress,
+    pub module: Identifier,
+    pub name: Identifier,
+    pub type_args: Vec<AccessType>,
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub struct ResourceAccess {
+    pub resource: AccessStructTag,
+    /// Whether `resource` is a resource group, accessed through any of its members.
+    pub resource_group: bool,
+    pub address: AccessAddress,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct AccessSummary {
+    pub reads: BTreeSet<ResourceAccess>,
+    pub writes: BTreeSet<ResourceAccess>,
+}
+
+impl AccessType {
+    pub fn instantiate(&self, ty_args: &[TypeTag]) -> Option<TypeTag> {
+        Some(match self {
+            AccessType::Bool => TypeTag::Bool,
+            AccessType::U8 => TypeTag::U8,
+            AccessType::U16 => TypeTag::U16,
+            AccessType::U32 => TypeTag::U32,
+            AccessType::U64 => TypeTag::U64,
+            AccessType::U128 => TypeTag::U128,
+            AccessType::U256 => TypeTag::U256,
+            AccessType::Address => TypeTag::Address,
+            AccessType::Signer => TypeTag::Signer,
+            AccessType::Vector(ty) => TypeTag::Vector(Box::new(ty.instantiate(ty_args)?)),
+            AccessType::Struct(tag) => TypeTag::Struct(Box::new(tag.instantiate(ty_args)?)),
+            AccessType::TypeParameter(idx) => ty_args.get(*idx as usize)?.clone(),
+        })
+    }
+}
+
+impl AccessStructTag {
+    pub fn instantiate(&self, ty_args: &[TypeTag]) -> Option<StructTag> {
+        Some(StructTag {
+            address: self.address,
+            module: self.module.clone(),
+            name: self.name.clone(),
+            type_params: self
+                .type_args
+                .iter()
+                .map(|ty| ty.instantiate(ty_args))
+                .collect::<Option<_>>()?,
+        })
+    }
+}
+
+impl ResourceAccess {
+    /// Returns the location accessed by a call with the given signers, type arguments and BCS
+    /// encoded arguments, or `None` if the type arguments don't match the entry function.
+    /// Addresses that can't be resolved (e.g. a malformed argument) fall back to a wildcard.
+    pub fn instantiate(
+        &self,
+        signers: &[AccountAddress],
+        ty_args: &[TypeTag],
+        args: &[Vec<u8>],
+    ) -> Option<StorageLocation> {
+        let struct_tag = self.resource.instantiate(ty_args)?;
+        let address = match &self.address {
+            AccessAddress::Signer(idx) => signers.get(*idx as usize).copied(),
+            AccessAddress::Argument(idx) => args
+                .get(*idx as usize)
+                .and_then(|arg| bcs::from_bytes::<AccountAddress>(arg).ok()),
+            AccessAddress::Constant(address) => Some(*address),
+            AccessAddress::Any => None,
+        };
+        Some(match address {
+            Some(address) if self.resource_group => StorageLocation::Specific(
+                StateKey::access_path(AccessPath::resource_group_access_path(address, struct_tag)),
+            ),
+            Some(address) => StorageLocation::Specific(StateKey::access_path(
+                AccessPath::resource_access_path(address, struct_tag).ok()?,
+            )),
+            None => StorageLocation::WildCardStruct(struct_tag),
+        })
+    }
+}
+
+impl AccessSummary {
+    pub fn is_empty(&self) -> bool {
+        self.reads.is_empty() && self.writes.is_empty()
+    }
+
+    /// Returns the read and write hints of a call with the given signers, type arguments and BCS
+    /// encoded arguments. Locations both read and written are only part of the write hints.
+    pub fn instantiate(
+        &self,
+        signers: &[AccountAddress],
+        ty_args: &[TypeTag],
+        args: &[Vec<u8>],
+    ) -> Option<(Vec<StorageLocation>, Vec<StorageLocation>)> {
+        let mut write_hints = vec![];
+        for access in &self.writes {
+            let location = access.instantiate(signers, ty_args, args)?;
+            if !write_hints.contains(&location) {
+                write_hints.push(location);
+            }
+        }
+        let mut read_hints = vec![];
+        for access in &self.reads {
+            let location = access.instantiate(signers, ty_args, args)?;
+            if !write_hints.contains(&location) && !read_hints.contains(&location) {
+                read_hints.push(location);
+            }
+        }
+        Some((read_hints, write_hints))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use move_core_types::ident_str;
+
+    fn struct_tag(name: &str, type_args: Vec<AccessType>) -> AccessStructTag {
+        AccessStructTag {
+            address: AccountAddress::from_hex_literal("0xcafe").unwrap(),
+            module: ident_str!("m").to_owned(),
+            name: Identifier::new(name).unwrap(),
+            type_args,
+        }
+    }
+
+    fn access(resource: AccessStructTag, address: AccessAddress) -> ResourceAccess {
+        ResourceAccess {
+            resource,
+            resource_group: false,
+            address,
+        }
+    }
+
+    fn resource_location(address: AccountAddress, struct_tag: StructTag) -> StorageLocation {
+        StorageLocation::Specific(StateKey::access_path(
+            AccessPath::resource_access_path(address, struct_tag).unwrap(),
+        ))
+    }
+
+    fn arg(address: AccountAddress) -> Vec<u8> {
+        bcs::to_bytes(&address).unwrap()
+    }
+
+    #[test]
+    fn test_instantiate_addresses() {
+        let sender = AccountAddress::from_hex_literal("0x1234").unwrap();
+        let secondary_signer = AccountAddress::from_hex_literal("0x5678").unwrap();
+        let receiver = AccountAddress::from_hex_literal("0x9abc").unwrap();
+        let constant = AccountAddress::from_hex_literal("0xdef0").unwrap();
+        let tag = struct_tag("R", vec![]);
+        let summary = AccessSummary {
+            reads: BTreeSet::from([
+                access(tag.clone(), AccessAddress::Argument(1)),
+                access(tag.clone(), AccessAddress::Constant(constant)),
+                access(tag.clone(), AccessAddress::Any),
+            ]),
+            writes: BTreeSet::from([
+                access(tag.clone(), AccessAddress::Signer(0)),
+                access(tag.clone(), AccessAddress::Signer(1)),
+            ]),
+        };
+
+        // The first argument is a u64, the second the receiver's address.
+        let args = vec![bcs::to_bytes(&10u64).unwrap(), arg(receiver)];
+        let (read_hints, write_hints) = summary
+            .instantiate(&[sender, secondary_signer], &[], &args)
+            .unwrap();
+        let tag = tag.instantiate(&[]).unwrap();
+        assert_eq!(write_hints, vec![
+            resource_location(sender, tag.clone()),
+            resource_location(secondary_signer, tag.clone()),
+        ]);
+        assert_eq!(read_hints.len(), 3);
+        for location in [
+            resource_location(receiver, tag.clone()),
+            resource_location(constant, tag.clone()),
+            StorageLocation::WildCardStruct(tag.clone()),
+        ] {
+            assert!(read_hints.contains(&location), "{:?}", location);
+        }
+
+        // Addresses which can't be resolved fall back to a wildcard.
+        let (read_hints, write_hints) = summary.instantiate(&[sender], &[], &[vec![0; 3]]).unwrap();
+        assert_eq!(write_hints, vec![
+            resource_location(sender, tag.clone()),
+            StorageLocation::WildCardStruct(tag.clone()),
+        ]);
+        assert_eq!(read_hints, vec![resource_location(constant, tag)]);
+    }
+
+    #[test]
+    fn test_instantiate_generics() {
+        let sender = AccountAddress::from_hex_literal("0x1234").unwrap();
+        let summary = AccessSummary {
+            reads: BTreeSet::new(),
+            writes: BTreeSet::from([access(
+                struct_tag("Store", vec![
+                    AccessType::TypeParameter(1),
+                    AccessType::Vector(Box::new(AccessType::Struct(struct_tag("Inner", vec![
+                        AccessType::TypeParameter(0),
+                    ])))),
+                ]),
+                AccessAddress::Signer(0),
+            )]),
+        };
+
+        let ty_args = vec![TypeTag::U64, TypeTag::Address];
+        let (read_hints, write_hints) = summary.instantiate(&[sender], &ty_args, &[]).unwrap();
+        assert!(read_hints.is_empty());
+        let inner = struct_tag("Inner", vec![]).instantiate(&[]).unwrap();
+        let expected = StructTag {
+            type_params: vec![
+                TypeTag::Address,
+                TypeTag::Vector(Box::new(TypeTag::Struct(Box::new(StructTag {
+                    type_params: vec![TypeTag::U64],
+                    ..inner
+                })))),
+            ],
+            ..struct_tag("Store", vec![]).instantiate(&[]).unwrap()
+        };
+        assert_eq!(write_hints, vec![resource_location(sender, expected)]);
+
+        // Missing type arguments don't match the entry function.
+        assert_eq!(summary.instantiate(&[sender], &[TypeTag::U64], &[]), None);
+    }
+
+    #[test]
+    fn test_instantiate_resource_groups_and_overlaps() {
+        let sender = AccountAddress::from_hex_literal("0x1234").unwrap();
+        let group = struct_tag("Group", vec![]);
+        let tag = struct_tag("R", vec![]);
+        let summary = AccessSummary {
+            reads: BTreeSet::from([
+                access(tag.clone(), AccessAddress::Signer(0)),
+                access(tag.clone(), AccessAddress::Argument(0)),
+                ResourceAccess {
+                    resource: group.clone(),
+                    resource_group: true,
+                    address: AccessAddress::Signer(0),
+                },
+            ]),
+            writes: BTreeSet::from([access(tag.clone(), AccessAddress::Signer(0))]),
+        };
+
+        // The argument is the sender's address as well: the location is only written.
+        let (read_hints, write_hints) =
+            summary.instantiate(&[sender], &[], &[arg(sender)]).unwrap();
+        assert_eq!(write_hints, vec![resource_location(
+            sender,
+            tag.instantiate(&[]).unwrap()
+        )]);
+        assert_eq!(read_hints, vec![StorageLocation::Specific(
+            StateKey::access_path(AccessPath::resource_group_access_path(
+                sender,
+                group.instantiate(&[]).unwrap(),
+            ))
+        )]);
+    }
+}
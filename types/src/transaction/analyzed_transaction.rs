@@ -5,7 +5,9 @@ use crate::{
     access_path::AccessPath,
     account_config::{AccountResource, CoinStoreResource},
     state_store::{state_key::StateKey, table::TableHandle},
-    transaction::{SignedTransaction, Transaction, TransactionPayload},
+    transaction::{
+        access_summary::AccessSummary, SignedTransaction, Transaction, TransactionPayload,
+    },
 };
 use aptos_crypto::{hash::CryptoHash, HashValue};
 pub use move_core_types::abi::{
@@ -169,6 +171,81 @@ impl AnalyzedTransaction {
             read_hints,
         )
     }
+
+    /// Derives the hints of an entry function call from the access summary of the function. Besides
+    /// the resources accessed by the function, the prologue and epilogue write the sender's account
+    /// resource and the coin store paying for gas. Returns `None` for other payloads, or if the type
+    /// arguments don't match the summary.
+    pub fn access_summary_hints(
+        signed_txn: &SignedTransaction,
+        summary: &AccessSummary,
+    ) -> Option<(Vec<StorageLocation>, Vec<StorageLocation>)> {
+        let func = match signed_txn.payload() {
+            TransactionPayload::EntryFunction(func) => func,
+            _ => return None,
+        };
+        let authenticator = signed_txn.authenticator();
+        let mut signers = vec![signed_txn.sender()];
+        signers.extend(authenticator.secondary_signer_addreses());
+        let (read_hints, mut write_hints) =
+            summary.instantiate(&signers, func.ty_args(), func.args())?;
+
+        let gas_payer = authenticator
+            .fee_payer_address()
+            .unwrap_or_else(|| signed_txn.sender());
+        for location in [
+            Self::account_resource_location(signed_txn.sender()),
+            Self::coin_store_location(gas_payer),
+        ] {
+            if !write_hints.contains(&location) {
+                write_hints.push(location);
+            }
+        }
+        let read_hints = read_hints
+            .into_iter()
+            .filter(|location| !write_hints.contains(location))
+            .collect();
+        Some((read_hints, write_hints))
+    }
+
+    /// Same as `From<Transaction>` for user transactions, but never panics: payloads without known
+    /// hints (or with malformed arguments) conservatively get the sender's account resource as their
+    /// only write hint, since every user transaction bumps the sender's sequence number.
+    pub fn analyzed_transaction_with_fallback_hints(signed_txn: SignedTransaction) -> Self {
+        let sender_address = signed_txn.sender();
+        let receiver_address = match signed_txn.payload() {
+            TransactionPayload::EntryFunction(func)
+                if *func.module().address() == AccountAddress::ONE =>
+            {
+                match (func.module().name().as_str(), func.function().as_str()) {
+                    ("coin", "transfer")
+                    | ("aptos_account", "transfer")
+                    | ("aptos_account", "create_account") => func
+                        .args()
+                        .first()
+                        .and_then(|arg| bcs::from_bytes::<AccountAddress>(arg).ok())
+                        .map(|receiver| (func.module().name().as_str() == "coin", receiver)),
+                    _ => None,
+                }
+            },
+            _ => None,
+        };
+        match receiver_address {
+            Some((receiver_exists, receiver_address)) => {
+                Self::analyzed_transaction_for_coin_transfer(
+                    signed_txn,
+                    sender_address,
+                    receiver_address,
+                    receiver_exists,
+                )
+            },
+            None => {
+                AnalyzedTransaction::new(Transaction::UserTransaction(signed_txn), vec![], vec![
+                    Self::account_resource_location(sender_address),
+                ])
+            },
+        }
+    }
 }
 
 impl PartialEq<Self> for AnalyzedTransaction {
@@ -239,3 +316,198 @@ impl From<AnalyzedTransaction> for Transaction {
         val.transaction
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        chain_id::ChainId,
+        transaction::{
+            access_summary::{AccessAddress, AccessStructTag, AccessType, ResourceAccess},
+            authenticator::AccountAuthenticator,
+            EntryFunction, RawTransaction, RawTransactionWithData, Script,
+        },
+    };
+    use aptos_crypto::{ed25519::Ed25519PrivateKey, PrivateKey, SigningKey, Uniform};
+    use move_core_types::{
+        ident_str,
+        language_storage::{ModuleId, TypeTag},
+    };
+    use std::collections::BTreeSet;
+
+    fn address(literal: &str) -> AccountAddress {
+        AccountAddress::from_hex_literal(literal).unwrap()
+    }
+
+    fn raw_txn(sender: AccountAddress, payload: TransactionPayload) -> RawTransaction {
+        RawTransaction::new(
+            sender,
+            0,
+            payload,
+            1_000_000,
+            100,
+            u64::MAX,
+            ChainId::test(),
+        )
+    }
+
+    fn entry_function(ty_args: Vec<TypeTag>, receiver: AccountAddress) -> TransactionPayload {
+        TransactionPayload::EntryFunction(EntryFunction::new(
+            ModuleId::new(address("0xcafe"), ident_str!("m").to_owned()),
+            ident_str!("f").to_owned(),
+            ty_args,
+            vec![bcs::to_bytes(&receiver).unwrap()],
+        ))
+    }
+
+    fn signed_txn(raw_txn: RawTransaction) -> SignedTransaction {
+        let private_key = Ed25519PrivateKey::generate_for_testing();
+        let signature = private_key.sign(&raw_txn).unwrap();
+        SignedTransaction::new(raw_txn, private_key.public_key(), signature)
+    }
+
+    fn fee_payer_txn(
+        raw_txn: RawTransaction,
+        secondary_signer: AccountAddress,
+        fee_payer: AccountAddress,
+    ) -> SignedTransaction {
+        let message = RawTransactionWithData::new_fee_payer(
+            raw_txn.clone(),
+            vec![secondary_signer],
+            fee_payer,
+        );
+        let authenticator = || {
+            let private_key = Ed25519PrivateKey::generate_for_testing();
+            AccountAuthenticator::ed25519(
+                private_key.public_key(),
+                private_key.sign(&message).unwrap(),
+            )
+        };
+        SignedTransaction::new_fee_payer(
+            raw_txn,
+            authenticator(),
+            vec![secondary_signer],
+            vec![authenticator()],
+            fee_payer,
+            authenticator(),
+        )
+    }
+
+    /// The summary of `0xcafe::m::f<T>(sender: &signer, other: &signer, receiver: address)`,
+    /// writing `R<T>` at `other` and reading it at `receiver`, as well as reading the coin store
+    /// of the sender.
+    fn summary() -> AccessSummary {
+        let resource = AccessStructTag {
+            address: address("0xcafe"),
+            module: ident_str!("m").to_owned(),
+            name: ident_str!("R").to_owned(),
+            type_args: vec![AccessType::TypeParameter(0)],
+        };
+        let coin_store = AccessStructTag {
+            address: AccountAddress::ONE,
+            module: ident_str!("coin").to_owned(),
+            name: ident_str!("CoinStore").to_owned(),
+            type_args: vec![AccessType::Struct(AccessStructTag {
+                address: AccountAddress::ONE,
+                module: ident_str!("aptos_coin").to_owned(),
+                name: ident_str!("AptosCoin").to_owned(),
+                type_args: vec![],
+            })],
+        };
+        let access = |resource: &AccessStructTag, address| ResourceAccess {
+            resource: resource.clone(),
+            resource_group: false,
+            address,
+        };
+        AccessSummary {
+            reads: BTreeSet::from([
+                access(&resource, AccessAddress::Argument(0)),
+                access(&coin_store, AccessAddress::Signer(0)),
+            ]),
+            writes: BTreeSet::from([access(&resource, AccessAddress::Signer(1))]),
+        }
+    }
+
+    fn resource_location(address: AccountAddress) -> StorageLocation {
+        StorageLocation::Specific(StateKey::access_path(
+            AccessPath::resource_access_path(address, resource_tag()).unwrap(),
+        ))
+    }
+
+    fn resource_tag() -> StructTag {
+        StructTag {
+            address: address("0xcafe"),
+            module: ident_str!("m").to_owned(),
+            name: ident_str!("R").to_owned(),
+            type_params: vec![TypeTag::U64],
+        }
+    }
+
+    #[test]
+    fn test_access_summary_hints_with_fee_payer() {
+        let (sender, secondary_signer, fee_payer, receiver) = (
+            address("0x1234"),
+            address("0x5678"),
+            address("0x9abc"),
+            address("0xdef0"),
+        );
+        let txn = fee_payer_txn(
+            raw_txn(sender, entry_function(vec![TypeTag::U64], receiver)),
+            secondary_signer,
+            fee_payer,
+        );
+
+        // The fee payer pays for gas, so the sender's coin store is only read.
+        let (read_hints, write_hints) =
+            AnalyzedTransaction::access_summary_hints(&txn, &summary()).unwrap();
+        assert_eq!(write_hints, vec![
+            resource_location(secondary_signer),
+            AnalyzedTransaction::account_resource_location(sender),
+            AnalyzedTransaction::coin_store_location(fee_payer),
+        ]);
+        assert_eq!(read_hints, vec![
+            AnalyzedTransaction::coin_store_location(sender),
+            resource_location(receiver),
+        ]);
+    }
+
+    #[test]
+    fn test_access_summary_hints_of_sender() {
+        let (sender, receiver) = (address("0x1234"), address("0xdef0"));
+        let txn = signed_txn(raw_txn(
+            sender,
+            entry_function(vec![TypeTag::U64], receiver),
+        ));
+
+        // There is no secondary signer to resolve the written resource, and the sender's coin
+        // store is written by the epilogue.
+        let (read_hints, write_hints) =
+            AnalyzedTransaction::access_summary_hints(&txn, &summary()).unwrap();
+        assert_eq!(write_hints, vec![
+            StorageLocation::WildCardStruct(resource_tag()),
+            AnalyzedTransaction::account_resource_location(sender),
+            AnalyzedTransaction::coin_store_location(sender),
+        ]);
+        assert_eq!(read_hints, vec![resource_location(receiver)]);
+    }
+
+    #[test]
+    fn test_access_summary_hints_mismatch() {
+        let (sender, receiver) = (address("0x1234"), address("0xdef0"));
+
+        // The type arguments don't match the summary.
+        let txn = signed_txn(raw_txn(sender, entry_function(vec![], receiver)));
+        assert_eq!(
+            AnalyzedTransaction::access_summary_hints(&txn, &summary()),
+            None
+        );
+
+        // Only entry function calls have a summary.
+        let script = TransactionPayload::Script(Script::new(vec![], vec![], vec![]));
+        let txn = signed_txn(raw_txn(sender, script));
+        assert_eq!(
+            AnalyzedTransaction::access_summary_hints(&txn, &summary()),
+            None
+        );
+    }
+}
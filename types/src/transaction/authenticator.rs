@@ -4,14 +4,18 @@
 
 use crate::{
     account_address::AccountAddress,
-    transaction::{RawTransaction, RawTransactionWithData},
+    transaction::{
+        webauthn::PartialAuthenticatorAssertionResponse, RawTransaction, RawTransactionWithData,
+    },
 };
 use anyhow::{ensure, Error, Result};
 use aptos_crypto::{
     ed25519::{Ed25519PublicKey, Ed25519Signature},
     hash::CryptoHash,
     multi_ed25519::{MultiEd25519PublicKey, MultiEd25519Signature},
-    traits::Signature,
+    secp256k1_ecdsa::{Secp256k1EcdsaPublicKey, Secp256k1EcdsaSignature},
+    secp256r1_ecdsa::Secp256r1EcdsaPublicKey,
+    traits::{signing_message, Signature},
     CryptoMaterialError, HashValue, ValidCryptoMaterial, ValidCryptoMaterialStringExt,
 };
 use aptos_crypto_derive::{CryptoHasher, DeserializeKey, SerializeKey};
@@ -65,6 +69,21 @@ pub enum TransactionAuthenticator {
         fee_payer_address: AccountAddress,
         fee_payer_signer: AccountAuthenticator,
     },
+    /// Single Secp256k1 ECDSA signature
+    Secp256k1Ecdsa {
+        public_key: Secp256k1EcdsaPublicKey,
+        signature: Secp256k1EcdsaSignature,
+    },
+    /// Single WebAuthn (passkey) assertion
+    WebAuthn {
+        public_key: Secp256r1EcdsaPublicKey,
+        signature: PartialAuthenticatorAssertionResponse,
+    },
+    /// Single sender authenticated by the Move function registered on its account
+    Abstraction {
+        #[serde(with = "serde_bytes")]
+        authenticator: Vec<u8>,
+    },
 }
 
 impl TransactionAuthenticator {
@@ -117,7 +136,35 @@ impl TransactionAuthenticator {
         }
     }
 
-    /// Return Ok if all AccountAuthenticator's public keys match their signatures, Err otherwise
+    /// Create a single-signature secp256k1 ecdsa authenticator
+    pub fn secp256k1_ecdsa(
+        public_key: Secp256k1EcdsaPublicKey,
+        signature: Secp256k1EcdsaSignature,
+    ) -> Self {
+        Self::Secp256k1Ecdsa {
+            public_key,
+            signature,
+        }
+    }
+
+    /// Create a single WebAuthn assertion authenticator
+    pub fn webauthn(
+        public_key: Secp256r1EcdsaPublicKey,
+        signature: PartialAuthenticatorAssertionResponse,
+    ) -> Self {
+        Self::WebAuthn {
+            public_key,
+            signature,
+        }
+    }
+
+    /// Create a single account abstraction authenticator
+    pub fn abstraction(authenticator: Vec<u8>) -> Self {
+        Self::Abstraction { authenticator }
+    }
+
+    /// Return Ok if all AccountAuthenticator's public keys match their signatures, Err otherwise.
+    /// Account abstraction authenticators are not checked here, but by the VM during validation.
     pub fn verify(&self, raw_txn: &RawTransaction) -> Result<()> {
         let num_sigs: usize = self.sender().number_of_signatures()
             + self
@@ -173,9 +220,47 @@ impl TransactionAuthenticator {
                 }
                 Ok(())
             },
+            Self::Secp256k1Ecdsa {
+                public_key,
+                signature,
+            } => signature.verify(raw_txn, public_key),
+            Self::WebAuthn {
+                public_key,
+                signature,
+            } => signature.verify(raw_txn, public_key),
+            Self::Abstraction { .. } => Ok(()),
         }
     }
 
+    /// Returns the message signed (or otherwise authenticated) by all signers of the
+    /// transaction, which also binds the secondary signers and fee payer, if any.
+    pub fn signing_message(&self, raw_txn: &RawTransaction) -> Result<Vec<u8>> {
+        let message = match self {
+            Self::FeePayer {
+                secondary_signer_addresses,
+                fee_payer_address,
+                ..
+            } => signing_message(&RawTransactionWithData::new_fee_payer(
+                raw_txn.clone(),
+                secondary_signer_addresses.clone(),
+                *fee_payer_address,
+            )),
+            Self::MultiAgent {
+                secondary_signer_addresses,
+                ..
+            } => signing_message(&RawTransactionWithData::new_multi_agent(
+                raw_txn.clone(),
+                secondary_signer_addresses.clone(),
+            )),
+            Self::Ed25519 { .. }
+            | Self::MultiEd25519 { .. }
+            | Self::Secp256k1Ecdsa { .. }
+            | Self::WebAuthn { .. }
+            | Self::Abstraction { .. } => signing_message(raw_txn),
+        };
+        Ok(message?)
+    }
+
     pub fn sender(&self) -> AccountAuthenticator {
         match self {
             Self::Ed25519 {
@@ -191,6 +276,17 @@ impl TransactionAuthenticator {
                 signature,
             } => AccountAuthenticator::multi_ed25519(public_key.clone(), signature.clone()),
             Self::MultiAgent { sender, .. } => sender.clone(),
+            Self::Secp256k1Ecdsa {
+                public_key,
+                signature,
+            } => AccountAuthenticator::secp256k1_ecdsa(public_key.clone(), signature.clone()),
+            Self::WebAuthn {
+                public_key,
+                signature,
+            } => AccountAuthenticator::webauthn(public_key.clone(), signature.clone()),
+            Self::Abstraction { authenticator } => {
+                AccountAuthenticator::abstraction(authenticator.clone())
+            },
         }
     }
 
@@ -200,7 +296,10 @@ impl TransactionAuthenticator {
             | Self::MultiEd25519 {
                 public_key: _,
                 signature: _,
-            } => vec![],
+            }
+            | Self::Secp256k1Ecdsa { .. }
+            | Self::WebAuthn { .. }
+            | Self::Abstraction { .. } => vec![],
             Self::FeePayer {
                 sender: _,
                 secondary_signer_addresses,
@@ -220,7 +319,10 @@ impl TransactionAuthenticator {
             | Self::MultiEd25519 {
                 public_key: _,
                 signature: _,
-            } => vec![],
+            }
+            | Self::Secp256k1Ecdsa { .. }
+            | Self::WebAuthn { .. }
+            | Self::Abstraction { .. } => vec![],
             Self::FeePayer {
                 sender: _,
                 secondary_signer_addresses: _,
@@ -235,9 +337,23 @@ impl TransactionAuthenticator {
         }
     }
 
+    /// Returns all account authenticators (i.e., of the sender, the secondary signers and the
+    /// fee payer, if any) included in this transaction authenticator.
+    pub fn all_signers(&self) -> Vec<AccountAuthenticator> {
+        let mut signers = vec![self.sender()];
+        signers.extend(self.secondary_signers());
+        signers.extend(self.fee_payer_signer());
+        signers
+    }
+
     pub fn fee_payer_address(&self) -> Option<AccountAddress> {
         match self {
-            Self::Ed25519 { .. } | Self::MultiEd25519 { .. } | Self::MultiAgent { .. } => None,
+            Self::Ed25519 { .. }
+            | Self::MultiEd25519 { .. }
+            | Self::MultiAgent { .. }
+            | Self::Secp256k1Ecdsa { .. }
+            | Self::WebAuthn { .. }
+            | Self::Abstraction { .. } => None,
             Self::FeePayer {
                 sender: _,
                 secondary_signer_addresses: _,
@@ -250,7 +366,12 @@ impl TransactionAuthenticator {
 
     pub fn fee_payer_signer(&self) -> Option<AccountAuthenticator> {
         match self {
-            Self::Ed25519 { .. } | Self::MultiEd25519 { .. } | Self::MultiAgent { .. } => None,
+            Self::Ed25519 { .. }
+            | Self::MultiEd25519 { .. }
+            | Self::MultiAgent { .. }
+            | Self::Secp256k1Ecdsa { .. }
+            | Self::WebAuthn { .. }
+            | Self::Abstraction { .. } => None,
             Self::FeePayer {
                 sender: _,
                 secondary_signer_addresses: _,
@@ -335,6 +456,33 @@ impl fmt::Display for TransactionAuthenticator {
                     sender, sec_addrs, sec_signers,
                 )
             },
+            Self::Secp256k1Ecdsa {
+                public_key: _,
+                signature: _,
+            } => {
+                write!(
+                    f,
+                    "TransactionAuthenticator[scheme: Secp256k1Ecdsa, sender: {}]",
+                    self.sender()
+                )
+            },
+            Self::WebAuthn {
+                public_key: _,
+                signature: _,
+            } => {
+                write!(
+                    f,
+                    "TransactionAuthenticator[scheme: WebAuthn, sender: {}]",
+                    self.sender()
+                )
+            },
+            Self::Abstraction { .. } => {
+                write!(
+                    f,
+                    "TransactionAuthenticator[scheme: Abstraction, sender: {}]",
+                    self.sender()
+                )
+            },
         }
     }
 }
@@ -352,6 +500,9 @@ impl fmt::Display for TransactionAuthenticator {
 pub enum Scheme {
     Ed25519 = 0,
     MultiEd25519 = 1,
+    Secp256k1Ecdsa = 2,
+    WebAuthn = 3,
+    Abstraction = 4,
     // ... add more schemes here
     /// Scheme identifier used to derive addresses (not the authentication key) of objects and
     /// resources accounts. This application serves to domain separate hashes. Without such
@@ -369,6 +520,9 @@ impl fmt::Display for Scheme {
         let display = match self {
             Scheme::Ed25519 => "Ed25519",
             Scheme::MultiEd25519 => "MultiEd25519",
+            Scheme::Secp256k1Ecdsa => "Secp256k1Ecdsa",
+            Scheme::WebAuthn => "WebAuthn",
+            Scheme::Abstraction => "Abstraction",
             Scheme::DeriveAuid => "DeriveAuid",
             Scheme::DeriveObjectAddressFromObject => "DeriveObjectAddressFromObject",
             Scheme::DeriveObjectAddressFromGuid => "DeriveObjectAddressFromGuid",
@@ -391,6 +545,21 @@ pub enum AccountAuthenticator {
         public_key: MultiEd25519PublicKey,
         signature: MultiEd25519Signature,
     },
+    /// Single Secp256k1 ECDSA signature
+    Secp256k1Ecdsa {
+        public_key: Secp256k1EcdsaPublicKey,
+        signature: Secp256k1EcdsaSignature,
+    },
+    /// Single WebAuthn (passkey) assertion
+    WebAuthn {
+        public_key: Secp256r1EcdsaPublicKey,
+        signature: PartialAuthenticatorAssertionResponse,
+    },
+    /// An opaque authenticator checked by the Move function registered on the signer's account
+    Abstraction {
+        #[serde(with = "serde_bytes")]
+        authenticator: Vec<u8>,
+    },
     // ... add more schemes here
 }
 
@@ -400,6 +569,9 @@ impl AccountAuthenticator {
         match self {
             Self::Ed25519 { .. } => Scheme::Ed25519,
             Self::MultiEd25519 { .. } => Scheme::MultiEd25519,
+            Self::Secp256k1Ecdsa { .. } => Scheme::Secp256k1Ecdsa,
+            Self::WebAuthn { .. } => Scheme::WebAuthn,
+            Self::Abstraction { .. } => Scheme::Abstraction,
         }
     }
 
@@ -422,7 +594,41 @@ impl AccountAuthenticator {
         }
     }
 
-    /// Return Ok if the authenticator's public key matches its signature, Err otherwise
+    /// Create a single-signature secp256k1 ecdsa authenticator
+    pub fn secp256k1_ecdsa(
+        public_key: Secp256k1EcdsaPublicKey,
+        signature: Secp256k1EcdsaSignature,
+    ) -> Self {
+        Self::Secp256k1Ecdsa {
+            public_key,
+            signature,
+        }
+    }
+
+    /// Create a single WebAuthn assertion authenticator
+    pub fn webauthn(
+        public_key: Secp256r1EcdsaPublicKey,
+        signature: PartialAuthenticatorAssertionResponse,
+    ) -> Self {
+        Self::WebAuthn {
+            public_key,
+            signature,
+        }
+    }
+
+    /// Create an account abstraction authenticator
+    pub fn abstraction(authenticator: Vec<u8>) -> Self {
+        Self::Abstraction { authenticator }
+    }
+
+    /// Whether the authenticator is checked by a Move function registered on the account,
+    /// rather than by a native signature scheme.
+    pub fn is_abstraction(&self) -> bool {
+        matches!(self, Self::Abstraction { .. })
+    }
+
+    /// Return Ok if the authenticator's public key matches its signature, Err otherwise.
+    /// Account abstraction authenticators are not checked here, but by the VM during validation.
     pub fn verify<T: Serialize + CryptoHash>(&self, message: &T) -> Result<()> {
         match self {
             Self::Ed25519 {
@@ -433,6 +639,15 @@ impl AccountAuthenticator {
                 public_key,
                 signature,
             } => signature.verify(message, public_key),
+            Self::Secp256k1Ecdsa {
+                public_key,
+                signature,
+            } => signature.verify(message, public_key),
+            Self::WebAuthn {
+                public_key,
+                signature,
+            } => signature.verify(message, public_key),
+            Self::Abstraction { .. } => Ok(()),
         }
     }
 
@@ -441,15 +656,22 @@ impl AccountAuthenticator {
         match self {
             Self::Ed25519 { public_key, .. } => public_key.to_bytes().to_vec(),
             Self::MultiEd25519 { public_key, .. } => public_key.to_bytes().to_vec(),
+            Self::Secp256k1Ecdsa { public_key, .. } => public_key.to_bytes().to_vec(),
+            Self::WebAuthn { public_key, .. } => public_key.to_bytes().to_vec(),
+            Self::Abstraction { .. } => vec![],
         }
     }
 
-    /// Return the raw bytes of `self.signature`
-    pub fn signature_bytes(&self) -> Vec<u8> {
-        match self {
+    /// Return the raw bytes of `self.signature` (for WebAuthn, the BCS serialized assertion)
+    pub fn signature_bytes(&self) -> Result<Vec<u8>> {
+        let bytes = match self {
             Self::Ed25519 { signature, .. } => signature.to_bytes().to_vec(),
             Self::MultiEd25519 { signature, .. } => signature.to_bytes().to_vec(),
-        }
+            Self::Secp256k1Ecdsa { signature, .. } => signature.to_bytes().to_vec(),
+            Self::WebAuthn { signature, .. } => bcs::to_bytes(signature)?,
+            Self::Abstraction { authenticator } => authenticator.clone(),
+        };
+        Ok(bytes)
     }
 
     /// Return an authentication key preimage derived from `self`'s public key and scheme id
@@ -467,6 +689,9 @@ impl AccountAuthenticator {
         match self {
             Self::Ed25519 { .. } => 1,
             Self::MultiEd25519 { signature, .. } => signature.signatures().len(),
+            Self::Secp256k1Ecdsa { .. } => 1,
+            Self::WebAuthn { .. } => 1,
+            Self::Abstraction { .. } => 1,
         }
     }
 }
@@ -519,6 +744,16 @@ impl AuthenticationKey {
         Self::from_preimage(&AuthenticationKeyPreimage::multi_ed25519(public_key))
     }
 
+    /// Create an authentication key from a Secp256k1 ECDSA public key
+    pub fn secp256k1_ecdsa(public_key: &Secp256k1EcdsaPublicKey) -> Self {
+        Self::from_preimage(&AuthenticationKeyPreimage::secp256k1_ecdsa(public_key))
+    }
+
+    /// Create an authentication key from the Secp256r1 ECDSA public key of a WebAuthn credential
+    pub fn webauthn(public_key: &Secp256r1EcdsaPublicKey) -> Self {
+        Self::from_preimage(&AuthenticationKeyPreimage::webauthn(public_key))
+    }
+
     /// Return an address derived from the last `AccountAddress::LENGTH` bytes of this
     /// authentication key.
     pub fn derived_address(&self) -> AccountAddress {
@@ -574,6 +809,16 @@ impl AuthenticationKeyPreimage {
         Self::new(public_key.to_bytes(), Scheme::MultiEd25519)
     }
 
+    /// Construct a preimage from a Secp256k1 ECDSA public key (in its uncompressed form)
+    pub fn secp256k1_ecdsa(public_key: &Secp256k1EcdsaPublicKey) -> AuthenticationKeyPreimage {
+        Self::new(public_key.to_bytes().to_vec(), Scheme::Secp256k1Ecdsa)
+    }
+
+    /// Construct a preimage from the Secp256r1 ECDSA public key of a WebAuthn credential
+    pub fn webauthn(public_key: &Secp256r1EcdsaPublicKey) -> AuthenticationKeyPreimage {
+        Self::new(public_key.to_bytes().to_vec(), Scheme::WebAuthn)
+    }
+
     /// Construct a preimage from a transaction-derived AUID as (txn_hash || auid_scheme_id)
     pub fn auid(txn_hash: Vec<u8>, auid_counter: u64) -> AuthenticationKeyPreimage {
         let mut hash_arg = Vec::new();
@@ -596,7 +841,7 @@ impl fmt::Display for AccountAuthenticator {
             "AccountAuthenticator[scheme id: {:?}, public key: {}, signature: {}]",
             self.scheme(),
             hex::encode(self.public_key_bytes()),
-            hex::encode(self.signature_bytes())
+            hex::encode(self.signature_bytes().map_err(|_| fmt::Error)?)
         )
     }
 }
@@ -657,11 +902,77 @@ impl fmt::Display for AuthenticationKey {
 
 #[cfg(test)]
 mod tests {
-    use crate::transaction::authenticator::AuthenticationKey;
+    use crate::transaction::{
+        authenticator::{AccountAuthenticator, AuthenticationKey},
+        webauthn::PartialAuthenticatorAssertionResponse,
+    };
+    use aptos_crypto::{
+        secp256k1_ecdsa::{Secp256k1EcdsaPrivateKey, Secp256k1EcdsaPublicKey},
+        secp256r1_ecdsa::{Secp256r1EcdsaPrivateKey, Secp256r1EcdsaPublicKey},
+        test_utils::TestAptosCrypto,
+        HashValue, SigningKey, Uniform,
+    };
     use std::str::FromStr;
 
     #[test]
     fn test_from_str_should_not_panic_by_given_empty_string() {
         assert!(AuthenticationKey::from_str("").is_err());
     }
+
+    #[test]
+    fn test_secp256k1_ecdsa_authentication_key() {
+        let private_key = Secp256k1EcdsaPrivateKey::generate_for_testing();
+        let public_key = Secp256k1EcdsaPublicKey::from(&private_key);
+
+        // The authentication key is sha3(public_key | 0x02)
+        let mut preimage = public_key.to_bytes().to_vec();
+        preimage.push(2);
+        let expected = AuthenticationKey::new(*HashValue::sha3_256_of(&preimage).as_ref());
+        assert_eq!(AuthenticationKey::secp256k1_ecdsa(&public_key), expected);
+
+        // The account authenticator derives the same key and verifies its signature
+        let message = TestAptosCrypto("Test message".to_string());
+        let signature = private_key.sign(&message).unwrap();
+        let authenticator = AccountAuthenticator::secp256k1_ecdsa(public_key, signature);
+        assert_eq!(authenticator.authentication_key(), expected);
+        assert_eq!(authenticator.number_of_signatures(), 1);
+        authenticator.verify(&message).unwrap();
+    }
+
+    #[test]
+    fn test_webauthn_authenticator() {
+        let private_key = Secp256r1EcdsaPrivateKey::generate_for_testing();
+        let public_key = Secp256r1EcdsaPublicKey::from(&private_key);
+
+        // The authentication key is sha3(public_key | 0x03)
+        let mut preimage = public_key.to_bytes().to_vec();
+        preimage.push(3);
+        let expected = AuthenticationKey::new(*HashValue::sha3_256_of(&preimage).as_ref());
+        assert_eq!(AuthenticationKey::webauthn(&public_key), expected);
+
+        let message = TestAptosCrypto("Test message".to_string());
+        let assertion =
+            PartialAuthenticatorAssertionResponse::sign_for_testing(&private_key, &message);
+        let authenticator = AccountAuthenticator::webauthn(public_key.clone(), assertion.clone());
+        assert_eq!(authenticator.authentication_key(), expected);
+        assert_eq!(authenticator.number_of_signatures(), 1);
+        authenticator.verify(&message).unwrap();
+
+        // The assertion is bound to the message through its challenge
+        let other_message = TestAptosCrypto("Other message".to_string());
+        assert!(authenticator.verify(&other_message).is_err());
+
+        // Tampering with the client data invalidates the signature
+        let mut client_data_json = assertion.client_data_json().to_vec();
+        client_data_json.push(b' ');
+        let tampered = AccountAuthenticator::webauthn(
+            public_key,
+            PartialAuthenticatorAssertionResponse::new(
+                assertion.signature().clone(),
+                assertion.authenticator_data().to_vec(),
+                client_data_json,
+            ),
+        );
+        assert!(tampered.verify(&message).is_err());
+    }
 }
@@ -0,0 +1,163 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Support for authenticating transactions with WebAuthn assertions, as produced by platform
+//! authenticators such as passkeys.
+//!
+//! A WebAuthn authenticator does not sign the transaction directly. Instead, the client passes a
+//! challenge to the authenticator, which signs `authenticator_data || sha256(client_data_json)`
+//! with its secp256r1 key, where `client_data_json` embeds the (base64url encoded) challenge. To
+//! bind an assertion to a transaction, the challenge must be the SHA3-256 hash of the
+//! transaction's signing message (see [`challenge`]).
+//!
+//! Note that the relying party id and origin are not checked on chain: the account's
+//! authentication key already commits to the credential's public key.
+
+use anyhow::{ensure, Context, Result};
+use aptos_crypto::{
+    hash::CryptoHash,
+    secp256r1_ecdsa::{Secp256r1EcdsaPrivateKey, Secp256r1EcdsaPublicKey, Secp256r1EcdsaSignature},
+    traits::{signing_message, Signature},
+    HashValue,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// The `type` of the client data of an assertion (as opposed to a credential creation).
+pub const WEBAUTHN_GET_TYPE: &str = "webauthn.get";
+
+/// The minimum length of the authenticator data: the rpIdHash (32 bytes), the flags (1 byte)
+/// and the signature counter (4 bytes).
+pub const MIN_AUTHENTICATOR_DATA_LENGTH: usize = 37;
+
+/// The user present (UP) bit of the authenticator data flags.
+const FLAG_USER_PRESENT: u8 = 0x01;
+
+/// The parts of a WebAuthn `AuthenticatorAssertionResponse` that are required to verify it. The
+/// `userHandle` is omitted, as the signer is identified by its public key.
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct PartialAuthenticatorAssertionResponse {
+    /// The secp256r1 signature over `authenticator_data || sha256(client_data_json)`, in its
+    /// low-S form.
+    signature: Secp256r1EcdsaSignature,
+    /// The raw authenticator data.
+    #[serde(with = "serde_bytes")]
+    authenticator_data: Vec<u8>,
+    /// The raw (UTF-8 encoded) JSON serialization of the client data.
+    #[serde(with = "serde_bytes")]
+    client_data_json: Vec<u8>,
+}
+
+/// The subset of the WebAuthn `CollectedClientData` dictionary that is checked on chain.
+#[derive(Deserialize)]
+struct CollectedClientData {
+    #[serde(rename = "type")]
+    ty: String,
+    challenge: String,
+}
+
+impl PartialAuthenticatorAssertionResponse {
+    pub fn new(
+        signature: Secp256r1EcdsaSignature,
+        authenticator_data: Vec<u8>,
+        client_data_json: Vec<u8>,
+    ) -> Self {
+        Self {
+            signature,
+            authenticator_data,
+            client_data_json,
+        }
+    }
+
+    pub fn signature(&self) -> &Secp256r1EcdsaSignature {
+        &self.signature
+    }
+
+    pub fn authenticator_data(&self) -> &[u8] {
+        &self.authenticator_data
+    }
+
+    pub fn client_data_json(&self) -> &[u8] {
+        &self.client_data_json
+    }
+
+    /// Return Ok if the assertion is a valid signature of `public_key` on a challenge bound to
+    /// `message`, Err otherwise.
+    pub fn verify<T: Serialize + CryptoHash>(
+        &self,
+        message: &T,
+        public_key: &Secp256r1EcdsaPublicKey,
+    ) -> Result<()> {
+        let client_data: CollectedClientData = serde_json::from_slice(&self.client_data_json)
+            .context("Malformed WebAuthn client data")?;
+        ensure!(
+            client_data.ty == WEBAUTHN_GET_TYPE,
+            "Unexpected WebAuthn client data type: {}",
+            client_data.ty
+        );
+        let actual_challenge =
+            base64::decode_config(&client_data.challenge, base64::URL_SAFE_NO_PAD)
+                .context("Malformed WebAuthn challenge")?;
+        ensure!(
+            actual_challenge == challenge(message)?.to_vec(),
+            "WebAuthn challenge does not match the transaction"
+        );
+
+        ensure!(
+            self.authenticator_data.len() >= MIN_AUTHENTICATOR_DATA_LENGTH,
+            "WebAuthn authenticator data is too short"
+        );
+        ensure!(
+            self.authenticator_data[32] & FLAG_USER_PRESENT != 0,
+            "WebAuthn assertion was not made with user presence"
+        );
+
+        self.signature
+            .verify_arbitrary_msg(&self.verification_data(), public_key)
+    }
+
+    /// The data signed by the authenticator: `authenticator_data || sha256(client_data_json)`.
+    fn verification_data(&self) -> Vec<u8> {
+        let mut data = self.authenticator_data.clone();
+        data.extend_from_slice(&Sha256::digest(&self.client_data_json));
+        data
+    }
+
+    /// Creates an assertion for `message` with the given credential key, as a software
+    /// authenticator (with the given origin) would. The authenticator data only attests user
+    /// presence, with an empty rpIdHash and signature counter.
+    pub fn sign_with_private_key<T: Serialize + CryptoHash>(
+        private_key: &Secp256r1EcdsaPrivateKey,
+        message: &T,
+        origin: &str,
+    ) -> Result<Self> {
+        let client_data_json = serde_json::to_vec(&serde_json::json!({
+            "type": WEBAUTHN_GET_TYPE,
+            "challenge": base64::encode_config(challenge(message)?.to_vec(), base64::URL_SAFE_NO_PAD),
+            "origin": origin,
+            "crossOrigin": false,
+        }))?;
+        let mut authenticator_data = vec![0u8; MIN_AUTHENTICATOR_DATA_LENGTH];
+        authenticator_data[32] = FLAG_USER_PRESENT;
+
+        let mut verification_data = authenticator_data.clone();
+        verification_data.extend_from_slice(&Sha256::digest(&client_data_json));
+        let signature = private_key.sign_webauthn_verification_data(&verification_data);
+        Ok(Self::new(signature, authenticator_data, client_data_json))
+    }
+
+    /// Creates an assertion for `message` without a real authenticator (for tests only).
+    #[cfg(any(test, feature = "fuzzing"))]
+    pub fn sign_for_testing<T: Serialize + CryptoHash>(
+        private_key: &Secp256r1EcdsaPrivateKey,
+        message: &T,
+    ) -> Self {
+        Self::sign_with_private_key(private_key, message, "http://localhost:4000").unwrap()
+    }
+}
+
+/// Returns the challenge a WebAuthn assertion must be made over to authenticate `message`, i.e.,
+/// the SHA3-256 hash of its signing message.
+pub fn challenge<T: Serialize + CryptoHash>(message: &T) -> Result<HashValue> {
+    Ok(HashValue::sha3_256_of(&signing_message(message)?))
+}